@@ -146,12 +146,13 @@ impl AstAnalyzer {
         let tokens_string = mac.tokens.to_string();
         
         // Simple regex-based parsing for routes like: GET "/path" => handler
-        let route_pattern = regex::Regex::new(r#"(\w+)\s+"([^"]+)"\s*=>\s*(\w+)"#)?;
+        // (or a qualified handler such as `super::shared::show`)
+        let route_pattern = regex::Regex::new(r#"(\w+)\s+"([^"]+)"\s*=>\s*(\w+(?:\s*::\s*\w+)*)"#)?;
         
         for cap in route_pattern.captures_iter(&tokens_string) {
             let method = cap[1].to_string();
             let path = cap[2].to_string();
-            let handler = cap[3].to_string();
+            let handler = cap[3].split_whitespace().collect::<String>();
             
             let parameters = Self::extract_path_parameters(&path);
             
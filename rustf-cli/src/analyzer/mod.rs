@@ -10,6 +10,7 @@ pub mod lookup;
 pub mod lru_cache;
 pub mod analysis_cache;
 pub mod streaming;
+pub mod wiring;
 
 pub use project::ProjectAnalyzer;
 pub use streaming::{StreamingAnalyzer, StreamingConfigBuilder, MemoryStats};
@@ -0,0 +1,465 @@
+//! Wiring checks for `rustf-cli validate`
+//!
+//! These catch mistakes that compile fine but break the app at runtime:
+//! a route pointing at a handler that is not in its controller, two routes
+//! that match the same requests, and auto-discovered files missing the
+//! function their `auto_*!()` macro calls. Files are walked with the same
+//! rules as the macros (up to three levels deep, skipping `mod.rs`,
+//! `*.inc.rs` and names starting with `_`).
+
+use super::ast::AstAnalyzer;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use syn::{Item, UseTree};
+use walkdir::WalkDir;
+
+/// Directory under `src/`, the function its macro calls and the signature it expects
+const DISCOVERED: &[(&str, &str, &str)] = &[
+    ("controllers", "install", "pub fn install() -> Vec<Route>"),
+    (
+        "models",
+        "register",
+        "pub fn register(registry: &mut ModelRegistry)",
+    ),
+    (
+        "middleware",
+        "install",
+        "pub fn install(registry: &mut MiddlewareRegistry)",
+    ),
+    (
+        "events",
+        "install",
+        "pub fn install(emitter: &mut EventEmitter)",
+    ),
+    (
+        "definitions",
+        "install",
+        "pub fn install(defs: &mut Definitions)",
+    ),
+    ("workers", "install", "pub async fn install() -> Result<()>"),
+];
+
+/// A wiring mistake found in one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WiringIssue {
+    /// File path relative to the project
+    pub file: String,
+    pub message: String,
+}
+
+impl fmt::Display for WiringIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+/// A route as registered by one controller file
+struct DeclaredRoute {
+    file: String,
+    method: String,
+    path: String,
+    handler: String,
+}
+
+/// Run every wiring check against the project at `project_path`
+pub fn check_project(project_path: &Path) -> Vec<WiringIssue> {
+    let src_dir = project_path.join("src");
+    let auto_discover = uses_auto_discover(&src_dir);
+    let ast = AstAnalyzer::default();
+
+    let mut issues = Vec::new();
+    let mut routes = Vec::new();
+
+    for (dir_name, fn_name, signature) in DISCOVERED {
+        let discovered = auto_discover || mentions_macro(&src_dir, &format!("auto_{}!", dir_name));
+
+        for path in discovered_files(&src_dir.join(dir_name)) {
+            let file = relative(project_path, &path);
+            let syntax = match std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|source| syn::parse_file(&source).ok())
+            {
+                Some(syntax) => syntax,
+                // Syntax errors are reported by the build check
+                None => continue,
+            };
+
+            if *dir_name == "controllers" {
+                issues.extend(check_handlers(&ast, &path, &file, &syntax, &mut routes));
+            }
+            if discovered {
+                issues.extend(check_entry_point(
+                    &file, &syntax, dir_name, fn_name, signature,
+                ));
+            }
+        }
+    }
+
+    issues.extend(check_conflicts(&routes));
+    issues
+}
+
+/// Routes of one controller whose handler is not defined or imported in the file
+fn check_handlers(
+    ast: &AstAnalyzer,
+    path: &Path,
+    file: &str,
+    syntax: &syn::File,
+    routes: &mut Vec<DeclaredRoute>,
+) -> Vec<WiringIssue> {
+    let mut issues = Vec::new();
+    let mut local_names = BTreeSet::new();
+    let mut handlers = Vec::new();
+
+    for item in &syntax.items {
+        match item {
+            Item::Fn(func) => {
+                let name = func.sig.ident.to_string();
+                for (method, route_path) in route_attributes(func) {
+                    routes.push(DeclaredRoute {
+                        file: file.to_string(),
+                        method,
+                        path: route_path,
+                        handler: name.clone(),
+                    });
+                }
+                if name != "install" {
+                    handlers.push(name.clone());
+                }
+                local_names.insert(name);
+            }
+            Item::Use(item_use) => collect_use_names(&item_use.tree, &mut local_names),
+            _ => {}
+        }
+    }
+
+    for route in ast.extract_routes(path).unwrap_or_default() {
+        // Handlers from other modules (`super::shared::show`) cannot be checked here
+        if !route.handler.contains("::") && !local_names.contains(&route.handler) {
+            let available = if handlers.is_empty() {
+                "the file defines no handlers".to_string()
+            } else {
+                format!("handlers in this file: {}", handlers.join(", "))
+            };
+            issues.push(WiringIssue {
+                file: file.to_string(),
+                message: format!(
+                    "route {} {} points to `{}`, which is not defined or imported here ({})",
+                    route.method, route.path, route.handler, available
+                ),
+            });
+        }
+        routes.push(DeclaredRoute {
+            file: file.to_string(),
+            method: route.method,
+            path: route.path,
+            handler: route.handler,
+        });
+    }
+
+    issues
+}
+
+/// The function an `auto_*!()` macro calls must exist, be public and, for workers, be async
+fn check_entry_point(
+    file: &str,
+    syntax: &syn::File,
+    dir_name: &str,
+    fn_name: &str,
+    signature: &str,
+) -> Option<WiringIssue> {
+    let mut has_route_attributes = false;
+    let mut entry_point = None;
+
+    for item in &syntax.items {
+        if let Item::Fn(func) = item {
+            if func.sig.ident == fn_name {
+                entry_point = Some(func);
+            }
+            has_route_attributes |= !route_attributes(func).is_empty();
+        }
+    }
+
+    let message = match entry_point {
+        // Controllers may declare all their routes with `#[route(...)]` instead
+        None if dir_name == "controllers" && has_route_attributes => return None,
+        None => format!(
+            "missing `{}`, which auto_{}!() calls for every file in src/{}/ \
+             (prefix the file name with `_` to exclude it from auto-discovery)",
+            signature, dir_name, dir_name
+        ),
+        Some(func) if !matches!(func.vis, syn::Visibility::Public(_)) => format!(
+            "`{}` must be public for auto_{}!() to call it: `{}`",
+            fn_name, dir_name, signature
+        ),
+        Some(func) if dir_name == "workers" && func.sig.asyncness.is_none() => format!(
+            "`{}` must be async, auto_workers!() awaits it: `{}`",
+            fn_name, signature
+        ),
+        Some(_) => return None,
+    };
+
+    Some(WiringIssue {
+        file: file.to_string(),
+        message,
+    })
+}
+
+/// Routes that the router cannot tell apart, so only one of them is ever reached
+fn check_conflicts(routes: &[DeclaredRoute]) -> Vec<WiringIssue> {
+    let mut by_key: BTreeMap<(String, String), Vec<&DeclaredRoute>> = BTreeMap::new();
+    for route in routes {
+        let method = route.method.to_uppercase();
+        // XHR routes are registered for both GET and POST
+        let methods = if method == "XHR" {
+            vec!["GET".to_string(), "POST".to_string()]
+        } else {
+            vec![method]
+        };
+        for method in methods {
+            by_key
+                .entry((method, route_shape(&route.path)))
+                .or_default()
+                .push(route);
+        }
+    }
+
+    by_key
+        .into_iter()
+        .filter(|(_, routes)| routes.len() > 1)
+        .map(|((method, _), routes)| {
+            let registrations: Vec<String> = routes
+                .iter()
+                .map(|route| format!("{} -> {} ({})", route.path, route.handler, route.file))
+                .collect();
+            WiringIssue {
+                file: routes[0].file.clone(),
+                message: format!(
+                    "{} routes match the same requests, only the last one registered \
+                     will handle them: {}",
+                    method,
+                    registrations.join(", ")
+                ),
+            }
+        })
+        .collect()
+}
+
+/// A path as the router sees it: parameter names and empty segments do not matter
+///
+/// Constraints are kept, since `{id:int}` and `{slug}` at the same place
+/// match different requests; only parameters with the same constraint (or
+/// none) collide.
+fn route_shape(path: &str) -> String {
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if segment == "*" || segment.starts_with("{*") {
+                "*".to_string()
+            } else if let Some(inner) = segment
+                .strip_prefix('{')
+                .and_then(|segment| segment.strip_suffix('}'))
+            {
+                match inner.split_once(':') {
+                    Some((_, constraint)) => format!("{{:{}}}", constraint.trim()),
+                    None => "{}".to_string(),
+                }
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// `(METHOD, path)` of every `#[route(METHOD, "/path")]` on a function
+fn route_attributes(func: &syn::ItemFn) -> Vec<(String, String)> {
+    let pattern = regex::Regex::new(r#"^\s*(\w+)\s*,\s*"([^"]+)""#).unwrap();
+
+    func.attrs
+        .iter()
+        .filter(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "route")
+        })
+        .filter_map(|attr| {
+            let tokens = attr.meta.require_list().ok()?.tokens.to_string();
+            let captures = pattern.captures(&tokens)?;
+            Some((captures[1].to_string(), captures[2].to_string()))
+        })
+        .collect()
+}
+
+/// Names a `use` item brings into scope
+fn collect_use_names(tree: &UseTree, names: &mut BTreeSet<String>) {
+    match tree {
+        UseTree::Path(path) => collect_use_names(&path.tree, names),
+        UseTree::Name(name) => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Rename(rename) => {
+            names.insert(rename.rename.to_string());
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                collect_use_names(tree, names);
+            }
+        }
+        UseTree::Glob(_) => {}
+    }
+}
+
+/// Files an `auto_*!()` macro would pick up from `dir`, in a stable order
+fn discovered_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            path.is_file()
+                && file_name.ends_with(".rs")
+                && file_name != "mod.rs"
+                && !file_name.ends_with(".inc.rs")
+                && !file_name.starts_with('_')
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// `#[rustf::auto_discover]` wires up every directory
+fn uses_auto_discover(src_dir: &Path) -> bool {
+    mentions_macro(src_dir, "auto_discover")
+}
+
+fn mentions_macro(src_dir: &Path, name: &str) -> bool {
+    ["main.rs", "lib.rs"].iter().any(|file| {
+        std::fs::read_to_string(src_dir.join(file))
+            .map(|source| source.contains(name))
+            .unwrap_or(false)
+    })
+}
+
+fn relative(project_path: &Path, path: &Path) -> String {
+    path.strip_prefix(project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn messages(dir: &tempfile::TempDir) -> Vec<String> {
+        check_project(dir.path())
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_handlers_and_conflicts() {
+        let dir = project(&[
+            ("src/main.rs", "fn main() { let _ = auto_controllers!(); }"),
+            (
+                "src/controllers/users.rs",
+                r#"
+                use super::shared::{export as export_users};
+                pub fn install() -> Vec<Route> {
+                    routes![
+                        GET "/users" => index,
+                        GET "/users/{id}" => shw,
+                        GET "/users/export" => export_users,
+                    ]
+                }
+                async fn index(ctx: &mut Context) -> Result<()> { ctx.text("") }
+                async fn show(ctx: &mut Context) -> Result<()> { ctx.text("") }
+                "#,
+            ),
+            (
+                "src/controllers/admin/users.rs",
+                r#"
+                #[route(GET, "/users/{user_id}/")]
+                pub async fn detail(ctx: &mut Context) -> Result<()> { ctx.text("") }
+                "#,
+            ),
+        ]);
+
+        assert_eq!(
+            messages(&dir),
+            vec![
+                "src/controllers/users.rs: route GET /users/{id} points to `shw`, which is not \
+                 defined or imported here (handlers in this file: index, show)",
+                "src/controllers/admin/users.rs: GET routes match the same requests, only the \
+                 last one registered will handle them: /users/{user_id}/ -> detail \
+                 (src/controllers/admin/users.rs), /users/{id} -> shw (src/controllers/users.rs)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discovered_files_need_entry_points() {
+        let dir = project(&[
+            ("src/main.rs", "#[rustf::auto_discover]\nasync fn main() {}"),
+            ("src/models/users.rs", "pub struct Users;"),
+            ("src/models/_helpers.rs", "pub fn helper() {}"),
+            ("src/models/base/users.inc.rs", "pub struct UsersBase;"),
+            (
+                "src/middleware/auth.rs",
+                "fn install(registry: &mut MiddlewareRegistry) {}",
+            ),
+            (
+                "src/workers/cleanup.rs",
+                "pub fn install() -> Result<()> { Ok(()) }",
+            ),
+            (
+                "src/events/startup.rs",
+                "pub fn install(emitter: &mut EventEmitter) {}",
+            ),
+        ]);
+
+        let messages = messages(&dir);
+        assert_eq!(messages.len(), 3, "{:#?}", messages);
+        assert!(messages[0].starts_with(
+            "src/models/users.rs: missing `pub fn register(registry: &mut ModelRegistry)`"
+        ));
+        assert!(messages[1].starts_with("src/middleware/auth.rs: `install` must be public"));
+        assert!(messages[2].starts_with("src/workers/cleanup.rs: `install` must be async"));
+    }
+
+    #[test]
+    fn test_route_shape() {
+        assert_eq!(route_shape("/users/{id}/"), "/users/{}");
+        assert_eq!(route_shape("/files/{*path}"), "/files/*");
+        assert_eq!(route_shape("/"), "/");
+
+        assert_eq!(
+            route_shape("/users/{id:int}"),
+            route_shape("/users/{user:int}")
+        );
+        assert_ne!(route_shape("/users/{id:int}"), route_shape("/users/{slug}"));
+        assert_ne!(
+            route_shape("/users/{id:int}"),
+            route_shape("/users/{id:uuid}")
+        );
+    }
+}
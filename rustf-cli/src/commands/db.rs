@@ -109,7 +109,9 @@ pub enum DbAction {
         output: Option<PathBuf>,
     },
 
-    /// Generate RustF schema from database structure
+    /// Generate RustF schema YAML from database structure (tables, columns,
+    /// keys, foreign key relations and indexes)
+    #[command(visible_alias = "introspect")]
     GenerateSchema {
         /// Named connection to use (defaults to primary)
         #[arg(long)]
@@ -219,21 +219,81 @@ pub fn generate_relations_yaml(columns: &[ColumnInfo]) -> String {
         if column.is_foreign_key {
             if let (Some(foreign_table), Some(foreign_column)) = (&column.foreign_table, &column.foreign_column) {
                 let relation_name = column.name.trim_end_matches("_id");
-                belongs_to_relations.push((relation_name, foreign_table, &column.name, foreign_column));
+                belongs_to_relations.push((relation_name, foreign_table, &column.name, foreign_column, &column.on_delete, &column.on_update));
             }
         }
     }
     
     if !belongs_to_relations.is_empty() {
         yaml.push_str("    belongs_to:\n");
-        for (name, table, local, foreign) in belongs_to_relations {
+        for (name, table, local, foreign, on_delete, on_update) in belongs_to_relations {
             yaml.push_str(&format!("      {}:\n", name));
             yaml.push_str(&format!("        model: {}\n", to_pascal_case(table)));
             yaml.push_str(&format!("        local_field: {}\n", local));
             yaml.push_str(&format!("        foreign_field: {}\n", foreign));
+            if let Some(action) = on_delete.as_deref().and_then(foreign_key_action) {
+                yaml.push_str(&format!("        on_delete: {}\n", action));
+            }
+            if let Some(action) = on_update.as_deref().and_then(foreign_key_action) {
+                yaml.push_str(&format!("        on_update: {}\n", action));
+            }
             yaml.push_str(&format!("        ai: \"Reference to {} for relational data integrity\"\n", table));
         }
     }
     
+    yaml
+}
+
+/// Map a referential action as reported by the database (`SET NULL`,
+/// `CASCADE`, ...) to the schema's `on_delete`/`on_update` value
+pub fn foreign_key_action(rule: &str) -> Option<&'static str> {
+    match rule.trim().to_uppercase().as_str() {
+        "CASCADE" => Some("cascade"),
+        "RESTRICT" => Some("restrict"),
+        "SET NULL" => Some("set_null"),
+        "SET DEFAULT" => Some("set_default"),
+        "NO ACTION" => Some("no_action"),
+        _ => None,
+    }
+}
+
+/// Generate the table-level `primary_key` list for keys spanning several
+/// columns, which keeps the key order that `primary_key: true` flags lose
+pub fn generate_primary_key_yaml(indexes: &[IndexInfo]) -> String {
+    match indexes.iter().find(|index| index.is_primary) {
+        Some(index) if index.columns.len() > 1 => {
+            format!("  primary_key: [{}]\n", index.columns.join(", "))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Generate YAML for table indexes
+///
+/// The primary key and single-column unique indexes are already described
+/// on the fields, so only the remaining indexes are listed, by name.
+pub fn generate_indexes_yaml(indexes: &[IndexInfo], columns: &[ColumnInfo]) -> String {
+    let mut indexes: Vec<&IndexInfo> = indexes
+        .iter()
+        .filter(|index| !index.is_primary && !index.columns.is_empty())
+        .filter(|index| {
+            !(index.is_unique
+                && index.columns.len() == 1
+                && columns.iter().any(|c| c.name == index.columns[0] && c.is_unique))
+        })
+        .collect();
+    indexes.sort_by(|a, b| a.name.cmp(&b.name));
+    
+    let mut yaml = String::new();
+    for index in indexes {
+        if index.is_unique {
+            yaml.push_str(&format!("    - fields: [{}]\n", index.columns.join(", ")));
+            yaml.push_str("      unique: true\n");
+        } else if index.columns.len() == 1 {
+            yaml.push_str(&format!("    - {}\n", index.columns[0]));
+        } else {
+            yaml.push_str(&format!("    - [{}]\n", index.columns.join(", ")));
+        }
+    }
     yaml
 }
\ No newline at end of file
@@ -97,23 +97,7 @@ impl MySqlIntrospector {
             }
         } else {
             // Regular data type
-            let data_type = if column.data_type.contains("varchar") || column.data_type.contains("char") {
-                if let Some(max_length) = column.max_length {
-                    format!("string({})", max_length)
-                } else {
-                    "string".to_string()
-                }
-            } else if column.data_type == "text" {
-                "text".to_string()  // Don't parameterize text type
-            } else if column.data_type.contains("decimal") {
-                if let (Some(precision), Some(scale)) = (column.numeric_precision, column.numeric_scale) {
-                    format!("decimal({},{})", precision, scale)
-                } else {
-                    "decimal".to_string()
-                }
-            } else {
-                column.data_type.clone()
-            };
+            let data_type = Self::schema_field_type(column);
             field.push_str(&format!("      type: {}\n", data_type));
             
             // Add default value or auto-increment flag
@@ -126,8 +110,13 @@ impl MySqlIntrospector {
             }
         }
         
-        // Add lang_type
-        let lang_type = self.map_to_rust_type(&column.data_type, column.is_nullable);
+        // Add lang_type (TINYINT(1) columns are booleans)
+        let rust_source_type = if Self::schema_field_type(column) == "boolean" {
+            "boolean"
+        } else {
+            column.data_type.as_str()
+        };
+        let lang_type = self.map_to_rust_type(rust_source_type, column.is_nullable);
         field.push_str(&format!("      lang_type: {}\n", lang_type));
         
         // Add nullable flag
@@ -169,6 +158,34 @@ impl MySqlIntrospector {
         field
     }
     
+    /// Map a MySQL column type back to the schema field type it was created from
+    fn schema_field_type(column: &ColumnInfo) -> String {
+        let column_type = column.column_type.as_deref().unwrap_or("").to_lowercase();
+        match column.data_type.to_lowercase().as_str() {
+            // MySQL has no boolean type, BOOLEAN columns are created as TINYINT(1)
+            "tinyint" if column_type.starts_with("tinyint(1)") => "boolean".to_string(),
+            "char" | "varchar" => match column.max_length {
+                Some(max_length) => format!("string({})", max_length),
+                None => "string".to_string(),
+            },
+            "decimal" | "numeric" => match (column.numeric_precision, column.numeric_scale) {
+                (Some(precision), Some(scale)) => format!("decimal({},{})", precision, scale),
+                _ => "decimal".to_string(),
+            },
+            data_type @ ("tinyint" | "smallint" | "mediumint" | "int" | "bigint" | "float"
+            | "double" | "date" | "time" | "datetime" | "timestamp" | "text" | "tinytext"
+            | "mediumtext" | "longtext" | "json") => data_type.to_string(),
+            "integer" => "int".to_string(),
+            "real" => "double".to_string(),
+            "year" => "smallint".to_string(),
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit" => {
+                "blob".to_string()
+            }
+            // SET and spatial types have no schema equivalent
+            _ => "text".to_string(),
+        }
+    }
+    
     fn map_to_rust_type(&self, data_type: &str, is_nullable: bool) -> String {
         let base_type = match data_type {
             "int" | "integer" => "i32",
@@ -228,6 +245,7 @@ impl MySqlIntrospector {
         // Add AI context based on table name
         let ai_context = generate_table_ai_context(&description.table.name);
         yaml.push_str(&format!("  ai_context: \"{}\"\n", ai_context));
+        yaml.push_str(&generate_primary_key_yaml(&description.indexes));
         
         yaml.push_str("  \n  fields:\n");
         
@@ -244,15 +262,10 @@ impl MySqlIntrospector {
         }
         
         // Generate indexes
-        if !description.indexes.is_empty() {
+        let indexes = generate_indexes_yaml(&description.indexes, &description.columns);
+        if !indexes.is_empty() {
             yaml.push_str("  \n  indexes:\n");
-            for index in &description.indexes {
-                if index.columns.len() == 1 {
-                    yaml.push_str(&format!("    - {}\n", index.columns[0]));
-                } else {
-                    yaml.push_str(&format!("    - [{}]\n", index.columns.join(", ")));
-                }
-            }
+            yaml.push_str(&indexes);
         }
         
         // Generate triggers
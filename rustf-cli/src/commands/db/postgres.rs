@@ -195,9 +195,11 @@ impl PostgresIntrospector {
             field.push_str(&format!("      postgres_type_name: {}\n", postgres_type));
         }
         
-        // Add default value if present
+        // Add default value if present; sequence defaults are serial columns
         if let Some(default) = &column.default_value {
-            if !default.is_empty() && default != "NULL" {
+            if default.starts_with("nextval(") {
+                field.push_str("      auto: true\n");
+            } else if !default.is_empty() && default != "NULL" {
                 // Clean PostgreSQL casting syntax (e.g., 'value'::type -> value)
                 let clean_default = self.clean_default_value(default);
                 field.push_str(&format!("      default: {}\n", clean_default));
@@ -399,6 +401,7 @@ impl PostgresIntrospector {
         // Add AI context based on table name
         let ai_context = generate_table_ai_context(&description.table.name);
         yaml.push_str(&format!("  ai_context: \"{}\"\n", ai_context));
+        yaml.push_str(&generate_primary_key_yaml(&description.indexes));
         
         // Fetch all custom types and find which ones are used in this table
         let all_types = self.get_all_custom_types().await.unwrap_or_default();
@@ -457,15 +460,10 @@ impl PostgresIntrospector {
         }
         
         // Generate indexes
-        if !description.indexes.is_empty() {
+        let indexes = generate_indexes_yaml(&description.indexes, &description.columns);
+        if !indexes.is_empty() {
             yaml.push_str("  \n  indexes:\n");
-            for index in &description.indexes {
-                if index.columns.len() == 1 {
-                    yaml.push_str(&format!("    - {}\n", index.columns[0]));
-                } else {
-                    yaml.push_str(&format!("    - [{}]\n", index.columns.join(", ")));
-                }
-            }
+            yaml.push_str(&indexes);
         }
         
         yaml.push_str("\n");
@@ -3,11 +3,13 @@
 use super::{common::*, DatabaseIntrospector};
 use anyhow::Result;
 use async_trait::async_trait;
-use sqlx::{Pool, Sqlite};
-use std::path::PathBuf;
+use sqlx::{Pool, Row, Sqlite};
+use std::path::{Path, PathBuf};
+use tokio::fs;
 
 pub struct SqliteIntrospector {
     pool: Pool<Sqlite>,
+    db_name: String,
 }
 
 impl SqliteIntrospector {
@@ -16,44 +18,702 @@ impl SqliteIntrospector {
             .max_connections(5)
             .connect(database_url)
             .await?;
-        
-        Ok(Self { pool })
+
+        // SQLite has no database name, use the file name instead
+        let path = database_url
+            .trim_start_matches("sqlite://")
+            .trim_start_matches("sqlite:");
+        let path = path.split('?').next().unwrap_or(path);
+        let db_name = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| !stem.is_empty() && *stem != ":memory:")
+            .unwrap_or("sqlite")
+            .to_string();
+
+        Ok(Self { pool, db_name })
+    }
+
+    /// Map a declared SQLite column type back to the schema field type
+    ///
+    /// SQLite keeps the type as written in `CREATE TABLE`, so the names the
+    /// schema migrations emit (`VARCHAR(255)`, `BOOLEAN`, `TIMESTAMP`, ...)
+    /// map back directly. Anything else falls back to SQLite's own type
+    /// affinity rules.
+    fn schema_field_type(declared_type: &str) -> String {
+        let declared = declared_type.trim().to_uppercase();
+        let (base, params) = match declared.find('(') {
+            Some(paren) => (
+                declared[..paren].trim(),
+                declared[paren + 1..].trim_end_matches(')').trim(),
+            ),
+            None => (declared.as_str(), ""),
+        };
+        let params: Vec<&str> = params
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        match base {
+            "INTEGER" | "INT" | "INT4" => "int".to_string(),
+            "BIGINT" | "INT8" | "UNSIGNED BIG INT" => "bigint".to_string(),
+            "SMALLINT" | "INT2" => "smallint".to_string(),
+            "TINYINT" => "tinyint".to_string(),
+            "MEDIUMINT" => "mediumint".to_string(),
+            "SERIAL" => "serial".to_string(),
+            "VARCHAR" | "CHARACTER VARYING" | "VARYING CHARACTER" | "NVARCHAR" | "CHAR"
+            | "CHARACTER" | "NCHAR" | "NATIVE CHARACTER" => match params.first() {
+                Some(length) => format!("string({})", length),
+                None => "string".to_string(),
+            },
+            "TEXT" | "CLOB" => "text".to_string(),
+            "DECIMAL" | "NUMERIC" => match params.as_slice() {
+                [precision, scale] => format!("decimal({},{})", precision, scale),
+                _ => "decimal".to_string(),
+            },
+            "REAL" | "FLOAT" => "float".to_string(),
+            "DOUBLE" | "DOUBLE PRECISION" => "double".to_string(),
+            "BOOLEAN" | "BOOL" => "boolean".to_string(),
+            "TIMESTAMP" => "timestamp".to_string(),
+            "DATETIME" => "datetime".to_string(),
+            "DATE" => "date".to_string(),
+            "TIME" => "time".to_string(),
+            "JSON" => "json".to_string(),
+            "JSONB" => "jsonb".to_string(),
+            "UUID" => "uuid".to_string(),
+            "BLOB" | "BYTEA" | "" => "blob".to_string(),
+            other if other.contains("INT") => "int".to_string(),
+            other if other.contains("CHAR") || other.contains("CLOB") || other.contains("TEXT") => {
+                "text".to_string()
+            }
+            other if other.contains("BLOB") => "blob".to_string(),
+            other if other.contains("REAL") || other.contains("FLOA") || other.contains("DOUB") => {
+                "double".to_string()
+            }
+            _ => "decimal".to_string(),
+        }
+    }
+
+    /// Extract the values of a `CHECK (column IN ('a', 'b'))` constraint,
+    /// which is how enum fields are created on SQLite
+    fn parse_check_values(create_sql: &str, column: &str) -> Vec<String> {
+        let normalized: String = create_sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        let upper = normalized.to_uppercase();
+        let column = column.to_uppercase();
+
+        let candidates = [
+            format!("CHECK ({} IN (", column),
+            format!("CHECK (\"{}\" IN (", column),
+            format!("CHECK ({} IN(", column),
+        ];
+        let Some(start) = candidates
+            .iter()
+            .find_map(|needle| upper.find(needle.as_str()).map(|pos| pos + needle.len()))
+        else {
+            return Vec::new();
+        };
+
+        let mut values = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        let mut chars = normalized[start..].chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\'' if in_quotes && chars.peek() == Some(&'\'') => {
+                    current.push('\'');
+                    chars.next();
+                }
+                '\'' if in_quotes => {
+                    values.push(std::mem::take(&mut current));
+                    in_quotes = false;
+                }
+                '\'' => in_quotes = true,
+                ')' if !in_quotes => break,
+                _ if in_quotes => current.push(ch),
+                _ => {}
+            }
+        }
+        values
+    }
+
+    fn map_to_rust_type(&self, field_type: &str, is_nullable: bool) -> String {
+        let base = field_type.split('(').next().unwrap_or(field_type);
+        let base_type = match base {
+            "int" | "serial" | "mediumint" => "i32",
+            "bigint" => "i64",
+            "smallint" => "i16",
+            "tinyint" => "i8",
+            "float" => "f32",
+            "double" => "f64",
+            "decimal" => "Decimal",
+            "boolean" => "bool",
+            "date" => "NaiveDate",
+            "time" => "NaiveTime",
+            "timestamp" | "datetime" => "DateTime<Utc>",
+            "json" | "jsonb" => "serde_json::Value",
+            "uuid" => "Uuid",
+            "blob" => "Vec<u8>",
+            _ => "String",
+        };
+
+        if is_nullable {
+            format!("Option<{}>", base_type)
+        } else {
+            base_type.to_string()
+        }
+    }
+
+    /// First primary key column of a table, the target of foreign keys
+    /// declared without an explicit column
+    async fn primary_key_column(&self, table_name: &str) -> Result<Option<String>> {
+        let rows = sqlx::query(&format!(
+            "PRAGMA table_info(\"{}\")",
+            table_name.replace('"', "\"\"")
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut key: Option<(i64, String)> = None;
+        for row in rows {
+            let pk: i64 = row.try_get("pk")?;
+            if pk > 0 && key.as_ref().is_none_or(|(position, _)| pk < *position) {
+                key = Some((pk, row.try_get("name")?));
+            }
+        }
+        Ok(key.map(|(_, name)| name))
+    }
+
+    fn generate_field_yaml(&self, column: &ColumnInfo) -> String {
+        let mut field = String::new();
+        let escaped_field_name = escape_yaml_field_name(&column.name);
+        field.push_str(&format!("    {}:\n", escaped_field_name));
+
+        // Enum values recovered from the CHECK constraint, stored as JSON
+        let enum_values: Vec<String> = column
+            .column_type
+            .as_deref()
+            .and_then(|values| serde_json::from_str(values).ok())
+            .unwrap_or_default();
+
+        let field_type = if enum_values.is_empty() {
+            Self::schema_field_type(&column.data_type)
+        } else {
+            "enum".to_string()
+        };
+        field.push_str(&format!("      type: {}\n", field_type));
+
+        if !enum_values.is_empty() {
+            field.push_str("      values:\n");
+            for value in &enum_values {
+                field.push_str(&format!("        - \"{}\"\n", value));
+            }
+        }
+
+        // Add default value or auto-increment flag
+        if let Some(default) = &column.default_value {
+            if default == "AUTO_INCREMENT" {
+                field.push_str("      auto: true\n");
+            } else if !default.is_empty() && !default.eq_ignore_ascii_case("NULL") {
+                field.push_str(&format!("      default: {}\n", default));
+            }
+        }
+
+        // Add lang_type
+        let lang_type = self.map_to_rust_type(&field_type, column.is_nullable);
+        field.push_str(&format!("      lang_type: {}\n", lang_type));
+
+        // Add nullable flag
+        if column.is_nullable {
+            field.push_str("      nullable: true\n");
+        } else {
+            field.push_str("      required: true\n");
+        }
+
+        // Add primary key flag
+        if column.is_primary_key {
+            field.push_str("      primary_key: true\n");
+        }
+
+        // Add unique flag
+        if column.is_unique {
+            field.push_str("      unique: true\n");
+        }
+
+        // Add AI hint
+        let ai_hint = generate_field_ai_hint(&column.name, &field_type, column.is_foreign_key);
+        field.push_str(&format!("      ai: \"{}\"\n", ai_hint));
+
+        // Add foreign key reference
+        if column.is_foreign_key {
+            if let (Some(table), Some(col)) = (&column.foreign_table, &column.foreign_column) {
+                field.push_str(&format!("      foreign_key: {}.{}\n", table, col));
+            }
+        }
+
+        field.push('\n');
+        field
+    }
+
+    fn generate_table_schema_yaml(&self, description: &TableDescription) -> Result<String> {
+        let mut yaml = String::new();
+
+        // Add header comment
+        yaml.push_str(&format!(
+            "# {} entity - Generated from database\n\n",
+            description.table.name
+        ));
+
+        // Model name (convert table name to PascalCase)
+        let model_name = to_pascal_case(&description.table.name);
+        yaml.push_str(&format!("{}:\n", model_name));
+        yaml.push_str(&format!("  table: {}\n", description.table.name));
+        yaml.push_str("  database_type: sqlite\n");
+        yaml.push_str(&format!("  database_name: {}\n", self.db_name));
+
+        let element_type = if description.table.table_type == "view" {
+            "view"
+        } else {
+            "table"
+        };
+        yaml.push_str(&format!("  element_type: {}\n", element_type));
+        yaml.push_str("  version: 1\n");
+
+        // Add AI context based on table name
+        let ai_context = generate_table_ai_context(&description.table.name);
+        yaml.push_str(&format!("  ai_context: \"{}\"\n", ai_context));
+        yaml.push_str(&generate_primary_key_yaml(&description.indexes));
+
+        yaml.push_str("  \n  fields:\n");
+
+        // Generate fields
+        for column in &description.columns {
+            yaml.push_str(&self.generate_field_yaml(column));
+        }
+
+        // Generate relations if foreign keys exist
+        let relations = generate_relations_yaml(&description.columns);
+        if !relations.is_empty() {
+            yaml.push_str("  \n  relations:\n");
+            yaml.push_str(&relations);
+        }
+
+        // Generate indexes
+        let indexes = generate_indexes_yaml(&description.indexes, &description.columns);
+        if !indexes.is_empty() {
+            yaml.push_str("  \n  indexes:\n");
+            yaml.push_str(&indexes);
+        }
+
+        // Generate triggers
+        if !description.triggers.is_empty() {
+            yaml.push_str("  \n  triggers:\n");
+            for trigger in &description.triggers {
+                yaml.push_str(&format!("    - name: {}\n", trigger.name));
+                yaml.push_str(&format!("      event: {}\n", trigger.event));
+                yaml.push_str(&format!("      timing: {}\n", trigger.timing));
+                yaml.push_str(&format!("      for_each: {}\n", trigger.for_each));
+                yaml.push_str("      ai: \"Database trigger - executes automatically on table changes. Consider application logic implications.\"\n");
+                yaml.push('\n');
+            }
+        }
+
+        yaml.push('\n');
+        Ok(yaml)
     }
 }
 
 #[async_trait]
 impl DatabaseIntrospector for SqliteIntrospector {
-    async fn list_tables(&self, _metadata: bool) -> Result<Vec<TableInfo>> {
-        // TODO: Implement SQLite table listing
-        Ok(Vec::new())
+    async fn list_tables(&self, metadata: bool) -> Result<Vec<TableInfo>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT name, type
+            FROM sqlite_master
+            WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%'
+            ORDER BY name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut tables = Vec::new();
+        for row in rows {
+            let name: String = row.try_get("name")?;
+            let row_count = if metadata {
+                let count_query = format!(
+                    "SELECT COUNT(*) AS row_count FROM \"{}\"",
+                    name.replace('"', "\"\"")
+                );
+                sqlx::query(&count_query)
+                    .fetch_one(&self.pool)
+                    .await
+                    .ok()
+                    .and_then(|row| row.try_get("row_count").ok())
+            } else {
+                None
+            };
+
+            tables.push(TableInfo {
+                name,
+                schema: None,
+                table_type: row.try_get("type")?,
+                row_count,
+                size_bytes: None,
+                comment: None,
+            });
+        }
+
+        Ok(tables)
     }
-    
-    async fn describe_table(&self, _table_name: &str) -> Result<TableDescription> {
-        // TODO: Implement SQLite table description
-        anyhow::bail!("SQLite describe_table not yet implemented")
+
+    async fn describe_table(&self, table_name: &str) -> Result<TableDescription> {
+        let table_row = sqlx::query(
+            "SELECT name, type, sql FROM sqlite_master WHERE name = ? AND type IN ('table', 'view')"
+        )
+        .bind(table_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let (table_info, create_sql) = match table_row {
+            Some(row) => (
+                TableInfo {
+                    name: row.try_get("name")?,
+                    schema: None,
+                    table_type: row.try_get("type")?,
+                    row_count: None,
+                    size_bytes: None,
+                    comment: None,
+                },
+                row.try_get::<Option<String>, _>("sql")?.unwrap_or_default(),
+            ),
+            None => anyhow::bail!("Table '{}' not found", table_name),
+        };
+
+        // Pragmas take the table name as an identifier, not a bound parameter
+        let quoted_table = format!("\"{}\"", table_name.replace('"', "\"\""));
+
+        // Get column information (pk holds the position in the primary key)
+        let column_rows = sqlx::query(&format!("PRAGMA table_info({})", quoted_table))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut primary_key: Vec<(i64, String)> = Vec::new();
+        for row in &column_rows {
+            let pk: i64 = row.try_get("pk")?;
+            if pk > 0 {
+                primary_key.push((pk, row.try_get("name")?));
+            }
+        }
+        primary_key.sort();
+        let primary_key: Vec<String> = primary_key.into_iter().map(|(_, name)| name).collect();
+
+        // Get foreign keys (one row per column of each constraint)
+        let fk_rows = sqlx::query(&format!("PRAGMA foreign_key_list({})", quoted_table))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut foreign_keys = std::collections::HashMap::new();
+        for row in fk_rows {
+            let column_name: String = row.try_get("from")?;
+            let foreign_table: String = row.try_get("table")?;
+            // A missing target column means the referenced table's primary key
+            let foreign_column: Option<String> = row.try_get("to").ok().flatten();
+            let on_delete: Option<String> = row.try_get("on_delete").ok();
+            let on_update: Option<String> = row.try_get("on_update").ok();
+            foreign_keys.insert(
+                column_name,
+                (foreign_table, foreign_column, on_delete, on_update),
+            );
+        }
+
+        // Get indexes; the primary key is described from table_info instead
+        let index_rows = sqlx::query(&format!("PRAGMA index_list({})", quoted_table))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut indexes = Vec::new();
+        if !primary_key.is_empty() {
+            indexes.push(IndexInfo {
+                name: "PRIMARY".to_string(),
+                columns: primary_key.clone(),
+                is_unique: true,
+                is_primary: true,
+            });
+        }
+        for row in index_rows {
+            let origin: String = row.try_get("origin")?;
+            if origin == "pk" {
+                continue;
+            }
+            let index_name: String = row.try_get("name")?;
+            let is_unique: i64 = row.try_get("unique")?;
+
+            let column_rows = sqlx::query(&format!(
+                "PRAGMA index_info(\"{}\")",
+                index_name.replace('"', "\"\"")
+            ))
+            .fetch_all(&self.pool)
+            .await?;
+            let mut columns: Vec<(i64, String)> = Vec::new();
+            for column_row in column_rows {
+                // Expression indexes have no column name
+                if let Ok(Some(name)) = column_row.try_get::<Option<String>, _>("name") {
+                    columns.push((column_row.try_get("seqno")?, name));
+                }
+            }
+            columns.sort();
+
+            indexes.push(IndexInfo {
+                name: index_name,
+                columns: columns.into_iter().map(|(_, name)| name).collect(),
+                is_unique: is_unique == 1,
+                is_primary: false,
+            });
+        }
+
+        let mut columns = Vec::new();
+        for row in column_rows {
+            let column_name: String = row.try_get("name")?;
+            let data_type: String = row.try_get("type")?;
+            let not_null: i64 = row.try_get("notnull")?;
+            let is_primary_key = primary_key.contains(&column_name);
+            let is_unique = indexes.iter().any(|index| {
+                index.is_unique && !index.is_primary && index.columns == [column_name.clone()]
+            });
+
+            // A single INTEGER PRIMARY KEY column aliases the rowid and is assigned automatically
+            let is_auto_increment = is_primary_key
+                && primary_key.len() == 1
+                && data_type.eq_ignore_ascii_case("INTEGER");
+            let default_value = if is_auto_increment {
+                Some("AUTO_INCREMENT".to_string())
+            } else {
+                row.try_get("dflt_value").ok().flatten()
+            };
+
+            let (foreign_table, foreign_column, on_delete, on_update) =
+                match foreign_keys.get(&column_name) {
+                    Some((table, column, del, upd)) => {
+                        let column = match column {
+                            Some(column) => column.clone(),
+                            None => self
+                                .primary_key_column(table)
+                                .await?
+                                .unwrap_or_else(|| "id".to_string()),
+                        };
+                        (Some(table.clone()), Some(column), del.clone(), upd.clone())
+                    }
+                    None => (None, None, None, None),
+                };
+
+            let enum_values = Self::parse_check_values(&create_sql, &column_name);
+            let column_type = if enum_values.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&enum_values)?)
+            };
+
+            columns.push(ColumnInfo {
+                name: column_name,
+                data_type,
+                column_type,
+                postgres_type_name: None,
+                // SQLite lets primary key columns hold NULL unless declared NOT NULL,
+                // but rowid aliases never do
+                is_nullable: not_null == 0 && !is_auto_increment && !is_primary_key,
+                default_value,
+                is_primary_key,
+                is_unique,
+                is_foreign_key: foreign_table.is_some(),
+                foreign_table,
+                foreign_column,
+                on_delete,
+                on_update,
+                comment: None,
+                max_length: None,
+                numeric_precision: None,
+                numeric_scale: None,
+            });
+        }
+
+        // Get triggers
+        let trigger_rows = sqlx::query(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND tbl_name = ?",
+        )
+        .bind(table_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut triggers = Vec::new();
+        for row in trigger_rows {
+            let body: Option<String> = row.try_get("sql").ok();
+            let sql = body.as_deref().unwrap_or("").to_uppercase();
+            let timing = ["INSTEAD OF", "BEFORE", "AFTER"]
+                .into_iter()
+                .find(|timing| sql.contains(timing))
+                .unwrap_or("BEFORE");
+            let event = ["INSERT", "UPDATE", "DELETE"]
+                .into_iter()
+                .filter_map(|event| sql.find(event).map(|pos| (pos, event)))
+                .min()
+                .map(|(_, event)| event)
+                .unwrap_or("");
+
+            triggers.push(TriggerInfo {
+                name: row.try_get("name")?,
+                event: event.to_string(),
+                timing: timing.to_string(),
+                // SQLite only supports row-level triggers
+                for_each: "ROW".to_string(),
+                condition: None,
+                body,
+                description: None,
+            });
+        }
+
+        Ok(TableDescription {
+            table: table_info,
+            columns,
+            indexes,
+            constraints: Vec::new(),
+            triggers,
+        })
     }
-    
+
     async fn generate_schemas(
         &self,
-        _output_dir: &PathBuf,
-        _force: bool,
-        _filter_tables: &[String],
+        output_dir: &PathBuf,
+        force: bool,
+        filter_tables: &[String],
     ) -> Result<()> {
-        // TODO: Implement SQLite schema generation
-        anyhow::bail!("SQLite schema generation not yet implemented")
+        // Create output directory if it doesn't exist
+        if !output_dir.exists() {
+            fs::create_dir_all(output_dir).await?;
+        }
+
+        let tables = self.list_tables(false).await?;
+        println!("📋 Found {} tables", tables.len());
+
+        let mut generated_count = 0;
+        let mut skipped_count = 0;
+
+        for table in tables {
+            // Filter tables if specified
+            if !filter_tables.is_empty() && !filter_tables.contains(&table.name) {
+                continue;
+            }
+
+            let schema_file = output_dir.join(format!("{}.yaml", table.name));
+
+            // Skip if file exists and force is false
+            if schema_file.exists() && !force {
+                println!("⚠️  Skipping existing schema: {}", schema_file.display());
+                skipped_count += 1;
+                continue;
+            }
+
+            // Get detailed table description
+            let description = self.describe_table(&table.name).await?;
+
+            // Generate YAML schema
+            let yaml_content = self.generate_table_schema_yaml(&description)?;
+
+            // Write schema file
+            fs::write(&schema_file, yaml_content).await?;
+            println!("✅ Generated schema: {}", schema_file.display());
+            generated_count += 1;
+        }
+
+        // Generate _meta.yaml
+        let meta_file = output_dir.join("_meta.yaml");
+        if !meta_file.exists() || force {
+            let meta_content = self.generate_meta_yaml().await?;
+            fs::write(&meta_file, meta_content).await?;
+            println!("✅ Generated metadata: {}", meta_file.display());
+        }
+
+        println!(
+            "📊 Generated {} schemas, skipped {}",
+            generated_count, skipped_count
+        );
+        Ok(())
     }
-    
+
     async fn generate_meta_yaml(&self) -> Result<String> {
-        // TODO: Implement SQLite meta YAML generation
-        Ok("# SQLite meta YAML generation not yet implemented\n".to_string())
+        let mut yaml = String::new();
+        yaml.push_str("# Global schema configuration - Generated from SQLite database\n\n");
+        yaml.push_str("version: \"1.0\"\n");
+        yaml.push_str("database_type: sqlite\n");
+        yaml.push_str(&format!("database_name: {}\n", self.db_name));
+        yaml.push_str(&format!(
+            "description: \"Schema generated from SQLite database '{}'\"\n",
+            self.db_name
+        ));
+        yaml.push_str("ai_context: \"Generated schemas from existing SQLite database structure with intelligent field mapping\"\n");
+        yaml.push('\n');
+        yaml.push_str("# Code generation settings\n");
+        yaml.push_str("generation:\n");
+        yaml.push_str("  base_class: \"BaseModel\"\n");
+        yaml.push_str("  use_traits: [HasTimestamps]\n");
+        yaml.push('\n');
+
+        Ok(yaml)
     }
-    
+
     async fn get_database_name(&self) -> Result<String> {
-        Ok("sqlite".to_string())
+        Ok(self.db_name.clone())
     }
-    
+
     async fn export_data(&self, _query: &str, _format: &str) -> Result<String> {
         // TODO: Implement SQLite data export
         Ok("[]".to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_field_type_mapping() {
+        assert_eq!(SqliteIntrospector::schema_field_type("INTEGER"), "int");
+        assert_eq!(SqliteIntrospector::schema_field_type("BIGINT"), "bigint");
+        assert_eq!(
+            SqliteIntrospector::schema_field_type("VARCHAR(255)"),
+            "string(255)"
+        );
+        assert_eq!(
+            SqliteIntrospector::schema_field_type("decimal(10, 2)"),
+            "decimal(10,2)"
+        );
+        assert_eq!(
+            SqliteIntrospector::schema_field_type("DOUBLE PRECISION"),
+            "double"
+        );
+        assert_eq!(SqliteIntrospector::schema_field_type("BOOLEAN"), "boolean");
+        assert_eq!(
+            SqliteIntrospector::schema_field_type("TIMESTAMP"),
+            "timestamp"
+        );
+        assert_eq!(SqliteIntrospector::schema_field_type("BYTEA"), "blob");
+        assert_eq!(SqliteIntrospector::schema_field_type(""), "blob");
+        // Unknown names follow SQLite's affinity rules
+        assert_eq!(
+            SqliteIntrospector::schema_field_type("UNSIGNED INTEGER"),
+            "int"
+        );
+        assert_eq!(SqliteIntrospector::schema_field_type("STRING"), "decimal");
+        assert_eq!(SqliteIntrospector::schema_field_type("NATIVE TEXT"), "text");
+    }
+
+    #[test]
+    fn test_parse_check_values() {
+        let sql = "CREATE TABLE users (\n  id INTEGER PRIMARY KEY,\n  role TEXT CHECK (role IN ('admin', 'user''s')) NOT NULL\n)";
+        assert_eq!(
+            SqliteIntrospector::parse_check_values(sql, "role"),
+            vec!["admin".to_string(), "user's".to_string()]
+        );
+        assert!(SqliteIntrospector::parse_check_values(sql, "id").is_empty());
+    }
+}
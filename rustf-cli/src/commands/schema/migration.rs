@@ -0,0 +1,645 @@
+//! Incremental migrations from schema diffs, shared by the database backends
+//!
+//! `schema generate migration <name>` compares the schema files with the
+//! snapshot taken when the previous migration was generated, and writes the
+//! difference as a pair of `.up.sql` / `.down.sql` files. The backends only
+//! differ in how they spell `ALTER TABLE`, so they pass their dialect and
+//! their type mapping to [`generate_migration`].
+
+use rustf_schema::types::FieldType;
+use rustf_schema::{ColumnChange, ColumnDiff, Field, Index, Schema, SchemaDiff, Table, TableDiff};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Directory under the migrations output holding the last migrated schema
+pub const SNAPSHOT_DIR: &str = ".schema_snapshot";
+
+/// SQL flavour of the generated statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+/// Backend mapping from schema types to column types
+pub type TypeMapper = fn(&FieldType) -> String;
+
+/// Generated SQL for both directions of a migration
+#[derive(Debug, Clone)]
+pub struct MigrationSql {
+    pub up: String,
+    pub down: String,
+    /// Destructive operations in the up migration
+    pub warnings: Vec<String>,
+}
+
+/// Write `<timestamp>_<name>.up.sql` and `.down.sql` for the changes since
+/// the previous schema, then snapshot the current one
+pub async fn generate_migration(
+    schema_path: &Path,
+    output_path: &Path,
+    name: &str,
+    from: Option<PathBuf>,
+    dialect: Dialect,
+    sql_type: TypeMapper,
+) -> anyhow::Result<()> {
+    println!("🚀 Generating migration '{}'...", name);
+
+    let previous_path = from.unwrap_or_else(|| output_path.join(SNAPSHOT_DIR));
+    if !previous_path.exists() {
+        anyhow::bail!(
+            "No previous schema at {}. Generate the initial migration with `schema generate migrations`, or pass --from <DIR>",
+            previous_path.display()
+        );
+    }
+
+    let old = Schema::load_from_directory(&previous_path).await?;
+    let new = Schema::load_from_directory(schema_path).await?;
+    let migration = render(&old, &new, dialect, sql_type);
+    if migration.up.is_empty() {
+        println!(
+            "✅ Schema unchanged since {}, nothing to generate",
+            previous_path.display()
+        );
+        return Ok(());
+    }
+
+    if !output_path.exists() {
+        fs::create_dir_all(output_path).await?;
+    }
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let slug = super::to_snake_case(name);
+    let up_file = output_path.join(format!("{}_{}.up.sql", timestamp, slug));
+    let down_file = output_path.join(format!("{}_{}.down.sql", timestamp, slug));
+    fs::write(&up_file, with_header(&migration.up, name, "up")).await?;
+    fs::write(&down_file, with_header(&migration.down, name, "down")).await?;
+    write_snapshot(schema_path, output_path).await?;
+
+    println!("✅ Generated {}", up_file.display());
+    println!("✅ Generated {}", down_file.display());
+    if !migration.warnings.is_empty() {
+        println!("\n⚠️  Review before applying, this migration is destructive:");
+        for warning in &migration.warnings {
+            println!("   - {}", warning);
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy the schema files to the snapshot directory the next migration
+/// is diffed against
+pub async fn write_snapshot(schema_path: &Path, output_path: &Path) -> anyhow::Result<()> {
+    let snapshot = output_path.join(SNAPSHOT_DIR);
+    if snapshot.exists() {
+        fs::remove_dir_all(&snapshot).await?;
+    }
+    fs::create_dir_all(&snapshot).await?;
+
+    let mut entries = fs::read_dir(schema_path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+        if is_yaml {
+            fs::copy(&path, snapshot.join(entry.file_name())).await?;
+        }
+    }
+    Ok(())
+}
+
+/// SQL turning `old` into `new` and back
+pub fn render(old: &Schema, new: &Schema, dialect: Dialect, sql_type: TypeMapper) -> MigrationSql {
+    let up = Schema::diff(old, new);
+    let down = Schema::diff(new, old);
+    let renderer = Renderer { dialect, sql_type };
+
+    MigrationSql {
+        up: renderer.diff(&up),
+        down: renderer.diff(&down),
+        warnings: up.warnings(),
+    }
+}
+
+fn with_header(sql: &str, name: &str, direction: &str) -> String {
+    format!(
+        "-- Migration: {} ({})\n-- Generated from schema changes, review before applying\n\n{}",
+        name, direction, sql
+    )
+}
+
+struct Renderer {
+    dialect: Dialect,
+    sql_type: TypeMapper,
+}
+
+impl Renderer {
+    fn diff(&self, diff: &SchemaDiff) -> String {
+        let mut sql = Vec::new();
+
+        for table in &diff.added_tables {
+            sql.push(self.create_table(&table.table, table));
+            sql.extend(
+                table
+                    .indexes
+                    .iter()
+                    .map(|index| self.create_index(table, index)),
+            );
+        }
+        for table in &diff.altered_tables {
+            sql.push(self.alter_table(table).join("\n"));
+        }
+        for table in &diff.removed_tables {
+            sql.push(format!("DROP TABLE {};", table.table));
+        }
+
+        if sql.is_empty() {
+            String::new()
+        } else {
+            sql.join("\n\n") + "\n"
+        }
+    }
+
+    fn create_table(&self, name: &str, table: &Table) -> String {
+        let composite_key = table.has_composite_key();
+        let mut field_names: Vec<&String> = table.fields.keys().collect();
+        field_names.sort();
+
+        let mut definitions: Vec<String> = field_names
+            .into_iter()
+            .map(|field_name| {
+                let field = &table.fields[field_name];
+                let mut definition = self.column_definition(&table.table, field_name, field);
+                if table.is_primary_key(field_name) && !composite_key {
+                    definition.push_str(" PRIMARY KEY");
+                }
+                format!("    {}", definition)
+            })
+            .collect();
+        if composite_key {
+            definitions.push(format!(
+                "    PRIMARY KEY ({})",
+                table.primary_key_fields().join(", ")
+            ));
+        }
+
+        format!("CREATE TABLE {} (\n{}\n);", name, definitions.join(",\n"))
+    }
+
+    /// `name TYPE [NOT NULL] [UNIQUE] [DEFAULT ...] [CHECK ...]`
+    fn column_definition(&self, table: &str, name: &str, field: &Field) -> String {
+        let mut definition = format!("{} {}", name, self.column_type(field));
+        if !field.is_nullable() {
+            definition.push_str(" NOT NULL");
+        }
+        if field.constraints.unique == Some(true) && field.constraints.primary_key != Some(true) {
+            definition.push_str(" UNIQUE");
+        }
+        if let Some(default) = default_sql(field) {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if let Some(check) = enum_check(name, field) {
+            definition.push_str(&format!(
+                " CONSTRAINT {} CHECK ({})",
+                check_name(table, name),
+                check
+            ));
+        }
+        definition
+    }
+
+    /// Column type without the enum CHECK, which is named separately
+    fn column_type(&self, field: &Field) -> String {
+        match field.field_type {
+            FieldType::Enum { .. } => "TEXT".to_string(),
+            _ => (self.sql_type)(&field.field_type),
+        }
+    }
+
+    fn create_index(&self, table: &Table, index: &Index) -> String {
+        let fields = index.fields();
+        let (unique, index_type) = match index {
+            Index::Detailed {
+                unique, index_type, ..
+            } => (unique.unwrap_or(false), index_type.as_deref()),
+            _ => (false, None),
+        };
+        let using = match (self.dialect, index_type) {
+            (Dialect::Postgres, Some(kind)) => format!(" USING {}", kind),
+            _ => String::new(),
+        };
+        format!(
+            "CREATE {}INDEX {} ON {}{} ({});",
+            if unique { "UNIQUE " } else { "" },
+            index_name(&table.table, &fields),
+            table.table,
+            using,
+            fields.join(", ")
+        )
+    }
+
+    fn drop_index(&self, table: &str, index: &Index) -> String {
+        let name = index_name(table, &index.fields());
+        match self.dialect {
+            Dialect::MySql => format!("DROP INDEX {} ON {};", name, table),
+            Dialect::Postgres | Dialect::Sqlite => format!("DROP INDEX {};", name),
+        }
+    }
+
+    fn alter_table(&self, diff: &TableDiff) -> Vec<String> {
+        let mut sql = vec![format!("-- Table: {}", diff.table)];
+        let table = &diff.table;
+
+        sql.extend(
+            diff.removed_indexes
+                .iter()
+                .map(|index| self.drop_index(table, index)),
+        );
+
+        if self.dialect == Dialect::Sqlite && needs_rebuild(diff) {
+            sql.extend(self.rebuild_table(diff));
+        } else {
+            if diff.primary_key.is_some() {
+                sql.push(match self.dialect {
+                    Dialect::MySql => format!("ALTER TABLE {} DROP PRIMARY KEY;", table),
+                    _ => format!("ALTER TABLE {} DROP CONSTRAINT {}_pkey;", table, table),
+                });
+            }
+            for column in &diff.added_columns {
+                sql.push(format!(
+                    "ALTER TABLE {} ADD COLUMN {};",
+                    table,
+                    self.column_definition(table, &column.name, column)
+                ));
+            }
+            for column in &diff.altered_columns {
+                sql.extend(self.alter_column(table, column));
+            }
+            for column in &diff.removed_columns {
+                sql.push(format!(
+                    "ALTER TABLE {} DROP COLUMN {};",
+                    table, column.name
+                ));
+            }
+            if let Some((_, key)) = &diff.primary_key {
+                if !key.is_empty() {
+                    sql.push(format!(
+                        "ALTER TABLE {} ADD PRIMARY KEY ({});",
+                        table,
+                        key.join(", ")
+                    ));
+                }
+            }
+        }
+
+        sql.extend(
+            diff.added_indexes
+                .iter()
+                .map(|index| self.create_index(&diff.new, index)),
+        );
+
+        for column in &diff.altered_columns {
+            if column.changes.contains(&ColumnChange::ForeignKey) {
+                sql.push(format!(
+                    "-- Foreign key of {}.{} changed; foreign keys are not managed by generated migrations",
+                    table, column.name
+                ));
+            }
+        }
+        for constraint in &diff.added_constraints {
+            sql.push(format!(
+                "-- Constraint added (checked by the application): {}",
+                constraint.message
+            ));
+        }
+        for constraint in &diff.removed_constraints {
+            sql.push(format!(
+                "-- Constraint removed (checked by the application): {}",
+                constraint.message
+            ));
+        }
+
+        sql
+    }
+
+    /// Statements for one changed column (Postgres and MySQL)
+    fn alter_column(&self, table: &str, column: &ColumnDiff) -> Vec<String> {
+        let name = &column.name;
+        let mut sql = Vec::new();
+
+        // Fill NULLs first so the NOT NULL constraint can be applied
+        if column.becomes_not_null() {
+            if let Some(default) = default_sql(&column.new) {
+                sql.push(format!(
+                    "UPDATE {} SET {} = {} WHERE {} IS NULL;",
+                    table, name, default, name
+                ));
+            }
+        }
+
+        let old_check = enum_check(name, &column.old).is_some();
+        let new_check = enum_check(name, &column.new);
+        let type_changed = column.changes.contains(&ColumnChange::Type);
+        if type_changed && old_check {
+            sql.push(match self.dialect {
+                Dialect::MySql => format!(
+                    "ALTER TABLE {} DROP CHECK {};",
+                    table,
+                    check_name(table, name)
+                ),
+                _ => format!(
+                    "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {};",
+                    table,
+                    check_name(table, name)
+                ),
+            });
+        }
+
+        match self.dialect {
+            Dialect::MySql => {
+                let changes_definition = column.changes.iter().any(|change| {
+                    matches!(
+                        change,
+                        ColumnChange::Type | ColumnChange::Nullability | ColumnChange::Default
+                    )
+                });
+                if changes_definition {
+                    // MODIFY restates the whole column, so leave out UNIQUE
+                    // and CHECK, which are handled on their own
+                    let mut definition = format!("{} {}", name, self.column_type(&column.new));
+                    if !column.new.is_nullable() {
+                        definition.push_str(" NOT NULL");
+                    }
+                    if let Some(default) = default_sql(&column.new) {
+                        definition.push_str(&format!(" DEFAULT {}", default));
+                    }
+                    sql.push(format!(
+                        "ALTER TABLE {} MODIFY COLUMN {};",
+                        table, definition
+                    ));
+                }
+            }
+            _ => {
+                let alter = format!("ALTER TABLE {} ALTER COLUMN {}", table, name);
+                if type_changed {
+                    let sql_type = self.column_type(&column.new);
+                    sql.push(format!(
+                        "{} TYPE {} USING {}::{};",
+                        alter, sql_type, name, sql_type
+                    ));
+                }
+                if column.changes.contains(&ColumnChange::Default) {
+                    sql.push(match default_sql(&column.new) {
+                        Some(default) => format!("{} SET DEFAULT {};", alter, default),
+                        None => format!("{} DROP DEFAULT;", alter),
+                    });
+                }
+                if column.changes.contains(&ColumnChange::Nullability) {
+                    if column.new.is_nullable() {
+                        sql.push(format!("{} DROP NOT NULL;", alter));
+                    } else {
+                        sql.push(format!("{} SET NOT NULL;", alter));
+                    }
+                }
+            }
+        }
+
+        if type_changed {
+            if let Some(check) = new_check {
+                sql.push(format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} CHECK ({});",
+                    table,
+                    check_name(table, name),
+                    check
+                ));
+            }
+        }
+
+        if column.changes.contains(&ColumnChange::Unique) {
+            let unique = column.new.constraints.unique == Some(true);
+            sql.push(match (self.dialect, unique) {
+                (Dialect::MySql, true) => format!("ALTER TABLE {} ADD UNIQUE ({});", table, name),
+                (Dialect::MySql, false) => format!("ALTER TABLE {} DROP INDEX {};", table, name),
+                (_, true) => format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {}_{}_key UNIQUE ({});",
+                    table, table, name, name
+                ),
+                (_, false) => format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {}_{}_key;",
+                    table, table, name
+                ),
+            });
+        }
+
+        sql
+    }
+
+    /// SQLite cannot alter columns in place: copy the rows into a table
+    /// with the new definition and swap it in
+    fn rebuild_table(&self, diff: &TableDiff) -> Vec<String> {
+        let table = &diff.table;
+        let rebuilt = format!("{}__new", table);
+
+        let mut kept: Vec<&str> = diff
+            .new
+            .fields
+            .keys()
+            .filter(|name| diff.old.fields.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        kept.sort_unstable();
+
+        let selected: Vec<String> = kept
+            .iter()
+            .map(|name| {
+                let altered = diff.altered_columns.iter().find(|c| c.name == *name);
+                match altered
+                    .and_then(|c| c.becomes_not_null().then(|| default_sql(&c.new)).flatten())
+                {
+                    Some(default) => format!("COALESCE({}, {})", name, default),
+                    None => name.to_string(),
+                }
+            })
+            .collect();
+
+        vec![
+            "PRAGMA foreign_keys = OFF;".to_string(),
+            self.create_table(&rebuilt, &diff.new),
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {};",
+                rebuilt,
+                kept.join(", "),
+                selected.join(", "),
+                table
+            ),
+            format!("DROP TABLE {};", table),
+            format!("ALTER TABLE {} RENAME TO {};", rebuilt, table),
+            // Indexes went away with the old table
+            diff.new
+                .indexes
+                .iter()
+                .filter(|index| {
+                    !diff
+                        .added_indexes
+                        .iter()
+                        .any(|added| added.fields() == index.fields())
+                })
+                .map(|index| self.create_index(&diff.new, index))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "PRAGMA foreign_keys = ON;".to_string(),
+        ]
+        .into_iter()
+        .filter(|statement| !statement.is_empty())
+        .collect()
+    }
+}
+
+/// Whether SQLite needs the table rebuilt rather than `ADD`/`DROP COLUMN`
+fn needs_rebuild(diff: &TableDiff) -> bool {
+    diff.primary_key.is_some()
+        || diff.altered_columns.iter().any(|column| {
+            column
+                .changes
+                .iter()
+                .any(|change| *change != ColumnChange::ForeignKey)
+        })
+        || diff
+            .added_columns
+            .iter()
+            .any(|column| column.constraints.unique == Some(true))
+}
+
+fn index_name(table: &str, fields: &[&str]) -> String {
+    format!("idx_{}_{}", table, fields.join("_"))
+}
+
+fn check_name(table: &str, column: &str) -> String {
+    format!("chk_{}_{}", table, column)
+}
+
+/// `column IN (...)` for enum columns
+fn enum_check(column: &str, field: &Field) -> Option<String> {
+    match &field.field_type {
+        FieldType::Enum { values, .. } => Some(format!(
+            "{} IN ({})",
+            column,
+            values
+                .iter()
+                .map(|v| quote(v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        _ => None,
+    }
+}
+
+fn default_sql(field: &Field) -> Option<String> {
+    match field.constraints.default.as_ref()? {
+        serde_json::Value::String(s) => Some(quote(s)),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustf_schema::FieldConstraints;
+
+    fn sql_type(field_type: &FieldType) -> String {
+        field_type.base_type().to_uppercase()
+    }
+
+    fn field(field_type: &str, nullable: bool, default: Option<serde_json::Value>) -> Field {
+        Field {
+            name: String::new(),
+            field_type: FieldType::parse(field_type),
+            lang_type: None,
+            postgres_type_name: None,
+            constraints: FieldConstraints {
+                nullable: Some(nullable),
+                default,
+                ..Default::default()
+            },
+            ai: None,
+            example: None,
+        }
+    }
+
+    fn schema(fields: Vec<(&str, Field)>) -> Schema {
+        let mut schema = Schema::new();
+        let table = Table {
+            name: "users".to_string(),
+            table: "users".to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(n, f)| (n.to_string(), f))
+                .collect(),
+            ..Default::default()
+        };
+        schema.tables.insert("users".to_string(), table);
+        schema
+    }
+
+    #[test]
+    fn test_alter_column_per_dialect() {
+        let old = schema(vec![("status", field("string", true, None))]);
+        let new = schema(vec![
+            (
+                "status",
+                field("text", false, Some(serde_json::json!("active"))),
+            ),
+            ("bio", field("text", true, None)),
+        ]);
+
+        let postgres = render(&old, &new, Dialect::Postgres, sql_type);
+        assert!(postgres
+            .up
+            .contains("ALTER TABLE users ADD COLUMN bio TEXT;"));
+        assert!(postgres
+            .up
+            .contains("UPDATE users SET status = 'active' WHERE status IS NULL;"));
+        assert!(postgres
+            .up
+            .contains("ALTER TABLE users ALTER COLUMN status TYPE TEXT USING status::TEXT;"));
+        assert!(postgres
+            .up
+            .contains("ALTER TABLE users ALTER COLUMN status SET NOT NULL;"));
+        assert!(postgres.down.contains("ALTER TABLE users DROP COLUMN bio;"));
+        assert!(postgres
+            .down
+            .contains("ALTER TABLE users ALTER COLUMN status DROP NOT NULL;"));
+        assert_eq!(postgres.warnings.len(), 2);
+
+        let mysql = render(&old, &new, Dialect::MySql, sql_type);
+        assert!(mysql
+            .up
+            .contains("ALTER TABLE users MODIFY COLUMN status TEXT NOT NULL DEFAULT 'active';"));
+
+        let sqlite = render(&old, &new, Dialect::Sqlite, sql_type);
+        assert!(sqlite.up.contains("CREATE TABLE users__new ("));
+        assert!(sqlite.up.contains(
+            "INSERT INTO users__new (status) SELECT COALESCE(status, 'active') FROM users;"
+        ));
+        assert!(sqlite
+            .up
+            .contains("ALTER TABLE users__new RENAME TO users;"));
+    }
+
+    #[test]
+    fn test_unchanged_schema_renders_nothing() {
+        let schema = schema(vec![("name", field("text", true, None))]);
+        let migration = render(&schema, &schema.clone(), Dialect::Postgres, sql_type);
+        assert!(migration.up.is_empty());
+        assert!(migration.warnings.is_empty());
+    }
+}
@@ -5,6 +5,7 @@
 pub mod postgres;
 pub mod mysql;
 pub mod sqlite;
+pub mod migration;
 
 use crate::analyzer::OutputFormat;
 use clap::{Args, Subcommand};
@@ -87,6 +88,25 @@ pub enum GenerateTarget {
         schema_path: PathBuf,
     },
     
+    /// Generate up/down SQL for the schema changes since the last migration
+    Migration {
+        /// Migration name, used in the file names
+        name: String,
+        
+        /// Directory with the previous schema (defaults to the snapshot
+        /// saved in the output directory by the last generated migration)
+        #[arg(long)]
+        from: Option<PathBuf>,
+        
+        /// Output directory for migrations
+        #[arg(short = 'o', long, default_value = "migrations")]
+        output: PathBuf,
+        
+        /// Path to schema directory
+        #[arg(short = 's', long, default_value = "schemas")]
+        schema_path: PathBuf,
+    },
+    
     /// Generate Rust model structs
     Models {
         /// Overwrite existing files
@@ -118,6 +138,7 @@ impl SchemaCommand {
         let backend = if let SchemaAction::Generate { ref target } = self.action {
             match target {
                 GenerateTarget::Migrations { schema_path, .. } |
+                GenerateTarget::Migration { schema_path, .. } |
                 GenerateTarget::Models { schema_path, .. } => {
                     detect_database_backend_from_path(schema_path).await?
                 }
@@ -204,6 +225,133 @@ pub fn escape_rust_keyword(field_name: &str) -> String {
     }
 }
 
+/// Shared utility: Generate optimistic locking items for a model's BaseModel impl
+///
+/// A non-nullable `lock_version` column opts the table into version checks on update.
+pub fn version_column_impl(table: &rustf_schema::Table) -> String {
+    match table.fields.get("lock_version") {
+        Some(field) if !field.constraints.nullable.unwrap_or(false) => {
+            "    const VERSION_COLUMN: Option<&'static str> = Some(\"lock_version\");\n    \n    fn set_version(&mut self, version: i64) {\n        self.lock_version = version as _;\n    }\n    \n".to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Shared utility: Generate the composite key columns for a model's BaseModel impl
+///
+/// Only tables whose primary key spans several columns declare them.
+pub fn composite_key_impl(table: &rustf_schema::Table) -> String {
+    if !table.has_composite_key() {
+        return String::new();
+    }
+    let columns: Vec<String> = table
+        .primary_key_fields()
+        .iter()
+        .map(|column| format!("\"{}\"", column))
+        .collect();
+    format!(
+        "    const PRIMARY_KEY_COLUMNS: &'static [&'static str] = &[{}];\n    \n",
+        columns.join(", ")
+    )
+}
+
+/// Shared utility: Generate the query cache opt-in for a model's BaseModel impl
+///
+/// Tables with `invalidate_cache: true` clear their cached queries on every write.
+pub fn cache_invalidation_impl(table: &rustf_schema::Table) -> String {
+    if table.invalidate_cache {
+        "    const INVALIDATE_QUERY_CACHE: bool = true;\n    \n".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Shared utility: Generate the audit opt-in for a model's BaseModel impl
+///
+/// Tables with `audit: true` record their model writes in the audit log.
+pub fn audit_writes_impl(table: &rustf_schema::Table) -> String {
+    if table.audit {
+        "    const AUDIT_WRITES: bool = true;\n    \n".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Shared utility: Generate the relation list for a model's BaseModel impl
+///
+/// Covers the model's own relations and those of every table reachable
+/// through them, so nested preloads like `with("posts.comments")` resolve.
+pub fn relations_impl(table: &rustf_schema::Table, schema: &Schema) -> String {
+    let find = |model: &str| {
+        schema.tables.get(model).or_else(|| {
+            schema
+                .tables
+                .values()
+                .find(|t| t.name == model || t.table == model)
+        })
+    };
+
+    let mut visited: Vec<&str> = Vec::new();
+    let mut pending = vec![table];
+    let mut entries = Vec::new();
+    while let Some(current) = pending.pop() {
+        if visited.contains(&current.table.as_str()) {
+            continue;
+        }
+        visited.push(&current.table);
+
+        let relations = &current.relations;
+        let mut declared: Vec<(&String, &str, &str, &str, &str)> = Vec::new();
+        for (name, rel) in relations.belongs_to.iter().flatten() {
+            declared.push((
+                name,
+                "BelongsTo",
+                &rel.model,
+                &rel.local_field,
+                &rel.foreign_field,
+            ));
+        }
+        for (name, rel) in relations.has_one.iter().flatten() {
+            declared.push((
+                name,
+                "HasOne",
+                &rel.model,
+                &rel.local_field,
+                &rel.foreign_field,
+            ));
+        }
+        for (name, rel) in relations.has_many.iter().flatten() {
+            declared.push((
+                name,
+                "HasMany",
+                &rel.model,
+                &rel.local_field,
+                &rel.foreign_field,
+            ));
+        }
+        declared.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, kind, model, local_field, foreign_field) in declared {
+            let Some(target) = find(model) else {
+                continue;
+            };
+            entries.push(format!(
+                "        rustf::models::Relation {{\n            owner: \"{}\",\n            name: \"{}\",\n            kind: rustf::models::RelationKind::{},\n            table: \"{}\",\n            local_field: \"{}\",\n            foreign_field: \"{}\",\n        }},\n",
+                current.table, name, kind, target.table, local_field, foreign_field
+            ));
+            pending.push(target);
+        }
+    }
+
+    if entries.is_empty() {
+        return String::new();
+    }
+    format!(
+        "    const RELATIONS: &'static [rustf::models::Relation] = &[\n{}    ];\n    \n",
+        entries.concat()
+    )
+}
+
 /// Convert string to PascalCase
 pub fn to_pascal_case(s: &str) -> String {
     s.split('_')
@@ -239,6 +239,22 @@ async fn generate_code(target: GenerateTarget) -> anyhow::Result<()> {
         } => {
             generate_migrations(&schema_path, &output).await?;
         }
+        GenerateTarget::Migration {
+            name,
+            from,
+            schema_path,
+            output,
+        } => {
+            super::migration::generate_migration(
+                &schema_path,
+                &output,
+                &name,
+                from,
+                super::migration::Dialect::Postgres,
+                field_type_to_sql,
+            )
+            .await?;
+        }
     }
     Ok(())
 }
@@ -441,6 +457,7 @@ async fn generate_migrations(schema_path: &Path, output_path: &Path) -> anyhow::
 
     let sql_code = generate_sql_schema(&schema)?;
     fs::write(&migration_file, sql_code).await?;
+    super::migration::write_snapshot(schema_path, output_path).await?;
 
     println!("✅ Generated {}", migration_file.display());
     println!("🎉 Migration generated successfully!");
@@ -627,9 +644,11 @@ fn generate_sql_schema(schema: &Schema) -> anyhow::Result<String> {
         sql.push_str(&format!("CREATE TABLE {} (\n", table.table));
 
         let mut field_definitions = Vec::new();
+        let composite_key = table.has_composite_key();
 
         for (field_name, field) in &table.fields {
             let sql_type = field_type_to_sql(&field.field_type);
+            let is_key = table.is_primary_key(field_name);
             let mut definition = format!("    {} {}", field_name, sql_type);
 
             if field.constraints.required == Some(true) || field.constraints.nullable != Some(true)
@@ -637,12 +656,11 @@ fn generate_sql_schema(schema: &Schema) -> anyhow::Result<String> {
                 definition.push_str(" NOT NULL");
             }
 
-            if field.constraints.primary_key == Some(true) {
+            if is_key && !composite_key {
                 definition.push_str(" PRIMARY KEY");
             }
 
-            if field.constraints.unique == Some(true) && field.constraints.primary_key != Some(true)
-            {
+            if field.constraints.unique == Some(true) && !is_key {
                 definition.push_str(" UNIQUE");
             }
 
@@ -660,6 +678,14 @@ fn generate_sql_schema(schema: &Schema) -> anyhow::Result<String> {
             field_definitions.push(definition);
         }
 
+        // Composite keys are declared once, after the columns
+        if composite_key {
+            field_definitions.push(format!(
+                "    PRIMARY KEY ({})",
+                table.primary_key_fields().join(", ")
+            ));
+        }
+
         sql.push_str(&field_definitions.join(",\n"));
         sql.push_str("\n);\n\n");
     }
@@ -979,7 +1005,7 @@ fn process_template(content: &str, variables: &HashMap<String, String>) -> Strin
 /// Prepare template variables for base model generation
 fn prepare_base_model_variables(
     table: &rustf_schema::Table,
-    _schema: &Schema,
+    schema: &Schema,
     checksum: &str,
     generation_time: &str,
     pool_type: &str,
@@ -2956,6 +2982,11 @@ fn prepare_base_model_variables(
         .map(|f| f.name.clone())
         .unwrap_or_else(|| "id".to_string());
     vars.insert("primary_key".to_string(), primary_key);
+    vars.insert("version_column_impl".to_string(), super::version_column_impl(table));
+    vars.insert("composite_key_impl".to_string(), super::composite_key_impl(table));
+    vars.insert("cache_invalidation_impl".to_string(), super::cache_invalidation_impl(table));
+    vars.insert("audit_writes_impl".to_string(), super::audit_writes_impl(table));
+    vars.insert("relations_impl".to_string(), super::relations_impl(table, schema));
 
     // Generate PostgreSQL FromRow implementation block
     let mut from_row_pg = Vec::new();
@@ -6,6 +6,10 @@ use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
+/// Plural variants generated for `@(#key, count)` usages. Languages with more
+/// CLDR categories (few, many, ...) add them by hand in their own resource file.
+const PLURAL_VARIANTS: [&str; 2] = ["one", "other"];
+
 pub struct TranslationScanner {
     sections: IndexMap<String, IndexMap<String, String>>,
     key_generator: KeyGenerator,
@@ -53,7 +57,13 @@ impl TranslationScanner {
         for cap in pattern.captures_iter(&content) {
             let text = &cap[1];
             
-            if text.starts_with('#') {
+            if let Some((key, _count)) = text.strip_prefix('#').and_then(|t| t.split_once(',')) {
+                // Plural usage @(#key, count) - track the CLDR variants every language needs
+                for category in PLURAL_VARIANTS {
+                    view_translations.entry(format!("{}.{}", key.trim(), category))
+                        .or_insert_with(String::new);
+                }
+            } else if text.starts_with('#') {
                 // Custom key reference - just track it exists
                 let key = &text[1..];
                 view_translations.entry(key.to_string())
@@ -1,3 +1,4 @@
+use crate::analyzer::wiring;
 use anyhow::Result;
 use std::fs;
 use std::path::PathBuf;
@@ -147,7 +148,19 @@ pub async fn run(project_path: PathBuf, fix: bool) -> Result<()> {
         }
     }
 
-    // 5. Validate dependencies and build
+    // 5. Validate routes and auto-discovery wiring
+    println!("\n🔗 Validating routes and auto-discovered files...");
+
+    let wiring_issues = wiring::check_project(&project_path);
+    if wiring_issues.is_empty() {
+        println!("✅ Routes and auto-discovered files are wired correctly");
+    } else {
+        for issue in wiring_issues {
+            issues.push(format!("❌ {}", issue));
+        }
+    }
+
+    // 6. Validate dependencies and build
     println!("\n🔧 Validating build configuration...");
 
     // Check if the project can be built
@@ -170,7 +183,7 @@ pub async fn run(project_path: PathBuf, fix: bool) -> Result<()> {
         }
     }
 
-    // 6. Report results
+    // 7. Report results
     println!("\n📊 Validation Summary");
     println!("=====================");
 
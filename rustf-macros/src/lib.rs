@@ -5,14 +5,16 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use syn::{parse_macro_input, parse_quote, ItemFn};
 use walkdir::WalkDir;
 
 /// Auto-discover and include all controllers from src/controllers/*.rs
 ///
 /// This macro scans the controllers directory at build time and generates
-/// the necessary module declarations and route aggregation code.
+/// the necessary module declarations and route aggregation code. Routes come
+/// from each controller's `install()` function, if it has one, followed by
+/// its `#[route(...)]` handlers.
 ///
 /// # Usage
 /// ```rust,ignore
@@ -26,6 +28,69 @@ pub fn auto_controllers(_input: TokenStream) -> TokenStream {
     generate_auto_discovery("controllers", "install").into()
 }
 
+/// Declare a route on a controller handler
+///
+/// `auto_controllers!()` collects annotated handlers into the route list, so
+/// the controller needs no `install()` function. A handler can carry several
+/// route attributes. The `xhr` flag restricts the route to AJAX requests, and
+/// the `XHR` method matches both GET and POST like `Route::xhr`.
+///
+/// # Usage
+/// ```rust,ignore
+/// use rustf::prelude::*;
+///
+/// #[route(GET, "/users/{id}")]
+/// #[route(GET, "/api/users/{id}", xhr)]
+/// pub async fn show(ctx: &mut Context) -> Result<()> {
+///     ctx.json(json!({ "id": ctx.param("id") }))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn route(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as RouteArgs);
+    let input_fn = parse_macro_input!(input as ItemFn);
+
+    if let Err(error) = args.validate() {
+        return error.to_compile_error().into();
+    }
+    if input_fn.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(input_fn.sig.fn_token, "route handlers must be async")
+            .to_compile_error()
+            .into();
+    }
+
+    // Routes are collected by auto_controllers!(); the handler is unchanged
+    quote! { #input_fn }.into()
+}
+
+/// Validate the request body before a handler runs
+///
+/// The handler takes the validated value as its second argument. The body is
+/// checked with the validator registered under the given name, then
+/// deserialized into the argument's type; on failure the handler is skipped
+/// and the client gets a 400 problem document listing the errors.
+///
+/// # Usage
+/// ```rust,ignore
+/// use rustf::prelude::*;
+///
+/// #[route(POST, "/users")]
+/// #[validate(body = "UserCreate")]
+/// pub async fn create(ctx: &mut Context, user: UserCreate) -> Result<()> {
+///     ctx.json(json!({ "name": user.name }))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ValidateArgs);
+    let input_fn = parse_macro_input!(input as ItemFn);
+
+    match expand_validate(args, input_fn) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
 /// Auto-discover and include all models from src/models/*.rs
 ///
 /// This macro scans the models directory at build time and generates
@@ -130,6 +195,212 @@ pub fn auto_workers(_input: TokenStream) -> TokenStream {
     generate_worker_discovery().into()
 }
 
+/// Arguments of `#[route(METHOD, "/path")]` and `#[route(METHOD, "/path", xhr)]`
+struct RouteArgs {
+    method: syn::Ident,
+    path: syn::LitStr,
+    xhr: bool,
+}
+
+impl syn::parse::Parse for RouteArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let method: syn::Ident = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let path: syn::LitStr = input.parse()?;
+
+        let mut xhr = false;
+        if input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            let flag: syn::Ident = input.parse()?;
+            if flag != "xhr" {
+                return Err(syn::Error::new_spanned(flag, "expected `xhr`"));
+            }
+            xhr = true;
+            input.parse::<Option<syn::Token![,]>>()?;
+        }
+
+        Ok(Self { method, path, xhr })
+    }
+}
+
+impl RouteArgs {
+    const METHODS: [&'static str; 8] = [
+        "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "XHR",
+    ];
+
+    fn validate(&self) -> syn::Result<()> {
+        if !Self::METHODS.contains(&self.method.to_string().as_str()) {
+            return Err(syn::Error::new_spanned(
+                &self.method,
+                format!(
+                    "unknown HTTP method, expected one of {}",
+                    Self::METHODS.join(", ")
+                ),
+            ));
+        }
+        if !self.path.value().starts_with('/') {
+            return Err(syn::Error::new_spanned(
+                &self.path,
+                "route path must start with `/`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `#[validate(body = "Validator")]`
+struct ValidateArgs {
+    body: syn::LitStr,
+}
+
+impl syn::parse::Parse for ValidateArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        if key != "body" {
+            return Err(syn::Error::new_spanned(
+                key,
+                "expected `body = \"Validator\"`",
+            ));
+        }
+        input.parse::<syn::Token![=]>()?;
+        let body = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self { body })
+    }
+}
+
+/// Wrap a handler taking `(ctx, value)` into a plain `(ctx)` handler
+///
+/// The original function is kept as a nested item and called with the
+/// validated body, so other attributes (like `#[route]`) see a regular handler.
+fn expand_validate(args: ValidateArgs, input_fn: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    if input_fn.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            input_fn.sig.fn_token,
+            "validated handlers must be async",
+        ));
+    }
+
+    let inputs: Vec<&syn::FnArg> = input_fn.sig.inputs.iter().collect();
+    let typed = |arg: &syn::FnArg| match arg {
+        syn::FnArg::Typed(pat_type) => Some(pat_type.clone()),
+        syn::FnArg::Receiver(_) => None,
+    };
+    let (Some(ctx_arg), Some(body_arg), 2) = (
+        inputs.first().and_then(|arg| typed(arg)),
+        inputs.get(1).and_then(|arg| typed(arg)),
+        inputs.len(),
+    ) else {
+        return Err(syn::Error::new_spanned(
+            &input_fn.sig.inputs,
+            "expected `(ctx: &mut Context, body: T)`",
+        ));
+    };
+    let syn::Pat::Ident(ctx_ident) = ctx_arg.pat.as_ref() else {
+        return Err(syn::Error::new_spanned(
+            &ctx_arg.pat,
+            "the context argument must be a plain identifier",
+        ));
+    };
+    let ctx = &ctx_ident.ident;
+    let body_type = &body_arg.ty;
+    let validator = &args.body;
+
+    let mut inner = input_fn.clone();
+    inner.attrs.clear();
+    inner.vis = syn::Visibility::Inherited;
+    inner.sig.ident = syn::Ident::new(
+        &format!("__validated_{}", input_fn.sig.ident),
+        input_fn.sig.ident.span(),
+    );
+    let inner_ident = &inner.sig.ident;
+
+    let mut outer_sig = input_fn.sig.clone();
+    outer_sig.inputs = parse_quote! { #ctx_arg };
+    let attrs = &input_fn.attrs;
+    let vis = &input_fn.vis;
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #outer_sig {
+            #inner
+
+            let body = #ctx.validated_body::<#body_type>(#validator).await?;
+            #inner_ident(#ctx, body).await
+        }
+    })
+}
+
+/// Generate the route collection code for one controller file
+///
+/// Calls the controller's `install()` function when it defines one, then adds
+/// a route for every `#[route(...)]` attribute on its top-level functions.
+fn controller_routes(
+    path: &Path,
+    module_ident: &syn::Ident,
+    fn_name: &str,
+    path_prefix: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let install_ident = syn::Ident::new(fn_name, proc_macro2::Span::call_site());
+
+    // Unparseable files keep the old behaviour so the compiler reports the real error
+    let file = match std::fs::read_to_string(path)
+        .ok()
+        .and_then(|source| syn::parse_file(&source).ok())
+    {
+        Some(file) => file,
+        None => return quote! { routes.extend(#module_ident::#install_ident()); },
+    };
+
+    let mut calls = Vec::new();
+    let mut annotated = Vec::new();
+
+    for item in &file.items {
+        let syn::Item::Fn(item_fn) = item else {
+            continue;
+        };
+
+        if item_fn.sig.ident == fn_name {
+            calls.push(quote! { routes.extend(#module_ident::#install_ident()); });
+        }
+
+        for attr in &item_fn.attrs {
+            let is_route = attr
+                .path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "route");
+            // Invalid arguments are reported by the attribute macro itself
+            let Some(args) = is_route
+                .then(|| attr.parse_args::<RouteArgs>().ok())
+                .flatten()
+            else {
+                continue;
+            };
+
+            let handler = &item_fn.sig.ident;
+            let method = args.method.to_string();
+            let route_path = args.path;
+            // `XHR` routes are AJAX-only, like Route::xhr()
+            let xhr = args.xhr || method == "XHR";
+            annotated.push(quote! {
+                routes.push({
+                    let mut route = #path_prefix::routing::Route::new(
+                        #method,
+                        #route_path,
+                        |ctx| Box::pin(async { #module_ident::#handler(ctx).await }),
+                    );
+                    route.xhr_only = #xhr;
+                    route
+                });
+            });
+        }
+    }
+
+    calls.extend(annotated);
+    quote! { #(#calls)* }
+}
+
 /// Generate auto-discovery code for a given directory and function name
 fn generate_auto_discovery(dir_name: &str, fn_name: &str) -> proc_macro2::TokenStream {
     let manifest_dir =
@@ -204,6 +475,13 @@ fn generate_auto_discovery(dir_name: &str, fn_name: &str) -> proc_macro2::TokenS
         };
     }
 
+    // Use correct path prefix based on whether we're in rustf framework or user project
+    let path_prefix = if is_rustf_framework {
+        quote! { crate }
+    } else {
+        quote! { rustf }
+    };
+
     let mut modules = Vec::new();
     let mut function_calls = Vec::new();
 
@@ -290,10 +568,12 @@ fn generate_auto_discovery(dir_name: &str, fn_name: &str) -> proc_macro2::TokenS
             // Generate function call using the simple identifier
             match dir_name {
                 "controllers" => {
-                    let fn_ident = syn::Ident::new(fn_name, proc_macro2::Span::call_site());
-                    function_calls.push(quote! {
-                        routes.extend(#module_ident::#fn_ident());
-                    });
+                    function_calls.push(controller_routes(
+                        path,
+                        &module_ident,
+                        fn_name,
+                        &path_prefix,
+                    ));
                 }
                 "models" => {
                     let fn_ident = syn::Ident::new(fn_name, proc_macro2::Span::call_site());
@@ -344,10 +624,12 @@ fn generate_auto_discovery(dir_name: &str, fn_name: &str) -> proc_macro2::TokenS
             // Generate function call with full module path
             match dir_name {
                 "controllers" => {
-                    let fn_ident = syn::Ident::new(fn_name, proc_macro2::Span::call_site());
-                    function_calls.push(quote! {
-                        routes.extend(#module_ident::#fn_ident());
-                    });
+                    function_calls.push(controller_routes(
+                        path,
+                        &module_ident,
+                        fn_name,
+                        &path_prefix,
+                    ));
                 }
                 "models" => {
                     let fn_ident = syn::Ident::new(fn_name, proc_macro2::Span::call_site());
@@ -385,13 +667,6 @@ fn generate_auto_discovery(dir_name: &str, fn_name: &str) -> proc_macro2::TokenS
     }
 
     // Generate the final code based on directory type
-    // Use correct path prefix based on whether we're in rustf framework or user project
-    let path_prefix = if is_rustf_framework {
-        quote! { crate }
-    } else {
-        quote! { rustf }
-    };
-
     match dir_name {
         "controllers" => {
             let controller_count = function_calls.len();
@@ -896,3 +1171,74 @@ fn generate_worker_discovery() -> proc_macro2::TokenStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn expand(args: &str, input: proc_macro2::TokenStream) -> syn::Result<ItemFn> {
+        let args: ValidateArgs = syn::parse_str(args)?;
+        let tokens = expand_validate(args, syn::parse2(input)?)?;
+        syn::parse2(tokens)
+    }
+
+    #[test]
+    fn test_validate_expansion() {
+        let expanded = expand(
+            r#"body = "UserCreate""#,
+            quote! {
+                #[route(POST, "/users")]
+                pub async fn create(ctx: &mut Context, user: UserCreate) -> Result<()> {
+                    ctx.json(json!({ "name": user.name }))
+                }
+            },
+        )
+        .unwrap();
+
+        // The handler keeps its name, attributes and visibility, and takes only the context
+        assert_eq!(expanded.sig.ident, "create");
+        assert_eq!(expanded.sig.inputs.len(), 1);
+        assert!(expanded.attrs[0].path().is_ident("route"));
+        assert!(matches!(expanded.vis, syn::Visibility::Public(_)));
+
+        // The original body moves to a nested function called with the validated value
+        let syn::Stmt::Item(syn::Item::Fn(inner)) = &expanded.block.stmts[0] else {
+            panic!("expected the nested handler first");
+        };
+        assert_eq!(inner.sig.ident, "__validated_create");
+        assert_eq!(inner.sig.inputs.len(), 2);
+        assert!(inner.attrs.is_empty());
+
+        let body = expanded.block.to_token_stream().to_string();
+        assert!(body.contains(r#"ctx . validated_body :: < UserCreate > ("UserCreate") . await ?"#));
+        assert!(body.contains("__validated_create (ctx , body) . await"));
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_handlers() {
+        let error = |args: &str, input| expand(args, input).unwrap_err().to_string();
+
+        assert_eq!(
+            error(
+                r#"body = "UserCreate""#,
+                quote! { fn create(ctx: &mut Context, user: UserCreate) -> Result<()> {} }
+            ),
+            "validated handlers must be async"
+        );
+        assert_eq!(
+            error(
+                r#"body = "UserCreate""#,
+                quote! { async fn create(ctx: &mut Context) -> Result<()> {} }
+            ),
+            "expected `(ctx: &mut Context, body: T)`"
+        );
+        assert_eq!(
+            error(
+                r#"schema = "UserCreate""#,
+                quote! { async fn create(ctx: &mut Context, user: UserCreate) -> Result<()> {} }
+            ),
+            r#"expected `body = "Validator"`"#
+        );
+    }
+}
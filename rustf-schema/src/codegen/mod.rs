@@ -56,6 +56,9 @@ impl TemplateGenerator {
     pub fn new() -> Self {
         let mut handlebars = Handlebars::new();
         
+        // Generated code is Rust/SQL, not HTML
+        handlebars.register_escape_fn(handlebars::no_escape);
+        
         // Register helper functions
         handlebars.register_helper("snake_case", Box::new(snake_case_helper));
         handlebars.register_helper("camel_case", Box::new(camel_case_helper));
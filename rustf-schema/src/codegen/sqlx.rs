@@ -44,10 +44,48 @@ impl SqlxGenerator {
             serde_json::to_value(&rust_fields)?);
         context.variables.insert("primary_key".to_string(), 
             serde_json::to_value(self.find_primary_key(table))?);
+        let insert_fields = self.generate_insert_fields(table)?;
+        let insert_placeholders: Vec<String> = (1..=insert_fields.len())
+            .map(|index| format!("${}", index))
+            .collect();
+        context.variables.insert("insert_placeholders".to_string(),
+            serde_json::to_value(insert_placeholders.join(", "))?);
         context.variables.insert("insert_fields".to_string(), 
-            serde_json::to_value(self.generate_insert_fields(table)?)?);
+            serde_json::to_value(insert_fields)?);
+        let update_fields = self.generate_update_fields(table)?;
+        context.variables.insert("primary_key_placeholder".to_string(), 
+            serde_json::to_value(update_fields.len() + 1)?);
+        
+        // Key lookups use every primary key column; composite keys take a tuple
+        let key = self.primary_key_info(table, &rust_fields, update_fields.len());
+        context.variables.insert("composite_key".to_string(),
+            serde_json::to_value(key.fields.len() > 1)?);
+        context.variables.insert("key_fields".to_string(),
+            serde_json::to_value(&key.fields)?);
+        context.variables.insert("key_type".to_string(),
+            serde_json::to_value(&key.rust_type)?);
+        context.variables.insert("key_param".to_string(),
+            serde_json::to_value(&key.param)?);
+        context.variables.insert("key_args".to_string(),
+            serde_json::to_value(&key.args)?);
+        context.variables.insert("key_where".to_string(),
+            serde_json::to_value(&key.where_clause)?);
+        context.variables.insert("key_update_where".to_string(),
+            serde_json::to_value(&key.update_where_clause)?);
+        context.variables.insert("update_set".to_string(),
+            serde_json::to_value(Self::numbered_assignments(&update_fields))?);
         context.variables.insert("update_fields".to_string(), 
-            serde_json::to_value(self.generate_update_fields(table)?)?);
+            serde_json::to_value(update_fields)?);
+        
+        // Encrypted columns are wrapped on write and read
+        let encrypted_fields: Vec<&str> = rust_fields.iter()
+            .filter(|field| field.encrypted)
+            .map(|field| field.name.as_str())
+            .collect();
+        context.variables.insert("has_encrypted".to_string(),
+            serde_json::to_value(!encrypted_fields.is_empty())?);
+        context.variables.insert("encrypted_fields".to_string(),
+            serde_json::to_value(&encrypted_fields)?);
         
         // Add type constants for AI agent reference
         context.variables.insert("type_constants".to_string(),
@@ -68,13 +106,26 @@ impl SqlxGenerator {
     
     /// Generate CRUD operations for a table
     pub fn generate_crud(&self, table_name: &str, table: &Table, schema: &Schema) -> Result<String> {
-        let context = GenerationContext {
+        let mut context = GenerationContext {
             schema: schema.clone(),
             table: table.clone(),
             table_name: table_name.to_string(),
             variables: HashMap::new(),
         };
         
+        let rust_fields = self.generate_rust_fields(table)?;
+        let key = self.primary_key_info(table, &rust_fields, 0);
+        context.variables.insert("primary_key".to_string(), 
+            serde_json::to_value(self.find_primary_key(table))?);
+        context.variables.insert("key_type".to_string(),
+            serde_json::to_value(&key.rust_type)?);
+        context.variables.insert("key_param".to_string(),
+            serde_json::to_value(&key.param)?);
+        context.variables.insert("key_args".to_string(),
+            serde_json::to_value(&key.args)?);
+        context.variables.insert("key_where".to_string(),
+            serde_json::to_value(&key.where_clause)?);
+        
         self.template_generator.render("crud", &context)
     }
     
@@ -162,9 +213,10 @@ impl SqlxGenerator {
                 rust_type,
                 sqlx_type,
                 nullable: field.constraints.nullable.unwrap_or(false),
-                primary_key: field.constraints.primary_key.unwrap_or(false),
+                primary_key: table.is_primary_key(field_name),
                 auto_increment: matches!(field.constraints.auto, Some(crate::types::AutoGenerate::Boolean(true))),
                 unique: field.constraints.unique.unwrap_or(false),
+                encrypted: field.constraints.encrypted.unwrap_or(false),
                 default: field.constraints.default.clone(),
                 ai_hint: field.ai.clone(),
             });
@@ -173,13 +225,68 @@ impl SqlxGenerator {
         Ok(fields)
     }
     
+    /// Primary key column list, comma-separated for composite keys
     fn find_primary_key(&self, table: &Table) -> Option<String> {
-        for (field_name, field) in &table.fields {
-            if field.constraints.primary_key.unwrap_or(false) {
-                return Some(field_name.clone());
-            }
+        let fields = table.primary_key_fields();
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields.join(", "))
+        }
+    }
+    
+    /// SET list of an UPDATE, with placeholders numbered from `$1`
+    fn numbered_assignments(fields: &[String]) -> String {
+        fields.iter()
+            .enumerate()
+            .map(|(index, name)| format!("{} = ${}", name, index + 1))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+    
+    /// Describe how generated code binds the primary key
+    ///
+    /// `placeholder_offset` is the number of placeholders that precede the key
+    /// in the statement (the SET columns of an UPDATE).
+    fn primary_key_info(&self, table: &Table, rust_fields: &[RustField], placeholder_offset: usize) -> KeyInfo {
+        let fields: Vec<String> = table.primary_key_fields().into_iter().map(String::from).collect();
+        let types: Vec<String> = fields.iter()
+            .map(|name| {
+                rust_fields.iter()
+                    .find(|field| &field.name == name)
+                    .map(|field| field.rust_type.clone())
+                    .unwrap_or_else(|| "String".to_string())
+            })
+            .collect();
+        
+        let condition = |offset: usize| fields.iter()
+            .enumerate()
+            .map(|(index, name)| format!("{} = ${}", name, offset + index + 1))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        
+        let (rust_type, param, args) = if fields.len() > 1 {
+            (
+                format!("({})", types.join(", ")),
+                "key".to_string(),
+                (0..fields.len()).map(|index| format!("key.{}", index)).collect(),
+            )
+        } else {
+            (
+                types.first().cloned().unwrap_or_default(),
+                "id".to_string(),
+                vec!["id".to_string()],
+            )
+        };
+        
+        KeyInfo {
+            where_clause: condition(0),
+            update_where_clause: condition(placeholder_offset),
+            fields,
+            rust_type,
+            param,
+            args,
         }
-        None
     }
     
     fn generate_insert_fields(&self, table: &Table) -> Result<Vec<String>> {
@@ -187,7 +294,7 @@ impl SqlxGenerator {
         
         for (field_name, field) in &table.fields {
             // Skip auto-increment primary keys
-            if field.constraints.primary_key.unwrap_or(false) && 
+            if table.is_primary_key(field_name) && 
                matches!(field.constraints.auto, Some(crate::types::AutoGenerate::Boolean(true))) {
                 continue;
             }
@@ -201,9 +308,9 @@ impl SqlxGenerator {
     fn generate_update_fields(&self, table: &Table) -> Result<Vec<String>> {
         let mut fields = Vec::new();
         
-        for (field_name, field) in &table.fields {
+        for field_name in table.fields.keys() {
             // Skip primary keys from updates
-            if field.constraints.primary_key.unwrap_or(false) {
+            if table.is_primary_key(field_name) {
                 continue;
             }
             
@@ -284,10 +391,28 @@ pub struct RustField {
     pub primary_key: bool,
     pub auto_increment: bool,
     pub unique: bool,
+    pub encrypted: bool,
     pub default: Option<serde_json::Value>,
     pub ai_hint: Option<String>,
 }
 
+/// Primary key binding details for template generation
+#[derive(Debug, Clone)]
+struct KeyInfo {
+    /// Key column names in key order
+    fields: Vec<String>,
+    /// Rust type of the key (a tuple for composite keys)
+    rust_type: String,
+    /// Name of the key argument in generated lookups
+    param: String,
+    /// Expressions binding each key column from the argument
+    args: Vec<String>,
+    /// `col = $1 AND ...` matching the key
+    where_clause: String,
+    /// Same condition with placeholders after the UPDATE columns
+    update_where_clause: String,
+}
+
 /// Type constant representation for AI agent reference
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct TypeConstant {
@@ -325,9 +450,12 @@ mod tests {
             tags: vec![],
             ai_context: None,
             fields: HashMap::new(),
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         let id_field = Field {
@@ -347,4 +475,43 @@ mod tests {
         
         assert_eq!(generator.find_primary_key(&table), Some("id".to_string()));
     }
+    
+    #[test]
+    fn test_composite_primary_key() {
+        let generator = SqlxGenerator::new().unwrap();
+        
+        let field = |name: &str, field_type: &str| Field {
+            name: name.to_string(),
+            field_type: FieldType::Simple(field_type.to_string()),
+            lang_type: None,
+            postgres_type_name: None,
+            constraints: FieldConstraints::default(),
+            ai: None,
+            example: None,
+        };
+        
+        let mut table = Table {
+            name: "UserRole".to_string(),
+            table: "user_roles".to_string(),
+            primary_key: vec!["user_id".to_string(), "role".to_string()],
+            ..Default::default()
+        };
+        table.fields.insert("user_id".to_string(), field("user_id", "int"));
+        table.fields.insert("role".to_string(), field("role", "string"));
+        table.fields.insert("granted_at".to_string(), field("granted_at", "timestamp"));
+        
+        let mut schema = Schema::new();
+        schema.tables.insert("UserRole".to_string(), table.clone());
+        let code = generator.generate_model("UserRole", &table, &schema).unwrap();
+        
+        assert!(code.contains("pub async fn find(pool: &sqlx::PgPool, key: (i32, String))"));
+        assert!(code.contains("WHERE user_id = $1 AND role = $2"));
+        assert!(code.contains("VALUES ($1, $2, $3)"));
+        assert!(code.contains("SET granted_at = $1\n"));
+        assert!(code.contains("WHERE user_id = $2 AND role = $3"));
+        assert!(!code.contains("Option::is_none"));
+        
+        let crud = generator.generate_crud("UserRole", &table, &schema).unwrap();
+        assert!(crud.contains("pub async fn delete_by_id(&self, key: (i32, String))"));
+    }
 }
\ No newline at end of file
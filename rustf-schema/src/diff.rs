@@ -0,0 +1,420 @@
+//! Differences between two versions of a schema
+//!
+//! [`Schema::diff`] compares tables by their database name and reports what
+//! a migration has to do to turn the old schema into the new one. The result
+//! is plain data; turning it into SQL is up to the caller, since every
+//! database alters columns differently.
+//!
+//! ```rust,ignore
+//! let diff = Schema::diff(&old, &new);
+//! for warning in diff.warnings() {
+//!     eprintln!("warning: {}", warning);
+//! }
+//! ```
+
+use crate::{Constraint, Field, Index, Schema, Table};
+use std::collections::BTreeMap;
+
+/// Everything that changed between two schemas
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDiff {
+    /// Tables only in the new schema
+    pub added_tables: Vec<Table>,
+    /// Tables only in the old schema
+    pub removed_tables: Vec<Table>,
+    /// Tables in both schemas whose definition changed
+    pub altered_tables: Vec<TableDiff>,
+}
+
+/// Changes to a table present in both schemas
+#[derive(Debug, Clone)]
+pub struct TableDiff {
+    /// Database table name
+    pub table: String,
+    /// The table as it was
+    pub old: Table,
+    /// The table as it is now
+    pub new: Table,
+    /// Columns only in the new table (with `name` set)
+    pub added_columns: Vec<Field>,
+    /// Columns only in the old table (with `name` set)
+    pub removed_columns: Vec<Field>,
+    /// Columns in both tables whose definition changed
+    pub altered_columns: Vec<ColumnDiff>,
+    /// Indexes only in the new table
+    pub added_indexes: Vec<Index>,
+    /// Indexes only in the old table
+    pub removed_indexes: Vec<Index>,
+    /// Table constraints only in the new table
+    pub added_constraints: Vec<Constraint>,
+    /// Table constraints only in the old table
+    pub removed_constraints: Vec<Constraint>,
+    /// Old and new primary key columns, when the key changed
+    pub primary_key: Option<(Vec<String>, Vec<String>)>,
+}
+
+/// Changes to a single column
+#[derive(Debug, Clone)]
+pub struct ColumnDiff {
+    /// Column name
+    pub name: String,
+    /// The column as it was
+    pub old: Field,
+    /// The column as it is now
+    pub new: Field,
+    /// What changed
+    pub changes: Vec<ColumnChange>,
+}
+
+/// Aspect of a column that changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnChange {
+    /// Type, length, precision or enum values
+    Type,
+    /// `NULL` / `NOT NULL`
+    Nullability,
+    /// Default value
+    Default,
+    /// Unique constraint
+    Unique,
+    /// Foreign key target or its actions
+    ForeignKey,
+}
+
+impl Schema {
+    /// Compute the changes that turn `old` into `new`
+    ///
+    /// Tables are matched by their database name and listed in name order,
+    /// so the same two schemas always produce the same diff.
+    pub fn diff(old: &Schema, new: &Schema) -> SchemaDiff {
+        let old_tables = tables_by_name(old);
+        let new_tables = tables_by_name(new);
+        let mut diff = SchemaDiff::default();
+
+        for (name, table) in &new_tables {
+            match old_tables.get(name) {
+                None => diff.added_tables.push((*table).clone()),
+                Some(old_table) => {
+                    if let Some(table_diff) = TableDiff::compute(old_table, table) {
+                        diff.altered_tables.push(table_diff);
+                    }
+                }
+            }
+        }
+        for (name, table) in &old_tables {
+            if !new_tables.contains_key(name) {
+                diff.removed_tables.push((*table).clone());
+            }
+        }
+
+        diff
+    }
+}
+
+impl SchemaDiff {
+    /// Whether the schemas are the same
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.altered_tables.is_empty()
+    }
+
+    /// Whether applying the changes can lose data or fail on existing rows
+    pub fn is_destructive(&self) -> bool {
+        !self.warnings().is_empty()
+    }
+
+    /// One message per change that can lose data or fail on existing rows
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .removed_tables
+            .iter()
+            .map(|table| format!("Table '{}' is dropped with all its rows", table.table))
+            .collect();
+
+        for table in &self.altered_tables {
+            for column in &table.removed_columns {
+                warnings.push(format!(
+                    "Column '{}.{}' is dropped with its data",
+                    table.table, column.name
+                ));
+            }
+            for column in &table.added_columns {
+                if !column.is_nullable() && column.constraints.default.is_none() {
+                    warnings.push(format!(
+                        "Column '{}.{}' is NOT NULL without a default; adding it fails if the table has rows",
+                        table.table, column.name
+                    ));
+                }
+            }
+            for column in &table.altered_columns {
+                let name = format!("{}.{}", table.table, column.name);
+                if column.changes.contains(&ColumnChange::Type) {
+                    warnings.push(format!(
+                        "Column '{}' changes type from {} to {}; existing values may not convert",
+                        name,
+                        describe_type(&column.old),
+                        describe_type(&column.new)
+                    ));
+                }
+                if column.becomes_not_null() {
+                    warnings.push(format!(
+                        "Column '{}' becomes NOT NULL; the migration fails if it holds NULLs",
+                        name
+                    ));
+                }
+                if column.changes.contains(&ColumnChange::Unique)
+                    && column.new.constraints.unique == Some(true)
+                {
+                    warnings.push(format!(
+                        "Column '{}' becomes UNIQUE; the migration fails on duplicate values",
+                        name
+                    ));
+                }
+            }
+            if table.primary_key.is_some() {
+                warnings.push(format!(
+                    "Primary key of '{}' changes; references to it must be updated",
+                    table.table
+                ));
+            }
+        }
+
+        warnings
+    }
+}
+
+impl TableDiff {
+    /// Changes between two versions of a table, `None` if there are none
+    pub fn compute(old: &Table, new: &Table) -> Option<Self> {
+        let mut diff = TableDiff {
+            table: new.table.clone(),
+            old: old.clone(),
+            new: new.clone(),
+            added_columns: Vec::new(),
+            removed_columns: Vec::new(),
+            altered_columns: Vec::new(),
+            added_indexes: Vec::new(),
+            removed_indexes: Vec::new(),
+            added_constraints: Vec::new(),
+            removed_constraints: Vec::new(),
+            primary_key: None,
+        };
+
+        let old_fields: BTreeMap<&String, &Field> = old.fields.iter().collect();
+        let new_fields: BTreeMap<&String, &Field> = new.fields.iter().collect();
+
+        for (name, field) in &new_fields {
+            match old_fields.get(name) {
+                None => diff.added_columns.push(named(name, field)),
+                Some(old_field) => {
+                    let changes = column_changes(old_field, field);
+                    if !changes.is_empty() {
+                        diff.altered_columns.push(ColumnDiff {
+                            name: (*name).clone(),
+                            old: named(name, old_field),
+                            new: named(name, field),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+        for (name, field) in &old_fields {
+            if !new_fields.contains_key(name) {
+                diff.removed_columns.push(named(name, field));
+            }
+        }
+
+        diff.added_indexes = missing_from(&new.indexes, &old.indexes);
+        diff.removed_indexes = missing_from(&old.indexes, &new.indexes);
+        diff.added_constraints = missing_from(&new.constraints, &old.constraints);
+        diff.removed_constraints = missing_from(&old.constraints, &new.constraints);
+
+        let old_key: Vec<String> = old
+            .primary_key_fields()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let new_key: Vec<String> = new
+            .primary_key_fields()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        if old_key != new_key {
+            diff.primary_key = Some((old_key, new_key));
+        }
+
+        let unchanged = diff.added_columns.is_empty()
+            && diff.removed_columns.is_empty()
+            && diff.altered_columns.is_empty()
+            && diff.added_indexes.is_empty()
+            && diff.removed_indexes.is_empty()
+            && diff.added_constraints.is_empty()
+            && diff.removed_constraints.is_empty()
+            && diff.primary_key.is_none();
+        (!unchanged).then_some(diff)
+    }
+}
+
+impl ColumnDiff {
+    /// Whether the column goes from nullable to `NOT NULL`
+    pub fn becomes_not_null(&self) -> bool {
+        self.old.is_nullable() && !self.new.is_nullable()
+    }
+}
+
+fn tables_by_name(schema: &Schema) -> BTreeMap<&str, &Table> {
+    schema
+        .tables
+        .values()
+        .map(|table| (table.table.as_str(), table))
+        .collect()
+}
+
+fn named(name: &str, field: &Field) -> Field {
+    let mut field = field.clone();
+    field.name = name.to_string();
+    field
+}
+
+fn column_changes(old: &Field, new: &Field) -> Vec<ColumnChange> {
+    let mut changes = Vec::new();
+    if !same(&old.field_type, &new.field_type) {
+        changes.push(ColumnChange::Type);
+    }
+    if old.is_nullable() != new.is_nullable() {
+        changes.push(ColumnChange::Nullability);
+    }
+    if old.constraints.default != new.constraints.default {
+        changes.push(ColumnChange::Default);
+    }
+    if old.constraints.unique.unwrap_or(false) != new.constraints.unique.unwrap_or(false) {
+        changes.push(ColumnChange::Unique);
+    }
+    if old.constraints.foreign_key != new.constraints.foreign_key
+        || old.constraints.on_delete != new.constraints.on_delete
+        || old.constraints.on_update != new.constraints.on_update
+    {
+        changes.push(ColumnChange::ForeignKey);
+    }
+    changes
+}
+
+/// Compare schema values that do not implement `PartialEq`
+fn same<T: serde::Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Items of `items` that have no equal in `others`
+fn missing_from<T: serde::Serialize + Clone>(items: &[T], others: &[T]) -> Vec<T> {
+    items
+        .iter()
+        .filter(|item| !others.iter().any(|other| same(*item, other)))
+        .cloned()
+        .collect()
+}
+
+fn describe_type(field: &Field) -> String {
+    serde_json::to_value(&field.field_type)
+        .map(|value| match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        })
+        .unwrap_or_else(|_| field.field_type.base_type().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FieldConstraints, FieldType};
+
+    fn field(field_type: &str, nullable: bool) -> Field {
+        Field {
+            name: String::new(),
+            field_type: FieldType::parse(field_type),
+            lang_type: None,
+            postgres_type_name: None,
+            constraints: FieldConstraints {
+                nullable: Some(nullable),
+                ..Default::default()
+            },
+            ai: None,
+            example: None,
+        }
+    }
+
+    fn schema(tables: Vec<(&str, Vec<(&str, Field)>)>) -> Schema {
+        let mut schema = Schema::new();
+        for (name, fields) in tables {
+            let table = Table {
+                name: name.to_string(),
+                table: name.to_string(),
+                fields: fields
+                    .into_iter()
+                    .map(|(n, f)| (n.to_string(), f))
+                    .collect(),
+                ..Default::default()
+            };
+            schema.tables.insert(name.to_string(), table);
+        }
+        schema
+    }
+
+    #[test]
+    fn test_diff_tables_and_columns() {
+        let mut id = field("serial", false);
+        id.constraints.primary_key = Some(true);
+
+        let old = schema(vec![
+            (
+                "users",
+                vec![
+                    ("id", id.clone()),
+                    ("email", field("string(100)", true)),
+                    ("legacy", field("text", true)),
+                ],
+            ),
+            ("sessions", vec![("id", id.clone())]),
+        ]);
+        let mut new = schema(vec![
+            (
+                "users",
+                vec![
+                    ("id", id.clone()),
+                    ("email", field("string(255)", false)),
+                    ("bio", field("text", true)),
+                ],
+            ),
+            ("posts", vec![("id", id)]),
+        ]);
+        let users = new.tables.get_mut("users").unwrap();
+        users.indexes.push(Index::Simple("email".to_string()));
+
+        let diff = Schema::diff(&old, &new);
+        assert_eq!(diff.added_tables[0].table, "posts");
+        assert_eq!(diff.removed_tables[0].table, "sessions");
+
+        let users = &diff.altered_tables[0];
+        assert_eq!(users.added_columns[0].name, "bio");
+        assert_eq!(users.removed_columns[0].name, "legacy");
+        assert_eq!(
+            users.altered_columns[0].changes,
+            vec![ColumnChange::Type, ColumnChange::Nullability]
+        );
+        assert!(users.altered_columns[0].becomes_not_null());
+        assert_eq!(users.added_indexes[0].fields(), vec!["email"]);
+        assert!(users.removed_indexes.is_empty());
+        assert!(users.primary_key.is_none());
+
+        // Dropped table, dropped column, type change and NOT NULL
+        assert_eq!(diff.warnings().len(), 4);
+        assert!(diff.is_destructive());
+    }
+
+    #[test]
+    fn test_identical_schemas_have_no_diff() {
+        let s = schema(vec![("users", vec![("name", field("text", true))])]);
+        assert!(Schema::diff(&s, &s.clone()).is_empty());
+    }
+}
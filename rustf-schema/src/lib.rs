@@ -41,6 +41,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
 
+pub mod diff;
 pub mod parser;
 pub mod types;
 pub mod validator;
@@ -48,6 +49,7 @@ pub mod validator;
 #[cfg(feature = "codegen")]
 pub mod codegen;
 
+pub use diff::{ColumnChange, ColumnDiff, SchemaDiff, TableDiff};
 pub use parser::SchemaParser;
 pub use types::*;
 pub use validator::SchemaValidator;
@@ -260,6 +262,11 @@ impl Schema {
             name.hash(&mut hasher);
             table.version.hash(&mut hasher);
             
+            // Only hashed when set, so schemas without a key list keep their checksum
+            if !table.primary_key.is_empty() {
+                table.primary_key.hash(&mut hasher);
+            }
+            
             // Hash field types and constraints
             let mut sorted_fields: Vec<_> = table.fields.iter().collect();
             sorted_fields.sort_by_key(|(name, _)| *name);
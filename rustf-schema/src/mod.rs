@@ -161,9 +161,12 @@ mod tests {
             tags: vec![],
             ai_context: None,
             fields: HashMap::new(),
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         // Add a field
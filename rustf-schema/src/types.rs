@@ -39,6 +39,11 @@ pub struct Table {
     #[serde(default)]
     pub fields: HashMap<String, Field>,
     
+    /// Primary key columns in key order, for keys spanning several fields
+    /// (when empty, the fields marked `primary_key: true` form the key)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub primary_key: Vec<String>,
+    
     /// Table relations
     #[serde(default)]
     pub relations: Relations,
@@ -50,6 +55,14 @@ pub struct Table {
     /// Table constraints
     #[serde(default)]
     pub constraints: Vec<Constraint>,
+    
+    /// Clear cached queries for this table whenever a model writes to it
+    #[serde(default)]
+    pub invalidate_cache: bool,
+    
+    /// Record model writes to this table in the audit log
+    #[serde(default)]
+    pub audit: bool,
 }
 
 /// Field definition
@@ -146,6 +159,10 @@ pub struct FieldConstraints {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hidden: Option<bool>,
     
+    /// Stored as ciphertext, decrypted on read (not searchable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<bool>,
+    
     #[serde(skip_serializing_if = "Option::is_none")]
     pub computed: Option<String>,
     
@@ -358,6 +375,56 @@ impl FieldType {
     }
 }
 
+impl Table {
+    /// Names of the primary key columns in key order
+    ///
+    /// The table-level `primary_key` list wins; otherwise the fields marked
+    /// `primary_key: true` are used, sorted by name so the order is stable.
+    pub fn primary_key_fields(&self) -> Vec<&str> {
+        if !self.primary_key.is_empty() {
+            return self.primary_key.iter().map(String::as_str).collect();
+        }
+        
+        let mut fields: Vec<&str> = self.fields.iter()
+            .filter(|(_, field)| field.constraints.primary_key == Some(true))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        fields.sort_unstable();
+        fields
+    }
+    
+    /// Whether `field` is part of the primary key
+    pub fn is_primary_key(&self, field: &str) -> bool {
+        self.primary_key_fields().contains(&field)
+    }
+    
+    /// Whether the primary key spans more than one column
+    pub fn has_composite_key(&self) -> bool {
+        self.primary_key_fields().len() > 1
+    }
+}
+
+impl Field {
+    /// Whether the column accepts NULL
+    ///
+    /// Columns are `NOT NULL` when marked `required` or not marked `nullable`.
+    pub fn is_nullable(&self) -> bool {
+        self.constraints.required != Some(true) && self.constraints.nullable == Some(true)
+    }
+}
+
+impl Index {
+    /// Names of the fields covered by this index
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Index::Simple(field) => vec![field.as_str()],
+            Index::Composite(fields) | Index::Detailed { fields, .. } => {
+                fields.iter().map(String::as_str).collect()
+            },
+        }
+    }
+}
+
 /// Custom deserializer for FieldType that handles string parsing
 #[allow(dead_code)]
 fn deserialize_field_type<'de, D>(deserializer: D) -> Result<FieldType, D::Error>
@@ -439,6 +506,7 @@ impl<'de> Deserialize<'de> for Field {
             Nullable,
             Default,
             Hidden,
+            Encrypted,
             ForeignKey,
             MinLength,
             MaxLength,
@@ -496,6 +564,7 @@ impl<'de> Deserialize<'de> for Field {
                         FieldKey::Nullable => constraints.nullable = Some(map.next_value()?),
                         FieldKey::Default => constraints.default = Some(map.next_value()?),
                         FieldKey::Hidden => constraints.hidden = Some(map.next_value()?),
+                        FieldKey::Encrypted => constraints.encrypted = Some(map.next_value()?),
                         FieldKey::ForeignKey => constraints.foreign_key = Some(map.next_value()?),
                         FieldKey::MinLength => constraints.min_length = Some(map.next_value()?),
                         FieldKey::MaxLength => constraints.max_length = Some(map.next_value()?),
@@ -572,9 +641,12 @@ impl Default for Table {
             tags: Vec::new(),
             ai_context: None,
             fields: HashMap::new(),
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: Vec::new(),
             constraints: Vec::new(),
+            invalidate_cache: false,
+            audit: false,
         }
     }
 }
@@ -50,8 +50,7 @@ impl SchemaValidator {
         // Check that tables (but not views) have a primary key
         let is_view = table.element_type.as_deref() == Some("view");
         if !is_view {
-            let has_primary_key = table.fields.values()
-                .any(|field| field.constraints.primary_key == Some(true));
+            let has_primary_key = !table.primary_key_fields().is_empty();
                 
             if !has_primary_key {
                 return Err(SchemaError::Validation(
@@ -62,7 +61,15 @@ impl SchemaValidator {
         
         // Validate each field
         for (field_name, field) in &table.fields {
-            Self::validate_field(name, field_name, field)?;
+            Self::validate_field(name, field_name, field, table.is_primary_key(field_name))?;
+        }
+        
+        if let Some(error) = Self::primary_key_errors(name, table).into_iter().next() {
+            return Err(SchemaError::Validation(error));
+        }
+        
+        if let Some(error) = Self::encrypted_index_errors(name, table).into_iter().next() {
+            return Err(SchemaError::Validation(error));
         }
         
         Ok(())
@@ -80,8 +87,7 @@ impl SchemaValidator {
         // Check that tables (but not views) have a primary key
         let is_view = table.element_type.as_deref() == Some("view");
         if !is_view {
-            let has_primary_key = table.fields.values()
-                .any(|field| field.constraints.primary_key == Some(true));
+            let has_primary_key = !table.primary_key_fields().is_empty();
                 
             if !has_primary_key {
                 result.add_error(format!("Table '{}' has no primary key", name));
@@ -90,15 +96,24 @@ impl SchemaValidator {
         
         // Validate each field
         for (field_name, field) in &table.fields {
-            let field_result = Self::validate_field_comprehensive(name, field_name, field);
+            let field_result = Self::validate_field_comprehensive(
+                name, field_name, field, table.is_primary_key(field_name));
             result.merge(field_result);
         }
         
+        for error in Self::primary_key_errors(name, table) {
+            result.add_error(error);
+        }
+        
+        for error in Self::encrypted_index_errors(name, table) {
+            result.add_error(error);
+        }
+        
         result
     }
     
     /// Validate a single field
-    fn validate_field(table_name: &str, field_name: &str, field: &Field) -> Result<()> {
+    fn validate_field(table_name: &str, field_name: &str, field: &Field, is_key: bool) -> Result<()> {
         // Validate field type
         Self::validate_field_type(&field.field_type)?;
         
@@ -115,7 +130,7 @@ impl SchemaValidator {
             match auto {
                 AutoGenerate::Boolean(true) => {
                     // Must be primary key or have specific type
-                    if field.constraints.primary_key != Some(true) 
+                    if !is_key
                         && !matches!(field.field_type.base_type(), "serial" | "uuid") {
                         return Err(SchemaError::Validation(
                             format!("Field '{}.{}' with auto=true must be primary key or serial/uuid type", 
@@ -155,11 +170,16 @@ impl SchemaValidator {
             }
         }
         
+        // Validate encrypted fields
+        if let Some(error) = Self::encrypted_field_errors(table_name, field_name, field).into_iter().next() {
+            return Err(SchemaError::Validation(error));
+        }
+        
         Ok(())
     }
     
     /// Validate a single field and collect all errors
-    fn validate_field_comprehensive(table_name: &str, field_name: &str, field: &Field) -> ValidationResult {
+    fn validate_field_comprehensive(table_name: &str, field_name: &str, field: &Field, is_key: bool) -> ValidationResult {
         let mut result = ValidationResult::new();
         
         // Validate field type
@@ -178,7 +198,7 @@ impl SchemaValidator {
             match auto {
                 AutoGenerate::Boolean(true) => {
                     // Must be primary key or have specific type
-                    if field.constraints.primary_key != Some(true) 
+                    if !is_key
                         && !matches!(field.field_type.base_type(), "serial" | "uuid") {
                         result.add_error(format!("Field '{}.{}' with auto=true must be primary key or serial/uuid type", 
                             table_name, field_name));
@@ -212,9 +232,99 @@ impl SchemaValidator {
             }
         }
         
+        // Validate encrypted fields
+        for error in Self::encrypted_field_errors(table_name, field_name, field) {
+            result.add_error(error);
+        }
+        
         result
     }
     
+    /// Check that an encrypted field is not used in ways that need to compare its value
+    ///
+    /// Ciphertexts are randomized, so keys, uniqueness and foreign keys cannot
+    /// work on them. Only string columns can hold the encoded ciphertext.
+    fn encrypted_field_errors(table_name: &str, field_name: &str, field: &Field) -> Vec<String> {
+        let mut errors = Vec::new();
+        if field.constraints.encrypted != Some(true) {
+            return errors;
+        }
+        
+        if !matches!(field.field_type.base_type(), "string" | "varchar" | "text") {
+            errors.push(format!("Encrypted field '{}.{}' must be a string or text type", 
+                table_name, field_name));
+        }
+        
+        let conflicts = [
+            ("primary_key", field.constraints.primary_key == Some(true)),
+            ("unique", field.constraints.unique == Some(true)),
+            ("foreign_key", field.constraints.foreign_key.is_some()),
+        ];
+        for (constraint, set) in conflicts {
+            if set {
+                errors.push(format!("Encrypted field '{}.{}' cannot be {}: encrypted columns are not searchable", 
+                    table_name, field_name, constraint));
+            }
+        }
+        
+        errors
+    }
+    
+    /// Check that the primary key columns exist and can identify a row
+    fn primary_key_errors(name: &str, table: &Table) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+        
+        for key in table.primary_key_fields() {
+            if !seen.insert(key) {
+                errors.push(format!("Primary key of table '{}' lists '{}' twice", name, key));
+                continue;
+            }
+            match table.fields.get(key) {
+                None => errors.push(format!("Primary key field '{}.{}' does not exist", name, key)),
+                Some(field) if field.constraints.nullable == Some(true) => {
+                    errors.push(format!("Primary key field '{}.{}' cannot be nullable", name, key));
+                },
+                Some(field) if field.constraints.encrypted == Some(true) && !table.primary_key.is_empty() => {
+                    errors.push(format!("Encrypted field '{}.{}' cannot be primary_key: encrypted columns are not searchable", 
+                        name, key));
+                },
+                Some(_) => {},
+            }
+        }
+        
+        // With an explicit key list, field flags must not disagree with it
+        if !table.primary_key.is_empty() {
+            let mut flagged: Vec<&String> = table.fields.iter()
+                .filter(|(field_name, field)| {
+                    field.constraints.primary_key == Some(true) && !table.primary_key.contains(field_name)
+                })
+                .map(|(field_name, _)| field_name)
+                .collect();
+            flagged.sort();
+            for field_name in flagged {
+                errors.push(format!("Field '{}.{}' is marked primary_key but is not in the table's primary_key list", 
+                    name, field_name));
+            }
+        }
+        
+        errors
+    }
+    
+    /// Check that no index covers an encrypted field
+    fn encrypted_index_errors(name: &str, table: &Table) -> Vec<String> {
+        table.indexes.iter()
+            .flat_map(|index| index.fields())
+            .filter(|field| {
+                table.fields.get(*field)
+                    .map(|f| f.constraints.encrypted == Some(true))
+                    .unwrap_or(false)
+            })
+            .map(|field| format!("Encrypted field '{}.{}' cannot be indexed: encrypted columns are not searchable", 
+                name, field))
+            .collect()
+    }
+    
     /// Validate field type
     pub fn validate_field_type(field_type: &FieldType) -> Result<()> {
         match field_type {
@@ -288,14 +398,14 @@ impl SchemaValidator {
         for (table_name, table) in &schema.tables {
             for (field_name, field) in &table.fields {
                 if let Some(fk) = &field.constraints.foreign_key {
-                    let (_target_table, target_field) = schema.resolve_field_ref(fk)
+                    let (target_table, target_field) = schema.resolve_field_ref(fk)
                         .map_err(|_| SchemaError::Validation(
                             format!("Foreign key '{}' in '{}.{}' references non-existent field", 
                                 fk, table_name, field_name)
                         ))?;
                     
                     // Check that target field is suitable for foreign key
-                    if target_field.constraints.primary_key != Some(true) 
+                    if !Self::is_unique_target(target_table, fk) 
                         && target_field.constraints.unique != Some(true) {
                         return Err(SchemaError::Validation(
                             format!("Foreign key '{}' in '{}.{}' must reference a primary key or unique field", 
@@ -308,6 +418,14 @@ impl SchemaValidator {
         Ok(())
     }
     
+    /// Whether the field referenced by `fk` is the whole primary key of its table
+    ///
+    /// A single column of a composite key is not unique on its own.
+    fn is_unique_target(target_table: &Table, fk: &str) -> bool {
+        let target_field = fk.rsplit('.').next().unwrap_or(fk);
+        target_table.primary_key_fields() == [target_field]
+    }
+    
     /// Validate foreign key references and collect all errors
     fn validate_foreign_keys_comprehensive(schema: &Schema) -> ValidationResult {
         let mut result = ValidationResult::new();
@@ -316,9 +434,9 @@ impl SchemaValidator {
             for (field_name, field) in &table.fields {
                 if let Some(fk) = &field.constraints.foreign_key {
                     match schema.resolve_field_ref(fk) {
-                        Ok((_target_table, target_field)) => {
+                        Ok((target_table, target_field)) => {
                             // Check that target field is suitable for foreign key
-                            if target_field.constraints.primary_key != Some(true) 
+                            if !Self::is_unique_target(target_table, fk) 
                                 && target_field.constraints.unique != Some(true) {
                                 result.add_error(format!("Foreign key '{}' in '{}.{}' must reference a primary key or unique field", 
                                     fk, table_name, field_name));
@@ -554,9 +672,12 @@ mod tests {
             tags: vec![],
             ai_context: None,
             fields: HashMap::new(),
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         // Add primary key field
@@ -591,9 +712,12 @@ mod tests {
             tags: vec![],
             ai_context: None,
             fields: HashMap::new(),
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         // Add field without primary key
@@ -611,4 +735,34 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("no primary key"));
     }
+    
+    #[test]
+    fn test_validate_composite_primary_key() {
+        let field = |name: &str| Field {
+            name: name.to_string(),
+            field_type: FieldType::Simple("int".to_string()),
+            lang_type: None,
+            postgres_type_name: None,
+            constraints: FieldConstraints::default(),
+            ai: None,
+            example: None,
+        };
+        
+        let mut table = Table {
+            name: "UserRole".to_string(),
+            table: "user_roles".to_string(),
+            primary_key: vec!["user_id".to_string(), "role_id".to_string()],
+            ..Default::default()
+        };
+        table.fields.insert("user_id".to_string(), field("user_id"));
+        table.fields.insert("role_id".to_string(), field("role_id"));
+        
+        assert!(SchemaValidator::validate_table("UserRole", &table).is_ok());
+        assert_eq!(table.primary_key_fields(), vec!["user_id", "role_id"]);
+        assert!(table.has_composite_key());
+        
+        table.primary_key.push("tenant_id".to_string());
+        let errors = SchemaValidator::validate_table_comprehensive("UserRole", &table).errors;
+        assert!(errors.iter().any(|e| e.contains("'UserRole.tenant_id' does not exist")));
+    }
 }
\ No newline at end of file
@@ -87,9 +87,12 @@ mod tests {
             tags: vec!["core".to_string()],
             ai_context: Some("Main user table for authentication".to_string()),
             fields,
+            primary_key: Vec::new(),
             relations: Relations::default(),
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         ("User".to_string(), table)
@@ -185,6 +188,57 @@ mod tests {
         assert!(code.contains("#[rustf_schema(table = \"users\", version = 1)]"));
     }
 
+    #[test]
+    fn test_generate_model_with_encrypted_field() {
+        let generator = SqlxGenerator::new().unwrap();
+        let (table_name, mut table) = create_test_table();
+        table.fields.insert("ssn".to_string(), Field {
+            name: "ssn".to_string(),
+            field_type: FieldType::Simple("text".to_string()),
+            lang_type: None,
+            postgres_type_name: None,
+            constraints: FieldConstraints {
+                nullable: Some(true),
+                encrypted: Some(true),
+                ..Default::default()
+            },
+            ai: None,
+            example: None,
+        });
+        
+        let code = generator.generate_model(&table_name, &table, &Schema::new()).unwrap();
+        
+        // Plaintext on the struct, wrapped on the way in and out
+        assert!(code.contains("pub ssn: Option<String>"));
+        assert!(code.contains("pub const ENCRYPTED_FIELDS: &'static [&'static str] = &[\"ssn\"];"));
+        assert!(code.contains("stored.ssn = self.ssn.as_deref().map(encrypt_field)"));
+        assert!(code.contains("self.ssn = self.ssn.as_deref().map(decrypt_field)"));
+        assert!(code.contains("let stored = self.encrypt_fields()?;"));
+        assert!(code.contains("rows.into_iter().map(Self::decrypt_fields).collect()"));
+        
+        // Raw filters on encrypted columns fail at runtime
+        assert!(code.contains("ensure_not_filtered(where_clause, Self::ENCRYPTED_FIELDS)"));
+        
+        // Models without encrypted fields are unaffected
+        let (_, plain) = create_test_table();
+        let code = generator.generate_model(&table_name, &plain, &Schema::new()).unwrap();
+        assert!(!code.contains("encrypt_fields"));
+    }
+
+    #[test]
+    fn test_generate_model_with_cache_invalidation() {
+        let generator = SqlxGenerator::new().unwrap();
+        let (table_name, mut table) = create_test_table();
+        
+        let code = generator.generate_model(&table_name, &table, &Schema::new()).unwrap();
+        assert!(!code.contains("invalidate_table"));
+        
+        // Insert, update and delete each clear the table's cached queries
+        table.invalidate_cache = true;
+        let code = generator.generate_model(&table_name, &table, &Schema::new()).unwrap();
+        assert_eq!(code.matches("rustf::cache::query::invalidate_table(\"users\");").count(), 3);
+    }
+
     #[test]
     fn test_generate_crud() {
         let generator = SqlxGenerator::new().unwrap();
@@ -325,9 +379,12 @@ mod tests {
             tags: vec![],
             ai_context: None,
             fields: post_fields,
+            primary_key: Vec::new(),
             relations,
             indexes: vec![],
             constraints: vec![],
+            invalidate_cache: false,
+            audit: false,
         };
         
         schema.tables.insert("Post".to_string(), post_table.clone());
@@ -36,9 +36,12 @@ fn create_basic_table(name: &str, table: &str) -> Table {
         tags: vec![],
         ai_context: None,
         fields,
+        primary_key: Vec::new(),
         relations: Relations::default(),
         indexes: vec![],
         constraints: vec![],
+        invalidate_cache: false,
+        audit: false,
     }
 }
 
@@ -62,9 +65,12 @@ fn test_validate_table_without_fields() {
         tags: vec![],
         ai_context: None,
         fields: HashMap::new(),
+        primary_key: Vec::new(),
         relations: Relations::default(),
         indexes: vec![],
         constraints: vec![],
+        invalidate_cache: false,
+        audit: false,
     };
     
     let result = SchemaValidator::validate_table("Empty", &table);
@@ -99,9 +105,12 @@ fn test_validate_table_without_primary_key() {
         tags: vec![],
         ai_context: None,
         fields,
+        primary_key: Vec::new(),
         relations: Relations::default(),
         indexes: vec![],
         constraints: vec![],
+        invalidate_cache: false,
+        audit: false,
     };
     
     let result = SchemaValidator::validate_table("NoPK", &table);
@@ -140,6 +149,42 @@ fn test_validate_field_constraints() {
     assert!(error.to_string().contains("both required and nullable"));
 }
 
+#[test]
+fn test_validate_encrypted_fields() {
+    let encrypted = |field_type: &str, unique: Option<bool>| Field {
+        name: "ssn".to_string(),
+        field_type: FieldType::Simple(field_type.to_string()),
+        lang_type: None,
+        postgres_type_name: None,
+        constraints: FieldConstraints {
+            encrypted: Some(true),
+            unique,
+            ..Default::default()
+        },
+        ai: None,
+        example: None,
+    };
+    
+    let mut table = create_basic_table("Person", "people");
+    table.fields.insert("ssn".to_string(), encrypted("text", None));
+    assert!(SchemaValidator::validate_table("Person", &table).is_ok());
+    
+    // Indexing an encrypted column is rejected
+    table.indexes.push(rustf_schema::Index::Simple("ssn".to_string()));
+    let error = SchemaValidator::validate_table("Person", &table).unwrap_err();
+    assert!(error.to_string().contains("cannot be indexed"));
+    
+    // So are unique constraints and non-string types
+    let mut table = create_basic_table("Person", "people");
+    table.fields.insert("ssn".to_string(), encrypted("integer", Some(true)));
+    let mut schema = Schema::new();
+    schema.tables.insert("Person".to_string(), table);
+    let result = SchemaValidator::validate_comprehensive(&schema).unwrap();
+    let errors = result.errors.join("\n");
+    assert!(errors.contains("must be a string or text type"));
+    assert!(errors.contains("cannot be unique"));
+}
+
 #[test]
 fn test_validate_auto_generation() {
     let mut schema = Schema::new();
@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustf::config::ViewConfig;
+use rustf::views::ViewEngine;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn setup_views() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("layouts")).unwrap();
+    fs::write(
+        dir.path().join("layouts/default.html"),
+        "<html><head><title>@{M.title}</title></head><body>@{body}</body></html>",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("list.html"),
+        "<h1>@{M.title}</h1>\n<ul>\n@{foreach item in M.items}\n  <li>@{item.name}: @{item.price}</li>\n@{end}\n</ul>",
+    )
+    .unwrap();
+    dir
+}
+
+fn engine(dir: &TempDir, cache_enabled: bool) -> ViewEngine {
+    let config = ViewConfig {
+        cache_enabled,
+        ..ViewConfig::default()
+    };
+    ViewEngine::totaljs_filesystem_with_config(dir.path().to_str().unwrap(), Some(&config))
+}
+
+fn benchmark_render(c: &mut Criterion) {
+    let dir = setup_views();
+    let data = json!({
+        "title": "Products",
+        "items": (0..20)
+            .map(|i| json!({ "name": format!("Item {}", i), "price": i * 10 }))
+            .collect::<Vec<_>>(),
+    });
+
+    let cached = engine(&dir, true);
+    c.bench_function("view_render_cached", |b| {
+        b.iter(|| {
+            let html = cached.render(black_box("list"), &data, Some("layouts/default"));
+            black_box(html)
+        })
+    });
+
+    let uncached = engine(&dir, false);
+    c.bench_function("view_render_uncached", |b| {
+        b.iter(|| {
+            let html = uncached.render(black_box("list"), &data, Some("layouts/default"));
+            black_box(html)
+        })
+    });
+}
+
+criterion_group!(benches, benchmark_render);
+criterion_main!(benches);
@@ -1,18 +1,26 @@
 use crate::config::{AppConfig, TemplateEngine, TemplateStorage};
 use crate::context::Context;
-use crate::error::Result;
+use crate::error::pages::accepts_json;
+use crate::error::{ErrorPages, Result};
 use crate::events::{EventContext, EventEmitter};
-use crate::http::{Request, Response, Server};
+use crate::http::{Request, Response, Server, TimingPhase};
 use crate::middleware::{MiddlewareRegistry, MiddlewareResult};
 use crate::models::ModelRegistry;
+use crate::plugins::{Plugin, PluginRegistry};
 use crate::routing::{Route, Router};
+use crate::security::static_files::{file_response, StaticFileOptions};
 use crate::shared::SharedRegistry;
 use crate::views::ViewEngine;
 use crate::workers::WorkerManager;
-use hyper::Body;
+use futures::FutureExt;
+use hyper::{Body, StatusCode};
+use std::any::Any;
 use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 /// Memory-safe RustF application with Arc-based component sharing
@@ -28,9 +36,20 @@ pub struct RustF {
     shared: Arc<SharedRegistry>,
     events: Arc<RwLock<EventEmitter>>,
     workers: Option<Arc<WorkerManager>>,
+    plugins: PluginRegistry,
+    #[cfg(feature = "dev")]
+    live_reload: Option<Arc<crate::dev::LiveReload>>,
+    openapi: Option<OpenApiEndpoint>,
     pub config: Arc<AppConfig>,
 }
 
+/// Where the OpenAPI document is served, and its title and version
+struct OpenApiEndpoint {
+    path: String,
+    title: String,
+    version: String,
+}
+
 impl Default for RustF {
     fn default() -> Self {
         Self::new()
@@ -98,6 +117,10 @@ impl RustF {
             shared: Arc::new(SharedRegistry::new()),
             events: Arc::new(RwLock::new(EventEmitter::new())),
             workers: None,
+            plugins: PluginRegistry::new(),
+            #[cfg(feature = "dev")]
+            live_reload: None,
+            openapi: None,
             config: config_arc,
         }
     }
@@ -218,10 +241,15 @@ impl RustF {
     }
 
     pub fn controllers(mut self, routes: Vec<Route>) -> Self {
+        self.add_routes(routes);
+        self
+    }
+
+    /// Add routes in place (for plugins)
+    pub fn add_routes(&mut self, routes: Vec<Route>) {
         for route in routes {
             self.router.add_route(route);
         }
-        self
     }
 
     pub fn models<F>(mut self, register_fn: F) -> Self
@@ -295,6 +323,11 @@ impl RustF {
         self
     }
 
+    /// Mutable access to the middleware registry (for plugins)
+    pub fn middleware_mut(&mut self) -> &mut MiddlewareRegistry {
+        &mut self.middleware
+    }
+
     /// Register multiple shared modules from a function (for auto-discovery)
     ///
     /// # Example
@@ -357,6 +390,16 @@ impl RustF {
     ///     });
     /// ```
     pub fn workers_from<F, Fut>(mut self, register_fn: F) -> Self
+    where
+        F: FnOnce(Arc<WorkerManager>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        self.add_workers(register_fn);
+        self
+    }
+
+    /// Register workers in place (for plugins), enabling workers if needed
+    pub fn add_workers<F, Fut>(&mut self, register_fn: F)
     where
         F: FnOnce(Arc<WorkerManager>) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<()>> + Send + 'static,
@@ -372,8 +415,6 @@ impl RustF {
                 }
             });
         }
-
-        self
     }
 
     /// Get the worker manager if workers are enabled
@@ -387,6 +428,25 @@ impl RustF {
         self
     }
 
+    /// Serve an OpenAPI 3.0 document of the registered routes at `path`
+    ///
+    /// The document is built for each request, so it lists routes added after
+    /// this call too. Middleware runs as for a route, so it can restrict access.
+    ///
+    /// ```rust,ignore
+    /// let app = RustF::new()
+    ///     .controllers(routes)
+    ///     .openapi("/openapi.json", "Shop API", "1.0.0");
+    /// ```
+    pub fn openapi(mut self, path: &str, title: &str, version: &str) -> Self {
+        self.openapi = Some(OpenApiEndpoint {
+            path: path.to_string(),
+            title: title.to_string(),
+            version: version.to_string(),
+        });
+        self
+    }
+
     pub fn views(mut self, directory: &str) -> Self {
         Arc::get_mut(&mut self.views)
             .expect("Views should not be shared during configuration")
@@ -460,6 +520,15 @@ impl RustF {
     ///     });
     /// ```
     pub fn events_from<F>(self, register_fn: F) -> Self
+    where
+        F: FnOnce(&mut EventEmitter),
+    {
+        self.add_events(register_fn);
+        self
+    }
+
+    /// Register event handlers in place (for plugins)
+    pub fn add_events<F>(&self, register_fn: F)
     where
         F: FnOnce(&mut EventEmitter),
     {
@@ -469,6 +538,32 @@ impl RustF {
         } else {
             log::warn!("Could not register event handlers - events system is locked");
         }
+    }
+
+    /// Install a plugin
+    ///
+    /// The plugin registers its routes, middleware, events and workers as soon
+    /// as all of its dependencies are installed, so dependencies always install
+    /// first; its startup and shutdown hooks run with the application. Plugins
+    /// are identified by name, so installing the same one twice is a no-op.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let app = RustF::new()
+    ///     .plugin(AuthPlugin::new())
+    ///     .plugin(AdminPlugin);
+    /// ```
+    pub fn plugin<P: Plugin>(mut self, plugin: P) -> Self {
+        let plugin: Arc<dyn Plugin> = Arc::new(plugin);
+        if !self.plugins.add(plugin.clone()) {
+            log::warn!("Plugin '{}' is already installed - ignoring", plugin.name());
+            return self;
+        }
+
+        while let Some(plugin) = self.plugins.next_to_install() {
+            plugin.install(&mut self);
+            log::info!("Plugin '{}' installed", plugin.name());
+        }
         self
     }
 
@@ -582,6 +677,8 @@ impl RustF {
     ///   - `"cors"` - Cross-Origin Resource Sharing
     ///   - `"rate_limit"` - Rate limiting
     ///   - `"csrf"` - CSRF protection
+    ///   - `"degraded"` - Read-only degraded mode while the database is down
+    ///   - `"timeout"` - 503 (or 504) for handlers that run too long
     ///
     /// # Examples
     /// ```rust,ignore
@@ -674,7 +771,10 @@ impl RustF {
     /// using their `.from_config()` methods which read from config.toml.
     fn load_builtin_middleware(self, middleware_names: &[&str]) -> Self {
         self.middleware_from(move |registry| {
-            use crate::middleware::builtin::{LoggingMiddleware, CorsMiddleware, RateLimitMiddleware};
+            use crate::middleware::builtin::{
+                CorsMiddleware, DegradedModeMiddleware, LoggingMiddleware, RateLimitMiddleware,
+                TimeoutMiddleware,
+            };
             use crate::security::CsrfMiddleware;
 
             for name in middleware_names {
@@ -694,8 +794,16 @@ impl RustF {
                         let csrf = CsrfMiddleware::from_config();
                         registry.register_inbound("csrf", csrf);
                     }
+                    "degraded" => {
+                        let degraded = DegradedModeMiddleware::from_config();
+                        registry.register_dual("degraded", degraded);
+                    }
+                    "timeout" => {
+                        let timeout = TimeoutMiddleware::from_config();
+                        registry.register_inbound("timeout", timeout);
+                    }
                     unknown => {
-                        log::warn!("Unknown builtin middleware '{}' - ignoring. Available: logging, cors, rate_limit, csrf", unknown);
+                        log::warn!("Unknown builtin middleware '{}' - ignoring. Available: logging, cors, rate_limit, csrf, degraded, timeout", unknown);
                     }
                 }
             }
@@ -710,6 +818,9 @@ impl RustF {
         }
         log::info!("Global configuration (CONF) initialized");
 
+        // Share the emitter with code that raises events outside handlers
+        crate::events::install_global(self.events.clone(), self.config.clone());
+
         // Initialize global repository (APP/MAIN)
         if let Err(e) = crate::repository::APP::init(serde_json::json!({})) {
             log::error!("Failed to initialize global repository (APP/MAIN): {}", e);
@@ -761,10 +872,21 @@ impl RustF {
             }
         }
 
+        // Install the configured audit log unless the app installed its own
+        if self.config.audit.enabled && crate::audit::global().is_none() {
+            let _ = crate::audit::install(crate::audit::AuditLog::from_config(&self.config.audit));
+            log::info!("Audit log enabled ({:?} sink)", self.config.audit.sink);
+        }
+
+        crate::database::slow_query::set_threshold(match self.config.database.slow_query_ms {
+            0 => None,
+            ms => Some(std::time::Duration::from_millis(ms)),
+        });
+
         // Auto-register session middleware if enabled in config (dual-phase)
         // This must be done after database initialization as it may need DB/Redis connections
         if let Some(session_middleware) =
-            crate::session::config_adapter::create_session_middleware(&self.config).await
+            crate::session::config_adapter::create_session_middleware(&self.config).await?
         {
             self.middleware.register_dual("session", session_middleware);
         }
@@ -778,6 +900,12 @@ impl RustF {
             )));
         }
 
+        // Start plugins once the database and shared modules they build on are up
+        if let Err(e) = self.plugins.start_all().await {
+            log::error!("Plugin startup failed: {}", e);
+            return Err(e);
+        }
+
         // Emit modules.ready event
         if let Err(e) = self
             .events
@@ -800,6 +928,26 @@ impl RustF {
             log::info!("Global VIEW API initialized");
         }
 
+        // Watch filesystem views and reload open pages when they change
+        #[cfg(feature = "dev")]
+        if !self.config.environment.is_production()
+            && matches!(self.config.views.storage, TemplateStorage::Filesystem)
+        {
+            match crate::dev::LiveReload::watch_views(
+                &self.config.views.directory,
+                self.views.clone(),
+            ) {
+                Ok(live_reload) => {
+                    log::info!(
+                        "Live reload enabled for views in: {}",
+                        self.config.views.directory
+                    );
+                    self.live_reload = Some(Arc::new(live_reload));
+                }
+                Err(e) => log::warn!("Live reload disabled: {}", e),
+            }
+        }
+
         // Emit middleware.ready event
         if !self.middleware.is_empty() {
             if let Err(e) = self
@@ -887,6 +1035,9 @@ impl RustF {
         // Give event handlers a moment to process
         tokio::time::sleep(Duration::from_millis(100)).await;
 
+        // Stop plugins while the workers and modules they use are still running
+        self.plugins.shutdown_all().await;
+
         // 2. Shutdown workers if enabled
         if let Some(workers) = &self.workers {
             log::info!("Shutting down workers...");
@@ -917,32 +1068,95 @@ impl RustF {
     }
 
     pub async fn handle_request(&self, req: hyper::Request<Body>) -> Result<Response> {
-        let request = Request::from_hyper(req).await?;
+        let max_body_size = self.max_body_size(&req);
+        let accept = req
+            .headers()
+            .get(hyper::header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let request = match Request::from_hyper_with_limit(req, max_body_size).await {
+            Ok(request) => request,
+            Err(error @ crate::error::Error::PayloadTooLarge(_)) => {
+                log::debug!("Rejected request body: {}", error);
+                return ErrorPages::new(Arc::clone(&self.views), Arc::clone(&self.config))
+                    .create_error_response(413, Some(&error), None, accept.as_deref());
+            }
+            Err(error) => return Err(error),
+        };
 
         // Check for static files first (match prefix safely using request path without query)
         let request_path = request.path().to_string();
+
+        #[cfg(feature = "dev")]
+        if let Some(live_reload) = &self.live_reload {
+            if request_path == crate::dev::LIVE_RELOAD_PATH {
+                return Ok(live_reload.response());
+            }
+        }
+
         for (prefix, dir) in &self.static_dirs {
             if let Some(relative_suffix) = Self::match_static_prefix(&request_path, prefix) {
                 return self
-                    .serve_static_file(dir, relative_suffix, &request_path)
+                    .serve_static_file(&request, dir, relative_suffix, &request_path)
                     .await;
             }
         }
 
         // Create memory-safe context with Arc references
         let mut context = Context::new(request, Arc::clone(&self.views));
+        if self.config.server.server_timing {
+            context.enable_timings();
+        }
 
         // Execute middleware chain + route handler
-        let result = self.execute_middleware_chain(&mut context).await?;
+        let request_id = context
+            .header("x-request-id")
+            .or_else(|| context.header("x-correlation-id"))
+            .map(str::to_string);
+        let chain = crate::http::request_id::scope(
+            request_id.clone(),
+            crate::audit::scope(crate::database::transaction::scope(
+                self.execute_middleware_chain(&mut context),
+            )),
+        );
+        #[cfg(feature = "otel")]
+        let result = crate::telemetry::scope(chain).await;
+        #[cfg(not(feature = "otel"))]
+        let result = chain.await;
 
         // Get response from result
-        let response = match result {
-            MiddlewareResult::Continue => {
+        let mut response = match result {
+            Ok(MiddlewareResult::Continue) => {
                 // This should not happen - the final handler should always return Stop
                 log::warn!("Middleware chain returned Continue but no more handlers available");
                 Response::internal_error()
             }
-            MiddlewareResult::Stop(response) => response,
+            Ok(MiddlewareResult::Stop(response)) => response,
+            Err(error) => self.error_response(&context, &error, request_id.as_deref()),
+        };
+
+        // Handlers that only set `ETag` or `Last-Modified` still get 304s
+        response.apply_conditional(
+            &context.req.method,
+            context.req.headers.get("if-none-match").map(String::as_str),
+            context
+                .req
+                .headers
+                .get("if-modified-since")
+                .map(String::as_str),
+        );
+
+        if let Some(timings) = context.timings() {
+            response.add_header("Server-Timing", &timings.server_timing());
+        }
+
+        #[cfg(feature = "dev")]
+        let response = {
+            let mut response = response;
+            if self.live_reload.is_some() {
+                crate::dev::inject_script(&mut response);
+            }
+            response
         };
 
         Ok(response)
@@ -962,8 +1176,10 @@ impl RustF {
         // Get sorted middleware (by priority)
         let middleware_list = self.middleware.get_sorted();
         let mut outbound_stack = Vec::new();
+        let mut early_response = None;
 
         // Phase 1: INBOUND - Process request through middleware
+        let clock = ctx.timing_clock();
         for middleware in &middleware_list {
             // Only process if middleware has inbound phase and should run
             if let Some(ref inbound) = middleware.inbound {
@@ -979,6 +1195,7 @@ impl RustF {
                             }
                         }
                         InboundAction::Stop => {
+                            ctx.record_timing(TimingPhase::Inbound, clock);
                             // Early return - use response set on context
                             let response =
                                 ctx.take_response().unwrap_or_else(Response::internal_error);
@@ -988,17 +1205,26 @@ impl RustF {
                             // This middleware wants to process the response
                             outbound_stack.push(middleware);
                         }
+                        InboundAction::Respond(response) => {
+                            // Skip the rest of the chain and the handler, but
+                            // not the outbound phase of the middleware so far
+                            early_response = Some(response);
+                            break;
+                        }
                     }
                 }
             }
         }
 
-        // Phase 2: Execute the route handler
-        let result = self.execute_route_handler(ctx).await?;
+        ctx.record_timing(TimingPhase::Inbound, clock);
 
-        let response = match result {
-            MiddlewareResult::Stop(resp) => resp,
-            MiddlewareResult::Continue => Response::internal_error(),
+        // Phase 2: Execute the route handler
+        let response = match early_response {
+            Some(response) => response,
+            None => match self.execute_route_handler(ctx).await? {
+                MiddlewareResult::Stop(resp) => resp,
+                MiddlewareResult::Continue => Response::internal_error(),
+            },
         };
 
         // Set the response in context for outbound middleware to access
@@ -1006,11 +1232,13 @@ impl RustF {
 
         // Phase 3: OUTBOUND - Process response through middleware (in reverse order)
         // Context has all modifications from both inbound middleware and handler
+        let clock = ctx.timing_clock();
         for middleware in outbound_stack.iter().rev() {
             if let Some(ref outbound) = middleware.outbound {
                 outbound.process_response(ctx).await?;
             }
         }
+        ctx.record_timing(TimingPhase::Outbound, clock);
 
         // Get the final response from context
         let final_response = ctx.take_response().unwrap_or_else(Response::internal_error);
@@ -1018,6 +1246,14 @@ impl RustF {
         Ok(MiddlewareResult::Stop(final_response))
     }
 
+    /// Body size limit for a request: its route's if set, else `server.max_body_size`
+    fn max_body_size(&self, req: &hyper::Request<Body>) -> usize {
+        self.router
+            .match_route(req.method().as_str(), req.uri().path())
+            .and_then(|(route_info, _)| route_info.max_body_size)
+            .unwrap_or(self.config.server.max_body_size)
+    }
+
     /// Execute the route handler (final step in the chain)
     async fn execute_route_handler(&self, ctx: &mut Context) -> Result<MiddlewareResult> {
         // Try to match route
@@ -1034,28 +1270,207 @@ impl RustF {
             }
 
             ctx.req.params = params;
+            ctx.req.route = Some(route_info.pattern.clone());
+
+            // Audit entries default to the session user unless middleware chose an actor
+            if crate::audit::current_actor().is_none() {
+                crate::audit::set_actor(
+                    ctx.session()
+                        .and_then(|session| session.get_user_id())
+                        .map(|id| id.to_string()),
+                );
+            }
 
             // Handler modifies context in place (sets response)
-            (route_info.handler)(ctx).await?;
+            let clock = ctx.timing_clock();
+            let timeout = ctx.handler_timeout();
+            let outcome = if self.config.server.catch_panics {
+                // A panic becomes a 500 so outbound middleware still runs
+                let caught = within(
+                    timeout,
+                    AssertUnwindSafe((route_info.handler)(ctx)).catch_unwind(),
+                )
+                .await;
+                match caught {
+                    Ok(Ok(outcome)) => Ok(outcome),
+                    Ok(Err(payload)) => {
+                        return Ok(MiddlewareResult::Stop(self.panic_response(ctx, payload)));
+                    }
+                    Err(timed_out) => Err(timed_out),
+                }
+            } else {
+                within(timeout, (route_info.handler)(ctx)).await
+            };
+            ctx.record_timing(TimingPhase::Handler, clock);
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err((duration, status)) => {
+                    return Ok(MiddlewareResult::Stop(
+                        self.timeout_response(ctx, duration, status),
+                    ));
+                }
+            };
+            if let Err(e) = outcome {
+                // Validation failures become RFC 7807 problem details for API
+                // requests and the error page otherwise, as in `ErrorPages`
+                if e.is_validation() {
+                    let response = if ctx.header("accept").is_some_and(accepts_json) {
+                        crate::error::problem::problem_response(
+                            e.status_code(),
+                            &e,
+                            Some(ctx.path()),
+                        )
+                    } else {
+                        let request_id = crate::http::request_id::current();
+                        self.error_response(ctx, &e, request_id.as_deref())
+                    };
+                    return Ok(MiddlewareResult::Stop(response));
+                }
+                return Err(e);
+            }
 
             // Get the response from context or return 500 if not set
-            let response = ctx.take_response().unwrap_or_else(Response::internal_error);
+            let mut response = ctx.take_response().unwrap_or_else(Response::internal_error);
+
+            // HEAD keeps the headers of the GET response but never sends a body
+            if ctx.req.method.eq_ignore_ascii_case("HEAD") {
+                // Streamed bodies have no known length, so only buffered ones get Content-Length
+                let streamed = response.take_stream().is_some();
+                if !streamed
+                    && !response
+                        .headers
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                {
+                    let length = response.body.len().to_string();
+                    response.add_header("Content-Length", &length);
+                }
+                response.body.clear();
+            }
 
             Ok(MiddlewareResult::Stop(response))
+        } else if let Some(openapi) = self.openapi.as_ref().filter(|openapi| {
+            ctx.req.method.eq_ignore_ascii_case("GET") && ctx.path() == openapi.path
+        }) {
+            let document = self.router.openapi(&openapi.title, &openapi.version);
+            Ok(MiddlewareResult::Stop(Response::json(document)?))
+        } else if ctx.req.method.eq_ignore_ascii_case("OPTIONS") {
+            // Answer OPTIONS for any known path with the methods registered for it
+            let allowed = self.router.allowed_methods(&ctx.req.uri);
+            if allowed.is_empty() {
+                return Ok(MiddlewareResult::Stop(Response::not_found()));
+            }
+            Ok(MiddlewareResult::Stop(
+                Response::no_content().with_header("Allow", &allowed.join(", ")),
+            ))
         } else {
             Ok(MiddlewareResult::Stop(Response::not_found()))
         }
     }
 
+    /// Build the response for an error returned by middleware or a handler
+    ///
+    /// API requests (`Accept: application/json`) get the `Response::json_error`
+    /// envelope, browsers the error page for the error's status.
+    fn error_response(
+        &self,
+        ctx: &Context,
+        error: &crate::error::Error,
+        request_id: Option<&str>,
+    ) -> Response {
+        let status = error.status_code();
+        if status >= 500 {
+            log::error!(
+                "Request {} {} failed (request id: {}): {}",
+                ctx.req.method,
+                ctx.path(),
+                request_id.unwrap_or("none"),
+                error
+            );
+        }
+
+        ErrorPages::new(Arc::clone(&self.views), Arc::clone(&self.config))
+            .create_error_response(status, Some(error), request_id, ctx.header("accept"))
+            .unwrap_or_else(|_| Response::internal_error())
+    }
+
+    /// Build the response for a handler that did not complete within its timeout
+    fn timeout_response(
+        &self,
+        ctx: &mut Context,
+        timeout: Duration,
+        status: StatusCode,
+    ) -> Response {
+        let request_id = crate::http::request_id::current();
+
+        log::warn!(
+            "Handler timed out after {:?} on {} {} (request id: {})",
+            timeout,
+            ctx.req.method,
+            ctx.path(),
+            request_id.as_deref().unwrap_or("none")
+        );
+
+        ctx.take_response();
+        let error = crate::error::Error::Timeout(format!(
+            "Request did not complete within {}s",
+            timeout.as_secs_f64()
+        ));
+        ErrorPages::new(Arc::clone(&self.views), Arc::clone(&self.config))
+            .create_error_response(
+                status.as_u16(),
+                Some(&error),
+                request_id.as_deref(),
+                ctx.header("accept"),
+            )
+            .unwrap_or_else(|_| Response::new(status))
+    }
+
+    /// Log a caught handler panic and build the 500 response for it
+    ///
+    /// Whatever the handler set on the context before panicking is discarded.
+    fn panic_response(&self, ctx: &mut Context, payload: Box<dyn Any + Send>) -> Response {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("non-string panic payload");
+        let request_id = crate::http::request_id::current();
+
+        log::error!(
+            "Handler panicked on {} {} (request id: {}): {}",
+            ctx.req.method,
+            ctx.path(),
+            request_id.as_deref().unwrap_or("none"),
+            message
+        );
+
+        ctx.take_response();
+        let error = crate::error::Error::internal(format!("Handler panicked: {}", message));
+        ErrorPages::new(Arc::clone(&self.views), Arc::clone(&self.config))
+            .create_error_response(
+                500,
+                Some(&error),
+                request_id.as_deref(),
+                ctx.header("accept"),
+            )
+            .unwrap_or_else(|_| Response::internal_error())
+    }
+
     async fn serve_static_file(
         &self,
+        request: &Request,
         base_dir: &PathBuf,
         relative_suffix: &str,
         full_request_path: &str,
     ) -> Result<Response> {
+        let options = StaticFileOptions::from(&self.config.static_files);
+
         // Try the suffix after the prefix first (preferred behaviour)
         if let Some(candidate) = Self::sanitize_and_join(base_dir, relative_suffix) {
-            if let Some(response) = Self::try_read_static_file(&candidate).await? {
+            if let Some(response) =
+                Self::try_read_static_file(request, &candidate, &options).await?
+            {
                 return Ok(response);
             }
         }
@@ -1063,7 +1478,9 @@ impl RustF {
         // Fall back to historical behaviour (prefix included) for compatibility
         let trimmed_full = full_request_path.trim_start_matches('/');
         if let Some(candidate) = Self::sanitize_and_join(base_dir, trimmed_full) {
-            if let Some(response) = Self::try_read_static_file(&candidate).await? {
+            if let Some(response) =
+                Self::try_read_static_file(request, &candidate, &options).await?
+            {
                 return Ok(response);
             }
         }
@@ -1119,15 +1536,16 @@ impl RustF {
         Some(base.join(clean))
     }
 
-    async fn try_read_static_file(path: &Path) -> Result<Option<Response>> {
+    async fn try_read_static_file(
+        request: &Request,
+        path: &Path,
+        options: &StaticFileOptions,
+    ) -> Result<Option<Response>> {
         match tokio::fs::metadata(path).await {
             Ok(metadata) if metadata.is_file() => {
-                let content = tokio::fs::read(path).await?;
                 let content_type = Self::infer_content_type(path);
                 Ok(Some(
-                    Response::ok()
-                        .with_header("Content-Type", content_type)
-                        .with_body(content),
+                    file_response(request, path, content_type, options).await?,
                 ))
             }
             Ok(_) => Ok(None),
@@ -1159,3 +1577,18 @@ impl RustF {
         }
     }
 }
+
+/// Await a handler future, giving up once its timeout, if any, passes
+///
+/// Fails with the timeout and the status to answer when it passed.
+async fn within<F: Future>(
+    timeout: Option<(Duration, StatusCode)>,
+    future: F,
+) -> std::result::Result<F::Output, (Duration, StatusCode)> {
+    match timeout {
+        Some((duration, status)) => tokio::time::timeout(duration, future)
+            .await
+            .map_err(|_| (duration, status)),
+        None => Ok(future.await),
+    }
+}
@@ -0,0 +1,335 @@
+//! Structured audit log
+//!
+//! Records who did what as `{actor, action, target, metadata, timestamp}`
+//! entries. An [`AuditLog`] writes entries to an [`AuditSink`]: the built-in
+//! sinks append JSON lines to a file or insert rows into a database table,
+//! and applications can plug in their own.
+//!
+//! The log is installed once, either from the `[audit]` config section when
+//! the app starts or explicitly with [`install`]:
+//!
+//! ```toml
+//! [audit]
+//! enabled = true
+//! sink = "database"      # or "file"
+//! table = "audit_log"
+//! path = "logs/audit.log"
+//! ```
+//!
+//! Handlers record entries with `ctx.audit(action, target, metadata)`. Models
+//! that set `BaseModel::AUDIT_WRITES` record their writes automatically.
+//!
+//! The actor defaults to the session user id of the current request. Code
+//! that authenticates differently (API tokens, for example) can override it
+//! for the rest of the request with [`set_actor`].
+
+use crate::config::{AuditConfig, AuditSinkKind};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+tokio::task_local! {
+    /// Actor of the request being handled on this task
+    static ACTOR: RefCell<Option<String>>;
+}
+
+/// Installed audit log
+static GLOBAL_AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+
+/// A single audit record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Who performed the action (`None` for system activity)
+    pub actor: Option<String>,
+    /// What was done, e.g. `"user.promote"` or `"orders.update"`
+    pub action: String,
+    /// What it was done to, e.g. `"users:42"`
+    pub target: String,
+    /// Free-form details
+    pub metadata: Value,
+    /// When it happened
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    /// Create an entry stamped with the current time
+    pub fn new(
+        actor: Option<String>,
+        action: impl Into<String>,
+        target: impl Into<String>,
+        metadata: Value,
+    ) -> Self {
+        Self {
+            actor,
+            action: action.into(),
+            target: target.into(),
+            metadata,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Destination for audit entries
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Persist one entry
+    async fn write(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+/// Appends one JSON object per line to a file
+pub struct FileSink {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileSink {
+    /// Create a sink for `path`; the file and its directory are created on first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileSink {
+    async fn write(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        // Keep lines from concurrent requests whole
+        let _guard = self.lock.lock().await;
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Inserts a row per entry into a table on the default database
+///
+/// The table needs `actor`, `action`, `target`, `metadata` and `created_at`
+/// columns, for example on PostgreSQL:
+///
+/// ```sql
+/// CREATE TABLE audit_log (
+///     id BIGSERIAL PRIMARY KEY,
+///     actor TEXT,
+///     action TEXT NOT NULL,
+///     target TEXT NOT NULL,
+///     metadata JSONB NOT NULL,
+///     created_at TIMESTAMPTZ NOT NULL
+/// );
+/// ```
+pub struct DatabaseSink {
+    table: String,
+}
+
+impl DatabaseSink {
+    /// Create a sink writing to `table`
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for DatabaseSink {
+    async fn write(&self, entry: &AuditEntry) -> Result<()> {
+        let data = HashMap::from([
+            ("actor".to_string(), entry.actor.clone().into()),
+            ("action".to_string(), entry.action.clone().into()),
+            ("target".to_string(), entry.target.clone().into()),
+            ("metadata".to_string(), entry.metadata.clone().into()),
+            ("created_at".to_string(), entry.timestamp.into()),
+        ]);
+        let (sql, params) = crate::db::DB::query()?
+            .from(&self.table)
+            .build_insert(&data)
+            .map_err(|e| Error::internal(format!("Failed to build audit insert: {}", e)))?;
+
+        crate::db::DB::execute_with_params(&sql, params).await?;
+        Ok(())
+    }
+}
+
+/// Records audit entries to a sink
+#[derive(Clone)]
+pub struct AuditLog {
+    sink: Arc<dyn AuditSink>,
+}
+
+impl AuditLog {
+    /// Create an audit log writing to `sink`
+    pub fn new(sink: impl AuditSink + 'static) -> Self {
+        Self {
+            sink: Arc::new(sink),
+        }
+    }
+
+    /// Create an audit log with the sink selected in `[audit]`
+    pub fn from_config(config: &AuditConfig) -> Self {
+        match config.sink {
+            AuditSinkKind::File => Self::new(FileSink::new(&config.path)),
+            AuditSinkKind::Database => Self::new(DatabaseSink::new(&config.table)),
+        }
+    }
+
+    /// Record an action by the current actor
+    pub async fn record(&self, action: &str, target: &str, metadata: Value) -> Result<()> {
+        self.record_entry(AuditEntry::new(current_actor(), action, target, metadata))
+            .await
+    }
+
+    /// Record a fully built entry
+    pub async fn record_entry(&self, entry: AuditEntry) -> Result<()> {
+        self.sink.write(&entry).await
+    }
+}
+
+/// Install the application's audit log
+///
+/// Only the first call takes effect; later ones return an error. When
+/// `[audit]` is enabled the app installs a log at startup unless one was
+/// installed before.
+pub fn install(log: AuditLog) -> Result<()> {
+    GLOBAL_AUDIT_LOG
+        .set(log)
+        .map_err(|_| Error::internal("Audit log already installed"))
+}
+
+/// The installed audit log, if any
+pub fn global() -> Option<&'static AuditLog> {
+    GLOBAL_AUDIT_LOG.get()
+}
+
+/// Record an action by the current actor on the installed audit log
+///
+/// Fails if no audit log is installed, so compliance records are never
+/// dropped silently.
+pub async fn record(action: &str, target: &str, metadata: Value) -> Result<()> {
+    record_entry(AuditEntry::new(current_actor(), action, target, metadata)).await
+}
+
+/// Record a fully built entry on the installed audit log
+pub async fn record_entry(entry: AuditEntry) -> Result<()> {
+    global()
+        .ok_or_else(|| {
+            Error::internal("Audit log not installed; enable [audit] or call audit::install")
+        })?
+        .record_entry(entry)
+        .await
+}
+
+/// Record a model write if an audit log is installed
+///
+/// The write has already happened, so a failing sink is logged rather than
+/// returned.
+pub(crate) async fn record_model_write(table: &str, action: &str, target: String, metadata: Value) {
+    let Some(log) = global() else {
+        return;
+    };
+    let action = format!("{}.{}", table, action);
+    if let Err(e) = log.record(&action, &target, metadata).await {
+        log::error!(
+            "Failed to record audit entry '{}' for {}: {}",
+            action,
+            target,
+            e
+        );
+    }
+}
+
+/// Run a request future with a slot for its actor
+pub async fn scope<F: Future>(fut: F) -> F::Output {
+    ACTOR.scope(RefCell::new(None), fut).await
+}
+
+/// Set the actor for the rest of the current request
+///
+/// Does nothing when called outside of [`scope`].
+pub fn set_actor(actor: Option<String>) {
+    let _ = ACTOR.try_with(|slot| *slot.borrow_mut() = actor);
+}
+
+/// Actor of the current request, if any
+pub fn current_actor() -> Option<String> {
+    ACTOR.try_with(|slot| slot.borrow().clone()).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Default)]
+    struct MemorySink(StdMutex<Vec<AuditEntry>>);
+
+    #[async_trait]
+    impl AuditSink for Arc<MemorySink> {
+        async fn write(&self, entry: &AuditEntry) -> Result<()> {
+            self.0.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_actor_comes_from_request_scope() {
+        let sink = Arc::new(MemorySink::default());
+        let log = AuditLog::new(sink.clone());
+
+        scope(async {
+            set_actor(Some("42".to_string()));
+            log.record("user.promote", "users:7", json!({"role": "admin"}))
+                .await
+                .unwrap();
+        })
+        .await;
+        log.record("cleanup.run", "sessions", json!({}))
+            .await
+            .unwrap();
+
+        let entries = sink.0.lock().unwrap();
+        assert_eq!(entries[0].actor.as_deref(), Some("42"));
+        assert_eq!(entries[0].metadata["role"], "admin");
+        assert_eq!(entries[1].actor, None);
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_appends_json_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit/audit.log");
+        let log = AuditLog::new(FileSink::new(&path));
+
+        log.record("a", "t1", json!({})).await.unwrap();
+        log.record("b", "t2", json!({"n": 1})).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<AuditEntry> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].action, "b");
+        assert_eq!(entries[1].metadata["n"], 1);
+    }
+}
@@ -2,15 +2,21 @@
 ///
 /// Features:
 /// - Thread-safe with RwLock
-/// - LRU eviction when capacity is reached
+/// - LRU, LFU or FIFO eviction when capacity is reached
 /// - TTL support with automatic expiration
 /// - Cache statistics and monitoring
 /// - Background cleanup of expired entries
-use super::{current_timestamp, Cache, CacheConfig, CacheEntry, CacheKey, CacheStats};
+/// - Single-flight computation of missing entries (`get_or_compute`)
+use super::stats::{emit_hit_rate_low, HitRateWindow};
+use super::{
+    current_timestamp, Cache, CacheConfig, CacheEntry, CacheKey, CacheStats, EvictionPolicy,
+    InFlight,
+};
 use crate::error::{Error, Result};
 use log::{debug, info, warn};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
 /// Thread-safe in-memory cache with LRU eviction
@@ -18,6 +24,9 @@ pub struct MemoryCache<T: Clone> {
     data: Arc<RwLock<HashMap<CacheKey, CacheEntry<T>>>>,
     config: CacheConfig,
     stats: Arc<RwLock<CacheStats>>,
+    in_flight: Arc<InFlight>,
+    /// Current sampling window of `config.hit_rate_alert`
+    hit_rate_window: Arc<Mutex<HitRateWindow>>,
 }
 
 impl<T: Clone> MemoryCache<T> {
@@ -35,6 +44,8 @@ impl<T: Clone> MemoryCache<T> {
             data: Arc::new(RwLock::new(HashMap::new())),
             config,
             stats: Arc::new(RwLock::new(stats)),
+            in_flight: Arc::new(InFlight::default()),
+            hit_rate_window: Arc::new(Mutex::new(HitRateWindow::new())),
         }
     }
 
@@ -45,6 +56,52 @@ impl<T: Clone> MemoryCache<T> {
         Self::with_config(config)
     }
 
+    /// Get a cached value, computing and caching it on a miss
+    ///
+    /// Only one computation runs per key at a time: concurrent callers for the
+    /// same missing key wait for the first one and then receive the value it
+    /// cached, instead of all recomputing it (thundering herd). Errors are not
+    /// cached; the next waiter retries the computation, as it does when the
+    /// computation panics or its future is dropped. `ResponseCache::get_or_render`
+    /// coalesces rendered pages the same way.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let stats = cache
+    ///     .get_or_compute("dashboard:stats", Some(Duration::from_secs(60)), || async {
+    ///         compute_dashboard_stats().await
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn get_or_compute<F, Fut>(
+        &self,
+        key: impl Into<CacheKey>,
+        ttl: Option<Duration>,
+        compute: F,
+    ) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let key = key.into();
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let _guard = self.in_flight.acquire(&key).await?;
+
+        // Another caller may have filled the entry while we were waiting
+        if let Some(entry) = self.get_entry_internal(&key) {
+            self.record_hit();
+            return Ok(entry.value);
+        }
+
+        debug!("Computing cache entry '{}'", key);
+        let value = compute().await?;
+        self.put(key, value.clone(), ttl)?;
+        Ok(value)
+    }
+
     /// Update cache statistics after a hit
     fn record_hit(&self) {
         if self.config.enable_stats {
@@ -52,6 +109,7 @@ impl<T: Clone> MemoryCache<T> {
                 stats.hits += 1;
             }
         }
+        self.sample_hit_rate(true);
     }
 
     /// Update cache statistics after a miss
@@ -61,6 +119,21 @@ impl<T: Clone> MemoryCache<T> {
                 stats.misses += 1;
             }
         }
+        self.sample_hit_rate(false);
+    }
+
+    /// Count a lookup in the hit rate alert window, if one is configured
+    fn sample_hit_rate(&self, hit: bool) {
+        let Some(alert) = &self.config.hit_rate_alert else {
+            return;
+        };
+        let closed = match self.hit_rate_window.lock() {
+            Ok(mut window) => window.record(hit, alert),
+            Err(_) => None,
+        };
+        if let Some(window) = closed {
+            emit_hit_rate_low(alert, &window, self.stats());
+        }
     }
 
     /// Update cache statistics after an eviction
@@ -90,22 +163,30 @@ impl<T: Clone> MemoryCache<T> {
         }
     }
 
-    /// Evict least recently used entry to make space
-    fn evict_lru(&self, data: &mut HashMap<CacheKey, CacheEntry<T>>) -> Result<()> {
+    /// Evict one entry, chosen by the eviction policy, to make space
+    fn evict(&self, data: &mut HashMap<CacheKey, CacheEntry<T>>) -> Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
-        // Find the entry with the oldest last_access time
-        let lru_key = data
-            .iter()
-            .min_by_key(|(_, entry)| entry.last_access)
+        let entries = data.iter();
+        let victim = match self.config.eviction_policy {
+            EvictionPolicy::Lru => entries.min_by_key(|(_, entry)| entry.last_access),
+            EvictionPolicy::Lfu => {
+                entries.min_by_key(|(_, entry)| (entry.access_count, entry.last_access))
+            }
+            EvictionPolicy::Fifo => entries.min_by_key(|(_, entry)| entry.created_at),
+        };
+        let victim_key = victim
             .map(|(key, _)| key.clone())
-            .ok_or_else(|| Error::internal("Failed to find LRU entry for eviction"))?;
+            .ok_or_else(|| Error::internal("Failed to find cache entry for eviction"))?;
 
-        data.remove(&lru_key);
+        data.remove(&victim_key);
         self.record_eviction();
-        debug!("Evicted LRU cache entry: {}", lru_key);
+        debug!(
+            "Evicted cache entry ({:?}): {}",
+            self.config.eviction_policy, victim_key
+        );
 
         Ok(())
     }
@@ -179,9 +260,12 @@ impl<T: Clone> Cache<T> for MemoryCache<T> {
         let entry = CacheEntry::new(value, ttl);
 
         if let Ok(mut data) = self.data.write() {
-            // Check if we need to make space
-            while data.len() >= self.config.max_entries && !data.is_empty() {
-                self.evict_lru(&mut data)?;
+            // Check if we need to make space; replacing an entry needs none
+            while !data.contains_key(&key)
+                && data.len() >= self.config.max_entries
+                && !data.is_empty()
+            {
+                self.evict(&mut data)?;
             }
 
             data.insert(key.clone(), entry);
@@ -280,6 +364,8 @@ impl<T: Clone> Clone for MemoryCache<T> {
             data: Arc::clone(&self.data),
             config: self.config.clone(),
             stats: Arc::clone(&self.stats),
+            in_flight: Arc::clone(&self.in_flight),
+            hit_rate_window: Arc::clone(&self.hit_rate_window),
         }
     }
 }
@@ -339,6 +425,73 @@ mod tests {
         assert_eq!(cache.get(&"key3".to_string()), Some("value3".to_string()));
     }
 
+    /// Fill a cache of 3 under `policy` and insert a fourth entry: `key1` is
+    /// the oldest and most read, `key2` was read long ago, `key3` never
+    fn evicted_key(policy: EvictionPolicy) -> String {
+        let cache: MemoryCache<String> = MemoryCache::with_config(CacheConfig {
+            max_entries: 3,
+            eviction_policy: policy,
+            ..CacheConfig::default()
+        });
+
+        let pause = || thread::sleep(Duration::from_millis(10));
+        cache
+            .put("key1".to_string(), "value1".to_string(), None)
+            .unwrap();
+        pause();
+        cache
+            .put("key2".to_string(), "value2".to_string(), None)
+            .unwrap();
+        cache.get(&"key2".to_string());
+        pause();
+        cache
+            .put("key3".to_string(), "value3".to_string(), None)
+            .unwrap();
+        pause();
+        for _ in 0..3 {
+            cache.get(&"key1".to_string());
+        }
+
+        cache
+            .put("key4".to_string(), "value4".to_string(), None)
+            .unwrap();
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.stats().evictions, 1);
+
+        let evicted: Vec<String> = (1..=3)
+            .map(|i| format!("key{}", i))
+            .filter(|key| !cache.contains_key(key))
+            .collect();
+        assert_eq!(evicted.len(), 1);
+        evicted[0].clone()
+    }
+
+    #[test]
+    fn test_eviction_policies() {
+        assert_eq!(evicted_key(EvictionPolicy::Lru), "key2");
+        assert_eq!(evicted_key(EvictionPolicy::Lfu), "key3");
+        assert_eq!(evicted_key(EvictionPolicy::Fifo), "key1");
+    }
+
+    #[test]
+    fn test_replacing_entry_does_not_evict() {
+        let cache: MemoryCache<String> = MemoryCache::with_capacity(2);
+        cache
+            .put("key1".to_string(), "value1".to_string(), None)
+            .unwrap();
+        cache
+            .put("key2".to_string(), "value2".to_string(), None)
+            .unwrap();
+        cache
+            .put("key1".to_string(), "updated".to_string(), None)
+            .unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"key1".to_string()), Some("updated".to_string()));
+        assert_eq!(cache.get(&"key2".to_string()), Some("value2".to_string()));
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
     #[test]
     fn test_ttl_expiration() {
         let cache: MemoryCache<String> = MemoryCache::new();
@@ -401,6 +554,85 @@ mod tests {
         assert_eq!(cache.get(&"key2".to_string()), Some("value2".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_get_or_compute_single_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache: MemoryCache<String> = MemoryCache::new();
+        let computations = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let cache = cache.clone();
+                let computations = Arc::clone(&computations);
+                tokio::spawn(async move {
+                    cache
+                        .get_or_compute("expensive", None, || async move {
+                            computations.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok("computed".to_string())
+                        })
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), "computed");
+        }
+
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+        assert!(cache.in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_or_compute_does_not_cache_errors() {
+        let cache: MemoryCache<String> = MemoryCache::new();
+
+        let result = cache
+            .get_or_compute("failing", None, || async {
+                Err(Error::internal("backend down"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(!cache.contains_key(&"failing".to_string()));
+
+        let value = cache
+            .get_or_compute("failing", None, || async { Ok("recovered".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(value, "recovered");
+    }
+
+    #[tokio::test]
+    async fn test_get_or_compute_releases_key_after_panic_or_cancel() {
+        let cache: MemoryCache<String> = MemoryCache::new();
+
+        let panicked = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                cache
+                    .get_or_compute("report", None, || async { panic!("render failed") })
+                    .await
+            }
+        })
+        .await;
+        assert!(panicked.is_err());
+        assert!(cache.in_flight.is_empty());
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            cache.get_or_compute("report", None, || async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok("late".to_string())
+            }),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert!(cache.in_flight.is_empty());
+    }
+
     #[test]
     fn test_thread_safety() {
         let cache: MemoryCache<String> = MemoryCache::new();
@@ -10,7 +10,9 @@ pub mod query;
 pub mod response;
 pub mod stats;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Cache key type for consistency across all cache implementations
@@ -58,26 +60,44 @@ impl<T> CacheEntry<T> {
     }
 }
 
+/// Which entry to evict when a cache is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Least recently used: oldest `last_access`
+    #[default]
+    Lru,
+    /// Least frequently used: lowest `access_count`, then oldest `last_access`
+    Lfu,
+    /// First in, first out: oldest `created_at`
+    Fifo,
+}
+
 /// Cache configuration for all cache types
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     /// Maximum number of entries in the cache
     pub max_entries: usize,
+    /// Entry evicted when `max_entries` is reached
+    pub eviction_policy: EvictionPolicy,
     /// Default TTL for cache entries (None = no expiration)
     pub default_ttl: Option<Duration>,
     /// Enable cache statistics collection
     pub enable_stats: bool,
     /// Cleanup interval for expired entries
     pub cleanup_interval: Duration,
+    /// Emit `cache.hit_rate_low` when the hit rate drops (None = disabled)
+    pub hit_rate_alert: Option<stats::HitRateAlert>,
 }
 
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             max_entries: 1000,
+            eviction_policy: EvictionPolicy::Lru,
             default_ttl: Some(Duration::from_secs(3600)), // 1 hour
             enable_stats: true,
             cleanup_interval: Duration::from_secs(300), // 5 minutes
+            hit_rate_alert: None,
         }
     }
 }
@@ -175,6 +195,67 @@ pub fn cache_key_with_hash(components: &[&str]) -> CacheKey {
     }
 }
 
+/// Per-key locks guarding in-flight computations (single-flight)
+///
+/// Shared by `MemoryCache::get_or_compute` and `ResponseCache::get_or_render`
+/// so concurrent misses on the same key compute once.
+#[derive(Default)]
+pub(crate) struct InFlight {
+    locks: Mutex<HashMap<CacheKey, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl InFlight {
+    /// Wait until no other caller holds `key`, then hold it until the guard drops
+    pub(crate) async fn acquire(&self, key: &CacheKey) -> Result<InFlightGuard<'_>> {
+        let lock = {
+            let mut locks = self
+                .locks
+                .lock()
+                .map_err(|_| Error::internal("Failed to acquire in-flight lock for cache"))?;
+            Arc::clone(locks.entry(key.clone()).or_default())
+        };
+
+        // Built before waiting so a cancelled waiter still cleans up
+        let mut guard = InFlightGuard {
+            in_flight: self,
+            key: key.clone(),
+            lock,
+            held: None,
+        };
+        guard.held = Some(Arc::clone(&guard.lock).lock_owned().await);
+        Ok(guard)
+    }
+
+    /// Check whether no key is held or waited on
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.locks.lock().map_or(true, |locks| locks.is_empty())
+    }
+}
+
+/// Holds a key of [`InFlight`]; dropping it releases the key
+///
+/// The key's lock is removed once no other caller holds or waits on it, also
+/// when the computation panicked or its future was dropped.
+pub(crate) struct InFlightGuard<'a> {
+    in_flight: &'a InFlight,
+    key: CacheKey,
+    lock: Arc<tokio::sync::Mutex<()>>,
+    held: Option<tokio::sync::OwnedMutexGuard<()>>,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.held = None;
+        if let Ok(mut locks) = self.in_flight.locks.lock() {
+            // One reference in the map, one here: nobody else is waiting
+            if Arc::strong_count(&self.lock) == 2 {
+                locks.remove(&self.key);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
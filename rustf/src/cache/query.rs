@@ -4,15 +4,18 @@ use super::memory::MemoryCache;
 /// Features:
 /// - SQL query result caching with parameterized query support
 /// - Table-based cache invalidation
+/// - Tag-based invalidation of entries cached under custom keys
 /// - Query fingerprinting for cache keys
 /// - Prepared statement parameter handling
 /// - Cache warming and preloading
 /// - Query execution time tracking
-use super::{cache_key_with_hash, Cache, CacheConfig, CacheKey};
-use crate::error::Result;
-use log::{debug, info};
+use super::{cache_key_with_hash, Cache, CacheConfig, CacheKey, EvictionPolicy};
+use crate::error::{Error, Result};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
 use std::time::Duration;
 
 /// Cached query result with metadata
@@ -88,6 +91,8 @@ impl Default for QueryCacheConfig {
                 default_ttl: Some(Duration::from_secs(1800)), // 30 minutes
                 enable_stats: true,
                 cleanup_interval: Duration::from_secs(300),
+                eviction_policy: EvictionPolicy::Lru,
+                hit_rate_alert: None,
             },
             enable_table_invalidation: true,
             cache_select_only: true,
@@ -99,11 +104,69 @@ impl Default for QueryCacheConfig {
 }
 
 /// Database query cache with table-based invalidation
+///
+/// Entries are indexed by tag for invalidation. Query results are tagged
+/// with the tables their SQL references, so invalidating a table is
+/// invalidating the tag of the same name.
 pub struct QueryCache {
     cache: MemoryCache<QueryCacheEntry>,
     config: QueryCacheConfig,
-    /// Maps table names to cache keys for invalidation
-    table_to_keys: MemoryCache<HashSet<CacheKey>>,
+    /// Maps tags (table names included) to cache keys for invalidation
+    tag_to_keys: RwLock<TagIndex>,
+}
+
+/// Reverse index from tags to the keys cached under them
+///
+/// Keys of entries evicted or expired from the cache stay in the index until
+/// it is pruned, which happens whenever it has grown to twice its size after
+/// the last pruning (and at least to the cache capacity).
+#[derive(Default)]
+struct TagIndex {
+    keys: HashMap<String, HashSet<CacheKey>>,
+    /// Number of indexed keys
+    len: usize,
+    /// Number of indexed keys that triggers the next pruning
+    prune_at: usize,
+}
+
+impl TagIndex {
+    fn new(capacity: usize) -> Self {
+        Self {
+            prune_at: capacity,
+            ..Self::default()
+        }
+    }
+
+    fn insert(&mut self, tag: &str, key: &CacheKey) {
+        if self
+            .keys
+            .entry(tag.to_string())
+            .or_default()
+            .insert(key.clone())
+        {
+            self.len += 1;
+        }
+    }
+
+    fn remove(&mut self, tag: &str) -> Option<HashSet<CacheKey>> {
+        let keys = self.keys.remove(tag)?;
+        self.len -= keys.len();
+        Some(keys)
+    }
+
+    /// Drop keys no longer cached, and tags left without keys
+    fn retain_cached(&mut self, cache: &MemoryCache<QueryCacheEntry>) {
+        self.keys.retain(|_, keys| {
+            keys.retain(|key| cache.contains_key(key));
+            !keys.is_empty()
+        });
+        self.len = self.keys.values().map(HashSet::len).sum();
+    }
+
+    fn clear(&mut self) {
+        self.keys.clear();
+        self.len = 0;
+    }
 }
 
 impl QueryCache {
@@ -115,12 +178,11 @@ impl QueryCache {
     /// Create new query cache with custom configuration
     pub fn with_config(config: QueryCacheConfig) -> Self {
         let cache = MemoryCache::with_config(config.cache_config.clone());
-        let table_to_keys = MemoryCache::with_capacity(100); // Reasonable number of tables
 
         Self {
             cache,
+            tag_to_keys: RwLock::new(TagIndex::new(config.cache_config.max_entries)),
             config,
-            table_to_keys,
         }
     }
 
@@ -243,11 +305,10 @@ impl QueryCache {
         // Cache the query result
         self.cache.put(cache_key.clone(), entry, ttl)?;
 
-        // Update table-to-keys mapping for invalidation
+        // Tag the entry with its tables for invalidation
         if self.config.enable_table_invalidation {
-            for table_name in referenced_tables {
-                self.add_key_to_table_mapping(&table_name, &cache_key)?;
-            }
+            let tables: Vec<&str> = referenced_tables.iter().map(String::as_str).collect();
+            self.add_key_to_tags(&cache_key, &tables);
         }
 
         debug!(
@@ -265,45 +326,97 @@ impl QueryCache {
         self.cache.get(&cache_key)
     }
 
-    /// Add cache key to table mapping for invalidation
-    fn add_key_to_table_mapping(&self, table_name: &str, cache_key: &CacheKey) -> Result<()> {
-        let mut keys = self
-            .table_to_keys
-            .get(&table_name.to_string())
-            .unwrap_or_else(HashSet::new);
-        keys.insert(cache_key.clone());
-        self.table_to_keys.put(table_name.to_string(), keys, None)?;
+    /// Cache a result under a custom key, tagged for invalidation
+    ///
+    /// The entry is dropped by `invalidate_tag` for any of its tags; tag it
+    /// with the tables it was read from so model writes invalidate it too.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// cache.put_tagged("dashboard:top_users", result, None, &["users", "orders"])?;
+    /// // After writing to users:
+    /// cache.invalidate_tag("users")?;
+    /// ```
+    pub fn put_tagged(
+        &self,
+        key: impl Into<CacheKey>,
+        value: Value,
+        ttl: Option<Duration>,
+        tags: &[&str],
+    ) -> Result<()> {
+        let key = key.into();
+        let entry = QueryCacheEntry::new(
+            value,
+            key.clone(),
+            Vec::new(),
+            tags.iter().map(|tag| tag.to_string()).collect(),
+            Duration::ZERO,
+        );
+
+        self.cache.put(key.clone(), entry, ttl)?;
+        self.add_key_to_tags(&key, tags);
+        debug!("Cached tagged result: key={}, tags={:?}", key, tags);
         Ok(())
     }
 
-    /// Invalidate cache entries for specific tables
-    pub fn invalidate_table(&self, table_name: &str) -> Result<usize> {
-        if !self.config.enable_table_invalidation {
-            return Ok(0);
-        }
+    /// Get a result cached with `put_tagged`
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.cache.get(&key.to_string()).map(|entry| entry.result)
+    }
 
-        let mut invalidated_count = 0;
+    /// Add a cache key to the reverse index of each tag
+    ///
+    /// Prunes the index when it has grown, so keys of evicted entries do not
+    /// accumulate when nothing invalidates their tags.
+    fn add_key_to_tags(&self, cache_key: &CacheKey, tags: &[&str]) {
+        if let Ok(mut tag_to_keys) = self.tag_to_keys.write() {
+            for tag in tags {
+                tag_to_keys.insert(tag, cache_key);
+            }
 
-        if let Some(cache_keys) = self.table_to_keys.get(&table_name.to_string()) {
-            for cache_key in cache_keys {
-                if self.cache.remove(&cache_key).is_some() {
-                    invalidated_count += 1;
-                    debug!("Invalidated cached query: {}", cache_key);
-                }
+            if tag_to_keys.len > tag_to_keys.prune_at {
+                tag_to_keys.retain_cached(&self.cache);
+                tag_to_keys.prune_at =
+                    (tag_to_keys.len * 2).max(self.config.cache_config.max_entries);
             }
+        }
+    }
 
-            // Clear the table mapping
-            self.table_to_keys.remove(&table_name.to_string());
+    /// Invalidate every cache entry carrying a tag
+    pub fn invalidate_tag(&self, tag: &str) -> Result<usize> {
+        let cache_keys = match self.tag_to_keys.write() {
+            Ok(mut tag_to_keys) => tag_to_keys.remove(tag),
+            Err(_) => {
+                return Err(Error::internal(
+                    "Failed to acquire write lock for query cache tags",
+                ))
+            }
+        };
+        let Some(cache_keys) = cache_keys else {
+            return Ok(0);
+        };
 
-            info!(
-                "Invalidated {} cached queries for table '{}'",
-                invalidated_count, table_name
-            );
+        let mut invalidated_count = 0;
+        for cache_key in cache_keys {
+            if self.cache.remove(&cache_key).is_some() {
+                invalidated_count += 1;
+                debug!("Invalidated cached query: {}", cache_key);
+            }
         }
 
+        info!(
+            "Invalidated {} cached queries for tag '{}'",
+            invalidated_count, tag
+        );
+
         Ok(invalidated_count)
     }
 
+    /// Invalidate cache entries for specific tables
+    pub fn invalidate_table(&self, table_name: &str) -> Result<usize> {
+        self.invalidate_tag(table_name)
+    }
+
     /// Invalidate cache entries for multiple tables
     pub fn invalidate_tables(&self, table_names: &[&str]) -> Result<usize> {
         let mut total_invalidated = 0;
@@ -318,7 +431,9 @@ impl QueryCache {
     /// Clear all cached queries
     pub fn clear(&self) {
         self.cache.clear();
-        self.table_to_keys.clear();
+        if let Ok(mut tag_to_keys) = self.tag_to_keys.write() {
+            tag_to_keys.clear();
+        }
     }
 
     /// Get cache statistics
@@ -327,13 +442,26 @@ impl QueryCache {
 
         QueryCacheStats {
             base_stats,
-            cached_tables: self.table_to_keys.len(),
+            cached_tables: self
+                .tag_to_keys
+                .read()
+                .map(|tags| tags.keys.len())
+                .unwrap_or(0),
         }
     }
 
     /// Cleanup expired entries
+    ///
+    /// Also drops keys of expired or evicted entries from the tag index, and
+    /// tags left without keys.
     pub fn cleanup_expired(&self) -> usize {
-        self.cache.cleanup_expired() + self.table_to_keys.cleanup_expired()
+        let cleaned = self.cache.cleanup_expired();
+
+        if let Ok(mut tag_to_keys) = self.tag_to_keys.write() {
+            tag_to_keys.retain_cached(&self.cache);
+        }
+
+        cleaned
     }
 
     /// Warm cache with commonly used queries
@@ -359,10 +487,50 @@ impl QueryCache {
     }
 }
 
+/// Application-wide query cache
+static GLOBAL_QUERY_CACHE: Lazy<QueryCache> = Lazy::new(QueryCache::new);
+
+/// Get the application-wide query cache
+///
+/// Models that set `BaseModel::INVALIDATE_QUERY_CACHE` clear their table's
+/// entries in this cache after every write.
+pub fn global() -> &'static QueryCache {
+    &GLOBAL_QUERY_CACHE
+}
+
+/// Invalidate cached queries for a table in the application-wide cache
+///
+/// Returns the number of invalidated entries; failures are logged, since a
+/// write that already succeeded should not be reported as failed.
+pub fn invalidate_table(table_name: &str) -> usize {
+    global().invalidate_table(table_name).unwrap_or_else(|e| {
+        warn!(
+            "Failed to invalidate cached queries for table '{}': {}",
+            table_name, e
+        );
+        0
+    })
+}
+
+/// Invalidate tagged entries in the application-wide cache
+///
+/// Returns the number of invalidated entries; failures are logged like
+/// `invalidate_table`.
+pub fn invalidate_tag(tag: &str) -> usize {
+    global().invalidate_tag(tag).unwrap_or_else(|e| {
+        warn!(
+            "Failed to invalidate cached queries for tag '{}': {}",
+            tag, e
+        );
+        0
+    })
+}
+
 /// Extended statistics for query cache
 #[derive(Debug, Clone)]
 pub struct QueryCacheStats {
     pub base_stats: super::CacheStats,
+    /// Number of tags (table names included) in the invalidation index
     pub cached_tables: usize,
 }
 
@@ -517,4 +685,70 @@ mod tests {
         // Verify it's no longer cached
         assert!(cache.get_cached_result(query, &params).is_none());
     }
+
+    #[test]
+    fn test_tag_invalidation() {
+        let cache = QueryCache::new();
+        cache
+            .put_tagged("top_users", json!([1, 2]), None, &["users"])
+            .unwrap();
+        cache
+            .put_tagged("user_orders", json!([3]), None, &["users", "orders"])
+            .unwrap();
+        cache
+            .put_tagged("products", json!([4]), None, &["products"])
+            .unwrap();
+        assert_eq!(cache.get("top_users"), Some(json!([1, 2])));
+
+        assert_eq!(cache.invalidate_tag("users").unwrap(), 2);
+        assert!(cache.get("top_users").is_none());
+        assert!(cache.get("user_orders").is_none());
+        assert_eq!(cache.get("products"), Some(json!([4])));
+
+        // Already gone through the other tag
+        assert_eq!(cache.invalidate_tag("orders").unwrap(), 0);
+        assert_eq!(cache.invalidate_tag("unknown").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tag_index_cleanup_on_expiry() {
+        let cache = QueryCache::new();
+        cache
+            .put_tagged(
+                "short_lived",
+                json!([1]),
+                Some(Duration::from_millis(10)),
+                &["users"],
+            )
+            .unwrap();
+        cache
+            .put_tagged("long_lived", json!([2]), None, &["orders"])
+            .unwrap();
+        assert_eq!(cache.stats().cached_tables, 2);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.cleanup_expired(), 1);
+
+        let tag_to_keys = cache.tag_to_keys.read().unwrap();
+        assert!(!tag_to_keys.keys.contains_key("users"));
+        assert!(tag_to_keys.keys["orders"].contains("long_lived"));
+    }
+
+    #[test]
+    fn test_tag_index_pruned_on_eviction() {
+        let mut config = QueryCacheConfig::default();
+        config.cache_config.max_entries = 10;
+        let cache = QueryCache::with_config(config);
+
+        // Evicted entries are never invalidated, yet the index stays bounded
+        for i in 0..100 {
+            cache
+                .put_tagged(format!("report:{}", i), json!(i), None, &["users"])
+                .unwrap();
+        }
+
+        let tag_to_keys = cache.tag_to_keys.read().unwrap();
+        assert!(tag_to_keys.len <= 20);
+        assert!(tag_to_keys.keys["users"].contains("report:99"));
+    }
 }
@@ -8,15 +8,42 @@ use super::memory::MemoryCache;
 /// - Cache-Control header handling
 /// - Vary header support for content negotiation
 /// - Response compression awareness
-use super::{cache_key_with_hash, Cache, CacheConfig, CacheKey};
+/// - Optional coalescing of concurrent identical requests (`get_or_render`)
+use super::{cache_key_with_hash, Cache, CacheConfig, CacheKey, InFlight};
 use crate::error::Result;
 use crate::http::response::Response;
 use log::debug;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Headers that are regenerated from the entry or must never be replayed
+const UNCACHED_HEADERS: &[&str] = &["content-type", "etag", "last-modified", "cache-control"];
+
+/// Whether a response may be replayed to other clients
+///
+/// Responses setting cookies, marked `Cache-Control: private` or `no-store`,
+/// or varying on every request header (`Vary: *`) are not.
+pub(crate) fn is_shareable(headers: &[(String, String)]) -> bool {
+    headers.iter().all(|(name, value)| {
+        if name.eq_ignore_ascii_case("set-cookie") {
+            false
+        } else if name.eq_ignore_ascii_case("cache-control") {
+            !value.split(',').any(|directive| {
+                let directive = directive.split('=').next().unwrap_or("").trim();
+                directive.eq_ignore_ascii_case("private")
+                    || directive.eq_ignore_ascii_case("no-store")
+            })
+        } else if name.eq_ignore_ascii_case("vary") {
+            !value.split(',').any(|header| header.trim() == "*")
+        } else {
+            true
+        }
+    })
+}
+
 /// HTTP response cache entry with metadata
 #[derive(Debug, Clone)]
 pub struct ResponseCacheEntry {
@@ -106,6 +133,7 @@ impl ResponseCacheEntry {
 pub struct ResponseCache {
     cache: MemoryCache<ResponseCacheEntry>,
     config: ResponseCacheConfig,
+    in_flight: InFlight,
 }
 
 /// Configuration for response caching
@@ -125,6 +153,9 @@ pub struct ResponseCacheConfig {
     pub cacheable_content_types: Vec<String>,
     /// Status codes to cache
     pub cacheable_status_codes: Vec<u16>,
+    /// Let concurrent identical requests wait for the first one to render
+    /// instead of all rendering the same response on a miss
+    pub coalesce_requests: bool,
 }
 
 impl Default for ResponseCacheConfig {
@@ -145,6 +176,7 @@ impl Default for ResponseCacheConfig {
                 "text/xml".to_string(),
             ],
             cacheable_status_codes: vec![200, 203, 300, 301, 302, 304, 404, 410],
+            coalesce_requests: false,
         }
     }
 }
@@ -165,26 +197,93 @@ impl ResponseCache {
     pub fn with_config(config: ResponseCacheConfig) -> Self {
         let cache = MemoryCache::with_config(config.cache_config.clone());
 
-        Self { cache, config }
+        Self {
+            cache,
+            config,
+            in_flight: InFlight::default(),
+        }
     }
 
     /// Generate cache key for request
+    ///
+    /// Each of the configured `vary_headers` contributes its request value,
+    /// empty when absent, so responses negotiated differently (gzip and
+    /// identity, two languages) get separate entries. Header names are
+    /// matched case-insensitively.
     pub fn generate_cache_key(
         &self,
         method: &str,
         path: &str,
         headers: &HashMap<String, String>,
     ) -> CacheKey {
+        let vary_values: Vec<String> = self
+            .config
+            .vary_headers
+            .iter()
+            .map(|header_name| {
+                let value = headers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(header_name))
+                    .map(|(_, value)| value.as_str())
+                    .unwrap_or("");
+                format!("{}={}", header_name.to_ascii_lowercase(), value)
+            })
+            .collect();
+
         let mut components = vec![method, path];
+        components.extend(vary_values.iter().map(String::as_str));
+        cache_key_with_hash(&components)
+    }
+
+    /// Add the configured `vary_headers` to a response's `Vary` header
+    fn with_vary(&self, mut headers: HashMap<String, String>) -> HashMap<String, String> {
+        if self.config.vary_headers.is_empty() {
+            return headers;
+        }
 
-        // Add vary headers to cache key
+        let existing = headers
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case("vary"))
+            .cloned();
+        let mut vary: Vec<String> = existing
+            .and_then(|name| headers.remove(&name))
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
         for header_name in &self.config.vary_headers {
-            if let Some(header_value) = headers.get(&header_name.to_lowercase()) {
-                components.push(header_value);
+            if !vary
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(header_name))
+            {
+                vary.push(header_name.clone());
             }
         }
 
-        cache_key_with_hash(&components)
+        headers.insert("Vary".to_string(), vary.join(", "));
+        headers
+    }
+
+    /// Whether every header the response varies on is part of the cache key
+    ///
+    /// A response negotiated on a header outside `vary_headers` (`Vary:
+    /// Cookie`, say) would be replayed to clients sending other values.
+    fn varies_within_key(&self, headers: &[(String, String)]) -> bool {
+        headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("vary"))
+            .flat_map(|(_, value)| value.split(','))
+            .map(str::trim)
+            .filter(|header| !header.is_empty())
+            .all(|header| {
+                self.config
+                    .vary_headers
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(header))
+            })
     }
 
     /// Check if response should be cached
@@ -227,6 +326,7 @@ impl ResponseCache {
             return Ok(());
         }
 
+        let headers = self.with_vary(headers);
         let entry = ResponseCacheEntry::new(body, status_code, content_type, headers, ttl);
         self.cache.put(key.clone(), entry, ttl)?;
 
@@ -239,6 +339,112 @@ impl ResponseCache {
         self.cache.get(key)
     }
 
+    /// Serve a request from the cache, rendering and caching it on a miss
+    ///
+    /// Only GET and HEAD requests are cached; `headers` uses lowercase names,
+    /// as for [`generate_cache_key`](Self::generate_cache_key). Streaming,
+    /// binary and cookie-setting responses are returned without being cached.
+    ///
+    /// With `coalesce_requests` enabled, concurrent requests with the same
+    /// method, path and vary headers render once: the first renders while the
+    /// others wait, then they are served the entry it cached. When that
+    /// response turns out not to be cacheable, the next waiter renders.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let response = cache
+    ///     .get_or_render("GET", "/reports/daily", &headers, Some(Duration::from_secs(30)), || async {
+    ///         render_daily_report().await
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn get_or_render<F, Fut>(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &HashMap<String, String>,
+        ttl: Option<Duration>,
+        render: F,
+    ) -> Result<Response>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Response>>,
+    {
+        if !matches!(method.to_ascii_uppercase().as_str(), "GET" | "HEAD") {
+            return render().await;
+        }
+
+        let key = self.generate_cache_key(method, path, headers);
+        if let Some(response) = self.cached_response(&key) {
+            return Ok(response);
+        }
+
+        if !self.config.coalesce_requests {
+            let response = render().await?;
+            self.store_response(&key, &response, ttl)?;
+            return Ok(response);
+        }
+
+        let _guard = self.in_flight.acquire(&key).await?;
+
+        // The request we waited for may have cached the response
+        if let Some(response) = self.cached_response(&key) {
+            return Ok(response);
+        }
+
+        debug!("Rendering coalesced response: key={}", key);
+        let response = render().await?;
+        self.store_response(&key, &response, ttl)?;
+        Ok(response)
+    }
+
+    fn cached_response(&self, key: &CacheKey) -> Option<Response> {
+        self.cache
+            .get(key)
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| entry.to_response())
+    }
+
+    /// Cache a rendered response if it can be replayed to other clients
+    pub(crate) fn store_response(
+        &self,
+        key: &CacheKey,
+        response: &Response,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        if response.is_streaming()
+            || !is_shareable(&response.headers)
+            || !self.varies_within_key(&response.headers)
+        {
+            return Ok(());
+        }
+        let Ok(body) = String::from_utf8(response.body.clone()) else {
+            return Ok(());
+        };
+
+        let content_type = response
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.clone())
+            .unwrap_or_else(|| "text/html".to_string());
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(name, _)| !UNCACHED_HEADERS.contains(&name.to_ascii_lowercase().as_str()))
+            .cloned()
+            .collect();
+
+        self.cache_response(
+            key.clone(),
+            body,
+            response.status.as_u16(),
+            content_type,
+            headers,
+            ttl,
+        )
+    }
+
     /// Handle conditional request (ETag/Last-Modified validation)
     pub fn handle_conditional_request(
         &self,
@@ -428,6 +634,162 @@ mod tests {
         assert!(matches!(result, Some(ConditionalResponse::Modified(_))));
     }
 
+    #[tokio::test]
+    async fn test_get_or_render_coalesces_requests() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let cache = Arc::new(ResponseCache::with_config(ResponseCacheConfig {
+            coalesce_requests: true,
+            ..Default::default()
+        }));
+        let renders = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                let renders = Arc::clone(&renders);
+                tokio::spawn(async move {
+                    let headers = HashMap::new();
+                    cache
+                        .get_or_render("GET", "/report", &headers, None, || async move {
+                            renders.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok(Response::html("report"))
+                        })
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().body, b"report");
+        }
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+        assert!(cache.in_flight.is_empty());
+
+        // Responses setting cookies are never shared
+        let headers = HashMap::new();
+        let mut private = Response::html("private");
+        private.add_header("Set-Cookie", "sid=1");
+        cache
+            .get_or_render("GET", "/me", &headers, None, || async { Ok(private) })
+            .await
+            .unwrap();
+        let key = cache.generate_cache_key("GET", "/me", &headers);
+        assert!(cache.get_response(&key).is_none());
+    }
+
+    #[test]
+    fn test_is_shareable() {
+        let headers = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+
+        assert!(is_shareable(&headers(&[
+            ("Cache-Control", "public, max-age=60"),
+            ("Vary", "Accept-Encoding"),
+        ])));
+        assert!(!is_shareable(&headers(&[("set-cookie", "sid=1")])));
+        assert!(!is_shareable(&headers(&[(
+            "Cache-Control",
+            "max-age=0, Private"
+        )])));
+        assert!(!is_shareable(&headers(&[("cache-control", "no-store")])));
+        assert!(!is_shareable(&headers(&[("Vary", "Accept, *")])));
+    }
+
+    #[tokio::test]
+    async fn test_vary_outside_cache_key_not_cached() {
+        let cache = ResponseCache::new();
+        let headers = HashMap::new();
+
+        let mut negotiated = Response::html("en");
+        negotiated.add_header("Vary", "accept-language");
+        cache
+            .get_or_render("GET", "/home", &headers, None, || async { Ok(negotiated) })
+            .await
+            .unwrap();
+        let key = cache.generate_cache_key("GET", "/home", &headers);
+        assert!(cache.get_response(&key).is_some());
+
+        let mut per_user = Response::html("dashboard");
+        per_user.add_header("Vary", "Accept-Encoding, Cookie");
+        cache
+            .get_or_render("GET", "/dashboard", &headers, None, || async {
+                Ok(per_user)
+            })
+            .await
+            .unwrap();
+        let key = cache.generate_cache_key("GET", "/dashboard", &headers);
+        assert!(cache.get_response(&key).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vary_headers_separate_entries() {
+        let cache = ResponseCache::new();
+        let mut gzip = HashMap::new();
+        gzip.insert("Accept-Encoding".to_string(), "gzip".to_string());
+        let mut identity = HashMap::new();
+        identity.insert("accept-encoding".to_string(), "identity".to_string());
+
+        let gzip_key = cache.generate_cache_key("GET", "/page", &gzip);
+        let identity_key = cache.generate_cache_key("GET", "/page", &identity);
+        assert_ne!(gzip_key, identity_key);
+
+        // A value may not stand in for another vary header
+        let mut language = HashMap::new();
+        language.insert("accept-language".to_string(), "gzip".to_string());
+        assert_ne!(
+            gzip_key,
+            cache.generate_cache_key("GET", "/page", &language)
+        );
+
+        for (headers, body) in [(&gzip, "compressed"), (&identity, "plain")] {
+            let response = cache
+                .get_or_render("GET", "/page", headers, None, || async move {
+                    Ok(Response::html(body))
+                })
+                .await
+                .unwrap();
+            assert_eq!(response.body, body.as_bytes());
+        }
+
+        assert_eq!(cache.get_response(&gzip_key).unwrap().body, "compressed");
+        assert_eq!(cache.get_response(&identity_key).unwrap().body, "plain");
+        assert_eq!(cache.stats().entries, 2);
+
+        // Served responses carry the Vary header
+        let served = cache
+            .get_or_render("GET", "/page", &gzip, None, || async {
+                Ok(Response::html("rendered again"))
+            })
+            .await
+            .unwrap();
+        assert_eq!(served.body, b"compressed");
+        let vary = served
+            .headers
+            .iter()
+            .find(|(name, _)| name == "Vary")
+            .map(|(_, value)| value.as_str());
+        assert_eq!(vary, Some("Accept-Encoding, Accept-Language"));
+    }
+
+    #[test]
+    fn test_vary_header_merges_response_vary() {
+        let cache = ResponseCache::new();
+        let mut headers = HashMap::new();
+        headers.insert("vary".to_string(), "Origin, accept-encoding".to_string());
+
+        let headers = cache.with_vary(headers);
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["Vary"], "Origin, accept-encoding, Accept-Language");
+    }
+
     #[test]
     fn test_cache_key_generation() {
         let cache = ResponseCache::new();
@@ -6,10 +6,11 @@
 /// - Cache entry lifecycle metrics
 /// - Performance impact measurements
 /// - Export capabilities for monitoring systems
+/// - Low hit rate alerts through the `cache.hit_rate_low` event
 use super::CacheStats;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Comprehensive cache statistics across all cache types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -564,6 +565,117 @@ pub struct CacheStatsSources {
     pub memory_caches: Vec<(String, Box<dyn Fn() -> CacheStats>)>,
 }
 
+/// Alert raised when a cache's hit rate drops, set in `CacheConfig::hit_rate_alert`
+///
+/// The hit rate is measured over consecutive sampling windows. When a window
+/// closes below the threshold, `events::CACHE_HIT_RATE_LOW` is emitted on the
+/// application's emitter with the window and cache statistics; the next
+/// window starts empty, so a lasting drop raises at most one event per window.
+#[derive(Debug, Clone)]
+pub struct HitRateAlert {
+    /// Cache name reported in the event
+    pub cache: String,
+    /// Hit rate below which the event is emitted (0.0 - 1.0)
+    pub threshold: f64,
+    /// Length of a sampling window
+    pub window: Duration,
+    /// Fewest lookups in a window for its hit rate to count
+    pub min_requests: u64,
+}
+
+impl HitRateAlert {
+    /// Alert when `cache` hits less than `threshold` of lookups over `window`
+    pub fn new(cache: impl Into<String>, threshold: f64, window: Duration) -> Self {
+        Self {
+            cache: cache.into(),
+            threshold,
+            window,
+            min_requests: 100,
+        }
+    }
+
+    /// Set the fewest lookups a window needs before it can raise the alert
+    pub fn with_min_requests(mut self, min_requests: u64) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+}
+
+/// Lookups of the current sampling window of a `HitRateAlert`
+#[derive(Debug)]
+pub(crate) struct HitRateWindow {
+    started_at: Instant,
+    hits: u64,
+    misses: u64,
+}
+
+impl HitRateWindow {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Count a lookup, closing the window once it is `alert.window` old
+    ///
+    /// Returns the closed window's hits and misses when its hit rate is below
+    /// the threshold.
+    pub(crate) fn record(&mut self, hit: bool, alert: &HitRateAlert) -> Option<CacheStats> {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+
+        if self.started_at.elapsed() < alert.window {
+            return None;
+        }
+
+        let window = CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            ..CacheStats::default()
+        };
+        *self = Self::new();
+
+        (window.hits + window.misses >= alert.min_requests && window.hit_rate() < alert.threshold)
+            .then_some(window)
+    }
+}
+
+/// Emit `events::CACHE_HIT_RATE_LOW` for a closed window, in the background
+pub(crate) fn emit_hit_rate_low(alert: &HitRateAlert, window: &CacheStats, stats: CacheStats) {
+    let data = serde_json::json!({
+        "cache": alert.cache,
+        "hit_rate": window.hit_rate(),
+        "threshold": alert.threshold,
+        "window_secs": alert.window.as_secs_f64(),
+        "window_hits": window.hits,
+        "window_misses": window.misses,
+        "stats": stats,
+    });
+    log::warn!(
+        "Cache '{}' hit rate {:.2} fell below {:.2}",
+        alert.cache,
+        window.hit_rate(),
+        alert.threshold
+    );
+
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => {
+            runtime.spawn(async move {
+                let event = crate::events::events::CACHE_HIT_RATE_LOW;
+                if let Err(e) = crate::events::emit_global(event, Some(data)).await {
+                    log::warn!("Failed to emit '{}': {}", event, e);
+                }
+            });
+        }
+        Err(_) => log::debug!("No runtime to emit the cache hit rate event"),
+    }
+}
+
 /// Get current timestamp
 fn current_timestamp() -> u64 {
     SystemTime::now()
@@ -582,6 +694,31 @@ impl Default for CacheStatsCollector {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hit_rate_window() {
+        let alert = HitRateAlert::new("test", 0.5, Duration::from_millis(20)).with_min_requests(4);
+        let mut window = HitRateWindow::new();
+
+        // The window stays open until it is old enough
+        for _ in 0..4 {
+            assert!(window.record(false, &alert).is_none());
+        }
+        std::thread::sleep(Duration::from_millis(25));
+        let closed = window.record(true, &alert).unwrap();
+        assert_eq!((closed.hits, closed.misses), (1, 4));
+
+        // The next window starts empty: too few lookups to alert
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(window.record(false, &alert).is_none());
+
+        // A window at or above the threshold does not alert
+        for _ in 0..4 {
+            window.record(true, &alert);
+        }
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(window.record(false, &alert).is_none());
+    }
+
     #[test]
     fn test_linear_trend_calculation() {
         let collector = CacheStatsCollector::new();
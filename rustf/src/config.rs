@@ -5,6 +5,9 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+/// Profile sections recognised at the top level of `config.toml`
+pub const CONFIG_PROFILES: &[&str] = &["development", "production", "staging", "test"];
+
 /// Environment type for configuration
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -13,6 +16,8 @@ pub enum Environment {
     #[default]
     Development,
     Production,
+    Staging,
+    Test,
 }
 
 impl Environment {
@@ -21,6 +26,8 @@ impl Environment {
         match env.to_lowercase().as_str() {
             "production" | "prod" => Environment::Production,
             "development" | "dev" => Environment::Development,
+            "staging" => Environment::Staging,
+            "test" => Environment::Test,
             _ => Environment::Development,
         }
     }
@@ -30,6 +37,8 @@ impl Environment {
         match self {
             Environment::Development => "dev",
             Environment::Production => "prod",
+            Environment::Staging => "staging",
+            Environment::Test => "test",
         }
     }
 
@@ -44,6 +53,10 @@ pub struct AppConfig {
     #[serde(default)]
     pub environment: Environment,
 
+    /// Application secret used to sign cookies and other tamper-proof values
+    #[serde(default)]
+    pub secret: Option<String>,
+
     #[serde(default)]
     pub server: ServerConfig,
 
@@ -68,6 +81,18 @@ pub struct AppConfig {
     #[serde(default)]
     pub uploads: UploadConfig,
 
+    #[serde(default)]
+    pub json: JsonConfig,
+
+    #[serde(default)]
+    pub query: QueryConfig,
+
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    #[serde(default)]
+    pub i18n: I18nConfig,
+
     // All other sections - user-defined configuration sections
     // These sections are stored as TOML values and can be deserialized on-demand
     #[serde(flatten)]
@@ -99,6 +124,34 @@ pub struct ServerConfig {
 
     #[serde(default = "default_shutdown_timeout")]
     pub shutdown_timeout: u64,
+
+    /// Answer a panicking handler with a 500 instead of letting the panic
+    /// unwind through the connection task
+    #[serde(default = "default_catch_panics")]
+    pub catch_panics: bool,
+
+    /// Time the phases of every request and report them in a
+    /// `Server-Timing` header (leave off in production)
+    #[serde(default)]
+    pub server_timing: bool,
+
+    /// Requests handled at once; further requests get a `503` with
+    /// `Retry-After` instead of queuing (0 means no limit)
+    #[serde(default)]
+    pub max_concurrent_requests: usize,
+
+    /// Seconds sent in `Retry-After` when the request limit is reached
+    #[serde(default = "default_overload_retry_after")]
+    pub overload_retry_after: u64,
+
+    /// Path prefixes that bypass `max_concurrent_requests`, e.g. health checks
+    #[serde(default = "default_overload_excluded_paths")]
+    pub overload_excluded_paths: Vec<String>,
+
+    /// Largest request body accepted, in bytes; bigger ones get a `413`
+    /// before they are read in full (0 means no limit)
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -167,6 +220,10 @@ pub struct SessionConfig {
     #[serde(default = "default_fingerprint_mode")]
     pub fingerprint_mode: String,
 
+    /// What to do with a `SameSite=None` cookie that is not `Secure`
+    #[serde(default)]
+    pub same_site_none: SameSiteNoneMode,
+
     #[serde(default)]
     pub storage: SessionStorageConfig,
 
@@ -174,6 +231,19 @@ pub struct SessionConfig {
     pub exempt_routes: Vec<String>,
 }
 
+/// Handling of `SameSite=None` cookies without the `Secure` attribute
+///
+/// Browsers silently drop such cookies, so they are never sent as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SameSiteNoneMode {
+    /// Add `Secure` and log a warning
+    #[default]
+    Warn,
+    /// Refuse to set the cookie
+    Error,
+}
+
 /// Session storage backend configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
@@ -195,11 +265,29 @@ pub enum SessionStorageConfig {
         #[serde(default = "default_redis_command_timeout")]
         command_timeout: u64,
     },
+    /// Memcached-based session storage (requires the `memcached` feature)
+    Memcached {
+        /// Server URLs, e.g. `memcache://localhost:11211`
+        servers: Vec<String>,
+        #[serde(default = "default_redis_prefix")]
+        prefix: String,
+        /// Connections per server
+        #[serde(default = "default_memcached_pool_size")]
+        pool_size: u32,
+        #[serde(default = "default_redis_connection_timeout")]
+        connection_timeout: u64,
+        #[serde(default = "default_redis_command_timeout")]
+        command_timeout: u64,
+    },
     /// Database-based session storage
     Database {
         #[serde(default = "default_sessions_table")]
         table: String,
         connection_url: Option<String>,
+        /// Registry connection to use when `connection_url` is unset;
+        /// defaults to the default database
+        #[serde(default)]
+        connection: Option<String>,
         #[serde(default = "default_cleanup_interval")]
         cleanup_interval: u64,
     },
@@ -226,6 +314,10 @@ pub struct StaticConfig {
 
     #[serde(default = "default_cache_max_age")]
     pub cache_max_age: u64,
+
+    /// Serve a `.br`/`.gz` sibling of a file when the client accepts that encoding
+    #[serde(default = "default_precompressed")]
+    pub precompressed: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -238,6 +330,10 @@ pub struct DatabaseConfig {
 
     #[serde(default)]
     pub timeout: Option<u64>,
+
+    /// Log statements taking at least this many milliseconds (0, the default, disables)
+    #[serde(default)]
+    pub slow_query_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -285,6 +381,127 @@ pub struct UploadConfig {
     pub create_directories: bool,
 }
 
+/// Limits applied to JSON request bodies before they are parsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonConfig {
+    /// Maximum nesting depth of objects and arrays
+    #[serde(default = "default_json_max_depth")]
+    pub max_depth: usize,
+
+    /// Maximum number of keys in one object or elements in one array
+    #[serde(default = "default_json_max_elements")]
+    pub max_elements: usize,
+
+    /// What to do with numbers that cannot be represented exactly
+    #[serde(default)]
+    pub numbers: JsonNumbers,
+}
+
+/// Handling of JSON numbers that do not fit a 64-bit integer or an `f64`
+///
+/// Integers beyond the `i64`/`u64` range and decimals with more than 15
+/// significant digits lose precision when parsed as numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonNumbers {
+    /// Leave them to the parser: decimals are rounded, oversized integers fail
+    #[default]
+    Lossy,
+    /// Reject the body with a 400
+    Strict,
+    /// Pass them on as strings holding the original digits
+    Preserve,
+}
+
+/// Query string parsing options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryConfig {
+    /// Accepted syntax for multi-value parameters
+    #[serde(default)]
+    pub array_syntax: QueryArraySyntax,
+}
+
+/// How a query string may repeat a parameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryArraySyntax {
+    /// Accept both `tag=a&tag=b` and `tag[]=a&tag[]=b`
+    #[default]
+    Both,
+    /// Only `tag=a&tag=b`; `tag[]` is an ordinary key
+    Repeat,
+    /// Only `tag[]=a&tag[]=b`; a repeated plain key keeps its last value
+    Brackets,
+}
+
+/// Audit log settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Install an audit log with the configured sink when the app starts
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Where entries go
+    #[serde(default)]
+    pub sink: AuditSinkKind,
+
+    /// File the `file` sink appends JSON lines to
+    #[serde(default = "default_audit_path")]
+    pub path: String,
+
+    /// Table the `database` sink inserts into
+    #[serde(default = "default_audit_table")]
+    pub table: String,
+}
+
+/// Locale detection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct I18nConfig {
+    /// Locales the application has translations for, e.g. `["en", "fr", "de-CH"]`
+    ///
+    /// When empty only `default_locale` is available.
+    #[serde(default)]
+    pub locales: Vec<String>,
+
+    /// Locale used when nothing the client asks for is available
+    #[serde(default = "default_locale")]
+    pub default_locale: String,
+
+    /// Query parameter that selects a locale explicitly
+    #[serde(default = "default_locale_param")]
+    pub query_param: String,
+
+    /// Cookie that remembers an explicitly chosen locale
+    #[serde(default = "default_locale_param")]
+    pub cookie: String,
+
+    /// Session key holding the user's locale
+    #[serde(default = "default_locale_param")]
+    pub session_key: String,
+}
+
+/// Built-in audit log destinations
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSinkKind {
+    /// Append one JSON object per line to `path`
+    #[default]
+    File,
+    /// Insert a row per entry into `table` on the default database
+    Database,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: AuditSinkKind::default(),
+            path: default_audit_path(),
+            table: default_audit_table(),
+        }
+    }
+}
+
 // Default value functions
 fn default_host() -> String {
     "127.0.0.1".to_string()
@@ -298,9 +515,21 @@ fn default_timeout() -> u64 {
 fn default_shutdown_timeout() -> u64 {
     30
 }
+fn default_catch_panics() -> bool {
+    true
+}
 fn default_max_connections() -> usize {
     1000
 }
+fn default_overload_retry_after() -> u64 {
+    5
+}
+fn default_overload_excluded_paths() -> Vec<String> {
+    vec!["/health".to_string()]
+}
+fn default_max_body_size() -> usize {
+    64 * 1024 * 1024
+}
 fn default_views_dir() -> String {
     "views".to_string()
 }
@@ -340,6 +569,10 @@ fn default_static_prefix() -> String {
 fn default_cache_max_age() -> u64 {
     86400
 }
+fn default_precompressed() -> bool {
+    true
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -352,6 +585,12 @@ fn default_max_file_size() -> u64 {
 fn default_max_files() -> usize {
     5
 }
+fn default_json_max_depth() -> usize {
+    64
+}
+fn default_json_max_elements() -> usize {
+    10_000
+}
 
 // Session storage defaults
 fn default_cleanup_interval() -> u64 {
@@ -369,9 +608,24 @@ fn default_redis_connection_timeout() -> u64 {
 fn default_redis_command_timeout() -> u64 {
     3000
 } // 3 seconds
+fn default_memcached_pool_size() -> u32 {
+    10
+}
 fn default_sessions_table() -> String {
     "sessions".to_string()
 }
+fn default_audit_path() -> String {
+    "logs/audit.log".to_string()
+}
+fn default_audit_table() -> String {
+    "audit_log".to_string()
+}
+fn default_locale() -> String {
+    "en".to_string()
+}
+fn default_locale_param() -> String {
+    "locale".to_string()
+}
 
 impl Default for ServerConfig {
     fn default() -> Self {
@@ -384,6 +638,12 @@ impl Default for ServerConfig {
             ssl_key: None,
             max_connections: default_max_connections(),
             shutdown_timeout: default_shutdown_timeout(),
+            catch_panics: default_catch_panics(),
+            server_timing: false,
+            max_concurrent_requests: 0,
+            overload_retry_after: default_overload_retry_after(),
+            overload_excluded_paths: default_overload_excluded_paths(),
+            max_body_size: default_max_body_size(),
         }
     }
 }
@@ -411,6 +671,7 @@ impl Default for SessionConfig {
             absolute_timeout: default_absolute_timeout(),
             same_site: default_same_site(),
             fingerprint_mode: default_fingerprint_mode(),
+            same_site_none: SameSiteNoneMode::default(),
             storage: SessionStorageConfig::default(),
             exempt_routes: Vec::new(),
         }
@@ -424,6 +685,7 @@ impl Default for StaticConfig {
             url_prefix: default_static_prefix(),
             cache_enabled: true,
             cache_max_age: default_cache_max_age(),
+            precompressed: default_precompressed(),
         }
     }
 }
@@ -466,6 +728,28 @@ impl Default for UploadConfig {
     }
 }
 
+impl Default for JsonConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: default_json_max_depth(),
+            max_elements: default_json_max_elements(),
+            numbers: JsonNumbers::default(),
+        }
+    }
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            locales: Vec::new(),
+            default_locale: default_locale(),
+            query_param: default_locale_param(),
+            cookie: default_locale_param(),
+            session_key: default_locale_param(),
+        }
+    }
+}
+
 impl AppConfig {
     /// Load configuration with environment-specific overrides
     pub fn load() -> Result<Self> {
@@ -509,6 +793,9 @@ impl AppConfig {
                 .unwrap_or(toml::Value::Table(toml::map::Map::new()))
         };
 
+        // Apply the active `[profile]` section of config.toml
+        merged_value = Self::apply_profile(merged_value, &Self::active_profile()?)?;
+
         // Load and merge environment-specific configuration if it exists
         let env_config_path = base_dir.join(format!("config.{}.toml", env.as_str()));
         if env_config_path.exists() {
@@ -573,25 +860,27 @@ impl AppConfig {
     }
 
     /// Load configuration from TOML file
+    ///
+    /// The profile selected by `RUSTF_ENV` (see [`AppConfig::active_profile`])
+    /// is merged over the base sections, then environment variable overrides
+    /// are applied.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path_ref = path.as_ref();
 
-        // Enhanced error message with path information
-        let content = fs::read_to_string(path_ref).map_err(|e| {
-            Error::internal(format!(
-                "Failed to read config file '{}': {}. Make sure the file exists and is readable.",
-                path_ref.display(),
-                e
-            ))
-        })?;
+        let profile = Self::active_profile()?;
+        let value = Self::apply_profile(Self::load_toml_value(path_ref)?, &profile)?;
 
-        let mut config: AppConfig = toml::from_str(&content).map_err(|e| {
+        let mut config: AppConfig = value.try_into().map_err(|e| {
             Error::internal(format!(
-                "Failed to parse config file '{}': {}. Check TOML syntax.",
+                "Invalid configuration in '{}': {}",
                 path_ref.display(),
                 e
             ))
         })?;
+        if env::var("RUSTF_ENV").is_ok() {
+            config.environment = Environment::from_str(&profile);
+        }
+        config.apply_env_overrides()?;
 
         // Resolve paths relative to the config file's directory
         if let Some(parent_dir) = path_ref.parent() {
@@ -637,6 +926,95 @@ impl AppConfig {
         Ok(value)
     }
 
+    /// Name of the `config.toml` profile to apply
+    ///
+    /// Taken from `RUSTF_ENV` (`prod` and `dev` are accepted as aliases), or
+    /// from [`AppConfig::detect_environment`] when it is not set. Names other
+    /// than those in [`CONFIG_PROFILES`] are an error rather than silently
+    /// falling back to development.
+    pub fn active_profile() -> Result<String> {
+        match env::var("RUSTF_ENV") {
+            Ok(name) => Self::profile_name(&name),
+            Err(_) => Ok(match Self::detect_environment() {
+                Environment::Production => "production",
+                Environment::Development => "development",
+                Environment::Staging => "staging",
+                Environment::Test => "test",
+            }
+            .to_string()),
+        }
+    }
+
+    fn profile_name(name: &str) -> Result<String> {
+        let name = match name.trim().to_lowercase().as_str() {
+            "prod" => "production".to_string(),
+            "dev" => "development".to_string(),
+            other => other.to_string(),
+        };
+        if CONFIG_PROFILES.contains(&name.as_str()) {
+            Ok(name)
+        } else {
+            Err(Error::internal(format!(
+                "Unknown configuration profile '{}' in RUSTF_ENV (expected one of: {})",
+                name,
+                CONFIG_PROFILES.join(", ")
+            )))
+        }
+    }
+
+    /// Merge the `[<profile>]` table over the rest of the file
+    ///
+    /// A table named after a profile is one only when it overrides sections:
+    /// built-in ones or those of the base file. Profile tables are removed,
+    /// so inactive profiles never end up as custom sections, while a custom
+    /// `[test]` section with keys of its own is kept as is.
+    fn apply_profile(value: toml::Value, profile: &str) -> Result<toml::Value> {
+        let toml::Value::Table(mut base) = value else {
+            return Ok(value);
+        };
+
+        let builtin = match serde_json::to_value(AppConfig::default()) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        let overrides_sections = |table: &toml::map::Map<String, toml::Value>| {
+            table.keys().all(|key| {
+                builtin.contains_key(key)
+                    || (base.contains_key(key) && !CONFIG_PROFILES.contains(&key.as_str()))
+            })
+        };
+        let profiles: Vec<&str> = CONFIG_PROFILES
+            .iter()
+            .copied()
+            .filter(|name| match base.get(*name) {
+                Some(toml::Value::Table(table)) => overrides_sections(table),
+                _ => false,
+            })
+            .collect();
+
+        let mut active = None;
+        for name in profiles {
+            if let Some(toml::Value::Table(table)) = base.remove(name) {
+                if name == profile {
+                    active = Some(table);
+                }
+            }
+        }
+
+        match active {
+            Some(overrides) => {
+                serde_toml_merge::merge(toml::Value::Table(base), toml::Value::Table(overrides))
+                    .map_err(|e| {
+                        Error::internal(format!(
+                            "Failed to merge configuration profile '{}': {}",
+                            profile, e
+                        ))
+                    })
+            }
+            None => Ok(toml::Value::Table(base)),
+        }
+    }
+
     /// Create configuration with environment variable overrides
     pub fn from_env() -> Result<Self> {
         let mut config = AppConfig::default();
@@ -719,6 +1097,17 @@ impl AppConfig {
             }
         }
 
+        // The session cookie is only Secure in production
+        if self.session.enabled
+            && self.session.same_site.eq_ignore_ascii_case("none")
+            && self.session.same_site_none == SameSiteNoneMode::Error
+            && !self.environment.is_production()
+        {
+            return Err(Error::internal(
+                "session.same_site = \"None\" requires a Secure cookie, which is only set in production",
+            ));
+        }
+
         // Validate production-specific requirements
         if self.environment.is_production() && self.database.url.is_none() {
             eprintln!("WARNING: No database configured in production environment");
@@ -755,6 +1144,11 @@ impl AppConfig {
             self.environment = Environment::from_str(&env);
         }
 
+        // Application secret override
+        if let Ok(secret) = env::var("RUSTF_SECRET") {
+            self.secret = Some(secret);
+        }
+
         // Server overrides
         if let Ok(host) = env::var("RUSTF_HOST") {
             self.server.host = host;
@@ -785,6 +1179,11 @@ impl AppConfig {
                 .parse()
                 .map_err(|_| Error::internal("Invalid RUSTF_MAX_CONNECTIONS value"))?;
         }
+        if let Ok(max_body_size) = env::var("RUSTF_MAX_BODY_SIZE") {
+            self.server.max_body_size = max_body_size
+                .parse()
+                .map_err(|_| Error::internal("Invalid RUSTF_MAX_BODY_SIZE value"))?;
+        }
 
         // View overrides
         if let Ok(views_dir) = env::var("RUSTF_VIEWS_DIR") {
@@ -851,6 +1250,11 @@ impl AppConfig {
                     .map_err(|_| Error::internal("Invalid RUSTF_DB_TIMEOUT value"))?,
             );
         }
+        if let Ok(slow_query_ms) = env::var("RUSTF_DB_SLOW_QUERY_MS") {
+            self.database.slow_query_ms = slow_query_ms
+                .parse()
+                .map_err(|_| Error::internal("Invalid RUSTF_DB_SLOW_QUERY_MS value"))?;
+        }
 
         // Static files overrides
         if let Ok(static_dir) = env::var("RUSTF_STATIC_DIR") {
@@ -869,6 +1273,11 @@ impl AppConfig {
                 .parse()
                 .map_err(|_| Error::internal("Invalid RUSTF_STATIC_MAX_AGE value"))?;
         }
+        if let Ok(precompressed) = env::var("RUSTF_STATIC_PRECOMPRESSED") {
+            self.static_files.precompressed = precompressed
+                .parse()
+                .map_err(|_| Error::internal("Invalid RUSTF_STATIC_PRECOMPRESSED value"))?;
+        }
 
         // CORS overrides
         if let Ok(cors) = env::var("RUSTF_CORS_ENABLED") {
@@ -912,6 +1321,29 @@ impl AppConfig {
                 .map_err(|_| Error::internal("Invalid RUSTF_MAX_FILES value"))?;
         }
 
+        // JSON body limit overrides
+        if let Ok(max_depth) = env::var("RUSTF_JSON_MAX_DEPTH") {
+            self.json.max_depth = max_depth
+                .parse()
+                .map_err(|_| Error::internal("Invalid RUSTF_JSON_MAX_DEPTH value"))?;
+        }
+        if let Ok(max_elements) = env::var("RUSTF_JSON_MAX_ELEMENTS") {
+            self.json.max_elements = max_elements
+                .parse()
+                .map_err(|_| Error::internal("Invalid RUSTF_JSON_MAX_ELEMENTS value"))?;
+        }
+        if let Ok(numbers) = env::var("RUSTF_JSON_NUMBERS") {
+            self.json.numbers =
+                match numbers.to_lowercase().as_str() {
+                    "lossy" => JsonNumbers::Lossy,
+                    "strict" => JsonNumbers::Strict,
+                    "preserve" => JsonNumbers::Preserve,
+                    _ => return Err(Error::internal(
+                        "Invalid RUSTF_JSON_NUMBERS value. Use 'lossy', 'strict' or 'preserve'.",
+                    )),
+                };
+        }
+
         Ok(())
     }
 
@@ -1273,6 +1705,77 @@ debug = true
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    #[test]
+    fn test_profile_overrides_base_sections() {
+        let value: toml::Value = toml::from_str(
+            r#"
+[server]
+host = "127.0.0.1"
+port = 8000
+
+[production.server]
+host = "0.0.0.0"
+
+[test]
+database = { url = "sqlite::memory:" }
+"#,
+        )
+        .unwrap();
+
+        let production = AppConfig::apply_profile(value.clone(), "production").unwrap();
+        assert_eq!(production["server"]["host"].as_str(), Some("0.0.0.0"));
+        assert_eq!(production["server"]["port"].as_integer(), Some(8000));
+        assert!(production.get("production").is_none());
+        assert!(production.get("test").is_none());
+
+        let config: AppConfig = AppConfig::apply_profile(value, "development")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert!(config.sections.is_empty());
+    }
+
+    #[test]
+    fn test_profile_names() {
+        assert_eq!(AppConfig::profile_name("prod").unwrap(), "production");
+        assert_eq!(AppConfig::profile_name("Test").unwrap(), "test");
+        assert!(AppConfig::profile_name("qa").is_err());
+
+        assert_eq!(Environment::from_str("staging"), Environment::Staging);
+        assert_eq!(Environment::from_str("test"), Environment::Test);
+        assert!(!Environment::Staging.is_production());
+    }
+
+    #[test]
+    fn test_custom_section_named_like_a_profile_is_kept() {
+        let value: toml::Value = toml::from_str(
+            r#"
+[payment]
+provider = "stripe"
+
+[staging.payment]
+provider = "sandbox"
+
+[test]
+api_url = "http://localhost:9000"
+"#,
+        )
+        .unwrap();
+
+        let staging = AppConfig::apply_profile(value.clone(), "staging").unwrap();
+        assert_eq!(staging["payment"]["provider"].as_str(), Some("sandbox"));
+        assert!(staging.get("staging").is_none());
+        assert_eq!(
+            staging["test"]["api_url"].as_str(),
+            Some("http://localhost:9000")
+        );
+
+        let test = AppConfig::apply_profile(value, "test").unwrap();
+        assert_eq!(test["payment"]["provider"].as_str(), Some("stripe"));
+        assert!(test.get("test").is_some());
+    }
+
     #[test]
     fn test_custom_sections_with_toml_loading() {
         // Test that custom sections are properly loaded and accessible
@@ -26,10 +26,20 @@
 //!     // Database is configured
 //! }
 //! ```
+//!
+//! # Environment overlay
+//!
+//! Every path can be overridden by an environment variable named after it:
+//! `RUSTF_` followed by the path in upper case, with `.` and `-` replaced by
+//! `_`. `my-section.api_endpoint` is read from `RUSTF_MY_SECTION_API_ENDPOINT`
+//! first and only falls back to the file when the variable is unset. Values
+//! are parsed as JSON when possible (`8080`, `true`, `["a", "b"]`), then as a
+//! plain string, then as a comma-separated list.
 
 use crate::config::AppConfig;
 use crate::error::{Error, Result};
 use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -86,7 +96,10 @@ impl CONF {
     /// Get a configuration value by dot-notation path
     ///
     /// Returns the value at the specified path, or None if the path doesn't exist.
-    /// The type T must implement Deserialize.
+    /// The type T must implement Deserialize. The matching `RUSTF_*` environment
+    /// variable takes precedence over the file. A value of the wrong type is
+    /// logged and treated as missing; use [`try_get`](Self::try_get) to handle
+    /// the error instead.
     ///
     /// # Arguments
     /// * `path` - Dot-separated path to the configuration value
@@ -96,10 +109,57 @@ impl CONF {
     /// let port: Option<u16> = CONF::get("server.port");
     /// let db_url: Option<String> = CONF::get("database.url");
     /// ```
-    pub fn get<T: serde::de::DeserializeOwned>(path: &str) -> Option<T> {
-        let store = CONFIG.get()?;
-        let value = Self::get_nested(&store.json, path)?;
-        serde_json::from_value(value.clone()).ok()
+    pub fn get<T: DeserializeOwned>(path: &str) -> Option<T> {
+        Self::try_get(path).unwrap_or_else(|e| {
+            log::warn!("{}", e);
+            None
+        })
+    }
+
+    /// Get a configuration value, failing if it has the wrong type
+    ///
+    /// Like [`get`](Self::get), but a value that cannot be deserialized into
+    /// `T` is returned as an error naming the path (or environment variable)
+    /// and the expected type.
+    ///
+    /// # Returns
+    /// * `Ok(Some(value))` - The value exists and has the right type
+    /// * `Ok(None)` - Neither the environment nor the file sets the path
+    /// * `Err(Error::Internal)` - The value exists but is not a valid `T`
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let workers: usize = CONF::try_get("jobs.workers")?.unwrap_or(4);
+    /// ```
+    pub fn try_get<T: DeserializeOwned>(path: &str) -> Result<Option<T>> {
+        if let Some((name, raw)) = Self::env_override(path) {
+            return Self::parse_env_value(&raw).map(Some).map_err(|e| {
+                Error::internal(format!(
+                    "Environment variable {} (overriding '{}') is not a valid {}: {}",
+                    name,
+                    path,
+                    std::any::type_name::<T>(),
+                    e
+                ))
+            });
+        }
+
+        let Some(value) = CONFIG
+            .get()
+            .and_then(|store| Self::get_nested(&store.json, path))
+        else {
+            return Ok(None);
+        };
+        serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| {
+                Error::internal(format!(
+                    "Configuration value '{}' is not a valid {}: {}",
+                    path,
+                    std::any::type_name::<T>(),
+                    e
+                ))
+            })
     }
 
     /// Get a string configuration value
@@ -132,13 +192,7 @@ impl CONF {
     /// let max_connections = CONF::get_int("server.max_connections");
     /// ```
     pub fn get_int(path: &str) -> Option<i64> {
-        let store = CONFIG.get()?;
-        let value = Self::get_nested(&store.json, path)?;
-
-        match value {
-            Value::Number(n) => n.as_i64().or_else(|| n.as_u64().map(|u| u as i64)),
-            _ => None,
-        }
+        Self::get(path)
     }
 
     /// Get a boolean configuration value
@@ -169,13 +223,7 @@ impl CONF {
     /// let rate = CONF::get_float("custom.exchange_rate");
     /// ```
     pub fn get_float(path: &str) -> Option<f64> {
-        let store = CONFIG.get()?;
-        let value = Self::get_nested(&store.json, path)?;
-
-        match value {
-            Value::Number(n) => n.as_f64(),
-            _ => None,
-        }
+        Self::get(path)
     }
 
     /// Get a configuration value with a default
@@ -191,7 +239,7 @@ impl CONF {
     /// let port = CONF::get_or("server.port", 8000);
     /// let timeout = CONF::get_or("server.timeout", 30);
     /// ```
-    pub fn get_or<T: serde::de::DeserializeOwned>(path: &str, default: T) -> T {
+    pub fn get_or<T: DeserializeOwned>(path: &str, default: T) -> T {
         Self::get(path).unwrap_or(default)
     }
 
@@ -209,10 +257,11 @@ impl CONF {
     /// }
     /// ```
     pub fn has(path: &str) -> bool {
-        CONFIG
-            .get()
-            .and_then(|store| Self::get_nested(&store.json, path))
-            .is_some()
+        Self::env_override(path).is_some()
+            || CONFIG
+                .get()
+                .and_then(|store| Self::get_nested(&store.json, path))
+                .is_some()
     }
 
     /// Get the entire configuration
@@ -283,6 +332,37 @@ impl CONF {
         CONFIG.get().is_some()
     }
 
+    /// Name of the environment variable that overrides `path`
+    fn env_var_name(path: &str) -> String {
+        format!("RUSTF_{}", path.to_uppercase().replace(['.', '-'], "_"))
+    }
+
+    /// Read the environment override for `path`, if set
+    fn env_override(path: &str) -> Option<(String, String)> {
+        let name = Self::env_var_name(path);
+        std::env::var(&name).ok().map(|value| (name, value))
+    }
+
+    /// Parse an environment value as JSON, then as a string, then as a list
+    fn parse_env_value<T: DeserializeOwned>(raw: &str) -> serde_json::Result<T> {
+        if let Ok(value) = serde_json::from_str::<Value>(raw) {
+            if let Ok(parsed) = serde_json::from_value(value) {
+                return Ok(parsed);
+            }
+        }
+
+        let as_string = serde_json::from_value(Value::String(raw.to_string()));
+        if as_string.is_ok() || !raw.contains(',') {
+            return as_string;
+        }
+
+        let items = raw
+            .split(',')
+            .map(|item| Value::String(item.trim().to_string()))
+            .collect();
+        serde_json::from_value(Value::Array(items)).or(as_string)
+    }
+
     /// Internal helper to get nested values from JSON using dot notation
     fn get_nested<'a>(obj: &'a Value, path: &str) -> Option<&'a Value> {
         let parts: Vec<&str> = path.split('.').collect();
@@ -346,4 +426,32 @@ mod tests {
         assert!(CONF::is_development());
         assert!(!CONF::is_production());
     }
+
+    #[test]
+    fn test_env_overlay() {
+        assert_eq!(
+            CONF::env_var_name("my-section.api_endpoint"),
+            "RUSTF_MY_SECTION_API_ENDPOINT"
+        );
+
+        std::env::set_var("RUSTF_OVERLAY_TEST_PORT", "9090");
+        std::env::set_var("RUSTF_OVERLAY_TEST_HOSTS", "a.example, b.example");
+        std::env::set_var("RUSTF_OVERLAY_TEST_NAME", "42");
+
+        assert_eq!(CONF::get::<u16>("overlay_test.port"), Some(9090));
+        assert_eq!(
+            CONF::get::<Vec<String>>("overlay_test.hosts"),
+            Some(vec!["a.example".to_string(), "b.example".to_string()])
+        );
+        assert_eq!(
+            CONF::get_string("overlay_test.name"),
+            Some("42".to_string())
+        );
+        assert!(CONF::has("overlay_test.port"));
+
+        let mismatch = CONF::try_get::<bool>("overlay_test.port").unwrap_err();
+        assert!(mismatch.to_string().contains("RUSTF_OVERLAY_TEST_PORT"));
+        assert_eq!(CONF::get::<bool>("overlay_test.port"), None);
+        assert_eq!(CONF::try_get::<u16>("overlay_test.missing").unwrap(), None);
+    }
 }
@@ -1,17 +1,24 @@
 use crate::error::{Error, Result};
+use crate::http::{headers, locale};
 use crate::http::{
-    BodyData, FileCollection, FormValue, Request, RequestData, Response, UploadedFile,
+    BodyData, ClientHints, CookieJar, CookieOptions, FileCollection, FormValue, JsonArrayWriter,
+    Request, RequestData, RequestTimings, Response, SignatureOptions, SseStream, TimingPhase,
+    UploadedFile,
 };
+use crate::security::static_files::{streamed_file_response, SecureStaticFileServer};
+use crate::security::{PathValidator, SecurityConfig};
 use crate::session::Session;
-use crate::views::ViewEngine;
+use crate::utils::pagination::PageMeta;
+use crate::views::{FormState, ViewEngine};
 use hyper::StatusCode;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use simd_json;
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 /// HTTP request context focused on request/response operations
 ///
@@ -32,6 +39,12 @@ pub struct Context {
     cached_form_data: Option<Result<HashMap<String, String>>>,
     /// Cached form data with array support
     cached_form_data_arrays: Option<Result<HashMap<String, FormValue>>>,
+    /// Phase timings, only collected when `server.server_timing` is on
+    timings: Option<RequestTimings>,
+    /// Locale detected on first use or set by the handler
+    locale: OnceLock<String>,
+    /// Time the route handler has, and the status answered when it runs out
+    handler_timeout: Option<(Duration, StatusCode)>,
 }
 
 // Context is automatically Send + Sync due to Arc<T> being Send + Sync
@@ -56,6 +69,46 @@ impl Context {
             data: HashMap::new(),
             cached_form_data: None,
             cached_form_data_arrays: None,
+            timings: None,
+            locale: OnceLock::new(),
+            handler_timeout: None,
+        }
+    }
+
+    /// Time spent so far in each phase of this request
+    ///
+    /// `None` unless `server.server_timing` is enabled in the configuration.
+    pub fn timings(&self) -> Option<&RequestTimings> {
+        self.timings.as_ref()
+    }
+
+    /// Start collecting phase timings for this request
+    pub(crate) fn enable_timings(&mut self) {
+        self.timings = Some(RequestTimings::start());
+    }
+
+    /// Give up on the route handler after `timeout`, answering `status`
+    ///
+    /// Set by `TimeoutMiddleware`; the application enforces it when it runs
+    /// the handler.
+    pub fn set_handler_timeout(&mut self, timeout: Duration, status: StatusCode) {
+        self.handler_timeout = Some((timeout, status));
+    }
+
+    /// Time the route handler has, and the status answered when it runs out
+    pub fn handler_timeout(&self) -> Option<(Duration, StatusCode)> {
+        self.handler_timeout
+    }
+
+    /// Start of a timed phase, or `None` when timings are off
+    pub(crate) fn timing_clock(&self) -> Option<Instant> {
+        self.timings.as_ref().map(|_| Instant::now())
+    }
+
+    /// Add the time since `clock` to `phase`
+    pub(crate) fn record_timing(&mut self, phase: TimingPhase, clock: Option<Instant>) {
+        if let (Some(timings), Some(clock)) = (self.timings.as_mut(), clock) {
+            timings.record(phase, clock.elapsed());
         }
     }
 
@@ -199,6 +252,28 @@ impl Context {
         Ok(())
     }
 
+    /// Record an audit entry for this request
+    ///
+    /// The actor is the one set with `audit::set_actor`, or else the session
+    /// user id. Fails if no audit log is installed.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ctx.audit("user.promote", &format!("users:{}", id), json!({ "role": "admin" }))
+    ///     .await?;
+    /// ```
+    pub async fn audit(&self, action: &str, target: &str, metadata: Value) -> Result<()> {
+        let actor = crate::audit::current_actor().or_else(|| {
+            self.session()
+                .and_then(|session| session.get_user_id())
+                .map(|id| id.to_string())
+        });
+        crate::audit::record_entry(crate::audit::AuditEntry::new(
+            actor, action, target, metadata,
+        ))
+        .await
+    }
+
     // Flash message helpers (stored in session)
 
     /// Set flash message
@@ -256,6 +331,11 @@ impl Context {
 
     /// Render a view template with data - now memory safe
     pub fn view(&mut self, template: &str, data: Value) -> Result<()> {
+        self.render_view(template, data, None)
+    }
+
+    /// Render a view, with the state of a redrawn form when there is one
+    fn render_view(&mut self, template: &str, data: Value, form: Option<&FormState>) -> Result<()> {
         // Safe reference access - no unsafe code needed
         let views = &self.views;
 
@@ -300,31 +380,73 @@ impl Context {
             Value::Null
         };
 
+        let locale = self.locale().to_string();
+
         // Pass all context data to view engine through special internal fields
         if let Value::Object(ref mut map) = final_data {
             // Add the context repository data for templates to access
             map.insert("_context_repository".to_string(), repository_value);
             // Add session data for templates to access
             map.insert("_context_session".to_string(), session_value);
+            // Locale the translations are looked up in
+            map.insert("_context_locale".to_string(), Value::String(locale));
         } else {
             // Wrap non-object data
             final_data = serde_json::json!({
                 "data": final_data,
                 "_context_repository": repository_value,
-                "_context_session": session_value
+                "_context_session": session_value,
+                "_context_locale": locale
             });
         }
 
-        let rendered = views.render(template, &final_data, self.layout_name.as_deref())?;
+        let clock = self.timing_clock();
+        let layout = self.layout_name.as_deref();
+        let rendered = match form {
+            Some(form) => views.render_form(template, &final_data, layout, form)?,
+            None => views.render(template, &final_data, layout)?,
+        };
+        self.record_timing(TimingPhase::View, clock);
         self.update_response_body(rendered.into_bytes(), "text/html; charset=utf-8", None);
         Ok(())
     }
 
     /// Redirect to another URL
     pub fn redirect(&mut self, path: &str) -> Result<()> {
+        self.redirect_with_status(path, StatusCode::FOUND)
+    }
+
+    /// Store a flash message and redirect with `303 See Other` (Post-Redirect-Get)
+    pub fn redirect_with_flash(
+        &mut self,
+        path: &str,
+        key: &str,
+        value: impl serde::Serialize,
+    ) -> Result<()> {
+        self.flash(key, value)?;
+        self.redirect_with_status(path, StatusCode::SEE_OTHER)
+    }
+
+    /// Redirect to the referring page, or to `fallback` if there is none
+    ///
+    /// Only same-origin referrers are followed, so a crafted `Referer` header
+    /// cannot turn this into an open redirect.
+    pub fn back(&mut self, fallback: &str) -> Result<()> {
+        let target = self
+            .req
+            .referrer()
+            .zip(self.req.host())
+            .and_then(|(referer, host)| same_origin_path(referer, host))
+            .unwrap_or_else(|| fallback.to_string());
+
+        self.redirect(&target)
+    }
+
+    /// Redirect with a specific 3xx status
+    fn redirect_with_status(&mut self, path: &str, status: StatusCode) -> Result<()> {
         // For redirects, we need to preserve headers but also set Location and status
         let response = self.res.as_mut().unwrap();
-        response.status = StatusCode::FOUND;
+        response.status = status;
         response.body = Vec::new();
 
         // Remove any existing Location header
@@ -456,6 +578,80 @@ impl Context {
         Ok(())
     }
 
+    /// Answer `304 Not Modified` if the client's copy matches `etag`
+    ///
+    /// The closure computes a cheap validator for the resource, e.g. from an
+    /// `updated_at` timestamp. When the request's `If-None-Match` matches it
+    /// the response becomes a 304 and this returns `true`: the handler should
+    /// return without doing the expensive work. Otherwise the `ETag` header
+    /// is set and the handler renders the response as usual.
+    ///
+    /// ```rust,ignore
+    /// let post = Posts::find(id).await?;
+    /// if ctx.etag_cache(|| post.updated_at.timestamp().to_string()) {
+    ///     return Ok(());
+    /// }
+    /// ctx.view("posts/show", render_post(&post).await?)
+    /// ```
+    ///
+    /// Only `GET` and `HEAD` requests are answered with a 304.
+    pub fn etag_cache<F, S>(&mut self, etag: F) -> bool
+    where
+        F: FnOnce() -> S,
+        S: AsRef<str>,
+    {
+        let etag = headers::entity_tag(etag().as_ref());
+        let not_modified = self.is_cacheable_method()
+            && self
+                .req
+                .headers
+                .get("if-none-match")
+                .is_some_and(|header| headers::if_none_match(header, &etag));
+
+        self.conditional_response(not_modified, "ETag", etag)
+    }
+
+    /// Answer `304 Not Modified` if the resource hasn't changed since the client's copy
+    ///
+    /// The `Last-Modified` counterpart of [`etag_cache`](Self::etag_cache),
+    /// checked against `If-Modified-Since` with one second precision. When
+    /// both are used, a request carrying `If-None-Match` is judged by the
+    /// entity tag alone.
+    pub fn last_modified_cache<F>(&mut self, modified: F) -> bool
+    where
+        F: FnOnce() -> chrono::DateTime<chrono::Utc>,
+    {
+        let modified = modified().timestamp().max(0) as u64;
+        let not_modified = self.is_cacheable_method()
+            && !self.req.headers.contains_key("if-none-match")
+            && self
+                .req
+                .headers
+                .get("if-modified-since")
+                .and_then(|date| SecureStaticFileServer::parse_http_date(date))
+                .is_some_and(|since| modified <= since);
+
+        let last_modified = SecureStaticFileServer::format_http_date(modified);
+        self.conditional_response(not_modified, "Last-Modified", last_modified)
+    }
+
+    fn is_cacheable_method(&self) -> bool {
+        matches!(self.req.method.as_str(), "GET" | "HEAD")
+    }
+
+    /// Set the validator header, and the 304 when the client's copy is current
+    fn conditional_response(&mut self, not_modified: bool, header: &str, value: String) -> bool {
+        if not_modified {
+            self.update_response(Response::not_modified().with_header(header, &value));
+        } else if let Some(response) = self.res.as_mut() {
+            response
+                .headers
+                .retain(|(name, _)| !name.eq_ignore_ascii_case(header));
+            response.add_header(header, &value);
+        }
+        not_modified
+    }
+
     // Total.js-style client information properties
 
     /// Get client IP address (Total.js: controller.ip)
@@ -468,6 +664,19 @@ impl Context {
         self.req.user_agent()
     }
 
+    /// Browser, platform and device of the client
+    ///
+    /// Read from the `Sec-CH-UA*` client hints when sent, with the parsed
+    /// `User-Agent` filling the gaps:
+    ///
+    /// ```rust,ignore
+    /// let hints = ctx.client_hints();
+    /// let layout = if hints.mobile { "layouts/mobile" } else { "layouts/main" };
+    /// ```
+    pub fn client_hints(&self) -> ClientHints {
+        self.req.client_hints()
+    }
+
     /// Check if request is from mobile device (Total.js: controller.mobile)
     pub fn is_mobile(&self) -> bool {
         self.req.is_mobile()
@@ -493,6 +702,50 @@ impl Context {
         self.req.language()
     }
 
+    /// Locale of this request, e.g. `fr-FR`
+    ///
+    /// Detected on first use from the query parameter, cookie and session
+    /// value named in the `[i18n]` configuration, then `Accept-Language`,
+    /// and limited to `i18n.locales`. Views translate with this locale.
+    pub fn locale(&self) -> &str {
+        self.locale.get_or_init(|| {
+            let config = crate::configuration::CONF::all()
+                .map(|config| config.i18n.clone())
+                .unwrap_or_default();
+            let cookie = self.req.cookie(&config.cookie);
+            let session: Option<String> = self.session_get(&config.session_key);
+            locale::resolve(
+                &config,
+                [
+                    self.query(&config.query_param),
+                    cookie.as_deref(),
+                    session.as_deref(),
+                ],
+                self.req.accept_languages(),
+            )
+        })
+    }
+
+    /// Switch this request to `locale` and remember it in the session
+    ///
+    /// Fails with `Error::InvalidInput` if the locale is not one of
+    /// `i18n.locales`.
+    pub fn set_locale(&mut self, locale: &str) -> Result<()> {
+        let config = crate::configuration::CONF::all()
+            .map(|config| config.i18n.clone())
+            .unwrap_or_default();
+        let available = locale::available_locales(&config);
+        let locale = locale::negotiate(locale, &available)
+            .ok_or_else(|| Error::InvalidInput(format!("Locale '{}' is not available", locale)))?
+            .to_string();
+
+        if let Some(session) = self.session() {
+            session.set(&config.session_key, &locale)?;
+        }
+        self.locale = OnceLock::from(locale);
+        Ok(())
+    }
+
     /// Get HTTP referrer (Total.js: controller.referrer)
     pub fn referrer(&self) -> Option<&str> {
         self.req.referrer()
@@ -557,6 +810,151 @@ impl Context {
         Ok(())
     }
 
+    /// Stream a file from disk for inline display, with `Range` support
+    ///
+    /// The file is read while it is sent rather than loaded into memory, and
+    /// partial requests get `206 Partial Content`, so it suits media and large
+    /// downloads. `path` is used as given; when it is derived from request
+    /// input use [`stream_file_in`](Self::stream_file_in) instead.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// async fn video(ctx: &mut Context) -> Result<()> {
+    ///     ctx.require_auth()?;
+    ///     ctx.stream_file("storage/videos/intro.mp4").await
+    /// }
+    /// ```
+    pub async fn stream_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let response = streamed_file_response(&self.req, path.as_ref(), None).await?;
+        self.update_response(response);
+        Ok(())
+    }
+
+    /// Stream a file from disk as an attachment named `download_name`
+    pub async fn stream_download<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        download_name: &str,
+    ) -> Result<()> {
+        let response =
+            streamed_file_response(&self.req, path.as_ref(), Some(download_name)).await?;
+        self.update_response(response);
+        Ok(())
+    }
+
+    /// Stream `relative_path` from inside `base_dir` for inline display
+    ///
+    /// The path goes through `PathValidator`, so traversal (`../`), symlinks
+    /// leading outside `base_dir` and blocked extensions such as executables
+    /// and scripts are answered with 404.
+    pub async fn stream_file_in<P: AsRef<Path>>(
+        &mut self,
+        base_dir: P,
+        relative_path: &str,
+    ) -> Result<()> {
+        let config = SecurityConfig {
+            allowed_extensions: HashSet::new(),
+            ..SecurityConfig::default()
+        };
+        let path = PathValidator::new(base_dir, &config)?
+            .validate_path(relative_path)
+            .map_err(|e| {
+                log::warn!("Rejected file path '{}': {}", relative_path, e);
+                Error::RouteNotFound(format!("File not found: {}", relative_path))
+            })?;
+        self.stream_file(path).await
+    }
+
+    /// Stream a body to the client chunk by chunk
+    ///
+    /// Each chunk is sent as soon as `chunks` yields it, so the body can be
+    /// larger than memory or produced slowly. Use [`stream_file`](Self::stream_file)
+    /// for files on disk.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// async fn report(ctx: &mut Context) -> Result<()> {
+    ///     let lines = futures::stream::iter(1..=3)
+    ///         .map(|n| Ok(Bytes::from(format!("line {}\n", n))));
+    ///     ctx.stream_body("text/plain; charset=utf-8", lines)
+    /// }
+    /// ```
+    pub fn stream_body<S>(&mut self, content_type: &str, chunks: S) -> Result<()>
+    where
+        S: futures::Stream<Item = Result<hyper::body::Bytes>> + Send + 'static,
+    {
+        self.update_response(Response::chunked(content_type, chunks));
+        Ok(())
+    }
+
+    /// Stream Server-Sent Events to the client
+    ///
+    /// The response stays open until `events` completes; idle periods are
+    /// filled with keep-alive comments every 15 seconds.
+    pub fn sse<S>(&mut self, events: S) -> Result<()>
+    where
+        S: futures::Stream<Item = crate::http::SseEvent> + Send + 'static,
+    {
+        self.sse_with_keep_alive(events, Some(crate::http::sse::DEFAULT_KEEP_ALIVE))
+    }
+
+    /// Stream Server-Sent Events with a custom keep-alive interval (`None` disables it)
+    pub fn sse_with_keep_alive<S>(
+        &mut self,
+        events: S,
+        keep_alive: Option<std::time::Duration>,
+    ) -> Result<()>
+    where
+        S: futures::Stream<Item = crate::http::SseEvent> + Send + 'static,
+    {
+        self.update_response(Response::sse(events, keep_alive));
+        Ok(())
+    }
+
+    /// Open a Server-Sent Events response and return the handle that feeds it
+    ///
+    /// The handle is usually moved into a spawned task; the response is sent
+    /// as soon as the handler returns and stays open until every handle is
+    /// dropped. Idle periods are filled with keep-alive comments every 15
+    /// seconds.
+    pub fn sse_stream(&mut self) -> SseStream {
+        self.sse_stream_with_keep_alive(Some(crate::http::sse::DEFAULT_KEEP_ALIVE))
+    }
+
+    /// Open a Server-Sent Events response with a custom keep-alive interval (`None` disables it)
+    pub fn sse_stream_with_keep_alive(
+        &mut self,
+        keep_alive: Option<std::time::Duration>,
+    ) -> SseStream {
+        let (response, stream) = Response::sse_stream(keep_alive);
+        self.update_response(response);
+        stream
+    }
+
+    /// Stream `items` to the client as a JSON array
+    ///
+    /// Each element is written as soon as the stream yields it, e.g. rows of
+    /// a large export. An error from the stream cuts the body short.
+    pub fn json_stream<S, T>(&mut self, items: S) -> Result<()>
+    where
+        S: futures::Stream<Item = Result<T>> + Send + 'static,
+        T: serde::Serialize + Send + 'static,
+    {
+        self.update_response(Response::json_stream(items));
+        Ok(())
+    }
+
+    /// Respond with a JSON array filled through the returned writer
+    ///
+    /// The writer is usually moved into a spawned task; the response is sent
+    /// as soon as the handler returns and stays open until
+    /// [`JsonArrayWriter::finish`] is called or every writer is dropped.
+    pub fn json_array_writer(&mut self) -> JsonArrayWriter {
+        let (response, writer) = Response::json_array_writer();
+        self.update_response(response);
+        writer
+    }
+
     /// Set flash success message
     pub fn flash_success(&self, message: impl Into<String>) -> Result<()> {
         self.flash("success", message.into())
@@ -596,11 +994,35 @@ impl Context {
         self.req.params.get(key).map(|s| s.as_str())
     }
 
+    /// Get all URL parameters of the matched route
+    ///
+    /// A catch-all segment (`/files/{*path}`) appears as one entry holding
+    /// the remaining path, e.g. `"docs/2024/report.pdf"`.
+    pub fn param_map(&self) -> &HashMap<String, String> {
+        &self.req.params
+    }
+
     /// Get a query parameter
     pub fn query(&self, key: &str) -> Option<&str> {
         self.req.query.get(key).map(|s| s.as_str())
     }
 
+    /// Get every value of a repeated query parameter, in URL order
+    ///
+    /// `?tag=a&tag=b` (or `?tag[]=a&tag[]=b`, depending on
+    /// `query.array_syntax`) yields `["a", "b"]`.
+    pub fn query_all(&self, key: &str) -> Vec<&str> {
+        self.req.query_all(key)
+    }
+
+    /// Deserialize the query parameters into a typed structure
+    ///
+    /// Repeated keys fill `Vec<T>` fields; values are parsed into the field
+    /// types. Fails with `Error::InvalidInput` when a value does not parse.
+    pub fn query_into<T: DeserializeOwned>(&self) -> Result<T> {
+        self.req.query_into()
+    }
+
     // New typed query parameter methods
 
     /// Get a query parameter (returns error if missing)
@@ -660,6 +1082,32 @@ impl Context {
         })
     }
 
+    /// Get a route parameter parsed as `T` (returns error if missing or invalid)
+    ///
+    /// Both failures are `Error::InvalidInput`, answered with a 400 naming the
+    /// parameter and the expected type:
+    ///
+    /// ```rust,ignore
+    /// let id: i64 = ctx.param_parsed("id")?; // "/users/abc": id expected i64, got "abc"
+    /// ```
+    pub fn param_parsed<T: std::str::FromStr>(&self, key: &str) -> Result<T> {
+        let value = self.str_param(key)?;
+        value.parse().map_err(|_| {
+            let type_name = std::any::type_name::<T>();
+            Error::InvalidInput(format!(
+                "Route parameter '{}' expected {}, got \"{}\"",
+                key,
+                type_name.rsplit("::").next().unwrap_or(type_name),
+                value
+            ))
+        })
+    }
+
+    /// Get a route parameter as an owned string, if present
+    pub fn param_opt(&self, key: &str) -> Option<String> {
+        self.param(key).map(str::to_string)
+    }
+
     /// Get a route parameter with default
     pub fn str_param_or(&self, key: &str, default: &str) -> String {
         self.str_param(key).unwrap_or_else(|_| default.to_string())
@@ -670,6 +1118,27 @@ impl Context {
         self.int_param(key).unwrap_or(default)
     }
 
+    /// Get a route parameter parsed as `T`, or fail with a 404
+    ///
+    /// A missing or unparseable parameter means the URL names nothing that
+    /// exists, so `/users/abc` answers like any other unknown page:
+    ///
+    /// ```rust,ignore
+    /// let id = ctx.param_or_404::<i64>("id")?;
+    /// let user = Users::find_or_404(id).await?;
+    /// ```
+    pub fn param_or_404<T: std::str::FromStr>(&self, key: &str) -> Result<T> {
+        self.param(key)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| {
+                Error::RouteNotFound(format!(
+                    "{} (invalid route parameter '{}')",
+                    self.path(),
+                    key
+                ))
+            })
+    }
+
     /// Get request body as JSON
     pub fn body_json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
         self.req.body_as_json()
@@ -699,22 +1168,126 @@ impl Context {
 
     /// Parse form data into a typed structure
     pub fn body_form_typed<T: DeserializeOwned>(&mut self) -> Result<T> {
-        let form_data = self.body_form_data()?;
+        self.form_into()
+    }
 
-        // Convert HashMap<String, FormValue> to serde_json::Value for deserialization
-        let mut json_map = serde_json::Map::new();
-        for (key, value) in form_data {
-            let json_value = match value {
-                FormValue::Single(s) => serde_json::Value::String(s),
-                FormValue::Multiple(v) => {
-                    serde_json::Value::Array(v.into_iter().map(serde_json::Value::String).collect())
+    /// Deserialize the form body into a typed structure, nested fields included
+    ///
+    /// Bracketed names are rebuilt: `user[address][city]` fills a nested
+    /// struct, `items[0][name]` or `items[][name]` a `Vec` of structs, and
+    /// `tags[]` (or a repeated `tags`) a `Vec<String>`. Values are parsed into
+    /// the field types, as with `query_into`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// #[derive(Deserialize)]
+    /// struct LineItem { sku: String, quantity: u32 }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Order { email: String, items: Vec<LineItem> }
+    ///
+    /// // email=a@b.c&items[0][sku]=A1&items[0][quantity]=2
+    /// let order: Order = ctx.form_into()?;
+    /// ```
+    pub fn form_into<T: DeserializeOwned>(&self) -> Result<T> {
+        self.req.form_into()
+    }
+
+    /// Parse the body, check it with a registered validator and deserialize it
+    ///
+    /// Form bodies are validated as a JSON object of their fields; any other
+    /// content type is parsed as JSON. The validator may be sync or an
+    /// `AsyncValidator`, which is awaited. Failures are validation errors, which
+    /// the router answers with a 400 problem document. This is what the
+    /// `#[validate(body = "...")]` attribute calls before the handler runs.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let user: UserCreate = ctx.validated_body("UserCreate").await?;
+    /// ```
+    pub async fn validated_body<T: DeserializeOwned>(&mut self, validator: &str) -> Result<T> {
+        let content_type = self
+            .header("content-type")
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let value = if content_type.starts_with("application/x-www-form-urlencoded")
+            || content_type.starts_with("multipart/form-data")
+        {
+            self.body_form_value()?
+        } else if self.req.body_as_string().trim().is_empty() {
+            return Err(invalid_body("Request body is required", "required"));
+        } else {
+            self.req.body_as_json::<Value>().map_err(|e| match e {
+                Error::Internal(message) => invalid_body(message, "invalid_json"),
+                other => other,
+            })?
+        };
+
+        // Async validators may hit the database; release the definitions
+        // lock before awaiting them
+        let definitions = crate::definitions::get().await;
+        let async_validator = {
+            let definitions = definitions.read().await;
+            match definitions.validators.get_async(validator) {
+                Some(async_validator) => Some(async_validator),
+                None => {
+                    definitions.validators.validate(validator, &value, None)?;
+                    None
                 }
-            };
-            json_map.insert(key, json_value);
+            }
+        };
+        if let Some(async_validator) = async_validator {
+            async_validator.validate(&value, None).await?;
         }
 
-        let json_value = serde_json::Value::Object(json_map);
-        serde_json::from_value(json_value).map_err(Error::Json)
+        serde_json::from_value(value).map_err(|e| invalid_body(e.to_string(), "invalid_type"))
+    }
+
+    /// Validate the submitted form, or render `view` again with the errors
+    ///
+    /// Runs [`validated_body`](Self::validated_body). When validation fails,
+    /// the submitted values and errors go through a
+    /// [`FormBuilder`](crate::forms::FormBuilder), and `view` is rendered with
+    /// status 422 and its [`state`](crate::forms::FormBuilder::state): the
+    /// first error of each field as `errors` and the submitted values, secrets
+    /// left out, as `old`; a failure not tied to a field is in `errors.form`.
+    /// `None` then tells the handler the response is ready. Other data the
+    /// view needs is set in the repository beforehand.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let Some(signup) = ctx.validate_form_or_redraw::<Signup>("signup", "signup_view").await? else {
+    ///     return Ok(());
+    /// };
+    /// ```
+    ///
+    /// ```html
+    /// <input name="email" value="@{old.email}">
+    /// @{if errors.email}<span class="error">@{errors.email}</span>@{fi}
+    /// ```
+    pub async fn validate_form_or_redraw<T: DeserializeOwned>(
+        &mut self,
+        validator: &str,
+        view: &str,
+    ) -> Result<Option<T>> {
+        let error = match self.validated_body(validator).await {
+            Ok(value) => return Ok(Some(value)),
+            Err(error) if error.is_validation() => error,
+            Err(error) => return Err(error),
+        };
+
+        let action = self.path().to_string();
+        let method = self.req.method.clone();
+        let mut form = crate::forms::FormBuilder::new(&action, &method).fill(self)?;
+        form.add_validation_errors(&error);
+        self.render_view(view, serde_json::json!({}), Some(&form.state()?))?;
+        self.status(StatusCode::UNPROCESSABLE_ENTITY);
+        Ok(None)
+    }
+
+    /// Form fields as a JSON object, bracketed names nested, repeated fields as arrays
+    fn body_form_value(&mut self) -> Result<Value> {
+        crate::http::form::to_value(&self.req.body_as_form_pairs())
     }
 
     // New typed body field methods
@@ -783,11 +1356,17 @@ impl Context {
             let text = self.req.body_as_string();
             if text.is_empty() {
                 Ok(serde_json::Value::Null)
+            } else if serde_json::from_str::<serde::de::IgnoredAny>(&text).is_err() {
+                // Not JSON, return the text as a string value
+                Ok(serde_json::Value::String(text))
             } else {
-                // Try to parse as JSON using simd-json (2-3x faster), fallback to string value
-                let mut text_bytes = text.into_bytes();
+                // Accepted as JSON, so the JSON body limits apply
+                crate::http::json_limits::check_configured(text.as_bytes())?;
+                // Parse using simd-json (2-3x faster), fallback to string value
+                let mut text_bytes =
+                    crate::http::json_numbers::apply_configured(text.as_bytes())?.into_owned();
                 Ok(simd_json::from_slice(&mut text_bytes)
-                    .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&text_bytes).to_string())))
+                    .unwrap_or(serde_json::Value::String(text)))
             }
         }
     }
@@ -797,6 +1376,49 @@ impl Context {
         self.req.body_as_string()
     }
 
+    /// Get the body bytes exactly as received
+    ///
+    /// Parsing the body never consumes it, so this stays available to
+    /// handlers after signature checks or `body_json()`.
+    pub fn body_bytes(&self) -> &[u8] {
+        self.req.body_bytes()
+    }
+
+    /// Verify an HMAC-SHA256 webhook signature over the raw body
+    ///
+    /// `header_name` holds the signature, either bare (hex or base64,
+    /// optionally `sha256=`-prefixed) or as Stripe-style `t=...,v1=...`
+    /// parts. Signed timestamps older or newer than five minutes are
+    /// rejected. The comparison is constant-time.
+    ///
+    /// ```rust,ignore
+    /// if !ctx.verify_signature("X-Hub-Signature-256", &secret) {
+    ///     return ctx.throw401(Some("Invalid signature"));
+    /// }
+    /// let event: PushEvent = ctx.body_json()?;
+    /// ```
+    pub fn verify_signature(&self, header_name: &str, secret: &str) -> bool {
+        self.verify_signature_with(header_name, secret, &SignatureOptions::default())
+    }
+
+    /// Verify a webhook signature with a timestamp header or custom tolerance
+    pub fn verify_signature_with(
+        &self,
+        header_name: &str,
+        secret: &str,
+        options: &SignatureOptions,
+    ) -> bool {
+        // Request header names are stored lowercase
+        let Some(header) = self.header(&header_name.to_ascii_lowercase()) else {
+            return false;
+        };
+        let timestamp = options
+            .timestamp_header
+            .as_deref()
+            .and_then(|name| self.header(&name.to_ascii_lowercase()));
+        crate::http::signature::verify(header, self.body_bytes(), secret, timestamp, options)
+    }
+
     /// Helper function to convert form data to JSON
     fn form_to_json(form_data: &HashMap<String, FormValue>) -> serde_json::Value {
         let mut json_map = serde_json::Map::new();
@@ -911,6 +1533,18 @@ impl Context {
         Ok(())
     }
 
+    /// Return a page of items in the standard list envelope
+    ///
+    /// Responds with `{"data": [...], "meta": {"page", "per_page", "total",
+    /// "total_pages"}}`. `meta` usually comes from `ModelQuery::fetch_page`;
+    /// build it with `PageMeta::new(page, per_page, total)` otherwise.
+    pub fn json_paginated<T: serde::Serialize>(&mut self, items: T, meta: &PageMeta) -> Result<()> {
+        self.json(serde_json::json!({
+            "data": serde_json::to_value(items)?,
+            "meta": meta,
+        }))
+    }
+
     /// Return text response
     pub fn text(&mut self, content: impl Into<String>) -> Result<()> {
         self.update_response_body(
@@ -970,11 +1604,42 @@ impl Context {
 
     // Total.js compatibility convenience methods
 
+    /// Get all cookies sent with the request
+    pub fn cookies(&self) -> &CookieJar {
+        self.req.cookies()
+    }
+
     /// Get cookie value by name (Total.js: controller.cookie)
     pub fn cookie(&self, name: &str) -> Option<String> {
         self.req.cookie(name)
     }
 
+    /// Get a verified signed cookie value
+    pub fn signed_cookie(&self, name: &str) -> Option<String> {
+        self.req.signed_cookie(name)
+    }
+
+    /// Set a standalone cookie on the response
+    pub fn set_cookie(&mut self, name: &str, value: &str, options: &CookieOptions) -> Result<()> {
+        match self.res.as_mut() {
+            Some(response) => response.set_cookie(name, value, options),
+            None => Ok(()),
+        }
+    }
+
+    /// Set an HMAC-signed cookie on the response
+    pub fn set_signed_cookie(
+        &mut self,
+        name: &str,
+        value: &str,
+        options: &CookieOptions,
+    ) -> Result<()> {
+        match self.res.as_mut() {
+            Some(response) => response.set_signed_cookie(name, value, options),
+            None => Ok(()),
+        }
+    }
+
     /// Get host from Host header (Total.js: controller.host)
     pub fn host(&self) -> Option<&str> {
         self.req.host()
@@ -1153,6 +1818,36 @@ impl Context {
     }
 }
 
+/// Extract the path of a referrer URL if it points to `host`
+///
+/// Accepts absolute `http(s)://host/...` URLs and root-relative paths; anything
+/// else (other hosts, protocol-relative `//evil.com`, other schemes) is rejected.
+fn same_origin_path(referer: &str, host: &str) -> Option<String> {
+    let referer = referer.trim();
+
+    let path = if let Some(rest) = referer
+        .strip_prefix("https://")
+        .or_else(|| referer.strip_prefix("http://"))
+    {
+        let (authority, path) = match rest.find(['/', '?', '#']) {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        if !authority.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        path
+    } else {
+        referer
+    };
+
+    if !path.starts_with('/') || path.starts_with("//") || path.starts_with("/\\") {
+        return None;
+    }
+
+    Some(path.to_string())
+}
+
 /// Constant-time comparison to prevent timing attacks
 fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
@@ -1166,6 +1861,15 @@ fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     result == 0
 }
 
+/// Validation error for a request body that could not be read as expected
+fn invalid_body(message: impl Into<String>, code: &str) -> Error {
+    let message = message.into();
+    Error::validation_fields(
+        "Invalid request body",
+        vec![crate::error::FieldError::new("body", message, code)],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1188,6 +1892,79 @@ mod tests {
         Context::new(request, views)
     }
 
+    #[test]
+    fn test_same_origin_path() {
+        let host = "example.com";
+
+        assert_eq!(
+            same_origin_path("https://example.com/users?page=2", host),
+            Some("/users?page=2".to_string())
+        );
+        assert_eq!(
+            same_origin_path("http://example.com", host),
+            Some("/".to_string())
+        );
+        assert_eq!(
+            same_origin_path("/dashboard", host),
+            Some("/dashboard".to_string())
+        );
+
+        assert_eq!(same_origin_path("https://evil.com/users", host), None);
+        assert_eq!(
+            same_origin_path("https://example.com.evil.com/", host),
+            None
+        );
+        assert_eq!(same_origin_path("//evil.com/users", host), None);
+        assert_eq!(same_origin_path("javascript:alert(1)", host), None);
+    }
+
+    #[test]
+    fn test_back_uses_fallback_for_foreign_referer() {
+        let mut ctx = create_test_context();
+        ctx.req
+            .headers
+            .insert("host".to_string(), "example.com".to_string());
+        ctx.req
+            .headers
+            .insert("referer".to_string(), "https://evil.com/phish".to_string());
+
+        ctx.back("/home").unwrap();
+
+        let response = ctx.get_response().unwrap();
+        assert_eq!(response.status, StatusCode::FOUND);
+        assert!(response
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Location" && value == "/home"));
+    }
+
+    #[test]
+    fn test_etag_cache() {
+        let mut ctx = create_test_context();
+        ctx.req
+            .headers
+            .insert("if-none-match".to_string(), "W/\"42\"".to_string());
+
+        assert!(ctx.etag_cache(|| "42"));
+        let response = ctx.get_response().unwrap();
+        assert_eq!(response.status, StatusCode::NOT_MODIFIED);
+        assert!(response
+            .headers
+            .iter()
+            .any(|(name, value)| name == "ETag" && value == "\"42\""));
+
+        // A stale copy gets the new tag and the handler carries on
+        let mut ctx = create_test_context();
+        ctx.req
+            .headers
+            .insert("if-none-match".to_string(), "\"41\"".to_string());
+        assert!(!ctx.etag_cache(|| "42"));
+        ctx.plain("fresh").unwrap();
+        let response = ctx.get_response().unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(response.headers.iter().any(|(name, _)| name == "ETag"));
+    }
+
     #[test]
     fn test_query_methods() {
         let ctx = create_test_context();
@@ -1230,6 +2007,33 @@ mod tests {
         assert_eq!(ctx.int_param_or("id", 0), 123);
         assert_eq!(ctx.str_param_or("missing", "default"), "default");
         assert_eq!(ctx.int_param_or("missing", 42), 42);
+
+        // Typed params fail as not found
+        assert_eq!(ctx.param_or_404::<i64>("id").unwrap(), 123);
+        assert_eq!(ctx.param_or_404::<String>("slug").unwrap(), "test-post");
+        for key in ["slug", "missing"] {
+            let error = ctx.param_or_404::<i64>(key).unwrap_err();
+            assert_eq!(error.status_code(), 404);
+        }
+    }
+
+    #[test]
+    fn test_param_parsed() {
+        let ctx = create_test_context();
+
+        assert_eq!(ctx.param_parsed::<i64>("id").unwrap(), 123);
+        assert_eq!(ctx.param_opt("slug").as_deref(), Some("test-post"));
+        assert_eq!(ctx.param_opt("missing"), None);
+
+        let missing = ctx.param_parsed::<i64>("missing").unwrap_err();
+        assert_eq!(missing.status_code(), 400);
+
+        let invalid = ctx.param_parsed::<i64>("slug").unwrap_err();
+        assert_eq!(invalid.status_code(), 400);
+        assert_eq!(
+            invalid.to_string(),
+            "Invalid input: Route parameter 'slug' expected i64, got \"test-post\""
+        );
     }
 
     #[test]
@@ -1344,6 +2148,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_full_body_with_bracketed_text() {
+        let mut request = Request::new("POST", "/test", "1.1");
+        request
+            .headers
+            .insert("content-type".to_string(), "text/plain".to_string());
+
+        // Not JSON, however deep the brackets go
+        let text = format!("{} done", "[".repeat(100));
+        request.set_body(text.as_bytes().to_vec());
+
+        let views = Arc::new(ViewEngine::new());
+        let mut ctx = Context::new(request, views);
+
+        assert_eq!(ctx.full_body().unwrap(), serde_json::Value::String(text));
+    }
+
     #[test]
     fn test_full_body_with_json_text() {
         let mut request = Request::new("POST", "/test", "1.1");
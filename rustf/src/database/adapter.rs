@@ -18,6 +18,65 @@ pub struct QueryResult {
     pub last_insert_id: Option<i64>,
 }
 
+/// A parameterized statement, one entry of a batch
+#[derive(Debug, Clone)]
+pub struct Statement {
+    /// SQL with backend-specific placeholders
+    pub sql: String,
+    /// Parameters bound in order
+    pub params: Vec<SqlValue>,
+}
+
+impl Statement {
+    /// Create a statement from SQL and its parameters
+    pub fn new(sql: impl Into<String>, params: Vec<SqlValue>) -> Self {
+        Self {
+            sql: sql.into(),
+            params,
+        }
+    }
+}
+
+/// Prepared-statement cache usage of a connection pool
+///
+/// The figures are sampled from a single idle connection, so taking them
+/// never holds more than one connection away from the pool. Connections fill
+/// their caches independently; treat the sample as representative rather than
+/// exact. When every connection is busy, nothing is sampled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// Configured cache capacity per connection (`0` when disabled)
+    pub capacity: usize,
+    /// Number of connections inspected (`0` or `1`)
+    pub connections: usize,
+    /// Prepared statements cached on the sampled connection
+    pub cached_statements: usize,
+    /// Statements cached on the fullest inspected connection
+    pub largest_cache: usize,
+}
+
+impl StatementCacheStats {
+    /// Sample stats from one idle connection of `pool`
+    pub(crate) fn from_pool<DB>(pool: &sqlx::Pool<DB>, capacity: usize) -> Self
+    where
+        DB: sqlx::Database + sqlx::database::HasStatementCache,
+    {
+        use sqlx::Connection;
+
+        let mut stats = Self {
+            capacity,
+            ..Self::default()
+        };
+        if let Some(connection) = pool.try_acquire() {
+            let cached = connection.cached_statements_size();
+            stats.connections = 1;
+            stats.cached_statements = cached;
+            stats.largest_cache = cached;
+        }
+        stats
+    }
+}
+
 /// Unified database adapter trait
 ///
 /// This trait provides a common interface for all database backends,
@@ -53,6 +112,19 @@ pub trait DatabaseAdapter: Send + Sync {
     /// * `Err(Error)` - If the query fails
     async fn fetch_all(&self, sql: &str, params: Vec<SqlValue>) -> Result<Vec<JsonValue>>;
 
+    /// Fetch all rows without adding the statement to the prepared-statement cache
+    ///
+    /// Meant for one-off queries (reports, ad-hoc analytics) that would
+    /// otherwise evict statements the application runs all the time. The
+    /// default implementation runs the query through `fetch_all`.
+    async fn fetch_all_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<SqlValue>,
+    ) -> Result<Vec<JsonValue>> {
+        self.fetch_all(sql, params).await
+    }
+
     /// Fetch a single row from a SELECT query
     ///
     /// # Arguments
@@ -65,6 +137,37 @@ pub trait DatabaseAdapter: Send + Sync {
     /// * `Err(Error)` - If the query fails
     async fn fetch_one(&self, sql: &str, params: Vec<SqlValue>) -> Result<Option<JsonValue>>;
 
+    /// Execute several statements on one connection, in order
+    ///
+    /// With `transaction` set the statements run in a single transaction that
+    /// is rolled back if any of them fails. Otherwise each statement commits
+    /// on its own and the batch stops at the first failure.
+    ///
+    /// The default implementation only supports non-transactional batches and
+    /// runs each statement through `execute`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u64>)` - Rows affected by each statement, in order
+    /// * `Err(Error)` - If a statement fails
+    async fn execute_batch(
+        &self,
+        statements: Vec<Statement>,
+        transaction: bool,
+    ) -> Result<Vec<u64>> {
+        if transaction {
+            return Err(crate::error::Error::database_transaction(
+                "Transactional batches are not supported by this adapter",
+            ));
+        }
+
+        let mut counts = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let result = self.execute(&statement.sql, statement.params).await?;
+            counts.push(result.rows_affected);
+        }
+        Ok(counts)
+    }
+
     /// Test database connectivity
     ///
     /// # Returns
@@ -72,6 +175,27 @@ pub trait DatabaseAdapter: Send + Sync {
     /// * `Err(Error)` - Connection test failed
     async fn ping(&self) -> Result<bool>;
 
+    /// Open and validate pooled connections ahead of the first request
+    ///
+    /// Pool-backed adapters open up to the pool's `min_connections` and ping
+    /// each one. The default implementation only pings once.
+    ///
+    /// # Returns
+    /// * `Ok(count)` - Number of connections validated
+    /// * `Err(Error)` - If a connection could not be opened or validated
+    async fn warm_up(&self) -> Result<u32> {
+        self.ping().await.map(|_| 1)
+    }
+
+    /// Prepared-statement cache usage, for adapters that cache statements
+    ///
+    /// # Returns
+    /// * `Ok(Some(stats))` - Usage sampled from one idle pooled connection
+    /// * `Ok(None)` - The adapter does not cache prepared statements
+    async fn statement_cache_stats(&self) -> Result<Option<StatementCacheStats>> {
+        Ok(None)
+    }
+
     /// Create a new query builder for this database
     ///
     /// # Returns
@@ -1,12 +1,15 @@
 //! SQLite database adapter implementation
 
-use crate::database::adapter::{DatabaseAdapter, QueryResult};
+use crate::database::adapter::{DatabaseAdapter, QueryResult, Statement, StatementCacheStats};
+use crate::database::config::{DatabaseConnectionConfig, DEFAULT_STATEMENT_CACHE_CAPACITY};
 use crate::database::types::{SqlValue, SqliteTypeConverter, TypeConverter};
+use crate::database::{retry, slow_query};
+use crate::error::retry::RetryPolicy;
 use crate::error::{Error, Result};
 use crate::models::query_builder::DatabaseBackend;
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
-use sqlx::SqlitePool;
+use sqlx::{Connection, Executor, SqliteConnection, SqlitePool};
 use std::sync::Arc;
 
 /// SQLite database adapter
@@ -15,6 +18,8 @@ pub struct SqliteAdapter {
     name: String,
     pool: Arc<SqlitePool>,
     converter: SqliteTypeConverter,
+    retry_policy: Option<RetryPolicy>,
+    statement_cache_capacity: usize,
 }
 
 impl SqliteAdapter {
@@ -28,6 +33,44 @@ impl SqliteAdapter {
             name: name.into(),
             pool: Arc::new(pool),
             converter: SqliteTypeConverter::new(),
+            retry_policy: None,
+            statement_cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+        })
+    }
+
+    /// Create a new SQLite adapter using the pool settings from `config`
+    ///
+    /// When `config.lazy` is set no connection is opened until the first query.
+    pub async fn with_config(
+        name: impl Into<String>,
+        config: &DatabaseConnectionConfig,
+    ) -> Result<Self> {
+        let mut options = config.pool_options::<sqlx::Sqlite>();
+        if let Some(seconds) = config.statement_timeout {
+            // SQLite has no statement timeout; this bounds waiting for locks
+            let sql = format!("PRAGMA busy_timeout = {}", seconds * 1000);
+            options = options.after_connect(move |conn, _| {
+                let sql = sql.clone();
+                Box::pin(async move { conn.execute(sql.as_str()).await.map(|_| ()) })
+            });
+        }
+        let connect_options = config
+            .url
+            .parse::<sqlx::sqlite::SqliteConnectOptions>()
+            .map_err(|e| Error::template(format!("Invalid SQLite URL: {}", e)))?
+            .statement_cache_capacity(config.statement_cache_capacity);
+        let pool = if config.lazy {
+            Ok(options.connect_lazy_with(connect_options))
+        } else {
+            options.connect_with(connect_options).await
+        }
+        .map_err(|e| Error::template(format!("Failed to connect to SQLite: {}", e)))?;
+
+        let mut adapter = Self::from_pool(name, pool);
+        adapter.statement_cache_capacity = config.statement_cache_capacity;
+        Ok(match &config.retry {
+            Some(retry) => adapter.with_retry_policy(retry.policy()),
+            None => adapter,
         })
     }
 
@@ -37,9 +80,20 @@ impl SqliteAdapter {
             name: name.into(),
             pool: Arc::new(pool),
             converter: SqliteTypeConverter::new(),
+            retry_policy: None,
+            statement_cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
         }
     }
 
+    /// Retry statements that fail with a transient error
+    ///
+    /// Deadlocks, serialization failures and pool timeouts are retried; lost
+    /// connections only for reads.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
     /// Get reference to the underlying pool
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
@@ -49,6 +103,71 @@ impl SqliteAdapter {
     fn row_to_json(&self, row: &sqlx::sqlite::SqliteRow) -> Result<JsonValue> {
         self.converter.row_to_json(row)
     }
+
+    /// Fetch all rows, keeping the prepared statement cached when `persistent`
+    async fn fetch_rows(
+        &self,
+        sql: &str,
+        params: Vec<SqlValue>,
+        persistent: bool,
+    ) -> Result<Vec<JsonValue>> {
+        let write = retry::is_write(sql);
+        let params = &params;
+        let rows = retry::run(self.retry_policy.as_ref(), sql, move || async move {
+            let mut query = sqlx::query(sql).persistent(persistent);
+
+            // Bind parameters using the converter
+            for param in params.iter().cloned() {
+                query = SqliteTypeConverter::bind_param(query, param);
+            }
+
+            slow_query::observe(&self.name, sql, query.fetch_all(&*self.pool))
+                .await
+                .map_err(|e| {
+                    retry::query_error(DatabaseBackend::SQLite, "SQLite fetch_all failed", e, write)
+                })
+        })
+        .await?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(self.row_to_json(&row)?);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Run batch statements in order on one connection
+///
+/// Inside a transaction nothing is committed until the end, so a lost
+/// connection is safe to retry and is reported as such.
+async fn run_statements(
+    adapter: &str,
+    conn: &mut SqliteConnection,
+    statements: &[Statement],
+    in_transaction: bool,
+) -> Result<Vec<u64>> {
+    let mut counts = Vec::with_capacity(statements.len());
+    for (index, statement) in statements.iter().enumerate() {
+        let mut query = sqlx::query(&statement.sql);
+        for param in statement.params.iter().cloned() {
+            query = SqliteTypeConverter::bind_param(query, param);
+        }
+
+        let result = slow_query::observe(adapter, &statement.sql, query.execute(&mut *conn))
+            .await
+            .map_err(|e| {
+                retry::query_error(
+                    DatabaseBackend::SQLite,
+                    &format!("SQLite batch statement {} failed", index + 1),
+                    e,
+                    !in_transaction && retry::is_write(&statement.sql),
+                )
+            })?;
+        counts.push(result.rows_affected());
+    }
+    Ok(counts)
 }
 
 #[async_trait]
@@ -62,17 +181,23 @@ impl DatabaseAdapter for SqliteAdapter {
     }
 
     async fn execute(&self, sql: &str, params: Vec<SqlValue>) -> Result<QueryResult> {
-        let mut query = sqlx::query(sql);
+        let write = retry::is_write(sql);
+        let params = &params;
+        let result = retry::run(self.retry_policy.as_ref(), sql, move || async move {
+            let mut query = sqlx::query(sql);
 
-        // Bind parameters using the converter
-        for param in params {
-            query = SqliteTypeConverter::bind_param(query, param);
-        }
+            // Bind parameters using the converter
+            for param in params.iter().cloned() {
+                query = SqliteTypeConverter::bind_param(query, param);
+            }
 
-        let result = query
-            .execute(&*self.pool)
-            .await
-            .map_err(|e| Error::template(format!("SQLite execute failed: {}", e)))?;
+            slow_query::observe(&self.name, sql, query.execute(&*self.pool))
+                .await
+                .map_err(|e| {
+                    retry::query_error(DatabaseBackend::SQLite, "SQLite execute failed", e, write)
+                })
+        })
+        .await?;
 
         Ok(QueryResult {
             rows_affected: result.rows_affected(),
@@ -81,38 +206,35 @@ impl DatabaseAdapter for SqliteAdapter {
     }
 
     async fn fetch_all(&self, sql: &str, params: Vec<SqlValue>) -> Result<Vec<JsonValue>> {
-        let mut query = sqlx::query(sql);
-
-        // Bind parameters using the converter
-        for param in params {
-            query = SqliteTypeConverter::bind_param(query, param);
-        }
-
-        let rows = query
-            .fetch_all(&*self.pool)
-            .await
-            .map_err(|e| Error::template(format!("SQLite fetch_all failed: {}", e)))?;
-
-        let mut results = Vec::new();
-        for row in rows {
-            results.push(self.row_to_json(&row)?);
-        }
+        self.fetch_rows(sql, params, true).await
+    }
 
-        Ok(results)
+    async fn fetch_all_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<SqlValue>,
+    ) -> Result<Vec<JsonValue>> {
+        self.fetch_rows(sql, params, false).await
     }
 
     async fn fetch_one(&self, sql: &str, params: Vec<SqlValue>) -> Result<Option<JsonValue>> {
-        let mut query = sqlx::query(sql);
+        let write = retry::is_write(sql);
+        let params = &params;
+        let row = retry::run(self.retry_policy.as_ref(), sql, move || async move {
+            let mut query = sqlx::query(sql);
 
-        // Bind parameters using the converter
-        for param in params {
-            query = SqliteTypeConverter::bind_param(query, param);
-        }
+            // Bind parameters using the converter
+            for param in params.iter().cloned() {
+                query = SqliteTypeConverter::bind_param(query, param);
+            }
 
-        let row = query
-            .fetch_optional(&*self.pool)
-            .await
-            .map_err(|e| Error::template(format!("SQLite fetch_one failed: {}", e)))?;
+            slow_query::observe(&self.name, sql, query.fetch_optional(&*self.pool))
+                .await
+                .map_err(|e| {
+                    retry::query_error(DatabaseBackend::SQLite, "SQLite fetch_one failed", e, write)
+                })
+        })
+        .await?;
 
         match row {
             Some(row) => Ok(Some(self.row_to_json(&row)?)),
@@ -120,6 +242,47 @@ impl DatabaseAdapter for SqliteAdapter {
         }
     }
 
+    async fn execute_batch(
+        &self,
+        statements: Vec<Statement>,
+        transaction: bool,
+    ) -> Result<Vec<u64>> {
+        if statements.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !transaction {
+            let mut conn = self.pool.acquire().await.map_err(|e| {
+                retry::query_error(DatabaseBackend::SQLite, "SQLite batch failed", e, false)
+            })?;
+            return run_statements(&self.name, &mut conn, &statements, false).await;
+        }
+
+        // A failed attempt rolls back every statement, so the whole batch can be retried
+        let statements = &statements;
+        retry::run_atomic(self.retry_policy.as_ref(), move || async move {
+            let mut tx = self.pool.begin().await.map_err(|e| {
+                retry::query_error(
+                    DatabaseBackend::SQLite,
+                    "SQLite batch begin failed",
+                    e,
+                    false,
+                )
+            })?;
+            let counts = run_statements(&self.name, &mut tx, statements, true).await?;
+            tx.commit().await.map_err(|e| {
+                retry::query_error(
+                    DatabaseBackend::SQLite,
+                    "SQLite batch commit failed",
+                    e,
+                    true,
+                )
+            })?;
+            Ok(counts)
+        })
+        .await
+    }
+
     async fn ping(&self) -> Result<bool> {
         sqlx::query("SELECT 1")
             .fetch_one(&*self.pool)
@@ -128,6 +291,34 @@ impl DatabaseAdapter for SqliteAdapter {
             .map_err(|e| Error::template(format!("SQLite ping failed: {}", e)))
     }
 
+    async fn warm_up(&self) -> Result<u32> {
+        let target = self.pool.options().get_min_connections().max(1);
+
+        // Hold every connection until all are validated so the pool opens distinct ones
+        let mut connections = Vec::with_capacity(target as usize);
+        for _ in 0..target {
+            let mut connection = self
+                .pool
+                .acquire()
+                .await
+                .map_err(|e| Error::template(format!("SQLite warm-up failed: {}", e)))?;
+            connection
+                .ping()
+                .await
+                .map_err(|e| Error::template(format!("SQLite warm-up failed: {}", e)))?;
+            connections.push(connection);
+        }
+
+        Ok(target)
+    }
+
+    async fn statement_cache_stats(&self) -> Result<Option<StatementCacheStats>> {
+        Ok(Some(StatementCacheStats::from_pool(
+            &self.pool,
+            self.statement_cache_capacity,
+        )))
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -136,3 +327,101 @@ impl DatabaseAdapter for SqliteAdapter {
         Box::new(self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_adapter() -> SqliteAdapter {
+        // One connection, so every statement sees the same in-memory database
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let adapter = SqliteAdapter::from_pool("test", pool);
+        adapter
+            .execute(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+                vec![],
+            )
+            .await
+            .unwrap();
+        adapter
+    }
+
+    async fn names(adapter: &SqliteAdapter) -> Vec<JsonValue> {
+        adapter
+            .fetch_all("SELECT name FROM users ORDER BY id", vec![])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row["name"].clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_affected_rows() {
+        let adapter = memory_adapter().await;
+        let counts = adapter
+            .execute_batch(
+                vec![
+                    Statement::new("INSERT INTO users (name) VALUES (?)", vec!["ann".into()]),
+                    Statement::new("INSERT INTO users (name) VALUES (?)", vec!["bob".into()]),
+                    Statement::new("UPDATE users SET name = upper(name)", vec![]),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(counts, vec![1, 1, 2]);
+        assert_eq!(names(&adapter).await, vec!["ANN", "BOB"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back() {
+        let adapter = memory_adapter().await;
+        let statements = vec![
+            Statement::new("INSERT INTO users (name) VALUES (?)", vec!["ann".into()]),
+            Statement::new("INSERT INTO users (name) VALUES (?)", vec![SqlValue::Null]),
+        ];
+
+        assert!(adapter
+            .execute_batch(statements.clone(), true)
+            .await
+            .is_err());
+        assert!(names(&adapter).await.is_empty());
+
+        // Without a transaction the first insert stays
+        assert!(adapter.execute_batch(statements, false).await.is_err());
+        assert_eq!(names(&adapter).await, vec!["ann"]);
+    }
+
+    #[tokio::test]
+    async fn test_unprepared_queries_are_not_cached() {
+        let adapter = memory_adapter().await;
+        let cached = || async {
+            // Connections go back to the pool in the background
+            while adapter.pool().num_idle() == 0 {
+                tokio::task::yield_now().await;
+            }
+            let stats = adapter.statement_cache_stats().await.unwrap().unwrap();
+            assert_eq!(stats.connections, 1);
+            stats.cached_statements
+        };
+        let before = cached().await;
+
+        adapter
+            .fetch_all_unprepared("SELECT count(*) AS n FROM users", vec![])
+            .await
+            .unwrap();
+        assert_eq!(cached().await, before);
+
+        adapter
+            .fetch_all("SELECT count(*) AS n FROM users", vec![])
+            .await
+            .unwrap();
+        assert_eq!(cached().await, before + 1);
+    }
+}
@@ -0,0 +1,19 @@
+//! Process-wide database availability
+//!
+//! Degraded mode is entered when the database stops answering probes (see
+//! `DegradedModeMiddleware`) and reported by the health check.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the application is running degraded, without its database
+static DEGRADED: AtomicBool = AtomicBool::new(false);
+
+/// Check whether the application is in degraded mode
+pub fn is_degraded() -> bool {
+    DEGRADED.load(Ordering::SeqCst)
+}
+
+/// Enter or leave degraded mode, returning the previous mode
+pub(crate) fn set_degraded(degraded: bool) -> bool {
+    DEGRADED.swap(degraded, Ordering::SeqCst)
+}
@@ -3,8 +3,13 @@
 //! This module handles parsing database configurations from config files
 //! and environment variables, supporting multiple named database connections.
 
+use crate::error::retry::RetryPolicy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Prepared statements cached per connection unless configured otherwise (sqlx's default)
+pub(crate) const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 100;
 
 /// Configuration for a single database connection
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +40,97 @@ pub struct DatabaseConnectionConfig {
     /// Whether this database should be set as the default
     #[serde(default)]
     pub is_default: bool,
+
+    /// Defer connecting until the first query instead of at startup
+    #[serde(default)]
+    pub lazy: bool,
+
+    /// Open and ping `min_connections` connections at startup (ignored when `lazy`)
+    #[serde(default)]
+    pub warm_up: bool,
+
+    /// Retry transient failures (deadlocks, dropped connections) on this connection
+    #[serde(default)]
+    pub retry: Option<DatabaseRetryConfig>,
+
+    /// Cancel statements running longer than this many seconds
+    ///
+    /// Applied to every pooled connection when it opens: PostgreSQL
+    /// `statement_timeout`, MySQL `max_execution_time` (SELECTs only) and
+    /// SQLite `busy_timeout` (time spent waiting for locks).
+    #[serde(default)]
+    pub statement_timeout: Option<u64>,
+
+    /// Prepared statements kept per connection, least recently used evicted
+    ///
+    /// `0` disables the cache so every statement is prepared and discarded.
+    #[serde(default = "default_statement_cache_capacity")]
+    pub statement_cache_capacity: usize,
+}
+
+impl DatabaseConnectionConfig {
+    /// Build sqlx pool options from this configuration
+    pub fn pool_options<DB: sqlx::Database>(&self) -> sqlx::pool::PoolOptions<DB> {
+        sqlx::pool::PoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections.min(self.max_connections))
+            .acquire_timeout(Duration::from_secs(self.connect_timeout))
+            .idle_timeout(Duration::from_secs(self.idle_timeout))
+            .max_lifetime(Duration::from_secs(self.max_lifetime))
+    }
+}
+
+/// Retry settings for statements that fail with a transient error
+///
+/// Only errors reported as retryable are retried: deadlocks and serialization
+/// failures, pool timeouts, and lost connections on reads. Transaction control
+/// statements are never retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseRetryConfig {
+    /// Total number of attempts, including the first one
+    #[serde(default = "default_retry_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in milliseconds
+    #[serde(default = "default_retry_initial_delay")]
+    pub initial_delay_ms: u64,
+
+    /// Upper bound for the delay between retries in milliseconds
+    #[serde(default = "default_retry_max_delay")]
+    pub max_delay_ms: u64,
+
+    /// Factor applied to the delay after each attempt
+    #[serde(default = "default_retry_backoff")]
+    pub backoff_multiplier: f32,
+
+    /// Randomize delays so concurrent retries spread out
+    #[serde(default = "default_true")]
+    pub jitter: bool,
+}
+
+impl DatabaseRetryConfig {
+    /// Build the retry policy used by the adapters
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts.max(1),
+            initial_delay: Duration::from_millis(self.initial_delay_ms),
+            max_delay: Duration::from_millis(self.max_delay_ms),
+            backoff_multiplier: self.backoff_multiplier,
+            jitter: self.jitter,
+        }
+    }
+}
+
+impl Default for DatabaseRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_attempts(),
+            initial_delay_ms: default_retry_initial_delay(),
+            max_delay_ms: default_retry_max_delay(),
+            backoff_multiplier: default_retry_backoff(),
+            jitter: true,
+        }
+    }
 }
 
 /// Configuration for multiple databases
@@ -116,6 +212,11 @@ impl DatabasesConfig {
                 idle_timeout: default_idle_timeout(),
                 max_lifetime: default_max_lifetime(),
                 is_default: true,
+                lazy: false,
+                warm_up: false,
+                retry: None,
+                statement_timeout: None,
+                statement_cache_capacity: default_statement_cache_capacity(),
             };
 
             config.add_database("primary", db_config);
@@ -141,6 +242,24 @@ fn default_idle_timeout() -> u64 {
 fn default_max_lifetime() -> u64 {
     1800
 } // 30 minutes
+fn default_retry_attempts() -> u32 {
+    3
+}
+fn default_retry_initial_delay() -> u64 {
+    50
+}
+fn default_retry_max_delay() -> u64 {
+    2000
+}
+fn default_retry_backoff() -> f32 {
+    2.0
+}
+fn default_statement_cache_capacity() -> usize {
+    DEFAULT_STATEMENT_CACHE_CAPACITY
+}
+fn default_true() -> bool {
+    true
+}
 
 /// Builder for DatabaseConnectionConfig
 pub struct DatabaseConnectionConfigBuilder {
@@ -151,6 +270,11 @@ pub struct DatabaseConnectionConfigBuilder {
     idle_timeout: u64,
     max_lifetime: u64,
     is_default: bool,
+    lazy: bool,
+    warm_up: bool,
+    retry: Option<DatabaseRetryConfig>,
+    statement_timeout: Option<u64>,
+    statement_cache_capacity: usize,
 }
 
 impl DatabaseConnectionConfigBuilder {
@@ -164,6 +288,11 @@ impl DatabaseConnectionConfigBuilder {
             idle_timeout: default_idle_timeout(),
             max_lifetime: default_max_lifetime(),
             is_default: false,
+            lazy: false,
+            warm_up: false,
+            retry: None,
+            statement_timeout: None,
+            statement_cache_capacity: default_statement_cache_capacity(),
         }
     }
 
@@ -209,6 +338,36 @@ impl DatabaseConnectionConfigBuilder {
         self
     }
 
+    /// Defer connecting until first use
+    pub fn lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Open and validate `min_connections` connections at startup
+    pub fn warm_up(mut self, warm_up: bool) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// Retry transient failures with the given settings
+    pub fn retry(mut self, retry: DatabaseRetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Cancel statements running longer than `seconds`
+    pub fn statement_timeout(mut self, seconds: u64) -> Self {
+        self.statement_timeout = Some(seconds);
+        self
+    }
+
+    /// Keep at most `capacity` prepared statements per connection (`0` disables)
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.statement_cache_capacity = capacity;
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> Result<DatabaseConnectionConfig, String> {
         let url = self.url.ok_or("Database URL is required")?;
@@ -221,6 +380,11 @@ impl DatabaseConnectionConfigBuilder {
             idle_timeout: self.idle_timeout,
             max_lifetime: self.max_lifetime,
             is_default: self.is_default,
+            lazy: self.lazy,
+            warm_up: self.warm_up,
+            retry: self.retry,
+            statement_timeout: self.statement_timeout,
+            statement_cache_capacity: self.statement_cache_capacity,
         })
     }
 }
@@ -254,6 +418,11 @@ mod tests {
             idle_timeout: 600,
             max_lifetime: 1800,
             is_default: true,
+            lazy: false,
+            warm_up: false,
+            retry: None,
+            statement_timeout: None,
+            statement_cache_capacity: 100,
         };
 
         config.add_database("primary", db_config.clone());
@@ -278,6 +447,11 @@ mod tests {
                 idle_timeout: 600,
                 max_lifetime: 1800,
                 is_default: false,
+                lazy: false,
+                warm_up: false,
+                retry: None,
+                statement_timeout: None,
+                statement_cache_capacity: 100,
             },
         );
 
@@ -292,6 +466,11 @@ mod tests {
                 idle_timeout: 600,
                 max_lifetime: 1800,
                 is_default: true,
+                lazy: false,
+                warm_up: false,
+                retry: None,
+                statement_timeout: None,
+                statement_cache_capacity: 100,
             },
         );
 
@@ -328,5 +507,74 @@ mod tests {
         assert_eq!(config.max_connections, 5);
         assert_eq!(config.min_connections, 1);
         assert!(config.is_default);
+        assert!(!config.lazy);
+        assert!(!config.warm_up);
+    }
+
+    #[test]
+    fn test_lazy_and_warm_up_options() {
+        let config = DatabaseConnectionConfigBuilder::new()
+            .url("postgresql://localhost/app")
+            .lazy(true)
+            .warm_up(true)
+            .build()
+            .unwrap();
+
+        assert!(config.lazy);
+        assert!(config.warm_up);
+
+        let parsed: DatabaseConnectionConfig =
+            serde_json::from_str(r#"{"url": "sqlite://./app.db", "warm_up": true}"#).unwrap();
+        assert!(!parsed.lazy);
+        assert!(parsed.warm_up);
+        assert_eq!(parsed.min_connections, 1);
+    }
+
+    #[test]
+    fn test_retry_config() {
+        let parsed: DatabaseConnectionConfig = serde_json::from_str(
+            r#"{"url": "postgresql://localhost/app", "retry": {"max_attempts": 5}}"#,
+        )
+        .unwrap();
+        let policy = parsed.retry.unwrap().policy();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.initial_delay, Duration::from_millis(50));
+        assert_eq!(policy.max_delay, Duration::from_secs(2));
+
+        let config = DatabaseConnectionConfigBuilder::new()
+            .url("sqlite://./app.db")
+            .build()
+            .unwrap();
+        assert!(config.retry.is_none());
+    }
+
+    #[test]
+    fn test_statement_timeout() {
+        let parsed: DatabaseConnectionConfig = serde_json::from_str(
+            r#"{"url": "postgresql://localhost/app", "statement_timeout": 15}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.statement_timeout, Some(15));
+
+        let config = DatabaseConnectionConfigBuilder::new()
+            .url("sqlite://./app.db")
+            .build()
+            .unwrap();
+        assert_eq!(config.statement_timeout, None);
+    }
+
+    #[test]
+    fn test_statement_cache_capacity() {
+        let parsed: DatabaseConnectionConfig = serde_json::from_str(
+            r#"{"url": "postgresql://localhost/app", "statement_cache_capacity": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.statement_cache_capacity, 0);
+
+        let config = DatabaseConnectionConfigBuilder::new()
+            .url("sqlite://./app.db")
+            .build()
+            .unwrap();
+        assert_eq!(config.statement_cache_capacity, 100);
     }
 }
@@ -6,13 +6,18 @@
 
 pub mod adapter;
 pub mod adapters;
+pub mod availability;
 pub mod config;
 pub mod registry;
+pub mod retry;
+pub mod slow_query;
+pub mod transaction;
 pub mod types;
 
 // Re-export main types for convenience
-pub use adapter::{DatabaseAdapter, QueryResult};
+pub use adapter::{DatabaseAdapter, QueryResult, Statement, StatementCacheStats};
 pub use adapters::{MySqlAdapter, PostgresAdapter, SqliteAdapter};
-pub use config::{DatabaseConnectionConfig, DatabasesConfig};
+pub use config::{DatabaseConnectionConfig, DatabaseRetryConfig, DatabasesConfig};
 pub use registry::{DatabaseRegistry, RegistryStats};
+pub use transaction::Transaction;
 pub use types::{DatabaseBackend, SqlValue, TypeConverter, TypeRegistry};
@@ -139,6 +139,33 @@ impl DatabaseRegistry {
         Ok(adapter.query())
     }
 
+    /// Warm up a single registered database
+    ///
+    /// # Returns
+    /// * `Ok(count)` - Number of connections opened and validated
+    /// * `Err(Error)` - If the database doesn't exist or a connection fails
+    pub async fn warm_up_database(&self, name: &str) -> Result<u32> {
+        let adapter = self
+            .get(name)
+            .await
+            .ok_or_else(|| Error::template(format!("Database '{}' not found", name)))?;
+
+        adapter.warm_up().await
+    }
+
+    /// Warm up every registered database
+    ///
+    /// # Returns
+    /// * `Ok(count)` - Total number of connections opened and validated
+    /// * `Err(Error)` - On the first database that fails to warm up
+    pub async fn warm_up(&self) -> Result<u32> {
+        let mut total = 0;
+        for name in self.list_databases().await {
+            total += self.warm_up_database(&name).await?;
+        }
+        Ok(total)
+    }
+
     /// List all registered database names
     pub async fn list_databases(&self) -> Vec<String> {
         let adapters = self.adapters.read().await;
@@ -253,4 +280,12 @@ mod tests {
         let result = registry.get_default().await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_warm_up_empty_registry() {
+        let registry = DatabaseRegistry::new();
+
+        assert_eq!(registry.warm_up().await.unwrap(), 0);
+        assert!(registry.warm_up_database("missing").await.is_err());
+    }
 }
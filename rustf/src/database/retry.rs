@@ -0,0 +1,435 @@
+//! Retrying statements that fail with transient errors
+//!
+//! Adapters configured with a [`RetryPolicy`] run every statement through
+//! [`run`], which executes it again while the failure is retryable. Driver
+//! errors are classified by [`query_error`]:
+//!
+//! - deadlocks and serialization failures become `Error::DatabaseConflict`
+//!   (the database rolled the statement back, so running it again is safe)
+//! - pool timeouts become `Error::DatabasePool`
+//! - lost connections become `Error::DatabaseConnection` for reads only; a
+//!   write may have been applied before the connection dropped, so it is
+//!   reported as a non-retryable `Error::DatabaseQuery`
+//! - statements cancelled by the connection's `statement_timeout` become
+//!   `Error::Timeout`, which is not retried here: the statement would most
+//!   likely run out of time again
+//! - unique, foreign key, NOT NULL and CHECK violations become
+//!   `Error::ConstraintViolation` with the constraint name where the driver
+//!   reports one; they are never retried
+//!
+//! Explicit transactions (`database::Transaction`) talk to sqlx directly and
+//! are never retried here; transaction control statements sent through an
+//! adapter are not retried either. Transactional batches are retried as a
+//! whole with [`run_atomic`].
+
+use crate::error::retry::{with_retry_if, RetryPolicy};
+use crate::error::{ConstraintKind, Error, Result};
+use crate::models::query_builder::DatabaseBackend;
+use std::future::Future;
+
+/// Run `operation`, retrying it under `policy` when one is configured
+pub(crate) async fn run<T, F, Fut>(
+    policy: Option<&RetryPolicy>,
+    sql: &str,
+    mut operation: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match policy {
+        Some(policy) if !is_transaction_control(sql) => {
+            with_retry_if(policy.clone(), operation, is_transient).await
+        }
+        _ => operation().await,
+    }
+}
+
+/// Run a unit of work that the database undoes as a whole on failure
+///
+/// Used for transactional batches: unlike [`run`] there is no statement to
+/// inspect, and every attempt starts a fresh transaction.
+pub(crate) async fn run_atomic<T, F, Fut>(
+    policy: Option<&RetryPolicy>,
+    mut operation: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match policy {
+        Some(policy) => with_retry_if(policy.clone(), operation, is_transient).await,
+        None => operation().await,
+    }
+}
+
+fn is_transient(error: &Error) -> bool {
+    error.is_retryable() && !matches!(error, Error::Timeout(_))
+}
+
+/// Convert a driver error into a framework error
+///
+/// `context` prefixes the message (e.g. "PostgreSQL execute failed").
+pub(crate) fn query_error(
+    backend: DatabaseBackend,
+    context: &str,
+    error: sqlx::Error,
+    write: bool,
+) -> Error {
+    let message = format!("{}: {}", context, error);
+    if let sqlx::Error::Database(db) = &error {
+        if let Some(kind) = constraint_kind(db.as_ref()) {
+            let constraint = constraint_name(backend, db.as_ref());
+            return Error::constraint_violation(kind, constraint, message);
+        }
+    }
+    match &error {
+        sqlx::Error::Database(db) if is_conflict(backend, db.as_ref()) => {
+            Error::database_conflict(message)
+        }
+        sqlx::Error::Database(db) if is_timeout(backend, db.as_ref()) => Error::timeout(message),
+        sqlx::Error::PoolTimedOut => Error::database_pool(message),
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::Protocol(_) if write => {
+            Error::database_query(message)
+        }
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::Protocol(_) => {
+            Error::database_connection(message)
+        }
+        _ => Error::template(message),
+    }
+}
+
+/// Whether `sql` may modify data
+///
+/// Anything that is not plainly a read counts as a write.
+pub(crate) fn is_write(sql: &str) -> bool {
+    !matches!(
+        first_keyword(sql).as_str(),
+        "SELECT" | "SHOW" | "EXPLAIN" | "DESCRIBE"
+    )
+}
+
+fn is_transaction_control(sql: &str) -> bool {
+    matches!(
+        first_keyword(sql).as_str(),
+        "BEGIN" | "START" | "COMMIT" | "END" | "ROLLBACK" | "SAVEPOINT" | "RELEASE"
+    )
+}
+
+fn first_keyword(sql: &str) -> String {
+    sql.trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase()
+}
+
+fn is_conflict(
+    backend: DatabaseBackend,
+    error: &(dyn sqlx::error::DatabaseError + 'static),
+) -> bool {
+    error_code(backend, error).is_some_and(|code| is_conflict_code(backend, &code))
+}
+
+fn is_timeout(
+    backend: DatabaseBackend,
+    error: &(dyn sqlx::error::DatabaseError + 'static),
+) -> bool {
+    error_code(backend, error).is_some_and(|code| is_timeout_code(backend, &code))
+}
+
+fn error_code(
+    backend: DatabaseBackend,
+    error: &(dyn sqlx::error::DatabaseError + 'static),
+) -> Option<String> {
+    match backend {
+        DatabaseBackend::MySQL | DatabaseBackend::MariaDB => error
+            .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+            .map(|e| e.number().to_string()),
+        _ => error.code().map(|code| code.into_owned()),
+    }
+}
+
+/// Map the driver's classification (SQLSTATE class 23, MySQL and SQLite
+/// constraint codes) to a constraint kind
+fn constraint_kind(error: &(dyn sqlx::error::DatabaseError + 'static)) -> Option<ConstraintKind> {
+    match error.kind() {
+        sqlx::error::ErrorKind::UniqueViolation => Some(ConstraintKind::Unique),
+        sqlx::error::ErrorKind::ForeignKeyViolation => Some(ConstraintKind::ForeignKey),
+        sqlx::error::ErrorKind::NotNullViolation => Some(ConstraintKind::NotNull),
+        sqlx::error::ErrorKind::CheckViolation => Some(ConstraintKind::Check),
+        _ => None,
+    }
+}
+
+/// Name of the violated constraint
+///
+/// PostgreSQL reports it as a field of the error (the column for NOT NULL
+/// violations); MySQL and SQLite only mention it in the message.
+fn constraint_name(
+    backend: DatabaseBackend,
+    error: &(dyn sqlx::error::DatabaseError + 'static),
+) -> Option<String> {
+    if let Some(constraint) = error.constraint() {
+        return Some(constraint.to_string());
+    }
+    match backend {
+        DatabaseBackend::Postgres => {
+            let error = error.try_downcast_ref::<sqlx::postgres::PgDatabaseError>()?;
+            match (error.table(), error.column()) {
+                (Some(table), Some(column)) => Some(format!("{}.{}", table, column)),
+                (None, Some(column)) => Some(column.to_string()),
+                _ => None,
+            }
+        }
+        _ => constraint_from_message(error.message()),
+    }
+}
+
+/// Extract the constraint from a MySQL or SQLite error message
+///
+/// - MySQL: ``Duplicate entry 'a@b.c' for key 'users.email_unique'``,
+///   ``... CONSTRAINT `fk_posts_user` FOREIGN KEY ...``,
+///   ``Column 'name' cannot be null``, ``Check constraint 'age_positive' is violated.``
+/// - SQLite: `UNIQUE constraint failed: users.email`,
+///   `NOT NULL constraint failed: users.name`, `CHECK constraint failed: age_positive`
+///
+/// SQLite foreign key errors name nothing.
+fn constraint_from_message(message: &str) -> Option<String> {
+    let quoted = |text: &str, quote: char| -> Option<String> {
+        let rest = text.strip_prefix(quote)?;
+        rest.find(quote).map(|end| rest[..end].to_string())
+    };
+
+    if let Some((_, rest)) = message.split_once("constraint failed: ") {
+        // A composite unique index lists every column: "t.a, t.b"
+        return Some(rest.trim().to_string()).filter(|name| !name.is_empty());
+    }
+    if let Some((_, rest)) = message.rsplit_once(" for key ") {
+        return quoted(rest, '\'');
+    }
+    if let Some((_, rest)) = message.split_once("CONSTRAINT ") {
+        return quoted(rest, '`');
+    }
+    if let Some(rest) = message
+        .strip_prefix("Column ")
+        .or_else(|| message.strip_prefix("Check constraint "))
+    {
+        return quoted(rest, '\'');
+    }
+    None
+}
+
+/// Deadlock and serialization failure codes
+///
+/// PostgreSQL reports SQLSTATEs, MySQL server error numbers and SQLite
+/// (extended) result codes.
+fn is_conflict_code(backend: DatabaseBackend, code: &str) -> bool {
+    match backend {
+        // serialization_failure, deadlock_detected
+        DatabaseBackend::Postgres => matches!(code, "40001" | "40P01"),
+        // ER_LOCK_DEADLOCK, ER_LOCK_WAIT_TIMEOUT
+        DatabaseBackend::MySQL | DatabaseBackend::MariaDB => matches!(code, "1213" | "1205"),
+        // SQLITE_BUSY, SQLITE_LOCKED and their extended codes
+        DatabaseBackend::SQLite => {
+            matches!(code, "5" | "6" | "261" | "262" | "517" | "773")
+        }
+    }
+}
+
+/// Codes for statements cancelled by the statement timeout
+///
+/// SQLite has no statement timeout; its busy timeout ends in SQLITE_BUSY,
+/// which stays a conflict.
+fn is_timeout_code(backend: DatabaseBackend, code: &str) -> bool {
+    match backend {
+        // query_canceled
+        DatabaseBackend::Postgres => code == "57014",
+        // ER_QUERY_TIMEOUT
+        DatabaseBackend::MySQL | DatabaseBackend::MariaDB => code == "3024",
+        DatabaseBackend::SQLite => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_statement_classification() {
+        assert!(!is_write("  select * from users"));
+        assert!(!is_write("(SELECT 1) UNION (SELECT 2)"));
+        assert!(is_write("INSERT INTO users (name) VALUES ($1)"));
+        assert!(is_write(
+            "WITH moved AS (DELETE FROM a RETURNING *) SELECT * FROM moved"
+        ));
+
+        assert!(is_transaction_control("BEGIN"));
+        assert!(is_transaction_control("start transaction"));
+        assert!(is_transaction_control("ROLLBACK TO SAVEPOINT sp1"));
+        assert!(!is_transaction_control("UPDATE accounts SET balance = 0"));
+    }
+
+    #[test]
+    fn test_conflict_codes() {
+        assert!(is_conflict_code(DatabaseBackend::Postgres, "40P01"));
+        assert!(!is_conflict_code(DatabaseBackend::Postgres, "23505"));
+        assert!(is_conflict_code(DatabaseBackend::MySQL, "1213"));
+        assert!(is_conflict_code(DatabaseBackend::SQLite, "517"));
+        assert!(!is_conflict_code(DatabaseBackend::SQLite, "19"));
+
+        assert!(is_timeout_code(DatabaseBackend::Postgres, "57014"));
+        assert!(is_timeout_code(DatabaseBackend::MySQL, "3024"));
+        assert!(!is_timeout_code(DatabaseBackend::SQLite, "5"));
+    }
+
+    #[test]
+    fn test_constraint_from_message() {
+        let cases = [
+            (
+                "Duplicate entry 'a@b.c' for key 'users.email_unique'",
+                Some("users.email_unique"),
+            ),
+            (
+                "Cannot add or update a child row: a foreign key constraint fails \
+                 (`app`.`posts`, CONSTRAINT `fk_posts_user` FOREIGN KEY (`user_id`) \
+                 REFERENCES `users` (`id`))",
+                Some("fk_posts_user"),
+            ),
+            ("Column 'name' cannot be null", Some("name")),
+            (
+                "Check constraint 'age_positive' is violated.",
+                Some("age_positive"),
+            ),
+            ("UNIQUE constraint failed: users.email", Some("users.email")),
+            (
+                "UNIQUE constraint failed: members.team_id, members.user_id",
+                Some("members.team_id, members.user_id"),
+            ),
+            ("NOT NULL constraint failed: users.name", Some("users.name")),
+            ("FOREIGN KEY constraint failed", None),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(
+                constraint_from_message(message).as_deref(),
+                expected,
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_constraint_violations() {
+        use sqlx::Connection;
+
+        let mut conn = sqlx::SqliteConnection::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE, \
+             age INTEGER CONSTRAINT age_positive CHECK (age > 0))",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO users (email, age) VALUES ('a@b.c', 30)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let cases = [
+            (
+                "INSERT INTO users (email, age) VALUES ('a@b.c', 20)",
+                ConstraintKind::Unique,
+                "users.email",
+                409,
+            ),
+            (
+                "INSERT INTO users (email, age) VALUES (NULL, 20)",
+                ConstraintKind::NotNull,
+                "users.email",
+                400,
+            ),
+            (
+                "INSERT INTO users (email, age) VALUES ('c@d.e', -1)",
+                ConstraintKind::Check,
+                "age_positive",
+                400,
+            ),
+        ];
+        for (sql, kind, constraint, status) in cases {
+            let error = sqlx::query(sql).execute(&mut conn).await.unwrap_err();
+            let error = query_error(DatabaseBackend::SQLite, "insert", error, true);
+            match &error {
+                Error::ConstraintViolation {
+                    kind: actual,
+                    constraint: name,
+                    ..
+                } => {
+                    assert_eq!(*actual, kind, "{}", sql);
+                    assert_eq!(name.as_deref(), Some(constraint), "{}", sql);
+                }
+                other => panic!("{}: unexpected {:?}", sql, other),
+            }
+            assert_eq!(error.status_code(), status);
+            assert!(!error.is_retryable());
+        }
+    }
+
+    #[test]
+    fn test_lost_connection_on_write_is_not_retryable() {
+        let lost = || sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        let read = query_error(DatabaseBackend::Postgres, "read", lost(), false);
+        let write = query_error(DatabaseBackend::Postgres, "write", lost(), true);
+
+        assert!(read.is_retryable());
+        assert!(!write.is_retryable());
+        assert!(
+            query_error(DatabaseBackend::MySQL, "x", sqlx::Error::PoolTimedOut, true)
+                .is_retryable()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_conflicts() {
+        let policy = RetryPolicy::fixed(3, Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+        let operation = || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(Error::database_conflict("deadlock detected"))
+            } else {
+                Ok(1)
+            }
+        };
+
+        assert_eq!(
+            run(Some(&policy), "UPDATE t SET a = 1", operation)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        attempts.store(0, Ordering::SeqCst);
+        assert!(run(Some(&policy), "COMMIT", operation).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        attempts.store(0, Ordering::SeqCst);
+        assert!(run(None, "UPDATE t SET a = 1", operation).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Timed-out statements are not run again
+        attempts.store(0, Ordering::SeqCst);
+        let timed_out = || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(Error::timeout("statement timeout"))
+        };
+        assert!(run(Some(&policy), "SELECT pg_sleep(60)", timed_out)
+            .await
+            .is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
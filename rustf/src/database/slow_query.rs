@@ -0,0 +1,130 @@
+//! Logging of slow statements
+//!
+//! Off until a threshold is set, from `database.slow_query_ms` or with
+//! [`set_threshold`]. Every attempt at a statement sent through a
+//! `DatabaseAdapter` is then timed on its own, so retry backoff is never
+//! counted. Attempts that take at least the threshold are logged as warnings
+//! under the `rustf::slow_query` target, with the adapter name, the
+//! duration, the request id of the current request and the SQL. Parameters
+//! are never logged, and quoted literals in the SQL text are replaced with
+//! `'?'`.
+//!
+//! The threshold can be changed while the app runs:
+//!
+//! ```rust,ignore
+//! use rustf::database::slow_query;
+//! use std::time::Duration;
+//!
+//! slow_query::set_threshold(Some(Duration::from_millis(100)));
+//! slow_query::set_threshold(None); // stop logging
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Current threshold in milliseconds, 0 when disabled
+static THRESHOLD_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Log statements taking at least `threshold`, or none with `None`
+pub fn set_threshold(threshold: Option<Duration>) {
+    let millis = threshold.map_or(0, |t| (t.as_millis() as u64).max(1));
+    THRESHOLD_MS.store(millis, Ordering::Relaxed);
+}
+
+/// The current threshold, `None` when slow statements are not logged
+pub fn threshold() -> Option<Duration> {
+    match THRESHOLD_MS.load(Ordering::Relaxed) {
+        0 => None,
+        millis => Some(Duration::from_millis(millis)),
+    }
+}
+
+/// Await one attempt at a statement and log it if it was slow
+pub(crate) async fn observe<F: Future>(adapter: &str, sql: &str, fut: F) -> F::Output {
+    let Some(threshold) = threshold() else {
+        return fut.await;
+    };
+
+    let started = Instant::now();
+    let output = fut.await;
+    let elapsed = started.elapsed();
+    if elapsed >= threshold {
+        let request_id = crate::http::request_id::current();
+        log::warn!(
+            target: "rustf::slow_query",
+            "Slow query on '{}' took {} ms (request id: {}): {}",
+            adapter,
+            elapsed.as_millis(),
+            request_id.as_deref().unwrap_or("none"),
+            redact(sql)
+        );
+    }
+    output
+}
+
+/// Replace quoted literals with `'?'` and collapse whitespace
+fn redact(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            pending_space = !out.is_empty();
+            continue;
+        }
+        if pending_space {
+            out.push(' ');
+            pending_space = false;
+        }
+        if c != '\'' {
+            out.push(c);
+            continue;
+        }
+
+        // Skip to the closing quote; '' is an escaped quote inside the literal
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        }
+        out.push_str("'?'");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("SELECT *\n  FROM users\n  WHERE email = 'a@b.c' AND id = $1"),
+            "SELECT * FROM users WHERE email = '?' AND id = $1"
+        );
+        assert_eq!(
+            redact("UPDATE t SET note = 'it''s' WHERE n = 'x'"),
+            "UPDATE t SET note = '?' WHERE n = '?'"
+        );
+        assert_eq!(redact("SELECT 'unterminated"), "SELECT '?'");
+    }
+
+    #[tokio::test]
+    async fn test_threshold_toggles() {
+        assert_eq!(threshold(), None);
+
+        set_threshold(Some(Duration::from_millis(250)));
+        assert_eq!(threshold(), Some(Duration::from_millis(250)));
+        assert_eq!(observe("primary", "SELECT 1", async { 7 }).await, 7);
+
+        set_threshold(None);
+        assert_eq!(threshold(), None);
+        assert_eq!(observe("primary", "SELECT 1", async { 8 }).await, 8);
+    }
+}
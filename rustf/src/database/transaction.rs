@@ -0,0 +1,271 @@
+//! Transaction handle with after-commit hooks
+//!
+//! Side effects such as sending a welcome email should only happen once the
+//! data they describe is durable. `Transaction` wraps a sqlx transaction and
+//! queues work registered with `after_commit` / `emit_after_commit` until the
+//! transaction commits. On rollback, or if the handle is dropped without
+//! committing, the queue is discarded.
+//!
+//! ```rust,ignore
+//! use rustf::database::Transaction;
+//!
+//! let mut tx = Transaction::begin(&*DB::pg_pool()?).await?;
+//!
+//! sqlx::query("INSERT INTO users (email) VALUES ($1)")
+//!     .bind(&email)
+//!     .execute(&mut *tx)
+//!     .await?;
+//! tx.emit_after_commit("user.created", Some(json!({ "email": email })));
+//!
+//! tx.commit().await?; // `user.created` fires here
+//! ```
+//!
+//! Model write events (`BaseModel::EMIT_EVENTS`) go through [`emit_event`]:
+//! while a transaction begun on the same task is open they join its queue,
+//! otherwise they fire right away. Request handlers run inside [`scope`]
+//! already; background tasks that open transactions wrap their work in it.
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+/// Deferred work run after a successful commit
+type AfterCommitHook = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send>;
+
+/// Queue of hooks shared between a transaction and its task's open-transaction stack
+type HookQueue = Arc<Mutex<Vec<AfterCommitHook>>>;
+
+tokio::task_local! {
+    /// Queues of the transactions open on this task, innermost last
+    static OPEN_TRANSACTIONS: RefCell<Vec<Weak<Mutex<Vec<AfterCommitHook>>>>>;
+}
+
+/// Run a future in which open transactions capture [`emit_event`]
+pub async fn scope<F: Future>(fut: F) -> F::Output {
+    OPEN_TRANSACTIONS.scope(RefCell::new(Vec::new()), fut).await
+}
+
+/// Emit an event once the innermost open transaction of this task commits
+///
+/// Without an open transaction (or outside [`scope`]) the event is emitted
+/// immediately with `events::emit_global`.
+pub async fn emit_event(event: impl Into<String>, data: Option<Value>) -> Result<()> {
+    let event = event.into();
+    let queue = OPEN_TRANSACTIONS
+        .try_with(|open| {
+            let mut open = open.borrow_mut();
+            // Queues of committed, rolled back or dropped transactions are gone
+            open.retain(|queue| queue.strong_count() > 0);
+            open.last().and_then(Weak::upgrade)
+        })
+        .ok()
+        .flatten();
+
+    match queue {
+        Some(queue) => {
+            queue
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(emit_hook(event, data));
+            Ok(())
+        }
+        None => crate::events::emit_global(&event, data).await,
+    }
+}
+
+fn emit_hook(event: String, data: Option<Value>) -> AfterCommitHook {
+    Box::new(move || Box::pin(async move { crate::events::emit_global(&event, data).await }))
+}
+
+/// A database transaction that defers hooks until commit
+pub struct Transaction<DB: sqlx::Database> {
+    inner: sqlx::Transaction<'static, DB>,
+    after_commit: HookQueue,
+}
+
+impl<DB: sqlx::Database> Transaction<DB> {
+    /// Begin a new transaction on the given pool
+    pub async fn begin(pool: &sqlx::Pool<DB>) -> Result<Self> {
+        let inner = pool
+            .begin()
+            .await
+            .map_err(|e| Error::database_transaction(format!("Failed to begin: {}", e)))?;
+
+        Ok(Self::from_sqlx(inner))
+    }
+
+    /// Wrap an existing sqlx transaction
+    ///
+    /// Inside [`scope`], the transaction becomes the innermost open one of the
+    /// current task until it is committed, rolled back or dropped.
+    pub fn from_sqlx(inner: sqlx::Transaction<'static, DB>) -> Self {
+        let after_commit = HookQueue::default();
+        let _ = OPEN_TRANSACTIONS.try_with(|open| {
+            open.borrow_mut().push(Arc::downgrade(&after_commit));
+        });
+
+        Self {
+            inner,
+            after_commit,
+        }
+    }
+
+    /// Queue a hook to run after the transaction commits
+    ///
+    /// Hooks run in registration order. A failing hook is logged and does not
+    /// stop the remaining hooks, since the data is already committed.
+    pub fn after_commit<F, Fut>(&mut self, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.hooks().push(Box::new(move || Box::pin(hook())));
+    }
+
+    /// Queue an application event to be emitted after the transaction commits
+    pub fn emit_after_commit(&mut self, event: impl Into<String>, data: Option<Value>) {
+        self.hooks().push(emit_hook(event.into(), data));
+    }
+
+    /// Number of hooks waiting for commit
+    pub fn pending_hooks(&self) -> usize {
+        self.hooks().len()
+    }
+
+    fn hooks(&self) -> std::sync::MutexGuard<'_, Vec<AfterCommitHook>> {
+        self.after_commit
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Commit the transaction, then run the queued hooks
+    pub async fn commit(self) -> Result<()> {
+        let Self {
+            inner,
+            after_commit,
+        } = self;
+
+        inner
+            .commit()
+            .await
+            .map_err(|e| Error::database_transaction(format!("Failed to commit: {}", e)))?;
+
+        let hooks =
+            std::mem::take(&mut *after_commit.lock().unwrap_or_else(PoisonError::into_inner));
+        drop(after_commit);
+        run_hooks(hooks).await;
+        Ok(())
+    }
+
+    /// Roll back the transaction and discard the queued hooks
+    pub async fn rollback(self) -> Result<()> {
+        let pending = self.pending_hooks();
+        if pending > 0 {
+            log::debug!("Discarding {} after-commit hook(s) on rollback", pending);
+        }
+
+        self.inner
+            .rollback()
+            .await
+            .map_err(|e| Error::database_transaction(format!("Failed to roll back: {}", e)))
+    }
+}
+
+impl<DB: sqlx::Database> Deref for Transaction<DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<DB: sqlx::Database> DerefMut for Transaction<DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+async fn run_hooks(hooks: Vec<AfterCommitHook>) {
+    for hook in hooks {
+        if let Err(e) = hook().await {
+            log::error!("After-commit hook failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    async fn memory_pool() -> sqlx::SqlitePool {
+        sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_after_commit() {
+        let pool = memory_pool().await;
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let mut tx = Transaction::begin(&pool).await.unwrap();
+        let hook_counter = counter.clone();
+        tx.after_commit(move || async move {
+            hook_counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert_eq!(tx.pending_hooks(), 1);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        tx.commit().await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hooks_discarded_on_rollback() {
+        let pool = memory_pool().await;
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let mut tx = Transaction::begin(&pool).await.unwrap();
+        let hook_counter = counter.clone();
+        tx.after_commit(move || async move {
+            hook_counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        tx.rollback().await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_events_join_the_innermost_open_transaction() {
+        let pool = memory_pool().await;
+
+        scope(async {
+            let outer = Transaction::begin(&pool).await.unwrap();
+            emit_event("users.create", None).await.unwrap();
+            assert_eq!(outer.pending_hooks(), 1);
+
+            let inner = Transaction::begin(&pool).await.unwrap();
+            emit_event("users.update", None).await.unwrap();
+            assert_eq!(inner.pending_hooks(), 1);
+            inner.rollback().await.unwrap();
+
+            // Back to the outer transaction once the inner one is gone
+            emit_event("users.delete", None).await.unwrap();
+            assert_eq!(outer.pending_hooks(), 2);
+            outer.commit().await.unwrap();
+
+            // Nothing is open any more, so the event is emitted right away
+            emit_event("users.create", None).await.unwrap();
+            OPEN_TRANSACTIONS
+                .with(|open| assert!(open.borrow().iter().all(|queue| queue.strong_count() == 0)));
+        })
+        .await;
+    }
+}
@@ -3,9 +3,11 @@
 //! This module provides global database access with support for both
 //! single database (legacy) and multiple database connections.
 
-use crate::database::config::DatabasesConfig;
+use crate::database::config::{DatabaseConnectionConfig, DatabasesConfig};
 use crate::database::types::SqlValue;
-use crate::database::{adapters::*, DatabaseAdapter, DatabaseRegistry};
+use crate::database::{
+    adapters::*, DatabaseAdapter, DatabaseRegistry, Statement, StatementCacheStats,
+};
 use crate::error::{Error, Result};
 use crate::models::query_builder::{AnyDatabase, DatabaseBackend, QueryBuilder};
 use once_cell::sync::OnceCell;
@@ -115,21 +117,27 @@ impl DB {
                 Self::sanitize_url(&db_config.url)
             );
 
-            let adapter = Self::create_adapter(name, &db_config.url).await?;
+            let adapter = Self::create_adapter_with_config(name, db_config).await?;
             let is_default = default_name.as_ref().map(|d| d == name).unwrap_or(false);
 
+            if db_config.warm_up && !db_config.lazy {
+                let connections = adapter.warm_up().await?;
+                log::info!(
+                    "Database '{}' warmed up with {} connection(s)",
+                    name,
+                    connections
+                );
+            }
+
             registry.register(name.clone(), adapter, is_default).await?;
         }
 
         // If we have a default database, also set it in the legacy DATABASE global
         if let Some(default_name) = default_name {
-            if let Some(_adapter) = registry.get(&default_name).await {
-                // Convert adapter back to AnyDatabase for legacy compatibility
-                // This is a bit of a hack but maintains backward compatibility
-                if let Some(default_config) = config.get(&default_name) {
-                    if let Ok(db) = AnyDatabase::connect(&default_config.url).await {
-                        let _ = DATABASE.set(Some(Arc::new(db)));
-                    }
+            if let Some(adapter) = registry.get(&default_name).await {
+                // Share the adapter's pool so lazy/warm-up settings apply to legacy access too
+                if let Some(db) = Self::any_database(adapter.as_ref()) {
+                    let _ = DATABASE.set(Some(Arc::new(db)));
                 }
             }
         }
@@ -138,7 +146,7 @@ impl DB {
     }
 
     /// Create a database adapter based on the URL
-    async fn create_adapter(name: &str, url: &str) -> Result<Box<dyn DatabaseAdapter>> {
+    pub(crate) async fn create_adapter(name: &str, url: &str) -> Result<Box<dyn DatabaseAdapter>> {
         if url.starts_with("postgresql://") || url.starts_with("postgres://") {
             let adapter = PostgresAdapter::new(name, url).await?;
             Ok(Box::new(adapter))
@@ -156,6 +164,54 @@ impl DB {
         }
     }
 
+    /// Create a database adapter honouring the pool settings of `config`
+    async fn create_adapter_with_config(
+        name: &str,
+        config: &DatabaseConnectionConfig,
+    ) -> Result<Box<dyn DatabaseAdapter>> {
+        let url = config.url.as_str();
+        if url.starts_with("postgresql://") || url.starts_with("postgres://") {
+            let adapter = PostgresAdapter::with_config(name, config).await?;
+            Ok(Box::new(adapter))
+        } else if url.starts_with("mysql://") || url.starts_with("mariadb://") {
+            let adapter = MySqlAdapter::with_config(name, config).await?;
+            Ok(Box::new(adapter))
+        } else if url.starts_with("sqlite://") {
+            let adapter = SqliteAdapter::with_config(name, config).await?;
+            Ok(Box::new(adapter))
+        } else {
+            Err(Error::template(format!(
+                "Unsupported database URL scheme: {}",
+                url
+            )))
+        }
+    }
+
+    /// Wrap an adapter's pool as an AnyDatabase for the legacy global
+    fn any_database(adapter: &dyn DatabaseAdapter) -> Option<AnyDatabase> {
+        let any = adapter.as_any();
+        if let Some(postgres) = any.downcast_ref::<PostgresAdapter>() {
+            Some(AnyDatabase::Postgres(postgres.pool().clone()))
+        } else if let Some(mysql) = any.downcast_ref::<MySqlAdapter>() {
+            Some(AnyDatabase::MySQL(mysql.pool().clone()))
+        } else {
+            any.downcast_ref::<SqliteAdapter>()
+                .map(|sqlite| AnyDatabase::SQLite(sqlite.pool().clone()))
+        }
+    }
+
+    /// Open and validate connections for every registered database
+    ///
+    /// Useful after `init_registry` when warm-up should happen at a specific
+    /// point of startup rather than during registration.
+    ///
+    /// # Returns
+    /// * `Ok(count)` - Total number of connections validated
+    /// * `Err(Error)` - If the registry is not initialized or a connection fails
+    pub async fn warm_up() -> Result<u32> {
+        Self::get_registry()?.warm_up().await
+    }
+
     /// Get access to the database registry
     fn get_registry() -> Result<Arc<DatabaseRegistry>> {
         REGISTRY
@@ -196,6 +252,11 @@ impl DB {
         Self::r#use(name).await
     }
 
+    /// Get the adapter of the default database
+    pub async fn default_adapter() -> Result<Box<dyn DatabaseAdapter>> {
+        Self::get_registry()?.get_default().await
+    }
+
     /// Get a query builder for the configured database
     ///
     /// Returns a QueryBuilder that's pre-configured for the current database backend.
@@ -282,6 +343,30 @@ impl DB {
         Self::connection().map(|db| db.backend())
     }
 
+    /// Run a query, recorded as a child span of the current request when the
+    /// `otel` feature is enabled
+    async fn traced<T>(
+        sql: &str,
+        query: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        #[cfg(feature = "otel")]
+        {
+            let system = match Self::backend() {
+                Some(DatabaseBackend::Postgres) => "postgresql",
+                Some(DatabaseBackend::MySQL) => "mysql",
+                Some(DatabaseBackend::MariaDB) => "mariadb",
+                Some(DatabaseBackend::SQLite) => "sqlite",
+                None => "other_sql",
+            };
+            crate::telemetry::trace_query(system, sql, query).await
+        }
+        #[cfg(not(feature = "otel"))]
+        {
+            let _ = sql;
+            query.await
+        }
+    }
+
     /// Get the PostgreSQL pool if database is PostgreSQL
     ///
     /// # Returns
@@ -341,6 +426,10 @@ impl DB {
     /// * `Ok(u64)` - Number of rows affected
     /// * `Err(Error)` - If execution fails
     pub async fn execute_with_params(sql: &str, params: Vec<SqlValue>) -> Result<u64> {
+        Self::traced(sql, Self::execute_with_params_untraced(sql, params)).await
+    }
+
+    async fn execute_with_params_untraced(sql: &str, params: Vec<SqlValue>) -> Result<u64> {
         // Try registry first
         if let Ok(registry) = Self::get_registry() {
             if let Ok(adapter) = registry.get_default().await {
@@ -376,6 +465,78 @@ impl DB {
         }
     }
 
+    /// Execute several parameterized statements in a single transaction
+    ///
+    /// The statements run in order on one connection and are committed
+    /// together; if any of them fails, none of them take effect. Use this for
+    /// denormalized updates that must touch several tables atomically.
+    ///
+    /// # Arguments
+    /// * `statements` - Statements to execute, in order
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u64>)` - Rows affected by each statement, in order
+    /// * `Err(Error)` - If a statement fails (the transaction is rolled back)
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// use rustf::database::Statement;
+    ///
+    /// let counts = DB::batch(vec![
+    ///     Statement::new(
+    ///         "UPDATE posts SET author_name = $1 WHERE author_id = $2",
+    ///         vec![name.clone().into(), user_id.into()],
+    ///     ),
+    ///     Statement::new(
+    ///         "UPDATE comments SET author_name = $1 WHERE author_id = $2",
+    ///         vec![name.into(), user_id.into()],
+    ///     ),
+    /// ])
+    /// .await?;
+    /// ```
+    pub async fn batch(statements: Vec<Statement>) -> Result<Vec<u64>> {
+        Self::execute_batch(statements, true).await
+    }
+
+    /// Execute several parameterized statements without a transaction
+    ///
+    /// Each statement commits on its own. Execution stops at the first
+    /// failure, leaving earlier statements applied.
+    pub async fn batch_without_transaction(statements: Vec<Statement>) -> Result<Vec<u64>> {
+        Self::execute_batch(statements, false).await
+    }
+
+    async fn execute_batch(statements: Vec<Statement>, transaction: bool) -> Result<Vec<u64>> {
+        // Try registry first
+        if let Ok(registry) = Self::get_registry() {
+            if let Ok(adapter) = registry.get_default().await {
+                return adapter.execute_batch(statements, transaction).await;
+            }
+        }
+
+        // Fallback to legacy connection
+        let db = Self::connection()
+            .ok_or_else(|| Error::template("Database not configured".to_string()))?;
+
+        match db.as_ref() {
+            AnyDatabase::Postgres(pool) => {
+                PostgresAdapter::from_pool("default", pool.clone())
+                    .execute_batch(statements, transaction)
+                    .await
+            }
+            AnyDatabase::MySQL(pool) => {
+                MySqlAdapter::from_pool("default", pool.clone())
+                    .execute_batch(statements, transaction)
+                    .await
+            }
+            AnyDatabase::SQLite(pool) => {
+                SqliteAdapter::from_pool("default", pool.clone())
+                    .execute_batch(statements, transaction)
+                    .await
+            }
+        }
+    }
+
     /// Fetch all rows from a query with parameters
     ///
     /// Returns results as JSON values for flexibility.
@@ -390,6 +551,13 @@ impl DB {
     pub async fn fetch_all_with_params(
         sql: &str,
         params: Vec<SqlValue>,
+    ) -> Result<Vec<serde_json::Value>> {
+        Self::traced(sql, Self::fetch_all_with_params_untraced(sql, params)).await
+    }
+
+    async fn fetch_all_with_params_untraced(
+        sql: &str,
+        params: Vec<SqlValue>,
     ) -> Result<Vec<serde_json::Value>> {
         // Try registry first
         if let Ok(registry) = Self::get_registry() {
@@ -422,6 +590,99 @@ impl DB {
         }
     }
 
+    /// Fetch all rows without caching the prepared statement
+    ///
+    /// For one-off queries such as reports or ad-hoc analytics: the statement
+    /// is prepared, run and discarded instead of taking a slot in the
+    /// connection's prepared-statement cache.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let totals = DB::query_unprepared(
+    ///     "SELECT region, SUM(amount) AS total FROM orders WHERE created_at >= $1 GROUP BY region",
+    ///     vec![since.into()],
+    /// )
+    /// .await?;
+    /// ```
+    pub async fn query_unprepared(
+        sql: &str,
+        params: Vec<SqlValue>,
+    ) -> Result<Vec<serde_json::Value>> {
+        Self::traced(sql, Self::query_unprepared_untraced(sql, params)).await
+    }
+
+    async fn query_unprepared_untraced(
+        sql: &str,
+        params: Vec<SqlValue>,
+    ) -> Result<Vec<serde_json::Value>> {
+        // Try registry first
+        if let Ok(registry) = Self::get_registry() {
+            if let Ok(adapter) = registry.get_default().await {
+                return adapter.fetch_all_unprepared(sql, params).await;
+            }
+        }
+
+        // Fallback to legacy connection
+        let db = Self::connection()
+            .ok_or_else(|| Error::template("Database not configured".to_string()))?;
+
+        match db.as_ref() {
+            AnyDatabase::Postgres(pool) => {
+                PostgresAdapter::from_pool("default", pool.clone())
+                    .fetch_all_unprepared(sql, params)
+                    .await
+            }
+            AnyDatabase::MySQL(pool) => {
+                MySqlAdapter::from_pool("default", pool.clone())
+                    .fetch_all_unprepared(sql, params)
+                    .await
+            }
+            AnyDatabase::SQLite(pool) => {
+                SqliteAdapter::from_pool("default", pool.clone())
+                    .fetch_all_unprepared(sql, params)
+                    .await
+            }
+        }
+    }
+
+    /// Prepared-statement cache usage of the default database
+    ///
+    /// Use `DB::adapter(name)` and `statement_cache_stats()` on the adapter
+    /// for other registered databases.
+    ///
+    /// # Returns
+    /// * `Ok(Some(stats))` - Usage sampled from one idle pooled connection
+    /// * `Ok(None)` - The adapter does not cache prepared statements
+    /// * `Err(Error)` - If no database is configured
+    pub async fn statement_cache_stats() -> Result<Option<StatementCacheStats>> {
+        if let Ok(registry) = Self::get_registry() {
+            if let Ok(adapter) = registry.get_default().await {
+                return adapter.statement_cache_stats().await;
+            }
+        }
+
+        let db = Self::connection()
+            .ok_or_else(|| Error::template("Database not configured".to_string()))?;
+
+        match db.as_ref() {
+            AnyDatabase::Postgres(pool) => {
+                PostgresAdapter::from_pool("default", pool.clone())
+                    .statement_cache_stats()
+                    .await
+            }
+            AnyDatabase::MySQL(pool) => {
+                MySqlAdapter::from_pool("default", pool.clone())
+                    .statement_cache_stats()
+                    .await
+            }
+            AnyDatabase::SQLite(pool) => {
+                SqliteAdapter::from_pool("default", pool.clone())
+                    .statement_cache_stats()
+                    .await
+            }
+        }
+    }
+
     /// Fetch one row from a query with parameters
     ///
     /// Returns result as JSON value for flexibility.
@@ -436,6 +697,13 @@ impl DB {
     pub async fn fetch_one_with_params(
         sql: &str,
         params: Vec<SqlValue>,
+    ) -> Result<Option<serde_json::Value>> {
+        Self::traced(sql, Self::fetch_one_with_params_untraced(sql, params)).await
+    }
+
+    async fn fetch_one_with_params_untraced(
+        sql: &str,
+        params: Vec<SqlValue>,
     ) -> Result<Option<serde_json::Value>> {
         // Try registry first
         if let Ok(registry) = Self::get_registry() {
@@ -488,6 +756,19 @@ impl DB {
         params: Vec<SqlValue>,
         table: &str,
         id_field: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        Self::traced(
+            sql,
+            Self::execute_insert_returning_untraced(sql, params, table, id_field),
+        )
+        .await
+    }
+
+    async fn execute_insert_returning_untraced(
+        sql: &str,
+        params: Vec<SqlValue>,
+        table: &str,
+        id_field: &str,
     ) -> Result<Option<serde_json::Value>> {
         // Try registry first
         if let Ok(registry) = Self::get_registry() {
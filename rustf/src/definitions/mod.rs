@@ -32,7 +32,7 @@ pub mod validators;
 
 pub use helpers::{Helper, HelperRegistry};
 pub use traits::*;
-pub use validators::{Validator, ValidatorRegistry};
+pub use validators::{AsyncValidator, Validator, ValidatorRegistry};
 
 use once_cell::sync::Lazy;
 use std::sync::Arc;
@@ -78,6 +78,15 @@ impl Definitions {
         self.validators.register(name, validator);
     }
 
+    /// Register an async validator
+    pub fn register_async_validator(
+        &mut self,
+        name: &str,
+        validator: impl AsyncValidator + 'static,
+    ) {
+        self.validators.register_async(name, validator);
+    }
+
     /// Check if a helper exists
     pub fn has_helper(&self, name: &str) -> bool {
         self.helpers.exists(name)
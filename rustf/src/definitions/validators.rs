@@ -4,9 +4,11 @@
 //! for data validation in models, forms, and API requests.
 
 use crate::error::{Error, Result};
+use async_trait::async_trait;
 use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
 /// Result type for validators
@@ -65,9 +67,84 @@ where
     }
 }
 
+/// Trait for validators that need to await I/O
+///
+/// Use this for checks that cannot be answered from the value alone, such as
+/// "email must be unique", which needs a database round-trip.
+///
+/// ```rust,ignore
+/// struct UniqueEmail;
+///
+/// #[async_trait]
+/// impl AsyncValidator for UniqueEmail {
+///     async fn validate(&self, value: &Value, _options: Option<&Value>) -> ValidationResult {
+///         let email = value.get("email").and_then(|v| v.as_str()).unwrap_or("");
+///         if Users::query()?.where_eq("email", email).exists().await? {
+///             return Err(Error::validation("Email is already registered"));
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait AsyncValidator: Send + Sync {
+    /// Validate a value
+    ///
+    /// # Arguments
+    /// * `value` - The value to validate
+    /// * `options` - Optional validation options
+    ///
+    /// # Returns
+    /// Ok(()) if valid, Err with validation message if invalid
+    async fn validate(&self, value: &Value, options: Option<&Value>) -> ValidationResult;
+
+    /// Get the validator's name
+    fn name(&self) -> &str {
+        "unnamed"
+    }
+
+    /// Get the validator's description
+    fn description(&self) -> &str {
+        "No description available"
+    }
+}
+
+/// Function-based async validator implementation
+///
+/// The closure receives owned copies so the returned future can be `'static`.
+struct AsyncFunctionValidator<F> {
+    func: F,
+    name: String,
+    description: String,
+}
+
+#[async_trait]
+impl<F, Fut> AsyncValidator for AsyncFunctionValidator<F>
+where
+    F: Fn(Value, Option<Value>) -> Fut + Send + Sync,
+    Fut: Future<Output = ValidationResult> + Send + 'static,
+{
+    async fn validate(&self, value: &Value, options: Option<&Value>) -> ValidationResult {
+        (self.func)(value.clone(), options.cloned()).await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
 /// Registry for validators
+///
+/// Holds both synchronous [`Validator`]s and [`AsyncValidator`]s under a
+/// single namespace; registering a name replaces any validator of either
+/// kind with that name.
 pub struct ValidatorRegistry {
     validators: HashMap<String, Arc<dyn Validator>>,
+    async_validators: HashMap<String, Arc<dyn AsyncValidator>>,
 }
 
 // Manual Debug implementation since Arc<dyn Validator> doesn't implement Debug
@@ -79,6 +156,10 @@ impl std::fmt::Debug for ValidatorRegistry {
                 "validator_names",
                 &self.validators.keys().collect::<Vec<_>>(),
             )
+            .field(
+                "async_validator_names",
+                &self.async_validators.keys().collect::<Vec<_>>(),
+            )
             .finish()
     }
 }
@@ -88,6 +169,7 @@ impl ValidatorRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             validators: HashMap::new(),
+            async_validators: HashMap::new(),
         };
 
         // Register built-in validators
@@ -99,10 +181,19 @@ impl ValidatorRegistry {
     /// Register a validator
     pub fn register(&mut self, name: &str, validator: impl Validator + 'static) {
         log::debug!("Registering validator: {}", name);
+        self.async_validators.remove(name);
         self.validators
             .insert(name.to_string(), Arc::new(validator));
     }
 
+    /// Register an async validator
+    pub fn register_async(&mut self, name: &str, validator: impl AsyncValidator + 'static) {
+        log::debug!("Registering async validator: {}", name);
+        self.validators.remove(name);
+        self.async_validators
+            .insert(name.to_string(), Arc::new(validator));
+    }
+
     /// Register a function as a validator
     pub fn register_fn<F>(&mut self, name: &str, description: &str, func: F)
     where
@@ -116,24 +207,52 @@ impl ValidatorRegistry {
         self.register(name, validator);
     }
 
+    /// Register an async function as a validator
+    pub fn register_async_fn<F, Fut>(&mut self, name: &str, description: &str, func: F)
+    where
+        F: Fn(Value, Option<Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ValidationResult> + Send + 'static,
+    {
+        let validator = AsyncFunctionValidator {
+            func,
+            name: name.to_string(),
+            description: description.to_string(),
+        };
+        self.register_async(name, validator);
+    }
+
     /// Get a validator by name
     pub fn get(&self, name: &str) -> Option<Arc<dyn Validator>> {
         self.validators.get(name).cloned()
     }
 
-    /// Check if a validator exists
+    /// Get an async validator by name
+    pub fn get_async(&self, name: &str) -> Option<Arc<dyn AsyncValidator>> {
+        self.async_validators.get(name).cloned()
+    }
+
+    /// Check if a validator of either kind exists
     pub fn exists(&self, name: &str) -> bool {
-        self.validators.contains_key(name)
+        self.validators.contains_key(name) || self.async_validators.contains_key(name)
+    }
+
+    /// Check if the named validator is async
+    pub fn is_async(&self, name: &str) -> bool {
+        self.async_validators.contains_key(name)
     }
 
     /// Get the number of registered validators
     pub fn count(&self) -> usize {
-        self.validators.len()
+        self.validators.len() + self.async_validators.len()
     }
 
     /// List all registered validator names
     pub fn list(&self) -> Vec<String> {
-        self.validators.keys().cloned().collect()
+        self.validators
+            .keys()
+            .chain(self.async_validators.keys())
+            .cloned()
+            .collect()
     }
 
     /// Validate a value using a named validator
@@ -145,6 +264,12 @@ impl ValidatorRegistry {
     ) -> ValidationResult {
         match self.validators.get(validator_name) {
             Some(validator) => validator.validate(value, options),
+            None if self.async_validators.contains_key(validator_name) => {
+                Err(Error::internal(format!(
+                    "Validator '{}' is async; use validate_async",
+                    validator_name
+                )))
+            }
             None => Err(Error::internal(format!(
                 "Validator '{}' not found",
                 validator_name
@@ -152,6 +277,23 @@ impl ValidatorRegistry {
         }
     }
 
+    /// Validate a value using a named validator of either kind
+    ///
+    /// Sync validators run inline. Note that this borrows the registry across
+    /// the await; callers holding the definitions lock should clone the
+    /// validator out with [`get_async`](Self::get_async) first.
+    pub async fn validate_async(
+        &self,
+        validator_name: &str,
+        value: &Value,
+        options: Option<&Value>,
+    ) -> ValidationResult {
+        match self.async_validators.get(validator_name) {
+            Some(validator) => validator.validate(value, options).await,
+            None => self.validate(validator_name, value, options),
+        }
+    }
+
     /// Register built-in validators
     fn register_builtin_validators(&mut self) {
         // Required validator
@@ -619,4 +761,39 @@ mod tests {
             .validate("range", &json!(101), Some(&options))
             .is_err());
     }
+
+    #[tokio::test]
+    async fn test_async_validator() {
+        let mut registry = ValidatorRegistry::new();
+        registry.register_async_fn("unique_email", "Email not taken", |value, _| async move {
+            match value.as_str() {
+                Some("taken@example.com") => Err(Error::validation("Email is already registered")),
+                _ => Ok(()),
+            }
+        });
+
+        assert!(registry.exists("unique_email"));
+        assert!(registry.is_async("unique_email"));
+        assert!(registry
+            .validate_async("unique_email", &json!("new@example.com"), None)
+            .await
+            .is_ok());
+        assert!(registry
+            .validate_async("unique_email", &json!("taken@example.com"), None)
+            .await
+            .is_err());
+        // Sync validators are reachable through the async entry point
+        assert!(registry
+            .validate_async("email", &json!("invalid.email"), None)
+            .await
+            .is_err());
+        // ...but async ones cannot run synchronously
+        assert!(registry
+            .validate("unique_email", &json!("new@example.com"), None)
+            .is_err());
+
+        // Re-registering a name as sync replaces the async validator
+        registry.register_fn("unique_email", "", |_, _| Ok(()));
+        assert!(!registry.is_async("unique_email"));
+    }
 }
@@ -0,0 +1,176 @@
+//! Development mode: live reload of views
+//!
+//! With the `dev` feature enabled and a non-production environment,
+//! `app.start()` watches the views directory. When a template changes, the
+//! template cache is cleared and every open page is told to reload through a
+//! server-sent events endpoint. A small script connecting to that endpoint is
+//! injected into HTML responses.
+//!
+//! Only views hot-reload; Rust code changes still need a recompile. The
+//! injected script also reloads the page once the server comes back after a
+//! restart, which pairs well with `cargo watch -x run`.
+//!
+//! ```toml
+//! [dependencies]
+//! rustf = { version = "0.1", features = ["dev"] }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::http::sse::DEFAULT_KEEP_ALIVE;
+use crate::http::{Response, SseEvent};
+use crate::views::ViewEngine;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Path of the server-sent events endpoint used by the reload script
+pub const LIVE_RELOAD_PATH: &str = "/__rustf/livereload";
+
+/// Editors write files in several steps; reloads closer than this are merged
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Script injected before `</body>` in HTML responses
+const RELOAD_SCRIPT: &str = r#"<script>(function(){var lost=false,es=new EventSource("/__rustf/livereload");es.addEventListener("reload",function(){location.reload();});es.onerror=function(){lost=true;};es.onopen=function(){if(lost){location.reload();}};})();</script>"#;
+
+/// Watches the views directory and notifies connected browsers
+pub struct LiveReload {
+    sender: broadcast::Sender<()>,
+    _watcher: RecommendedWatcher,
+}
+
+impl LiveReload {
+    /// Start watching `directory`, clearing the view cache on every change
+    pub fn watch_views(directory: impl AsRef<Path>, views: Arc<ViewEngine>) -> Result<Self> {
+        let (sender, _) = broadcast::channel(16);
+        let reload_sender = sender.clone();
+        let last_reload: Mutex<Option<Instant>> = Mutex::new(None);
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    return;
+                }
+
+                views.clear_cache();
+
+                if let Ok(mut last) = last_reload.lock() {
+                    if last.is_some_and(|at| at.elapsed() < DEBOUNCE) {
+                        return;
+                    }
+                    *last = Some(Instant::now());
+                }
+
+                log::info!("View changed: {:?} - reloading browsers", event.paths);
+                // No receivers just means no page is open
+                let _ = reload_sender.send(());
+            })
+            .map_err(|e| Error::internal(format!("Failed to create view watcher: {}", e)))?;
+
+        watcher
+            .watch(directory.as_ref(), RecursiveMode::Recursive)
+            .map_err(|e| {
+                Error::internal(format!(
+                    "Failed to watch views directory '{}': {}",
+                    directory.as_ref().display(),
+                    e
+                ))
+            })?;
+
+        Ok(Self {
+            sender,
+            _watcher: watcher,
+        })
+    }
+
+    /// Trigger a reload in every connected browser
+    pub fn reload(&self) {
+        let _ = self.sender.send(());
+    }
+
+    /// Event stream response for `LIVE_RELOAD_PATH`
+    pub fn response(&self) -> Response {
+        let receiver = self.sender.subscribe();
+        let events = futures::stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                // A lagged receiver missed reloads, which still means "reload"
+                Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                    Some((SseEvent::new("reload").event("reload"), receiver))
+                }
+                Err(broadcast::error::RecvError::Closed) => None,
+            }
+        });
+
+        Response::sse(events, Some(DEFAULT_KEEP_ALIVE))
+    }
+}
+
+/// Inject the reload script into a buffered HTML response
+pub fn inject_script(response: &mut Response) {
+    let is_html = response.headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("content-type") && value.starts_with("text/html")
+    });
+    if !is_html || response.is_streaming() {
+        return;
+    }
+
+    let Ok(html) = std::str::from_utf8(&response.body) else {
+        return;
+    };
+    let position = html
+        .to_ascii_lowercase()
+        .rfind("</body>")
+        .unwrap_or(html.len());
+
+    let mut body = Vec::with_capacity(response.body.len() + RELOAD_SCRIPT.len());
+    body.extend_from_slice(&response.body[..position]);
+    body.extend_from_slice(RELOAD_SCRIPT.as_bytes());
+    body.extend_from_slice(&response.body[position..]);
+    response.body = body;
+
+    response
+        .headers
+        .retain(|(name, _)| !name.eq_ignore_ascii_case("content-length"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_script_before_body_end() {
+        let mut response = Response::ok()
+            .with_header("Content-Type", "text/html; charset=utf-8")
+            .with_header("Content-Length", "33")
+            .with_body(b"<html><BODY>hi</BODY></html>".to_vec());
+
+        inject_script(&mut response);
+
+        let html = String::from_utf8(response.body.clone()).unwrap();
+        assert!(html.starts_with("<html><BODY>hi<script>"));
+        assert!(html.ends_with("</script></BODY></html>"));
+        assert!(html.contains(LIVE_RELOAD_PATH));
+        assert!(!response
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-length")));
+    }
+
+    #[test]
+    fn test_inject_script_skips_non_html() {
+        let mut response = Response::ok()
+            .with_header("Content-Type", "application/json")
+            .with_body(b"{}".to_vec());
+
+        inject_script(&mut response);
+
+        assert_eq!(response.body, b"{}");
+    }
+}
@@ -370,7 +370,7 @@ impl ErrorLogger {
             Error::Template(_) => "Template processing error".to_string(),
             Error::Json(_) => "Data processing error".to_string(),
             Error::Session(_) => "Session error".to_string(),
-            Error::Validation(_) => "Validation error".to_string(),
+            Error::Validation(_) | Error::ValidationFields { .. } => "Validation error".to_string(),
             Error::Io(_) => "File system error".to_string(),
             _ => "Internal server error".to_string(),
         }
@@ -385,7 +385,7 @@ impl ErrorLogger {
             Error::RouteNotFound(_) => "Routing".to_string(),
             Error::ModelNotFound(_) => "Model".to_string(),
             Error::Session(_) => "Session".to_string(),
-            Error::Validation(_) => "Validation".to_string(),
+            Error::Validation(_) | Error::ValidationFields { .. } => "Validation".to_string(),
             Error::InvalidInput(_) => "Input".to_string(),
             Error::Io(_) => "IO".to_string(),
             Error::Internal(_) => "Internal".to_string(),
@@ -394,10 +394,14 @@ impl ErrorLogger {
             Error::DatabaseTransaction(_) => "DatabaseTransaction".to_string(),
             Error::DatabaseMigration(_) => "DatabaseMigration".to_string(),
             Error::DatabasePool(_) => "DatabasePool".to_string(),
+            Error::DatabaseConflict(_) => "DatabaseConflict".to_string(),
+            Error::ConstraintViolation { .. } => "ConstraintViolation".to_string(),
+            Error::StaleModel(_) => "StaleModel".to_string(),
             Error::Network(_) => "Network".to_string(),
             Error::Authentication(_) => "Authentication".to_string(),
             Error::Authorization(_) => "Authorization".to_string(),
             Error::RateLimit(_) => "RateLimit".to_string(),
+            Error::PayloadTooLarge(_) => "PayloadTooLarge".to_string(),
             Error::ExternalService { .. } => "ExternalService".to_string(),
             Error::Timeout(_) => "Timeout".to_string(),
             Error::WithContext { .. } => "Context".to_string(),
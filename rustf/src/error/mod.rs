@@ -3,6 +3,7 @@ use thiserror::Error;
 pub mod context;
 pub mod logging;
 pub mod pages;
+pub mod problem;
 pub mod retry;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -13,8 +14,11 @@ pub use pages::{CheckStatus, ErrorPages, HealthCheck, HealthCheckResult};
 // Re-export logging types for easy access
 pub use logging::{ErrorLogger, LogConfig, LogEntry, LogLevel, LogOutput, RequestContext};
 
+// Re-export problem details types
+pub use problem::FieldError;
+
 // Re-export retry logic
-pub use retry::{with_retry, RetryPolicy, RetryableError};
+pub use retry::{with_retry, with_retry_if, RetryPolicy, RetryableError};
 
 // Re-export context helpers
 pub use context::{ErrorChain, ErrorContext};
@@ -43,6 +47,12 @@ pub enum Error {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Validation error: {message}")]
+    ValidationFields {
+        message: String,
+        errors: Vec<FieldError>,
+    },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -68,6 +78,21 @@ pub enum Error {
     #[error("Database pool error: {0}")]
     DatabasePool(String),
 
+    #[error("Database conflict: {0}")]
+    DatabaseConflict(String),
+
+    /// A statement broke a table constraint, e.g. inserted a duplicate email
+    #[error("Constraint violation: {message}")]
+    ConstraintViolation {
+        kind: ConstraintKind,
+        /// Constraint name, or `table.column` where the database names no constraint
+        constraint: Option<String>,
+        message: String,
+    },
+
+    #[error("Stale model: {0}")]
+    StaleModel(String),
+
     // Network and external service errors
     #[error("Network error: {0}")]
     Network(String),
@@ -88,6 +113,10 @@ pub enum Error {
     #[error("Rate limit exceeded: {0}")]
     RateLimit(String),
 
+    /// A request body, or one part of a multipart body, over the size limit
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Redis error: {0}")]
     Redis(#[from] redis::RedisError),
 
@@ -122,6 +151,19 @@ impl From<deadpool_redis::PoolError> for Error {
     }
 }
 
+/// The kind of constraint reported by `Error::ConstraintViolation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// Unique index or primary key (409)
+    Unique,
+    /// Foreign key (409)
+    ForeignKey,
+    /// NOT NULL column (400)
+    NotNull,
+    /// CHECK constraint (400)
+    Check,
+}
+
 impl Error {
     pub fn template(msg: impl Into<String>) -> Self {
         Self::Template(msg.into())
@@ -135,6 +177,14 @@ impl Error {
         Self::Validation(msg.into())
     }
 
+    /// Validation error carrying per-field errors for form placement
+    pub fn validation_fields(msg: impl Into<String>, errors: Vec<FieldError>) -> Self {
+        Self::ValidationFields {
+            message: msg.into(),
+            errors,
+        }
+    }
+
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
     }
@@ -160,6 +210,27 @@ impl Error {
         Self::DatabasePool(msg.into())
     }
 
+    /// Serialization failure or deadlock; the statement can be run again
+    pub fn database_conflict(msg: impl Into<String>) -> Self {
+        Self::DatabaseConflict(msg.into())
+    }
+
+    pub fn constraint_violation(
+        kind: ConstraintKind,
+        constraint: Option<String>,
+        msg: impl Into<String>,
+    ) -> Self {
+        Self::ConstraintViolation {
+            kind,
+            constraint,
+            message: msg.into(),
+        }
+    }
+
+    pub fn stale_model(msg: impl Into<String>) -> Self {
+        Self::StaleModel(msg.into())
+    }
+
     // Network error constructors
     pub fn network(msg: impl Into<String>) -> Self {
         Self::Network(msg.into())
@@ -189,6 +260,10 @@ impl Error {
         Self::RateLimit(msg.into())
     }
 
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(msg.into())
+    }
+
     // Add context to an error
     pub fn with_context(self, context: impl Into<String>) -> Self {
         Self::WithContext {
@@ -197,6 +272,58 @@ impl Error {
         }
     }
 
+    /// Get the innermost error, skipping any context wrappers
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Check if this is a validation error (with or without field errors)
+    pub fn is_validation(&self) -> bool {
+        matches!(
+            self.root(),
+            Error::Validation(_) | Error::ValidationFields { .. }
+        )
+    }
+
+    /// Get the field errors of a validation error, if any
+    pub fn field_errors(&self) -> Option<&[FieldError]> {
+        match self.root() {
+            Error::ValidationFields { errors, .. } => Some(errors),
+            _ => None,
+        }
+    }
+
+    /// Check if this is a unique constraint violation (a duplicate row)
+    ///
+    /// ```rust,ignore
+    /// match user.update().await {
+    ///     Err(e) if e.is_unique_violation() && e.constraint_name() == Some("users_email_key") => {
+    ///         ctx.throw409(Some("Email already taken"))
+    ///     }
+    ///     other => other,
+    /// }
+    /// ```
+    pub fn is_unique_violation(&self) -> bool {
+        matches!(
+            self.root(),
+            Error::ConstraintViolation {
+                kind: ConstraintKind::Unique,
+                ..
+            }
+        )
+    }
+
+    /// Get the name of the violated constraint, if the database reported one
+    pub fn constraint_name(&self) -> Option<&str> {
+        match self.root() {
+            Error::ConstraintViolation { constraint, .. } => constraint.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Check if error is retryable
     pub fn is_retryable(&self) -> bool {
         matches!(
@@ -206,6 +333,7 @@ impl Error {
                 | Error::Timeout(_)
                 | Error::ExternalService { .. }
                 | Error::DatabasePool(_)
+                | Error::DatabaseConflict(_)
         )
     }
 
@@ -218,7 +346,7 @@ impl Error {
             Error::RouteNotFound(_) => "E_ROUTE_NOT_FOUND",
             Error::ModelNotFound(_) => "E_MODEL_NOT_FOUND",
             Error::Session(_) => "E_SESSION",
-            Error::Validation(_) => "E_VALIDATION",
+            Error::Validation(_) | Error::ValidationFields { .. } => "E_VALIDATION",
             Error::InvalidInput(_) => "E_INVALID_INPUT",
             Error::Io(_) => "E_IO",
             Error::Internal(_) => "E_INTERNAL",
@@ -227,12 +355,21 @@ impl Error {
             Error::DatabaseTransaction(_) => "E_DB_TRANSACTION",
             Error::DatabaseMigration(_) => "E_DB_MIGRATION",
             Error::DatabasePool(_) => "E_DB_POOL",
+            Error::DatabaseConflict(_) => "E_DB_CONFLICT",
+            Error::ConstraintViolation { kind, .. } => match kind {
+                ConstraintKind::Unique => "E_DB_UNIQUE",
+                ConstraintKind::ForeignKey => "E_DB_FOREIGN_KEY",
+                ConstraintKind::NotNull => "E_DB_NOT_NULL",
+                ConstraintKind::Check => "E_DB_CHECK",
+            },
+            Error::StaleModel(_) => "E_STALE_MODEL",
             Error::Network(_) => "E_NETWORK",
             Error::ExternalService { .. } => "E_EXTERNAL_SERVICE",
             Error::Timeout(_) => "E_TIMEOUT",
             Error::Authentication(_) => "E_AUTH",
             Error::Authorization(_) => "E_AUTHZ",
             Error::RateLimit(_) => "E_RATE_LIMIT",
+            Error::PayloadTooLarge(_) => "E_PAYLOAD_TOO_LARGE",
             Error::Redis(_) => "E_REDIS",
             Error::RedisPool(_) => "E_REDIS_POOL",
             Error::WithContext { source, .. } => source.error_code(),
@@ -242,10 +379,16 @@ impl Error {
     /// Get HTTP status code for the error
     pub fn status_code(&self) -> u16 {
         match self {
-            Error::Validation(_) | Error::InvalidInput(_) => 400,
+            Error::Validation(_) | Error::ValidationFields { .. } | Error::InvalidInput(_) => 400,
             Error::Authentication(_) => 401,
             Error::Authorization(_) => 403,
             Error::RouteNotFound(_) | Error::ModelNotFound(_) => 404,
+            Error::StaleModel(_) => 409,
+            Error::ConstraintViolation { kind, .. } => match kind {
+                ConstraintKind::Unique | ConstraintKind::ForeignKey => 409,
+                ConstraintKind::NotNull | ConstraintKind::Check => 400,
+            },
+            Error::PayloadTooLarge(_) => 413,
             Error::RateLimit(_) => 429,
             Error::Timeout(_) => 408,
             Error::WithContext { source, .. } => source.status_code(),
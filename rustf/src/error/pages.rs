@@ -4,7 +4,7 @@
 //! that don't leak sensitive information like stack traces in production environments.
 
 use crate::config::AppConfig;
-use crate::error::{Error, Result};
+use crate::error::{problem, Error, Result};
 use crate::http::Response;
 use crate::views::ViewEngine;
 use hyper::StatusCode;
@@ -257,10 +257,12 @@ impl ErrorPages {
             403 => "Forbidden",
             404 => "Not Found",
             409 => "Conflict",
+            413 => "Payload Too Large",
             429 => "Too Many Requests",
             500 => "Internal Server Error",
             502 => "Bad Gateway",
             503 => "Service Unavailable",
+            504 => "Gateway Timeout",
             _ => "Error",
         }
     }
@@ -273,10 +275,12 @@ impl ErrorPages {
             403 => "You don't have permission to access this resource.",
             404 => "The page or resource you're looking for could not be found. It may have been moved or deleted.",
             409 => "There was a conflict with your request. The resource may have been modified by another user.",
+            413 => "The data you sent is too large. Please send less data or a smaller file.",
             429 => "Too many requests have been made. Please wait a moment and try again.",
             500 => "An internal server error occurred. Our team has been notified and is working to fix the issue.",
             502 => "Bad gateway error. The server is temporarily unavailable.",
             503 => "The service is temporarily unavailable. Please try again later.",
+            504 => "The server took too long to respond. Please try again later.",
             _ => "An error occurred while processing your request.",
         }
     }
@@ -302,41 +306,66 @@ impl ErrorPages {
         }
     }
 
-    /// Create a JSON error response for API endpoints
-    pub fn create_json_error_response(
+    /// Create an error response in the `Response::json_error` envelope
+    ///
+    /// The code comes from `Error::error_code()`. Outside development mode
+    /// only client-facing messages (validation, invalid input, not found,
+    /// auth, rate limit) are passed through; database and internal errors get
+    /// the generic status text, so driver messages never reach clients.
+    pub fn create_api_error_response(
         &self,
         status_code: u16,
         error: Option<&Error>,
         request_id: Option<&str>,
-    ) -> Result<Response> {
-        let user_message = self.get_user_friendly_message(status_code);
+    ) -> Response {
+        let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let code = match error {
+            Some(err) => err.error_code(),
+            None if status_code >= 500 => "E_INTERNAL",
+            None => "E_HTTP",
+        };
+        let message = match error {
+            Some(err) if self.development_mode || is_client_facing(err) => err.to_string(),
+            _ => self.get_status_text(status_code).to_string(),
+        };
 
-        let mut error_data = json!({
-            "error": true,
-            "status": status_code,
-            "message": user_message,
-            "request_id": request_id.unwrap_or("unknown"),
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        });
+        Response::json_error_with_request_id(status, code, &message, request_id)
+    }
 
-        // Include detailed error information only in development mode
-        if self.development_mode {
-            if let Some(err) = error {
-                error_data["details"] = json!({
-                    "error_message": err.to_string(),
-                    "error_type": self.classify_error_type(err),
-                });
-            }
+    /// Create an RFC 7807 `application/problem+json` response
+    ///
+    /// Used for validation errors so clients get machine-readable field errors.
+    /// The request ID is added as a `request_id` extension member.
+    pub fn create_problem_response(
+        &self,
+        status_code: u16,
+        error: &Error,
+        request_id: Option<&str>,
+    ) -> Result<Response> {
+        let mut problem = problem::problem_details(status_code, error, None);
+        if let (Some(request_id), Some(object)) = (request_id, problem.as_object_mut()) {
+            object.insert("request_id".to_string(), json!(request_id));
         }
 
-        Ok(Response::new(
-            StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(
+            Response::new(StatusCode::from_u16(status_code).unwrap_or(StatusCode::BAD_REQUEST))
+                .with_header("Content-Type", problem::PROBLEM_JSON)
+                .with_body(serde_json::to_string(&problem)?.into_bytes()),
         )
-        .with_header("Content-Type", "application/json")
-        .with_body(serde_json::to_string(&error_data)?.into_bytes()))
+    }
+
+    /// Replace the RFC 7807 document shape for validation errors
+    ///
+    /// Applies to every problem response, including validation errors returned
+    /// from route handlers. Returns `false` if a formatter was already set.
+    pub fn set_problem_formatter(formatter: problem::ProblemFormatter) -> bool {
+        problem::set_problem_formatter(formatter)
     }
 
     /// Create appropriate error response based on request Accept header
+    ///
+    /// Validation errors requested as JSON are answered with problem details,
+    /// other errors requested as JSON with the `Response::json_error` envelope.
     pub fn create_error_response(
         &self,
         status_code: u16,
@@ -344,10 +373,11 @@ impl ErrorPages {
         request_id: Option<&str>,
         accept_header: Option<&str>,
     ) -> Result<Response> {
-        if let Some(accept) = accept_header {
-            if accept.contains("application/json") {
-                return self.create_json_error_response(status_code, error, request_id);
+        if accept_header.is_some_and(accepts_json) {
+            if let Some(err) = error.filter(|e| e.is_validation()) {
+                return self.create_problem_response(status_code, err, request_id);
             }
+            return Ok(self.create_api_error_response(status_code, error, request_id));
         }
 
         // Default to HTML error page
@@ -355,6 +385,35 @@ impl ErrorPages {
     }
 }
 
+/// Check whether an Accept header asks for JSON
+///
+/// Matches `application/json` and `+json` types such as
+/// `application/problem+json`. Used by every error path that picks between
+/// JSON and the HTML error page.
+pub(crate) fn accepts_json(accept: &str) -> bool {
+    accept.split(',').any(|range| {
+        let media_type = range.split(';').next().unwrap_or("").trim();
+        media_type.eq_ignore_ascii_case("application/json")
+            || media_type.to_ascii_lowercase().ends_with("+json")
+    })
+}
+
+/// Errors whose message is written for the client rather than the developer
+fn is_client_facing(error: &Error) -> bool {
+    matches!(
+        error.root(),
+        Error::Validation(_)
+            | Error::ValidationFields { .. }
+            | Error::InvalidInput(_)
+            | Error::RouteNotFound(_)
+            | Error::ModelNotFound(_)
+            | Error::Authentication(_)
+            | Error::Authorization(_)
+            | Error::RateLimit(_)
+            | Error::PayloadTooLarge(_)
+    )
+}
+
 /// HTML escape utility function
 fn html_escape(input: &str) -> String {
     input
@@ -381,6 +440,11 @@ impl HealthCheck {
             status: "healthy".to_string(),
             timestamp: chrono::Utc::now().to_rfc3339(),
             version: env!("CARGO_PKG_VERSION").to_string(),
+            mode: if crate::database::availability::is_degraded() {
+                "degraded".to_string()
+            } else {
+                "normal".to_string()
+            },
             checks: std::collections::HashMap::new(),
         };
 
@@ -467,6 +531,10 @@ pub struct HealthCheckResult {
     pub status: String,
     pub timestamp: String,
     pub version: String,
+    /// `normal`, or `degraded` while the database is unreachable (see
+    /// `DegradedModeMiddleware`)
+    #[serde(default)]
+    pub mode: String,
     pub checks: std::collections::HashMap<String, CheckStatus>,
 }
 
@@ -559,14 +627,12 @@ mod tests {
         assert!(body_str.contains("test-123"));
     }
 
-    #[tokio::test]
-    async fn test_json_error_response() {
+    #[test]
+    fn test_json_error_response() {
         let error_pages = create_test_error_pages();
         let error = Error::template("Test API error".to_string());
 
-        let response = error_pages
-            .create_json_error_response(400, Some(&error), Some("api-456"))
-            .unwrap();
+        let response = error_pages.create_api_error_response(400, Some(&error), Some("api-456"));
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST);
         assert!(response
@@ -574,13 +640,88 @@ mod tests {
             .iter()
             .any(|(k, v)| k == "Content-Type" && v.contains("application/json")));
 
-        let body_str = String::from_utf8(response.body).unwrap();
-        let mut body_bytes = body_str.clone().into_bytes();
-        let json_data: Value = simd_json::from_slice(&mut body_bytes).unwrap();
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json_data["error"]["code"], error.error_code());
+        assert_eq!(json_data["error"]["request_id"], "api-456");
+    }
+
+    #[test]
+    fn test_api_error_envelope() {
+        let error_pages = create_test_error_pages();
+        let error = Error::ModelNotFound("User 42".to_string());
+
+        let response = error_pages
+            .create_error_response(404, Some(&error), Some("req-2"), Some("application/json"))
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json_data["error"]["code"], "E_MODEL_NOT_FOUND");
+        assert_eq!(json_data["error"]["message"], "Model not found: User 42");
+        assert_eq!(json_data["error"]["request_id"], "req-2");
+
+        // Without a request, no id is attached
+        let response = Response::json_error(StatusCode::CONFLICT, "E_DB_CONFLICT", "Taken");
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json_data["error"]["request_id"], Value::Null);
+    }
+
+    #[test]
+    fn test_api_error_hides_database_messages_in_production() {
+        let mut error_pages = create_test_error_pages();
+        error_pages.development_mode = false;
+
+        let error = Error::constraint_violation(
+            crate::error::ConstraintKind::Unique,
+            Some("users_email_key".to_string()),
+            "duplicate key value violates unique constraint \"users_email_key\"",
+        );
+        let response = error_pages.create_api_error_response(409, Some(&error), None);
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json_data["error"]["code"], "E_DB_UNIQUE");
+        assert_eq!(json_data["error"]["message"], "Conflict");
+
+        let error = Error::InvalidInput("page must be a number".to_string());
+        let response = error_pages.create_api_error_response(400, Some(&error), None);
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(
+            json_data["error"]["message"],
+            "Invalid input: page must be a number"
+        );
+    }
+
+    #[test]
+    fn test_accepts_json() {
+        assert!(accepts_json("application/json"));
+        assert!(accepts_json("text/html;q=0.9, application/problem+json"));
+        assert!(accepts_json("Application/JSON; charset=utf-8"));
+        assert!(!accepts_json("text/html,application/xhtml+xml"));
+        assert!(!accepts_json("text/jsonish"));
+    }
+
+    #[test]
+    fn test_validation_error_as_problem_json() {
+        let error_pages = create_test_error_pages();
+        let error = Error::validation_fields(
+            "Invalid form",
+            vec![crate::error::FieldError::new(
+                "email", "Required", "required",
+            )],
+        );
+
+        let response = error_pages
+            .create_error_response(400, Some(&error), Some("req-1"), Some("application/json"))
+            .unwrap();
+
+        assert!(response
+            .headers
+            .iter()
+            .any(|(k, v)| k == "Content-Type" && v == "application/problem+json"));
 
+        let json_data: Value = serde_json::from_slice(&response.body).unwrap();
         assert_eq!(json_data["status"], 400);
-        assert_eq!(json_data["error"], true);
-        assert_eq!(json_data["request_id"], "api-456");
+        assert_eq!(json_data["request_id"], "req-1");
+        assert_eq!(json_data["errors"][0]["field"], "email");
     }
 
     #[tokio::test]
@@ -0,0 +1,160 @@
+//! RFC 7807 problem details for validation errors
+//!
+//! Validation failures are returned as `application/problem+json` documents so
+//! frontends can place each message next to the right form field:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Bad Request",
+//!   "status": 400,
+//!   "detail": "Invalid registration data",
+//!   "instance": "/users",
+//!   "errors": [
+//!     { "field": "email", "message": "Invalid email address", "code": "email" }
+//!   ]
+//! }
+//! ```
+//!
+//! The document shape can be replaced application-wide with
+//! `ErrorPages::set_problem_formatter`.
+
+use super::Error;
+use crate::http::Response;
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+/// Content type of RFC 7807 responses
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// A validation error attached to a single input field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Name of the offending field (e.g., "email", "address.zip")
+    pub field: String,
+    /// Human-readable message
+    pub message: String,
+    /// Machine-readable code (e.g., "required", "min_length")
+    pub code: String,
+}
+
+impl FieldError {
+    /// Create a new field error
+    pub fn new(
+        field: impl Into<String>,
+        message: impl Into<String>,
+        code: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            code: code.into(),
+        }
+    }
+}
+
+/// Builds the problem document from the status code, the error and the
+/// request path (`instance`)
+pub type ProblemFormatter = fn(u16, &Error, Option<&str>) -> Value;
+
+static PROBLEM_FORMATTER: OnceLock<ProblemFormatter> = OnceLock::new();
+
+/// Install a custom problem formatter
+///
+/// Returns `false` if a formatter was already installed.
+pub fn set_problem_formatter(formatter: ProblemFormatter) -> bool {
+    PROBLEM_FORMATTER.set(formatter).is_ok()
+}
+
+/// Build the default RFC 7807 document for an error
+pub fn default_problem_details(status_code: u16, error: &Error, instance: Option<&str>) -> Value {
+    let title = StatusCode::from_u16(status_code)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("Error");
+
+    let detail = match error.root() {
+        Error::Validation(message) | Error::ValidationFields { message, .. } => message.as_str(),
+        _ => title,
+    };
+
+    let mut problem = json!({
+        "type": "about:blank",
+        "title": title,
+        "status": status_code,
+        "detail": detail,
+        "errors": error.field_errors().unwrap_or_default(),
+    });
+
+    if let Some(instance) = instance {
+        problem["instance"] = json!(instance);
+    }
+
+    problem
+}
+
+/// Build the problem document using the installed formatter, if any
+pub fn problem_details(status_code: u16, error: &Error, instance: Option<&str>) -> Value {
+    match PROBLEM_FORMATTER.get() {
+        Some(formatter) => formatter(status_code, error, instance),
+        None => default_problem_details(status_code, error, instance),
+    }
+}
+
+/// Create an `application/problem+json` response for an error
+pub fn problem_response(status_code: u16, error: &Error, instance: Option<&str>) -> Response {
+    let body = problem_details(status_code, error, instance).to_string();
+
+    Response::new(StatusCode::from_u16(status_code).unwrap_or(StatusCode::BAD_REQUEST))
+        .with_header("Content-Type", PROBLEM_JSON)
+        .with_body(body.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_details_with_field_errors() {
+        let error = Error::validation_fields(
+            "Invalid registration data",
+            vec![
+                FieldError::new("email", "Invalid email address", "email"),
+                FieldError::new("password", "Too short", "min_length"),
+            ],
+        );
+
+        let problem = default_problem_details(400, &error, Some("/users"));
+
+        assert_eq!(problem["title"], "Bad Request");
+        assert_eq!(problem["status"], 400);
+        assert_eq!(problem["detail"], "Invalid registration data");
+        assert_eq!(problem["instance"], "/users");
+        assert_eq!(problem["errors"][0]["field"], "email");
+        assert_eq!(problem["errors"][1]["code"], "min_length");
+    }
+
+    #[test]
+    fn test_problem_details_for_plain_validation_error() {
+        let error = Error::validation("Value is required").with_context("Saving user");
+        let problem = default_problem_details(400, &error, None);
+
+        assert_eq!(problem["detail"], "Value is required");
+        assert_eq!(problem["errors"], json!([]));
+        assert!(problem.get("instance").is_none());
+    }
+
+    #[test]
+    fn test_problem_response_content_type() {
+        let error = Error::validation("Bad input");
+        let response = problem_response(400, &error, None);
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert!(response
+            .headers
+            .iter()
+            .any(|(k, v)| k == "Content-Type" && v == PROBLEM_JSON));
+    }
+}
@@ -4,8 +4,10 @@
 //! transient failures in network operations, database connections, etc.
 
 use super::{Error, Result};
+use std::collections::VecDeque;
 use std::future::Future;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 /// Trait for errors that can be retried
@@ -96,7 +98,7 @@ impl RetryPolicy {
     }
 
     /// Calculate delay for a given attempt
-    fn calculate_delay(&self, attempt: u32) -> Duration {
+    pub(crate) fn calculate_delay(&self, attempt: u32) -> Duration {
         let mut delay = self.initial_delay;
 
         if self.backoff_multiplier > 1.0 {
@@ -124,10 +126,27 @@ impl RetryPolicy {
 }
 
 /// Execute an async operation with retry logic
-pub async fn with_retry<F, Fut, T>(policy: RetryPolicy, mut operation: F) -> Result<T>
+pub async fn with_retry<F, Fut, T>(policy: RetryPolicy, operation: F) -> Result<T>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T>>,
+{
+    with_retry_if(policy, operation, |error| error.is_retryable()).await
+}
+
+/// Execute an async operation, retrying the errors `should_retry` accepts
+///
+/// Like [`with_retry`], for callers that need a narrower notion of
+/// retryable than [`Error::is_retryable`].
+pub async fn with_retry_if<F, Fut, T, P>(
+    policy: RetryPolicy,
+    mut operation: F,
+    should_retry: P,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    P: Fn(&Error) -> bool,
 {
     let mut last_error = None;
 
@@ -136,7 +155,7 @@ where
             Ok(result) => return Ok(result),
             Err(error) => {
                 // Check if error is retryable
-                if !error.is_retryable() || attempt == policy.max_attempts {
+                if !should_retry(&error) || attempt == policy.max_attempts {
                     return Err(error);
                 }
 
@@ -224,32 +243,122 @@ impl Default for RetryBuilder {
 }
 
 /// Circuit breaker for preventing cascading failures
+///
+/// While closed, call outcomes are counted over a rolling window (one minute
+/// by default). The circuit opens when the failures in the window reach
+/// `failure_threshold`, or their share of the calls reaches the rate set with
+/// [`with_failure_rate`](Self::with_failure_rate). An open circuit fails calls
+/// at once with `Error::ExternalService` for `timeout`, then half-opens: up
+/// to `half_open_max_calls` probes go through, `success_threshold` successful
+/// ones close it again and a failed one reopens it.
+///
+/// Clones share the same circuit.
+#[derive(Clone)]
 pub struct CircuitBreaker {
     failure_threshold: u32,
     success_threshold: u32,
     timeout: Duration,
     half_open_max_calls: u32,
+    /// Span over which outcomes are counted while closed
+    window: Duration,
+    /// Failure ratio that opens the circuit, once the window holds this many calls
+    failure_rate: Option<(f64, u32)>,
+    /// Service named in short-circuit errors
+    service: String,
+
+    state: Arc<Mutex<Circuit>>,
+}
 
-    state: std::sync::Arc<tokio::sync::RwLock<CircuitState>>,
+/// Observable state of a [`CircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through and their outcomes are counted
+    Closed,
+    /// Calls fail at once until the timeout passes
+    Open,
+    /// A limited number of probe calls go through
+    HalfOpen,
 }
 
-#[derive(Debug, Clone)]
-enum CircuitState {
+#[derive(Debug)]
+enum Circuit {
     Closed {
-        failure_count: u32,
+        window: OutcomeWindow,
     },
     Open {
-        opened_at: std::time::Instant,
+        opened_at: Instant,
     },
     HalfOpen {
+        since: Instant,
         success_count: u32,
-        failure_count: u32,
         calls_count: u32,
     },
 }
 
+/// Call outcomes over a rolling window, in buckets of a tenth of it
+#[derive(Debug, Default)]
+struct OutcomeWindow {
+    buckets: VecDeque<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    start: Instant,
+    successes: u32,
+    failures: u32,
+}
+
+impl OutcomeWindow {
+    fn record(&mut self, now: Instant, window: Duration, failed: bool) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| now.duration_since(bucket.start) >= window)
+        {
+            self.buckets.pop_front();
+        }
+
+        let bucket_span = window / 10;
+        let starts_bucket = self
+            .buckets
+            .back()
+            .is_none_or(|bucket| now.duration_since(bucket.start) >= bucket_span);
+        if starts_bucket {
+            self.buckets.push_back(Bucket {
+                start: now,
+                successes: 0,
+                failures: 0,
+            });
+        }
+        let Some(current) = self.buckets.back_mut() else {
+            return;
+        };
+        if failed {
+            current.failures += 1;
+        } else {
+            current.successes += 1;
+        }
+    }
+
+    /// Failures and total calls in the window
+    fn counts(&self) -> (u32, u32) {
+        self.buckets
+            .iter()
+            .fold((0, 0), |(failures, total), bucket| {
+                (
+                    failures + bucket.failures,
+                    total + bucket.failures + bucket.successes,
+                )
+            })
+    }
+}
+
 impl CircuitBreaker {
     /// Create a new circuit breaker
+    ///
+    /// Opens after `failure_threshold` failures within the window, stays open
+    /// for `timeout`, then closes after `success_threshold` successful probes
+    /// out of at most `half_open_max_calls`.
     pub fn new(
         failure_threshold: u32,
         success_threshold: u32,
@@ -261,97 +370,163 @@ impl CircuitBreaker {
             success_threshold,
             timeout,
             half_open_max_calls,
-            state: std::sync::Arc::new(tokio::sync::RwLock::new(CircuitState::Closed {
-                failure_count: 0,
+            window: Duration::from_secs(60),
+            failure_rate: None,
+            service: "circuit_breaker".to_string(),
+            state: Arc::new(Mutex::new(Circuit::Closed {
+                window: OutcomeWindow::default(),
             })),
         }
     }
 
-    /// Execute an operation through the circuit breaker
+    /// Count failures over `window` instead of the last minute
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Also open when `rate` (0.0 to 1.0) of the calls in the window failed,
+    /// once it holds at least `minimum_calls`
+    pub fn with_failure_rate(mut self, rate: f64, minimum_calls: u32) -> Self {
+        self.failure_rate = Some((rate, minimum_calls.max(1)));
+        self
+    }
+
+    /// Name the protected service in short-circuit errors
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.service = service.into();
+        self
+    }
+
+    /// Current state of the circuit
+    ///
+    /// An open circuit whose timeout has passed reports `Open` until the
+    /// next call probes the service.
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            Circuit::Closed { .. } => CircuitState::Closed,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Run `operation` through the circuit breaker
+    ///
+    /// ```rust,ignore
+    /// let breaker = CircuitBreaker::new(5, 2, Duration::from_secs(30), 1).with_service("geo");
+    /// let place = breaker.call(|| geocode(&address)).await?;
+    /// ```
+    pub async fn call<F, Fut, T>(&self, operation: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.admit()?;
+        let result = operation().await;
+        self.record(result.is_err());
+        result
+    }
+
+    /// Execute an operation through the circuit breaker (same as [`call`](Self::call))
     pub async fn execute<F, Fut, T>(&self, operation: F) -> Result<T>
     where
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<T>>,
     {
-        // Check if we should allow the call
-        {
-            let mut state = self.state.write().await;
-            match *state {
-                CircuitState::Open { opened_at } => {
-                    if opened_at.elapsed() < self.timeout {
-                        return Err(Error::external_service(
-                            "circuit_breaker",
-                            "Circuit breaker is open",
-                        ));
-                    }
-                    // Transition to half-open
-                    *state = CircuitState::HalfOpen {
-                        success_count: 0,
-                        failure_count: 0,
-                        calls_count: 0,
-                    };
+        self.call(operation).await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Circuit> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Let a call through, or fail it when the circuit is open
+    fn admit(&self) -> Result<()> {
+        let mut state = self.lock();
+        match &mut *state {
+            Circuit::Closed { .. } => Ok(()),
+            Circuit::Open { opened_at } => {
+                if opened_at.elapsed() < self.timeout {
+                    return Err(Error::external_service(
+                        self.service.clone(),
+                        "Circuit breaker is open",
+                    ));
                 }
-                CircuitState::HalfOpen { calls_count, .. } => {
-                    if calls_count >= self.half_open_max_calls {
-                        return Err(Error::external_service(
-                            "circuit_breaker",
-                            "Circuit breaker is testing",
-                        ));
-                    }
+                log::info!("Circuit breaker for {} half-open", self.service);
+                *state = Circuit::HalfOpen {
+                    since: Instant::now(),
+                    success_count: 0,
+                    calls_count: 1,
+                };
+                Ok(())
+            }
+            Circuit::HalfOpen {
+                since,
+                success_count,
+                calls_count,
+            } => {
+                // Probes that never reported back (e.g. cancelled) free their
+                // slots after another timeout
+                if since.elapsed() >= self.timeout {
+                    *since = Instant::now();
+                    *success_count = 0;
+                    *calls_count = 0;
                 }
-                _ => {}
+                if *calls_count >= self.half_open_max_calls {
+                    return Err(Error::external_service(
+                        self.service.clone(),
+                        "Circuit breaker is testing",
+                    ));
+                }
+                *calls_count += 1;
+                Ok(())
             }
         }
+    }
 
-        // Execute the operation
-        let result = operation().await;
-
-        // Update state based on result
-        let mut state = self.state.write().await;
-        match result {
-            Ok(_) => match *state {
-                CircuitState::Closed { .. } => {}
-                CircuitState::HalfOpen {
-                    success_count,
-                    calls_count,
-                    ..
-                } => {
-                    if success_count + 1 >= self.success_threshold {
-                        *state = CircuitState::Closed { failure_count: 0 };
-                    } else {
-                        *state = CircuitState::HalfOpen {
-                            success_count: success_count + 1,
-                            failure_count: 0,
-                            calls_count: calls_count + 1,
-                        };
-                    }
+    /// Update the circuit with the outcome of a call
+    fn record(&self, failed: bool) {
+        let mut state = self.lock();
+        let now = Instant::now();
+        match &mut *state {
+            Circuit::Closed { window } => {
+                window.record(now, self.window, failed);
+                if failed && self.should_open(window) {
+                    log::warn!("Circuit breaker for {} opened", self.service);
+                    *state = Circuit::Open { opened_at: now };
                 }
-                _ => {}
-            },
-            Err(_) => match *state {
-                CircuitState::Closed { failure_count } => {
-                    if failure_count + 1 >= self.failure_threshold {
-                        *state = CircuitState::Open {
-                            opened_at: std::time::Instant::now(),
-                        };
-                    } else {
-                        *state = CircuitState::Closed {
-                            failure_count: failure_count + 1,
-                        };
-                    }
-                }
-                CircuitState::HalfOpen { failure_count, .. } => {
-                    if failure_count + 1 >= self.failure_threshold {
-                        *state = CircuitState::Open {
-                            opened_at: std::time::Instant::now(),
+            }
+            Circuit::HalfOpen { success_count, .. } => {
+                if failed {
+                    log::warn!("Circuit breaker for {} reopened", self.service);
+                    *state = Circuit::Open { opened_at: now };
+                } else {
+                    *success_count += 1;
+                    if *success_count >= self.success_threshold {
+                        log::info!("Circuit breaker for {} closed", self.service);
+                        *state = Circuit::Closed {
+                            window: OutcomeWindow::default(),
                         };
                     }
                 }
-                _ => {}
-            },
+            }
+            // A call admitted before the circuit opened
+            Circuit::Open { .. } => {}
         }
+    }
 
-        result
+    fn should_open(&self, window: &OutcomeWindow) -> bool {
+        let (failures, total) = window.counts();
+        if failures >= self.failure_threshold {
+            return true;
+        }
+        match self.failure_rate {
+            Some((rate, minimum_calls)) => {
+                total >= minimum_calls && f64::from(failures) >= rate * f64::from(total)
+            }
+            None => false,
+        }
     }
 }
 
@@ -476,4 +651,82 @@ mod tests {
         let result = breaker.execute(|| async { Ok("Success again") }).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_probe_closes() {
+        let breaker = CircuitBreaker::new(3, 1, Duration::from_millis(50), 1).with_service("geo");
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        for _ in 0..3 {
+            let _: Result<()> = breaker
+                .call(|| async { Err(Error::network("Connection refused")) })
+                .await;
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // Short-circuited without running the operation
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let result = breaker
+            .call(|| async {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        let error = result.unwrap_err();
+        assert_eq!(error.error_code(), "E_EXTERNAL_SERVICE");
+        assert!(error.to_string().contains("geo"));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+
+        // The successful probe closes the circuit
+        breaker.call(|| async { Ok(()) }).await.unwrap();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_failed_probe_reopens() {
+        let breaker = CircuitBreaker::new(1, 1, Duration::from_millis(50), 1);
+        let _: Result<()> = breaker
+            .call(|| async { Err(Error::network("Failed")) })
+            .await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let result: Result<()> = breaker
+            .call(|| async { Err(Error::network("Still failing")) })
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Still failing"));
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_failure_rate_over_window() {
+        let breaker = CircuitBreaker::new(100, 1, Duration::from_secs(30), 1)
+            .with_window(Duration::from_millis(50))
+            .with_failure_rate(0.5, 4);
+
+        // Failures that left the window are forgotten
+        for _ in 0..3 {
+            let _: Result<()> = breaker
+                .call(|| async { Err(Error::network("Failed")) })
+                .await;
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+
+        breaker.call(|| async { Ok(()) }).await.unwrap();
+        breaker.call(|| async { Ok(()) }).await.unwrap();
+        let _: Result<()> = breaker
+            .call(|| async { Err(Error::network("Failed")) })
+            .await;
+        // 1 failure out of 3 calls, under the minimum
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let _: Result<()> = breaker
+            .call(|| async { Err(Error::network("Failed")) })
+            .await;
+        // 2 failures out of 4 calls
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
 }
@@ -11,7 +11,7 @@ use serde_json::Value;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
 
@@ -115,6 +115,9 @@ pub mod events {
     pub const DB_MIGRATE: &str = "db.migrate"; // Database migration needed
     pub const DB_CONNECTED: &str = "db.connected"; // Database connection established
     pub const DB_DISCONNECTED: &str = "db.disconnected"; // Database connection lost
+
+    /// Cache events
+    pub const CACHE_HIT_RATE_LOW: &str = "cache.hit_rate_low"; // Hit rate below a `HitRateAlert`
 }
 
 /// Context provided to event handlers
@@ -630,6 +633,38 @@ impl Default for EventEmitter {
     }
 }
 
+/// Application emitter shared with code outside the request/event lifecycle
+static GLOBAL_EMITTER: OnceLock<(Arc<RwLock<EventEmitter>>, Arc<crate::config::AppConfig>)> =
+    OnceLock::new();
+
+/// Register the application's emitter for `emit_global`
+pub(crate) fn install_global(
+    emitter: Arc<RwLock<EventEmitter>>,
+    config: Arc<crate::config::AppConfig>,
+) {
+    let _ = GLOBAL_EMITTER.set((emitter, config));
+}
+
+/// Emit an event on the application's emitter from anywhere
+///
+/// Used for events raised outside of handlers, such as model events deferred
+/// until a transaction commits. Does nothing before the app starts serving.
+pub async fn emit_global(event: &str, data: Option<Value>) -> crate::Result<()> {
+    match GLOBAL_EMITTER.get() {
+        Some((emitter, config)) => {
+            let emitter = emitter.read().await;
+            emitter.emit_internal(event, data, config.clone()).await
+        }
+        None => {
+            log::debug!(
+                "Event '{}' dropped: no application emitter installed",
+                event
+            );
+            Ok(())
+        }
+    }
+}
+
 /// Helper function for creating async event handlers
 ///
 /// # Example
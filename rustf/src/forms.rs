@@ -3,11 +3,44 @@
 //! This module provides utilities for creating HTML forms with built-in validation,
 //! CSRF protection, and error handling. It's designed to be AI-friendly with
 //! clear method names and comprehensive examples.
-
+//!
+//! A form bound to the request carries its CSRF token and the submitted
+//! values, so a failed POST can redraw the same form with the user's input
+//! and an error under each offending field:
+//!
+//! ```rust,ignore
+//! fn signup_form() -> FormBuilder {
+//!     FormBuilder::new("/signup", "POST")
+//!         .email("email").label("Email").required().email_validation().end()
+//!         .password("password").label("Password").required().min_length(8).end()
+//! }
+//!
+//! async fn show(ctx: &mut Context) -> Result<()> {
+//!     let form = signup_form().bind(ctx)?;
+//!     ctx.view("signup", json!({ "form": form.render() }))
+//! }
+//!
+//! async fn create(ctx: &mut Context) -> Result<()> {
+//!     let mut form = signup_form().bind(ctx)?;
+//!     let data = match form.validate() {
+//!         Ok(data) => data,
+//!         Err(_) => return ctx.view("signup", json!({ "form": form.render() })),
+//!     };
+//!     // ... create the account from `data`
+//!     ctx.redirect("/welcome")
+//! }
+//! ```
+
+use crate::context::Context;
 use crate::error::{Error, Result};
 use crate::security::validation::{CsrfProtection, InputValidator, ValidationRule};
+use crate::views::FormState;
+use serde_json::Value;
 use std::collections::HashMap;
 
+/// Parts of field names whose values are never sent back to the browser
+const SECRET_NAMES: [&str; 4] = ["password", "passwd", "secret", "token"];
+
 /// HTML form builder with validation and CSRF protection
 pub struct FormBuilder {
     action: String,
@@ -16,6 +49,8 @@ pub struct FormBuilder {
     csrf_token: Option<String>,
     errors: HashMap<String, String>,
     values: HashMap<String, String>,
+    /// Submitted fields in body order, repeated names included
+    submitted: Vec<(String, String)>,
 }
 
 /// Form field definition
@@ -28,6 +63,8 @@ pub struct FormField {
     pub required: bool,
     pub validation_rules: Vec<ValidationRule>,
     pub attributes: HashMap<String, String>,
+    /// `(value, label)` pairs of a select field
+    pub options: Vec<(String, String)>,
 }
 
 impl FormBuilder {
@@ -49,6 +86,7 @@ impl FormBuilder {
             csrf_token: None,
             errors: HashMap::new(),
             values: HashMap::new(),
+            submitted: Vec::new(),
         }
     }
 
@@ -66,10 +104,123 @@ impl FormBuilder {
 
     /// Set form values (typically from previous submission)
     pub fn with_values(mut self, values: HashMap<String, String>) -> Self {
+        self.submitted = values.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
         self.values = values;
         self
     }
 
+    /// Bind the form to the current request
+    ///
+    /// Issues a CSRF token through the session (the one `CsrfMiddleware`
+    /// checks) and, unless this is a GET or HEAD request, fills the form
+    /// with the submitted values. Requires a session.
+    pub fn bind(mut self, ctx: &mut Context) -> Result<Self> {
+        self.csrf_token = Some(ctx.generate_csrf(None)?);
+        self.fill(ctx)
+    }
+
+    /// Fill the form with the values submitted in the current request
+    ///
+    /// Does nothing for GET and HEAD requests. Unlike `bind`, no CSRF token
+    /// is issued, so no session is needed.
+    pub fn fill(mut self, ctx: &mut Context) -> Result<Self> {
+        if !matches!(ctx.req.method.as_str(), "GET" | "HEAD") {
+            let mut values = ctx.body_form()?;
+            values.remove("_csrf_token");
+            values.remove("_token");
+            self.values = values;
+            self.submitted = ctx.req.body_as_form_pairs();
+        }
+        Ok(self)
+    }
+
+    /// Validate the current values against the field rules
+    ///
+    /// On failure the first error of each field is kept on the form, so
+    /// rendering it again shows the messages next to the fields.
+    pub fn validate(&mut self) -> Result<HashMap<String, String>> {
+        let result = self.validator().validate(&self.values);
+        if let Err(error) = &result {
+            self.add_validation_errors(error);
+        }
+        result
+    }
+
+    /// Keep the first error of each field of a validation error
+    ///
+    /// A validation error that names no field is kept as the `form` error.
+    pub fn add_validation_errors(&mut self, error: &Error) {
+        match error.field_errors() {
+            Some(fields) if !fields.is_empty() => {
+                for field_error in fields {
+                    self.errors
+                        .entry(field_error.field.clone())
+                        .or_insert_with(|| field_error.message.clone());
+                }
+            }
+            _ => {
+                if let Error::Validation(message) | Error::ValidationFields { message, .. } =
+                    error.root()
+                {
+                    self.errors
+                        .entry("form".to_string())
+                        .or_insert_with(|| message.clone());
+                }
+            }
+        }
+    }
+
+    /// Errors and submitted values for a hand-written template
+    ///
+    /// The view gets them as `errors` and `old`: errors on dotted fields
+    /// (`address.zip`) are nested like bracketed values (`address[zip]`), a
+    /// field sent several times is an array, and secret fields are left out.
+    pub fn state(&self) -> Result<FormState> {
+        let mut errors = serde_json::Map::new();
+        for (field, message) in &self.errors {
+            insert_field_error(&mut errors, field, message);
+        }
+        let old: Vec<(String, String)> = self
+            .submitted
+            .iter()
+            .filter(|(name, _)| !self.is_secret(name))
+            .cloned()
+            .collect();
+
+        Ok(FormState {
+            errors: Value::Object(errors),
+            old: crate::http::form::to_value(&old)?,
+        })
+    }
+
+    /// Whether a field's value must never be sent back to the browser
+    ///
+    /// Password inputs, and fields whose name contains `password`, `passwd`,
+    /// `secret` or `token` (CSRF tokens included).
+    fn is_secret(&self, name: &str) -> bool {
+        let lowercase = name.to_ascii_lowercase();
+        SECRET_NAMES.iter().any(|secret| lowercase.contains(secret))
+            || self
+                .fields
+                .iter()
+                .any(|field| field.name == name && field.field_type == "password")
+    }
+
+    /// Add an error to a field, e.g. after a uniqueness check failed
+    pub fn add_error(&mut self, field: &str, message: &str) {
+        self.errors.insert(field.to_string(), message.to_string());
+    }
+
+    /// Whether any field has an error
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Current value of a field
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
     /// Add a text input field
     pub fn text(self, name: &str) -> FormFieldBuilder {
         FormFieldBuilder::new("text", name, self)
@@ -141,9 +292,14 @@ impl FormBuilder {
             ));
         }
 
-        // Input field
+        // Input field; secrets are never sent back to the browser
         let empty_string = String::new();
-        let value = self.values.get(&field.name).unwrap_or(&empty_string);
+        let value = if self.is_secret(&field.name) {
+            &empty_string
+        } else {
+            self.values.get(&field.name).unwrap_or(&empty_string)
+        };
+        let attributes = render_attributes(&field.attributes);
         let error_class = if self.errors.contains_key(&field.name) {
             " error"
         } else {
@@ -153,7 +309,7 @@ impl FormBuilder {
         match field.field_type.as_str() {
             "textarea" => {
                 html.push_str(&format!(
-                    r#"<textarea name="{}" id="{}" class="form-control{}"{}{}>{}</textarea>"#,
+                    r#"<textarea name="{}" id="{}" class="form-control{}"{}{}{}>{}</textarea>"#,
                     field.name,
                     field.name,
                     error_class,
@@ -163,36 +319,50 @@ impl FormBuilder {
                         .as_ref()
                         .map(|p| format!(r#" placeholder="{}""#, html_escape(p)))
                         .unwrap_or_default(),
+                    attributes,
                     html_escape(value)
                 ));
             }
             "select" => {
                 html.push_str(&format!(
-                    r#"<select name="{}" id="{}" class="form-control{}"{}>"#,
+                    r#"<select name="{}" id="{}" class="form-control{}"{}{}>"#,
                     field.name,
                     field.name,
                     error_class,
-                    if field.required { " required" } else { "" }
+                    if field.required { " required" } else { "" },
+                    attributes
                 ));
-                // Note: Options would be added via attributes or separate method
+                for (option_value, label) in &field.options {
+                    html.push_str(&format!(
+                        r#"<option value="{}"{}>{}</option>"#,
+                        html_escape(option_value),
+                        if option_value == value {
+                            " selected"
+                        } else {
+                            ""
+                        },
+                        html_escape(label)
+                    ));
+                }
                 html.push_str("</select>");
             }
             "checkbox" => {
                 let checked = value == "true" || value == "1" || value == "on";
                 html.push_str(&format!(
-                    r#"<input type="{}" name="{}" id="{}" value="1" class="form-control{}"{}{}>"#,
+                    r#"<input type="{}" name="{}" id="{}" value="1" class="form-control{}"{}{}{}>"#,
                     field.field_type,
                     field.name,
                     field.name,
                     error_class,
                     if field.required { " required" } else { "" },
-                    if checked { " checked" } else { "" }
+                    if checked { " checked" } else { "" },
+                    attributes
                 ));
             }
             _ => {
                 // text, email, password, etc.
                 html.push_str(&format!(
-                    r#"<input type="{}" name="{}" id="{}" value="{}" class="form-control{}"{}{}>"#,
+                    r#"<input type="{}" name="{}" id="{}" value="{}" class="form-control{}"{}{}{}>"#,
                     field.field_type,
                     field.name,
                     field.name,
@@ -203,7 +373,8 @@ impl FormBuilder {
                         .placeholder
                         .as_ref()
                         .map(|p| format!(r#" placeholder="{}""#, html_escape(p)))
-                        .unwrap_or_default()
+                        .unwrap_or_default(),
+                    attributes
                 ));
             }
         }
@@ -251,6 +422,7 @@ impl FormFieldBuilder {
                 required: false,
                 validation_rules: Vec::new(),
                 attributes: HashMap::new(),
+                options: Vec::new(),
             },
             form,
         }
@@ -320,6 +492,14 @@ impl FormFieldBuilder {
         self
     }
 
+    /// Add an option to a select field
+    pub fn option(mut self, value: &str, label: &str) -> Self {
+        self.field
+            .options
+            .push((value.to_string(), label.to_string()));
+        self
+    }
+
     /// Finish building this field and return to form builder
     pub fn end(self) -> FormBuilder {
         let mut form = self.form;
@@ -395,6 +575,37 @@ fn html_escape(input: &str) -> String {
         .replace('\'', "&#x27;")
 }
 
+/// Place a field error at its dotted path (`address.zip`), first message wins
+fn insert_field_error(errors: &mut serde_json::Map<String, Value>, field: &str, message: &str) {
+    let (head, rest) = match field.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (field, None),
+    };
+    let entry = errors.entry(head.to_string());
+    match rest {
+        None => {
+            entry.or_insert_with(|| Value::String(message.to_string()));
+        }
+        Some(rest) => {
+            if let Value::Object(nested) =
+                entry.or_insert_with(|| Value::Object(Default::default()))
+            {
+                insert_field_error(nested, rest, message);
+            }
+        }
+    }
+}
+
+/// Render extra attributes in a stable order
+fn render_attributes(attributes: &HashMap<String, String>) -> String {
+    let mut names: Vec<&String> = attributes.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| format!(r#" {}="{}""#, name, html_escape(&attributes[name])))
+        .collect()
+}
+
 /// Built-in form templates for common use cases
 pub struct FormTemplates;
 
@@ -541,6 +752,81 @@ mod tests {
         assert!(html.contains("error"));
     }
 
+    #[test]
+    fn test_failed_validation_redraws_input() {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), "<Al>".to_string());
+        values.insert("password".to_string(), "secret".to_string());
+
+        let mut form = FormBuilder::new("/test", "POST")
+            .with_values(values)
+            .text("name")
+            .required()
+            .min_length(5)
+            .end()
+            .password("password")
+            .required()
+            .end()
+            .select("role")
+            .option("user", "User")
+            .option("admin", "Admin")
+            .attribute("data-kind", "role")
+            .end();
+
+        let error = form.validate().unwrap_err();
+        assert_eq!(error.field_errors().unwrap().len(), 1);
+        assert!(form.has_errors());
+
+        let html = form.render();
+        assert!(html.contains(r#"value="&lt;Al&gt;""#));
+        assert!(html.contains("Must be at least 5 characters long"));
+        assert!(!html.contains("secret"));
+        assert!(html.contains(r#"<option value="admin">Admin</option>"#));
+        assert!(html.contains(r#" data-kind="role""#));
+    }
+
+    #[test]
+    fn test_form_state_for_templates() {
+        let mut values = HashMap::new();
+        values.insert("email".to_string(), "ada@example.com".to_string());
+        values.insert("address[zip]".to_string(), "1".to_string());
+        values.insert("password_confirmation".to_string(), "hunter2".to_string());
+        values.insert("_csrf_token".to_string(), "abc".to_string());
+        values.insert("account[api_secret]".to_string(), "s3cr3t".to_string());
+        values.insert("pin".to_string(), "1234".to_string());
+
+        let mut form = FormBuilder::new("/signup", "POST")
+            .with_values(values)
+            .password("pin")
+            .end();
+        form.add_validation_errors(&Error::validation_fields(
+            "Invalid form",
+            vec![
+                crate::error::FieldError::new("email", "Taken", "unique"),
+                crate::error::FieldError::new("email", "Too long", "max_length"),
+                crate::error::FieldError::new("address.zip", "Required", "required"),
+            ],
+        ));
+
+        let state = form.state().unwrap();
+        assert_eq!(
+            state.errors,
+            serde_json::json!({ "email": "Taken", "address": { "zip": "Required" } })
+        );
+        assert_eq!(
+            state.old,
+            serde_json::json!({ "email": "ada@example.com", "address": { "zip": "1" } })
+        );
+
+        // The form error is kept when no field is named
+        let mut form = FormBuilder::new("/signup", "POST");
+        form.add_validation_errors(&Error::validation("Try again later"));
+        assert_eq!(
+            form.state().unwrap().errors,
+            serde_json::json!({ "form": "Try again later" })
+        );
+    }
+
     #[test]
     fn test_form_processor_csrf() {
         let processor = FormProcessor::new().with_csrf_protection("test_secret");
@@ -0,0 +1,251 @@
+//! Browser, platform and device detection
+//!
+//! Chromium-based browsers describe themselves with User-Agent Client Hints,
+//! sent by default over HTTPS:
+//!
+//! ```text
+//! Sec-CH-UA: "Chromium";v="124", "Google Chrome";v="124", "Not-A.Brand";v="99"
+//! Sec-CH-UA-Mobile: ?0
+//! Sec-CH-UA-Platform: "Windows"
+//! ```
+//!
+//! Other browsers only send `User-Agent`, so `Request::client_hints` starts
+//! from the parsed `User-Agent` and lets any hints that were sent override
+//! it. Names follow the values of `Sec-CH-UA-Platform` (`Windows`, `macOS`,
+//! `Android`, `iOS`, `Chrome OS`, `Linux`) and the browser's short name
+//! (`Chrome`, `Edge`, `Firefox`, `Safari`, `Opera`, ...).
+
+use serde::Serialize;
+
+/// What a request says about the browser it came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ClientHints {
+    /// Browser name, e.g. `Chrome`
+    pub browser: Option<String>,
+    /// Major version of the browser, e.g. `124`
+    pub browser_version: Option<String>,
+    /// Operating system, e.g. `Windows`
+    pub platform: Option<String>,
+    /// Phone-like device (tablets are not mobile, as in `Sec-CH-UA-Mobile`)
+    pub mobile: bool,
+}
+
+impl ClientHints {
+    /// Parse a legacy `User-Agent` string
+    pub fn from_user_agent(user_agent: &str) -> Self {
+        let (browser, browser_version) = match user_agent_browser(user_agent) {
+            Some((browser, version)) => (Some(browser.to_string()), version),
+            None => (None, None),
+        };
+        let ua = user_agent.to_lowercase();
+
+        Self {
+            browser,
+            browser_version,
+            platform: user_agent_platform(&ua).map(str::to_string),
+            mobile: ua.contains("mobi")
+                || ua.contains("iphone")
+                || ua.contains("ipod")
+                || ua.contains("windows phone"),
+        }
+    }
+
+    /// Override fields with the `Sec-CH-UA*` headers that were sent
+    pub fn with_hints(
+        mut self,
+        brands: Option<&str>,
+        mobile: Option<&str>,
+        platform: Option<&str>,
+    ) -> Self {
+        if let Some((browser, version)) = brands.and_then(preferred_brand) {
+            self.browser = Some(browser);
+            self.browser_version = version;
+        }
+        match mobile.map(str::trim) {
+            Some("?1") => self.mobile = true,
+            Some("?0") => self.mobile = false,
+            _ => {}
+        }
+        if let Some(platform) = platform.map(unquote).filter(|p| !p.is_empty()) {
+            if platform != "Unknown" {
+                self.platform = Some(platform.to_string());
+            }
+        }
+        self
+    }
+}
+
+/// Pick the real browser from a `Sec-CH-UA` brand list
+///
+/// Brands made up to keep parsers honest ("Not-A.Brand") are skipped, and a
+/// specific brand ("Google Chrome") wins over the engine ("Chromium").
+fn preferred_brand(header: &str) -> Option<(String, Option<String>)> {
+    let brands: Vec<(&str, Option<&str>)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let name = unquote(parts.next()?);
+            let version = parts.find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                (key.trim() == "v").then(|| unquote(value))
+            });
+            let grease = name.contains("Not") && name.contains("Brand");
+            (!name.is_empty() && !grease).then_some((name, version))
+        })
+        .collect();
+
+    let (name, version) = brands
+        .iter()
+        .find(|(name, _)| *name != "Chromium")
+        .or_else(|| brands.first())?;
+
+    let name = match *name {
+        "Google Chrome" => "Chrome",
+        "Microsoft Edge" => "Edge",
+        "Opera GX" => "Opera",
+        other => other,
+    };
+    let version = version.map(|v| v.split('.').next().unwrap_or(v).to_string());
+    Some((name.to_string(), version))
+}
+
+/// Browser name and major version from a `User-Agent`
+///
+/// Checked most specific first: Edge, Opera and Samsung Internet also claim
+/// to be Chrome, and Chrome also claims to be Safari.
+fn user_agent_browser(ua: &str) -> Option<(&'static str, Option<String>)> {
+    const BROWSERS: &[(&str, &str)] = &[
+        ("Edg/", "Edge"),
+        ("EdgA/", "Edge"),
+        ("EdgiOS/", "Edge"),
+        ("Edge/", "Edge"),
+        ("OPR/", "Opera"),
+        ("SamsungBrowser/", "Samsung Internet"),
+        ("Firefox/", "Firefox"),
+        ("FxiOS/", "Firefox"),
+        ("CriOS/", "Chrome"),
+        ("Chromium/", "Chromium"),
+        ("Chrome/", "Chrome"),
+    ];
+
+    for (token, name) in BROWSERS {
+        if let Some(index) = ua.find(token) {
+            return Some((name, major_version(&ua[index + token.len()..])));
+        }
+    }
+
+    if ua.contains("Safari/") {
+        let version = ua
+            .find("Version/")
+            .and_then(|index| major_version(&ua[index + "Version/".len()..]));
+        return Some(("Safari", version));
+    }
+    if let Some(index) = ua.find("MSIE ") {
+        return Some(("Internet Explorer", major_version(&ua[index + 5..])));
+    }
+    if ua.contains("Trident/") {
+        let version = ua
+            .find("rv:")
+            .and_then(|index| major_version(&ua[index + 3..]));
+        return Some(("Internet Explorer", version));
+    }
+    None
+}
+
+/// Operating system from a lowercased `User-Agent`
+fn user_agent_platform(ua: &str) -> Option<&'static str> {
+    // Android and Chrome OS user agents also mention Linux
+    if ua.contains("android") {
+        Some("Android")
+    } else if ua.contains("iphone") || ua.contains("ipad") || ua.contains("ipod") {
+        Some("iOS")
+    } else if ua.contains("windows") {
+        Some("Windows")
+    } else if ua.contains("cros ") {
+        Some("Chrome OS")
+    } else if ua.contains("mac os x") || ua.contains("macintosh") {
+        Some("macOS")
+    } else if ua.contains("linux") {
+        Some("Linux")
+    } else {
+        None
+    }
+}
+
+fn major_version(rest: &str) -> Option<String> {
+    let version: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    (!version.is_empty()).then_some(version)
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+        (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) \
+        AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+
+    fn hints(browser: &str, version: &str, platform: &str, mobile: bool) -> ClientHints {
+        ClientHints {
+            browser: Some(browser.to_string()),
+            browser_version: Some(version.to_string()),
+            platform: Some(platform.to_string()),
+            mobile,
+        }
+    }
+
+    #[test]
+    fn test_from_user_agent() {
+        assert_eq!(
+            ClientHints::from_user_agent(CHROME_WINDOWS),
+            hints("Chrome", "124", "Windows", false)
+        );
+        assert_eq!(
+            ClientHints::from_user_agent(SAFARI_IPHONE),
+            hints("Safari", "17", "iOS", true)
+        );
+        assert_eq!(
+            ClientHints::from_user_agent(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:125.0) Gecko/20100101 Firefox/125.0"
+            ),
+            hints("Firefox", "125", "macOS", false)
+        );
+        assert_eq!(
+            ClientHints::from_user_agent(
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/124.0.0.0 Mobile Safari/537.36 EdgA/124.0.0.0"
+            ),
+            hints("Edge", "124", "Android", true)
+        );
+        assert_eq!(
+            ClientHints::from_user_agent("curl/8.5.0"),
+            ClientHints::default()
+        );
+    }
+
+    #[test]
+    fn test_hints_override_user_agent() {
+        // Chrome's reduced User-Agent on Android hides the device; the hints do not
+        let parsed = ClientHints::from_user_agent(CHROME_WINDOWS).with_hints(
+            Some(r#""Chromium";v="124", "Microsoft Edge";v="124.0.2478.51", "Not-A.Brand";v="99""#),
+            Some("?1"),
+            Some(r#""Android""#),
+        );
+        assert_eq!(parsed, hints("Edge", "124", "Android", true));
+
+        // Only the engine and a GREASE brand
+        let parsed = ClientHints::default().with_hints(
+            Some(r#""Not A(Brand";v="8", "Chromium";v="132""#),
+            None,
+            Some(r#""Unknown""#),
+        );
+        assert_eq!(parsed.browser.as_deref(), Some("Chromium"));
+        assert_eq!(parsed.browser_version.as_deref(), Some("132"));
+        assert_eq!(parsed.platform, None);
+    }
+}
@@ -0,0 +1,348 @@
+//! Standalone cookie helpers
+//!
+//! Sessions manage their own cookie, but applications sometimes need
+//! independent cookies such as a "remember me" token. This module builds
+//! `Set-Cookie` header values and signs/verifies cookie values with an
+//! HMAC-SHA256 keyed by the application secret (`secret` in config.toml
+//! or the `RUSTF_SECRET` environment variable).
+//!
+//! Signed values have the form `<base64url(value)>.<base64url(mac)>`. The MAC
+//! covers both the cookie name and the value, so a signed value cannot be
+//! moved to another cookie name.
+//!
+//! Browsers drop `SameSite=None` cookies that are not `Secure`. Such cookies
+//! are upgraded to `Secure` with a warning, or rejected when
+//! `session.same_site_none = "error"` is configured.
+
+use crate::config::SameSiteNoneMode;
+use crate::error::{Error, Result};
+use crate::session::SameSite;
+use crate::utils::crypto::{constant_time_compare_bytes, hmac_sha256};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+/// Attributes applied to a `Set-Cookie` header
+#[derive(Debug, Clone)]
+pub struct CookieOptions {
+    /// Hide the cookie from JavaScript
+    pub http_only: bool,
+    /// Only send the cookie over HTTPS
+    pub secure: bool,
+    /// SameSite attribute
+    pub same_site: SameSite,
+    /// Lifetime in seconds (session cookie when `None`)
+    pub max_age: Option<u64>,
+    /// Cookie path
+    pub path: String,
+}
+
+impl Default for CookieOptions {
+    fn default() -> Self {
+        Self {
+            http_only: true,
+            secure: false,
+            same_site: SameSite::Lax,
+            max_age: None,
+            path: "/".to_string(),
+        }
+    }
+}
+
+impl CookieOptions {
+    /// Create options with secure defaults (HttpOnly, SameSite=Lax, Path=/)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the HttpOnly attribute
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set the Secure attribute
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set the SameSite attribute
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Set the Max-Age attribute in seconds
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Set the Path attribute
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Build the `Set-Cookie` header value for a cookie
+    ///
+    /// Fails for a `SameSite=None` cookie without `Secure` when the
+    /// configured policy is to reject it.
+    pub fn to_header(&self, name: &str, value: &str) -> Result<String> {
+        let secure = secure_attribute(name, self.same_site, self.secure)?;
+        let mut cookie = format!("{}={}; Path={}", name, value, self.path);
+
+        if let Some(max_age) = self.max_age {
+            cookie.push_str(&format!("; Max-Age={}", max_age));
+        }
+        if self.http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        if secure {
+            cookie.push_str("; Secure");
+        }
+        cookie.push_str(&format!("; SameSite={}", self.same_site));
+
+        Ok(cookie)
+    }
+}
+
+/// Decide the `Secure` attribute of a cookie using the configured policy
+pub fn secure_attribute(name: &str, same_site: SameSite, secure: bool) -> Result<bool> {
+    let mode = crate::configuration::CONF::all()
+        .map(|config| config.session.same_site_none)
+        .unwrap_or_default();
+    secure_attribute_with(name, same_site, secure, mode)
+}
+
+/// Decide the `Secure` attribute of a cookie
+///
+/// Returns `secure` unchanged unless the cookie is `SameSite=None` without
+/// `Secure`, which `mode` either upgrades or rejects.
+pub fn secure_attribute_with(
+    name: &str,
+    same_site: SameSite,
+    secure: bool,
+    mode: SameSiteNoneMode,
+) -> Result<bool> {
+    if secure || same_site != SameSite::None {
+        return Ok(secure);
+    }
+
+    match mode {
+        SameSiteNoneMode::Warn => {
+            log::warn!(
+                "Cookie '{}' uses SameSite=None without Secure; adding Secure",
+                name
+            );
+            Ok(true)
+        }
+        SameSiteNoneMode::Error => Err(Error::internal(format!(
+            "Cookie '{}' uses SameSite=None without Secure and would be dropped by browsers",
+            name
+        ))),
+    }
+}
+
+/// Cookies sent by the client, parsed from the `Cookie` header
+///
+/// Browsers may send several cookies with the same name (set for different
+/// paths or domains), most specific first. `get` returns the first one and
+/// `get_all` returns every value in header order. Values are stored as sent
+/// and unquoted on read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: Vec<(String, String)>,
+}
+
+impl CookieJar {
+    /// Parse a `Cookie` header value (`name=value; name2=value2`)
+    ///
+    /// Pairs without `=` or with an empty name are skipped.
+    pub fn parse(header: &str) -> Self {
+        let cookies = header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                Some((name.to_string(), value.trim().to_string()))
+            })
+            .collect();
+
+        Self { cookies }
+    }
+
+    /// Get the first value of a cookie, unquoted
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| unquote(value))
+    }
+
+    /// Get the last value of a cookie exactly as sent, quotes included
+    ///
+    /// These are the semantics of `Request::cookie`.
+    pub fn get_raw(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .rev()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get every value sent for a cookie name, unquoted
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.cookies
+            .iter()
+            .filter(|(cookie, _)| cookie == name)
+            .map(|(_, value)| unquote(value))
+            .collect()
+    }
+
+    /// Check whether a cookie was sent
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Iterate over all `(name, value)` pairs in header order, unquoted
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.cookies
+            .iter()
+            .map(|(name, value)| (name.as_str(), unquote(value)))
+    }
+
+    /// Number of cookies, counting duplicates
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Check whether no cookies were sent
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+}
+
+/// Strip the double quotes around a cookie value
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Get the application secret used for signing
+///
+/// Returns an error when no non-empty `secret` is configured.
+pub fn app_secret() -> Result<String> {
+    crate::configuration::CONF::get_string("secret")
+        .filter(|secret| !secret.is_empty())
+        .ok_or_else(|| {
+            Error::internal(
+                "Application secret not configured. Set 'secret' in config.toml or RUSTF_SECRET.",
+            )
+        })
+}
+
+/// Sign a cookie value with the given secret
+pub fn sign_value(name: &str, value: &str, secret: &str) -> String {
+    let encoded = URL_SAFE_NO_PAD.encode(value.as_bytes());
+    let mac = compute_mac(name, &encoded, secret);
+    format!("{}.{}", encoded, URL_SAFE_NO_PAD.encode(mac))
+}
+
+/// Verify a signed cookie value and return the original value
+///
+/// Returns `None` if the value is malformed or the signature does not match.
+pub fn verify_value(name: &str, signed: &str, secret: &str) -> Option<String> {
+    let (encoded, signature) = signed.rsplit_once('.')?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    let expected = compute_mac(name, encoded, secret);
+
+    if !constant_time_compare_bytes(&signature, &expected) {
+        return None;
+    }
+
+    let bytes = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn compute_mac(name: &str, encoded_value: &str, secret: &str) -> Vec<u8> {
+    let message = format!("{}={}", name, encoded_value);
+    hmac_sha256(secret.as_bytes(), message.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_header() {
+        let options = CookieOptions::new()
+            .secure(true)
+            .same_site(SameSite::Strict)
+            .max_age(3600)
+            .path("/account");
+
+        assert_eq!(
+            options.to_header("remember", "abc").unwrap(),
+            "remember=abc; Path=/account; Max-Age=3600; HttpOnly; Secure; SameSite=Strict"
+        );
+    }
+
+    #[test]
+    fn test_same_site_none_requires_secure() {
+        use SameSiteNoneMode::{Error, Warn};
+
+        assert!(!secure_attribute_with("a", SameSite::Lax, false, Error).unwrap());
+        assert!(secure_attribute_with("a", SameSite::None, true, Error).unwrap());
+        assert!(secure_attribute_with("a", SameSite::None, false, Warn).unwrap());
+        assert!(secure_attribute_with("a", SameSite::None, false, Error).is_err());
+
+        let header = CookieOptions::new()
+            .same_site(SameSite::None)
+            .to_header("embed", "1")
+            .unwrap();
+        assert!(header.contains("; Secure"));
+    }
+
+    #[test]
+    fn test_cookie_jar() {
+        let jar = CookieJar::parse("theme=dark; id=1; =skip; flag; id=2; quoted=\"a b\"");
+
+        assert_eq!(jar.get("theme"), Some("dark"));
+        assert_eq!(jar.get("id"), Some("1"));
+        assert_eq!(jar.get_all("id"), vec!["1", "2"]);
+        assert_eq!(jar.get("quoted"), Some("a b"));
+        assert!(!jar.contains("flag"));
+        assert_eq!(jar.len(), 4);
+        assert_eq!(
+            jar.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec!["theme", "id", "id", "quoted"]
+        );
+        assert!(CookieJar::parse("").is_empty());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signed = sign_value("remember", "user:42", "secret");
+        assert_eq!(
+            verify_value("remember", &signed, "secret"),
+            Some("user:42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let signed = sign_value("remember", "user:42", "secret");
+        let (_, signature) = signed.rsplit_once('.').unwrap();
+        let forged = format!("{}.{}", URL_SAFE_NO_PAD.encode("user:1"), signature);
+
+        assert_eq!(verify_value("remember", &forged, "secret"), None);
+        assert_eq!(verify_value("remember", &signed, "other"), None);
+        assert_eq!(verify_value("other", &signed, "secret"), None);
+        assert_eq!(verify_value("remember", "garbage", "secret"), None);
+    }
+}
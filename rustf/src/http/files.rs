@@ -198,12 +198,24 @@ pub struct MultipartParser;
 impl MultipartParser {
     /// Parse multipart form data from request body
     pub fn parse(body: &[u8], boundary: &str) -> Result<(FileCollection, HashMap<String, String>)> {
+        Self::parse_with_limit(body, boundary, usize::MAX)
+    }
+
+    /// Parse multipart form data, rejecting any part over `max_part_size` bytes
+    ///
+    /// Fails with `Error::PayloadTooLarge` as soon as an oversized part is
+    /// found, before it is copied.
+    pub fn parse_with_limit(
+        body: &[u8],
+        boundary: &str,
+        max_part_size: usize,
+    ) -> Result<(FileCollection, HashMap<String, String>)> {
         let boundary_bytes = format!("--{}", boundary).into_bytes();
         let mut files = FileCollection::new();
         let mut form_data = HashMap::new();
 
         // Simple multipart parsing (basic implementation)
-        let parts = Self::split_multipart(body, &boundary_bytes);
+        let parts = Self::split_multipart(body, &boundary_bytes, max_part_size)?;
 
         for part in parts {
             if let Some((headers, body)) = Self::parse_part(&part) {
@@ -229,12 +241,18 @@ impl MultipartParser {
         Ok((files, form_data))
     }
 
-    fn split_multipart(body: &[u8], boundary: &[u8]) -> Vec<Vec<u8>> {
+    fn split_multipart(body: &[u8], boundary: &[u8], max_part_size: usize) -> Result<Vec<Vec<u8>>> {
         let mut parts = Vec::new();
         let mut start = 0;
 
         while let Some(pos) = Self::find_bytes(body, boundary, start) {
             if start > 0 {
+                if pos - start > max_part_size {
+                    return Err(Error::payload_too_large(format!(
+                        "Multipart part exceeds the limit of {} bytes",
+                        max_part_size
+                    )));
+                }
                 let part = body[start..pos].to_vec();
                 if !part.is_empty() {
                     parts.push(part);
@@ -243,7 +261,7 @@ impl MultipartParser {
             start = pos + boundary.len();
         }
 
-        parts
+        Ok(parts)
     }
 
     fn find_bytes(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
@@ -296,3 +314,33 @@ impl MultipartParser {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multipart_body(file_size: usize) -> Vec<u8> {
+        let mut body = b"--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n\
+            --XYZ\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.bin\"\r\n\
+            Content-Type: application/octet-stream\r\n\r\n"
+            .to_vec();
+        body.resize(body.len() + file_size, b'x');
+        body.extend_from_slice(b"\r\n--XYZ--\r\n");
+        body
+    }
+
+    #[test]
+    fn test_parse_with_part_limit() {
+        let body = multipart_body(100);
+        let (files, form_data) = MultipartParser::parse_with_limit(&body, "XYZ", 1024).unwrap();
+        assert!(form_data.contains_key("title"));
+        assert_eq!(
+            files.get("upload").unwrap().filename.as_deref(),
+            Some("a.bin")
+        );
+
+        let body = multipart_body(4096);
+        let error = MultipartParser::parse_with_limit(&body, "XYZ", 1024).unwrap_err();
+        assert_eq!(error.status_code(), 413);
+    }
+}
@@ -0,0 +1,447 @@
+//! Nested form fields
+//!
+//! HTML forms have no nesting of their own, so nested data is spelled out in
+//! the field names. Brackets are rebuilt into nested values:
+//!
+//! ```text
+//! user[name]=Ada&user[address][city]=London   user: { name, address: { city } }
+//! items[0][sku]=A1&items[1][sku]=B2           items: [{ sku }, { sku }]
+//! items[][sku]=A1&items[][sku]=B2             items: [{ sku }, { sku }]
+//! tags[]=rust&tags[]=web                      tags: ["rust", "web"]
+//! ```
+//!
+//! Indexes must run from 0 without gaps, in any order; other numeric keys
+//! (`prices[2024]`) stay object keys. With `items[]`, a field that is already
+//! set on the last item starts the next one, so each item's fields must be
+//! sent together. A name used in two shapes (`tags[]` and `tags[x]`, or
+//! `tags` and `tags[x]`) is rejected. Leaf values are parsed into the target
+//! field types like query parameters (see `http::query`).
+
+use super::query::ValueDeserializer;
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde_json::Value;
+
+/// Deepest bracket nesting rebuilt; deeper names are kept as flat keys
+const MAX_DEPTH: usize = 32;
+
+/// Deserialize ordered form pairs into `T`, rebuilding bracketed names
+pub fn from_pairs<T: DeserializeOwned>(pairs: &[(String, String)]) -> Result<T> {
+    T::deserialize(build(pairs)?)
+        .map_err(|e| Error::InvalidInput(format!("Invalid form fields: {}", e)))
+}
+
+/// Ordered form pairs as a JSON object, bracketed names nested
+///
+/// Leaf values stay strings; a field given several values becomes an array.
+pub fn to_value(pairs: &[(String, String)]) -> Result<Value> {
+    Ok(build(pairs)?.into_value())
+}
+
+/// One node of the rebuilt form
+enum Field<'a> {
+    /// Every value sent for a leaf, in order
+    Values(Vec<&'a str>),
+    /// Named or indexed children (`user[name]`, `items[0]`)
+    Map(IndexMap<String, Field<'a>>),
+    /// Items added with `[]`
+    List(Vec<Field<'a>>),
+}
+
+fn build(pairs: &[(String, String)]) -> Result<Field<'_>> {
+    let mut root = Field::Map(IndexMap::new());
+    for (key, value) in pairs {
+        let (name, segments) = split_key(key);
+        let mut path = Vec::with_capacity(segments.len() + 1);
+        path.push(name);
+        path.extend(segments);
+        root.insert(&path, value).map_err(|()| {
+            Error::InvalidInput(format!(
+                "Invalid form fields: `{}` mixes appended, named and plain values",
+                key
+            ))
+        })?;
+    }
+    Ok(root)
+}
+
+/// Split `user[address][city]` into `user` and `["address", "city"]`
+///
+/// Names that are not well-formed bracket paths are kept whole.
+fn split_key(key: &str) -> (&str, Vec<&str>) {
+    let Some(open) = key.find('[') else {
+        return (key, Vec::new());
+    };
+    let (name, mut rest) = key.split_at(open);
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        let Some((segment, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) else {
+            return (key, Vec::new());
+        };
+        segments.push(segment);
+        rest = after;
+    }
+
+    if name.is_empty() || segments.len() > MAX_DEPTH {
+        return (key, Vec::new());
+    }
+    (name, segments)
+}
+
+impl<'a> Field<'a> {
+    /// Add a value under `path`; `Err` when the path needs another shape
+    fn insert(&mut self, path: &[&str], value: &'a str) -> std::result::Result<(), ()> {
+        let Some((&segment, rest)) = path.split_first() else {
+            let Field::Values(values) = self else {
+                return Err(());
+            };
+            values.push(value);
+            return Ok(());
+        };
+
+        // `tags[]` is a repeated value, like `tags=a&tags=b`
+        if segment.is_empty() && rest.is_empty() {
+            return self.insert(rest, value);
+        }
+
+        if segment.is_empty() {
+            let items = self.list()?;
+            let starts_item = match items.last() {
+                Some(Field::Map(map)) => map.contains_key(rest[0]),
+                _ => true,
+            };
+            if starts_item {
+                items.push(Field::Values(Vec::new()));
+            }
+            match items.last_mut() {
+                Some(item) => item.insert(rest, value),
+                None => Err(()),
+            }
+        } else {
+            self.map()?
+                .entry(segment.to_string())
+                .or_insert_with(|| Field::Values(Vec::new()))
+                .insert(rest, value)
+        }
+    }
+
+    /// A field nothing was inserted into yet, free to take any shape
+    fn is_unset(&self) -> bool {
+        matches!(self, Field::Values(values) if values.is_empty())
+    }
+
+    fn map(&mut self) -> std::result::Result<&mut IndexMap<String, Field<'a>>, ()> {
+        if self.is_unset() {
+            *self = Field::Map(IndexMap::new());
+        }
+        match self {
+            Field::Map(map) => Ok(map),
+            _ => Err(()),
+        }
+    }
+
+    fn list(&mut self) -> std::result::Result<&mut Vec<Field<'a>>, ()> {
+        if self.is_unset() {
+            *self = Field::List(Vec::new());
+        }
+        match self {
+            Field::List(items) => Ok(items),
+            _ => Err(()),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            Field::Values(values) if values.len() == 1 => Value::String(values[0].to_string()),
+            Field::Values(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|v| Value::String(v.to_string()))
+                    .collect(),
+            ),
+            Field::Map(map) => match indexed(map) {
+                Ok(items) => Value::Array(items.into_iter().map(Field::into_value).collect()),
+                Err(map) => Value::Object(
+                    map.into_iter()
+                        .map(|(key, field)| (key, field.into_value()))
+                        .collect(),
+                ),
+            },
+            Field::List(items) => Value::Array(items.into_iter().map(Field::into_value).collect()),
+        }
+    }
+}
+
+/// The children of `items[0]`, `items[1]`, ... in index order
+///
+/// Gives the map back unless the keys are exactly `0..len`, so sparse
+/// numeric keys such as years or ids stay an object.
+fn indexed(
+    map: IndexMap<String, Field<'_>>,
+) -> std::result::Result<Vec<Field<'_>>, IndexMap<String, Field<'_>>> {
+    let dense = !map.is_empty()
+        && map.keys().all(|key| {
+            key.parse::<usize>()
+                .is_ok_and(|index| index < map.len() && index.to_string() == *key)
+        });
+    if !dense {
+        return Err(map);
+    }
+    let mut items: Vec<(usize, Field)> = map
+        .into_iter()
+        .filter_map(|(key, field)| Some((key.parse().ok()?, field)))
+        .collect();
+    items.sort_by_key(|(index, _)| *index);
+    Ok(items.into_iter().map(|(_, field)| field).collect())
+}
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for Field<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_leaf {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+            match self {
+                Field::Values(values) => ValueDeserializer(values).$method(visitor),
+                other => other.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Field<'de> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Field::Values(values) => ValueDeserializer(values).deserialize_any(visitor),
+            Field::Map(map) => visitor.visit_map(MapDeserializer::new(map.into_iter())),
+            Field::List(items) => visitor.visit_seq(SeqDeserializer::new(items.into_iter())),
+        }
+    }
+
+    deserialize_leaf! {
+        deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Field::Values(values) => ValueDeserializer(values).deserialize_option(visitor),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Field::Values(values) => ValueDeserializer(values).deserialize_seq(visitor),
+            Field::Map(map) => match indexed(map) {
+                Ok(items) => visitor.visit_seq(SeqDeserializer::new(items.into_iter())),
+                Err(map) => visitor.visit_map(MapDeserializer::new(map.into_iter())),
+            },
+            Field::List(items) => visitor.visit_seq(SeqDeserializer::new(items.into_iter())),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self {
+            Field::Values(values) => {
+                ValueDeserializer(values).deserialize_enum(name, variants, visitor)
+            }
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Address {
+        city: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+        address: Address,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        sku: String,
+        quantity: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Order {
+        user: User,
+        items: Vec<Item>,
+        tags: Vec<String>,
+        gift: bool,
+    }
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_nested_structs_and_vectors() {
+        let indexed = pairs(&[
+            ("user[name]", "Ada"),
+            ("user[address][city]", "London"),
+            ("user[address][zip]", ""),
+            ("items[1][sku]", "B2"),
+            ("items[1][quantity]", "1"),
+            ("items[0][sku]", "A1"),
+            ("items[0][quantity]", "3"),
+            ("tags[]", "rust"),
+            ("tags[]", "web"),
+            ("gift", "on"),
+        ]);
+        let order: Order = from_pairs(&indexed).unwrap();
+
+        assert_eq!(order.user.name, "Ada");
+        assert_eq!(order.user.address.city, "London");
+        assert_eq!(order.user.address.zip, None);
+        assert_eq!(
+            order.items,
+            vec![
+                Item {
+                    sku: "A1".into(),
+                    quantity: 3
+                },
+                Item {
+                    sku: "B2".into(),
+                    quantity: 1
+                },
+            ]
+        );
+        assert_eq!(order.tags, vec!["rust", "web"]);
+        assert!(order.gift);
+
+        // Appended items start over whenever a field repeats
+        let appended = pairs(&[
+            ("user[name]", "Ada"),
+            ("user[address][city]", "London"),
+            ("items[][sku]", "A1"),
+            ("items[][quantity]", "3"),
+            ("items[][sku]", "B2"),
+            ("items[][quantity]", "1"),
+            ("tags", "rust"),
+            ("gift", "0"),
+        ]);
+        let order: Order = from_pairs(&appended).unwrap();
+        assert_eq!(order.items.len(), 2);
+        assert_eq!(
+            order.items[1],
+            Item {
+                sku: "B2".into(),
+                quantity: 1
+            }
+        );
+        assert_eq!(order.tags, vec!["rust"]);
+
+        let invalid: Result<Order> = from_pairs(&pairs(&[("items[0][quantity]", "many")]));
+        assert!(matches!(invalid, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_to_value() {
+        let value = to_value(&pairs(&[
+            ("user[name]", "Ada"),
+            ("items[1][sku]", "B2"),
+            ("items[0][sku]", "A1"),
+            ("prices[2024]", "10"),
+            ("gaps[0]", "a"),
+            ("gaps[2]", "c"),
+            ("padded[00]", "a"),
+            ("tags[]", "rust"),
+            ("tags[]", "web"),
+            ("broken[name", "kept"),
+            ("[0]", "kept"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "user": { "name": "Ada" },
+                "items": [{ "sku": "A1" }, { "sku": "B2" }],
+                "prices": { "2024": "10" },
+                "gaps": { "0": "a", "2": "c" },
+                "padded": { "00": "a" },
+                "tags": ["rust", "web"],
+                "broken[name": "kept",
+                "[0]": "kept",
+            })
+        );
+    }
+
+    #[test]
+    fn test_mixed_shapes_are_rejected() {
+        for mixed in [
+            &[("tags[]", "a"), ("tags[x]", "b")][..],
+            &[("tags[x]", "a"), ("tags[]", "b")],
+            &[("tags", "a"), ("tags[0]", "b")],
+            &[("items[0][sku]", "A1"), ("items[][sku]", "B2")],
+            &[("items[][sku]", "A1"), ("items[]", "B2")],
+        ] {
+            let result = to_value(&pairs(mixed));
+            assert!(
+                matches!(result, Err(Error::InvalidInput(_))),
+                "{:?} gave {:?}",
+                mixed,
+                result
+            );
+        }
+
+        // Plain and `[]` values are the same shape
+        let value = to_value(&pairs(&[("tags", "a"), ("tags[]", "b")])).unwrap();
+        assert_eq!(value, json!({ "tags": ["a", "b"] }));
+    }
+}
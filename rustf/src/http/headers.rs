@@ -0,0 +1,324 @@
+//! Typed views of common request headers
+//!
+//! The parsers behind `Request::content_type()`, `accept_languages()`,
+//! `range()` and `bearer_token()`, and the entity tag helpers used for
+//! conditional responses. They are lenient in what they accept
+//! from clients: malformed entries are skipped where the rest of the header
+//! still makes sense, and the whole header is ignored otherwise.
+//!
+//! [`RequestHeaders`] holds a request's headers together with the values
+//! parsed from them, and drops those values whenever the headers are
+//! borrowed mutably, so a middleware that rewrites a header is seen by
+//! everything that runs after it.
+
+use super::cookie::CookieJar;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+/// Headers of a request, with lowercase names
+///
+/// Reads and writes go through the underlying `HashMap`. Cookies,
+/// `Content-Type` and `Accept-Language` are parsed on first access and
+/// cached until the next mutable borrow.
+#[derive(Debug, Default)]
+pub struct RequestHeaders {
+    map: HashMap<String, String>,
+    /// Cookies parsed from the `Cookie` header
+    pub(super) cookie_jar: OnceLock<CookieJar>,
+    /// Parsed `Content-Type`
+    pub(super) content_type: OnceLock<Option<ContentType>>,
+    /// Parsed `Accept-Language`
+    pub(super) accept_languages: OnceLock<Vec<QualityItem>>,
+}
+
+impl RequestHeaders {
+    /// Drop the parsed values so they are read again from the headers
+    fn invalidate(&mut self) {
+        self.cookie_jar = OnceLock::new();
+        self.content_type = OnceLock::new();
+        self.accept_languages = OnceLock::new();
+    }
+}
+
+impl Deref for RequestHeaders {
+    type Target = HashMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for RequestHeaders {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.invalidate();
+        &mut self.map
+    }
+}
+
+impl From<HashMap<String, String>> for RequestHeaders {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self {
+            map,
+            ..Self::default()
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a RequestHeaders {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::collections::hash_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+/// A parsed `Content-Type` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentType {
+    /// Lowercased `type/subtype`, e.g. `application/json`
+    pub mime: String,
+    /// Parameters with lowercased names and unquoted values
+    pub params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// Parse a header value; `None` if there is no `type/subtype`
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.split(';');
+        let mime = parts.next()?.trim().to_ascii_lowercase();
+        let (kind, subtype) = mime.split_once('/')?;
+        if kind.is_empty() || subtype.is_empty() {
+            return None;
+        }
+
+        let params = parts
+            .filter_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                Some((name.trim().to_ascii_lowercase(), value.to_string()))
+            })
+            .collect();
+
+        Some(Self { mime, params })
+    }
+
+    /// Whether the media type is `mime` (case-insensitive, parameters ignored)
+    pub fn is(&self, mime: &str) -> bool {
+        self.mime.eq_ignore_ascii_case(mime)
+    }
+
+    /// Value of a parameter
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The `charset` parameter
+    pub fn charset(&self) -> Option<&str> {
+        self.param("charset")
+    }
+
+    /// The `boundary` parameter of multipart bodies
+    pub fn boundary(&self) -> Option<&str> {
+        self.param("boundary")
+    }
+
+    /// Whether this is a JSON body (`application/json` or `+json`)
+    pub fn is_json(&self) -> bool {
+        self.is("application/json") || self.mime.ends_with("+json")
+    }
+}
+
+/// An entry of a header with quality values, such as `Accept-Language`
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityItem {
+    /// The entry, e.g. `fr-CH` or `*`
+    pub value: String,
+    /// Its weight between 0 and 1 (1 when not given)
+    pub quality: f32,
+}
+
+/// Parse a comma-separated list with `;q=` weights, best first
+///
+/// Entries with `q=0` are refused by the client and left out; entries with
+/// equal weights keep their order.
+pub fn parse_quality_list(header: &str) -> Vec<QualityItem> {
+    let mut items: Vec<QualityItem> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let value = parts.next()?.trim();
+            if value.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?
+                .clamp(0.0, 1.0);
+            (quality > 0.0).then(|| QualityItem {
+                value: value.to_string(),
+                quality,
+            })
+        })
+        .collect();
+
+    items.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+    items
+}
+
+/// One range of a `Range: bytes=...` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRangeSpec {
+    /// `start-end`, both inclusive
+    FromTo(u64, u64),
+    /// `start-`, to the end
+    From(u64),
+    /// `-n`, the last n bytes
+    Suffix(u64),
+}
+
+impl ByteRangeSpec {
+    /// Inclusive start and end offsets within a body of `length` bytes
+    ///
+    /// `None` when the range is not satisfiable for that length.
+    pub fn resolve(&self, length: u64) -> Option<(u64, u64)> {
+        let last = length.checked_sub(1)?;
+        match *self {
+            ByteRangeSpec::FromTo(start, end) if start < length => Some((start, end.min(last))),
+            ByteRangeSpec::From(start) if start < length => Some((start, last)),
+            ByteRangeSpec::Suffix(suffix) if suffix > 0 => {
+                Some((length.saturating_sub(suffix), last))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parse a `Range` header in the `bytes` unit
+///
+/// `None` if the unit is not `bytes` or any range is malformed, in which
+/// case the header should be ignored.
+pub fn parse_range(header: &str) -> Option<Vec<ByteRangeSpec>> {
+    let specs = header.trim().strip_prefix("bytes=")?;
+    specs
+        .split(',')
+        .map(|spec| {
+            let (start, end) = spec.trim().split_once('-')?;
+            let (start, end) = (start.trim(), end.trim());
+            match (start.is_empty(), end.is_empty()) {
+                (true, false) => Some(ByteRangeSpec::Suffix(end.parse().ok()?)),
+                (false, true) => Some(ByteRangeSpec::From(start.parse().ok()?)),
+                (false, false) => {
+                    let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+                    (start <= end).then_some(ByteRangeSpec::FromTo(start, end))
+                }
+                (true, true) => None,
+            }
+        })
+        .collect()
+}
+
+/// Token of an `Authorization: Bearer <token>` header
+pub fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Quote `value` as an entity tag unless it already is one
+///
+/// `"abc"` and `W/"abc"` are kept as they are; anything else is wrapped in
+/// quotes, with embedded quotes dropped.
+pub fn entity_tag(value: &str) -> String {
+    let value = value.trim();
+    let quoted = |tag: &str| tag.len() >= 2 && tag.starts_with('"') && tag.ends_with('"');
+    if quoted(value) || value.strip_prefix("W/").is_some_and(quoted) {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('"', ""))
+    }
+}
+
+/// Whether an `If-None-Match` header matches `etag`
+///
+/// Uses the weak comparison `If-None-Match` calls for, so `W/"a"` matches
+/// `"a"`; `*` matches any tag.
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type() {
+        let ct =
+            ContentType::parse("Multipart/Form-Data; boundary=\"----abc\"; charset=UTF-8").unwrap();
+        assert!(ct.is("multipart/form-data"));
+        assert_eq!(ct.boundary(), Some("----abc"));
+        assert_eq!(ct.charset(), Some("UTF-8"));
+
+        assert!(ContentType::parse("application/vnd.api+json")
+            .unwrap()
+            .is_json());
+        assert_eq!(ContentType::parse("json"), None);
+    }
+
+    #[test]
+    fn test_quality_list_is_sorted() {
+        let langs = parse_quality_list("en;q=0.5, fr-CH, de;q=0, fr;q=0.9, *;q=0.1");
+        let values: Vec<&str> = langs.iter().map(|l| l.value.as_str()).collect();
+        assert_eq!(values, ["fr-CH", "fr", "en", "*"]);
+        assert_eq!(langs[1].quality, 0.9);
+    }
+
+    #[test]
+    fn test_byte_ranges() {
+        assert_eq!(
+            parse_range("bytes=0-99, 200-, -50"),
+            Some(vec![
+                ByteRangeSpec::FromTo(0, 99),
+                ByteRangeSpec::From(200),
+                ByteRangeSpec::Suffix(50),
+            ])
+        );
+        assert_eq!(parse_range("bytes=5-1"), None);
+        assert_eq!(parse_range("items=0-1"), None);
+
+        assert_eq!(ByteRangeSpec::FromTo(5, 100).resolve(10), Some((5, 9)));
+        assert_eq!(ByteRangeSpec::Suffix(3).resolve(10), Some((7, 9)));
+        assert_eq!(ByteRangeSpec::From(10).resolve(10), None);
+        assert_eq!(ByteRangeSpec::Suffix(0).resolve(10), None);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(bearer_token("Bearer abc.def"), Some("abc.def"));
+        assert_eq!(bearer_token("bearer  abc"), Some("abc"));
+        assert_eq!(bearer_token("Basic dXNlcjpwYXNz"), None);
+        assert_eq!(bearer_token("Bearer "), None);
+    }
+
+    #[test]
+    fn test_entity_tags() {
+        assert_eq!(entity_tag("1700000000"), "\"1700000000\"");
+        assert_eq!(entity_tag("W/\"v2\""), "W/\"v2\"");
+
+        assert!(if_none_match("\"a\", W/\"b\"", "\"b\""));
+        assert!(if_none_match("*", "\"c\""));
+        assert!(!if_none_match("\"a\"", "\"b\""));
+    }
+}
@@ -0,0 +1,100 @@
+//! Structural limits for JSON request bodies
+//!
+//! Bodies are scanned once, without allocating per value, before being handed
+//! to the JSON parser. A body nested deeper than `json.max_depth`, or with an
+//! object or array holding more than `json.max_elements` entries, is rejected
+//! with `Error::InvalidInput` instead of being parsed.
+
+use crate::config::JsonConfig;
+use crate::configuration::CONF;
+use crate::error::{Error, Result};
+
+/// Check a JSON body against the limits in the global configuration
+///
+/// Falls back to the default limits when configuration is not initialized.
+pub fn check_configured(body: &[u8]) -> Result<()> {
+    match CONF::all() {
+        Some(config) => check(body, &config.json),
+        None => check(body, &JsonConfig::default()),
+    }
+}
+
+/// Check a JSON body against the given limits
+///
+/// Malformed JSON is not reported here; the parser does that afterwards.
+pub fn check(body: &[u8], limits: &JsonConfig) -> Result<()> {
+    // Separators seen in each open container, innermost last
+    let mut separators: Vec<usize> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &byte in body {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                if separators.len() >= limits.max_depth {
+                    return Err(Error::InvalidInput(format!(
+                        "JSON body is nested deeper than {} levels",
+                        limits.max_depth
+                    )));
+                }
+                separators.push(0);
+            }
+            b'}' | b']' => {
+                separators.pop();
+            }
+            b',' => {
+                if let Some(count) = separators.last_mut() {
+                    *count += 1;
+                    // n separators means n + 1 entries
+                    if *count >= limits.max_elements {
+                        return Err(Error::InvalidInput(format!(
+                            "JSON body has an object or array with more than {} entries",
+                            limits.max_elements
+                        )));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_depth: usize, max_elements: usize) -> JsonConfig {
+        JsonConfig {
+            max_depth,
+            max_elements,
+            ..JsonConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_depth_and_element_limits() {
+        let limits = limits(3, 3);
+
+        assert!(check(br#"{"a": [1, 2, {"b": null}]}"#, &limits).is_ok());
+        assert!(check(br#"{"a": [[{"b": 1}]]}"#, &limits).is_err());
+
+        assert!(check(br#"{"a": 1, "b": 2, "c": 3}"#, &limits).is_ok());
+        assert!(check(br#"[1, 2, 3, 4]"#, &limits).is_err());
+
+        // Brackets and commas inside strings are not structure
+        assert!(check(br#"{"a": "[[[[,,,,\"]]]]"}"#, &limits).is_ok());
+    }
+}
@@ -0,0 +1,161 @@
+//! Exact handling of large JSON numbers
+//!
+//! JSON puts no bound on numbers, but bodies are parsed into `i64`, `u64` or
+//! `f64`: an integer beyond 64 bits fails to parse and a decimal with many
+//! digits is silently rounded. With `json.numbers = "strict"` a body holding
+//! such a number is rejected; with `"preserve"` the number is quoted so it
+//! reaches the handler as a string with its original digits, ready for a
+//! `String`, `Decimal` or custom field:
+//!
+//! ```text
+//! {"id": 123456789012345678901234, "amount": 1234567.123456789012}
+//! {"id": "123456789012345678901234", "amount": "1234567.123456789012"}
+//! ```
+//!
+//! Numbers that fit are left alone.
+
+use crate::config::{JsonConfig, JsonNumbers};
+use crate::configuration::CONF;
+use crate::error::{Error, Result};
+use std::borrow::Cow;
+
+/// Most significant digits an `f64` keeps for any decimal
+const F64_DIGITS: usize = 15;
+
+/// Apply the `json.numbers` mode from the global configuration
+///
+/// Falls back to the default mode when configuration is not initialized.
+pub fn apply_configured(body: &[u8]) -> Result<Cow<'_, [u8]>> {
+    match CONF::all() {
+        Some(config) => apply(body, config.json.numbers),
+        None => apply(body, JsonConfig::default().numbers),
+    }
+}
+
+/// Reject or quote the inexact numbers of a JSON body, depending on `mode`
+///
+/// Malformed JSON is not reported here; the parser does that afterwards.
+pub fn apply(body: &[u8], mode: JsonNumbers) -> Result<Cow<'_, [u8]>> {
+    if mode == JsonNumbers::Lossy {
+        return Ok(Cow::Borrowed(body));
+    }
+
+    let mut quoted: Option<Vec<u8>> = None;
+    // Body bytes already copied into `quoted`
+    let mut copied = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut index = 0;
+
+    while index < body.len() {
+        let byte = body[index];
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            index += 1;
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'-' | b'0'..=b'9' => {
+                let end = body[index..]
+                    .iter()
+                    .position(|b| !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+                    .map_or(body.len(), |len| index + len);
+                let number = std::str::from_utf8(&body[index..end]).unwrap_or_default();
+
+                if !is_exact(number) {
+                    if mode == JsonNumbers::Strict {
+                        return Err(Error::InvalidInput(
+                            "JSON body has a number that cannot be represented exactly".to_string(),
+                        ));
+                    }
+                    let output = quoted.get_or_insert_with(|| Vec::with_capacity(body.len() + 16));
+                    output.extend_from_slice(&body[copied..index]);
+                    output.push(b'"');
+                    output.extend_from_slice(number.as_bytes());
+                    output.push(b'"');
+                    copied = end;
+                }
+                index = end;
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    Ok(match quoted {
+        Some(mut output) => {
+            output.extend_from_slice(&body[copied..]);
+            Cow::Owned(output)
+        }
+        None => Cow::Borrowed(body),
+    })
+}
+
+/// Whether a number parses into `i64`, `u64` or `f64` without losing digits
+fn is_exact(number: &str) -> bool {
+    let Ok(value) = number.parse::<f64>() else {
+        // Not a number; the parser reports it
+        return true;
+    };
+    if !number.contains(['.', 'e', 'E']) {
+        return number.parse::<i64>().is_ok() || number.parse::<u64>().is_ok();
+    }
+
+    let mantissa = number.split(['e', 'E']).next().unwrap_or(number);
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0').trim_end_matches('0');
+    significant.len() <= F64_DIGITS && value.is_finite() && (value != 0.0 || significant.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preserve(body: &str) -> String {
+        String::from_utf8(
+            apply(body.as_bytes(), JsonNumbers::Preserve)
+                .unwrap()
+                .into_owned(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inexact_numbers() {
+        let body =
+            r#"{"id": 123456789012345678901234, "amount": -1234567.123456789012, "n": [1, 2.5e3]}"#;
+
+        assert_eq!(
+            preserve(body),
+            r#"{"id": "123456789012345678901234", "amount": "-1234567.123456789012", "n": [1, 2.5e3]}"#
+        );
+        assert!(apply(body.as_bytes(), JsonNumbers::Strict).is_err());
+        assert!(matches!(
+            apply(body.as_bytes(), JsonNumbers::Lossy),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_exact_numbers_untouched() {
+        // 64-bit integers, short decimals, padded zeros, numbers inside strings
+        let body = r#"{"a": 18446744073709551615, "b": -9223372036854775808, "c": 0.1, "d": 1.50000000000000000000, "e": 0.0000000000000000001, "f": "123456789012345678901234"}"#;
+
+        assert!(matches!(
+            apply(body.as_bytes(), JsonNumbers::Preserve),
+            Ok(Cow::Borrowed(_))
+        ));
+        assert!(apply(body.as_bytes(), JsonNumbers::Strict).is_ok());
+
+        // Overflow to infinity and underflow to zero lose the value
+        assert_eq!(preserve("[1e400, 1e-400]"), r#"["1e400", "1e-400"]"#);
+    }
+}
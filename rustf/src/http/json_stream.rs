@@ -0,0 +1,251 @@
+//! Streaming JSON arrays
+//!
+//! Large exports don't need to be built in memory before they are sent.
+//! Both helpers here write `[`, the elements separated by commas and `]`
+//! to a streaming response body as the elements are produced:
+//!
+//! - `ctx.json_stream(items)` consumes a `Stream` of `Result<T>`
+//! - `ctx.json_array_writer()` returns a [`JsonArrayWriter`] to push
+//!   elements from a spawned task, e.g. while walking a table in chunks
+//!
+//! ```rust,ignore
+//! async fn export(ctx: &mut Context) -> Result<()> {
+//!     let writer = ctx.json_array_writer();
+//!     tokio::spawn(async move {
+//!         let result = Orders::query()?
+//!             .chunk(1000, |orders| {
+//!                 let writer = writer.clone();
+//!                 async move { writer.write_all(&orders).await }
+//!             })
+//!             .await;
+//!         match result {
+//!             Ok(_) => writer.finish().await,
+//!             Err(e) => Err(e), // the array is left unterminated
+//!         }
+//!     });
+//!     Ok(())
+//! }
+//! ```
+//!
+//! A failure half way through cannot change the status that was already
+//! sent; the body is cut short instead, without the closing `]`, so clients
+//! fail to parse it rather than silently getting a partial list.
+
+use crate::error::{Error, Result};
+use futures::{Stream, StreamExt};
+use hyper::body::Bytes;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// Buffered bytes that trigger sending a chunk
+const FLUSH_BYTES: usize = 8 * 1024;
+
+/// Chunks that may wait for the client before writers are held back
+const CHANNEL_CHUNKS: usize = 16;
+
+/// Encode a stream of elements as a JSON array body
+///
+/// An error from `items` ends the body without the closing `]`.
+pub fn json_array_stream<S, T>(items: S) -> impl Stream<Item = std::io::Result<Bytes>> + Send
+where
+    S: Stream<Item = Result<T>> + Send + 'static,
+    T: Serialize + Send + 'static,
+{
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Empty,
+        Open,
+        Closed,
+    }
+
+    futures::stream::unfold(
+        (Box::pin(items), State::Empty),
+        |(mut items, state)| async move {
+            if state == State::Closed {
+                return None;
+            }
+
+            let next = match items.next().await {
+                Some(Ok(item)) => {
+                    let mut chunk = vec![if state == State::Empty { b'[' } else { b',' }];
+                    serde_json::to_writer(&mut chunk, &item)
+                        .map(|_| (chunk, State::Open))
+                        .map_err(Error::from)
+                }
+                Some(Err(e)) => Err(e),
+                None if state == State::Empty => Ok((b"[]".to_vec(), State::Closed)),
+                None => Ok((b"]".to_vec(), State::Closed)),
+            };
+
+            match next {
+                Ok((chunk, next)) => Some((Ok(Bytes::from(chunk)), (items, next))),
+                Err(e) => {
+                    log::error!("JSON array stream failed: {}", e);
+                    Some((Err(broken(e.to_string())), (items, State::Closed)))
+                }
+            }
+        },
+    )
+}
+
+/// Writes the elements of a JSON array to a streaming response
+///
+/// Clones share the same array, so the writer can be moved into closures
+/// that each produce part of it. Writes wait while the client is slow to
+/// read, which keeps memory bounded however long the array gets. Call
+/// [`finish`](Self::finish) once to close the array.
+#[derive(Clone)]
+pub struct JsonArrayWriter {
+    state: Arc<Mutex<WriterState>>,
+}
+
+struct WriterState {
+    sender: Option<mpsc::Sender<std::io::Result<Bytes>>>,
+    buffer: Vec<u8>,
+    started: bool,
+}
+
+impl JsonArrayWriter {
+    /// A writer and the body stream it feeds
+    pub fn channel() -> (
+        Self,
+        impl Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    ) {
+        let (sender, receiver) = mpsc::channel(CHANNEL_CHUNKS);
+        let writer = Self {
+            state: Arc::new(Mutex::new(WriterState {
+                sender: Some(sender),
+                buffer: Vec::with_capacity(FLUSH_BYTES),
+                started: false,
+            })),
+        };
+        let body = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|chunk| (chunk, receiver))
+        });
+        (writer, body)
+    }
+
+    /// Append one element
+    ///
+    /// Fails once the client has disconnected or the array was finished.
+    pub async fn write<T: Serialize>(&self, item: &T) -> Result<()> {
+        let encoded = serde_json::to_vec(item)?;
+        let mut state = self.state.lock().await;
+        state.ensure_open()?;
+
+        let separator = if state.started { b',' } else { b'[' };
+        state.started = true;
+        state.buffer.push(separator);
+        state.buffer.extend_from_slice(&encoded);
+        if state.buffer.len() >= FLUSH_BYTES {
+            state.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Append several elements
+    pub async fn write_all<T: Serialize>(&self, items: &[T]) -> Result<()> {
+        for item in items {
+            self.write(item).await?;
+        }
+        Ok(())
+    }
+
+    /// Close the array and end the response body
+    pub async fn finish(self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.ensure_open()?;
+
+        if !state.started {
+            state.buffer.push(b'[');
+        }
+        state.buffer.push(b']');
+        state.flush().await?;
+        state.sender = None;
+        Ok(())
+    }
+}
+
+impl WriterState {
+    fn ensure_open(&self) -> Result<()> {
+        match &self.sender {
+            Some(_) => Ok(()),
+            None => Err(Error::internal("JSON array writer is already finished")),
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let chunk = Bytes::from(std::mem::take(&mut self.buffer));
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| Error::internal("JSON array writer is already finished"))?;
+        if sender.send(Ok(chunk)).await.is_err() {
+            self.sender = None;
+            return Err(Error::Io(broken("client disconnected")));
+        }
+        Ok(())
+    }
+}
+
+fn broken(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::BrokenPipe, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect<S: Stream<Item = std::io::Result<Bytes>>>(body: S) -> (String, bool) {
+        let chunks: Vec<_> = body.collect().await;
+        let failed = chunks.iter().any(|chunk| chunk.is_err());
+        let text = chunks
+            .into_iter()
+            .filter_map(|chunk| chunk.ok())
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect();
+        (text, failed)
+    }
+
+    #[tokio::test]
+    async fn test_stream_encodes_array() {
+        let items = futures::stream::iter(vec![Ok(1), Ok(2), Ok(3)]);
+        assert_eq!(
+            collect(json_array_stream(items)).await,
+            ("[1,2,3]".into(), false)
+        );
+
+        let empty = futures::stream::iter(Vec::<Result<u32>>::new());
+        assert_eq!(
+            collect(json_array_stream(empty)).await,
+            ("[]".into(), false)
+        );
+
+        // A failure leaves the array unterminated
+        let failing = futures::stream::iter(vec![Ok(1), Err(Error::internal("db gone"))]);
+        assert_eq!(
+            collect(json_array_stream(failing)).await,
+            ("[1".into(), true)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writer_streams_elements() {
+        let (writer, body) = JsonArrayWriter::channel();
+        let producer = writer.clone();
+        tokio::spawn(async move {
+            producer.write(&serde_json::json!({"id": 1})).await?;
+            producer.write_all(&["a", "b"]).await?;
+            producer.finish().await
+        });
+        let (text, _) = collect(body).await;
+        assert_eq!(text, r#"[{"id":1},"a","b"]"#);
+
+        let (writer, body) = JsonArrayWriter::channel();
+        writer.clone().finish().await.unwrap();
+        assert!(writer.write(&1).await.is_err());
+        drop(writer);
+        assert_eq!(collect(body).await, ("[]".into(), false));
+    }
+}
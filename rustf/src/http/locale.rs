@@ -0,0 +1,117 @@
+//! Request locale detection
+//!
+//! `ctx.locale()` picks the locale of a request from, in order:
+//!
+//! 1. the `i18n.query_param` query parameter (`?locale=fr`)
+//! 2. the `i18n.cookie` cookie
+//! 3. the `i18n.session_key` session value
+//! 4. the `Accept-Language` header, best weight first
+//!
+//! Only locales listed in `i18n.locales` are accepted; a request that asks
+//! for none of them gets `i18n.default_locale`. Views rendered for the
+//! request translate with the detected locale.
+
+use crate::config::I18nConfig;
+use crate::http::QualityItem;
+
+/// Match a requested locale against the available ones
+///
+/// Tags are compared case-insensitively with `_` and `-` treated alike.
+/// An exact match wins, then a match on the language alone in either
+/// direction (`fr-CH` selects `fr`, `fr` selects `fr-FR`). Returns the
+/// available locale as configured.
+pub fn negotiate<'a>(requested: &str, available: &'a [String]) -> Option<&'a str> {
+    let requested = normalize(requested);
+    if requested.is_empty() || requested == "*" {
+        return None;
+    }
+    let language = requested.split('-').next().unwrap_or("");
+
+    available
+        .iter()
+        .find(|locale| normalize(locale) == requested)
+        .or_else(|| {
+            available.iter().find(|locale| {
+                let locale = normalize(locale);
+                locale == language || locale.split('-').next() == Some(language)
+            })
+        })
+        .map(|locale| locale.as_str())
+}
+
+/// Pick the locale from explicit choices and `Accept-Language`
+///
+/// `explicit` holds the query parameter, cookie and session values in
+/// priority order; the first one that is available wins.
+pub fn resolve<'a>(
+    config: &I18nConfig,
+    explicit: impl IntoIterator<Item = Option<&'a str>>,
+    accept_languages: &[QualityItem],
+) -> String {
+    let available = available_locales(config);
+
+    explicit
+        .into_iter()
+        .flatten()
+        .find_map(|choice| negotiate(choice, &available))
+        .or_else(|| {
+            accept_languages
+                .iter()
+                .find_map(|lang| negotiate(&lang.value, &available))
+        })
+        .unwrap_or(config.default_locale.as_str())
+        .to_string()
+}
+
+/// The configured locales, or just the default one when none are listed
+pub fn available_locales(config: &I18nConfig) -> Vec<String> {
+    if config.locales.is_empty() {
+        vec![config.default_locale.clone()]
+    } else {
+        config.locales.clone()
+    }
+}
+
+fn normalize(tag: &str) -> String {
+    tag.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::headers::parse_quality_list;
+
+    fn config() -> I18nConfig {
+        I18nConfig {
+            locales: vec!["en".into(), "fr-FR".into(), "de_CH".into()],
+            ..I18nConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = config().locales;
+        assert_eq!(negotiate("FR-fr", &available), Some("fr-FR"));
+        assert_eq!(negotiate("fr", &available), Some("fr-FR"));
+        assert_eq!(negotiate("en-GB", &available), Some("en"));
+        assert_eq!(negotiate("de-CH", &available), Some("de_CH"));
+        assert_eq!(negotiate("es", &available), None);
+        assert_eq!(negotiate("*", &available), None);
+    }
+
+    #[test]
+    fn test_resolve_order() {
+        let config = config();
+        let header = parse_quality_list("es, de;q=0.8, fr;q=0.5");
+
+        // Explicit choices win in order; unavailable ones are skipped
+        assert_eq!(
+            resolve(&config, [Some("it"), Some("fr"), Some("en")], &header),
+            "fr-FR"
+        );
+        // Otherwise the best available Accept-Language entry
+        assert_eq!(resolve(&config, [None, None, None], &header), "de_CH");
+        // Otherwise the default
+        assert_eq!(resolve(&config, [None], &parse_quality_list("es")), "en");
+    }
+}
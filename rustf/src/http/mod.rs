@@ -1,11 +1,42 @@
+pub mod client_hints;
+pub mod cookie;
 pub mod files;
+pub mod form;
+pub mod headers;
+pub mod json_limits;
+pub mod json_numbers;
+pub mod json_stream;
+pub mod locale;
+pub mod query;
 pub mod request;
 pub mod request_data;
+pub mod request_id;
 pub mod response;
 pub mod server;
+pub mod signature;
+pub mod sse;
+pub mod stream;
+pub mod timing;
 
+pub use client_hints::ClientHints;
+pub use cookie::{CookieJar, CookieOptions};
 pub use files::{FileCollection, MultipartParser, UploadedFile};
+pub use headers::{ByteRangeSpec, ContentType, QualityItem, RequestHeaders};
+pub use json_stream::JsonArrayWriter;
 pub use request::{FormValue, Request};
 pub use request_data::{BodyData, RequestData};
 pub use response::Response;
 pub use server::Server;
+pub use signature::SignatureOptions;
+pub use sse::{SseEvent, SseStream};
+pub use stream::BodyStream;
+pub use timing::{RequestTimings, TimingPhase};
+
+/// Whether `path` is `prefix` or below it, on a segment boundary
+///
+/// "/admin" covers "/admin/users" but not "/administrator"; a prefix ending
+/// in "/" covers everything that starts with it.
+pub(crate) fn path_is_under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'))
+}
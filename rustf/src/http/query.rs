@@ -0,0 +1,208 @@
+//! Typed deserialization of query parameters
+//!
+//! Query values are strings, so a plain JSON conversion cannot fill numeric
+//! or boolean fields. This deserializer parses each value into the type the
+//! target field asks for, and gathers repeated keys into sequences:
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize)]
+//! struct Filters {
+//!     tag: Vec<String>,  // ?tag=a&tag=b (or tag[]=a&tag[]=b)
+//!     page: Option<u32>, // ?page=2
+//!     archived: bool,    // ?archived=1
+//! }
+//! ```
+//!
+//! A single occurrence still fills a `Vec` field, and a non-sequence field
+//! given several values takes the last one.
+
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Unexpected, Visitor};
+
+/// Deserialize ordered query pairs into `T`
+pub fn from_pairs<T: DeserializeOwned>(pairs: &[(String, String)]) -> Result<T> {
+    let mut grouped: IndexMap<&str, Vec<&str>> = IndexMap::new();
+    for (key, value) in pairs {
+        grouped
+            .entry(key.as_str())
+            .or_default()
+            .push(value.as_str());
+    }
+
+    let map = MapDeserializer::<_, de::value::Error>::new(
+        grouped
+            .into_iter()
+            .map(|(key, values)| (key, ValueDeserializer(values))),
+    );
+    T::deserialize(map).map_err(|e| Error::InvalidInput(format!("Invalid query parameters: {}", e)))
+}
+
+/// All values given for one query key
+pub(super) struct ValueDeserializer<'a>(pub(super) Vec<&'a str>);
+
+impl<'a> ValueDeserializer<'a> {
+    fn last(&self) -> &'a str {
+        self.0.last().copied().unwrap_or("")
+    }
+}
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, Self::Error> {
+            let value = self.last();
+            match value.parse() {
+                Ok(parsed) => visitor.$visit(parsed),
+                Err(_) => Err(de::Error::invalid_value(Unexpected::Str(value), &visitor)),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.last())
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        match self.last() {
+            "true" | "1" | "yes" | "on" => visitor.visit_bool(true),
+            "false" | "0" | "no" | "off" | "" => visitor.visit_bool(false),
+            other => Err(de::Error::invalid_value(Unexpected::Str(other), &visitor)),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    /// An empty value (`?page=`) counts as absent
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        if self.last().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        let items = self
+            .0
+            .into_iter()
+            .map(|value| ValueDeserializer(vec![value]));
+        visitor.visit_seq(SeqDeserializer::new(items))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.last().into_deserializer())
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Sort {
+        Name,
+        Date,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Filters {
+        tag: Vec<String>,
+        ids: Vec<u32>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+        archived: bool,
+        sort: Sort,
+        q: String,
+    }
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        let filters: Filters = from_pairs(&pairs(&[
+            ("tag", "b"),
+            ("ids", "7"),
+            ("tag", "a"),
+            ("page", "2"),
+            ("per_page", ""),
+            ("archived", "on"),
+            ("sort", "date"),
+            ("q", "first"),
+            ("q", "last"),
+        ]))
+        .unwrap();
+
+        assert_eq!(filters.tag, vec!["b", "a"]);
+        assert_eq!(filters.ids, vec![7]);
+        assert_eq!(filters.page, Some(2));
+        assert_eq!(filters.per_page, None);
+        assert!(filters.archived);
+        assert_eq!(filters.sort, Sort::Date);
+        assert_eq!(filters.q, "last");
+
+        let invalid: Result<Filters> = from_pairs(&pairs(&[("ids", "x")]));
+        assert!(matches!(invalid, Err(Error::InvalidInput(_))));
+    }
+}
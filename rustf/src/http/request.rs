@@ -1,5 +1,9 @@
+use crate::config::QueryArraySyntax;
 use crate::error::{Error, Result};
+use crate::http::client_hints::ClientHints;
+use crate::http::cookie::CookieJar;
 use crate::http::files::{FileCollection, MultipartParser};
+use crate::http::headers::{self, ByteRangeSpec, ContentType, QualityItem, RequestHeaders};
 use hyper::{Body, Request as HyperRequest};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -42,9 +46,13 @@ impl FormValue {
 pub struct Request {
     pub method: String,
     pub uri: String,
-    pub headers: HashMap<String, String>,
+    pub headers: RequestHeaders,
     pub params: HashMap<String, String>,
     pub query: HashMap<String, String>,
+    /// Every query parameter in URL order, including repeated keys
+    query_pairs: Vec<(String, String)>,
+    /// Pattern of the matched route (e.g. "/users/{id}"), set by the router
+    pub route: Option<String>,
     body_bytes: Vec<u8>,
     files: Option<FileCollection>,
 }
@@ -57,9 +65,11 @@ impl Request {
         Request {
             method: method.to_string(),
             uri: uri.to_string(),
-            headers: HashMap::new(),
+            headers: RequestHeaders::default(),
             params: HashMap::new(),
             query: HashMap::new(),
+            query_pairs: Vec::new(),
+            route: None,
             body_bytes: Vec::new(),
             files: None,
         }
@@ -73,6 +83,17 @@ impl Request {
     }
 
     pub async fn from_hyper(req: HyperRequest<Body>) -> Result<Self> {
+        Self::from_hyper_with_limit(req, 0).await
+    }
+
+    /// Build a request, reading a body of at most `max_body_size` bytes
+    ///
+    /// Fails with `Error::PayloadTooLarge` without buffering the whole body
+    /// when it is bigger (0 means no limit).
+    pub async fn from_hyper_with_limit(
+        req: HyperRequest<Body>,
+        max_body_size: usize,
+    ) -> Result<Self> {
         let method = req.method().to_string();
         let uri = req.uri().to_string();
 
@@ -84,26 +105,42 @@ impl Request {
             }
         }
 
-        // Extract query parameters
-        let query = Self::parse_query(req.uri().query().unwrap_or(""));
+        // Extract query parameters; single-value lookups keep the keys as
+        // sent (`ids[]`) and see the last value
+        let array_syntax = crate::configuration::CONF::all()
+            .map(|config| config.query.array_syntax)
+            .unwrap_or_default();
+        let raw_query = req.uri().query().unwrap_or("");
+        let query = Self::parse_query(raw_query);
+        let query_pairs = Self::parse_query_pairs(raw_query, array_syntax);
 
         // Read body
-        let body_bytes = hyper::body::to_bytes(req.into_body()).await?.to_vec();
+        let content_length = req
+            .headers()
+            .get(hyper::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok());
+        let body_bytes =
+            super::server::read_body(req.into_body(), content_length, max_body_size).await?;
 
         Ok(Request {
             method,
             uri,
-            headers,
+            headers: headers.into(),
             params: HashMap::new(), // Will be filled by router
             query,
+            query_pairs,
+            route: None, // Will be filled by router
             body_bytes,
             files: None, // Will be parsed on demand
         })
     }
 
     pub fn body_as_json<T: DeserializeOwned>(&self) -> Result<T> {
+        super::json_limits::check_configured(&self.body_bytes)?;
+
         // Use simd-json for faster parsing (2-3x faster than serde_json)
-        let mut body_bytes = self.body_bytes.clone();
+        let mut body_bytes = super::json_numbers::apply_configured(&self.body_bytes)?.into_owned();
         simd_json::from_slice(&mut body_bytes)
             .map_err(|e| Error::internal(format!("Failed to parse JSON: {}", e)))
     }
@@ -119,10 +156,28 @@ impl Request {
         Ok(Self::parse_query_with_arrays(&body_str))
     }
 
+    /// Form body fields in body order, names kept as sent (`user[name]`, `tags[]`)
+    pub fn body_as_form_pairs(&self) -> Vec<(String, String)> {
+        let body_str = String::from_utf8_lossy(&self.body_bytes);
+        Self::parse_query_pairs(&body_str, QueryArraySyntax::Repeat)
+    }
+
+    /// Deserialize the form body into a typed structure, nested fields included
+    ///
+    /// See `http::form` for the bracket conventions.
+    pub fn form_into<T: DeserializeOwned>(&self) -> Result<T> {
+        super::form::from_pairs(&self.body_as_form_pairs())
+    }
+
     pub fn body_as_string(&self) -> String {
         String::from_utf8_lossy(&self.body_bytes).to_string()
     }
 
+    /// The body exactly as received
+    pub fn body_bytes(&self) -> &[u8] {
+        &self.body_bytes
+    }
+
     fn parse_query(query: &str) -> HashMap<String, String> {
         let mut result = HashMap::new();
         for pair in query.split('&') {
@@ -153,6 +208,63 @@ impl Request {
         result
     }
 
+    /// Get every value of a query parameter, in URL order
+    pub fn query_all(&self, key: &str) -> Vec<&str> {
+        self.query_pairs
+            .iter()
+            .filter(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Deserialize the query parameters into a typed structure
+    ///
+    /// Repeated keys fill `Vec` fields; other fields take the last value.
+    pub fn query_into<T: DeserializeOwned>(&self) -> Result<T> {
+        super::query::from_pairs(&self.query_pairs)
+    }
+
+    /// Get all query parameters in URL order, including repeated keys
+    pub fn query_pairs(&self) -> &[(String, String)] {
+        &self.query_pairs
+    }
+
+    /// Parse a query string into ordered pairs
+    ///
+    /// Depending on `syntax`, a `[]` suffix marks a repeated key and is
+    /// stripped, so `tag[]=a&tag[]=b` yields two `tag` pairs. Pairs without
+    /// `=` are skipped; a key or value with unsafe encoding becomes an empty
+    /// string, as in `parse_query`.
+    fn parse_query_pairs(query: &str, syntax: QueryArraySyntax) -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> = Vec::new();
+        // Position of each plain key, for `Brackets` where the last one wins
+        let mut plain: HashMap<String, usize> = HashMap::new();
+
+        for pair in query.split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let key = urlencoding::decode(key).unwrap_or_default().to_string();
+            let value = urlencoding::decode(value).unwrap_or_default().to_string();
+
+            match key.strip_suffix("[]") {
+                Some(name) if syntax != QueryArraySyntax::Repeat => {
+                    pairs.push((name.to_string(), value));
+                }
+                _ if syntax == QueryArraySyntax::Brackets => match plain.get(&key) {
+                    Some(&index) => pairs[index].1 = value,
+                    None => {
+                        plain.insert(key.clone(), pairs.len());
+                        pairs.push((key, value));
+                    }
+                },
+                _ => pairs.push((key, value)),
+            }
+        }
+
+        pairs
+    }
+
     /// Parse query/form data with support for arrays
     fn parse_query_with_arrays(query: &str) -> HashMap<String, FormValue> {
         let mut result: HashMap<String, Vec<String>> = HashMap::new();
@@ -221,6 +333,28 @@ impl Request {
         self.headers.get("user-agent").map(|s| s.as_str())
     }
 
+    /// Browser, platform and device parsed from `User-Agent` alone
+    ///
+    /// Prefer `client_hints`, which also reads the more reliable
+    /// `Sec-CH-UA*` headers when the browser sends them.
+    pub fn parsed_user_agent(&self) -> ClientHints {
+        self.user_agent()
+            .map(ClientHints::from_user_agent)
+            .unwrap_or_default()
+    }
+
+    /// Browser, platform and device from User-Agent Client Hints
+    ///
+    /// Falls back to the parsed `User-Agent` for anything the `Sec-CH-UA`,
+    /// `Sec-CH-UA-Mobile` and `Sec-CH-UA-Platform` headers leave out.
+    pub fn client_hints(&self) -> ClientHints {
+        self.parsed_user_agent().with_hints(
+            self.headers.get("sec-ch-ua").map(|s| s.as_str()),
+            self.headers.get("sec-ch-ua-mobile").map(|s| s.as_str()),
+            self.headers.get("sec-ch-ua-platform").map(|s| s.as_str()),
+        )
+    }
+
     /// Detect if request is from mobile device
     pub fn is_mobile(&self) -> bool {
         if let Some(ua) = self.user_agent() {
@@ -273,17 +407,57 @@ impl Request {
     }
 
     /// Get preferred language from Accept-Language header
+    ///
+    /// The entry with the highest q-value, e.g. `en-US` for
+    /// `"fr;q=0.8, en-US"`.
     pub fn language(&self) -> Option<&str> {
-        if let Some(accept_lang) = self.headers.get("accept-language") {
-            // Parse "en-US,en;q=0.9,fr;q=0.8" and return first language
-            accept_lang
-                .split(',')
-                .next()
-                .and_then(|lang| lang.split(';').next())
-                .map(|lang| lang.trim())
-        } else {
-            None
-        }
+        self.accept_languages()
+            .first()
+            .map(|lang| lang.value.as_str())
+    }
+
+    /// Languages from `Accept-Language`, best first
+    ///
+    /// Parsed on first access and cached until the headers change.
+    /// Languages sent with `q=0` are left out.
+    pub fn accept_languages(&self) -> &[QualityItem] {
+        self.headers.accept_languages.get_or_init(|| {
+            self.headers
+                .get("accept-language")
+                .map(|header| headers::parse_quality_list(header))
+                .unwrap_or_default()
+        })
+    }
+
+    /// The parsed `Content-Type` header
+    ///
+    /// Parsed on first access and cached until the headers change; `None`
+    /// when the header is missing or malformed.
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.headers
+            .content_type
+            .get_or_init(|| {
+                self.headers
+                    .get("content-type")
+                    .and_then(|header| ContentType::parse(header))
+            })
+            .as_ref()
+    }
+
+    /// Byte ranges requested with a `Range: bytes=...` header
+    ///
+    /// `None` when there is no header or it cannot be parsed, in which case
+    /// the whole body should be sent. Use `ByteRangeSpec::resolve` to turn a
+    /// range into offsets for a body of known length.
+    pub fn range(&self) -> Option<Vec<ByteRangeSpec>> {
+        self.headers
+            .get("range")
+            .and_then(|header| headers::parse_range(header))
+    }
+
+    /// Token of an `Authorization: Bearer` header
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.authorization().and_then(headers::bearer_token)
     }
 
     /// Get HTTP referrer
@@ -309,19 +483,26 @@ impl Request {
     /// Parse multipart form data to extract files
     fn parse_files(&mut self) -> Result<()> {
         // Check if this is a multipart form
-        if let Some(content_type) = self.headers.get("content-type") {
-            if content_type.starts_with("multipart/form-data") {
-                // Extract boundary
-                if let Some(boundary) = self.extract_boundary(content_type) {
-                    let (files, _form_data) = MultipartParser::parse(&self.body_bytes, &boundary)?;
-
-                    // Merge form data into existing body parsing (for body_as_form compatibility)
-                    // This is a bit hacky but maintains compatibility
-
-                    self.files = Some(files);
-                    return Ok(());
-                }
-            }
+        let boundary = self
+            .content_type()
+            .filter(|ct| ct.is("multipart/form-data"))
+            .and_then(|ct| ct.boundary())
+            .map(str::to_string);
+        if let Some(boundary) = boundary {
+            let max_part_size = crate::configuration::CONF::all()
+                .map(|config| config.uploads.max_file_size)
+                .unwrap_or_else(|| crate::config::UploadConfig::default().max_file_size);
+            let (files, _form_data) = MultipartParser::parse_with_limit(
+                &self.body_bytes,
+                &boundary,
+                usize::try_from(max_part_size).unwrap_or(usize::MAX),
+            )?;
+
+            // Merge form data into existing body parsing (for body_as_form compatibility)
+            // This is a bit hacky but maintains compatibility
+
+            self.files = Some(files);
+            return Ok(());
         }
 
         // Not multipart, create empty file collection
@@ -329,27 +510,37 @@ impl Request {
         Ok(())
     }
 
-    /// Extract boundary from Content-Type header
-    fn extract_boundary(&self, content_type: &str) -> Option<String> {
-        // Parse: multipart/form-data; boundary=----WebKitFormBoundary7MA4YWxkTrZu0gW
-        for part in content_type.split(';') {
-            let part = part.trim();
-            if part.starts_with("boundary=") {
-                return Some(part[9..].to_string());
-            }
-        }
-        None
-    }
-
     // Total.js compatibility methods - Phase 1: High Priority Features
 
+    /// Get all cookies sent with the request
+    ///
+    /// The `Cookie` header is parsed on first access and cached until the
+    /// headers change.
+    pub fn cookies(&self) -> &CookieJar {
+        self.headers.cookie_jar.get_or_init(|| {
+            self.headers
+                .get("cookie")
+                .map(|header| CookieJar::parse(header))
+                .unwrap_or_default()
+        })
+    }
+
     /// Get cookie value by name (Total.js: request.cookie(name))
+    ///
+    /// With duplicate names, the last value sent is returned, and values are
+    /// returned as sent (quotes are kept). Use `cookies()` for the first value
+    /// or unquoted values.
     pub fn cookie(&self, name: &str) -> Option<String> {
-        if let Some(cookie_header) = self.headers.get("cookie") {
-            Self::parse_cookies(cookie_header).get(name).cloned()
-        } else {
-            None
-        }
+        self.cookies().get_raw(name).map(str::to_string)
+    }
+
+    /// Get a signed cookie value, verifying its signature with the application secret
+    ///
+    /// Returns `None` if the cookie is missing, tampered with, or no secret is configured.
+    pub fn signed_cookie(&self, name: &str) -> Option<String> {
+        let signed = self.cookie(name)?;
+        let secret = crate::http::cookie::app_secret().ok()?;
+        crate::http::cookie::verify_value(name, &signed, &secret)
     }
 
     /// Get host from Host header (Total.js: request.host)
@@ -530,22 +721,6 @@ impl Request {
 
     // Helper methods
 
-    /// Parse cookies from Cookie header
-    fn parse_cookies(cookie_header: &str) -> HashMap<String, String> {
-        let mut cookies = HashMap::new();
-
-        for cookie_pair in cookie_header.split(';') {
-            let cookie_pair = cookie_pair.trim();
-            if let Some((name, value)) = cookie_pair.split_once('=') {
-                let name = name.trim().to_string();
-                let value = value.trim().to_string();
-                cookies.insert(name, value);
-            }
-        }
-
-        cookies
-    }
-
     /// Simple base64 encoding without external dependencies
     fn base64_encode(input: &[u8]) -> String {
         const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -585,6 +760,7 @@ impl Request {
         self.headers.clear();
         self.params.clear();
         self.query.clear();
+        self.query_pairs.clear();
         self.body_bytes.clear();
         self.files = None;
 
@@ -593,7 +769,7 @@ impl Request {
         const MAX_CAPACITY: usize = 1024;
 
         if self.headers.capacity() > MAX_CAPACITY {
-            self.headers = HashMap::new();
+            self.headers = RequestHeaders::default();
         }
         if self.params.capacity() > MAX_CAPACITY {
             self.params = HashMap::new();
@@ -805,6 +981,62 @@ mod urlencoding {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_query_pairs() {
+        let query = "tag=a&tag[]=b&page=1&tag=c&page=2&flag";
+        let pairs = |syntax| -> Vec<(String, String)> { Request::parse_query_pairs(query, syntax) };
+        let owned = |items: &[(&str, &str)]| -> Vec<(String, String)> {
+            items
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        assert_eq!(
+            pairs(QueryArraySyntax::Both),
+            owned(&[
+                ("tag", "a"),
+                ("tag", "b"),
+                ("page", "1"),
+                ("tag", "c"),
+                ("page", "2")
+            ])
+        );
+        assert_eq!(
+            pairs(QueryArraySyntax::Repeat),
+            owned(&[
+                ("tag", "a"),
+                ("tag[]", "b"),
+                ("page", "1"),
+                ("tag", "c"),
+                ("page", "2")
+            ])
+        );
+        // Only earlier plain pairs are replaced; `[]` values are kept
+        assert_eq!(
+            pairs(QueryArraySyntax::Brackets),
+            owned(&[("tag", "c"), ("tag", "b"), ("page", "2")])
+        );
+
+        // Unsafe encoding is kept as an empty string, like `parse_query`
+        assert_eq!(
+            Request::parse_query_pairs("path=../etc/passwd&page=1", QueryArraySyntax::Both),
+            owned(&[("path", ""), ("page", "1")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_keeps_bracket_keys() {
+        let req = HyperRequest::builder()
+            .uri("/items?ids[]=1&ids[]=2")
+            .body(Body::empty())
+            .unwrap();
+        let request = Request::from_hyper(req).await.unwrap();
+
+        assert_eq!(request.query.get("ids[]").map(String::as_str), Some("2"));
+        assert_eq!(request.query_all("ids"), vec!["1", "2"]);
+    }
+
     #[test]
     fn test_secure_url_decoding() {
         use super::urlencoding::decode;
@@ -1011,6 +1243,24 @@ mod tests {
         assert_eq!(empty_request.cookie("any"), None);
     }
 
+    #[test]
+    fn test_cookie_jar_duplicates() {
+        let mut request = Request::default();
+        request.headers.insert(
+            "cookie".to_string(),
+            "lang=fr; lang=en; theme=dark; quoted=\"a b\"".to_string(),
+        );
+
+        // `cookie` keeps its last-wins, as-sent semantics
+        assert_eq!(request.cookie("lang"), Some("en".to_string()));
+        assert_eq!(request.cookie("quoted"), Some("\"a b\"".to_string()));
+
+        assert_eq!(request.cookies().get("lang"), Some("fr"));
+        assert_eq!(request.cookies().get("quoted"), Some("a b"));
+        assert_eq!(request.cookies().get_all("lang"), vec!["fr", "en"]);
+        assert_eq!(request.cookies().len(), 4);
+    }
+
     #[test]
     fn test_host_and_hostname() {
         let mut request = Request::default();
@@ -1090,6 +1340,7 @@ mod tests {
             .insert("authorization".to_string(), "Bearer token123".to_string());
         assert!(request.is_authorized());
         assert_eq!(request.authorization(), Some("Bearer token123"));
+        assert_eq!(request.bearer_token(), Some("token123"));
 
         // Test with basic auth
         request.headers.insert(
@@ -1098,6 +1349,80 @@ mod tests {
         );
         assert!(request.is_authorized());
         assert_eq!(request.authorization(), Some("Basic dXNlcjpwYXNz"));
+        assert_eq!(request.bearer_token(), None);
+    }
+
+    #[test]
+    fn test_typed_headers() {
+        let mut request = Request::default();
+        request.headers.insert(
+            "accept-language".to_string(),
+            "fr;q=0.8, en-US, de;q=0".to_string(),
+        );
+        request.headers.insert(
+            "content-type".to_string(),
+            "application/json; charset=utf-8".to_string(),
+        );
+        request
+            .headers
+            .insert("range".to_string(), "bytes=0-99".to_string());
+
+        assert_eq!(request.language(), Some("en-US"));
+        assert_eq!(request.accept_languages().len(), 2);
+        assert!(request.content_type().unwrap().is_json());
+        assert_eq!(request.content_type().unwrap().charset(), Some("utf-8"));
+        assert_eq!(request.range(), Some(vec![ByteRangeSpec::FromTo(0, 99)]));
+    }
+
+    #[test]
+    fn test_parsed_headers_follow_header_changes() {
+        let mut request = Request::default();
+        request
+            .headers
+            .insert("content-type".to_string(), "text/plain".to_string());
+        request
+            .headers
+            .insert("cookie".to_string(), "theme=dark".to_string());
+        request
+            .headers
+            .insert("accept-language".to_string(), "fr".to_string());
+        assert!(request.content_type().unwrap().is("text/plain"));
+        assert_eq!(request.cookies().get("theme"), Some("dark"));
+        assert_eq!(request.language(), Some("fr"));
+
+        // A middleware rewriting headers after they were read
+        request
+            .headers
+            .insert("content-type".to_string(), "application/json".to_string());
+        request
+            .headers
+            .insert("cookie".to_string(), "theme=light".to_string());
+        request.headers.remove("accept-language");
+        assert!(request.content_type().unwrap().is_json());
+        assert_eq!(request.cookies().get("theme"), Some("light"));
+        assert_eq!(request.language(), None);
+    }
+
+    #[test]
+    fn test_client_hints() {
+        let mut request = Request::default();
+        assert_eq!(request.client_hints(), ClientHints::default());
+
+        request.headers.insert(
+            "user-agent".to_string(),
+            "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0".to_string(),
+        );
+        let parsed = request.parsed_user_agent();
+        assert_eq!(parsed.browser.as_deref(), Some("Firefox"));
+        assert_eq!(parsed.platform.as_deref(), Some("Linux"));
+        assert_eq!(request.client_hints(), parsed);
+
+        request
+            .headers
+            .insert("sec-ch-ua-mobile".to_string(), "?1".to_string());
+        let hints = request.client_hints();
+        assert!(hints.mobile);
+        assert_eq!(hints.browser.as_deref(), Some("Firefox"));
     }
 
     #[test]
@@ -231,6 +231,39 @@ impl RequestData {
         }
     }
 
+    /// Deserialize the form fields into a typed structure, nested fields included
+    ///
+    /// Field order is not kept here, so `items[][field]` values are grouped
+    /// by position: the first value of each field makes the first item.
+    pub fn form_into<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        let BodyData::Form(form) = &self.body else {
+            return Err(Error::InvalidInput(
+                "Request body is not a form".to_string(),
+            ));
+        };
+
+        let mut keys: Vec<&String> = form.keys().collect();
+        keys.sort();
+        let mut pairs = Vec::new();
+        for position in 0.. {
+            let before = pairs.len();
+            for key in &keys {
+                let value = match &form[*key] {
+                    FormValue::Single(value) => (position == 0).then_some(value),
+                    FormValue::Multiple(values) => values.get(position),
+                };
+                if let Some(value) = value {
+                    pairs.push(((*key).clone(), value.clone()));
+                }
+            }
+            if pairs.len() == before {
+                break;
+            }
+        }
+
+        super::form::from_pairs(&pairs)
+    }
+
     /// Check if this is a form submission
     pub fn is_form(&self) -> bool {
         matches!(&self.body, BodyData::Form(_))
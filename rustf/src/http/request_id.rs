@@ -0,0 +1,35 @@
+//! Request id of the request being handled
+//!
+//! The id comes from the `X-Request-Id` (or `X-Correlation-Id`) header and is
+//! made available to everything running on the request's task, such as
+//! `Response::json_error` and the slow query log.
+
+use std::future::Future;
+
+tokio::task_local! {
+    /// Request id of the request being handled on this task
+    static REQUEST_ID: Option<String>;
+}
+
+/// Run a request future with its request id available through [`current`]
+pub async fn scope<F: Future>(request_id: Option<String>, fut: F) -> F::Output {
+    REQUEST_ID.scope(request_id, fut).await
+}
+
+/// Request id of the request handled on the current task, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_id_scope() {
+        assert_eq!(current(), None);
+        let id = scope(Some("req-1".to_string()), async { current() }).await;
+        assert_eq!(id.as_deref(), Some("req-1"));
+        assert_eq!(current(), None);
+    }
+}
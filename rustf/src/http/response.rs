@@ -1,4 +1,10 @@
 use crate::error::Result;
+use crate::http::cookie::{self, CookieOptions};
+use crate::http::headers;
+use crate::http::json_stream::{self, JsonArrayWriter};
+use crate::http::sse::{self, SseEvent, SseStream};
+use crate::http::stream::{self, BodyStream};
+use crate::security::static_files::SecureStaticFileServer;
 use hyper::StatusCode;
 use serde::Serialize;
 use std::path::Path;
@@ -8,6 +14,8 @@ pub struct Response {
     pub status: StatusCode,
     pub headers: Vec<(String, String)>,
     pub body: Vec<u8>,
+    /// Streaming body sent instead of `body` when set
+    stream: Option<BodyStream>,
 }
 
 impl Response {
@@ -16,6 +24,7 @@ impl Response {
             status,
             headers: Vec::new(),
             body: Vec::new(),
+            stream: None,
         }
     }
 
@@ -249,6 +258,43 @@ impl Response {
             .with_body(json_string.into_bytes()))
     }
 
+    /// JSON error in the envelope used by the framework's own API errors
+    ///
+    /// ```json
+    /// { "error": { "code": "E_MODEL_NOT_FOUND", "message": "...", "request_id": "abc-123" } }
+    /// ```
+    ///
+    /// `request_id` comes from the `X-Request-Id` (or `X-Correlation-Id`)
+    /// header of the request being handled, and is null outside a request.
+    /// Use `Error::error_code()` for `code` to keep codes consistent:
+    ///
+    /// ```rust,ignore
+    /// let response = Response::json_error(StatusCode::CONFLICT, err.error_code(), "Email already taken");
+    /// ```
+    pub fn json_error(status: StatusCode, code: &str, message: &str) -> Self {
+        let request_id = crate::http::request_id::current();
+        Self::json_error_with_request_id(status, code, message, request_id.as_deref())
+    }
+
+    /// [`Response::json_error`] with an explicit request id
+    pub fn json_error_with_request_id(
+        status: StatusCode,
+        code: &str,
+        message: &str,
+        request_id: Option<&str>,
+    ) -> Self {
+        let body = serde_json::json!({
+            "error": {
+                "code": code,
+                "message": message,
+                "request_id": request_id,
+            }
+        });
+        Self::new(status)
+            .with_header("Content-Type", "application/json")
+            .with_body(body.to_string().into_bytes())
+    }
+
     pub fn html(content: impl Into<String>) -> Self {
         Self::ok()
             .with_header("Content-Type", "text/html; charset=utf-8")
@@ -271,11 +317,167 @@ impl Response {
         self.headers.push((name.to_string(), value.to_string()));
     }
 
+    /// Add a `Set-Cookie` header for a standalone cookie
+    ///
+    /// Fails if the options are rejected (see `CookieOptions::to_header`).
+    pub fn set_cookie(&mut self, name: &str, value: &str, options: &CookieOptions) -> Result<()> {
+        self.add_header("Set-Cookie", &options.to_header(name, value)?);
+        Ok(())
+    }
+
+    /// Add a cookie whose value is HMAC-signed with the application secret
+    ///
+    /// Read it back with `Request::signed_cookie`, which rejects tampered values.
+    /// Fails if no application secret is configured.
+    pub fn set_signed_cookie(
+        &mut self,
+        name: &str,
+        value: &str,
+        options: &CookieOptions,
+    ) -> Result<()> {
+        let secret = cookie::app_secret()?;
+        let signed = cookie::sign_value(name, value, &secret);
+        self.set_cookie(name, &signed, options)
+    }
+
     pub fn with_body(mut self, body: Vec<u8>) -> Self {
         self.body = body;
         self
     }
 
+    /// Send the body as a stream of chunks instead of `body`
+    pub fn with_stream<S>(mut self, stream: S) -> Self
+    where
+        S: futures::Stream<Item = std::io::Result<hyper::body::Bytes>> + Send + 'static,
+    {
+        self.body.clear();
+        self.stream = Some(BodyStream::new(stream));
+        self
+    }
+
+    /// Check whether the body is streamed
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Remove the streaming body, if any
+    pub fn take_stream(&mut self) -> Option<BodyStream> {
+        self.stream.take()
+    }
+
+    /// Turn the response into `304 Not Modified` if the client's copy is current
+    ///
+    /// Only `200` answers to `GET` and `HEAD` with an `ETag` or `Last-Modified`
+    /// header qualify. `If-None-Match` is checked against the `ETag` with the
+    /// weak comparison, so `W/"a"` matches `"a"`; `If-Modified-Since` is only
+    /// checked against `Last-Modified` when there is no `If-None-Match`. On a
+    /// match the body and its `Content-*` headers are dropped and `true` is
+    /// returned; validators and caching headers are kept.
+    pub fn apply_conditional(
+        &mut self,
+        method: &str,
+        if_none_match: Option<&str>,
+        if_modified_since: Option<&str>,
+    ) -> bool {
+        let is_cacheable_method =
+            method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD");
+        if self.status != StatusCode::OK || !is_cacheable_method {
+            return false;
+        }
+
+        let header = |name: &str| {
+            self.headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        let not_modified = match if_none_match {
+            Some(if_none_match) => {
+                header("ETag").is_some_and(|etag| headers::if_none_match(if_none_match, etag))
+            }
+            None => {
+                let since = if_modified_since.and_then(SecureStaticFileServer::parse_http_date);
+                let modified =
+                    header("Last-Modified").and_then(SecureStaticFileServer::parse_http_date);
+                matches!((since, modified), (Some(since), Some(modified)) if modified <= since)
+            }
+        };
+
+        if not_modified {
+            self.status = StatusCode::NOT_MODIFIED;
+            self.body.clear();
+            self.stream = None;
+            self.headers
+                .retain(|(name, _)| !name.to_ascii_lowercase().starts_with("content-"));
+        }
+        not_modified
+    }
+
+    /// Create a response whose body is streamed chunk by chunk
+    ///
+    /// Chunks are sent as `chunks` yields them, with `Transfer-Encoding:
+    /// chunked`, instead of being collected first. An error from the stream
+    /// cuts the body short.
+    pub fn chunked<S>(content_type: &str, chunks: S) -> Self
+    where
+        S: futures::Stream<Item = Result<hyper::body::Bytes>> + Send + 'static,
+    {
+        Self::ok()
+            .with_header("Content-Type", content_type)
+            .with_stream(stream::chunks(chunks))
+    }
+
+    /// Create a Server-Sent Events response
+    ///
+    /// Sets `text/event-stream`, disables caching and proxy buffering, and sends
+    /// a keep-alive comment after each `keep_alive` period without events.
+    pub fn sse<S>(events: S, keep_alive: Option<std::time::Duration>) -> Self
+    where
+        S: futures::Stream<Item = SseEvent> + Send + 'static,
+    {
+        Self::sse_body(sse::event_stream(events, keep_alive))
+    }
+
+    /// Create a Server-Sent Events response and the handle that sends its events
+    pub fn sse_stream(keep_alive: Option<std::time::Duration>) -> (Self, SseStream) {
+        let (stream, body) = SseStream::channel(keep_alive);
+        (Self::sse_body(body), stream)
+    }
+
+    fn sse_body<S>(body: S) -> Self
+    where
+        S: futures::Stream<Item = std::io::Result<hyper::body::Bytes>> + Send + 'static,
+    {
+        Self::ok()
+            .with_header("Content-Type", "text/event-stream")
+            .with_header("Cache-Control", "no-cache")
+            .with_header("X-Accel-Buffering", "no")
+            .with_stream(body)
+    }
+
+    /// Create a response that streams `items` as a JSON array
+    ///
+    /// Elements are serialized as they arrive, so memory stays bounded
+    /// however many rows the stream yields.
+    pub fn json_stream<S, T>(items: S) -> Self
+    where
+        S: futures::Stream<Item = Result<T>> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        Self::ok()
+            .with_header("Content-Type", "application/json")
+            .with_stream(json_stream::json_array_stream(items))
+    }
+
+    /// Create a streaming JSON array response and the writer that fills it
+    pub fn json_array_writer() -> (Self, JsonArrayWriter) {
+        let (writer, body) = JsonArrayWriter::channel();
+        let response = Self::ok()
+            .with_header("Content-Type", "application/json")
+            .with_stream(body);
+        (response, writer)
+    }
+
     /// Get the body size in bytes
     pub fn body_size(&self) -> usize {
         self.body.len()
@@ -288,8 +490,76 @@ impl Response {
             builder = builder.header(name, value);
         }
 
+        let body = match self.stream.and_then(|stream| stream.take()) {
+            Some(stream) => hyper::Body::wrap_stream(stream),
+            None => hyper::Body::from(self.body),
+        };
+
         builder
-            .body(hyper::Body::from(self.body))
+            .body(body)
             .unwrap_or_else(|_| hyper::Response::new(hyper::Body::empty()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> Response {
+        Response::html("<p>hello</p>")
+            .with_header("ETag", "\"v1\"")
+            .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_header("Cache-Control", "max-age=60")
+    }
+
+    #[test]
+    fn test_conditional_etag() {
+        let mut response = page();
+        assert!(response.apply_conditional("GET", Some("\"v1\""), None));
+        assert_eq!(response.status, StatusCode::NOT_MODIFIED);
+        assert!(response.body.is_empty());
+        assert!(!response
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type")));
+        assert!(response.headers.iter().any(|(name, _)| name == "ETag"));
+        assert!(response
+            .headers
+            .iter()
+            .any(|(name, _)| name == "Cache-Control"));
+
+        // A different tag gets the full response, whatever If-Modified-Since says
+        let mut response = page();
+        assert!(!response.apply_conditional(
+            "GET",
+            Some("\"v0\""),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        ));
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, b"<p>hello</p>");
+
+        // Weak comparison, in either direction, and lists of tags
+        assert!(page().apply_conditional("GET", Some("W/\"v1\""), None));
+        let mut weak = Response::ok().with_header("ETag", "W/\"v1\"");
+        assert!(weak.apply_conditional("HEAD", Some("\"v0\", \"v1\""), None));
+
+        // Only successful GET and HEAD requests
+        assert!(!page().apply_conditional("POST", Some("\"v1\""), None));
+        let mut created = page();
+        created.status = StatusCode::CREATED;
+        assert!(!created.apply_conditional("GET", Some("\"v1\""), None));
+    }
+
+    #[test]
+    fn test_conditional_last_modified() {
+        assert!(page().apply_conditional("GET", None, Some("Wed, 21 Oct 2015 07:28:00 GMT")));
+        assert!(page().apply_conditional("GET", None, Some("Thu, 22 Oct 2015 07:28:00 GMT")));
+        assert!(!page().apply_conditional("GET", None, Some("Tue, 20 Oct 2015 07:28:00 GMT")));
+        assert!(!page().apply_conditional("GET", None, Some("not a date")));
+        assert!(!Response::html("<p>hello</p>").apply_conditional(
+            "GET",
+            None,
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        ));
+    }
+}
@@ -1,10 +1,15 @@
 use crate::app::RustF;
-use crate::error::Result;
+use crate::config::ServerConfig;
+use crate::error::{Error, Result};
+use crate::http::{path_is_under, Response};
+use hyper::body::HttpBody;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::Server as HyperServer;
+use hyper::StatusCode;
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 pub struct Server {
     app: Arc<RustF>,
@@ -76,13 +81,22 @@ impl Server {
 
         // Keep reference to app for cleanup
         let app_ref = Arc::clone(&self.app);
+        let limit = Arc::new(RequestLimit::new(&self.app.config.server));
 
         let make_svc = make_service_fn(move |_conn| {
             let app = Arc::clone(&self.app);
+            let limit = Arc::clone(&limit);
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
                     let app = Arc::clone(&app);
+                    let limit = Arc::clone(&limit);
                     async move {
+                        // Held until the handler is done
+                        let _permit = match limit.admit(req.uri().path()) {
+                            Ok(permit) => permit,
+                            Err(response) => return Ok(response.into_hyper()),
+                        };
+
                         match app.handle_request(req).await {
                             Ok(response) => Ok::<hyper::Response<hyper::Body>, hyper::Error>(
                                 response.into_hyper(),
@@ -115,3 +129,178 @@ impl Server {
         Ok(())
     }
 }
+
+/// Caps the requests handled at once (`server.max_concurrent_requests`)
+///
+/// Requests over the limit are answered right away with a `503` rather than
+/// waiting for a slot, so an overloaded server sheds load instead of
+/// building an ever longer queue.
+struct RequestLimit {
+    permits: Option<Arc<Semaphore>>,
+    retry_after: String,
+    excluded_paths: Vec<String>,
+}
+
+impl RequestLimit {
+    fn new(config: &ServerConfig) -> Self {
+        if config.max_concurrent_requests > 0 {
+            log::info!(
+                "Limiting the server to {} concurrent requests",
+                config.max_concurrent_requests
+            );
+        }
+        Self {
+            permits: (config.max_concurrent_requests > 0)
+                .then(|| Arc::new(Semaphore::new(config.max_concurrent_requests))),
+            retry_after: config.overload_retry_after.to_string(),
+            excluded_paths: config.overload_excluded_paths.clone(),
+        }
+    }
+
+    /// Take a slot for a request to `path`
+    ///
+    /// Returns `Ok(None)` when the request is not limited and the `503`
+    /// response when no slot is free.
+    fn admit(&self, path: &str) -> std::result::Result<Option<OwnedSemaphorePermit>, Response> {
+        let Some(permits) = &self.permits else {
+            return Ok(None);
+        };
+        if self.is_excluded(path) {
+            return Ok(None);
+        }
+        match Arc::clone(permits).try_acquire_owned() {
+            Ok(permit) => Ok(Some(permit)),
+            Err(_) => {
+                log::debug!("Request limit reached, answering {} with 503", path);
+                Err(Response::new(StatusCode::SERVICE_UNAVAILABLE)
+                    .with_header("Retry-After", &self.retry_after)
+                    .with_header("Content-Type", "text/plain; charset=utf-8")
+                    .with_body(b"Service Unavailable".to_vec()))
+            }
+        }
+    }
+
+    /// Whether `path` is under an excluded prefix, on a segment boundary
+    fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_paths
+            .iter()
+            .any(|excluded| path_is_under(path, excluded))
+    }
+}
+
+/// Read a request body of at most `max_body_size` bytes (0 means no limit)
+///
+/// A `Content-Length` over the limit is rejected before anything is read,
+/// and a body without one stops being read as soon as it grows past the
+/// limit, so an oversized upload never ends up in memory. Both fail with
+/// `Error::PayloadTooLarge`, answered with `413 Payload Too Large`.
+pub(crate) async fn read_body(
+    mut body: hyper::Body,
+    content_length: Option<u64>,
+    max_body_size: usize,
+) -> Result<Vec<u8>> {
+    if max_body_size == 0 {
+        return Ok(hyper::body::to_bytes(body).await?.to_vec());
+    }
+
+    let too_large = || {
+        Error::payload_too_large(format!(
+            "Request body exceeds the limit of {} bytes",
+            max_body_size
+        ))
+    };
+    if content_length.is_some_and(|length| length > max_body_size as u64) {
+        return Err(too_large());
+    }
+
+    let mut body_bytes = Vec::with_capacity(content_length.unwrap_or(0) as usize);
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if body_bytes.len() + chunk.len() > max_body_size {
+            return Err(too_large());
+        }
+        body_bytes.extend_from_slice(&chunk);
+    }
+    Ok(body_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use hyper::body::Bytes;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn limit(max: usize) -> RequestLimit {
+        RequestLimit::new(&ServerConfig {
+            max_concurrent_requests: max,
+            ..ServerConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_request_limit_sheds_load() {
+        let limit = limit(2);
+        let first = limit.admit("/").unwrap();
+        let _second = limit.admit("/users").unwrap();
+
+        let response = limit.admit("/").unwrap_err();
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Retry-After" && value == "5"));
+
+        // Health checks bypass the limit, but not paths that only share the prefix
+        assert!(limit.admit("/health").unwrap().is_none());
+        assert!(limit.admit("/health/db").unwrap().is_none());
+        assert!(limit.admit("/healthy").is_err());
+
+        // A finished request frees its slot
+        drop(first);
+        assert!(limit.admit("/").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_no_limit_by_default() {
+        let limit = limit(0);
+        for _ in 0..100 {
+            assert!(limit.admit("/").unwrap().is_none());
+        }
+    }
+
+    /// A body of `chunks` 1 KiB chunks counting how many were read
+    fn counted_body(chunks: usize) -> (hyper::Body, Arc<AtomicUsize>) {
+        let read = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&read);
+        let stream = futures::stream::iter(0..chunks).map(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; 1024]))
+        });
+        (hyper::Body::wrap_stream(stream), read)
+    }
+
+    #[tokio::test]
+    async fn test_read_body_within_limit() {
+        let (body, _) = counted_body(4);
+        assert_eq!(read_body(body, None, 4096).await.unwrap().len(), 4096);
+
+        let (body, _) = counted_body(4);
+        assert_eq!(read_body(body, None, 0).await.unwrap().len(), 4096);
+    }
+
+    #[tokio::test]
+    async fn test_read_body_rejects_oversized_body() {
+        // Declared too large: nothing is read
+        let (body, read) = counted_body(1024);
+        let error = read_body(body, Some(1024 * 1024), 4096).await.unwrap_err();
+        assert_eq!(error.status_code(), 413);
+        assert_eq!(read.load(Ordering::SeqCst), 0);
+
+        // Streamed without a length: reading stops past the limit
+        let (body, read) = counted_body(1024);
+        let error = read_body(body, None, 4096).await.unwrap_err();
+        assert_eq!(error.status_code(), 413);
+        assert_eq!(read.load(Ordering::SeqCst), 5);
+    }
+}
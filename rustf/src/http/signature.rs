@@ -0,0 +1,196 @@
+//! HMAC request signatures for incoming webhooks
+//!
+//! Webhook senders sign the raw request body with a shared secret and put
+//! the HMAC-SHA256 in a header. Two header layouts are understood:
+//!
+//! - a bare signature, hex or base64, optionally prefixed with `sha256=`
+//!   (GitHub's `X-Hub-Signature-256: sha256=<hex>`)
+//! - comma-separated `t=<unix time>` and one or more `v1=<hex>` parts
+//!   (Stripe's `Stripe-Signature`), where the signed payload is
+//!   `<t>.<body>`
+//!
+//! Senders that put the timestamp in a header of its own are handled with
+//! [`SignatureOptions::timestamp_header`]; the signed payload is then
+//! `<timestamp>.<body>` as well. Whenever a timestamp is signed, it must lie
+//! within the tolerance of the current time so captured requests cannot be
+//! replayed later.
+
+use crate::utils::crypto::{constant_time_compare_bytes, hmac_sha256};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How a request signature is checked
+#[derive(Debug, Clone)]
+pub struct SignatureOptions {
+    /// Header carrying the signed Unix timestamp, if the sender uses one
+    pub timestamp_header: Option<String>,
+    /// Maximum distance between a signed timestamp and the current time
+    pub tolerance: Duration,
+}
+
+impl Default for SignatureOptions {
+    fn default() -> Self {
+        Self {
+            timestamp_header: None,
+            tolerance: Duration::from_secs(300),
+        }
+    }
+}
+
+impl SignatureOptions {
+    /// Create options with a five minute timestamp tolerance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the signed timestamp from `name`, and require it
+    pub fn timestamp_header(mut self, name: impl Into<String>) -> Self {
+        self.timestamp_header = Some(name.into());
+        self
+    }
+
+    /// Set the accepted clock distance for signed timestamps
+    pub fn tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// Verify a signature header against a request body
+///
+/// `timestamp` is the value of the configured timestamp header, if any.
+/// Returns false for malformed headers, stale timestamps and mismatches.
+pub fn verify(
+    header: &str,
+    body: &[u8],
+    secret: &str,
+    timestamp: Option<&str>,
+    options: &SignatureOptions,
+) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    verify_at(header, body, secret, timestamp, options, now)
+}
+
+fn verify_at(
+    header: &str,
+    body: &[u8],
+    secret: &str,
+    timestamp: Option<&str>,
+    options: &SignatureOptions,
+    now: u64,
+) -> bool {
+    let (signed_at, signatures) = match parse_timestamped(header) {
+        Some((t, signatures)) => (Some(t), signatures),
+        None => {
+            if options.timestamp_header.is_some() && timestamp.is_none() {
+                return false;
+            }
+            (timestamp.map(str::trim), vec![header.trim()])
+        }
+    };
+
+    let payload = match signed_at {
+        Some(t) => {
+            let Ok(seconds) = t.parse::<u64>() else {
+                return false;
+            };
+            if seconds.abs_diff(now) > options.tolerance.as_secs() {
+                return false;
+            }
+            [t.as_bytes(), b".", body].concat()
+        }
+        None => body.to_vec(),
+    };
+
+    let expected = hmac_sha256(secret.as_bytes(), &payload);
+    signatures
+        .into_iter()
+        .filter_map(decode_signature)
+        .any(|signature| constant_time_compare_bytes(&signature, &expected))
+}
+
+/// Split a `t=...,v1=...` header into its timestamp and signatures
+fn parse_timestamped(header: &str) -> Option<(&str, Vec<&str>)> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    Some((timestamp?, signatures))
+}
+
+/// Decode a hex or base64 signature, dropping a `sha256=` prefix
+fn decode_signature(value: &str) -> Option<Vec<u8>> {
+    let value = value.strip_prefix("sha256=").unwrap_or(value);
+    decode_hex(value).or_else(|| STANDARD.decode(value).ok())
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_plain_signatures() {
+        let body = br#"{"action":"opened"}"#;
+        let mac = hmac_sha256(b"secret", body);
+        let options = SignatureOptions::new();
+
+        let github = format!("sha256={}", hex(&mac));
+        assert!(verify(&github, body, "secret", None, &options));
+        let encoded = STANDARD.encode(&mac);
+        assert!(verify(&encoded, body, "secret", None, &options));
+        assert!(!verify(&github, body, "other", None, &options));
+        assert!(!verify(&github, b"{}", "secret", None, &options));
+        assert!(!verify("sha256=zz", body, "secret", None, &options));
+
+        // A required timestamp header that is missing fails verification
+        let options = options.timestamp_header("X-Timestamp");
+        assert!(!verify(&github, body, "secret", None, &options));
+    }
+
+    #[test]
+    fn test_timestamped_signatures() {
+        let body = b"payload";
+        let now = 1_700_000_000;
+        let signed = |t: u64| hex(&hmac_sha256(b"secret", format!("{}.payload", t).as_bytes()));
+        let options = SignatureOptions::new();
+
+        let header = format!("t={},v1=deadbeef,v1={}", now - 60, signed(now - 60));
+        assert!(verify_at(&header, body, "secret", None, &options, now));
+
+        // Replayed outside the tolerance
+        let header = format!("t={},v1={}", now - 600, signed(now - 600));
+        assert!(!verify_at(&header, body, "secret", None, &options, now));
+        let relaxed = SignatureOptions::new().tolerance(Duration::from_secs(900));
+        assert!(verify_at(&header, body, "secret", None, &relaxed, now));
+
+        // Timestamp from a separate header
+        let options = SignatureOptions::new().timestamp_header("X-Timestamp");
+        let t = (now + 10).to_string();
+        let header = signed(now + 10);
+        assert!(verify_at(&header, body, "secret", Some(&t), &options, now));
+        let t = "1";
+        assert!(!verify_at(&header, body, "secret", Some(t), &options, now));
+    }
+}
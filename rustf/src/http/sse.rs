@@ -0,0 +1,328 @@
+//! Server-Sent Events (SSE)
+//!
+//! Push live updates to the browser over a plain HTTP response:
+//!
+//! ```rust,ignore
+//! use rustf::http::SseEvent;
+//!
+//! async fn live(ctx: &mut Context) -> Result<()> {
+//!     let (tx, rx) = tokio::sync::mpsc::channel::<String>(16);
+//!     NOTIFICATIONS.subscribe(tx);
+//!
+//!     let events = futures::stream::unfold(rx, |mut rx| async move {
+//!         let message = rx.recv().await?;
+//!         Some((SseEvent::new(message).event("notice"), rx))
+//!     });
+//!
+//!     ctx.sse(events)
+//! }
+//! ```
+//!
+//! ```javascript
+//! new EventSource("/live").addEventListener("notice", e => console.log(e.data));
+//! ```
+//!
+//! When events are pushed from elsewhere, `ctx.sse_stream()` opens the
+//! response and returns an [`SseStream`] handle to send them through:
+//!
+//! ```rust,ignore
+//! async fn dashboard(ctx: &mut Context) -> Result<()> {
+//!     let stream = ctx.sse_stream();
+//!     tokio::spawn(async move {
+//!         loop {
+//!             let stats = collect_stats().await;
+//!             if stream.send_event("stats", &json!(stats)).await.is_err() {
+//!                 break; // client went away
+//!             }
+//!             tokio::time::sleep(Duration::from_secs(5)).await;
+//!         }
+//!     });
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use futures::{Stream, StreamExt};
+use hyper::body::Bytes;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Interval between keep-alive comments on idle streams
+pub const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Comment frame sent to keep idle connections (and proxies) open
+const KEEP_ALIVE_FRAME: &str = ": keep-alive\n\n";
+
+/// Frames an `SseStream` may queue before senders wait for the client
+const CHANNEL_FRAMES: usize = 16;
+
+/// A single server-sent event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Event type (`event:` line); clients listen with `addEventListener(event, ...)`
+    pub event: Option<String>,
+    /// Event payload; multi-line data is sent as several `data:` lines
+    pub data: String,
+    /// Event id (`id:` line), echoed back by the browser as `Last-Event-ID` on reconnect
+    pub id: Option<String>,
+    /// Reconnection delay in milliseconds (`retry:` line)
+    pub retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// Create an unnamed event with the given data
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create an event whose data is the JSON encoding of `value`
+    pub fn json<T: serde::Serialize>(value: &T) -> Result<Self> {
+        serde_json::to_string(value)
+            .map(Self::new)
+            .map_err(|e| Error::internal(format!("Failed to serialize SSE data: {}", e)))
+    }
+
+    /// Set the event type
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Set the event id
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the client reconnection delay in milliseconds
+    pub fn retry(mut self, millis: u64) -> Self {
+        self.retry = Some(millis);
+        self
+    }
+
+    /// Format the event as a wire frame terminated by a blank line
+    pub fn to_frame(&self) -> String {
+        let mut frame = String::new();
+
+        if let Some(event) = &self.event {
+            frame.push_str(&format!("event: {}\n", single_line(event)));
+        }
+        if let Some(id) = &self.id {
+            frame.push_str(&format!("id: {}\n", single_line(id)));
+        }
+        if let Some(retry) = self.retry {
+            frame.push_str(&format!("retry: {}\n", retry));
+        }
+        for line in self.data.split('\n') {
+            frame.push_str("data: ");
+            frame.push_str(line.strip_suffix('\r').unwrap_or(line));
+            frame.push('\n');
+        }
+
+        frame.push('\n');
+        frame
+    }
+}
+
+/// Field values other than data must not contain line breaks
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Format a comment frame, one `:` line per line of `text`
+///
+/// Clients ignore comments; they only keep the connection busy.
+pub fn comment_frame(text: &str) -> String {
+    let mut frame = String::new();
+    for line in text.split('\n') {
+        frame.push_str(": ");
+        frame.push_str(line.strip_suffix('\r').unwrap_or(line));
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
+/// Sends events to an open Server-Sent Events response
+///
+/// Clones share the same response, so the handle can be moved into several
+/// tasks. Sends wait while the client is slow to read and fail once it has
+/// disconnected. The response ends when every handle is dropped.
+#[derive(Clone)]
+pub struct SseStream {
+    sender: mpsc::Sender<String>,
+}
+
+impl SseStream {
+    /// A handle and the body stream it feeds
+    ///
+    /// `keep_alive` works as in [`event_stream`].
+    pub fn channel(
+        keep_alive: Option<Duration>,
+    ) -> (
+        Self,
+        impl Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    ) {
+        let (sender, receiver) = mpsc::channel(CHANNEL_FRAMES);
+        let frames = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|frame| (frame, receiver))
+        });
+        (Self { sender }, frame_stream(frames, keep_alive))
+    }
+
+    /// Send an event
+    pub async fn send(&self, event: SseEvent) -> Result<()> {
+        self.send_frame(event.to_frame()).await
+    }
+
+    /// Send an event of type `event` whose data is `data` as JSON
+    pub async fn send_event(&self, event: &str, data: &Value) -> Result<()> {
+        self.send(SseEvent::json(data)?.event(event)).await
+    }
+
+    /// Send a comment, ignored by clients
+    pub async fn send_comment(&self, text: &str) -> Result<()> {
+        self.send_frame(comment_frame(text)).await
+    }
+
+    /// Check whether the client has disconnected
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    async fn send_frame(&self, frame: String) -> Result<()> {
+        self.sender.send(frame).await.map_err(|_| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "client disconnected",
+            ))
+        })
+    }
+}
+
+/// Turn a stream of events into a stream of body chunks
+///
+/// When `keep_alive` is set, a comment frame is sent after each period
+/// without events. The body ends when the event stream completes.
+pub fn event_stream<S>(
+    events: S,
+    keep_alive: Option<Duration>,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + 'static
+where
+    S: Stream<Item = SseEvent> + Send + 'static,
+{
+    frame_stream(events.map(|event| event.to_frame()), keep_alive)
+}
+
+/// Send formatted frames, with keep-alive comments on idle periods
+fn frame_stream<S>(
+    frames: S,
+    keep_alive: Option<Duration>,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + 'static
+where
+    S: Stream<Item = String> + Send + 'static,
+{
+    let ticker = keep_alive.map(|period| {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker
+    });
+
+    futures::stream::unfold(
+        (Box::pin(frames), ticker),
+        |(mut frames, mut ticker)| async move {
+            let frame = match ticker.as_mut() {
+                Some(ticker) => tokio::select! {
+                    frame = frames.next() => {
+                        ticker.reset();
+                        frame
+                    }
+                    _ = ticker.tick() => Some(KEEP_ALIVE_FRAME.to_string()),
+                },
+                None => frames.next().await,
+            };
+
+            frame.map(|frame| (Ok(Bytes::from(frame)), (frames, ticker)))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_format() {
+        let event = SseEvent::new("line one\nline two")
+            .event("update")
+            .id("42")
+            .retry(5000);
+
+        assert_eq!(
+            event.to_frame(),
+            "event: update\nid: 42\nretry: 5000\ndata: line one\ndata: line two\n\n"
+        );
+        assert_eq!(
+            SseEvent::new("x").event("a\nb").to_frame(),
+            "event: a b\ndata: x\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_ends_with_events() {
+        let events = futures::stream::iter(vec![SseEvent::new("a"), SseEvent::new("b")]);
+        let chunks: Vec<Bytes> = event_stream(events, Some(DEFAULT_KEEP_ALIVE))
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(
+            chunks,
+            vec![Bytes::from("data: a\n\n"), Bytes::from("data: b\n\n")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_sends_events_and_comments() {
+        let (stream, body) = SseStream::channel(None);
+        let mut body = Box::pin(body);
+
+        stream
+            .send_event("stats", &serde_json::json!({"users": 3}))
+            .await
+            .unwrap();
+        assert_eq!(
+            body.next().await.unwrap().unwrap(),
+            Bytes::from("event: stats\ndata: {\"users\":3}\n\n")
+        );
+
+        stream.send_comment("still here\nreally").await.unwrap();
+        assert_eq!(
+            body.next().await.unwrap().unwrap(),
+            Bytes::from(": still here\n: really\n\n")
+        );
+
+        // Dropping every handle ends the response
+        drop(stream);
+        assert!(body.next().await.is_none());
+
+        // Sending fails once the client is gone
+        let (stream, body) = SseStream::channel(None);
+        drop(body);
+        assert!(stream.is_closed());
+        assert!(stream.send_comment("anyone?").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_on_idle_stream() {
+        let events = futures::stream::pending::<SseEvent>();
+        let mut body = Box::pin(event_stream(events, Some(Duration::from_millis(20))));
+
+        let chunk = body.next().await.unwrap().unwrap();
+        assert_eq!(chunk, Bytes::from(KEEP_ALIVE_FRAME));
+    }
+}
@@ -0,0 +1,102 @@
+//! Streaming response bodies
+//!
+//! Most responses carry their body in `Response::body`. Long-lived responses
+//! (server-sent events, large generated downloads) instead attach a byte
+//! stream that is forwarded to the client chunk by chunk as it is produced.
+//!
+//! A streamed body has no known length, so it is sent with
+//! `Transfer-Encoding: chunked` on HTTP/1.1 (HTTP/2 frames it natively).
+//! Outbound middleware still runs and may add headers, but anything that
+//! rewrites or inspects the body (caching, capture, the dev reload script)
+//! leaves streamed responses alone.
+
+use futures::{Stream, StreamExt};
+use hyper::body::Bytes;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Boxed stream of body chunks
+pub type ByteStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// A response body produced by a stream
+///
+/// The stream can only be consumed once. Clones share the same stream so that
+/// `Response` stays cheap to clone; whichever clone is sent first takes it.
+#[derive(Clone)]
+pub struct BodyStream {
+    inner: Arc<Mutex<Option<ByteStream>>>,
+}
+
+impl BodyStream {
+    /// Wrap a stream of body chunks
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            inner: Arc::new(Mutex::new(Some(Box::pin(stream)))),
+        }
+    }
+
+    /// Take the stream out, leaving this body empty
+    pub fn take(&self) -> Option<ByteStream> {
+        self.inner.lock().ok().and_then(|mut stream| stream.take())
+    }
+}
+
+/// Adapt a stream of `Result<Bytes>` chunks to a response body
+///
+/// The status and headers are already sent when the stream fails, so an
+/// error ends the body early and the client sees a truncated response.
+pub fn chunks<S>(chunks: S) -> impl Stream<Item = std::io::Result<Bytes>> + Send
+where
+    S: Stream<Item = crate::error::Result<Bytes>> + Send + 'static,
+{
+    chunks.map(|chunk| {
+        chunk.map_err(|e| {
+            log::error!("Streamed response body failed: {}", e);
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, e.to_string())
+        })
+    })
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BodyStream")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::http::Response;
+    use hyper::body::HttpBody;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_chunks_are_sent_before_stream_ends() {
+        let (tx, rx) = mpsc::channel::<crate::error::Result<Bytes>>(4);
+        let body = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        });
+        let mut body = Response::chunked("text/plain", body)
+            .into_hyper()
+            .into_body();
+        assert_eq!(HttpBody::size_hint(&body).exact(), None);
+
+        // Each chunk can be read while the producer is still running
+        tx.send(Ok(Bytes::from("first"))).await.unwrap();
+        assert_eq!(body.data().await.unwrap().unwrap(), Bytes::from("first"));
+        tx.send(Ok(Bytes::from("second"))).await.unwrap();
+        assert_eq!(body.data().await.unwrap().unwrap(), Bytes::from("second"));
+
+        tx.send(Err(Error::internal("producer failed")))
+            .await
+            .unwrap();
+        assert!(body.data().await.unwrap().is_err());
+        drop(tx);
+        assert!(body.data().await.is_none());
+    }
+}
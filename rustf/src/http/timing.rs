@@ -0,0 +1,116 @@
+//! Per-request timing breakdown
+//!
+//! With `server.server_timing = true` the framework measures how long each
+//! request spends in inbound middleware, the handler, view rendering and
+//! outbound middleware, exposes it through `ctx.timings()` and reports it in
+//! a `Server-Timing` header that browser devtools show next to the request.
+//! When the setting is off no clock is read at all.
+
+use std::time::{Duration, Instant};
+
+/// Phase of request processing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingPhase {
+    /// Inbound middleware
+    Inbound,
+    /// Route handler, including any view it rendered
+    Handler,
+    /// Template rendering
+    View,
+    /// Outbound middleware
+    Outbound,
+}
+
+/// Time spent in each phase of a request
+#[derive(Debug, Clone)]
+pub struct RequestTimings {
+    started: Instant,
+    inbound: Duration,
+    handler: Duration,
+    view: Duration,
+    outbound: Duration,
+}
+
+impl RequestTimings {
+    /// Start timing a request now
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            inbound: Duration::ZERO,
+            handler: Duration::ZERO,
+            view: Duration::ZERO,
+            outbound: Duration::ZERO,
+        }
+    }
+
+    /// Add time spent in `phase`
+    pub fn record(&mut self, phase: TimingPhase, elapsed: Duration) {
+        let total = match phase {
+            TimingPhase::Inbound => &mut self.inbound,
+            TimingPhase::Handler => &mut self.handler,
+            TimingPhase::View => &mut self.view,
+            TimingPhase::Outbound => &mut self.outbound,
+        };
+        *total += elapsed;
+    }
+
+    /// Time spent in inbound middleware
+    pub fn inbound(&self) -> Duration {
+        self.inbound
+    }
+
+    /// Time spent in the handler, not counting view rendering
+    pub fn handler(&self) -> Duration {
+        self.handler.saturating_sub(self.view)
+    }
+
+    /// Time spent rendering views
+    pub fn view(&self) -> Duration {
+        self.view
+    }
+
+    /// Time spent in outbound middleware
+    pub fn outbound(&self) -> Duration {
+        self.outbound
+    }
+
+    /// Time since the request started being processed
+    pub fn total(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Value for the `Server-Timing` response header, in milliseconds
+    pub fn server_timing(&self) -> String {
+        [
+            ("inbound", self.inbound()),
+            ("handler", self.handler()),
+            ("view", self.view()),
+            ("outbound", self.outbound()),
+            ("total", self.total()),
+        ]
+        .iter()
+        .map(|(name, duration)| format!("{};dur={:.3}", name, duration.as_secs_f64() * 1000.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_excludes_view_time() {
+        let mut timings = RequestTimings::start();
+        timings.record(TimingPhase::Inbound, Duration::from_millis(2));
+        timings.record(TimingPhase::Handler, Duration::from_millis(10));
+        timings.record(TimingPhase::View, Duration::from_millis(4));
+
+        assert_eq!(timings.handler(), Duration::from_millis(6));
+        assert_eq!(timings.view(), Duration::from_millis(4));
+
+        let header = timings.server_timing();
+        assert!(header.starts_with("inbound;dur=2.000, handler;dur=6.000, view;dur=4.000"));
+        assert!(header.contains("outbound;dur=0.000, total;dur="));
+    }
+}
@@ -21,6 +21,7 @@
 #![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used,))]
 
 pub mod app;
+pub mod audit;
 pub mod auto;
 pub mod cache;
 pub mod config;
@@ -31,8 +32,10 @@ pub mod error;
 pub mod events;
 pub mod forms;
 pub mod http;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
+pub mod plugins;
 pub mod pool;
 pub mod repository;
 pub mod routing;
@@ -55,6 +58,14 @@ pub mod db;
 // Migration system for database schema management
 pub mod migrations;
 
+// Live reload of views during development
+#[cfg(feature = "dev")]
+pub mod dev;
+
+// OpenTelemetry request and database spans
+#[cfg(feature = "otel")]
+pub mod telemetry;
+
 // Schema support through rustf-schema crate
 #[cfg(feature = "schema")]
 pub use rustf_schema as schema;
@@ -66,6 +77,7 @@ pub use context::Context;
 pub use error::{Error, Result};
 pub use http::{Request, Response};
 pub use middleware::{InboundAction, InboundMiddleware, MiddlewareResult, OutboundMiddleware};
+pub use plugins::Plugin;
 pub use routing::{Route, RouteHandler};
 
 // Re-export database access for backward compatibility
@@ -75,10 +87,15 @@ pub use events::{builtin, EventContext, EventEmitter};
 pub use pool::{global_request_pool, PooledRequest, RequestPool};
 pub use repository::{APP, MAIN};
 pub use security::{
-    CsrfConfig, CsrfMiddleware, HtmlEscaper, InputValidator, PathValidator, SecurityConfig,
+    CsrfConfig, CsrfMiddleware, HtmlEscaper, HtmlSanitizer, InputValidator, PathValidator,
+    SecurityConfig,
 };
+pub use session::database::DatabaseSessionStorage;
 pub use session::factory::SessionStorageFactory;
+#[cfg(feature = "memcached")]
+pub use session::memcached::MemcachedSessionStorage;
 pub use session::redis::RedisSessionStorage;
+pub use session::tiered::TieredSessionStorage;
 pub use session::{Session, SessionData, SessionStorage, SessionStore, StorageStats};
 pub use shared::{SharedModule, SharedModuleType, SharedRegistry, MODULE};
 pub use utils::{Utils, U};
@@ -105,7 +122,7 @@ pub use serde_json::{json, Value};
 // Re-export auto-discovery macros unconditionally
 pub use rustf_macros::{
     auto_controllers, auto_definitions, auto_discover, auto_events, auto_middleware, auto_models,
-    auto_modules, auto_workers,
+    auto_modules, auto_workers, route, validate,
 };
 
 /// Prelude module for common imports
@@ -119,6 +136,7 @@ pub mod prelude {
     pub use crate::configuration::CONF;
     pub use crate::repository::{APP, MAIN};
     pub use crate::shared::MODULE;
+    pub use crate::utils::pagination::{Page, PageMeta};
     pub use crate::utils::{Utils, U};
     pub use crate::views::VIEW;
     pub use crate::workers::WORKER;
@@ -134,7 +152,7 @@ pub mod prelude {
     pub use crate::events::{builtin, EventContext, EventEmitter};
 
     // Database model traits for generated models
-    pub use crate::models::{DatabaseModel, ModelQuery, OrderDirection, SqlValue};
+    pub use crate::models::{DatabaseModel, ModelQuery, OrderDirection, Repository, SqlValue};
 
     // Global database access
     pub use crate::db::DB;
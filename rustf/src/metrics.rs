@@ -0,0 +1,335 @@
+//! Prometheus-style metrics for RustF
+//!
+//! Collects request counts and latency histograms per route, plus hit rates
+//! of registered caches, and renders them in the Prometheus text exposition
+//! format. Requests are recorded by `MetricsMiddleware`; routes are labelled
+//! with their registered pattern (`/users/{id}`) rather than the concrete path
+//! so label cardinality stays bounded.
+//!
+//! ```rust,ignore
+//! use rustf::metrics;
+//! use rustf::middleware::builtin::MetricsMiddleware;
+//!
+//! let app = RustF::new()
+//!     .middleware_from(|registry| {
+//!         registry.register_dual("metrics", MetricsMiddleware::new());
+//!     })
+//!     .controllers(routes![GET "/metrics" => metrics::endpoint]);
+//!
+//! metrics::global().register_cache("responses", move || response_cache.stats());
+//! ```
+
+use crate::cache::CacheStats;
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::Response;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Route label used for requests that matched no route
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Default histogram buckets in seconds
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+type CacheStatsFn = Box<dyn Fn() -> CacheStats + Send + Sync>;
+
+/// Latency histogram for one method/route pair
+#[derive(Debug, Clone)]
+struct Histogram {
+    /// Cumulative counts per bucket upper bound
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bucket_count: usize) -> Self {
+        Self {
+            buckets: vec![0; bucket_count],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, bounds: &[f64], seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(bounds) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Collected request metrics
+#[derive(Default)]
+struct RequestMetrics {
+    /// (method, route, status) -> count
+    counts: BTreeMap<(String, String, u16), u64>,
+    /// (method, route) -> duration histogram
+    durations: BTreeMap<(String, String), Histogram>,
+}
+
+/// Registry of application metrics
+pub struct MetricsRegistry {
+    buckets: Vec<f64>,
+    requests: Mutex<RequestMetrics>,
+    caches: Mutex<Vec<(String, CacheStatsFn)>>,
+}
+
+impl MetricsRegistry {
+    /// Create a registry with the default histogram buckets
+    pub fn new() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+
+    /// Create a registry with custom histogram buckets (seconds, ascending)
+    pub fn with_buckets(mut buckets: Vec<f64>) -> Self {
+        buckets.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            buckets,
+            requests: Mutex::new(RequestMetrics::default()),
+            caches: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a completed request
+    pub fn record_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let Ok(mut requests) = self.requests.lock() else {
+            return;
+        };
+
+        *requests
+            .counts
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert(0) += 1;
+
+        let bucket_count = self.buckets.len();
+        requests
+            .durations
+            .entry((method.to_string(), route.to_string()))
+            .or_insert_with(|| Histogram::new(bucket_count))
+            .observe(&self.buckets, duration.as_secs_f64());
+    }
+
+    /// Register a cache whose statistics should be exported
+    pub fn register_cache<F>(&self, name: impl Into<String>, stats: F)
+    where
+        F: Fn() -> CacheStats + Send + Sync + 'static,
+    {
+        if let Ok(mut caches) = self.caches.lock() {
+            caches.push((name.into(), Box::new(stats)));
+        }
+    }
+
+    /// Clear all recorded request metrics
+    pub fn reset(&self) {
+        if let Ok(mut requests) = self.requests.lock() {
+            *requests = RequestMetrics::default();
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        if let Ok(requests) = self.requests.lock() {
+            self.render_requests(&requests, &mut out);
+        }
+        if let Ok(caches) = self.caches.lock() {
+            render_caches(&caches, &mut out);
+        }
+
+        out
+    }
+
+    fn render_requests(&self, requests: &RequestMetrics, out: &mut String) {
+        out.push_str("# HELP rustf_http_requests_total Total number of HTTP requests\n");
+        out.push_str("# TYPE rustf_http_requests_total counter\n");
+        for ((method, route, status), count) in &requests.counts {
+            let _ = writeln!(
+                out,
+                "rustf_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                escape_label(method),
+                escape_label(route),
+                status,
+                count
+            );
+        }
+
+        out.push_str("# HELP rustf_http_request_duration_seconds HTTP request latency\n");
+        out.push_str("# TYPE rustf_http_request_duration_seconds histogram\n");
+        for ((method, route), histogram) in &requests.durations {
+            let labels = format!(
+                "method=\"{}\",route=\"{}\"",
+                escape_label(method),
+                escape_label(route)
+            );
+            for (bound, count) in self.buckets.iter().zip(&histogram.buckets) {
+                let _ = writeln!(
+                    out,
+                    "rustf_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "rustf_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "rustf_http_request_duration_seconds_sum{{{}}} {}",
+                labels, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "rustf_http_request_duration_seconds_count{{{}}} {}",
+                labels, histogram.count
+            );
+        }
+    }
+}
+
+/// Cache metric name, type, help text and value extractor
+type CacheSeries = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&CacheStats) -> f64,
+);
+
+const CACHE_SERIES: [CacheSeries; 4] = [
+    ("rustf_cache_hits_total", "counter", "Cache hits", |s| {
+        s.hits as f64
+    }),
+    ("rustf_cache_misses_total", "counter", "Cache misses", |s| {
+        s.misses as f64
+    }),
+    (
+        "rustf_cache_entries",
+        "gauge",
+        "Entries currently cached",
+        |s| s.entries as f64,
+    ),
+    (
+        "rustf_cache_hit_ratio",
+        "gauge",
+        "Cache hit ratio (0-1)",
+        |s| s.hit_rate(),
+    ),
+];
+
+fn render_caches(caches: &[(String, CacheStatsFn)], out: &mut String) {
+    if caches.is_empty() {
+        return;
+    }
+
+    let stats: Vec<(String, CacheStats)> = caches
+        .iter()
+        .map(|(name, stats)| (escape_label(name), stats()))
+        .collect();
+
+    for (metric, kind, help, value) in CACHE_SERIES {
+        let _ = writeln!(out, "# HELP {} {}", metric, help);
+        let _ = writeln!(out, "# TYPE {} {}", metric, kind);
+        for (name, stats) in &stats {
+            let _ = writeln!(out, "{}{{cache=\"{}\"}} {}", metric, name, value(stats));
+        }
+    }
+}
+
+/// Escape a label value per the exposition format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static METRICS: OnceLock<MetricsRegistry> = OnceLock::new();
+
+/// Get the global metrics registry
+pub fn global() -> &'static MetricsRegistry {
+    METRICS.get_or_init(MetricsRegistry::new)
+}
+
+/// Route handler rendering the global registry for Prometheus scrapes
+pub async fn endpoint(ctx: &mut Context) -> Result<()> {
+    ctx.set_response(
+        Response::ok()
+            .with_header("Content-Type", PROMETHEUS_CONTENT_TYPE)
+            .with_body(global().render().into_bytes()),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_request_metrics() {
+        let registry = MetricsRegistry::with_buckets(vec![0.1, 1.0]);
+        registry.record_request("GET", "/users/{id}", 200, Duration::from_millis(50));
+        registry.record_request("GET", "/users/{id}", 200, Duration::from_millis(500));
+        registry.record_request("GET", "/users/{id}", 404, Duration::from_secs(2));
+
+        let output = registry.render();
+
+        assert!(output.contains(
+            "rustf_http_requests_total{method=\"GET\",route=\"/users/{id}\",status=\"200\"} 2"
+        ));
+        assert!(output.contains(
+            "rustf_http_requests_total{method=\"GET\",route=\"/users/{id}\",status=\"404\"} 1"
+        ));
+        assert!(output.contains(
+            "rustf_http_request_duration_seconds_bucket{method=\"GET\",route=\"/users/{id}\",le=\"0.1\"} 1"
+        ));
+        assert!(output.contains(
+            "rustf_http_request_duration_seconds_bucket{method=\"GET\",route=\"/users/{id}\",le=\"1\"} 2"
+        ));
+        assert!(output.contains(
+            "rustf_http_request_duration_seconds_bucket{method=\"GET\",route=\"/users/{id}\",le=\"+Inf\"} 3"
+        ));
+        assert!(output.contains(
+            "rustf_http_request_duration_seconds_count{method=\"GET\",route=\"/users/{id}\"} 3"
+        ));
+    }
+
+    #[test]
+    fn test_render_cache_metrics() {
+        let registry = MetricsRegistry::new();
+        registry.register_cache("templates", || CacheStats {
+            hits: 3,
+            misses: 1,
+            entries: 2,
+            ..Default::default()
+        });
+
+        let output = registry.render();
+
+        assert!(output.contains("rustf_cache_hits_total{cache=\"templates\"} 3"));
+        assert!(output.contains("rustf_cache_hit_ratio{cache=\"templates\"} 0.75"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,217 @@
+//! HTTP Basic authentication middleware for RustF
+//!
+//! Quick protection for internal tools such as `/admin` or `/metrics`.
+//! Credentials are checked by a pluggable verifier; requests without valid
+//! credentials receive `401 Unauthorized` with a `WWW-Authenticate` challenge.
+//!
+//! ```rust,ignore
+//! use rustf::middleware::builtin::BasicAuthMiddleware;
+//!
+//! app.middleware_from(|registry| {
+//!     registry.register_inbound(
+//!         "admin_auth",
+//!         BasicAuthMiddleware::with_credentials("Admin", "admin", "s3cret")
+//!             .paths(vec!["/admin", "/metrics"]),
+//!     );
+//! });
+//! ```
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::{path_is_under, Response};
+use crate::middleware::{InboundAction, InboundMiddleware};
+use crate::utils::crypto::constant_time_compare;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use hyper::StatusCode;
+use std::sync::Arc;
+
+/// Context key holding the authenticated username
+pub const BASIC_AUTH_USER: &str = "basic_auth_user";
+
+/// Credential verifier: receives the username and password, returns whether they are valid
+pub type BasicAuthVerifier = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+/// Middleware enforcing HTTP Basic authentication
+#[derive(Clone)]
+pub struct BasicAuthMiddleware {
+    /// Realm advertised in the WWW-Authenticate challenge
+    realm: String,
+    /// Credential verifier
+    verifier: BasicAuthVerifier,
+    /// Path prefixes to protect (all paths when empty)
+    paths: Vec<String>,
+}
+
+impl BasicAuthMiddleware {
+    /// Create basic auth middleware with a custom verifier
+    ///
+    /// Verifiers comparing secrets should use
+    /// `rustf::utils::crypto::constant_time_compare` to avoid timing attacks.
+    pub fn new<F>(realm: impl Into<String>, verifier: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            realm: realm.into(),
+            verifier: Arc::new(verifier),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Create basic auth middleware accepting a single username/password pair
+    ///
+    /// Both values are compared in constant time.
+    pub fn with_credentials(
+        realm: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        let expected_user = username.into();
+        let expected_pass = password.into();
+
+        Self::new(realm, move |user, pass| {
+            // Evaluate both comparisons so a wrong username costs the same as a wrong password
+            let user_ok = constant_time_compare(user, &expected_user);
+            let pass_ok = constant_time_compare(pass, &expected_pass);
+            user_ok & pass_ok
+        })
+    }
+
+    /// Only protect requests whose path starts with one of these prefixes
+    pub fn paths(mut self, paths: Vec<&str>) -> Self {
+        self.paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Whether `path` is under one of the protected prefixes, on a segment boundary
+    fn protects(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|prefix| path_is_under(path, prefix))
+    }
+
+    /// Decode an `Authorization: Basic ...` header into username and password
+    fn parse_credentials(header: &str) -> Option<(String, String)> {
+        let (scheme, encoded) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+
+        let decoded = general_purpose::STANDARD.decode(encoded.trim()).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (user, pass) = decoded.split_once(':')?;
+
+        Some((user.to_string(), pass.to_string()))
+    }
+
+    /// Build the 401 challenge response
+    fn challenge(&self) -> Response {
+        let realm = self.realm.replace('"', "'");
+
+        Response::new(StatusCode::UNAUTHORIZED)
+            .with_header(
+                "WWW-Authenticate",
+                &format!("Basic realm=\"{}\", charset=\"UTF-8\"", realm),
+            )
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(b"Unauthorized".to_vec())
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for BasicAuthMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        let credentials = ctx
+            .header("authorization")
+            .and_then(Self::parse_credentials);
+
+        match credentials {
+            Some((user, pass)) if (self.verifier)(&user, &pass) => {
+                ctx.set(BASIC_AUTH_USER, user)?;
+                Ok(InboundAction::Continue)
+            }
+            // Outbound middleware such as CORS still applies to the challenge
+            _ => Ok(InboundAction::Respond(self.challenge())),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "basic_auth"
+    }
+
+    fn priority(&self) -> i32 {
+        -550 // After CORS so preflights pass, before session loading
+    }
+
+    fn should_run(&self, ctx: &Context) -> bool {
+        self.protects(ctx.path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_credentials() {
+        // "admin:s3cret:with:colons"
+        let header = "Basic YWRtaW46czNjcmV0OndpdGg6Y29sb25z";
+        assert_eq!(
+            BasicAuthMiddleware::parse_credentials(header),
+            Some(("admin".to_string(), "s3cret:with:colons".to_string()))
+        );
+
+        assert_eq!(BasicAuthMiddleware::parse_credentials("Bearer abc"), None);
+        assert_eq!(
+            BasicAuthMiddleware::parse_credentials("Basic not-base64!"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_credentials_verifier() {
+        let middleware = BasicAuthMiddleware::with_credentials("Admin", "admin", "s3cret");
+
+        assert!((middleware.verifier)("admin", "s3cret"));
+        assert!(!(middleware.verifier)("admin", "wrong"));
+        assert!(!(middleware.verifier)("root", "s3cret"));
+    }
+
+    #[test]
+    fn test_challenge_header() {
+        let middleware = BasicAuthMiddleware::new("Internal \"Tools\"", |_, _| false);
+        let response = middleware.challenge();
+
+        assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+        assert!(response.headers.iter().any(|(k, v)| k == "WWW-Authenticate"
+            && v == "Basic realm=\"Internal 'Tools'\", charset=\"UTF-8\""));
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        let middleware = BasicAuthMiddleware::with_credentials("Admin", "admin", "s3cret")
+            .paths(vec!["/admin", "/metrics/"]);
+
+        assert!(middleware.protects("/admin"));
+        assert!(middleware.protects("/admin/users"));
+        assert!(middleware.protects("/metrics/cpu"));
+        assert!(!middleware.protects("/administrator"));
+        assert!(!middleware.protects("/metricsx"));
+        assert!(!middleware.protects("/"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_credentials_respond_with_challenge() {
+        let middleware = BasicAuthMiddleware::with_credentials("Admin", "admin", "s3cret");
+        let views = Arc::new(crate::views::ViewEngine::from_directory("views"));
+        let mut ctx = Context::new(crate::http::Request::default(), views);
+
+        match middleware.process_request(&mut ctx).await.unwrap() {
+            InboundAction::Respond(response) => {
+                assert_eq!(response.status, StatusCode::UNAUTHORIZED)
+            }
+            other => panic!("expected a challenge, got {:?}", other),
+        }
+        // The context keeps its default response; the challenge replaces it
+        assert_eq!(ctx.res.map(|res| res.status), Some(StatusCode::OK));
+    }
+}
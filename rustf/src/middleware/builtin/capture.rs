@@ -0,0 +1,419 @@
+//! Request capture middleware for debugging
+//!
+//! Records complete request/response exchanges (method, URI, headers and
+//! bodies) into a bounded in-memory ring buffer so that problems seen on real
+//! traffic can be reproduced. Sensitive headers are redacted before anything
+//! is stored, bodies are truncated to a configurable size, and the oldest
+//! exchanges are dropped once the buffer is full.
+//!
+//! Capturing is off unless the middleware is registered, and the
+//! `from_config` constructor keeps it off until `enabled = true`:
+//!
+//! ```toml
+//! [middleware.capture]
+//! enabled = false
+//! capacity = 100
+//! max_body_bytes = 16384
+//! redact_headers = ["authorization", "cookie", "set-cookie"]
+//! excluded_paths = ["/admin/captures"]
+//! ```
+//!
+//! Read the buffer with the `rustf::middleware::builtin::capture::endpoint`
+//! handler, which must be protected like any other admin route.
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::{path_is_under, Response};
+use crate::middleware::{InboundAction, InboundMiddleware, OutboundMiddleware};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// Context key holding the request half of an exchange
+const PENDING_KEY: &str = "capture_pending_request";
+
+/// Value stored in place of a redacted header
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers redacted unless configured otherwise
+const DEFAULT_REDACTED_HEADERS: [&str; 5] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// A captured request body or response body
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedBody {
+    /// Body as text (invalid UTF-8 is replaced), cut at `max_body_bytes`
+    pub content: String,
+    /// Full size of the body in bytes
+    pub size: usize,
+    /// Whether `content` was cut short
+    pub truncated: bool,
+}
+
+impl CapturedBody {
+    fn new(bytes: &[u8], max_bytes: usize) -> Self {
+        let kept = &bytes[..bytes.len().min(max_bytes)];
+        Self {
+            content: String::from_utf8_lossy(kept).into_owned(),
+            size: bytes.len(),
+            truncated: bytes.len() > max_bytes,
+        }
+    }
+}
+
+/// One recorded request and the response sent for it
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedExchange {
+    /// Sequence number, increasing across the process lifetime
+    pub id: u64,
+    /// When the request arrived (RFC 3339)
+    pub timestamp: String,
+    pub method: String,
+    pub uri: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: CapturedBody,
+    pub status: u16,
+    pub response_headers: Vec<(String, String)>,
+    /// `None` for streamed responses, whose body is never buffered
+    pub response_body: Option<CapturedBody>,
+    pub duration_ms: f64,
+}
+
+/// Request half of an exchange, kept in the context until the response is ready
+struct PendingRequest {
+    started: Instant,
+    timestamp: String,
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: CapturedBody,
+}
+
+/// Bounded ring buffer of captured exchanges
+pub struct CaptureBuffer {
+    entries: Mutex<VecDeque<CapturedExchange>>,
+    next_id: AtomicU64,
+}
+
+impl CaptureBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Append an exchange, dropping the oldest ones beyond `capacity`
+    fn push(&self, mut exchange: CapturedExchange, capacity: usize) {
+        exchange.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.push_back(exchange);
+        while entries.len() > capacity {
+            entries.pop_front();
+        }
+    }
+
+    /// Captured exchanges, newest first
+    pub fn entries(&self) -> Vec<CapturedExchange> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().rev().cloned().collect()
+    }
+
+    /// Find a captured exchange by id
+    pub fn get(&self, id: u64) -> Option<CapturedExchange> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().find(|exchange| exchange.id == id).cloned()
+    }
+
+    /// Number of exchanges currently held
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Check whether nothing has been captured
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every captured exchange
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Default for CaptureBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static CAPTURES: OnceLock<CaptureBuffer> = OnceLock::new();
+
+/// Get the global capture buffer
+pub fn global() -> &'static CaptureBuffer {
+    CAPTURES.get_or_init(CaptureBuffer::new)
+}
+
+/// Route handler listing captured exchanges as JSON, newest first
+///
+/// `?id=N` returns a single exchange (404 if it has been evicted).
+pub async fn endpoint(ctx: &mut Context) -> Result<()> {
+    let response = match ctx.query("id").map(str::parse::<u64>) {
+        Some(Ok(id)) => match global().get(id) {
+            Some(exchange) => Response::json(exchange)?,
+            None => Response::not_found(),
+        },
+        Some(Err(_)) => Response::bad_request(Some("Invalid capture id")),
+        None => Response::json(global().entries())?,
+    };
+    ctx.set_response(response);
+    Ok(())
+}
+
+/// Middleware recording requests and responses into the global capture buffer
+#[derive(Clone)]
+pub struct CaptureMiddleware {
+    enabled: bool,
+    /// Maximum number of exchanges kept
+    capacity: usize,
+    /// Bodies are cut after this many bytes
+    max_body_bytes: usize,
+    /// Lower-case names of headers whose values are never stored
+    redact_headers: HashSet<String>,
+    /// Path prefixes that are not captured
+    excluded_paths: Vec<String>,
+}
+
+impl CaptureMiddleware {
+    /// Capture every request, keeping the last 100 exchanges
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            capacity: 100,
+            max_body_bytes: 16 * 1024,
+            redact_headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            excluded_paths: Vec::new(),
+        }
+    }
+
+    /// Create capture middleware from the `[middleware.capture]` section
+    ///
+    /// Capturing stays disabled unless `enabled = true` is set, so the
+    /// middleware can be registered in every environment and switched on
+    /// with `RUSTF_MIDDLEWARE_CAPTURE_ENABLED=true` when needed.
+    pub fn from_config() -> Self {
+        use crate::configuration::CONF;
+
+        let mut middleware = Self::new();
+        middleware.enabled = CONF::get("middleware.capture.enabled").unwrap_or(false);
+        if let Some(capacity) = CONF::get("middleware.capture.capacity") {
+            middleware.capacity = capacity;
+        }
+        if let Some(max_body_bytes) = CONF::get("middleware.capture.max_body_bytes") {
+            middleware.max_body_bytes = max_body_bytes;
+        }
+        if let Some(headers) = CONF::get::<Vec<String>>("middleware.capture.redact_headers") {
+            middleware = middleware.redact_headers(headers.iter().map(String::as_str).collect());
+        }
+        if let Some(paths) = CONF::get::<Vec<String>>("middleware.capture.excluded_paths") {
+            middleware.excluded_paths = paths;
+        }
+        middleware
+    }
+
+    /// Keep at most `capacity` exchanges
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Cut stored bodies after `max_body_bytes` bytes
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Replace the list of redacted headers (case-insensitive)
+    pub fn redact_headers(mut self, headers: Vec<&str>) -> Self {
+        self.redact_headers = headers.into_iter().map(|h| h.to_lowercase()).collect();
+        self
+    }
+
+    /// Skip requests under one of these path prefixes, on a segment boundary
+    pub fn exclude_paths(mut self, paths: Vec<&str>) -> Self {
+        self.excluded_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Copy headers, replacing the values of redacted ones
+    fn redact<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a String, &'a String)>,
+    ) -> Vec<(String, String)> {
+        let mut redacted: Vec<(String, String)> = headers
+            .map(|(name, value)| {
+                if self.redact_headers.contains(&name.to_lowercase()) {
+                    (name.clone(), REDACTED.to_string())
+                } else {
+                    (name.clone(), value.clone())
+                }
+            })
+            .collect();
+        redacted.sort();
+        redacted
+    }
+}
+
+impl Default for CaptureMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for CaptureMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        let pending = PendingRequest {
+            started: Instant::now(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method: ctx.req.method.clone(),
+            uri: ctx.req.uri.clone(),
+            headers: self.redact(ctx.req.headers.iter()),
+            body: CapturedBody::new(ctx.req.body_as_string().as_bytes(), self.max_body_bytes),
+        };
+        ctx.set(PENDING_KEY, pending)?;
+        Ok(InboundAction::Capture)
+    }
+
+    fn name(&self) -> &'static str {
+        "capture"
+    }
+
+    fn priority(&self) -> i32 {
+        -980 // After logging and metrics, before anything that may reject the request
+    }
+
+    fn should_run(&self, ctx: &Context) -> bool {
+        self.enabled
+            && !self
+                .excluded_paths
+                .iter()
+                .any(|prefix| path_is_under(ctx.path(), prefix))
+    }
+}
+
+#[async_trait]
+impl OutboundMiddleware for CaptureMiddleware {
+    async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        let Some(pending) = ctx.get::<PendingRequest>(PENDING_KEY) else {
+            return Ok(());
+        };
+
+        let (status, response_headers, response_body) = match ctx.get_response() {
+            Some(response) => (
+                response.status.as_u16(),
+                self.redact(response.headers.iter().map(|(name, value)| (name, value))),
+                (!response.is_streaming())
+                    .then(|| CapturedBody::new(&response.body, self.max_body_bytes)),
+            ),
+            None => (500, Vec::new(), None),
+        };
+
+        let exchange = CapturedExchange {
+            id: 0,
+            timestamp: pending.timestamp.clone(),
+            method: pending.method.clone(),
+            uri: pending.uri.clone(),
+            request_headers: pending.headers.clone(),
+            request_body: pending.body.clone(),
+            status,
+            response_headers,
+            response_body,
+            duration_ms: pending.started.elapsed().as_secs_f64() * 1000.0,
+        };
+        global().push(exchange, self.capacity);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(uri: &str) -> CapturedExchange {
+        CapturedExchange {
+            id: 0,
+            timestamp: String::new(),
+            method: "GET".to_string(),
+            uri: uri.to_string(),
+            request_headers: Vec::new(),
+            request_body: CapturedBody::new(b"", 16),
+            status: 200,
+            response_headers: Vec::new(),
+            response_body: None,
+            duration_ms: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_capture_buffer_is_bounded_and_redacted() {
+        let buffer = CaptureBuffer::new();
+        for uri in ["/a", "/b", "/c"] {
+            buffer.push(exchange(uri), 2);
+        }
+        let entries = buffer.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].uri, "/c");
+        assert_eq!(entries[1].uri, "/b");
+        assert!(buffer.get(entries[1].id).is_some());
+
+        let body = CapturedBody::new(b"0123456789", 4);
+        assert_eq!(body.content, "0123");
+        assert_eq!(body.size, 10);
+        assert!(body.truncated);
+
+        let middleware = CaptureMiddleware::new().redact_headers(vec!["Authorization"]);
+        let name = |s: &str| s.to_string();
+        let headers = [
+            (name("authorization"), name("Bearer secret")),
+            (name("accept"), name("text/html")),
+        ];
+        let redacted = middleware.redact(headers.iter().map(|(k, v)| (k, v)));
+        assert_eq!(
+            redacted,
+            vec![
+                (name("accept"), name("text/html")),
+                (name("authorization"), name(REDACTED)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_excluded_paths_end_on_a_segment_boundary() {
+        let middleware = CaptureMiddleware::new().exclude_paths(vec!["/admin"]);
+        let views = std::sync::Arc::new(crate::views::ViewEngine::new());
+        let runs = |path: &str| {
+            let request = crate::http::Request::new("GET", path, "1.1");
+            middleware.should_run(&Context::new(request, views.clone()))
+        };
+
+        assert!(!runs("/admin"));
+        assert!(!runs("/admin/captures"));
+        assert!(runs("/administrator"));
+    }
+}
@@ -2,14 +2,35 @@
 //!
 //! This middleware handles CORS headers and preflight requests, demonstrating
 //! dual-phase middleware that can both modify responses and stop the chain for preflight.
+//!
+//! Different path prefixes can carry their own policy. The longest matching
+//! prefix wins, for preflights and regular requests alike:
+//!
+//! ```rust,ignore
+//! let cors = CorsMiddleware::new()
+//!     .group("/api/public", CorsConfig::default())
+//!     .group(
+//!         "/api/private",
+//!         CorsConfig {
+//!             allow_origins: vec!["https://app.example.com".to_string()],
+//!             allow_credentials: true,
+//!             ..CorsConfig::default()
+//!         },
+//!     )
+//!     .groups_only();
+//! ```
 
 use crate::context::Context;
 use crate::error::Result;
 use crate::middleware::{InboundAction, InboundMiddleware, OutboundMiddleware};
 use async_trait::async_trait;
+use serde::Deserialize;
 
 /// CORS middleware configuration
-#[derive(Clone)]
+///
+/// Missing fields take their default when deserialized.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct CorsConfig {
     pub allow_origin: String,
     /// Additional allowed origins (for multi-origin support)
@@ -43,6 +64,14 @@ impl Default for CorsConfig {
     }
 }
 
+/// A `[[middleware.cors.groups]]` entry
+#[derive(Deserialize)]
+struct CorsGroupConfig {
+    prefix: String,
+    #[serde(flatten)]
+    config: CorsConfig,
+}
+
 /// CORS middleware
 ///
 /// Handles CORS headers and preflight OPTIONS requests.
@@ -50,19 +79,53 @@ impl Default for CorsConfig {
 #[derive(Clone)]
 pub struct CorsMiddleware {
     config: CorsConfig,
+    /// Policies for path prefixes, longest prefix first
+    groups: Vec<(String, CorsConfig)>,
+    /// Leave requests outside every group alone
+    groups_only: bool,
 }
 
 impl CorsMiddleware {
     /// Create CORS middleware with default configuration
     pub fn new() -> Self {
-        Self {
-            config: CorsConfig::default(),
-        }
+        Self::with_config(CorsConfig::default())
     }
 
     /// Create CORS middleware with custom configuration
     pub fn with_config(config: CorsConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            groups: Vec::new(),
+            groups_only: false,
+        }
+    }
+
+    /// Use `config` for requests whose path is `prefix` or lies below it
+    ///
+    /// When several groups match, the longest prefix wins. Requests outside
+    /// every group use the middleware's own configuration.
+    pub fn group(mut self, prefix: &str, mut config: CorsConfig) -> Self {
+        if config.allow_credentials && config.allow_origin == "*" && config.allow_origins.is_empty()
+        {
+            log::warn!(
+                "CORS Security Warning: group '{}' allows credentials with a wildcard origin. \
+                Setting allow_origin to empty (will block all requests). Please specify explicit origins.",
+                prefix
+            );
+            config.allow_origin = String::new();
+        }
+
+        self.groups
+            .push((prefix.trim_end_matches('/').to_string(), config));
+        self.groups
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// Only send CORS headers for requests inside a group
+    pub fn groups_only(mut self) -> Self {
+        self.groups_only = true;
+        self
     }
 
     /// Builder method to set allowed origin
@@ -119,6 +182,18 @@ impl CorsMiddleware {
     /// max_age = 86400
     /// ```
     ///
+    /// Path prefixes with their own policy are listed as groups. Fields left
+    /// out of a group take the defaults above, not the top-level values:
+    ///
+    /// ```toml
+    /// [[middleware.cors.groups]]
+    /// prefix = "/api/private"
+    /// allow_origins = ["https://app.example.com"]
+    /// allow_credentials = true
+    /// ```
+    ///
+    /// Set `groups_only = true` to skip CORS for paths outside every group.
+    ///
     /// If configuration is not found, uses sensible defaults.
     pub fn from_config() -> Self {
         use crate::configuration::CONF;
@@ -173,9 +248,31 @@ impl CorsMiddleware {
             max_age: max_age.or(Some(86400)),
         };
 
-        Self::with_config(config)
+        let groups =
+            CONF::get::<Vec<CorsGroupConfig>>("middleware.cors.groups").unwrap_or_default();
+        let mut middleware = groups
+            .into_iter()
+            .fold(Self::with_config(config), |middleware, group| {
+                middleware.group(&group.prefix, group.config)
+            });
+        if CONF::get("middleware.cors.groups_only").unwrap_or(false) {
+            middleware = middleware.groups_only();
+        }
+        middleware
     }
 
+    /// The policy for `path`, if CORS applies to it
+    fn policy_for(&self, path: &str) -> Option<&CorsConfig> {
+        let group = self.groups.iter().find(|(prefix, _)| {
+            path.strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        });
+        match group {
+            Some((_, config)) => Some(config),
+            None if self.groups_only => None,
+            None => Some(&self.config),
+        }
+    }
 }
 
 impl Default for CorsMiddleware {
@@ -205,13 +302,21 @@ impl InboundMiddleware for CorsMiddleware {
     fn priority(&self) -> i32 {
         -600 // High priority (runs early, but after logging)
     }
+
+    fn should_run(&self, ctx: &Context) -> bool {
+        self.policy_for(ctx.path()).is_some()
+    }
 }
 
 #[async_trait]
 impl OutboundMiddleware for CorsMiddleware {
     async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        let Some(config) = self.policy_for(ctx.path()) else {
+            return Ok(());
+        };
+
         // Determine which origin to allow before getting mutable reference
-        let allowed_origin = self.determine_allowed_origin(ctx);
+        let allowed_origin = Self::determine_allowed_origin(config, ctx);
 
         if let Some(response) = ctx.res.as_mut() {
             // Add CORS headers to the response
@@ -221,35 +326,35 @@ impl OutboundMiddleware for CorsMiddleware {
             ));
 
             // Add Vary: Origin for dynamic origin handling (security best practice)
-            if !self.config.allow_origins.is_empty() || self.config.allow_credentials {
+            if !config.allow_origins.is_empty() || config.allow_credentials {
                 response.headers.push((
                     "Vary".to_string(),
                     "Origin".to_string(),
                 ));
             }
 
-            if !self.config.allow_methods.is_empty() {
-                let methods = self.config.allow_methods.join(", ");
+            if !config.allow_methods.is_empty() {
+                let methods = config.allow_methods.join(", ");
                 response
                     .headers
                     .push(("Access-Control-Allow-Methods".to_string(), methods));
             }
 
-            if !self.config.allow_headers.is_empty() {
-                let headers = self.config.allow_headers.join(", ");
+            if !config.allow_headers.is_empty() {
+                let headers = config.allow_headers.join(", ");
                 response
                     .headers
                     .push(("Access-Control-Allow-Headers".to_string(), headers));
             }
 
-            if self.config.allow_credentials {
+            if config.allow_credentials {
                 response.headers.push((
                     "Access-Control-Allow-Credentials".to_string(),
                     "true".to_string(),
                 ));
             }
 
-            if let Some(max_age) = self.config.max_age {
+            if let Some(max_age) = config.max_age {
                 response
                     .headers
                     .push(("Access-Control-Max-Age".to_string(), max_age.to_string()));
@@ -262,17 +367,17 @@ impl OutboundMiddleware for CorsMiddleware {
 
 impl CorsMiddleware {
     /// Determine which origin to allow based on request and configuration
-    fn determine_allowed_origin(&self, ctx: &Context) -> String {
+    fn determine_allowed_origin(config: &CorsConfig, ctx: &Context) -> String {
         // If wildcard is set and no additional origins, use wildcard
-        if self.config.allow_origin == "*" && self.config.allow_origins.is_empty() {
+        if config.allow_origin == "*" && config.allow_origins.is_empty() {
             return "*".to_string();
         }
 
         // If multiple origins configured, validate against request Origin header
-        if !self.config.allow_origins.is_empty() {
+        if !config.allow_origins.is_empty() {
             if let Some(request_origin) = ctx.req.headers.get("origin") {
                 // Check if request origin is in the allowed list
-                if self.config.allow_origins.iter().any(|o| o == request_origin) {
+                if config.allow_origins.iter().any(|o| o == request_origin) {
                     return request_origin.clone();
                 }
             }
@@ -281,7 +386,7 @@ impl CorsMiddleware {
         }
 
         // Single origin configured
-        self.config.allow_origin.clone()
+        config.allow_origin.clone()
     }
 }
 
@@ -545,4 +650,72 @@ mod tests {
             panic!("Expected response in context");
         }
     }
+
+    fn context_for(method: &str, uri: &str, origin: &str) -> Context {
+        let mut request = Request::default();
+        request.method = method.to_string();
+        request.uri = uri.to_string();
+        request
+            .headers
+            .insert("origin".to_string(), origin.to_string());
+
+        let views = Arc::new(ViewEngine::from_directory("views"));
+        Context::new(request, views)
+    }
+
+    fn response_header<'a>(ctx: &'a Context, name: &str) -> Option<&'a str> {
+        ctx.res
+            .as_ref()?
+            .headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[tokio::test]
+    async fn test_cors_groups_pick_longest_prefix() {
+        let middleware = CorsMiddleware::new()
+            .group("/api", CorsConfig::default())
+            .group(
+                "/api/private",
+                CorsConfig {
+                    allow_origins: vec!["https://app.example.com".to_string()],
+                    allow_credentials: true,
+                    ..CorsConfig::default()
+                },
+            )
+            .groups_only();
+
+        // Preflight for the private group reflects the allowed origin
+        let mut ctx = context_for("OPTIONS", "/api/private/orders", "https://app.example.com");
+        assert!(middleware.should_run(&ctx));
+        middleware.process_request(&mut ctx).await.unwrap();
+        middleware.process_response(&mut ctx).await.unwrap();
+        assert_eq!(
+            response_header(&ctx, "Access-Control-Allow-Origin"),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            response_header(&ctx, "Access-Control-Allow-Credentials"),
+            Some("true")
+        );
+
+        // The rest of /api is open
+        let mut ctx = context_for("GET", "/api/privateer?x=1", "https://other.example.com");
+        middleware.process_response(&mut ctx).await.unwrap();
+        assert_eq!(
+            response_header(&ctx, "Access-Control-Allow-Origin"),
+            Some("*")
+        );
+        assert_eq!(
+            response_header(&ctx, "Access-Control-Allow-Credentials"),
+            None
+        );
+
+        // Nothing outside the groups
+        let mut ctx = context_for("GET", "/account", "https://other.example.com");
+        assert!(!middleware.should_run(&ctx));
+        middleware.process_response(&mut ctx).await.unwrap();
+        assert_eq!(response_header(&ctx, "Access-Control-Allow-Origin"), None);
+    }
 }
@@ -0,0 +1,438 @@
+//! Degraded mode middleware for RustF
+//!
+//! Keeps the read-only parts of a site up while the database is unreachable.
+//! A background task pings the default database; after `failure_threshold`
+//! failed probes in a row the application enters degraded mode, and the first
+//! successful probe brings it back. While degraded:
+//!
+//! - write requests (anything but GET, HEAD and OPTIONS) receive a
+//!   `503 Service Unavailable` with a `Retry-After` header
+//! - GET and HEAD requests under `cache_paths` are answered with the last good
+//!   copy of the page, remembered while the database was up for the same
+//!   `Accept`, `Accept-Encoding` and `Accept-Language` values
+//! - other requests run as usual, so pages that never touch the database
+//!   keep working
+//!
+//! ```rust,ignore
+//! use rustf::middleware::builtin::DegradedModeMiddleware;
+//!
+//! let degraded = DegradedModeMiddleware::new()
+//!     .cache_paths(vec!["/", "/blog", "/products"])
+//!     .retry_after(30);
+//! let app = RustF::new().middleware_from(|registry| {
+//!     registry.register_dual("degraded", degraded);
+//! });
+//! ```
+//!
+//! `auto_load_with(&["degraded"])` registers it from the
+//! `[middleware.degraded]` configuration instead. The current mode is
+//! reported by the health check and by `DegradedModeMiddleware::is_active()`.
+
+use crate::cache::response::{ResponseCache, ResponseCacheConfig};
+use crate::context::Context;
+use crate::database::availability;
+use crate::db::DB;
+use crate::error::Result;
+use crate::http::{path_is_under, Response};
+use crate::middleware::{InboundAction, InboundMiddleware, OutboundMiddleware};
+use async_trait::async_trait;
+use hyper::StatusCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Whether the database probe task has been started
+static PROBE_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Middleware that serves stale pages and rejects writes while the database is down
+#[derive(Clone)]
+pub struct DegradedModeMiddleware {
+    /// Seconds between database probes
+    probe_interval: u64,
+    /// Consecutive failed probes before entering degraded mode
+    failure_threshold: u32,
+    /// Seconds advertised in the Retry-After header
+    retry_after: u64,
+    /// Message returned to rejected write requests
+    message: String,
+    /// Path prefixes whose GET responses are kept for degraded mode
+    cache_paths: Vec<String>,
+    /// Seconds a remembered page can be served for
+    cache_ttl: u64,
+    /// Path prefixes that always run normally
+    allowed_paths: Vec<String>,
+    /// Last good copies of the pages under `cache_paths`
+    cache: Arc<ResponseCache>,
+}
+
+impl DegradedModeMiddleware {
+    /// Create degraded mode middleware with defaults
+    ///
+    /// Probes every 5 seconds, degrades after 2 failures, advertises
+    /// Retry-After 30s and remembers pages for an hour. No pages are
+    /// remembered until `cache_paths` is set.
+    pub fn new() -> Self {
+        Self {
+            probe_interval: 5,
+            failure_threshold: 2,
+            retry_after: 30,
+            message: "The service is temporarily read-only. Please try again shortly.".to_string(),
+            cache_paths: Vec::new(),
+            cache_ttl: 3600,
+            allowed_paths: vec!["/health".to_string()],
+            cache: Arc::new(ResponseCache::with_config(ResponseCacheConfig {
+                cacheable_status_codes: vec![200],
+                vary_headers: vec![
+                    "Accept".to_string(),
+                    "Accept-Encoding".to_string(),
+                    "Accept-Language".to_string(),
+                ],
+                ..ResponseCacheConfig::default()
+            })),
+        }
+    }
+
+    /// Create degraded mode middleware from configuration file
+    ///
+    /// Reads configuration from `[middleware.degraded]` section in config.toml:
+    ///
+    /// ```toml
+    /// [middleware.degraded]
+    /// probe_interval = 5
+    /// failure_threshold = 2
+    /// retry_after = 30
+    /// message = "Read-only for a moment"
+    /// cache_paths = ["/", "/blog"]
+    /// cache_ttl = 3600
+    /// allowed_paths = ["/health"]
+    /// ```
+    pub fn from_config() -> Self {
+        use crate::configuration::CONF;
+
+        let mut middleware = Self::new();
+
+        if let Some(interval) = CONF::get("middleware.degraded.probe_interval") {
+            middleware.probe_interval = interval;
+        }
+        if let Some(threshold) = CONF::get("middleware.degraded.failure_threshold") {
+            middleware.failure_threshold = threshold;
+        }
+        if let Some(retry_after) = CONF::get("middleware.degraded.retry_after") {
+            middleware.retry_after = retry_after;
+        }
+        if let Some(message) = CONF::get_string("middleware.degraded.message") {
+            middleware.message = message;
+        }
+        if let Some(paths) = CONF::get::<Vec<String>>("middleware.degraded.cache_paths") {
+            middleware.cache_paths = paths;
+        }
+        if let Some(ttl) = CONF::get("middleware.degraded.cache_ttl") {
+            middleware.cache_ttl = ttl;
+        }
+        if let Some(paths) = CONF::get::<Vec<String>>("middleware.degraded.allowed_paths") {
+            middleware.allowed_paths = paths;
+        }
+
+        middleware
+    }
+
+    /// Set the number of seconds between database probes
+    pub fn probe_interval(mut self, seconds: u64) -> Self {
+        self.probe_interval = seconds.max(1);
+        self
+    }
+
+    /// Set how many probes in a row must fail before degrading
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Set the Retry-After value in seconds
+    pub fn retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = seconds;
+        self
+    }
+
+    /// Set the plain-text message returned to rejected writes
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Remember GET responses under these path prefixes for degraded mode
+    ///
+    /// Only list public pages: responses that set cookies, are marked
+    /// `private`/`no-store` or vary on other request headers are never
+    /// remembered, but anything else is served to every client while
+    /// degraded.
+    pub fn cache_paths(mut self, paths: Vec<&str>) -> Self {
+        self.cache_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Set how long a remembered page can be served, in seconds
+    pub fn cache_ttl(mut self, seconds: u64) -> Self {
+        self.cache_ttl = seconds;
+        self
+    }
+
+    /// Let requests to these path prefixes run normally while degraded
+    pub fn allow_paths(mut self, paths: Vec<&str>) -> Self {
+        self.allowed_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Check whether the application is in degraded mode
+    pub fn is_active() -> bool {
+        availability::is_degraded()
+    }
+
+    /// Enter or leave degraded mode
+    ///
+    /// The probe task does this on its own, so a mode set by hand only lasts
+    /// until the next probe.
+    pub fn set_active(active: bool) {
+        let previous = availability::set_degraded(active);
+        if previous != active {
+            if active {
+                log::error!("Database unreachable, entering degraded mode");
+            } else {
+                log::warn!("Database reachable again, leaving degraded mode");
+            }
+        }
+    }
+
+    /// Start the probe task once per process
+    fn ensure_probe(&self) {
+        if PROBE_STARTED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.probe_interval);
+        let threshold = self.failure_threshold;
+        tokio::spawn(async move {
+            let mut failures = 0u32;
+            loop {
+                tokio::time::sleep(interval).await;
+                // Ok(false) means no database is configured, which is not an outage
+                let reachable = !matches!(
+                    tokio::time::timeout(interval, DB::ping()).await,
+                    Err(_) | Ok(Err(_))
+                );
+                failures = next_failures(failures, reachable);
+                if reachable {
+                    Self::set_active(false);
+                } else if failures >= threshold {
+                    Self::set_active(true);
+                }
+            }
+        });
+    }
+
+    fn is_allowed(&self, path: &str) -> bool {
+        self.allowed_paths
+            .iter()
+            .any(|prefix| path_is_under(path, prefix))
+    }
+
+    fn is_cached_path(&self, path: &str) -> bool {
+        self.cache_paths
+            .iter()
+            .any(|prefix| path_is_under(path, prefix))
+    }
+
+    /// Whether the page requested by `ctx` is kept for degraded mode
+    fn remembers(&self, ctx: &Context) -> bool {
+        ctx.req.method.eq_ignore_ascii_case("GET") && self.is_cached_path(ctx.path())
+    }
+
+    /// The remembered copy of the page `ctx` requests, if it has not expired
+    ///
+    /// HEAD requests get the headers of the remembered GET response.
+    fn stale_response(&self, ctx: &Context) -> Option<Response> {
+        let key = self
+            .cache
+            .generate_cache_key("GET", &ctx.req.uri, &ctx.req.headers);
+        let entry = self.cache.get_response(&key).filter(|e| !e.is_expired())?;
+
+        let mut response = entry.to_response();
+        // Clients must not keep the stale copy once the site recovers
+        response
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("cache-control"));
+        if ctx.req.method.eq_ignore_ascii_case("HEAD") {
+            let length = std::mem::take(&mut response.body).len().to_string();
+            response.add_header("Content-Length", &length);
+        }
+        Some(
+            response
+                .with_header("Cache-Control", "no-cache")
+                .with_header("X-Degraded-Mode", "stale"),
+        )
+    }
+
+    fn unavailable(&self) -> Response {
+        Response::new(StatusCode::SERVICE_UNAVAILABLE)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_header("Retry-After", &self.retry_after.to_string())
+            .with_body(self.message.as_bytes().to_vec())
+    }
+}
+
+impl Default for DegradedModeMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Consecutive probe failures after a probe
+fn next_failures(failures: u32, reachable: bool) -> u32 {
+    if reachable {
+        0
+    } else {
+        failures.saturating_add(1)
+    }
+}
+
+fn is_read(method: &str) -> bool {
+    matches!(
+        method.to_ascii_uppercase().as_str(),
+        "GET" | "HEAD" | "OPTIONS"
+    )
+}
+
+#[async_trait]
+impl InboundMiddleware for DegradedModeMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        self.ensure_probe();
+
+        if !Self::is_active() || self.is_allowed(ctx.path()) {
+            return Ok(InboundAction::Continue);
+        }
+        if !is_read(&ctx.req.method) {
+            return Ok(InboundAction::Respond(self.unavailable()));
+        }
+        let method = &ctx.req.method;
+        if (method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD"))
+            && self.is_cached_path(ctx.path())
+        {
+            if let Some(response) = self.stale_response(ctx) {
+                return Ok(InboundAction::Respond(response));
+            }
+        }
+
+        Ok(InboundAction::Continue)
+    }
+
+    fn name(&self) -> &'static str {
+        "degraded"
+    }
+
+    fn priority(&self) -> i32 {
+        -940 // Right after maintenance mode
+    }
+}
+
+#[async_trait]
+impl OutboundMiddleware for DegradedModeMiddleware {
+    async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        if Self::is_active() || !self.remembers(ctx) {
+            return Ok(());
+        }
+        let Some(response) = ctx.get_response() else {
+            return Ok(());
+        };
+        if response.status != StatusCode::OK {
+            return Ok(());
+        }
+
+        // Responses that must not be shared are turned down by the cache
+        let key = self
+            .cache
+            .generate_cache_key("GET", &ctx.req.uri, &ctx.req.headers);
+        self.cache
+            .store_response(&key, response, Some(Duration::from_secs(self.cache_ttl)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matching() {
+        let middleware = DegradedModeMiddleware::new()
+            .cache_paths(vec!["/blog", "/docs/"])
+            .allow_paths(vec!["/health", "/admin"]);
+
+        assert!(middleware.is_cached_path("/blog"));
+        assert!(middleware.is_cached_path("/blog/first-post"));
+        assert!(!middleware.is_cached_path("/blogroll"));
+        assert!(middleware.is_cached_path("/docs/intro"));
+        assert!(!middleware.is_cached_path("/"));
+
+        assert!(middleware.is_allowed("/health"));
+        assert!(middleware.is_allowed("/admin/users"));
+        assert!(!middleware.is_allowed("/administrator"));
+        assert!(!middleware.is_allowed("/healthz"));
+        assert!(!middleware.is_allowed("/blog"));
+    }
+
+    #[test]
+    fn test_failures_reset_on_success() {
+        let failures = next_failures(next_failures(0, false), false);
+        assert_eq!(failures, 2);
+        assert_eq!(next_failures(failures, true), 0);
+
+        assert!(is_read("get"));
+        assert!(is_read("OPTIONS"));
+        assert!(!is_read("POST"));
+        assert!(!is_read("DELETE"));
+    }
+
+    fn request(method: &str, uri: &str, accept: &str) -> Context {
+        let mut request = crate::http::Request::new(method, uri, "HTTP/1.1");
+        request
+            .headers
+            .insert("accept".to_string(), accept.to_string());
+        Context::new(request, Arc::new(crate::views::ViewEngine::new()))
+    }
+
+    #[test]
+    fn test_stale_copies() {
+        let middleware = DegradedModeMiddleware::new().cache_paths(vec!["/"]);
+        let html = request("GET", "/?page=2", "text/html");
+        let key = middleware
+            .cache
+            .generate_cache_key("GET", "/?page=2", &html.req.headers);
+        let page = Response::new(StatusCode::OK)
+            .with_header("Content-Type", "text/html")
+            .with_body(b"<p>page 2</p>".to_vec());
+        middleware.cache.store_response(&key, &page, None).unwrap();
+
+        let stale = middleware.stale_response(&html).unwrap();
+        assert_eq!(stale.body, b"<p>page 2</p>");
+        assert!(stale
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Cache-Control" && value == "no-cache"));
+        assert!(middleware
+            .stale_response(&request("GET", "/?page=3", "text/html"))
+            .is_none());
+
+        // The copy is only served for the Accept it was rendered for
+        assert!(middleware
+            .stale_response(&request("GET", "/?page=2", "application/json"))
+            .is_none());
+
+        let head = middleware
+            .stale_response(&request("HEAD", "/?page=2", "text/html"))
+            .unwrap();
+        assert!(head.body.is_empty());
+        assert!(head
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Content-Length" && value == "13"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Maintenance mode middleware for RustF
+//!
+//! When maintenance mode is on, every request receives a `503 Service Unavailable`
+//! with a `Retry-After` header, except requests from allowlisted IPs or paths
+//! (health checks, admin endpoints). The flag is global and can be flipped at
+//! runtime without redeploying:
+//!
+//! ```rust,ignore
+//! use rustf::middleware::builtin::MaintenanceMiddleware;
+//!
+//! // From an admin controller
+//! MaintenanceMiddleware::enable();
+//! MaintenanceMiddleware::disable();
+//! ```
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::{path_is_under, Response};
+use crate::middleware::{InboundAction, InboundMiddleware};
+use async_trait::async_trait;
+use hyper::StatusCode;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Global maintenance flag shared by all middleware instances
+static MAINTENANCE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Middleware that returns 503 for all non-allowlisted requests while maintenance mode is on
+#[derive(Clone)]
+pub struct MaintenanceMiddleware {
+    /// Seconds advertised in the Retry-After header
+    retry_after: u64,
+    /// Optional view rendered as the maintenance page
+    view: Option<String>,
+    /// Message used when no view is configured
+    message: String,
+    /// Client IPs that bypass maintenance mode
+    allowed_ips: Vec<String>,
+    /// Path prefixes that bypass maintenance mode
+    allowed_paths: Vec<String>,
+}
+
+impl MaintenanceMiddleware {
+    /// Create maintenance middleware with defaults (Retry-After 300s, /health allowed)
+    pub fn new() -> Self {
+        Self {
+            retry_after: 300,
+            view: None,
+            message: "Service temporarily unavailable for maintenance. Please try again later."
+                .to_string(),
+            allowed_ips: Vec::new(),
+            allowed_paths: vec!["/health".to_string()],
+        }
+    }
+
+    /// Create maintenance middleware from configuration file
+    ///
+    /// Reads configuration from `[middleware.maintenance]` section in config.toml:
+    ///
+    /// ```toml
+    /// [middleware.maintenance]
+    /// enabled = false
+    /// retry_after = 300
+    /// view = "maintenance"
+    /// message = "Back soon!"
+    /// allowed_ips = ["10.0.0.5"]
+    /// allowed_paths = ["/health", "/admin/maintenance"]
+    /// ```
+    ///
+    /// `enabled` sets the initial state of the global flag.
+    pub fn from_config() -> Self {
+        use crate::configuration::CONF;
+
+        let mut middleware = Self::new();
+
+        if let Some(retry_after) = CONF::get("middleware.maintenance.retry_after") {
+            middleware.retry_after = retry_after;
+        }
+        if let Some(view) = CONF::get_string("middleware.maintenance.view") {
+            middleware.view = Some(view);
+        }
+        if let Some(message) = CONF::get_string("middleware.maintenance.message") {
+            middleware.message = message;
+        }
+        if let Some(ips) = CONF::get::<Vec<String>>("middleware.maintenance.allowed_ips") {
+            middleware.allowed_ips = ips;
+        }
+        if let Some(paths) = CONF::get::<Vec<String>>("middleware.maintenance.allowed_paths") {
+            middleware.allowed_paths = paths;
+        }
+        if let Some(enabled) = CONF::get_bool("middleware.maintenance.enabled") {
+            Self::set_enabled(enabled);
+        }
+
+        middleware
+    }
+
+    /// Set the Retry-After value in seconds
+    pub fn retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = seconds;
+        self
+    }
+
+    /// Render this view as the maintenance page
+    pub fn view(mut self, view: impl Into<String>) -> Self {
+        self.view = Some(view.into());
+        self
+    }
+
+    /// Set the plain-text message used when no view is configured
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Allow requests from these IPs during maintenance
+    pub fn allow_ips(mut self, ips: Vec<&str>) -> Self {
+        self.allowed_ips = ips.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Allow requests to these path prefixes during maintenance
+    pub fn allow_paths(mut self, paths: Vec<&str>) -> Self {
+        self.allowed_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Turn maintenance mode on
+    pub fn enable() {
+        Self::set_enabled(true);
+    }
+
+    /// Turn maintenance mode off
+    pub fn disable() {
+        Self::set_enabled(false);
+    }
+
+    /// Set maintenance mode on or off
+    pub fn set_enabled(enabled: bool) {
+        let previous = MAINTENANCE_MODE.swap(enabled, Ordering::SeqCst);
+        if previous != enabled {
+            log::warn!(
+                "Maintenance mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+    }
+
+    /// Check whether maintenance mode is on
+    pub fn is_enabled() -> bool {
+        MAINTENANCE_MODE.load(Ordering::SeqCst)
+    }
+
+    /// Check if the request bypasses maintenance mode
+    fn is_allowed(&self, ip: &str, path: &str) -> bool {
+        self.allowed_ips.iter().any(|allowed| allowed == ip)
+            || self
+                .allowed_paths
+                .iter()
+                .any(|allowed| path_is_under(path, allowed))
+    }
+}
+
+impl Default for MaintenanceMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for MaintenanceMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        if !Self::is_enabled() || self.is_allowed(&ctx.ip(), ctx.path()) {
+            return Ok(InboundAction::Continue);
+        }
+
+        let rendered = match &self.view {
+            Some(view) => match ctx.view(view, json!({ "retry_after": self.retry_after })) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::error!("Failed to render maintenance view '{}': {}", view, e);
+                    false
+                }
+            },
+            None => false,
+        };
+
+        if !rendered {
+            ctx.set_response(
+                Response::new(StatusCode::SERVICE_UNAVAILABLE)
+                    .with_header("Content-Type", "text/plain; charset=utf-8")
+                    .with_body(self.message.as_bytes().to_vec()),
+            );
+        }
+
+        ctx.status(StatusCode::SERVICE_UNAVAILABLE);
+        ctx.add_header("Retry-After", self.retry_after.to_string());
+
+        Ok(InboundAction::Stop)
+    }
+
+    fn name(&self) -> &'static str {
+        "maintenance"
+    }
+
+    fn priority(&self) -> i32 {
+        -950 // Run right after logging, before any other processing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        let middleware = MaintenanceMiddleware::new()
+            .allow_ips(vec!["10.0.0.5"])
+            .allow_paths(vec!["/health", "/admin/maintenance"]);
+
+        assert!(middleware.is_allowed("10.0.0.5", "/"));
+        assert!(middleware.is_allowed("1.2.3.4", "/health"));
+        assert!(middleware.is_allowed("1.2.3.4", "/admin/maintenance/off"));
+        assert!(!middleware.is_allowed("1.2.3.4", "/users"));
+        assert!(!middleware.is_allowed("1.2.3.4", "/healthz"));
+        assert!(!middleware.is_allowed("1.2.3.4", "/admin/maintenance-log"));
+    }
+
+    #[test]
+    fn test_toggle() {
+        MaintenanceMiddleware::enable();
+        assert!(MaintenanceMiddleware::is_enabled());
+        MaintenanceMiddleware::disable();
+        assert!(!MaintenanceMiddleware::is_enabled());
+    }
+}
@@ -0,0 +1,78 @@
+//! Request metrics middleware for RustF
+//!
+//! Records the count and latency of every request into the global
+//! `rustf::metrics` registry, labelled by method, matched route pattern and
+//! response status. Expose them with the `rustf::metrics::endpoint` handler.
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::path_is_under;
+use crate::metrics::{self, UNMATCHED_ROUTE};
+use crate::middleware::{InboundAction, InboundMiddleware, OutboundMiddleware};
+use async_trait::async_trait;
+use std::time::Instant;
+
+/// Context key holding the request start time
+const START_KEY: &str = "metrics_start_time";
+
+/// Middleware recording request counts and durations
+#[derive(Clone, Default)]
+pub struct MetricsMiddleware {
+    /// Path prefixes that are not recorded (e.g. the scrape endpoint itself)
+    excluded_paths: Vec<String>,
+}
+
+impl MetricsMiddleware {
+    /// Create metrics middleware recording every request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip recording requests under one of these path prefixes, on a segment boundary
+    pub fn exclude_paths(mut self, paths: Vec<&str>) -> Self {
+        self.excluded_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for MetricsMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        ctx.set(START_KEY, Instant::now())?;
+        Ok(InboundAction::Capture)
+    }
+
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn priority(&self) -> i32 {
+        -990 // Right after logging so the measured time covers the whole chain
+    }
+
+    fn should_run(&self, ctx: &Context) -> bool {
+        !self
+            .excluded_paths
+            .iter()
+            .any(|prefix| path_is_under(ctx.path(), prefix))
+    }
+}
+
+#[async_trait]
+impl OutboundMiddleware for MetricsMiddleware {
+    async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        let Some(start) = ctx.get::<Instant>(START_KEY) else {
+            return Ok(());
+        };
+        let duration = start.elapsed();
+
+        let status = ctx
+            .get_response()
+            .map(|response| response.status.as_u16())
+            .unwrap_or(500);
+        let route = ctx.req.route.as_deref().unwrap_or(UNMATCHED_ROUTE);
+
+        metrics::global().record_request(&ctx.req.method, route, status, duration);
+        Ok(())
+    }
+}
@@ -3,21 +3,39 @@
 //! This module provides common middleware that are frequently needed in web applications.
 //! These serve as examples for third-party middleware authors and provide immediate utility.
 
+pub mod basic_auth;
+pub mod capture;
 pub mod cors;
 pub mod csp;
+pub mod degraded;
 pub mod logging;
+pub mod maintenance;
+pub mod metrics;
 pub mod rate_limit;
 pub mod security_headers;
 pub mod session;
+pub mod timeout;
+#[cfg(feature = "otel")]
+pub mod tracing;
 pub mod validation;
 
 // Re-export middleware for convenience
+pub use basic_auth::BasicAuthMiddleware;
+pub use capture::CaptureMiddleware;
 pub use cors::{CorsConfig, CorsMiddleware};
 pub use csp::{CspConfig, CspMiddleware};
+pub use degraded::DegradedModeMiddleware;
 pub use logging::LoggingMiddleware;
-pub use rate_limit::RateLimitMiddleware;
+pub use maintenance::MaintenanceMiddleware;
+pub use metrics::MetricsMiddleware;
+pub use rate_limit::{
+    MemoryRateLimitStore, RateLimitHit, RateLimitMiddleware, RateLimitStore, RedisRateLimitStore,
+};
 pub use security_headers::SecurityHeadersMiddleware;
 pub use session::SessionMiddleware;
+pub use timeout::TimeoutMiddleware;
+#[cfg(feature = "otel")]
+pub use tracing::TracingMiddleware;
 pub use validation::{ValidationConfig, ValidationMiddleware};
 
 // Note: AuthMiddleware can be created using the session middleware
@@ -2,6 +2,11 @@
 //!
 //! This middleware provides IP-based rate limiting to protect against abuse
 //! and denial of service attacks.
+//!
+//! Request counts live in a [`RateLimitStore`]. The default
+//! [`MemoryRateLimitStore`] counts per process, so each instance behind a
+//! load balancer enforces its own limit; [`RedisRateLimitStore`] shares the
+//! counts between all instances.
 
 use crate::context::Context;
 use crate::error::Result;
@@ -9,10 +14,31 @@ use crate::http::Response;
 use crate::middleware::{InboundAction, InboundMiddleware};
 use async_trait::async_trait;
 use dashmap::DashMap;
+use deadpool_redis::{Config, Pool, Runtime};
 use serde_json::json;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Count recorded for a client in the current window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitHit {
+    /// Requests made in the current window, including this one
+    pub count: u32,
+    /// Seconds until the window resets
+    pub reset_in: u64,
+}
+
+/// Storage for rate limit counters
+///
+/// `hit` records one request for `key` and returns the count for the fixed
+/// window of `window_seconds` it falls into. Implementations must count
+/// atomically so concurrent requests are never lost.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Record a request and return the count in the current window
+    async fn hit(&self, key: &str, window_seconds: u64) -> Result<RateLimitHit>;
+}
+
 /// Rate limiting entry for tracking request counts
 #[derive(Clone)]
 struct RateLimitEntry {
@@ -20,6 +46,127 @@ struct RateLimitEntry {
     window_start: u64,
 }
 
+/// In-process counters; the default store
+#[derive(Clone, Default)]
+pub struct MemoryRateLimitStore {
+    entries: Arc<DashMap<String, RateLimitEntry>>,
+}
+
+impl MemoryRateLimitStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove entries older than two windows
+    fn cleanup_old_entries(&self, current_time: u64, window_seconds: u64) {
+        let cutoff = current_time.saturating_sub(window_seconds * 2);
+        self.entries.retain(|_, entry| entry.window_start > cutoff);
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn hit(&self, key: &str, window_seconds: u64) -> Result<RateLimitHit> {
+        let current_time = now();
+
+        // Occasionally clean up old entries (1% chance)
+        if rand::random::<f32>() < 0.01 {
+            self.cleanup_old_entries(current_time, window_seconds);
+        }
+
+        let mut entry = self
+            .entries
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitEntry {
+                count: 0,
+                window_start: current_time,
+            });
+
+        // Reset window if expired
+        if current_time - entry.window_start >= window_seconds {
+            entry.count = 0;
+            entry.window_start = current_time;
+        }
+        entry.count = entry.count.saturating_add(1);
+
+        Ok(RateLimitHit {
+            count: entry.count,
+            reset_in: window_seconds - (current_time - entry.window_start),
+        })
+    }
+}
+
+/// Counters shared through Redis
+///
+/// Each client key is incremented and given its expiry in one Lua script,
+/// so the window starts with the first request and concurrent requests
+/// from different instances are all counted.
+#[derive(Clone)]
+pub struct RedisRateLimitStore {
+    pool: Pool,
+    prefix: String,
+}
+
+/// INCR the key, start its window on the first hit, return count and TTL
+const HIT_SCRIPT: &str = r"
+local count = redis.call('INCR', KEYS[1])
+local ttl = redis.call('TTL', KEYS[1])
+if ttl < 0 then
+    redis.call('EXPIRE', KEYS[1], ARGV[1])
+    ttl = tonumber(ARGV[1])
+end
+return {count, ttl}
+";
+
+impl RedisRateLimitStore {
+    /// Create a store for `redis_url` with the prefix `rustf:ratelimit:`
+    ///
+    /// Connections are opened on first use.
+    pub fn new(redis_url: &str) -> Result<Self> {
+        let pool = Config::from_url(redis_url).create_pool(Some(Runtime::Tokio1))?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Create a store on an existing connection pool
+    pub fn from_pool(pool: Pool) -> Self {
+        Self {
+            pool,
+            prefix: "rustf:ratelimit:".to_string(),
+        }
+    }
+
+    /// Set the prefix of the counter keys
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn hit(&self, key: &str, window_seconds: u64) -> Result<RateLimitHit> {
+        let mut conn = self.pool.get().await?;
+        let (count, ttl): (u32, i64) = redis::Script::new(HIT_SCRIPT)
+            .key(format!("{}{}", self.prefix, key))
+            .arg(window_seconds.max(1))
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(RateLimitHit {
+            count,
+            reset_in: ttl.max(0) as u64,
+        })
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Advanced rate limiting middleware with configurable windows and limits
 #[derive(Clone)]
 pub struct RateLimitMiddleware {
@@ -27,8 +174,8 @@ pub struct RateLimitMiddleware {
     max_requests: u32,
     /// Time window in seconds
     window_seconds: u64,
-    /// Storage for rate limit entries
-    storage: Arc<DashMap<String, RateLimitEntry>>,
+    /// Storage for request counters
+    store: Arc<dyn RateLimitStore>,
     /// Paths to exclude from rate limiting
     excluded_paths: Vec<String>,
     /// Whether to use X-Forwarded-For header
@@ -37,11 +184,28 @@ pub struct RateLimitMiddleware {
 
 impl RateLimitMiddleware {
     /// Create new rate limiter with specified requests per window
+    ///
+    /// Counts are kept in process memory; use [`with_store`](Self::with_store)
+    /// to share them between instances.
     pub fn new(max_requests: u32, window_seconds: u64) -> Self {
+        Self::with_store(max_requests, window_seconds, MemoryRateLimitStore::new())
+    }
+
+    /// Create rate limiter counting requests in `store`
+    ///
+    /// ```rust,ignore
+    /// let store = RedisRateLimitStore::new("redis://127.0.0.1:6379")?;
+    /// let limiter = RateLimitMiddleware::with_store(100, 60, store);
+    /// ```
+    pub fn with_store(
+        max_requests: u32,
+        window_seconds: u64,
+        store: impl RateLimitStore + 'static,
+    ) -> Self {
         Self {
             max_requests,
             window_seconds,
-            storage: Arc::new(DashMap::new()),
+            store: Arc::new(store),
             excluded_paths: vec![
                 "/health".to_string(),
                 "/metrics".to_string(),
@@ -88,9 +252,12 @@ impl RateLimitMiddleware {
     /// window_seconds = 60
     /// excluded_paths = ["/health", "/metrics"]
     /// trust_proxy = true
+    /// redis_url = "redis://127.0.0.1:6379"   # optional, shares counts between instances
+    /// redis_prefix = "rustf:ratelimit:"
     /// ```
     ///
     /// If configuration is not found, uses sensible defaults (100 req/min).
+    /// An invalid `redis_url` falls back to in-memory counting.
     pub fn from_config() -> Self {
         use crate::configuration::CONF;
 
@@ -98,7 +265,23 @@ impl RateLimitMiddleware {
 
         let window_seconds = CONF::get("middleware.rate_limit.window_seconds").unwrap_or(60);
 
-        let mut middleware = Self::new(max_requests, window_seconds);
+        let redis_url = CONF::get::<String>("middleware.rate_limit.redis_url");
+        let mut middleware = match redis_url.map(|url| RedisRateLimitStore::new(&url)) {
+            Some(Ok(mut store)) => {
+                if let Some(prefix) = CONF::get::<String>("middleware.rate_limit.redis_prefix") {
+                    store = store.prefix(&prefix);
+                }
+                Self::with_store(max_requests, window_seconds, store)
+            }
+            Some(Err(e)) => {
+                log::error!(
+                    "Rate limit Redis store unavailable, counting in memory: {}",
+                    e
+                );
+                Self::new(max_requests, window_seconds)
+            }
+            None => Self::new(max_requests, window_seconds),
+        };
 
         // Optional: excluded paths
         if let Some(excluded) = CONF::get::<Vec<String>>("middleware.rate_limit.excluded_paths") {
@@ -135,19 +318,6 @@ impl RateLimitMiddleware {
             .iter()
             .any(|excluded| path == excluded || path.starts_with(excluded))
     }
-
-    /// Clean up old entries periodically
-    fn cleanup_old_entries(&self) {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        // Remove entries older than 2x the window
-        let cutoff = current_time - (self.window_seconds * 2);
-
-        self.storage.retain(|_, entry| entry.window_start > cutoff);
-    }
 }
 
 #[async_trait]
@@ -159,45 +329,27 @@ impl InboundMiddleware for RateLimitMiddleware {
         }
 
         let client_id = self.get_client_id(ctx);
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        // Occasionally clean up old entries (1% chance)
-        if rand::random::<f32>() < 0.01 {
-            self.cleanup_old_entries();
-        }
-
-        // Check and update rate limit
-        let mut entry = self
-            .storage
-            .entry(client_id.clone())
-            .or_insert_with(|| RateLimitEntry {
-                count: 0,
-                window_start: current_time,
-            });
-
-        // Reset window if expired
-        if current_time - entry.window_start >= self.window_seconds {
-            entry.count = 0;
-            entry.window_start = current_time;
-        }
 
-        // Increment request count
-        entry.count += 1;
+        // A failing store must not take the site down with it
+        let hit = match self.store.hit(&client_id, self.window_seconds).await {
+            Ok(hit) => hit,
+            Err(e) => {
+                log::warn!("Rate limit store failed, allowing request: {}", e);
+                return Ok(InboundAction::Continue);
+            }
+        };
+        let reset_at = now() + hit.reset_in;
 
         // Check if limit exceeded
-        if entry.count > self.max_requests {
+        if hit.count > self.max_requests {
             log::warn!(
                 "Rate limit exceeded for client: {} ({}/{})",
                 client_id,
-                entry.count,
+                hit.count,
                 self.max_requests
             );
 
-            // Calculate retry after
-            let retry_after = self.window_seconds - (current_time - entry.window_start);
+            let retry_after = hit.reset_in;
 
             // Set rate limit error response using context
             ctx.set_response(Response::new(hyper::StatusCode::TOO_MANY_REQUESTS)
@@ -205,7 +357,7 @@ impl InboundMiddleware for RateLimitMiddleware {
                 .with_header("Retry-After", &retry_after.to_string())
                 .with_header("X-RateLimit-Limit", &self.max_requests.to_string())
                 .with_header("X-RateLimit-Remaining", "0")
-                .with_header("X-RateLimit-Reset", &(entry.window_start + self.window_seconds).to_string())
+                .with_header("X-RateLimit-Reset", &reset_at.to_string())
                 .with_body(json!({
                     "error": "rate_limit_exceeded",
                     "message": format!("Rate limit exceeded. Maximum {} requests per {} seconds", 
@@ -217,10 +369,10 @@ impl InboundMiddleware for RateLimitMiddleware {
         }
 
         // Add rate limit headers to context for response
-        let remaining = self.max_requests - entry.count;
+        let remaining = self.max_requests - hit.count;
         let _ = ctx.set("rate_limit_limit", self.max_requests);
         let _ = ctx.set("rate_limit_remaining", remaining);
-        let _ = ctx.set("rate_limit_reset", entry.window_start + self.window_seconds);
+        let _ = ctx.set("rate_limit_reset", reset_at);
 
         Ok(InboundAction::Continue)
     }
@@ -251,6 +403,21 @@ mod tests {
         assert_eq!(auth_limiter.max_requests, 5);
     }
 
+    #[tokio::test]
+    async fn test_memory_store_counts_per_window() {
+        let store = MemoryRateLimitStore::new();
+
+        let first = store.hit("1.2.3.4", 60).await.unwrap();
+        assert_eq!(first.count, 1);
+        assert!(first.reset_in <= 60 && first.reset_in > 0);
+        assert_eq!(store.hit("1.2.3.4", 60).await.unwrap().count, 2);
+        assert_eq!(store.hit("5.6.7.8", 60).await.unwrap().count, 1);
+
+        // A zero-length window has always expired
+        assert_eq!(store.hit("9.9.9.9", 0).await.unwrap().count, 1);
+        assert_eq!(store.hit("9.9.9.9", 0).await.unwrap().count, 1);
+    }
+
     #[test]
     fn test_excluded_paths() {
         let limiter = RateLimitMiddleware::default().exclude_paths(vec!["/public", "/static"]);
@@ -70,13 +70,11 @@ impl OutboundMiddleware for SecurityHeadersMiddleware {
     async fn process_response(&self, ctx: &mut Context) -> Result<()> {
         if self.enabled {
             if let Some(response) = ctx.res.as_mut() {
-                // We need to clone and replace the response since apply_to_response returns a new one
-                // This is not ideal but works with the current SecurityHeaders API
-                let current_response =
-                    Response::new(response.status).with_body(response.body.clone());
+                // apply_to_response returns a new response; only its headers are
+                // kept, so the body (buffered or streamed) is left untouched
+                let mut temp_response = Response::new(response.status);
 
                 // Copy existing headers
-                let mut temp_response = current_response;
                 for (key, value) in &response.headers {
                     temp_response.headers.push((key.clone(), value.clone()));
                 }
@@ -17,10 +17,10 @@ pub struct SessionMiddleware {
 impl SessionMiddleware {
     /// Create new session middleware with custom configuration
     /// Uses default memory storage for backwards compatibility
-    pub fn new(config: SessionConfig) -> Self {
-        Self {
-            manager: SessionManager::with_default_storage(config),
-        }
+    pub fn new(config: SessionConfig) -> Result<Self> {
+        Ok(Self {
+            manager: SessionManager::with_default_storage(config)?,
+        })
     }
 
     /// Create with storage from configuration (async)
@@ -40,7 +40,7 @@ impl SessionMiddleware {
     }
 
     /// Create with default configuration
-    pub fn default() -> Self {
+    pub fn default() -> Result<Self> {
         Self::new(SessionConfig::default())
     }
 
@@ -48,10 +48,10 @@ impl SessionMiddleware {
     pub fn with_storage(
         storage: Arc<dyn crate::session::SessionStorage>,
         config: SessionConfig,
-    ) -> Self {
-        Self {
-            manager: SessionManager::new(storage, config),
-        }
+    ) -> Result<Self> {
+        Ok(Self {
+            manager: SessionManager::new(storage, config)?,
+        })
     }
 
     /// Check if route is exempt from session handling
@@ -171,7 +171,7 @@ impl OutboundMiddleware for SessionMiddleware {
                 self.manager.config.save_strategy,
                 SaveStrategy::EndOfRequest
             ) {
-                self.manager.force_save(session).await?;
+                self.manager.save_at_end_of_request(session).await?;
             } else if matches!(self.manager.config.save_strategy, SaveStrategy::Immediate) {
                 // For immediate strategy, save if dirty
                 if session.is_dirty() {
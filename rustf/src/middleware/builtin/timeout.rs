@@ -0,0 +1,123 @@
+//! Request timeout middleware for RustF
+//!
+//! Gives up on route handlers that run longer than a configured duration and
+//! answers `503 Service Unavailable` (or `504 Gateway Timeout`) instead, so a
+//! slow downstream call cannot hold a request forever. The middleware only
+//! sets the deadline on the context; the application races the handler
+//! against it and renders the error page for the status, or the JSON error
+//! envelope for API requests. Outbound middleware still runs on that response.
+//!
+//! ```rust,ignore
+//! use rustf::middleware::builtin::TimeoutMiddleware;
+//!
+//! let app = RustF::new().middleware_from(|registry| {
+//!     registry.register_inbound("timeout", TimeoutMiddleware::new(Duration::from_secs(30)));
+//! });
+//! ```
+//!
+//! When the deadline passes, the handler future is dropped at the `.await`
+//! it was waiting on, so handlers must be cancellation-safe: work done so far
+//! is not rolled back (use a transaction for writes that must happen
+//! together), and tasks started with `tokio::spawn` keep running. Only the
+//! handler is timed; inbound middleware and the sending of streamed bodies
+//! are not.
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::middleware::{InboundAction, InboundMiddleware};
+use async_trait::async_trait;
+use hyper::StatusCode;
+use std::time::Duration;
+
+/// Middleware that bounds how long route handlers may run
+#[derive(Clone)]
+pub struct TimeoutMiddleware {
+    /// Time a handler has to complete
+    timeout: Duration,
+    /// Status answered when it does not
+    status: StatusCode,
+}
+
+impl TimeoutMiddleware {
+    /// Create timeout middleware answering 503 after `timeout`
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            status: StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Create timeout middleware from configuration file
+    ///
+    /// Reads configuration from `[middleware.timeout]` section in config.toml:
+    ///
+    /// ```toml
+    /// [middleware.timeout]
+    /// seconds = 30
+    /// status = 504
+    /// ```
+    ///
+    /// Defaults to 30 seconds and 503.
+    pub fn from_config() -> Self {
+        use crate::configuration::CONF;
+
+        let mut middleware = Self::new(Duration::from_secs(
+            CONF::get("middleware.timeout.seconds").unwrap_or(30),
+        ));
+
+        if let Some(status) = CONF::get::<u16>("middleware.timeout.status") {
+            match StatusCode::from_u16(status) {
+                Ok(status) => middleware.status = status,
+                Err(_) => log::warn!("Ignoring invalid middleware.timeout.status {}", status),
+            }
+        }
+
+        middleware
+    }
+
+    /// Set the status answered when a handler times out (503 by default)
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for TimeoutMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        ctx.set_handler_timeout(self.timeout, self.status);
+        Ok(InboundAction::Continue)
+    }
+
+    fn name(&self) -> &'static str {
+        "timeout"
+    }
+
+    fn priority(&self) -> i32 {
+        -850 // Only sets the deadline, which applies to the handler wherever this runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Request;
+    use crate::views::ViewEngine;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_sets_handler_timeout() {
+        let request = Request::new("GET", "/reports", "HTTP/1.1");
+        let mut ctx = Context::new(request, Arc::new(ViewEngine::new()));
+        assert!(ctx.handler_timeout().is_none());
+
+        let middleware =
+            TimeoutMiddleware::new(Duration::from_secs(5)).status(StatusCode::GATEWAY_TIMEOUT);
+        middleware.process_request(&mut ctx).await.unwrap();
+
+        assert_eq!(
+            ctx.handler_timeout(),
+            Some((Duration::from_secs(5), StatusCode::GATEWAY_TIMEOUT))
+        );
+    }
+}
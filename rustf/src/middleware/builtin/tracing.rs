@@ -0,0 +1,107 @@
+//! OpenTelemetry request tracing middleware for RustF
+//!
+//! Opens a server span when a request comes in and closes it once the
+//! response is ready, recording method, matched route pattern, status and
+//! duration. Requires the `otel` feature; see `rustf::telemetry` for setup.
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::http::path_is_under;
+use crate::middleware::{InboundAction, InboundMiddleware, OutboundMiddleware};
+use crate::telemetry;
+use async_trait::async_trait;
+use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::KeyValue;
+use std::time::Instant;
+
+/// Context key holding the request span and its start time
+const SPAN_KEY: &str = "otel_request_span";
+
+/// Middleware creating an OpenTelemetry span per request
+#[derive(Clone, Default)]
+pub struct TracingMiddleware {
+    /// Path prefixes that are not traced (e.g. health checks)
+    excluded_paths: Vec<String>,
+}
+
+impl TracingMiddleware {
+    /// Create tracing middleware tracing every request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip tracing requests under one of these path prefixes, on a segment boundary
+    pub fn exclude_paths(mut self, paths: Vec<&str>) -> Self {
+        self.excluded_paths = paths.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+#[async_trait]
+impl InboundMiddleware for TracingMiddleware {
+    async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction> {
+        let parent = telemetry::extract_parent(&ctx.req.headers);
+        let tracer = telemetry::tracer();
+        let span = tracer
+            .span_builder(format!("HTTP {}", ctx.req.method))
+            .with_kind(SpanKind::Server)
+            .with_attributes([
+                KeyValue::new("http.request.method", ctx.req.method.clone()),
+                KeyValue::new("url.path", ctx.path().to_string()),
+            ])
+            .start_with_context(&tracer, &parent);
+        let cx = parent.with_span(span);
+
+        telemetry::set_current(cx.clone());
+        ctx.set(SPAN_KEY, (cx, Instant::now()))?;
+        Ok(InboundAction::Capture)
+    }
+
+    fn name(&self) -> &'static str {
+        "tracing"
+    }
+
+    fn priority(&self) -> i32 {
+        -995 // Before metrics so the span covers the whole chain
+    }
+
+    fn should_run(&self, ctx: &Context) -> bool {
+        !self
+            .excluded_paths
+            .iter()
+            .any(|prefix| path_is_under(ctx.path(), prefix))
+    }
+}
+
+#[async_trait]
+impl OutboundMiddleware for TracingMiddleware {
+    async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        let Some((cx, start)) = ctx.get::<(opentelemetry::Context, Instant)>(SPAN_KEY) else {
+            return Ok(());
+        };
+        let span = cx.span();
+
+        let status = ctx
+            .get_response()
+            .map(|response| response.status.as_u16())
+            .unwrap_or(500);
+
+        if let Some(route) = &ctx.req.route {
+            span.update_name(format!("{} {}", ctx.req.method, route));
+            span.set_attribute(KeyValue::new("http.route", route.clone()));
+        }
+        span.set_attribute(KeyValue::new(
+            "http.response.status_code",
+            i64::from(status),
+        ));
+        span.set_attribute(KeyValue::new(
+            "http.server.request.duration",
+            start.elapsed().as_secs_f64(),
+        ));
+        if status >= 500 {
+            span.set_status(Status::error(format!("HTTP {}", status)));
+        }
+        span.end();
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::context::Context;
 use crate::error::Result;
+use crate::http::Response;
 use async_trait::async_trait;
 use std::fmt::Debug;
 
@@ -20,6 +21,19 @@ pub enum InboundAction {
 
     /// Continue processing and ensure this middleware processes the response
     Capture,
+
+    /// Stop the chain and return this response
+    ///
+    /// Unlike `Stop`, outbound middleware registered before this one still
+    /// processes the response, so headers, cookies and logging apply to it.
+    Respond(Response),
+}
+
+impl InboundAction {
+    /// Stop the chain with a redirect to `location`
+    pub fn redirect(location: &str) -> Self {
+        Self::Respond(Response::redirect(location))
+    }
 }
 
 /// Trait for middleware that processes incoming requests
@@ -34,8 +48,10 @@ pub trait InboundMiddleware: Send + Sync + 'static {
     ///
     /// # Returns
     /// - `Continue`: Pass to next middleware without outbound processing
-    /// - `Stop(response)`: Return response immediately, skip remaining chain
+    /// - `Stop`: Return the response set on context immediately, skip remaining chain
     /// - `Capture`: Continue and guarantee outbound processing
+    /// - `Respond(response)`: Return `response`, running the outbound phase
+    ///   of the middleware that ran before
     async fn process_request(&self, ctx: &mut Context) -> Result<InboundAction>;
 
     /// Optional: Get the name of this middleware for debugging
@@ -83,6 +83,49 @@ impl UpdateBuilder {
     pub fn is_empty(&self) -> bool {
         self.set_clause.is_empty()
     }
+
+    /// Create an update builder with a clause for each changed field of a model
+    ///
+    /// Unchanged columns are left out so a partial update never overwrites
+    /// values written concurrently by someone else. Columns are sorted to keep
+    /// the generated SQL stable.
+    pub fn from_changes<M: ChangeTracking>(model: &M, placeholder: &str) -> Self {
+        let mut fields = model.changed_fields();
+        fields.sort();
+        fields.iter().fold(Self::new(), |builder, field| {
+            builder.set(field, placeholder)
+        })
+    }
+}
+
+/// Compute the next value of an optimistic locking version column
+///
+/// Keeps the integer variant of the current value so the bumped version binds
+/// with the column's type. Returns `None` for non-integer values.
+pub(crate) fn next_version(current: &SqlValue) -> Option<(i64, SqlValue)> {
+    let next = match current {
+        SqlValue::TinyInt(v) => SqlValue::TinyInt(v.checked_add(1)?),
+        SqlValue::SmallInt(v) => SqlValue::SmallInt(v.checked_add(1)?),
+        SqlValue::Int(v) => SqlValue::Int(v.checked_add(1)?),
+        SqlValue::BigInt(v) => SqlValue::BigInt(v.checked_add(1)?),
+        SqlValue::UnsignedTinyInt(v) => SqlValue::UnsignedTinyInt(v.checked_add(1)?),
+        SqlValue::UnsignedSmallInt(v) => SqlValue::UnsignedSmallInt(v.checked_add(1)?),
+        SqlValue::UnsignedInt(v) => SqlValue::UnsignedInt(v.checked_add(1)?),
+        SqlValue::UnsignedBigInt(v) => SqlValue::UnsignedBigInt(v.checked_add(1)?),
+        _ => return None,
+    };
+    let as_i64 = match &next {
+        SqlValue::TinyInt(v) => *v as i64,
+        SqlValue::SmallInt(v) => *v as i64,
+        SqlValue::Int(v) => *v as i64,
+        SqlValue::BigInt(v) => *v,
+        SqlValue::UnsignedTinyInt(v) => *v as i64,
+        SqlValue::UnsignedSmallInt(v) => *v as i64,
+        SqlValue::UnsignedInt(v) => *v as i64,
+        SqlValue::UnsignedBigInt(v) => i64::try_from(*v).ok()?,
+        _ => return None,
+    };
+    Some((as_i64, next))
 }
 
 /// Trait for change tracking in models
@@ -134,6 +177,52 @@ where
     /// The name of the primary key field (e.g., "id", "user_id", "uuid")
     const PRIMARY_KEY: &'static str;
 
+    /// Every column of a composite primary key, in key order
+    ///
+    /// Empty (the default) when `PRIMARY_KEY` alone identifies a row.
+    /// Keyset iteration such as `ModelQuery::chunk` refuses composite keys.
+    const PRIMARY_KEY_COLUMNS: &'static [&'static str] = &[];
+
+    /// Optional optimistic locking column (e.g., "lock_version")
+    ///
+    /// When set, `update()` bumps this integer column and only matches the row
+    /// if it still holds the version that was loaded. Models that set it must
+    /// also implement `set_version()`.
+    const VERSION_COLUMN: Option<&'static str> = None;
+
+    /// Optional soft-delete timestamp column (e.g., "deleted_at")
+    ///
+    /// When set, `Repository` hides rows where this column is not NULL and
+    /// its `delete()` stamps the column instead of removing the row.
+    const SOFT_DELETE_COLUMN: Option<&'static str> = None;
+
+    /// Clear cached queries for this table after every write
+    ///
+    /// Opt-in: when true, `update()`, `delete()`, bulk writes and
+    /// `Repository` writes invalidate the table's entries in
+    /// `cache::query::global()`.
+    const INVALIDATE_QUERY_CACHE: bool = false;
+
+    /// Record writes in the audit log
+    ///
+    /// Opt-in: when true and an audit log is installed, `update()`,
+    /// `delete()`, bulk writes and `Repository` writes add an entry with the
+    /// action `<table>.<operation>` for the current request's actor.
+    const AUDIT_WRITES: bool = false;
+
+    /// Emit an application event for every write
+    ///
+    /// Opt-in: when true, the same writes as `AUDIT_WRITES` emit the event
+    /// `<table>.<operation>` with the row id in `data.id`. Inside an open
+    /// `database::Transaction` the event waits for the commit.
+    const EMIT_EVENTS: bool = false;
+
+    /// Relations available to `ModelQuery::with()`
+    ///
+    /// Generated from the schema's `belongs_to`, `has_one` and `has_many`
+    /// entries, including those of related tables so nested paths resolve.
+    const RELATIONS: &'static [crate::models::relations::Relation] = &[];
+
     /// Get the ID value of this model instance
     fn id(&self) -> Self::IdType;
 
@@ -144,6 +233,59 @@ where
     /// This is used for dynamic field access in generic update/create operations
     fn get_field_value(&self, field_name: &str) -> crate::error::Result<SqlValue>;
 
+    /// Store the bumped lock version after a successful update
+    /// (implemented by generated models that declare `VERSION_COLUMN`)
+    fn set_version(&mut self, _version: i64) {}
+
+    /// Invalidate this table's cached queries if the model opted in
+    fn invalidate_query_cache() {
+        if Self::INVALIDATE_QUERY_CACHE {
+            crate::cache::query::invalidate_table(Self::TABLE_NAME);
+        }
+    }
+
+    /// Record a write in the audit log if the model opted in
+    ///
+    /// `id` is the written row, or `None` for writes that touch many rows.
+    async fn audit_write(operation: &str, id: Option<String>, metadata: serde_json::Value) {
+        if Self::AUDIT_WRITES {
+            let target = match id {
+                Some(id) => format!("{}:{}", Self::TABLE_NAME, id),
+                None => Self::TABLE_NAME.to_string(),
+            };
+            crate::audit::record_model_write(Self::TABLE_NAME, operation, target, metadata).await;
+        }
+    }
+
+    /// Emit the write event if the model opted in
+    ///
+    /// Deferred until commit while a transaction is open on this task.
+    async fn emit_write_event(operation: &str, id: Option<String>, metadata: serde_json::Value) {
+        if Self::EMIT_EVENTS {
+            let mut data = serde_json::json!({ "id": id });
+            if let (Some(data), serde_json::Value::Object(metadata)) =
+                (data.as_object_mut(), metadata)
+            {
+                data.extend(metadata);
+            }
+            let event = format!("{}.{}", Self::TABLE_NAME, operation);
+            if let Err(e) = crate::database::transaction::emit_event(event, Some(data)).await {
+                log::warn!(
+                    "Failed to emit {} event for {}: {}",
+                    operation,
+                    Self::TABLE_NAME,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Audit and announce a write, for the models that opted in
+    async fn after_write(operation: &str, id: Option<String>, metadata: serde_json::Value) {
+        Self::emit_write_event(operation, id.clone(), metadata.clone()).await;
+        Self::audit_write(operation, id, metadata).await;
+    }
+
     // =========================================================================
     // MODEL-SCOPED QUERY BUILDER (NEW)
     // =========================================================================
@@ -277,6 +419,22 @@ where
         Self::get_by_id_static(id).await
     }
 
+    /// Get a model by ID, or fail with `Error::ModelNotFound`
+    ///
+    /// The error renders as a 404 page (or JSON error for API clients), so
+    /// show and edit handlers need no not-found branch of their own.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let user = Users::find_or_404(ctx.param_or_404("id")?).await?;
+    /// ```
+    async fn find_or_404(id: Self::IdType) -> crate::error::Result<Self> {
+        let not_found = format!("{} {}", Self::TABLE_NAME, id);
+        Self::get_by_id_static(id)
+            .await?
+            .ok_or(crate::error::Error::ModelNotFound(not_found))
+    }
+
     /// Find a model by ID (deprecated alias)
     ///
     /// **DEPRECATED**: Use `get_by_id()` instead
@@ -333,8 +491,10 @@ where
         // Execute with proper parameter binding
         DB::execute_with_params(&sql, params)
             .await
-            .map_err(|e| crate::error::Error::template(format!("Failed to delete: {}", e)))?;
+            .map_err(|e| e.with_context(format!("Failed to delete from {}", Self::TABLE_NAME)))?;
 
+        Self::invalidate_query_cache();
+        Self::after_write("delete", Some(self.id().to_string()), serde_json::json!({})).await;
         Ok(())
     }
 
@@ -375,6 +535,11 @@ where
     /// This method efficiently updates only the fields that have been modified
     /// using the setter methods. It automatically tracks changes and generates
     /// optimized UPDATE queries.
+    ///
+    /// For models with a `VERSION_COLUMN`, the version is incremented and the
+    /// UPDATE only matches the row when its version is unchanged since load.
+    /// If another update got there first, `Error::StaleModel` is returned and
+    /// the model is left untouched so the caller can reload and retry.
     async fn update(&mut self) -> crate::error::Result<()> {
         use crate::db::DB;
         use std::collections::HashMap;
@@ -407,24 +572,119 @@ where
             AnyDatabase::SQLite(_) => DatabaseBackend::SQLite,
         };
 
-        let query_builder = QueryBuilder::new(backend)
+        let mut query_builder = QueryBuilder::new(backend)
             .from(Self::TABLE_NAME)
             .where_eq(Self::PRIMARY_KEY, self.id().into()); // Use .into() to convert IdType to SqlValue
 
+        // Optimistic locking: bump the version and require the loaded one
+        let mut bumped_version = None;
+        if let Some(column) = Self::VERSION_COLUMN {
+            let current = self.get_field_value(column)?;
+            let (version, next) = next_version(&current).ok_or_else(|| {
+                crate::error::Error::internal(format!(
+                    "Version column '{}' of {} must hold an integer",
+                    column,
+                    Self::TABLE_NAME
+                ))
+            })?;
+            update_data.insert(column.to_string(), next);
+            query_builder = query_builder.where_eq(column, current);
+            bumped_version = Some(version);
+        }
+
         let (sql, params) = query_builder.build_update(&update_data).map_err(|e| {
             crate::error::Error::template(format!("Failed to build update query: {}", e))
         })?;
 
         // Execute with proper parameter binding through DB::execute_with_params
-        DB::execute_with_params(&sql, params)
+        let rows_affected = DB::execute_with_params(&sql, params)
             .await
-            .map_err(|e| crate::error::Error::template(format!("Failed to update: {}", e)))?;
+            .map_err(|e| e.with_context(format!("Failed to update {}", Self::TABLE_NAME)))?;
+
+        if rows_affected > 0 {
+            Self::invalidate_query_cache();
+        }
+
+        if let Some(version) = bumped_version {
+            if rows_affected == 0 {
+                return Err(crate::error::Error::stale_model(format!(
+                    "{} with {} = {} was modified or deleted since it was loaded",
+                    Self::TABLE_NAME,
+                    Self::PRIMARY_KEY,
+                    self.id()
+                )));
+            }
+            self.set_version(version);
+        }
+
+        if rows_affected > 0 {
+            let mut fields: Vec<&String> = update_data.keys().collect();
+            fields.sort();
+            Self::after_write(
+                "update",
+                Some(self.id().to_string()),
+                serde_json::json!({ "fields": fields }),
+            )
+            .await;
+        }
 
         // Clear change tracking after successful update
         self.clear_changes();
         Ok(())
     }
 
+    /// Insert many records with multi-row INSERT statements
+    ///
+    /// Each record maps column names to values, and every record must have
+    /// the same columns. Records are sent in as few statements as the
+    /// backend's bind parameter limit allows (65535 for PostgreSQL and MySQL).
+    /// Statements run one after another, so a failure part-way leaves the
+    /// earlier chunks inserted.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - Number of rows inserted
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let records: Vec<HashMap<String, SqlValue>> = csv_rows
+    ///     .iter()
+    ///     .map(|row| HashMap::from([
+    ///         ("email".to_string(), row.email.clone().into()),
+    ///         ("name".to_string(), row.name.clone().into()),
+    ///     ]))
+    ///     .collect();
+    ///
+    /// let inserted = Users::insert_many(records).await?;
+    /// ```
+    async fn insert_many(
+        records: Vec<std::collections::HashMap<String, SqlValue>>,
+    ) -> crate::error::Result<u64> {
+        let inserted = bulk_write(Self::TABLE_NAME, &records, None).await?;
+        Self::invalidate_query_cache();
+        Self::after_write("insert_many", None, serde_json::json!({ "rows": inserted })).await;
+        Ok(inserted)
+    }
+
+    /// Insert many records, updating the existing row on conflict
+    ///
+    /// Rows whose `conflict_columns` match an existing row overwrite it with
+    /// the new values. MySQL resolves conflicts on any unique key and counts
+    /// each updated row twice in the returned number of affected rows.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let affected = Products::upsert_many(records, &["sku"]).await?;
+    /// ```
+    async fn upsert_many(
+        records: Vec<std::collections::HashMap<String, SqlValue>>,
+        conflict_columns: &[&str],
+    ) -> crate::error::Result<u64> {
+        let affected = bulk_write(Self::TABLE_NAME, &records, Some(conflict_columns)).await?;
+        Self::invalidate_query_cache();
+        Self::after_write("upsert_many", None, serde_json::json!({ "rows": affected })).await;
+        Ok(affected)
+    }
+
     // =========================================================================
     // INTERNAL HELPER METHODS - Used by framework, not by users
     // =========================================================================
@@ -496,6 +756,40 @@ where
     }
 }
 
+/// Run multi-row INSERT (or upsert) statements for `insert_many`/`upsert_many`
+async fn bulk_write(
+    table: &str,
+    records: &[std::collections::HashMap<String, SqlValue>],
+    conflict_columns: Option<&[&str]>,
+) -> crate::error::Result<u64> {
+    use crate::db::DB;
+
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    let backend = DB::backend()
+        .ok_or_else(|| crate::error::Error::template("Database not configured".to_string()))?;
+    let query = QueryBuilder::new(backend).from(table);
+
+    let statements = match conflict_columns {
+        Some(conflict_columns) => query.build_upsert_many(records, conflict_columns),
+        None => query.build_insert_many(records),
+    }
+    .map_err(|e| {
+        crate::error::Error::template(format!("Failed to build bulk insert query: {}", e))
+    })?;
+
+    let mut rows_affected = 0;
+    for (sql, params) in statements {
+        rows_affected += DB::execute_with_params(&sql, params)
+            .await
+            .map_err(|e| e.with_context(format!("Failed to bulk insert into {}", table)))?;
+    }
+
+    Ok(rows_affected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,6 +868,62 @@ mod tests {
         assert_eq!(updates.clause(), "name = ?, email = ?");
     }
 
+    #[test]
+    fn test_update_builder_from_changes() {
+        struct Tracked(HashSet<String>);
+
+        impl ChangeTracking for Tracked {
+            fn mark_changed(&mut self, field: &str, _is_null: bool) {
+                self.0.insert(field.to_string());
+            }
+            fn is_changed(&self, field: &str) -> bool {
+                self.0.contains(field)
+            }
+            fn is_null(&self, _field: &str) -> bool {
+                false
+            }
+            fn has_changes(&self) -> bool {
+                !self.0.is_empty()
+            }
+            fn clear_changes(&mut self) {
+                self.0.clear();
+            }
+            fn changed_fields(&self) -> Vec<String> {
+                self.0.iter().cloned().collect()
+            }
+            fn changed_fields_set(&self) -> &HashSet<String> {
+                &self.0
+            }
+            fn null_fields_set(&self) -> &HashSet<String> {
+                &self.0
+            }
+        }
+
+        let mut model = Tracked(HashSet::new());
+        assert!(UpdateBuilder::from_changes(&model, "?").is_empty());
+
+        model.mark_changed("name", false);
+        model.mark_changed("email", false);
+        assert_eq!(
+            UpdateBuilder::from_changes(&model, "?").clause(),
+            "email = ?, name = ?"
+        );
+    }
+
+    #[test]
+    fn test_next_version() {
+        assert!(matches!(
+            next_version(&SqlValue::Int(4)),
+            Some((5, SqlValue::Int(5)))
+        ));
+        assert!(matches!(
+            next_version(&SqlValue::BigInt(41)),
+            Some((42, SqlValue::BigInt(42)))
+        ));
+        assert!(next_version(&SqlValue::Int(i32::MAX)).is_none());
+        assert!(next_version(&SqlValue::String("1".to_string())).is_none());
+    }
+
     // Note: We can't easily test the actual database operations in unit tests
     // without setting up a test database, but we can test that the trait
     // compiles and the types are correct.
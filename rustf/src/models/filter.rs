@@ -24,6 +24,18 @@ use std::fmt::Debug;
 ///     .get()
 ///     .await?;
 /// ```
+///
+/// A function returning a filter is a named scope, applied with
+/// `ModelQuery::scope`:
+/// ```rust,ignore
+/// impl Users {
+///     pub fn active() -> ModelFilter {
+///         ModelFilter::new().where_eq("status", "active")
+///     }
+/// }
+///
+/// let users = Users::query()?.scope(Users::active).get().await?;
+/// ```
 #[derive(Clone, Debug, Default)]
 pub struct ModelFilter {
     conditions: Vec<WhereCondition>,
@@ -37,159 +49,172 @@ impl ModelFilter {
         }
     }
 
-    /// Add a WHERE = condition
-    pub fn where_eq<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
+    fn push(
+        mut self,
+        field: &str,
+        operator: &str,
+        value: SqlValue,
+        connector: WhereConnector,
+    ) -> Self {
         self.conditions.push(WhereCondition {
             column: field.to_string(),
-            operator: "=".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
+            operator: operator.to_string(),
+            value,
+            connector,
         });
         self
     }
 
+    /// `IN (...)` and `NOT IN (...)` carry their values in the operator, like the query builder
+    fn push_list<V: Into<SqlValue>>(
+        self,
+        field: &str,
+        operator: &str,
+        values: Vec<V>,
+        connector: WhereConnector,
+    ) -> Self {
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|v| v.into().to_sql_string())
+            .collect();
+        let operator = format!("{} ({})", operator, values.join(", "));
+        self.push(field, &operator, SqlValue::String(String::new()), connector)
+    }
+
+    /// Add a WHERE = condition
+    pub fn where_eq<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "=", value.into(), WhereConnector::And)
+    }
+
     /// Add a WHERE != condition
-    pub fn where_ne<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "!=".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_ne<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "!=", value.into(), WhereConnector::And)
     }
 
     /// Add a WHERE > condition
-    pub fn where_gt<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: ">".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_gt<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, ">", value.into(), WhereConnector::And)
     }
 
     /// Add a WHERE >= condition
-    pub fn where_gte<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: ">=".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_gte<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, ">=", value.into(), WhereConnector::And)
     }
 
     /// Add a WHERE < condition
-    pub fn where_lt<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "<".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_lt<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "<", value.into(), WhereConnector::And)
     }
 
     /// Add a WHERE <= condition
-    pub fn where_lte<V: Into<SqlValue>>(mut self, field: &str, value: V) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "<=".to_string(),
-            value: value.into(),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_lte<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "<=", value.into(), WhereConnector::And)
     }
 
     /// Add a WHERE LIKE condition
-    pub fn where_like(mut self, field: &str, pattern: &str) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "LIKE".to_string(),
-            value: SqlValue::String(pattern.to_string()),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_like(self, field: &str, pattern: &str) -> Self {
+        self.push(field, "LIKE", pattern.into(), WhereConnector::And)
     }
 
     /// Add a WHERE NOT LIKE condition
-    pub fn where_not_like(mut self, field: &str, pattern: &str) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "NOT LIKE".to_string(),
-            value: SqlValue::String(pattern.to_string()),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_not_like(self, field: &str, pattern: &str) -> Self {
+        self.push(field, "NOT LIKE", pattern.into(), WhereConnector::And)
     }
 
     /// Add a WHERE IN condition
-    pub fn where_in<V: Into<SqlValue>>(mut self, field: &str, values: Vec<V>) -> Self {
-        // For IN conditions, we'll use a special format
-        let values_vec: Vec<SqlValue> = values.into_iter().map(|v| v.into()).collect();
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "IN".to_string(),
-            value: SqlValue::Array(values_vec),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_in<V: Into<SqlValue>>(self, field: &str, values: Vec<V>) -> Self {
+        self.push_list(field, "IN", values, WhereConnector::And)
     }
 
     /// Add a WHERE NOT IN condition
-    pub fn where_not_in<V: Into<SqlValue>>(mut self, field: &str, values: Vec<V>) -> Self {
-        let values_vec: Vec<SqlValue> = values.into_iter().map(|v| v.into()).collect();
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "NOT IN".to_string(),
-            value: SqlValue::Array(values_vec),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_not_in<V: Into<SqlValue>>(self, field: &str, values: Vec<V>) -> Self {
+        self.push_list(field, "NOT IN", values, WhereConnector::And)
     }
 
     /// Add a WHERE IS NULL condition
-    pub fn where_null(mut self, field: &str) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "IS NULL".to_string(),
-            value: SqlValue::Null,
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_null(self, field: &str) -> Self {
+        self.push(field, "IS", SqlValue::Null, WhereConnector::And)
     }
 
     /// Add a WHERE IS NOT NULL condition
-    pub fn where_not_null(mut self, field: &str) -> Self {
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "IS NOT NULL".to_string(),
-            value: SqlValue::Null,
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_not_null(self, field: &str) -> Self {
+        self.push(field, "IS NOT", SqlValue::Null, WhereConnector::And)
     }
 
     /// Add a WHERE BETWEEN condition
-    pub fn where_between<V: Into<SqlValue>>(mut self, field: &str, start: V, end: V) -> Self {
-        // For BETWEEN, we'll use the first value and store the range in an array
-        self.conditions.push(WhereCondition {
-            column: field.to_string(),
-            operator: "BETWEEN".to_string(),
-            value: SqlValue::Array(vec![start.into(), end.into()]),
-            connector: WhereConnector::And,
-        });
-        self
+    pub fn where_between<V: Into<SqlValue>>(self, field: &str, start: V, end: V) -> Self {
+        // Stored as "start AND end", like the query builder does
+        let range = format!(
+            "{} AND {}",
+            start.into().to_sql_string(),
+            end.into().to_sql_string()
+        );
+        self.push(
+            field,
+            "BETWEEN",
+            SqlValue::String(range),
+            WhereConnector::And,
+        )
+    }
+
+    /// Add an OR WHERE = condition
+    pub fn or_where_eq<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "=", value.into(), WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE != condition
+    pub fn or_where_ne<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "!=", value.into(), WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE > condition
+    pub fn or_where_gt<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, ">", value.into(), WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE < condition
+    pub fn or_where_lt<V: Into<SqlValue>>(self, field: &str, value: V) -> Self {
+        self.push(field, "<", value.into(), WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE LIKE condition
+    pub fn or_where_like(self, field: &str, pattern: &str) -> Self {
+        self.push(field, "LIKE", pattern.into(), WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE IN condition
+    pub fn or_where_in<V: Into<SqlValue>>(self, field: &str, values: Vec<V>) -> Self {
+        self.push_list(field, "IN", values, WhereConnector::Or)
+    }
+
+    /// Add an OR WHERE IS NULL condition
+    pub fn or_where_null(self, field: &str) -> Self {
+        self.push(field, "IS", SqlValue::Null, WhereConnector::Or)
     }
 
     /// Combine with another filter using AND logic
+    ///
+    /// Either side containing OR is parenthesized first, so
+    /// `a OR b` and `c` becomes `(a OR b) AND c`.
     pub fn and(mut self, other: ModelFilter) -> Self {
-        self.conditions.extend(other.conditions);
+        WhereCondition::and_all(&mut self.conditions, &other.conditions);
         self
     }
 
+    /// Combine with another filter using OR logic
+    ///
+    /// Each side is parenthesized, so `a AND b` or `c AND d` becomes
+    /// `(a AND b) OR (c AND d)`.
+    pub fn or(self, other: ModelFilter) -> Self {
+        if self.is_empty() || other.is_empty() {
+            // An empty filter matches every row, and so does anything ORed with it
+            return Self::new();
+        }
+        let mut conditions = WhereCondition::group(&self.conditions, WhereConnector::And);
+        conditions.extend(WhereCondition::group(&other.conditions, WhereConnector::Or));
+        Self { conditions }
+    }
+
     /// Get the conditions for applying to a query
     pub(crate) fn get_conditions(&self) -> &[WhereCondition] {
         &self.conditions
@@ -197,11 +222,14 @@ impl ModelFilter {
 
     /// Check if the filter has any conditions
     pub fn is_empty(&self) -> bool {
-        self.conditions.is_empty()
+        self.len() == 0
     }
 
     /// Get the number of conditions
     pub fn len(&self) -> usize {
-        self.conditions.len()
+        self.conditions
+            .iter()
+            .filter(|condition| !condition.is_group_marker())
+            .count()
     }
 }
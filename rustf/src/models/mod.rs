@@ -6,6 +6,8 @@ pub mod base_model;
 pub mod filter;
 pub mod model_query;
 pub mod query_builder;
+pub mod relations;
+pub mod repository;
 /// pub mod macros;
 
 pub struct ModelRegistry {
@@ -75,6 +77,12 @@ pub use query_builder::{
 // Re-export model query builder
 pub use model_query::ModelQuery;
 
+// Re-export relation metadata for eager loading
+pub use relations::{Preloaded, Relation, RelationKind};
+
+// Re-export generic repository for adapter-scoped CRUD
+pub use repository::Repository;
+
 // Re-export filter for reusable query filters
 pub use filter::ModelFilter;
 
@@ -8,7 +8,10 @@ use crate::db::DB;
 use crate::error::{Error, Result};
 use crate::models::base_model::BaseModel;
 use crate::models::filter::ModelFilter;
-use crate::models::query_builder::{OrderDirection, QueryBuilder, QueryError};
+use crate::models::query_builder::{OrderDirection, QueryBuilder, QueryError, WhereCondition};
+use crate::models::relations::{self, Preloaded};
+use crate::utils::pagination::{Page, PageMeta};
+use std::future::Future;
 use std::marker::PhantomData;
 
 /// Model-scoped query builder that provides type-safe, chainable query operations
@@ -17,6 +20,7 @@ use std::marker::PhantomData;
 /// functionality with automatic table names and typed results.
 pub struct ModelQuery<T> {
     query_builder: QueryBuilder,
+    preloads: Vec<String>,
     _phantom: PhantomData<T>,
 }
 
@@ -29,10 +33,23 @@ impl<T: BaseModel> ModelQuery<T> {
 
         Ok(Self {
             query_builder,
+            preloads: Vec::new(),
             _phantom: PhantomData,
         })
     }
 
+    /// Create a ModelQuery from an existing QueryBuilder
+    ///
+    /// Used when the query targets a specific adapter rather than the
+    /// global connection (see `Repository`).
+    pub fn from_query_builder(query_builder: QueryBuilder) -> Self {
+        Self {
+            query_builder,
+            preloads: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
     // =========================================================================
     // TABLE ALIASING
     // =========================================================================
@@ -172,13 +189,51 @@ impl<T: BaseModel> ModelQuery<T> {
     ///     .await?;
     /// ```
     pub fn apply_filter(mut self, filter: &ModelFilter) -> Self {
-        // Apply each condition from the filter to the query builder
-        self.query_builder
-            .where_conditions
-            .extend(filter.get_conditions().iter().cloned());
+        // AND the filter onto the query, parenthesizing whichever side has an OR
+        WhereCondition::and_all(
+            &mut self.query_builder.where_conditions,
+            filter.get_conditions(),
+        );
         self
     }
 
+    /// Apply a named scope
+    ///
+    /// A scope is a function returning a [`ModelFilter`], usually defined on
+    /// the model. Scopes chain like any other condition and are ANDed with
+    /// the rest of the query; a scope containing OR is parenthesized.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// impl Users {
+    ///     pub fn active() -> ModelFilter {
+    ///         ModelFilter::new().where_eq("status", "active")
+    ///     }
+    ///
+    ///     pub fn recent() -> ModelFilter {
+    ///         ModelFilter::new().where_gt("created_at", Utc::now() - Duration::days(30))
+    ///     }
+    ///
+    ///     pub fn role(role: &str) -> ModelFilter {
+    ///         ModelFilter::new().where_eq("role", role).or_where_eq("is_admin", true)
+    ///     }
+    /// }
+    ///
+    /// // WHERE status = 'active' AND created_at > ... AND (role = 'editor' OR is_admin = true)
+    /// let users = Users::query()?
+    ///     .scope(Users::active)
+    ///     .scope(Users::recent)
+    ///     .scope(|| Users::role("editor"))
+    ///     .get()
+    ///     .await?;
+    /// ```
+    pub fn scope<F>(self, scope: F) -> Self
+    where
+        F: FnOnce() -> ModelFilter,
+    {
+        self.apply_filter(&scope())
+    }
+
     // =========================================================================
     // OR WHERE CONDITIONS
     // =========================================================================
@@ -276,6 +331,48 @@ impl<T: BaseModel> ModelQuery<T> {
         self
     }
 
+    // =========================================================================
+    // FULL-TEXT SEARCH
+    // =========================================================================
+
+    /// Full-text search over `columns`, requiring every word of `query`
+    ///
+    /// Uses `tsvector` matching on PostgreSQL, `MATCH ... AGAINST` on MySQL
+    /// and a `LIKE` fallback on SQLite.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let articles = Articles::query()?
+    ///     .full_text_search(&["title", "body"], &search)
+    ///     .order_by_relevance()
+    ///     .limit(20)
+    ///     .get_all()
+    ///     .await?;
+    /// ```
+    pub fn full_text_search(mut self, columns: &[&str], query: &str) -> Self {
+        self.query_builder = self
+            .query_builder
+            .full_text_search(columns.iter().copied(), query);
+        self
+    }
+
+    /// Full-text search against an SQLite FTS5 virtual table
+    pub fn full_text_search_fts5(mut self, columns: &[&str], query: &str) -> Self {
+        self.query_builder = self
+            .query_builder
+            .full_text_search_fts5(columns.iter().copied(), query);
+        self
+    }
+
+    /// Order by full-text relevance, best matches first
+    ///
+    /// The score is also selected as a `relevance` column, available
+    /// through `get_raw()`.
+    pub fn order_by_relevance(mut self) -> Self {
+        self.query_builder = self.query_builder.order_by_relevance();
+        self
+    }
+
     // =========================================================================
     // ORDERING AND LIMITS
     // =========================================================================
@@ -370,6 +467,46 @@ impl<T: BaseModel> ModelQuery<T> {
             .map_err(|e| Error::template(format!("Query execution failed: {}", e)))
     }
 
+    /// Preload a relation when the query runs with `get_all_loaded()`
+    ///
+    /// `name` is a relation from the model's schema, or a dotted path such
+    /// as `"posts.comments"` to preload relations of related rows too. Each
+    /// relation is fetched with one `IN (...)` query per 1000 keys.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let users = Users::query()?
+    ///     .with("posts.comments")
+    ///     .with("team")
+    ///     .get_all_loaded()
+    ///     .await?;
+    /// let posts: Vec<Posts> = users[0].many("posts")?;
+    /// ```
+    pub fn with(mut self, name: &str) -> Self {
+        self.preloads.push(name.to_string());
+        self
+    }
+
+    /// Execute query and return all matching records with the relations
+    /// requested through `with()`
+    pub async fn get_all_loaded(mut self) -> Result<Vec<Preloaded<T>>> {
+        let preloads = self.take_preloads();
+        let models = self.get_all().await?;
+        relations::preload(None, T::TABLE_NAME, T::RELATIONS, models, &preloads).await
+    }
+
+    /// Relations requested through `with()`, leaving none on the query
+    pub(crate) fn take_preloads(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.preloads)
+    }
+
+    /// Execute query and return the first matching record with the
+    /// relations requested through `with()`
+    pub async fn get_first_loaded(mut self) -> Result<Option<Preloaded<T>>> {
+        self.query_builder = self.query_builder.limit(1);
+        Ok(self.get_all_loaded().await?.into_iter().next())
+    }
+
     /// Execute query and return the first matching record
     ///
     /// Automatically adds LIMIT 1 to the query for efficiency.
@@ -498,6 +635,116 @@ impl<T: BaseModel> ModelQuery<T> {
         }
     }
 
+    /// Fetch one page of records (pages start at 1) with its page information
+    ///
+    /// Counts every record matching the query, ignoring its ordering and any
+    /// limit or offset, then loads the requested page. The returned `meta`
+    /// is what `ctx.json_paginated()` expects.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let page = Users::query()?
+    ///     .where_eq("is_active", true)
+    ///     .order_by("created_at", OrderDirection::Desc)
+    ///     .fetch_page(2, 20)
+    ///     .await?;
+    /// ctx.json_paginated(&page.items, &page.meta)
+    /// ```
+    pub async fn fetch_page(self, page: u32, per_page: u32) -> Result<Page<T>> {
+        let mut counter = self.query_builder.clone();
+        counter.order_by.clear();
+        counter.limit = None;
+        counter.offset = None;
+        let total = Self::from_query_builder(counter).count().await?;
+
+        let items = self.paginate(page, per_page).get_all().await?;
+        Ok(Page {
+            items,
+            meta: PageMeta::new(page, per_page, total),
+        })
+    }
+
+    /// Process every matching record in batches of `size`
+    ///
+    /// Pages through the results by primary key (`WHERE pk > last ORDER BY
+    /// pk LIMIT size`), so only one batch is held in memory and rows written
+    /// behind the cursor do not shift later batches. Each batch is read with
+    /// its own statement outside any transaction: whatever `f` writes is
+    /// committed before the next batch is fetched. An error from `f` stops
+    /// the iteration and is returned.
+    ///
+    /// Any ordering, limit or offset on the query is replaced. Queries with
+    /// top-level `or_where_*` conditions are rejected, since the cursor
+    /// condition cannot be combined with them safely; ORs inside a scope or
+    /// filter are parenthesized and fine. Models with a composite primary key
+    /// (`PRIMARY_KEY_COLUMNS`) are rejected too: one key column is not a
+    /// unique cursor, so rows sharing it would be skipped.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - Number of records passed to `f`
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let reindexed = Products::query()?
+    ///     .where_eq("is_active", true)
+    ///     .chunk(1000, |batch| async move {
+    ///         search_index.upsert(&batch).await
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn chunk<F, Fut>(self, size: u32, mut f: F) -> Result<u64>
+    where
+        F: FnMut(Vec<T>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        if size == 0 {
+            return Err(Error::template("Chunk size must be greater than zero"));
+        }
+        if T::PRIMARY_KEY_COLUMNS.len() > 1 {
+            return Err(Error::template(format!(
+                "chunk() does not support the composite primary key of '{}'",
+                T::TABLE_NAME
+            )));
+        }
+        if WhereCondition::has_top_level_or(&self.query_builder.where_conditions) {
+            return Err(Error::template(
+                "chunk() does not support queries with top-level OR conditions",
+            ));
+        }
+
+        let mut base = self.query_builder;
+        base.order_by.clear();
+        base.limit = None;
+        base.offset = None;
+
+        let mut last_id: Option<T::IdType> = None;
+        let mut processed = 0u64;
+        loop {
+            let mut page = base.clone();
+            if let Some(id) = last_id.take() {
+                page = page.where_gt(T::PRIMARY_KEY, id);
+            }
+            let page = page
+                .order_by(T::PRIMARY_KEY, OrderDirection::Asc)
+                .limit(i64::from(size));
+
+            let batch = Self::from_query_builder(page).get_all().await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            last_id = Some(last.id());
+
+            let len = batch.len();
+            processed += len as u64;
+            f(batch).await?;
+
+            if len < size as usize {
+                break;
+            }
+        }
+        Ok(processed)
+    }
+
     /// Check if any records exist matching the query
     ///
     /// This is more efficient than counting when you only need to know
@@ -564,6 +811,11 @@ impl<T: BaseModel> ModelQuery<T> {
         &self.query_builder
     }
 
+    /// Take the underlying QueryBuilder, consuming the ModelQuery
+    pub fn into_query_builder(self) -> QueryBuilder {
+        self.query_builder
+    }
+
     /// Get the raw SQL and parameters (for debugging)
     ///
     /// # Returns
@@ -3,13 +3,16 @@
 //! This module contains the database-agnostic query building structures
 //! and methods, working with the dialect system for database-specific SQL generation.
 
-use super::dialects::{create_dialect, DatabaseBackend, QueryError, SqlDialect};
+use super::dialects::{create_dialect, DatabaseBackend, FullTextSearch, QueryError, SqlDialect};
 use crate::database::types::SqlValue;
 use anyhow::Result;
 
 // Note: SqlValue is now imported from crate::database::types::SqlValue
 // The type system has been unified in the database::types module
 
+/// Column alias of the relevance score selected by `order_by_relevance()`
+pub const RELEVANCE_COLUMN: &str = "relevance";
+
 /// Main query builder that works with any database
 pub struct QueryBuilder {
     pub(crate) dialect: Box<dyn SqlDialect>,
@@ -25,6 +28,30 @@ pub struct QueryBuilder {
     pub(crate) _group_by: Vec<String>,
     pub(crate) _having_conditions: Vec<WhereCondition>,
     pub(crate) returning: Vec<String>,
+    pub(crate) full_text: Option<FullTextSearch>,
+    pub(crate) order_by_relevance: bool,
+}
+
+impl Clone for QueryBuilder {
+    fn clone(&self) -> Self {
+        Self {
+            dialect: create_dialect(self.backend),
+            backend: self.backend,
+            table: self.table.clone(),
+            table_alias: self.table_alias.clone(),
+            select_columns: self.select_columns.clone(),
+            where_conditions: self.where_conditions.clone(),
+            joins: self.joins.clone(),
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            _group_by: self._group_by.clone(),
+            _having_conditions: self._having_conditions.clone(),
+            returning: self.returning.clone(),
+            full_text: self.full_text.clone(),
+            order_by_relevance: self.order_by_relevance,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -41,6 +68,93 @@ pub enum WhereConnector {
     Or,
 }
 
+/// Operators of the markers that open and close a parenthesized group
+const GROUP_OPEN: &str = "(";
+const GROUP_CLOSE: &str = ")";
+
+impl WhereCondition {
+    /// Marker opening a group, joined to the preceding condition by `connector`
+    fn group_open(connector: WhereConnector) -> Self {
+        Self {
+            column: String::new(),
+            operator: GROUP_OPEN.to_string(),
+            value: SqlValue::Null,
+            connector,
+        }
+    }
+
+    /// Marker closing the innermost open group
+    fn group_close() -> Self {
+        Self {
+            column: String::new(),
+            operator: GROUP_CLOSE.to_string(),
+            value: SqlValue::Null,
+            connector: WhereConnector::And,
+        }
+    }
+
+    /// Whether this is a group marker rather than an actual condition
+    pub fn is_group_marker(&self) -> bool {
+        self.column.is_empty() && (self.operator == GROUP_OPEN || self.operator == GROUP_CLOSE)
+    }
+
+    /// Wrap `conditions` in parentheses joined to what precedes them by `connector`
+    ///
+    /// A single condition is not wrapped, only given the connector.
+    pub fn group(conditions: &[WhereCondition], connector: WhereConnector) -> Vec<WhereCondition> {
+        match conditions {
+            [] => Vec::new(),
+            [condition] => vec![WhereCondition {
+                connector,
+                ..condition.clone()
+            }],
+            _ => {
+                let mut grouped = Vec::with_capacity(conditions.len() + 2);
+                grouped.push(Self::group_open(connector));
+                grouped.extend(conditions.iter().cloned());
+                grouped.push(Self::group_close());
+                grouped
+            }
+        }
+    }
+
+    /// Whether `conditions` contain an OR outside any group
+    ///
+    /// Such a list must be parenthesized before more conditions are ANDed
+    /// to it, or the OR would bind them to one branch only.
+    pub fn has_top_level_or(conditions: &[WhereCondition]) -> bool {
+        let mut depth = 0usize;
+        for (i, condition) in conditions.iter().enumerate() {
+            if depth == 0 && i > 0 && matches!(condition.connector, WhereConnector::Or) {
+                return true;
+            }
+            if condition.is_group_marker() {
+                if condition.operator == GROUP_OPEN {
+                    depth += 1;
+                } else {
+                    depth = depth.saturating_sub(1);
+                }
+            }
+        }
+        false
+    }
+
+    /// AND `conditions` onto `existing`, parenthesizing either side if needed
+    pub fn and_all(existing: &mut Vec<WhereCondition>, conditions: &[WhereCondition]) {
+        if conditions.is_empty() {
+            return;
+        }
+        if Self::has_top_level_or(existing) {
+            *existing = Self::group(existing, WhereConnector::And);
+        }
+        if Self::has_top_level_or(conditions) {
+            existing.extend(Self::group(conditions, WhereConnector::And));
+        } else {
+            existing.extend(conditions.iter().cloned());
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct JoinClause {
     pub join_type: JoinType,
@@ -87,6 +201,8 @@ impl QueryBuilder {
             _group_by: Vec::new(),
             _having_conditions: Vec::new(),
             returning: Vec::new(),
+            full_text: None,
+            order_by_relevance: false,
         }
     }
 
@@ -249,23 +365,47 @@ impl QueryBuilder {
 
     /// Build the SQL query string with dialect-specific syntax
     pub fn build(&self) -> Result<(String, Vec<SqlValue>), QueryError> {
-        if self.table.is_none() {
+        let Some(table) = self.table.as_ref() else {
             return Err(QueryError::MissingClause {
                 clause: "from".to_string(),
             });
-        }
+        };
 
         let mut sql = String::new();
         let mut params = Vec::new();
-        let param_count = 1;
+        let mut param_count = 1;
+
+        if let Some(search) = &self.full_text {
+            if search.columns.is_empty() && !search.fts5 {
+                return Err(QueryError::InvalidSyntax {
+                    backend: self.backend,
+                    message: "Full-text search needs at least one column".to_string(),
+                });
+            }
+        }
+        // An aliased table is only known by its alias in the query
+        let search_table = self.table_alias.as_ref().unwrap_or(table);
+        let ranked_search = self.full_text.as_ref().filter(|_| self.order_by_relevance);
 
         // SELECT clause
         sql.push_str("SELECT ");
         sql.push_str(&self.select_columns.join(", "));
+        if let Some(search) = ranked_search {
+            let (relevance, relevance_params) =
+                self.dialect
+                    .full_text_relevance(search_table, search, param_count);
+            sql.push_str(&format!(
+                ", {} AS {}",
+                relevance,
+                self.dialect.quote_identifier(RELEVANCE_COLUMN)
+            ));
+            param_count += relevance_params.len();
+            params.extend(relevance_params);
+        }
 
         // FROM clause with quoted identifier and optional alias
         sql.push_str(" FROM ");
-        sql.push_str(&self.dialect.quote_identifier(self.table.as_ref().unwrap()));
+        sql.push_str(&self.dialect.quote_identifier(table));
         if let Some(alias) = &self.table_alias {
             sql.push_str(" AS ");
             sql.push_str(&self.dialect.quote_identifier(alias));
@@ -313,10 +453,25 @@ impl QueryBuilder {
         }
 
         // WHERE clause with proper enum handling
-        let (where_sql, where_params, _new_param_count) = self.build_where_clause(param_count);
-        sql.push_str(&where_sql);
+        let (where_sql, where_params, param_count) = self.build_where_clause(param_count);
         params.extend(where_params);
 
+        // Full-text predicate, ANDed with the other conditions as a whole
+        if let Some(search) = &self.full_text {
+            let (predicate, predicate_params) =
+                self.dialect
+                    .full_text_predicate(search_table, search, param_count);
+            match where_sql.strip_prefix(" WHERE ") {
+                Some(conditions) => {
+                    sql.push_str(&format!(" WHERE ({}) AND {}", conditions, predicate))
+                }
+                None => sql.push_str(&format!(" WHERE {}", predicate)),
+            }
+            params.extend(predicate_params);
+        } else {
+            sql.push_str(&where_sql);
+        }
+
         // GROUP BY clause
         if !self._group_by.is_empty() {
             sql.push_str(" GROUP BY ");
@@ -328,24 +483,27 @@ impl QueryBuilder {
             sql.push_str(&group_clauses.join(", "));
         }
 
-        // ORDER BY clause
-        if !self.order_by.is_empty() {
+        // ORDER BY clause, best full-text matches first when ranking
+        let mut order_clauses: Vec<String> = Vec::new();
+        if ranked_search.is_some() {
+            order_clauses.push(format!(
+                "{} DESC",
+                self.dialect.quote_identifier(RELEVANCE_COLUMN)
+            ));
+        }
+        order_clauses.extend(self.order_by.iter().map(|clause| {
+            let direction = match clause.direction {
+                OrderDirection::Asc => "ASC",
+                OrderDirection::Desc => "DESC",
+            };
+            format!(
+                "{} {}",
+                self.dialect.quote_identifier(&clause.column),
+                direction
+            )
+        }));
+        if !order_clauses.is_empty() {
             sql.push_str(" ORDER BY ");
-            let order_clauses: Vec<String> = self
-                .order_by
-                .iter()
-                .map(|clause| {
-                    let direction = match clause.direction {
-                        OrderDirection::Asc => "ASC",
-                        OrderDirection::Desc => "DESC",
-                    };
-                    format!(
-                        "{} {}",
-                        self.dialect.quote_identifier(&clause.column),
-                        direction
-                    )
-                })
-                .collect();
             sql.push_str(&order_clauses.join(", "));
         }
 
@@ -566,6 +724,7 @@ impl QueryBuilder {
     /// Convert to COUNT query
     pub fn count(mut self) -> Self {
         self.select_columns = vec!["COUNT(*)".to_string()];
+        self.order_by_relevance = false;
         self
     }
 
@@ -587,6 +746,23 @@ impl QueryBuilder {
         self
     }
 
+    /// Add `conditions` as one parenthesized condition, joined with AND
+    ///
+    /// `WHERE a = 1 AND (b = 2 OR c = 3)` is built by grouping the
+    /// conditions of `b = 2 OR c = 3`.
+    pub fn where_group(mut self, conditions: &[WhereCondition]) -> Self {
+        self.where_conditions
+            .extend(WhereCondition::group(conditions, WhereConnector::And));
+        self
+    }
+
+    /// Add `conditions` as one parenthesized condition, joined with OR
+    pub fn or_where_group(mut self, conditions: &[WhereCondition]) -> Self {
+        self.where_conditions
+            .extend(WhereCondition::group(conditions, WhereConnector::Or));
+        self
+    }
+
     /// Paginate results
     pub fn paginate(mut self, page: u32, per_page: u32) -> Self {
         let offset = (page.saturating_sub(1)) * per_page;
@@ -601,6 +777,62 @@ impl QueryBuilder {
         self
     }
 
+    /// Full-text search over `columns`, requiring every word of `query`
+    ///
+    /// The syntax depends on the backend:
+    /// - PostgreSQL: `to_tsvector(...) @@ plainto_tsquery(...)`
+    /// - MySQL/MariaDB: `MATCH (...) AGAINST (... IN NATURAL LANGUAGE MODE)`,
+    ///   which needs a FULLTEXT index over the same columns
+    /// - SQLite: a `LIKE` test per word and column (see `full_text_search_fts5`)
+    ///
+    /// Combine with `order_by_relevance()` to rank the results.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let query = QueryBuilder::new(DatabaseBackend::Postgres)
+    ///     .from("articles")
+    ///     .full_text_search(vec!["title", "body"], "rust web")
+    ///     .order_by_relevance();
+    /// ```
+    pub fn full_text_search<I, S>(mut self, columns: I, query: &str) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.full_text = Some(FullTextSearch {
+            columns: columns.into_iter().map(|c| c.into()).collect(),
+            query: query.to_string(),
+            fts5: false,
+        });
+        self
+    }
+
+    /// Full-text search against an SQLite FTS5 virtual table
+    ///
+    /// Uses `MATCH` instead of the `LIKE` fallback. `columns` restricts the
+    /// search to those columns and may be empty to search all of them. Other
+    /// backends generate the same SQL as `full_text_search`.
+    pub fn full_text_search_fts5<I, S>(self, columns: I, query: &str) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut query = self.full_text_search(columns, query);
+        if let Some(search) = query.full_text.as_mut() {
+            search.fts5 = true;
+        }
+        query
+    }
+
+    /// Select the full-text relevance score as `relevance` and order by it
+    ///
+    /// Best matches come first, ahead of any other ORDER BY columns. Has no
+    /// effect without `full_text_search`.
+    pub fn order_by_relevance(mut self) -> Self {
+        self.order_by_relevance = true;
+        self
+    }
+
     /// Helper function to generate SQL value expression for a given SqlValue
     /// Returns (sql_expression, should_bind_param)
     fn generate_value_expression(&self, value: &SqlValue, param_index: usize) -> (String, bool) {
@@ -643,14 +875,25 @@ impl QueryBuilder {
         let mut sql = String::from(" WHERE ");
         let mut params = Vec::new();
         let mut param_count = start_param_index;
+        // No connector before the first condition or right after "("
+        let mut group_start = true;
 
-        for (i, condition) in self.where_conditions.iter().enumerate() {
-            if i > 0 {
+        for condition in &self.where_conditions {
+            if condition.is_group_marker() && condition.operator == GROUP_CLOSE {
+                sql.push(')');
+                continue;
+            }
+            if !group_start {
                 match condition.connector {
                     WhereConnector::And => sql.push_str(" AND "),
                     WhereConnector::Or => sql.push_str(" OR "),
                 }
             }
+            group_start = condition.is_group_marker();
+            if group_start {
+                sql.push('(');
+                continue;
+            }
 
             // Handle IS NULL and IS NOT NULL specially (they don't take parameters)
             if condition.operator == "IS" || condition.operator == "IS NOT" {
@@ -790,6 +1033,129 @@ impl QueryBuilder {
         Ok((sql, params))
     }
 
+    /// Build multi-row INSERT queries
+    ///
+    /// Every row must have the same columns. Rows are split into several
+    /// statements when needed to stay under the backend's bind parameter limit.
+    pub fn build_insert_many(
+        &self,
+        rows: &[std::collections::HashMap<String, SqlValue>],
+    ) -> Result<Vec<(String, Vec<SqlValue>)>> {
+        self.build_bulk_insert(rows, None)
+    }
+
+    /// Build multi-row upsert queries
+    ///
+    /// Rows conflicting on `conflict_columns` update the existing row instead.
+    /// MySQL ignores `conflict_columns` and resolves conflicts on any unique key.
+    pub fn build_upsert_many(
+        &self,
+        rows: &[std::collections::HashMap<String, SqlValue>],
+        conflict_columns: &[&str],
+    ) -> Result<Vec<(String, Vec<SqlValue>)>> {
+        if conflict_columns.is_empty()
+            && !matches!(
+                self.backend,
+                DatabaseBackend::MySQL | DatabaseBackend::MariaDB
+            )
+        {
+            return Err(QueryError::InvalidSyntax {
+                backend: self.backend,
+                message: "No conflict columns provided for upsert".to_string(),
+            }
+            .into());
+        }
+
+        self.build_bulk_insert(rows, Some(conflict_columns))
+    }
+
+    fn build_bulk_insert(
+        &self,
+        rows: &[std::collections::HashMap<String, SqlValue>],
+        conflict_columns: Option<&[&str]>,
+    ) -> Result<Vec<(String, Vec<SqlValue>)>> {
+        let Some(table) = self.table.as_ref() else {
+            return Err(QueryError::MissingClause {
+                clause: "table".to_string(),
+            }
+            .into());
+        };
+
+        let Some(first) = rows.first().filter(|row| !row.is_empty()) else {
+            return Err(QueryError::InvalidSyntax {
+                backend: self.backend,
+                message: "No data provided for INSERT".to_string(),
+            }
+            .into());
+        };
+
+        // Sorted so that every row binds its values in the same order
+        let mut keys: Vec<&String> = first.keys().collect();
+        keys.sort();
+
+        if let Some(index) = rows
+            .iter()
+            .position(|row| row.len() != keys.len() || keys.iter().any(|k| !row.contains_key(*k)))
+        {
+            return Err(QueryError::InvalidSyntax {
+                backend: self.backend,
+                message: format!("Row {} has different columns than the first row", index),
+            }
+            .into());
+        }
+
+        let columns: Vec<String> = keys
+            .iter()
+            .map(|k| self.dialect.quote_identifier(k))
+            .collect();
+        let conflict_clause = conflict_columns.map(|conflict| {
+            let conflict: Vec<String> = conflict
+                .iter()
+                .map(|c| self.dialect.quote_identifier(c))
+                .collect();
+            self.dialect.upsert_conflict_clause(&columns, &conflict)
+        });
+
+        let rows_per_statement = (self.dialect.max_bind_params() / columns.len()).max(1);
+        let mut statements = Vec::new();
+
+        for chunk in rows.chunks(rows_per_statement) {
+            let mut param_index = 1;
+            let mut params = Vec::new();
+            let mut row_parts = Vec::with_capacity(chunk.len());
+
+            for row in chunk {
+                let mut value_parts = Vec::with_capacity(keys.len());
+                for key in &keys {
+                    let value = &row[*key];
+                    let (expression, should_bind) =
+                        self.generate_value_expression(value, param_index);
+                    value_parts.push(expression);
+
+                    if should_bind {
+                        params.push(value.clone());
+                        param_index += 1;
+                    }
+                }
+                row_parts.push(format!("({})", value_parts.join(", ")));
+            }
+
+            let mut sql = format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                self.dialect.quote_identifier(table),
+                columns.join(", "),
+                row_parts.join(", ")
+            );
+            if let Some(clause) = &conflict_clause {
+                sql.push_str(clause);
+            }
+
+            statements.push((sql, params));
+        }
+
+        Ok(statements)
+    }
+
     /// Build an UPDATE query
     pub fn build_update(
         &self,
@@ -959,6 +1325,205 @@ mod tests {
         let result = query.build();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_where_groups() {
+        let either = vec![
+            WhereCondition {
+                column: "role".to_string(),
+                operator: "=".to_string(),
+                value: "admin".into(),
+                connector: WhereConnector::And,
+            },
+            WhereCondition {
+                column: "is_owner".to_string(),
+                operator: "=".to_string(),
+                value: true.into(),
+                connector: WhereConnector::Or,
+            },
+        ];
+
+        let (sql, params) = QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("members")
+            .where_eq("tenant_id", 7)
+            .where_group(&either)
+            .build()
+            .unwrap();
+        assert!(sql.ends_with(r#"WHERE "tenant_id" = $1 AND ("role" = $2 OR "is_owner" = $3)"#));
+        assert_eq!(params.len(), 3);
+
+        // ANDing onto a list with a top-level OR parenthesizes it first
+        let mut conditions = either.clone();
+        WhereCondition::and_all(&mut conditions, &either[..1]);
+        assert!(!WhereCondition::has_top_level_or(&conditions));
+        let mut query = QueryBuilder::new(DatabaseBackend::Postgres).from("members");
+        query.where_conditions = conditions;
+        let (sql, _) = query.build().unwrap();
+        assert!(sql.ends_with(r#"WHERE ("role" = $1 OR "is_owner" = $2) AND "role" = $3"#));
+    }
+
+    #[test]
+    fn test_full_text_search_postgres() {
+        let (sql, params) = QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("articles")
+            .where_eq("published", true)
+            .or_where_eq("featured", true)
+            .full_text_search(vec!["title", "body"], "rust web")
+            .order_by_relevance()
+            .order_by("id", OrderDirection::Asc)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT *, ts_rank(to_tsvector(concat_ws(' ', \"title\", \"body\")), plainto_tsquery($1)) AS \"relevance\" \
+             FROM \"articles\" \
+             WHERE (\"published\" = $2 OR \"featured\" = $3) \
+             AND to_tsvector(concat_ws(' ', \"title\", \"body\")) @@ plainto_tsquery($4) \
+             ORDER BY \"relevance\" DESC, \"id\" ASC"
+        );
+        assert_eq!(params.len(), 4);
+        assert!(matches!(&params[3], SqlValue::String(q) if q == "rust web"));
+    }
+
+    #[test]
+    fn test_full_text_search_mysql() {
+        let (sql, params) = QueryBuilder::new(DatabaseBackend::MySQL)
+            .from("articles")
+            .full_text_search(vec!["title", "body"], "rust")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM `articles` WHERE MATCH (`title`, `body`) AGAINST (? IN NATURAL LANGUAGE MODE)"
+        );
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_full_text_search_sqlite() {
+        let (sql, params) = QueryBuilder::new(DatabaseBackend::SQLite)
+            .from("articles")
+            .full_text_search(vec!["title", "body"], "50% off")
+            .order_by_relevance()
+            .count()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) FROM \"articles\" WHERE \
+             (\"title\" LIKE ? ESCAPE '\\' OR \"body\" LIKE ? ESCAPE '\\') AND \
+             (\"title\" LIKE ? ESCAPE '\\' OR \"body\" LIKE ? ESCAPE '\\')"
+        );
+        assert!(matches!(&params[0], SqlValue::String(p) if p == "%50\\%%"));
+
+        let (sql, params) = QueryBuilder::new(DatabaseBackend::SQLite)
+            .from("articles_fts")
+            .full_text_search_fts5(Vec::<String>::new(), "say \"hi\"")
+            .order_by_relevance()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT *, -bm25(\"articles_fts\") AS \"relevance\" FROM \"articles_fts\" \
+             WHERE \"articles_fts\" MATCH ? ORDER BY \"relevance\" DESC"
+        );
+        assert!(matches!(&params[0], SqlValue::String(q) if q == "\"say\" \"\"\"hi\"\"\""));
+
+        // An aliased FTS5 table is matched through its alias
+        let (sql, _) = QueryBuilder::new(DatabaseBackend::SQLite)
+            .from("articles_fts")
+            .as_alias("a")
+            .full_text_search_fts5(Vec::<String>::new(), "rust")
+            .order_by_relevance()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT *, -bm25(\"a\") AS \"relevance\" FROM \"articles_fts\" AS \"a\" \
+             WHERE \"a\" MATCH ? ORDER BY \"relevance\" DESC"
+        );
+    }
+
+    fn bulk_row(id: i32, name: Option<&str>) -> std::collections::HashMap<String, SqlValue> {
+        let mut row = std::collections::HashMap::new();
+        row.insert("id".to_string(), SqlValue::Int(id));
+        row.insert(
+            "name".to_string(),
+            name.map_or(SqlValue::Null, |n| SqlValue::String(n.to_string())),
+        );
+        row
+    }
+
+    #[test]
+    fn test_build_insert_many() {
+        let rows = vec![bulk_row(1, Some("a")), bulk_row(2, None)];
+        let statements = QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("users")
+            .build_insert_many(&rows)
+            .unwrap();
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].0,
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES ($1, $2), ($3, NULL)"
+        );
+        assert_eq!(statements[0].1.len(), 3);
+
+        let mut mismatched = bulk_row(3, Some("c"));
+        mismatched.remove("name");
+        assert!(QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("users")
+            .build_insert_many(&[bulk_row(1, Some("a")), mismatched])
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_insert_many_respects_bind_limit() {
+        // 65535 / 2 columns = 32767 rows per statement
+        let rows: Vec<_> = (0..32768).map(|i| bulk_row(i, Some("x"))).collect();
+        let statements = QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("users")
+            .build_insert_many(&rows)
+            .unwrap();
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].1.len(), 65534);
+        assert_eq!(statements[1].1.len(), 2);
+        assert!(statements[1].0.ends_with("VALUES ($1, $2)"));
+    }
+
+    #[test]
+    fn test_build_upsert_many() {
+        let rows = vec![bulk_row(1, Some("a")), bulk_row(2, Some("b"))];
+
+        let statements = QueryBuilder::new(DatabaseBackend::SQLite)
+            .from("users")
+            .build_upsert_many(&rows, &["id"])
+            .unwrap();
+        assert_eq!(
+            statements[0].0,
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES (?, ?), (?, ?) \
+             ON CONFLICT(\"id\") DO UPDATE SET \"id\" = excluded.\"id\", \"name\" = excluded.\"name\""
+        );
+
+        let statements = QueryBuilder::new(DatabaseBackend::MySQL)
+            .from("users")
+            .build_upsert_many(&rows, &[])
+            .unwrap();
+        assert!(statements[0]
+            .0
+            .ends_with("ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `name` = VALUES(`name`)"));
+
+        assert!(QueryBuilder::new(DatabaseBackend::Postgres)
+            .from("users")
+            .build_upsert_many(&rows, &[])
+            .is_err());
+    }
 }
 
 // Support for HashMap (for JSON serialization)
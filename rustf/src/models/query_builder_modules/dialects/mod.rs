@@ -3,6 +3,8 @@
 //! This module contains database-specific SQL generation logic, separated
 //! by database type for better maintainability and extensibility.
 
+use crate::database::types::SqlValue;
+
 /// Database backend types supported by RustF
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DatabaseBackend {
@@ -37,6 +39,25 @@ pub enum QueryError {
     NotFound,
 }
 
+/// Full-text search request passed to the dialect
+#[derive(Clone, Debug)]
+pub struct FullTextSearch {
+    /// Columns to search (unquoted)
+    pub columns: Vec<String>,
+    /// User-supplied search text
+    pub query: String,
+    /// SQLite only: the table is an FTS5 virtual table, so `MATCH` is used
+    /// instead of the `LIKE` fallback
+    pub fts5: bool,
+}
+
+impl FullTextSearch {
+    /// Whitespace-separated words of the query
+    pub fn words(&self) -> Vec<&str> {
+        self.query.split_whitespace().collect()
+    }
+}
+
 /// Trait for database-specific SQL generation
 pub trait SqlDialect: Send + Sync {
     /// Quote an identifier (table name, column name) for this database
@@ -55,6 +76,16 @@ pub trait SqlDialect: Send + Sync {
     fn upsert_syntax(&self, table: &str, columns: &[String], conflict_columns: &[String])
         -> String;
 
+    /// Generate the conflict clause appended to an INSERT to turn it into an
+    /// upsert, updating every column from the proposed row
+    ///
+    /// Refers to the proposed values by column rather than by placeholder, so
+    /// it also works for multi-row inserts.
+    fn upsert_conflict_clause(&self, columns: &[String], conflict_columns: &[String]) -> String;
+
+    /// Maximum number of bind parameters in a single statement
+    fn max_bind_params(&self) -> usize;
+
     /// Get the current timestamp expression for this database
     fn current_timestamp(&self) -> &'static str;
 
@@ -64,6 +95,29 @@ pub trait SqlDialect: Send + Sync {
     /// Get the boolean type name for this database
     fn boolean_type(&self) -> &'static str;
 
+    /// Generate a full-text match predicate
+    ///
+    /// `table` is the searched table, or its alias when it has one, and
+    /// `next_param` the position of the first placeholder. Returns the SQL
+    /// fragment and the values to bind, in placeholder order.
+    fn full_text_predicate(
+        &self,
+        table: &str,
+        search: &FullTextSearch,
+        next_param: usize,
+    ) -> (String, Vec<SqlValue>);
+
+    /// Generate a relevance score expression for a full-text search
+    ///
+    /// Higher scores are better matches. Same arguments and return value as
+    /// `full_text_predicate`.
+    fn full_text_relevance(
+        &self,
+        table: &str,
+        search: &FullTextSearch,
+        next_param: usize,
+    ) -> (String, Vec<SqlValue>);
+
     /// Support for downcasting to specific dialect implementations
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -3,7 +3,8 @@
 //! This module contains MySQL and MariaDB-specific SQL generation logic,
 //! including handling of MySQL's specific syntax and limitations.
 
-use super::SqlDialect;
+use super::{FullTextSearch, SqlDialect};
+use crate::database::types::SqlValue;
 
 /// MySQL/MariaDB dialect
 pub struct MySQLDialect;
@@ -13,6 +14,25 @@ impl MySQLDialect {
     pub fn new() -> Self {
         Self
     }
+
+    /// `MATCH ... AGAINST` in natural language mode
+    ///
+    /// Requires a FULLTEXT index over exactly the searched columns. The same
+    /// expression serves as predicate and relevance score.
+    fn match_against(&self, search: &FullTextSearch) -> (String, Vec<SqlValue>) {
+        let columns: Vec<String> = search
+            .columns
+            .iter()
+            .map(|column| self.quote_identifier(column))
+            .collect();
+        (
+            format!(
+                "MATCH ({}) AGAINST (? IN NATURAL LANGUAGE MODE)",
+                columns.join(", ")
+            ),
+            vec![SqlValue::String(search.query.clone())],
+        )
+    }
 }
 
 impl Default for MySQLDialect {
@@ -54,13 +74,21 @@ impl SqlDialect for MySQLDialect {
         &self,
         table: &str,
         columns: &[String],
-        _conflict_columns: &[String],
+        conflict_columns: &[String],
     ) -> String {
         format!(
-            "INSERT INTO {} ({}) VALUES ({}) ON DUPLICATE KEY UPDATE {}",
+            "INSERT INTO {} ({}) VALUES ({}){}",
             table,
             columns.join(", "),
             vec!["?"; columns.len()].join(", "),
+            self.upsert_conflict_clause(columns, conflict_columns)
+        )
+    }
+
+    fn upsert_conflict_clause(&self, columns: &[String], _conflict_columns: &[String]) -> String {
+        // MySQL resolves conflicts on any unique key; the columns can't be named
+        format!(
+            " ON DUPLICATE KEY UPDATE {}",
             columns
                 .iter()
                 .map(|col| format!("{} = VALUES({})", col, col))
@@ -69,6 +97,11 @@ impl SqlDialect for MySQLDialect {
         )
     }
 
+    fn max_bind_params(&self) -> usize {
+        // Prepared statement placeholders are counted in 16 bits
+        65535
+    }
+
     fn current_timestamp(&self) -> &'static str {
         "CURRENT_TIMESTAMP()"
     }
@@ -81,6 +114,24 @@ impl SqlDialect for MySQLDialect {
         "TINYINT(1)"
     }
 
+    fn full_text_predicate(
+        &self,
+        _table: &str,
+        search: &FullTextSearch,
+        _next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        self.match_against(search)
+    }
+
+    fn full_text_relevance(
+        &self,
+        _table: &str,
+        search: &FullTextSearch,
+        _next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        self.match_against(search)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
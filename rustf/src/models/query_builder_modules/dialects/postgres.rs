@@ -3,7 +3,8 @@
 //! This module contains PostgreSQL-specific SQL generation logic,
 //! including support for PostgreSQL enums, arrays, JSONB, and other advanced features.
 
-use super::SqlDialect;
+use super::{FullTextSearch, SqlDialect};
+use crate::database::types::SqlValue;
 
 /// PostgreSQL dialect with advanced feature support
 pub struct PostgresDialect {
@@ -40,6 +41,18 @@ impl PostgresDialect {
             .get(&format!("{}.{}", table, column))
     }
 
+    /// Text search document covering the searched columns
+    ///
+    /// Create a matching expression index to keep searches fast:
+    /// `CREATE INDEX ... USING GIN (to_tsvector(concat_ws(' ', "title", "body")))`
+    fn text_search_document(&self, columns: &[String]) -> String {
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|column| self.quote_identifier(column))
+            .collect();
+        format!("to_tsvector(concat_ws(' ', {}))", columns.join(", "))
+    }
+
     /// Generate parameter placeholder with optional enum casting
     pub fn placeholder_with_enum_cast(&self, position: usize, enum_type: Option<&str>) -> String {
         match enum_type {
@@ -94,23 +107,34 @@ impl SqlDialect for PostgresDialect {
         conflict_columns: &[String],
     ) -> String {
         format!(
-            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO UPDATE SET {}",
+            "INSERT INTO {} ({}) VALUES ({}){}",
             table,
             columns.join(", "),
             (1..=columns.len())
                 .map(|i| format!("${}", i))
                 .collect::<Vec<_>>()
                 .join(", "),
+            self.upsert_conflict_clause(columns, conflict_columns)
+        )
+    }
+
+    fn upsert_conflict_clause(&self, columns: &[String], conflict_columns: &[String]) -> String {
+        format!(
+            " ON CONFLICT ({}) DO UPDATE SET {}",
             conflict_columns.join(", "),
             columns
                 .iter()
-                .enumerate()
-                .map(|(i, col)| format!("{} = ${}", col, i + 1))
+                .map(|col| format!("{} = EXCLUDED.{}", col, col))
                 .collect::<Vec<_>>()
                 .join(", ")
         )
     }
 
+    fn max_bind_params(&self) -> usize {
+        // Bind parameter numbers are 16-bit in the wire protocol
+        65535
+    }
+
     fn current_timestamp(&self) -> &'static str {
         "CURRENT_TIMESTAMP"
     }
@@ -123,6 +147,38 @@ impl SqlDialect for PostgresDialect {
         "BOOLEAN"
     }
 
+    fn full_text_predicate(
+        &self,
+        _table: &str,
+        search: &FullTextSearch,
+        next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        (
+            format!(
+                "{} @@ plainto_tsquery(${})",
+                self.text_search_document(&search.columns),
+                next_param
+            ),
+            vec![SqlValue::String(search.query.clone())],
+        )
+    }
+
+    fn full_text_relevance(
+        &self,
+        _table: &str,
+        search: &FullTextSearch,
+        next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        (
+            format!(
+                "ts_rank({}, plainto_tsquery(${}))",
+                self.text_search_document(&search.columns),
+                next_param
+            ),
+            vec![SqlValue::String(search.query.clone())],
+        )
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
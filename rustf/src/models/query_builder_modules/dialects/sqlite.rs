@@ -3,7 +3,8 @@
 //! This module contains SQLite-specific SQL generation logic,
 //! handling SQLite's specific syntax and capabilities.
 
-use super::SqlDialect;
+use super::{FullTextSearch, SqlDialect};
+use crate::database::types::SqlValue;
 
 /// SQLite dialect
 pub struct SQLiteDialect;
@@ -13,6 +14,42 @@ impl SQLiteDialect {
     pub fn new() -> Self {
         Self
     }
+
+    /// FTS5 query requiring every word, optionally restricted to `columns`
+    ///
+    /// Words are quoted as strings so user input cannot inject FTS5 query syntax.
+    fn fts5_query(search: &FullTextSearch) -> String {
+        let words: Vec<String> = search
+            .words()
+            .iter()
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect();
+
+        if search.columns.is_empty() {
+            words.join(" ")
+        } else {
+            format!("{{{}}} : ({})", search.columns.join(" "), words.join(" "))
+        }
+    }
+
+    /// `column LIKE ?` test for every (word, column) pair of a search
+    fn like_tests(&self, search: &FullTextSearch) -> Vec<(usize, String, SqlValue)> {
+        let mut tests = Vec::new();
+        for (index, word) in search.words().iter().enumerate() {
+            let escaped = word
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            for column in &search.columns {
+                tests.push((
+                    index,
+                    format!("{} LIKE ? ESCAPE '\\'", self.quote_identifier(column)),
+                    SqlValue::String(format!("%{}%", escaped)),
+                ));
+            }
+        }
+        tests
+    }
 }
 
 impl Default for SQLiteDialect {
@@ -59,10 +96,17 @@ impl SqlDialect for SQLiteDialect {
         conflict_columns: &[String],
     ) -> String {
         format!(
-            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) DO UPDATE SET {}",
+            "INSERT INTO {} ({}) VALUES ({}){}",
             table,
             columns.join(", "),
             vec!["?"; columns.len()].join(", "),
+            self.upsert_conflict_clause(columns, conflict_columns)
+        )
+    }
+
+    fn upsert_conflict_clause(&self, columns: &[String], conflict_columns: &[String]) -> String {
+        format!(
+            " ON CONFLICT({}) DO UPDATE SET {}",
             conflict_columns.join(", "),
             columns
                 .iter()
@@ -72,6 +116,11 @@ impl SqlDialect for SQLiteDialect {
         )
     }
 
+    fn max_bind_params(&self) -> usize {
+        // SQLITE_MAX_VARIABLE_NUMBER default since SQLite 3.32
+        32766
+    }
+
     fn current_timestamp(&self) -> &'static str {
         "CURRENT_TIMESTAMP"
     }
@@ -84,6 +133,66 @@ impl SqlDialect for SQLiteDialect {
         "INTEGER"
     }
 
+    fn full_text_predicate(
+        &self,
+        table: &str,
+        search: &FullTextSearch,
+        _next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        if search.words().is_empty() {
+            return ("1 = 0".to_string(), Vec::new());
+        }
+
+        if search.fts5 {
+            return (
+                format!("{} MATCH ?", self.quote_identifier(table)),
+                vec![SqlValue::String(Self::fts5_query(search))],
+            );
+        }
+
+        // LIKE fallback: every word must appear in at least one column
+        let mut groups: Vec<Vec<String>> = vec![Vec::new(); search.words().len()];
+        let mut params = Vec::new();
+        for (word, test, value) in self.like_tests(search) {
+            groups[word].push(test);
+            params.push(value);
+        }
+        let groups: Vec<String> = groups
+            .into_iter()
+            .map(|tests| format!("({})", tests.join(" OR ")))
+            .collect();
+
+        (groups.join(" AND "), params)
+    }
+
+    fn full_text_relevance(
+        &self,
+        table: &str,
+        search: &FullTextSearch,
+        _next_param: usize,
+    ) -> (String, Vec<SqlValue>) {
+        if search.fts5 {
+            // bm25() is lower for better matches
+            return (
+                format!("-bm25({})", self.quote_identifier(table)),
+                Vec::new(),
+            );
+        }
+
+        // LIKE fallback: number of (word, column) pairs that match
+        let (tests, params): (Vec<String>, Vec<SqlValue>) = self
+            .like_tests(search)
+            .into_iter()
+            .map(|(_, test, value)| (format!("({})", test), value))
+            .unzip();
+
+        if tests.is_empty() {
+            ("0".to_string(), Vec::new())
+        } else {
+            (format!("({})", tests.join(" + ")), params)
+        }
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
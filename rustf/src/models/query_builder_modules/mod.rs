@@ -11,7 +11,7 @@ pub mod schema;
 pub mod database;
 
 // Re-export the main types for backward compatibility
-pub use dialects::{DatabaseBackend, QueryError, SqlDialect};
+pub use dialects::{DatabaseBackend, FullTextSearch, QueryError, SqlDialect};
 pub use dialects::{MySQLDialect, PostgresDialect, SQLiteDialect};
 pub use schema::{CreateTableBuilder, SchemaBuilder};
 
@@ -20,7 +20,7 @@ pub use database::AnyDatabase;
 // Re-export commonly used types
 pub use core::{
     JoinClause, JoinType, OrderByClause, OrderDirection, QueryBuilder, WhereCondition,
-    WhereConnector,
+    WhereConnector, RELEVANCE_COLUMN,
 };
 
 // Re-export SqlValue from the unified type system
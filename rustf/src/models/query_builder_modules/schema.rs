@@ -23,6 +23,7 @@ impl SchemaBuilder {
             dialect: &self.dialect,
             backend: self.backend,
             table_name: table_name.to_string(),
+            if_not_exists: false,
             columns: Vec::new(),
             constraints: Vec::new(),
         }
@@ -33,6 +34,7 @@ pub struct CreateTableBuilder<'a> {
     dialect: &'a Box<dyn SqlDialect>,
     backend: DatabaseBackend,
     table_name: String,
+    if_not_exists: bool,
     columns: Vec<ColumnDefinition>,
     constraints: Vec<String>,
 }
@@ -104,10 +106,75 @@ impl<'a> CreateTableBuilder<'a> {
         self
     }
 
+    /// Add a 64-bit integer column
+    pub fn big_integer(mut self, name: &str) -> Self {
+        let data_type = match self.backend {
+            DatabaseBackend::SQLite => "INTEGER",
+            _ => "BIGINT",
+        };
+
+        self.columns.push(ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default: None,
+            constraints: Vec::new(),
+        });
+        self
+    }
+
+    /// Add a JSON column (JSONB on PostgreSQL, text on SQLite)
+    pub fn json(mut self, name: &str) -> Self {
+        let data_type = match self.backend {
+            DatabaseBackend::Postgres => "JSONB",
+            DatabaseBackend::MySQL | DatabaseBackend::MariaDB => "JSON",
+            DatabaseBackend::SQLite => "TEXT",
+        };
+
+        self.columns.push(ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default: None,
+            constraints: Vec::new(),
+        });
+        self
+    }
+
+    /// Make the last added column NOT NULL
+    pub fn not_null(mut self) -> Self {
+        if let Some(column) = self.columns.last_mut() {
+            column.nullable = false;
+        }
+        self
+    }
+
+    /// Add a primary key over the given columns
+    pub fn primary_key(mut self, columns: &[&str]) -> Self {
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|column| self.dialect.quote_identifier(column))
+            .collect();
+        self.constraints
+            .push(format!("PRIMARY KEY ({})", columns.join(", ")));
+        self
+    }
+
+    /// Skip creation when the table already exists
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
     /// Build the CREATE TABLE statement
     pub fn build(&self) -> String {
         let mut sql = format!(
-            "CREATE TABLE {} (\n",
+            "CREATE TABLE {}{} (\n",
+            if self.if_not_exists {
+                "IF NOT EXISTS "
+            } else {
+                ""
+            },
             self.dialect.quote_identifier(&self.table_name)
         );
 
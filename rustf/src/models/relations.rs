@@ -0,0 +1,415 @@
+//! Relation metadata and eager loading for models
+//!
+//! Generated models describe their `belongs_to`, `has_one` and `has_many`
+//! relations in `BaseModel::RELATIONS`. `ModelQuery::with()` uses them to
+//! load related rows up front: after the parent rows are fetched, each
+//! requested relation costs one `WHERE <key> IN (...)` query per
+//! [`PRELOAD_BATCH_SIZE`] parent keys.
+//!
+//! ```rust,ignore
+//! let users = Users::query()?
+//!     .where_eq("is_active", true)
+//!     .with("posts.comments")
+//!     .with("team")
+//!     .get_all_loaded()
+//!     .await?;
+//!
+//! for user in &users {
+//!     let posts: Vec<Posts> = user.many("posts")?;
+//!     let team: Option<Teams> = user.one("team")?;
+//! }
+//! ```
+//!
+//! Related rows are kept as JSON objects; nested relations appear as an
+//! extra key on them (each post above carries a `comments` array). They are
+//! read from the database the parent rows came from: the default one for
+//! `ModelQuery`, the repository's reader for `Repository::fetch_loaded`.
+
+use crate::database::adapter::DatabaseAdapter;
+use crate::database::types::SqlValue;
+use crate::db::DB;
+use crate::error::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Most keys bound to a single preload query
+///
+/// Larger sets are split, keeping each query well under the bind parameter
+/// limits of every backend.
+pub const PRELOAD_BATCH_SIZE: usize = 1000;
+
+/// How a related table is joined to the declaring one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    /// `local_field` holds the related row's `foreign_field` (one row)
+    BelongsTo,
+    /// The related row's `foreign_field` holds `local_field` (one row)
+    HasOne,
+    /// The related rows' `foreign_field` holds `local_field` (many rows)
+    HasMany,
+}
+
+/// A relation between two tables
+///
+/// Generated models list the relations of their own table along with those
+/// of every table reachable from it, so nested preloads can be resolved
+/// without knowing the related model types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relation {
+    /// Table declaring the relation
+    pub owner: &'static str,
+    /// Relation name used in `with()`
+    pub name: &'static str,
+    /// How the tables are joined
+    pub kind: RelationKind,
+    /// Related table
+    pub table: &'static str,
+    /// Column on the declaring table
+    pub local_field: &'static str,
+    /// Column on the related table
+    pub foreign_field: &'static str,
+}
+
+/// A model together with its preloaded relations
+#[derive(Debug, Clone)]
+pub struct Preloaded<T> {
+    /// The loaded model
+    pub model: T,
+    relations: HashMap<String, Value>,
+}
+
+impl<T> Preloaded<T> {
+    /// Raw value of a relation: an array for `has_many`, an object or null otherwise
+    pub fn related(&self, name: &str) -> Option<&Value> {
+        self.relations.get(name)
+    }
+
+    /// Deserialize a `has_many` relation
+    pub fn many<R: DeserializeOwned>(&self, name: &str) -> Result<Vec<R>> {
+        match self.relations.get(name) {
+            Some(value) => Ok(serde_json::from_value(value.clone())?),
+            None => Err(not_loaded(name)),
+        }
+    }
+
+    /// Deserialize a `belongs_to` or `has_one` relation
+    pub fn one<R: DeserializeOwned>(&self, name: &str) -> Result<Option<R>> {
+        match self.relations.get(name) {
+            Some(value) => Ok(serde_json::from_value(value.clone())?),
+            None => Err(not_loaded(name)),
+        }
+    }
+}
+
+/// Serializes as the model's fields plus one key per relation
+impl<T: Serialize> Serialize for Preloaded<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(&self.model).map_err(serde::ser::Error::custom)?;
+        if let Value::Object(fields) = &mut value {
+            for (name, related) in &self.relations {
+                fields.insert(name.clone(), related.clone());
+            }
+        }
+        value.serialize(serializer)
+    }
+}
+
+fn not_loaded(name: &str) -> Error {
+    Error::template(format!(
+        "Relation '{}' was not preloaded; add .with(\"{}\") to the query",
+        name, name
+    ))
+}
+
+/// Requested relations, e.g. `posts.comments` and `posts.tags` under `posts`
+#[derive(Debug, Default, PartialEq)]
+struct PreloadTree(BTreeMap<String, PreloadTree>);
+
+impl PreloadTree {
+    fn parse(paths: &[String]) -> Self {
+        let mut tree = Self::default();
+        for path in paths {
+            let mut node = &mut tree;
+            for name in path.split('.').map(str::trim).filter(|n| !n.is_empty()) {
+                node = node.0.entry(name.to_string()).or_default();
+            }
+        }
+        tree
+    }
+}
+
+/// Load the requested relations for `models` of `table`
+///
+/// Related rows are read through `db`, or the default database when `None`.
+pub(crate) async fn preload<T: Serialize>(
+    db: Option<&dyn DatabaseAdapter>,
+    table: &'static str,
+    relations: &'static [Relation],
+    models: Vec<T>,
+    paths: &[String],
+) -> Result<Vec<Preloaded<T>>> {
+    let tree = PreloadTree::parse(paths);
+    let mut rows = models
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    load(db, table, relations, &mut rows, &tree).await?;
+
+    Ok(models
+        .into_iter()
+        .zip(rows)
+        .map(|(model, mut row)| {
+            let relations = tree
+                .0
+                .keys()
+                .map(|name| {
+                    let value = row
+                        .as_object_mut()
+                        .and_then(|fields| fields.remove(name))
+                        .unwrap_or(Value::Null);
+                    (name.clone(), value)
+                })
+                .collect();
+            Preloaded { model, relations }
+        })
+        .collect())
+}
+
+type LoadFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Attach every relation in `tree` to `rows`, one query per relation and
+/// batch of keys
+fn load<'a>(
+    db: Option<&'a dyn DatabaseAdapter>,
+    owner: &'a str,
+    relations: &'static [Relation],
+    rows: &'a mut [Value],
+    tree: &'a PreloadTree,
+) -> LoadFuture<'a> {
+    Box::pin(async move {
+        for (name, nested) in &tree.0 {
+            let relation = relations
+                .iter()
+                .find(|r| r.owner == owner && r.name == name.as_str())
+                .ok_or_else(|| {
+                    Error::template(format!("Unknown relation '{}' on table {}", name, owner))
+                })?;
+
+            let keys = distinct_keys(rows, relation.local_field);
+            let mut children = Vec::new();
+            for batch in keys.chunks(PRELOAD_BATCH_SIZE) {
+                children.extend(fetch_related(db, relation, batch.to_vec()).await?);
+            }
+
+            if !nested.0.is_empty() {
+                load(db, relation.table, relations, &mut children, nested).await?;
+            }
+            attach(rows, relation, children);
+        }
+        Ok(())
+    })
+}
+
+/// Rows of `relation.table` whose foreign field is one of `keys`
+async fn fetch_related(
+    db: Option<&dyn DatabaseAdapter>,
+    relation: &Relation,
+    keys: Vec<SqlValue>,
+) -> Result<Vec<Value>> {
+    let query = match db {
+        Some(db) => db.query(),
+        None => DB::query()?,
+    };
+    let (sql, params) = query
+        .from(relation.table)
+        .where_in(relation.foreign_field, keys)
+        .build()
+        .map_err(|e| Error::template(format!("Query build failed: {}", e)))?;
+
+    match db {
+        Some(db) => db.fetch_all(&sql, params).await,
+        None => DB::fetch_all_with_params(&sql, params).await,
+    }
+}
+
+/// Distinct non-null values of `field` across `rows`
+fn distinct_keys(rows: &[Value], field: &str) -> Vec<SqlValue> {
+    let mut seen = HashMap::new();
+    for value in rows.iter().filter_map(|row| row.get(field)) {
+        if let Some(key) = key_of(value) {
+            seen.entry(key).or_insert_with(|| match value {
+                Value::Number(n) => n
+                    .as_i64()
+                    .map(SqlValue::from)
+                    .or_else(|| n.as_u64().map(SqlValue::from))
+                    .unwrap_or_else(|| SqlValue::from(n.as_f64().unwrap_or_default())),
+                Value::Bool(b) => SqlValue::from(*b),
+                other => SqlValue::from(key_of(other).unwrap_or_default()),
+            });
+        }
+    }
+    seen.into_values().collect()
+}
+
+/// Comparable form of a key column; numbers and strings read back from
+/// different drivers compare by their text
+fn key_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Put each child row under `relation.name` on the parents it belongs to
+fn attach(rows: &mut [Value], relation: &Relation, children: Vec<Value>) {
+    let mut by_key: HashMap<String, Vec<Value>> = HashMap::new();
+    for child in children {
+        if let Some(key) = child.get(relation.foreign_field).and_then(key_of) {
+            by_key.entry(key).or_default().push(child);
+        }
+    }
+
+    for row in rows.iter_mut() {
+        let matches = row
+            .get(relation.local_field)
+            .and_then(key_of)
+            .and_then(|key| by_key.get(&key));
+        let value = match relation.kind {
+            RelationKind::HasMany => Value::Array(matches.cloned().unwrap_or_default()),
+            RelationKind::BelongsTo | RelationKind::HasOne => matches
+                .and_then(|children| children.first().cloned())
+                .unwrap_or(Value::Null),
+        };
+        if let Value::Object(fields) = row {
+            fields.insert(relation.name.to_string(), value);
+        } else {
+            *row = Value::Object(Map::from_iter([(relation.name.to_string(), value)]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const POSTS: Relation = Relation {
+        owner: "users",
+        name: "posts",
+        kind: RelationKind::HasMany,
+        table: "posts",
+        local_field: "id",
+        foreign_field: "user_id",
+    };
+
+    const TEAM: Relation = Relation {
+        owner: "users",
+        name: "team",
+        kind: RelationKind::BelongsTo,
+        table: "teams",
+        local_field: "team_id",
+        foreign_field: "id",
+    };
+
+    #[test]
+    fn test_parse_nested_paths() {
+        let tree = PreloadTree::parse(&[
+            "posts.comments".to_string(),
+            "posts".to_string(),
+            "team".to_string(),
+        ]);
+        assert_eq!(tree.0.len(), 2);
+        assert!(tree.0["posts"].0.contains_key("comments"));
+        assert!(tree.0["team"].0.is_empty());
+    }
+
+    #[test]
+    fn test_attach_groups_children_by_key() {
+        let mut users = vec![
+            json!({"id": 1, "team_id": 10}),
+            json!({"id": 2, "team_id": null}),
+        ];
+        let keys = distinct_keys(&users, "team_id");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].to_sql_string(), "10");
+
+        // Keys read back as strings still match numeric ids
+        let posts = vec![
+            json!({"id": 5, "user_id": 1}),
+            json!({"id": 6, "user_id": "1"}),
+        ];
+        attach(&mut users, &POSTS, posts);
+        attach(&mut users, &TEAM, vec![json!({"id": 10, "name": "core"})]);
+
+        assert_eq!(users[0]["posts"].as_array().unwrap().len(), 2);
+        assert_eq!(users[1]["posts"], json!([]));
+        assert_eq!(users[0]["team"]["name"], "core");
+        assert_eq!(users[1]["team"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_preload_from_sqlite() {
+        use crate::database::adapters::SqliteAdapter;
+
+        const COMMENTS: Relation = Relation {
+            owner: "posts",
+            name: "comments",
+            kind: RelationKind::HasMany,
+            table: "comments",
+            local_field: "id",
+            foreign_field: "post_id",
+        };
+        const RELATIONS: &[Relation] = &[POSTS, TEAM, COMMENTS];
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = SqliteAdapter::from_pool("test", pool);
+        for sql in [
+            "CREATE TABLE teams (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER)",
+            "CREATE TABLE comments (id INTEGER PRIMARY KEY, post_id INTEGER)",
+            "INSERT INTO teams (id, name) VALUES (10, 'core')",
+            // One post per user, more users than fit in one batch
+            "WITH RECURSIVE n(id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < 2500)
+             INSERT INTO posts (id, user_id) SELECT id, id FROM n",
+            "INSERT INTO comments (id, post_id) VALUES (1, 1), (2, 1), (3, 2500)",
+        ] {
+            db.execute(sql, vec![]).await.unwrap();
+        }
+
+        let users: Vec<Value> = (1..=2500)
+            .map(|id| json!({"id": id, "team_id": if id == 1 { json!(10) } else { Value::Null }}))
+            .collect();
+        let loaded = preload(
+            Some(&db),
+            "users",
+            RELATIONS,
+            users,
+            &["posts.comments".to_string(), "team".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(loaded.len(), 2500);
+        assert!(loaded
+            .iter()
+            .all(|user| user.related("posts").unwrap().as_array().unwrap().len() == 1));
+        let first = loaded[0].related("posts").unwrap();
+        assert_eq!(first[0]["comments"].as_array().unwrap().len(), 2);
+        let last = loaded[2499].related("posts").unwrap();
+        assert_eq!(last[0]["user_id"], 2500);
+        assert_eq!(last[0]["comments"][0]["id"], 3);
+        assert_eq!(loaded[0].related("team").unwrap()["name"], "core");
+        assert_eq!(loaded[1].related("team"), Some(&Value::Null));
+    }
+}
@@ -0,0 +1,426 @@
+//! Generic data-access layer for models
+//!
+//! `Repository<T>` runs the usual CRUD operations for any `BaseModel` against
+//! an explicit database adapter instead of the global connection, which keeps
+//! data access out of the model structs:
+//!
+//! ```rust,ignore
+//! let users = Repository::<User>::new(DB::adapter("main").await?)
+//!     .with_replica(DB::adapter("main_replica").await?);
+//!
+//! let user = users.find(42).await?;
+//! let admins = users.find_by("role", "admin").await?;
+//! ```
+//!
+//! Reads go to the replica when one is set, writes always go to the primary.
+//! Models that declare `SOFT_DELETE_COLUMN` have deleted rows hidden from
+//! reads and `delete()` only stamps the column.
+
+use crate::database::adapter::DatabaseAdapter;
+use crate::database::types::SqlValue;
+use crate::error::{Error, Result};
+use crate::models::base_model::{next_version, BaseModel};
+use crate::models::model_query::ModelQuery;
+use crate::models::query_builder::{DatabaseBackend, OrderDirection};
+use crate::models::relations::{self, Preloaded};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// CRUD operations for model `T` on a specific database
+pub struct Repository<T> {
+    primary: Box<dyn DatabaseAdapter>,
+    replica: Option<Box<dyn DatabaseAdapter>>,
+    with_trashed: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: BaseModel> Repository<T> {
+    /// Create a repository reading and writing through `db`
+    pub fn new(db: Box<dyn DatabaseAdapter>) -> Self {
+        Self {
+            primary: db,
+            replica: None,
+            with_trashed: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Send reads to a read replica; writes still go to the primary
+    pub fn with_replica(mut self, replica: Box<dyn DatabaseAdapter>) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Include soft-deleted rows in reads
+    pub fn with_trashed(mut self) -> Self {
+        self.with_trashed = true;
+        self
+    }
+
+    /// Adapter used for reads
+    fn reader(&self) -> &dyn DatabaseAdapter {
+        self.replica.as_deref().unwrap_or(self.primary.as_ref())
+    }
+
+    /// Start a query on the model's table with the soft-delete scope applied
+    ///
+    /// Run it with [`fetch`](Self::fetch) so it executes on this repository's
+    /// database.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let recent = users
+    ///     .fetch(users.query().where_gt("created_at", since).limit(10))
+    ///     .await?;
+    /// ```
+    pub fn query(&self) -> ModelQuery<T> {
+        let mut builder = self.reader().query().from(T::TABLE_NAME);
+        if let (Some(column), false) = (T::SOFT_DELETE_COLUMN, self.with_trashed) {
+            builder = builder.where_null(column);
+        }
+        ModelQuery::from_query_builder(builder)
+    }
+
+    /// Execute a query built with [`query`](Self::query) and load the models
+    pub async fn fetch(&self, query: ModelQuery<T>) -> Result<Vec<T>> {
+        let (sql, params) = query
+            .to_sql()
+            .map_err(|e| Error::template(format!("Query build failed: {}", e)))?;
+
+        let rows = self
+            .reader()
+            .fetch_all(&sql, params)
+            .await
+            .map_err(|e| Error::database_query(e.to_string()))?;
+
+        let mut models = Vec::with_capacity(rows.len());
+        for row in rows {
+            models.push(Self::from_row(row).await?);
+        }
+        Ok(models)
+    }
+
+    /// Execute a query and load the relations requested with `with()`
+    ///
+    /// Related rows are read from this repository's database too.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let authors = users.fetch_loaded(users.query().with("posts")).await?;
+    /// ```
+    pub async fn fetch_loaded(&self, mut query: ModelQuery<T>) -> Result<Vec<Preloaded<T>>> {
+        let preloads = query.take_preloads();
+        let models = self.fetch(query).await?;
+        relations::preload(
+            Some(self.reader()),
+            T::TABLE_NAME,
+            T::RELATIONS,
+            models,
+            &preloads,
+        )
+        .await
+    }
+
+    /// Find a record by primary key
+    pub async fn find(&self, id: T::IdType) -> Result<Option<T>> {
+        let query = self.query().where_eq(T::PRIMARY_KEY, id).limit(1);
+        Ok(self.fetch(query).await?.into_iter().next())
+    }
+
+    /// Find all records where `column` equals `value`
+    pub async fn find_by<V: Into<SqlValue>>(&self, column: &str, value: V) -> Result<Vec<T>> {
+        self.fetch(self.query().where_eq(column, value)).await
+    }
+
+    /// Load every record of the table
+    pub async fn all(&self) -> Result<Vec<T>> {
+        self.fetch(self.query()).await
+    }
+
+    /// Load one page of records (pages start at 1)
+    ///
+    /// Returns the records together with the total number of records, which
+    /// is what `utils::pagination::Pagination::new` expects.
+    pub async fn paginate(&self, page: u32, per_page: u32) -> Result<(Vec<T>, i64)> {
+        let total = self.count().await?;
+        let records = self
+            .fetch(
+                self.query()
+                    .order_by(T::PRIMARY_KEY, OrderDirection::Asc)
+                    .paginate(page, per_page),
+            )
+            .await?;
+        Ok((records, total))
+    }
+
+    /// Count the records visible to this repository
+    pub async fn count(&self) -> Result<i64> {
+        let (sql, params) = self
+            .query()
+            .into_query_builder()
+            .count()
+            .build()
+            .map_err(|e| Error::template(format!("Count query build failed: {}", e)))?;
+
+        let row = self
+            .reader()
+            .fetch_one(&sql, params)
+            .await
+            .map_err(|e| Error::database_query(e.to_string()))?;
+
+        Ok(row.as_ref().and_then(count_from_row).unwrap_or(0))
+    }
+
+    /// Insert a record and return it as stored
+    ///
+    /// A NULL primary key is left out so the database can generate it.
+    /// The stored row is read back from the primary, so generated columns
+    /// and defaults are filled in.
+    pub async fn create(&self, model: &T) -> Result<T> {
+        let fields = serde_json::to_value(model)
+            .map_err(|e| Error::internal(format!("Failed to serialize model: {}", e)))?;
+        let columns = fields
+            .as_object()
+            .ok_or_else(|| Error::internal("Model must serialize to an object".to_string()))?;
+
+        let mut data = HashMap::new();
+        for column in columns.keys() {
+            let value = model.get_field_value(column)?;
+            if column == T::PRIMARY_KEY && value.is_null() {
+                continue;
+            }
+            data.insert(column.clone(), value);
+        }
+
+        let returns_row = matches!(
+            self.primary.backend(),
+            DatabaseBackend::Postgres | DatabaseBackend::SQLite
+        );
+        let mut builder = self.primary.query().from(T::TABLE_NAME);
+        if returns_row {
+            builder = builder.returning(vec!["*"]);
+        }
+        let (sql, params) = builder
+            .build_insert(&data)
+            .map_err(|e| Error::template(format!("Failed to build insert query: {}", e)))?;
+
+        // Keep the adapter's error (e.g. a unique violation) under the context
+        let failed = |e: Error| e.with_context(format!("Failed to insert into {}", T::TABLE_NAME));
+        let row = if returns_row {
+            self.primary.fetch_one(&sql, params).await.map_err(failed)?
+        } else {
+            let result = self.primary.execute(&sql, params).await.map_err(failed)?;
+            let id = match data.get(T::PRIMARY_KEY) {
+                Some(id) => id.clone(),
+                None => SqlValue::BigInt(result.last_insert_id.ok_or_else(|| {
+                    Error::database_query("Insert did not report a generated id".to_string())
+                })?),
+            };
+            let (sql, params) = self
+                .primary
+                .query()
+                .from(T::TABLE_NAME)
+                .where_eq(T::PRIMARY_KEY, id)
+                .limit(1)
+                .build()
+                .map_err(|e| Error::template(format!("Query build failed: {}", e)))?;
+            self.primary
+                .fetch_one(&sql, params)
+                .await
+                .map_err(|e| Error::database_query(e.to_string()))?
+        };
+
+        T::invalidate_query_cache();
+        let row = row.ok_or_else(|| {
+            Error::database_query(format!(
+                "Inserted {} row could not be read back",
+                T::TABLE_NAME
+            ))
+        })?;
+        let created = Self::from_row(row).await?;
+        T::after_write(
+            "create",
+            Some(created.id().to_string()),
+            serde_json::json!({}),
+        )
+        .await;
+        Ok(created)
+    }
+
+    /// Save the changed fields of a record
+    ///
+    /// Honours the model's `VERSION_COLUMN` the same way `BaseModel::update`
+    /// does and never touches soft-deleted rows.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The row was updated (or there was nothing to change)
+    /// * `Ok(false)` - No visible row has this primary key
+    /// * `Err(Error::StaleModel)` - The row was modified since it was loaded
+    pub async fn update(&self, model: &mut T) -> Result<bool> {
+        if !model.has_changes() {
+            return Ok(true);
+        }
+
+        let mut data = HashMap::new();
+        for field in model.changed_fields() {
+            let value = if model.is_null(&field) {
+                SqlValue::Null
+            } else {
+                model.get_field_value(&field)?
+            };
+            data.insert(field, value);
+        }
+
+        let mut builder = self
+            .primary
+            .query()
+            .from(T::TABLE_NAME)
+            .where_eq(T::PRIMARY_KEY, model.id());
+        if let Some(column) = T::SOFT_DELETE_COLUMN {
+            builder = builder.where_null(column);
+        }
+
+        let mut bumped_version = None;
+        if let Some(column) = T::VERSION_COLUMN {
+            let current = model.get_field_value(column)?;
+            let (version, next) = next_version(&current).ok_or_else(|| {
+                Error::internal(format!(
+                    "Version column '{}' of {} must hold an integer",
+                    column,
+                    T::TABLE_NAME
+                ))
+            })?;
+            data.insert(column.to_string(), next);
+            builder = builder.where_eq(column, current);
+            bumped_version = Some(version);
+        }
+
+        let (sql, params) = builder
+            .build_update(&data)
+            .map_err(|e| Error::template(format!("Failed to build update query: {}", e)))?;
+        let result = self
+            .primary
+            .execute(&sql, params)
+            .await
+            .map_err(|e| e.with_context(format!("Failed to update {}", T::TABLE_NAME)))?;
+
+        if result.rows_affected == 0 {
+            if bumped_version.is_some() {
+                return Err(Error::stale_model(format!(
+                    "{} with {} = {} was modified or deleted since it was loaded",
+                    T::TABLE_NAME,
+                    T::PRIMARY_KEY,
+                    model.id()
+                )));
+            }
+            return Ok(false);
+        }
+
+        T::invalidate_query_cache();
+        if let Some(version) = bumped_version {
+            model.set_version(version);
+        }
+        let mut fields: Vec<&String> = data.keys().collect();
+        fields.sort();
+        T::after_write(
+            "update",
+            Some(model.id().to_string()),
+            serde_json::json!({ "fields": fields }),
+        )
+        .await;
+        model.clear_changes();
+        Ok(true)
+    }
+
+    /// Delete a record by primary key
+    ///
+    /// Soft-deletable models get their `SOFT_DELETE_COLUMN` set to the current
+    /// time; other rows are removed. Returns whether a row was deleted.
+    pub async fn delete(&self, id: T::IdType) -> Result<bool> {
+        let Some(column) = T::SOFT_DELETE_COLUMN else {
+            return self.force_delete(id).await;
+        };
+
+        let target = id.to_string();
+        let data = HashMap::from([(column.to_string(), SqlValue::from(chrono::Utc::now()))]);
+        let (sql, params) = self
+            .primary
+            .query()
+            .from(T::TABLE_NAME)
+            .where_eq(T::PRIMARY_KEY, id)
+            .where_null(column)
+            .build_update(&data)
+            .map_err(|e| Error::template(format!("Failed to build delete query: {}", e)))?;
+
+        self.execute_write(&sql, params, "delete", target).await
+    }
+
+    /// Remove a record by primary key, even for soft-deletable models
+    pub async fn force_delete(&self, id: T::IdType) -> Result<bool> {
+        let target = id.to_string();
+        let (sql, params) = self
+            .primary
+            .query()
+            .from(T::TABLE_NAME)
+            .where_eq(T::PRIMARY_KEY, id)
+            .build_delete()
+            .map_err(|e| Error::template(format!("Failed to build delete query: {}", e)))?;
+
+        self.execute_write(&sql, params, "force_delete", target)
+            .await
+    }
+
+    async fn execute_write(
+        &self,
+        sql: &str,
+        params: Vec<SqlValue>,
+        operation: &str,
+        target: String,
+    ) -> Result<bool> {
+        let context = format!("Failed to {} {}", operation, T::TABLE_NAME);
+        let result = self
+            .primary
+            .execute(sql, params)
+            .await
+            .map_err(|e| e.with_context(context))?;
+        if result.rows_affected > 0 {
+            T::invalidate_query_cache();
+            T::after_write(operation, Some(target), serde_json::json!({})).await;
+        }
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn from_row(row: serde_json::Value) -> Result<T> {
+        T::from_row_data(row)
+            .await
+            .map_err(|e| Error::database_query(format!("Failed to load {}: {}", T::TABLE_NAME, e)))
+    }
+}
+
+/// Read the value of a `COUNT(*)` row
+fn count_from_row(row: &serde_json::Value) -> Option<i64> {
+    let value = match row {
+        serde_json::Value::Object(map) if map.len() == 1 => map.values().next()?,
+        serde_json::Value::Object(map) => map.get("count")?,
+        other => other,
+    };
+    match value {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_count_from_row() {
+        assert_eq!(count_from_row(&json!({"count": 12})), Some(12));
+        assert_eq!(count_from_row(&json!({"COUNT(*)": "7"})), Some(7));
+        assert_eq!(count_from_row(&json!(3)), Some(3));
+        assert_eq!(count_from_row(&json!({"a": 1, "b": 2})), None);
+    }
+}
@@ -0,0 +1,361 @@
+//! Plugin system for RustF
+//!
+//! A plugin packages reusable functionality (routes, middleware, event
+//! handlers, workers) behind a single `app.plugin(...)` call, and can hook into
+//! application startup and shutdown.
+//!
+//! ```rust,ignore
+//! use rustf::plugins::Plugin;
+//!
+//! struct AdminPlugin;
+//!
+//! #[async_trait::async_trait]
+//! impl Plugin for AdminPlugin {
+//!     fn name(&self) -> &'static str {
+//!         "admin"
+//!     }
+//!
+//!     fn dependencies(&self) -> Vec<&'static str> {
+//!         vec!["auth"]
+//!     }
+//!
+//!     fn install(&self, app: &mut RustF) {
+//!         app.add_routes(routes![GET "/admin" => admin::dashboard]);
+//!         app.middleware_mut()
+//!             .register_inbound("admin_guard", AdminGuard::new());
+//!     }
+//!
+//!     async fn on_startup(&self) -> Result<()> {
+//!         log::info!("Admin panel mounted at /admin");
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let app = RustF::new().plugin(AuthPlugin::new()).plugin(AdminPlugin);
+//! ```
+
+use crate::app::RustF;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A bundle of functionality installed into the application
+#[async_trait]
+pub trait Plugin: Send + Sync + 'static {
+    /// Unique plugin name, used to resolve dependencies
+    fn name(&self) -> &'static str;
+
+    /// Names of plugins that must also be installed
+    ///
+    /// Dependencies start before this plugin and shut down after it.
+    fn dependencies(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Register routes, middleware, events and workers
+    ///
+    /// Called once, as soon as the plugin and all of its dependencies have
+    /// been added with `RustF::plugin`, so dependencies are installed first.
+    fn install(&self, app: &mut RustF);
+
+    /// Called during startup, after the database and shared modules are ready
+    ///
+    /// An error aborts startup.
+    async fn on_startup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called during graceful shutdown, before workers and modules stop
+    async fn on_shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Registry of installed plugins
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn Plugin>>,
+    installed: HashSet<&'static str>,
+}
+
+impl PluginRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a plugin; returns false if one with the same name is already registered
+    pub fn add(&mut self, plugin: Arc<dyn Plugin>) -> bool {
+        if self.contains(plugin.name()) {
+            return false;
+        }
+        self.plugins.push(plugin);
+        true
+    }
+
+    /// Check whether a plugin is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.plugins.iter().any(|plugin| plugin.name() == name)
+    }
+
+    /// Names of registered plugins, in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    /// Check whether no plugins are registered
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Next plugin whose dependencies are all installed, marked as installed
+    ///
+    /// Plugins waiting for a dependency that was never added stay uninstalled;
+    /// `startup_order` reports them when the application starts.
+    pub(crate) fn next_to_install(&mut self) -> Option<Arc<dyn Plugin>> {
+        let installed = &self.installed;
+        let plugin = self
+            .plugins
+            .iter()
+            .find(|plugin| {
+                !installed.contains(plugin.name())
+                    && plugin
+                        .dependencies()
+                        .iter()
+                        .all(|dependency| installed.contains(dependency))
+            })?
+            .clone();
+        self.installed.insert(plugin.name());
+        Some(plugin)
+    }
+
+    /// Plugins ordered so that every plugin comes after its dependencies
+    ///
+    /// Registration order is kept where dependencies allow. Fails on missing
+    /// dependencies and dependency cycles.
+    pub fn startup_order(&self) -> Result<Vec<Arc<dyn Plugin>>> {
+        for plugin in &self.plugins {
+            for dependency in plugin.dependencies() {
+                if !self.contains(dependency) {
+                    return Err(Error::internal(format!(
+                        "Plugin '{}' depends on '{}', which is not installed",
+                        plugin.name(),
+                        dependency
+                    )));
+                }
+            }
+        }
+
+        let mut ordered: Vec<Arc<dyn Plugin>> = Vec::with_capacity(self.plugins.len());
+        let mut started: HashSet<&'static str> = HashSet::new();
+
+        while ordered.len() < self.plugins.len() {
+            let ready = self.plugins.iter().find(|plugin| {
+                !started.contains(plugin.name())
+                    && plugin
+                        .dependencies()
+                        .iter()
+                        .all(|dependency| started.contains(dependency))
+            });
+
+            let Some(plugin) = ready else {
+                let pending: Vec<&str> = self
+                    .names()
+                    .into_iter()
+                    .filter(|name| !started.contains(name))
+                    .collect();
+                return Err(Error::internal(format!(
+                    "Plugin dependency cycle between: {}",
+                    pending.join(", ")
+                )));
+            };
+
+            started.insert(plugin.name());
+            ordered.push(plugin.clone());
+        }
+
+        Ok(ordered)
+    }
+
+    /// Run every plugin's `on_startup` hook in dependency order
+    ///
+    /// If a hook fails, the plugins that already started are shut down in
+    /// reverse order before the error is returned.
+    pub async fn start_all(&self) -> Result<()> {
+        let order = self.startup_order()?;
+        for (started, plugin) in order.iter().enumerate() {
+            log::debug!("Starting plugin: {}", plugin.name());
+            if let Err(e) = plugin.on_startup().await {
+                Self::shutdown(&order[..started]).await;
+                return Err(Error::internal(format!(
+                    "Failed to start plugin '{}': {}",
+                    plugin.name(),
+                    e
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run every plugin's `on_shutdown` hook in reverse dependency order
+    ///
+    /// Errors are logged so that every plugin gets a chance to shut down.
+    pub async fn shutdown_all(&self) {
+        let order = self
+            .startup_order()
+            .unwrap_or_else(|_| self.plugins.clone());
+        Self::shutdown(&order).await;
+    }
+
+    async fn shutdown(plugins: &[Arc<dyn Plugin>]) {
+        for plugin in plugins.iter().rev() {
+            log::debug!("Shutting down plugin: {}", plugin.name());
+            if let Err(e) = plugin.on_shutdown().await {
+                log::warn!("Error shutting down plugin '{}': {}", plugin.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct TestPlugin {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+        log: Arc<Mutex<Vec<String>>>,
+        fail_startup: bool,
+    }
+
+    impl TestPlugin {
+        fn new(
+            name: &'static str,
+            dependencies: Vec<&'static str>,
+            log: &Arc<Mutex<Vec<String>>>,
+        ) -> Self {
+            Self {
+                name,
+                dependencies,
+                log: log.clone(),
+                fail_startup: false,
+            }
+        }
+
+        fn shared(
+            name: &'static str,
+            dependencies: Vec<&'static str>,
+            log: &Arc<Mutex<Vec<String>>>,
+        ) -> Arc<dyn Plugin> {
+            Arc::new(Self::new(name, dependencies, log))
+        }
+
+        fn failing(name: &'static str, log: &Arc<Mutex<Vec<String>>>) -> Arc<dyn Plugin> {
+            Arc::new(Self {
+                name,
+                dependencies: Vec::new(),
+                log: log.clone(),
+                fail_startup: true,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Plugin for TestPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+
+        fn install(&self, _app: &mut RustF) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("install {}", self.name));
+        }
+
+        async fn on_startup(&self) -> Result<()> {
+            if self.fail_startup {
+                return Err(Error::internal("boom"));
+            }
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("start {}", self.name));
+            Ok(())
+        }
+
+        async fn on_shutdown(&self) -> Result<()> {
+            self.log.lock().unwrap().push(format!("stop {}", self.name));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_start_first_and_stop_last() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = PluginRegistry::new();
+        registry.add(TestPlugin::shared("admin", vec!["auth"], &log));
+        registry.add(TestPlugin::shared("auth", vec![], &log));
+        assert!(!registry.add(TestPlugin::shared("auth", vec![], &log)));
+
+        registry.start_all().await.unwrap();
+        registry.shutdown_all().await;
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["start auth", "start admin", "stop admin", "stop auth"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_startup_stops_started_plugins() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = PluginRegistry::new();
+        registry.add(TestPlugin::shared("auth", vec![], &log));
+        registry.add(TestPlugin::shared("admin", vec!["auth"], &log));
+        registry.add(TestPlugin::failing("billing", &log));
+
+        let error = registry.start_all().await.err().unwrap();
+        assert!(error.to_string().contains("'billing'"));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["start auth", "start admin", "stop admin", "stop auth"]
+        );
+    }
+
+    #[test]
+    fn test_install_in_dependency_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        RustF::new()
+            .plugin(TestPlugin::new("admin", vec!["auth"], &log))
+            .plugin(TestPlugin::new("audit", vec![], &log))
+            .plugin(TestPlugin::new("auth", vec!["audit"], &log));
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["install audit", "install auth", "install admin"]
+        );
+    }
+
+    #[test]
+    fn test_missing_dependency_and_cycle() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut registry = PluginRegistry::new();
+        registry.add(TestPlugin::shared("admin", vec!["auth"], &log));
+        let error = registry.startup_order().err().unwrap();
+        assert!(error.to_string().contains("depends on 'auth'"));
+
+        let mut registry = PluginRegistry::new();
+        registry.add(TestPlugin::shared("a", vec!["b"], &log));
+        registry.add(TestPlugin::shared("b", vec!["a"], &log));
+        let error = registry.startup_order().err().unwrap();
+        assert!(error.to_string().contains("cycle between: a, b"));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod macros;
+pub mod openapi;
 pub mod router;
 pub mod trie;
 
@@ -17,6 +18,12 @@ pub struct Route {
     pub path: String,
     pub handler: RouteHandler,
     pub xhr_only: bool,
+    /// Body size limit replacing `server.max_body_size` for this route
+    pub max_body_size: Option<usize>,
+    /// Short summary of the route in the OpenAPI document
+    pub summary: Option<String>,
+    /// Longer description of the route in the OpenAPI document
+    pub description: Option<String>,
 }
 
 impl Route {
@@ -26,6 +33,9 @@ impl Route {
             path: path.to_string(),
             handler,
             xhr_only: false,
+            max_body_size: None,
+            summary: None,
+            description: None,
         }
     }
 
@@ -51,8 +61,34 @@ impl Route {
             path: path.to_string(),
             handler,
             xhr_only: true,
+            max_body_size: None,
+            summary: None,
+            description: None,
         }
     }
+
+    /// Accept request bodies up to `bytes` on this route, instead of
+    /// `server.max_body_size` (0 means no limit)
+    ///
+    /// ```rust,ignore
+    /// Route::post("/videos", upload_video).with_max_body_size(2 * 1024 * 1024 * 1024)
+    /// ```
+    pub fn with_max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = Some(bytes);
+        self
+    }
+
+    /// Set the summary of this route in the OpenAPI document
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+
+    /// Set the description of this route in the OpenAPI document
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
 }
 
 // Utility macro for creating routes
@@ -0,0 +1,312 @@
+//! OpenAPI 3.0 document generated from the registered routes
+//!
+//! Each route becomes an operation under its path, with one path parameter
+//! per `{...}` segment, typed from its constraint (`{id:int}` is an `int64`
+//! integer, `{id:uuid}` a `uuid` string, a regex becomes the schema
+//! `pattern`); routes differing only by constraints are merged into one
+//! operation with `oneOf` parameter schemas. Request and response payloads
+//! are not described: every operation gets a generic 200 and the framework's
+//! JSON error envelope as its default response.
+
+use serde_json::{json, Map, Value};
+
+/// Route as registered, with its optional documentation
+#[derive(Clone, Debug)]
+pub struct RouteDoc {
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Operations an OpenAPI path item can hold
+const OPERATIONS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Build the OpenAPI document for `routes`
+pub fn document(routes: &[RouteDoc], title: &str, version: &str) -> Value {
+    let mut paths = Map::new();
+
+    for route in routes {
+        let (path, parameters) = path_template(&route.path);
+        // XHR routes answer both GET and POST
+        let methods = if route.method == "XHR" {
+            vec!["get".to_string(), "post".to_string()]
+        } else {
+            vec![route.method.to_lowercase()]
+        };
+
+        for method in methods {
+            if !OPERATIONS.contains(&method.as_str()) {
+                log::debug!(
+                    "OpenAPI: skipping {} {}, not an OpenAPI operation",
+                    route.method,
+                    route.path
+                );
+                continue;
+            }
+
+            let mut operation = Map::new();
+            if let Some(summary) = &route.summary {
+                operation.insert("summary".to_string(), json!(summary));
+            }
+            if let Some(description) = &route.description {
+                operation.insert("description".to_string(), json!(description));
+            }
+            if !parameters.is_empty() {
+                operation.insert("parameters".to_string(), json!(parameters));
+            }
+            operation.insert(
+                "responses".to_string(),
+                json!({
+                    "200": { "description": "Successful response" },
+                    "default": {
+                        "description": "Error",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Error" }
+                            }
+                        }
+                    }
+                }),
+            );
+
+            let Value::Object(item) = paths.entry(path.clone()).or_insert_with(|| json!({})) else {
+                continue;
+            };
+            match item.get_mut(&method) {
+                // Routes differing only by constraints share one operation
+                Some(Value::Object(existing)) => {
+                    log::debug!(
+                        "OpenAPI: {} {} shares {} {} with another route; merging its parameters",
+                        route.method,
+                        route.path,
+                        method.to_uppercase(),
+                        path
+                    );
+                    merge_operation(existing, operation);
+                }
+                _ => {
+                    item.insert(method, Value::Object(operation));
+                }
+            }
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": title, "version": version },
+        "paths": paths,
+        "components": {
+            "schemas": {
+                "Error": {
+                    "type": "object",
+                    "properties": {
+                        "error": {
+                            "type": "object",
+                            "properties": {
+                                "code": { "type": "string" },
+                                "message": { "type": "string" },
+                                "request_id": { "type": "string", "nullable": true }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Fold an operation into one already documented for the same path and method
+///
+/// Path parameters whose schemas differ become a `oneOf` of both schemas, and
+/// a summary or description is only taken when the first route had none.
+fn merge_operation(existing: &mut Map<String, Value>, operation: Map<String, Value>) {
+    for key in ["summary", "description"] {
+        if let (None, Some(value)) = (existing.get(key), operation.get(key)) {
+            existing.insert(key.to_string(), value.clone());
+        }
+    }
+
+    let (Some(Value::Array(parameters)), Some(Value::Array(incoming))) =
+        (existing.get_mut("parameters"), operation.get("parameters"))
+    else {
+        return;
+    };
+    for (parameter, other) in parameters.iter_mut().zip(incoming) {
+        let schema = &other["schema"];
+        match parameter.get_mut("schema") {
+            Some(current) if current == schema => {}
+            Some(Value::Object(current)) if current.contains_key("oneOf") => {
+                if let Some(Value::Array(variants)) = current.get_mut("oneOf") {
+                    if !variants.contains(schema) {
+                        variants.push(schema.clone());
+                    }
+                }
+            }
+            Some(current) => *current = json!({ "oneOf": [current.clone(), schema.clone()] }),
+            None => {}
+        }
+    }
+}
+
+/// OpenAPI path template of a route path, and its path parameters
+///
+/// Constraints are dropped from the template (`/users/{id:int}` becomes
+/// `/users/{id}`) and catch-all segments become a plain parameter, since
+/// OpenAPI cannot express a parameter spanning several segments. An unnamed
+/// `*` catch-all becomes the parameter `wildcard`.
+fn path_template(path: &str) -> (String, Vec<Value>) {
+    let mut template = String::new();
+    let mut parameters = Vec::new();
+
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        template.push('/');
+
+        let inner = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(inner) => inner,
+            None if segment == "*" => "*wildcard",
+            None => {
+                template.push_str(segment);
+                continue;
+            }
+        };
+        let (name, schema) = match inner.strip_prefix('*') {
+            Some(name) => (name, json!({ "type": "string" })),
+            None => match inner.split_once(':') {
+                Some((name, constraint)) => (name, constraint_schema(constraint)),
+                None => (inner, json!({ "type": "string" })),
+            },
+        };
+
+        template.push_str(&format!("{{{}}}", name));
+        parameters.push(json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "schema": schema,
+        }));
+    }
+
+    if template.is_empty() {
+        template.push('/');
+    }
+    (template, parameters)
+}
+
+/// Schema of a parameter constraint (`int`, `uuid` or a regex)
+fn constraint_schema(constraint: &str) -> Value {
+    match constraint {
+        "int" => json!({ "type": "integer", "format": "int64" }),
+        "uuid" => json!({ "type": "string", "format": "uuid" }),
+        _ => {
+            let pattern = constraint
+                .strip_prefix("regex(")
+                .and_then(|s| s.strip_suffix(')'))
+                .unwrap_or(constraint);
+            json!({ "type": "string", "pattern": format!("^(?:{})$", pattern) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::Context;
+    use crate::error::Result;
+    use crate::routing::{Route, Router};
+    use std::future::Future;
+    use std::pin::Pin;
+
+    fn mock_handler(_ctx: &mut Context) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    #[test]
+    fn test_document_paths_and_parameters() {
+        let mut router = Router::new();
+        router.add_route(Route::get("/", mock_handler));
+        router.add_route(Route::get("/users", mock_handler).with_summary("List users"));
+        router.add_route(
+            Route::get("/users/{id:int}", mock_handler)
+                .with_summary("Show a user")
+                .with_description("Answers 404 when no user has the id"),
+        );
+        router.add_route(Route::delete("/users/{id:int}", mock_handler));
+        router.add_route(Route::get("/orders/{id:uuid}/lines/{line}", mock_handler));
+        router.add_route(Route::get("/files/{*path}", mock_handler));
+        router.add_route(Route::xhr("/search", mock_handler));
+
+        let document = router.openapi("Shop", "1.2.0");
+        assert_eq!(document["openapi"], "3.0.3");
+        assert_eq!(document["info"]["title"], "Shop");
+        assert_eq!(document["info"]["version"], "1.2.0");
+
+        let paths = &document["paths"];
+        assert!(paths["/"]["get"].is_object());
+        assert_eq!(paths["/users"]["get"]["summary"], "List users");
+        assert!(paths["/users"]["get"].get("parameters").is_none());
+
+        let user = &paths["/users/{id}"];
+        assert_eq!(user["get"]["summary"], "Show a user");
+        assert_eq!(
+            user["get"]["description"],
+            "Answers 404 when no user has the id"
+        );
+        assert!(user["delete"].is_object());
+        let id = &user["get"]["parameters"][0];
+        assert_eq!(id["name"], "id");
+        assert_eq!(id["in"], "path");
+        assert_eq!(id["required"], true);
+        assert_eq!(id["schema"]["type"], "integer");
+        assert_eq!(id["schema"]["format"], "int64");
+
+        let lines = &paths["/orders/{id}/lines/{line}"]["get"]["parameters"];
+        assert_eq!(lines[0]["schema"]["format"], "uuid");
+        assert_eq!(lines[1]["name"], "line");
+        assert_eq!(lines[1]["schema"]["type"], "string");
+
+        assert_eq!(
+            paths["/files/{path}"]["get"]["parameters"][0]["name"],
+            "path"
+        );
+        assert!(paths["/search"]["get"].is_object());
+        assert!(paths["/search"]["post"].is_object());
+        assert_eq!(
+            paths["/users"]["get"]["responses"]["default"]["content"]["application/json"]["schema"]
+                ["$ref"],
+            "#/components/schemas/Error"
+        );
+    }
+
+    #[test]
+    fn test_routes_differing_by_constraint_and_bare_wildcard() {
+        let mut router = Router::new();
+        router.add_route(Route::get("/users/{id:int}", mock_handler).with_summary("By id"));
+        router.add_route(Route::get("/users/{id:uuid}", mock_handler));
+        router.add_route(Route::get("/users/{id:regex([a-z]+)}", mock_handler));
+        router.add_route(Route::get("/assets/*", mock_handler));
+
+        let document = router.openapi("Shop", "1.0.0");
+        let user = &document["paths"]["/users/{id}"]["get"];
+        assert_eq!(user["summary"], "By id");
+        let variants = user["parameters"][0]["schema"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 3);
+        assert_eq!(variants[0]["format"], "int64");
+        assert_eq!(variants[1]["format"], "uuid");
+        assert_eq!(variants[2]["pattern"], "^(?:[a-z]+)$");
+
+        let assets = &document["paths"]["/assets/{wildcard}"]["get"];
+        assert_eq!(assets["parameters"][0]["name"], "wildcard");
+        assert_eq!(assets["parameters"][0]["in"], "path");
+    }
+
+    #[test]
+    fn test_regex_constraint_schema() {
+        assert_eq!(
+            super::constraint_schema("regex([a-z]{2})")["pattern"],
+            "^(?:[a-z]{2})$"
+        );
+        assert_eq!(super::constraint_schema("\\d+")["pattern"], "^(?:\\d+)$");
+    }
+}
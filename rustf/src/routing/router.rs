@@ -1,3 +1,4 @@
+use super::openapi::{self, RouteDoc};
 use super::trie::{RouteInfo, TrieRouter};
 use super::Route;
 use std::collections::HashMap;
@@ -5,6 +6,8 @@ use std::collections::HashMap;
 pub struct Router {
     trie: TrieRouter,
     route_count: usize,
+    /// Registered routes, in registration order, for the OpenAPI document
+    docs: Vec<RouteDoc>,
 }
 
 impl Default for Router {
@@ -19,6 +22,7 @@ impl Router {
         Self {
             trie: TrieRouter::new(),
             route_count: 0,
+            docs: Vec::new(),
         }
     }
 
@@ -26,22 +30,64 @@ impl Router {
     ///
     /// This now uses the high-performance Trie implementation for O(log n) lookup
     pub fn add_route(&mut self, route: Route) {
-        self.trie
-            .add_route(&route.method, &route.path, route.handler, route.xhr_only);
+        self.trie.insert(
+            &route.method,
+            RouteInfo {
+                handler: route.handler,
+                xhr_only: route.xhr_only,
+                pattern: route.path.clone(),
+                max_body_size: route.max_body_size,
+            },
+        );
         // XHR routes count as 2 (GET + POST)
         self.route_count += if route.method == "XHR" { 2 } else { 1 };
+        self.docs.push(RouteDoc {
+            method: route.method,
+            path: route.path,
+            summary: route.summary,
+            description: route.description,
+        });
     }
 
     /// Match a route in the router
     ///
     /// Returns the route info and extracted parameters if a match is found.
     /// Now provides O(log n) performance instead of the previous O(n) implementation.
+    ///
+    /// `HEAD` requests without an explicit `HEAD` route fall back to the `GET`
+    /// handler; the caller is responsible for dropping the response body.
     pub fn match_route(
         &self,
         method: &str,
         path: &str,
     ) -> Option<(&RouteInfo, HashMap<String, String>)> {
-        self.trie.match_route(method, path)
+        self.trie.match_route(method, path).or_else(|| {
+            if method.eq_ignore_ascii_case("HEAD") {
+                self.trie.match_route("GET", path)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Get the methods allowed for a path, as used in the `Allow` header
+    ///
+    /// Includes the implicit `HEAD` (when `GET` is registered) and `OPTIONS`.
+    /// Returns an empty list when no route matches the path.
+    pub fn allowed_methods(&self, path: &str) -> Vec<String> {
+        let mut methods = self.trie.methods_for(path);
+        if methods.is_empty() {
+            return methods;
+        }
+
+        if methods.iter().any(|m| m == "GET") && !methods.iter().any(|m| m == "HEAD") {
+            methods.push("HEAD".to_string());
+        }
+        if !methods.iter().any(|m| m == "OPTIONS") {
+            methods.push("OPTIONS".to_string());
+        }
+        methods.sort();
+        methods
     }
 
     /// Get the number of routes registered in this router
@@ -53,4 +99,47 @@ impl Router {
     pub fn is_empty(&self) -> bool {
         self.route_count == 0
     }
+
+    /// Build an OpenAPI 3.0 document describing the registered routes
+    pub fn openapi(&self, title: &str, version: &str) -> serde_json::Value {
+        openapi::document(&self.docs, title, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use crate::error::Result;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    fn mock_handler(_ctx: &mut Context) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    #[test]
+    fn test_head_falls_back_to_get() {
+        let mut router = Router::new();
+        router.add_route(Route::get("/users", mock_handler));
+        router.add_route(Route::post("/login", mock_handler));
+
+        assert!(router.match_route("HEAD", "/users").is_some());
+        assert!(router.match_route("HEAD", "/login").is_none());
+    }
+
+    #[test]
+    fn test_allowed_methods() {
+        let mut router = Router::new();
+        router.add_route(Route::get("/users/{id}", mock_handler));
+        router.add_route(Route::delete("/users/{id}", mock_handler));
+        router.add_route(Route::post("/login", mock_handler));
+
+        assert_eq!(
+            router.allowed_methods("/users/42"),
+            vec!["DELETE", "GET", "HEAD", "OPTIONS"]
+        );
+        assert_eq!(router.allowed_methods("/login"), vec!["OPTIONS", "POST"]);
+        assert!(router.allowed_methods("/missing").is_empty());
+    }
 }
@@ -4,6 +4,7 @@
 //! It provides O(log n) route matching instead of the previous O(n) implementation.
 
 use super::RouteHandler;
+use regex::Regex;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
@@ -12,6 +13,10 @@ use std::fmt::Debug;
 pub struct RouteInfo {
     pub handler: RouteHandler,
     pub xhr_only: bool,
+    /// Route path as registered (e.g. "/users/{id}")
+    pub pattern: String,
+    /// Body size limit replacing `server.max_body_size` for this route
+    pub max_body_size: Option<usize>,
 }
 
 /// A Trie node that can contain route handlers and parameters
@@ -19,19 +24,78 @@ pub struct RouteInfo {
 struct TrieNode {
     /// Exact path segment match
     static_children: HashMap<String, TrieNode>,
-    /// Dynamic parameter match (e.g., {id})
-    param_child: Option<(String, Box<TrieNode>)>,
-    /// Wildcard match (e.g., * or **)
-    wildcard_child: Option<Box<TrieNode>>,
+    /// Dynamic parameter matches (e.g., {id} or {id:int}), constrained ones
+    /// first, in registration order
+    param_children: Vec<ParamChild>,
+    /// Wildcard match for the remaining segments (`*`, or `{*name}` to
+    /// capture them under `name`)
+    wildcard_child: Option<(Option<String>, Box<TrieNode>)>,
     /// Route information for each HTTP method at this node
     handlers: HashMap<String, RouteInfo>,
 }
 
+/// A parameter edge of the trie
+#[derive(Debug)]
+struct ParamChild {
+    name: String,
+    constraint: Option<Constraint>,
+    node: Box<TrieNode>,
+}
+
+/// Inline constraint of a parameter segment (`{id:int}`)
+///
+/// Regexes are compiled once, when the route is registered, and shared by
+/// every route with the same constraint at that position.
+#[derive(Debug, Clone)]
+enum Constraint {
+    /// `{name:int}`: an `i64`
+    Int,
+    /// `{name:uuid}`
+    Uuid,
+    /// `{name:regex(...)}`, or a bare pattern such as `{name:\d+}`,
+    /// anchored to the whole segment
+    Regex(Regex),
+}
+
+impl Constraint {
+    fn parse(spec: &str) -> std::result::Result<Self, regex::Error> {
+        match spec {
+            "int" => Ok(Self::Int),
+            "uuid" => Ok(Self::Uuid),
+            _ => {
+                let pattern = spec
+                    .strip_prefix("regex(")
+                    .and_then(|s| s.strip_suffix(')'))
+                    .unwrap_or(spec);
+                Regex::new(&format!("^(?:{})$", pattern)).map(Self::Regex)
+            }
+        }
+    }
+
+    fn matches(&self, segment: &str) -> bool {
+        match self {
+            Self::Int => segment.parse::<i64>().is_ok(),
+            Self::Uuid => uuid::Uuid::parse_str(segment).is_ok(),
+            Self::Regex(regex) => regex.is_match(segment),
+        }
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Int, Self::Int) | (Self::Uuid, Self::Uuid) => true,
+            (Self::Regex(a), Self::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
 impl TrieNode {
     fn new() -> Self {
         Self {
             static_children: HashMap::new(),
-            param_child: None,
+            param_children: Vec::new(),
             wildcard_child: None,
             handlers: HashMap::new(),
         }
@@ -70,11 +134,35 @@ impl TrieRouter {
     /// * `handler` - Route handler function
     /// * `xhr_only` - Whether this route requires XHR/AJAX requests
     pub fn add_route(&mut self, method: &str, path: &str, handler: RouteHandler, xhr_only: bool) {
-        let segments = self.parse_path(path);
+        self.insert(
+            method,
+            RouteInfo {
+                handler,
+                xhr_only,
+                pattern: path.to_string(),
+                max_body_size: None,
+            },
+        );
+    }
+
+    /// Add a route described by `info`, at the path `info.pattern`
+    ///
+    /// # Panics
+    ///
+    /// When a constraint regex does not compile, so a broken route fails at
+    /// startup instead of never matching.
+    #[allow(clippy::panic)]
+    pub fn insert(&mut self, method: &str, info: RouteInfo) {
+        let path = info.pattern.as_str();
+        let segments = match self.parse_path(path) {
+            Ok(segments) => segments,
+            Err(e) => panic!("Invalid constraint in route {} {}: {}", method, path, e),
+        };
+        let segment_count = segments.len();
         let mut current = &mut self.root;
 
         // Navigate/create the trie path
-        for segment in segments {
+        for (index, segment) in segments.into_iter().enumerate() {
             match segment {
                 PathSegment::Static(segment_str) => {
                     current = current
@@ -82,17 +170,48 @@ impl TrieRouter {
                         .entry(segment_str)
                         .or_insert_with(TrieNode::new);
                 }
-                PathSegment::Parameter(param_name) => {
-                    if current.param_child.is_none() {
-                        current.param_child = Some((param_name, Box::new(TrieNode::new())));
-                    }
-                    current = &mut current.param_child.as_mut().unwrap().1;
+                PathSegment::Parameter(param_name, constraint) => {
+                    let children = &mut current.param_children;
+                    let index = match children.iter().position(|c| c.constraint == constraint) {
+                        Some(index) => index,
+                        None => {
+                            // Unconstrained parameters are tried last
+                            let index = if constraint.is_some() {
+                                children
+                                    .iter()
+                                    .position(|c| c.constraint.is_none())
+                                    .unwrap_or(children.len())
+                            } else {
+                                children.len()
+                            };
+                            children.insert(
+                                index,
+                                ParamChild {
+                                    name: param_name,
+                                    constraint,
+                                    node: Box::new(TrieNode::new()),
+                                },
+                            );
+                            index
+                        }
+                    };
+                    current = &mut children[index].node;
                 }
-                PathSegment::Wildcard => {
-                    if current.wildcard_child.is_none() {
-                        current.wildcard_child = Some(Box::new(TrieNode::new()));
+                PathSegment::Wildcard(name) => {
+                    let (capture, child) = current
+                        .wildcard_child
+                        .get_or_insert_with(|| (None, Box::new(TrieNode::new())));
+                    if name.is_some() {
+                        *capture = name;
+                    }
+                    current = child;
+                    if index + 1 < segment_count {
+                        log::warn!(
+                            "Route {} has segments after its wildcard; they are ignored",
+                            path
+                        );
+                        break;
                     }
-                    current = current.wildcard_child.as_mut().unwrap();
                 }
             }
         }
@@ -101,17 +220,11 @@ impl TrieRouter {
         // For XHR routes, we store with "XHR" as method but match on GET/POST
         if method == "XHR" {
             // XHR routes match both GET and POST
-            current
-                .handlers
-                .insert("GET".to_string(), RouteInfo { handler, xhr_only });
-            current
-                .handlers
-                .insert("POST".to_string(), RouteInfo { handler, xhr_only });
+            current.handlers.insert("GET".to_string(), info.clone());
+            current.handlers.insert("POST".to_string(), info);
             self.route_count += 2;
         } else {
-            current
-                .handlers
-                .insert(method.to_uppercase(), RouteInfo { handler, xhr_only });
+            current.handlers.insert(method.to_uppercase(), info);
             self.route_count += 1;
         }
     }
@@ -148,30 +261,59 @@ impl TrieRouter {
         None
     }
 
+    /// Get the HTTP methods registered for the node matching `path`
+    ///
+    /// Returns an empty list when no route matches the path. Methods are sorted.
+    pub fn methods_for(&self, path: &str) -> Vec<String> {
+        let path_only = path.split('?').next().unwrap_or(path);
+        let segments: Vec<&str> = path_only
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut params = HashMap::new();
+        let mut methods: Vec<String> = self
+            .match_segments(&self.root, &segments, 0, &mut params)
+            .map(|node| node.handlers.keys().cloned().collect())
+            .unwrap_or_default();
+        methods.sort();
+        methods
+    }
+
     /// Get the number of routes registered
     pub fn route_count(&self) -> usize {
         self.route_count
     }
 
     /// Parse a path into segments
-    fn parse_path(&self, path: &str) -> Vec<PathSegment> {
+    fn parse_path(&self, path: &str) -> std::result::Result<Vec<PathSegment>, regex::Error> {
         let path_clean = path.trim_start_matches('/');
         if path_clean.is_empty() {
-            return vec![];
+            return Ok(vec![]);
         }
 
         path_clean
             .split('/')
             .filter(|s| !s.is_empty())
             .map(|segment| {
-                if segment == "*" {
-                    PathSegment::Wildcard
+                Ok(if segment == "*" {
+                    PathSegment::Wildcard(None)
+                } else if let Some(name) =
+                    segment.strip_prefix("{*").and_then(|s| s.strip_suffix('}'))
+                {
+                    PathSegment::Wildcard(Some(name.to_string()))
                 } else if segment.starts_with('{') && segment.ends_with('}') {
-                    let param_name = segment[1..segment.len() - 1].to_string();
-                    PathSegment::Parameter(param_name)
+                    let inner = &segment[1..segment.len() - 1];
+                    match inner.split_once(':') {
+                        Some((name, spec)) => {
+                            PathSegment::Parameter(name.to_string(), Some(Constraint::parse(spec)?))
+                        }
+                        None => PathSegment::Parameter(inner.to_string(), None),
+                    }
                 } else {
                     PathSegment::Static(segment.to_string())
-                }
+                })
             })
             .collect()
     }
@@ -198,19 +340,27 @@ impl TrieRouter {
             }
         }
 
-        // Try parameter match
-        if let Some((param_name, child)) = &node.param_child {
-            params.insert(param_name.clone(), current_segment.to_string());
-            if let Some(result) = self.match_segments(child, segments, index + 1, params) {
+        // Try parameter matches, skipping those whose constraint rejects the segment
+        for child in &node.param_children {
+            if let Some(constraint) = &child.constraint {
+                if !constraint.matches(current_segment) {
+                    continue;
+                }
+            }
+            params.insert(child.name.clone(), current_segment.to_string());
+            if let Some(result) = self.match_segments(&child.node, segments, index + 1, params) {
                 return Some(result);
             }
             // Remove parameter if match failed
-            params.remove(param_name);
+            params.remove(&child.name);
         }
 
         // Try wildcard match (least specific)
-        if let Some(child) = &node.wildcard_child {
+        if let Some((capture, child)) = &node.wildcard_child {
             // Wildcard matches remaining segments
+            if let Some(name) = capture {
+                params.insert(name.clone(), segments[index..].join("/"));
+            }
             return Some(child);
         }
 
@@ -223,10 +373,10 @@ impl TrieRouter {
 enum PathSegment {
     /// Static segment (exact match)
     Static(String),
-    /// Parameter segment (e.g., {id})
-    Parameter(String),
-    /// Wildcard segment (*)
-    Wildcard,
+    /// Parameter segment (e.g., {id} or {id:int})
+    Parameter(String, Option<Constraint>),
+    /// Wildcard segment (`*`, or `{*name}` when captured)
+    Wildcard(Option<String>),
 }
 
 #[cfg(test)]
@@ -289,6 +439,112 @@ mod tests {
         assert_eq!(params.get("id"), Some(&"123".to_string()));
     }
 
+    #[test]
+    fn test_constrained_parameters() {
+        let mut router = TrieRouter::new();
+        router.add_route("GET", "/users/{slug}", mock_handler as RouteHandler, false);
+        router.add_route(
+            "GET",
+            "/users/{id:int}",
+            mock_handler as RouteHandler,
+            false,
+        );
+        router.add_route(
+            "GET",
+            "/orders/{id:uuid}",
+            mock_handler as RouteHandler,
+            false,
+        );
+        router.add_route(
+            "GET",
+            "/archive/{year:regex(\\d{4})}",
+            mock_handler as RouteHandler,
+            false,
+        );
+        router.add_route(
+            "GET",
+            "/tags/{code:[a-z]+}",
+            mock_handler as RouteHandler,
+            false,
+        );
+
+        let (info, params) = router.match_route("GET", "/users/42").unwrap();
+        assert_eq!(info.pattern, "/users/{id:int}");
+        assert_eq!(params.get("id"), Some(&"42".to_string()));
+
+        // Falls through to the unconstrained route
+        let (info, params) = router.match_route("GET", "/users/john").unwrap();
+        assert_eq!(info.pattern, "/users/{slug}");
+        assert_eq!(params.get("slug"), Some(&"john".to_string()));
+        assert!(!params.contains_key("id"));
+
+        assert!(router
+            .match_route("GET", "/orders/67e55044-10b1-426f-9247-bb680e5fe0c8")
+            .is_some());
+        assert!(router.match_route("GET", "/orders/42").is_none());
+
+        // Regexes are anchored to the whole segment
+        assert!(router.match_route("GET", "/archive/2024").is_some());
+        assert!(router.match_route("GET", "/archive/20245").is_none());
+        assert!(router.match_route("GET", "/tags/rust").is_some());
+        assert!(router.match_route("GET", "/tags/Rust1").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid constraint in route GET /users/{id:(}")]
+    fn test_invalid_constraint_panics_at_registration() {
+        let mut router = TrieRouter::new();
+        router.add_route("GET", "/users/{id:(}", mock_handler as RouteHandler, false);
+    }
+
+    #[test]
+    fn test_wildcard_captures_remaining_segments() {
+        let mut router = TrieRouter::new();
+        router.add_route("GET", "/files/{*path}", mock_handler as RouteHandler, false);
+        router.add_route("GET", "/files/{name}", mock_handler as RouteHandler, false);
+        router.add_route("GET", "/files/index", mock_handler as RouteHandler, false);
+
+        let (info, params) = router
+            .match_route("GET", "/files/docs/2024/report.pdf")
+            .unwrap();
+        assert_eq!(info.pattern, "/files/{*path}");
+        assert_eq!(
+            params.get("path"),
+            Some(&"docs/2024/report.pdf".to_string())
+        );
+
+        // Exact and single-parameter routes win over the wildcard
+        let (info, _) = router.match_route("GET", "/files/index").unwrap();
+        assert_eq!(info.pattern, "/files/index");
+        let (info, params) = router.match_route("GET", "/files/a.txt").unwrap();
+        assert_eq!(info.pattern, "/files/{name}");
+        assert_eq!(params.get("name"), Some(&"a.txt".to_string()));
+
+        // The wildcard needs at least one segment
+        assert!(router.match_route("GET", "/files").is_none());
+    }
+
+    #[test]
+    fn test_wildcard_after_rejected_constraint() {
+        let mut router = TrieRouter::new();
+        router.add_route("GET", "/files/{*path}", mock_handler as RouteHandler, false);
+        router.add_route(
+            "GET",
+            "/files/{id:int}",
+            mock_handler as RouteHandler,
+            false,
+        );
+
+        let (info, params) = router.match_route("GET", "/files/42").unwrap();
+        assert_eq!(info.pattern, "/files/{id:int}");
+        assert_eq!(params.get("id"), Some(&"42".to_string()));
+
+        let (info, params) = router.match_route("GET", "/files/a/b/c.txt").unwrap();
+        assert_eq!(info.pattern, "/files/{*path}");
+        assert_eq!(params.get("path"), Some(&"a/b/c.txt".to_string()));
+        assert!(!params.contains_key("id"));
+    }
+
     #[test]
     fn test_query_parameters_ignored() {
         let mut router = TrieRouter::new();
@@ -300,6 +556,25 @@ mod tests {
         assert!(router.match_route("GET", "/search?").is_some());
     }
 
+    #[test]
+    fn test_methods_for() {
+        let mut router = TrieRouter::new();
+        router.add_route("POST", "/users/{id}", mock_handler as RouteHandler, false);
+        router.add_route("GET", "/users/{id}", mock_handler as RouteHandler, false);
+
+        assert_eq!(router.methods_for("/users/1?full=1"), vec!["GET", "POST"]);
+        assert!(router.methods_for("/posts").is_empty());
+    }
+
+    #[test]
+    fn test_matched_route_pattern() {
+        let mut router = TrieRouter::new();
+        router.add_route("GET", "/users/{id}", mock_handler as RouteHandler, false);
+
+        let (info, _) = router.match_route("GET", "/users/42").unwrap();
+        assert_eq!(info.pattern, "/users/{id}");
+    }
+
     #[test]
     fn test_route_count() {
         let mut router = TrieRouter::new();
@@ -6,6 +6,7 @@
 //! ## Features
 //! - Path traversal protection for static file serving
 //! - HTML escaping and XSS prevention
+//! - Allowlist sanitizing of user-submitted HTML
 //! - Input validation and sanitization
 //! - Secure file handling utilities
 //! - Security headers management
@@ -16,13 +17,16 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 pub mod csrf;
+pub mod encryption;
 pub mod error_handling;
 pub mod headers;
+pub mod sanitizer;
 pub mod static_files;
 pub mod validation;
 
 // Re-export commonly used types for convenience
 pub use csrf::{CsrfConfig, CsrfMiddleware};
+pub use sanitizer::HtmlSanitizer;
 
 /// Security configuration for the framework
 #[derive(Clone, Debug)]
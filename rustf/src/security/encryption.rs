@@ -0,0 +1,257 @@
+//! Field-level encryption for model columns
+//!
+//! Schema fields marked `encrypted: true` are generated with calls into this
+//! module: values are plaintext on the Rust struct and AES-256-GCM ciphertext
+//! in the database. Each stored value carries the id of the key that
+//! encrypted it, so keys can be rotated without rewriting every row at once:
+//!
+//! ```text
+//! enc:<key id>:<base64(nonce || ciphertext)>
+//! ```
+//!
+//! Keys are read from `config.toml` on first use (base64, 32 bytes each):
+//!
+//! ```toml
+//! [encryption]
+//! current_key = "k2"
+//!
+//! [encryption.keys]
+//! k1 = "..." # still accepted for decryption
+//! k2 = "..."
+//! ```
+//!
+//! Encrypted columns cannot be searched: every encryption uses a fresh nonce,
+//! so equal plaintexts never produce equal ciphertexts.
+
+use crate::error::{Error, Result};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+
+/// Prefix marking an encrypted column value
+pub const ENCRYPTED_PREFIX: &str = "enc";
+
+/// Size of the AES-GCM nonce stored in front of the ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Application-wide cipher used by generated models
+static CIPHER: OnceCell<FieldCipher> = OnceCell::new();
+
+/// AES-256-GCM cipher with a current key and older keys kept for decryption
+pub struct FieldCipher {
+    current: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl FieldCipher {
+    /// Create a cipher encrypting with the given 32-byte key
+    pub fn new(key_id: &str, key: &[u8]) -> Result<Self> {
+        let mut keys = HashMap::new();
+        keys.insert(key_id.to_string(), Self::build_key(key_id, key)?);
+        Ok(Self {
+            current: key_id.to_string(),
+            keys,
+        })
+    }
+
+    /// Add a retired key, used only to decrypt values written with it
+    pub fn with_key(mut self, key_id: &str, key: &[u8]) -> Result<Self> {
+        self.keys
+            .insert(key_id.to_string(), Self::build_key(key_id, key)?);
+        Ok(self)
+    }
+
+    /// Build a cipher from the `[encryption]` configuration section
+    pub fn from_config() -> Result<Self> {
+        use crate::configuration::CONF;
+
+        let current = CONF::get_string("encryption.current_key").ok_or_else(|| {
+            Error::internal("encryption.current_key is not configured".to_string())
+        })?;
+        let keys: HashMap<String, String> = CONF::get("encryption.keys").unwrap_or_default();
+
+        let decode = |key_id: &str| -> Result<Vec<u8>> {
+            let encoded = keys.get(key_id).ok_or_else(|| {
+                Error::internal(format!("Encryption key '{}' is not configured", key_id))
+            })?;
+            STANDARD.decode(encoded.trim()).map_err(|e| {
+                Error::internal(format!(
+                    "Encryption key '{}' is not valid base64: {}",
+                    key_id, e
+                ))
+            })
+        };
+
+        let mut cipher = Self::new(&current, &decode(&current)?)?;
+        for key_id in keys.keys().filter(|id| **id != current) {
+            cipher = cipher.with_key(key_id, &decode(key_id)?)?;
+        }
+        Ok(cipher)
+    }
+
+    fn build_key(key_id: &str, key: &[u8]) -> Result<Aes256Gcm> {
+        if key_id.is_empty() || key_id.contains(':') {
+            return Err(Error::internal(format!(
+                "Invalid encryption key id '{}'",
+                key_id
+            )));
+        }
+        Aes256Gcm::new_from_slice(key).map_err(|_| {
+            Error::internal(format!(
+                "Encryption key '{}' must be 32 bytes, got {}",
+                key_id,
+                key.len()
+            ))
+        })
+    }
+
+    /// Id of the key new values are encrypted with
+    pub fn current_key_id(&self) -> &str {
+        &self.current
+    }
+
+    /// Encrypt a value with the current key
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let cipher = &self.keys[&self.current];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| Error::internal("Field encryption failed".to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!(
+            "{}:{}:{}",
+            ENCRYPTED_PREFIX,
+            self.current,
+            STANDARD.encode(payload)
+        ))
+    }
+
+    /// Decrypt a value written by [`encrypt`](Self::encrypt) with any known key
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let (key_id, encoded) = split_value(value)
+            .ok_or_else(|| Error::internal("Value is not an encrypted field".to_string()))?;
+        let cipher = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| Error::internal(format!("Unknown encryption key '{}'", key_id)))?;
+
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|_| Error::internal("Encrypted field is not valid base64".to_string()))?;
+        if payload.len() < NONCE_LEN {
+            return Err(Error::internal("Encrypted field is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::internal("Field decryption failed".to_string()))?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::internal("Decrypted field is not valid UTF-8".to_string()))
+    }
+
+    /// Check whether a stored value was encrypted with a key other than the current one
+    ///
+    /// Re-save such records to move them to the current key.
+    pub fn needs_rotation(&self, value: &str) -> bool {
+        split_value(value).is_some_and(|(key_id, _)| key_id != self.current)
+    }
+}
+
+/// Split `enc:<key id>:<payload>` into key id and payload
+fn split_value(value: &str) -> Option<(&str, &str)> {
+    let rest = value.strip_prefix(ENCRYPTED_PREFIX)?.strip_prefix(':')?;
+    rest.split_once(':')
+}
+
+/// Install the application cipher
+///
+/// Without this, the cipher is built from configuration on first use.
+pub fn install(cipher: FieldCipher) -> Result<()> {
+    CIPHER
+        .set(cipher)
+        .map_err(|_| Error::internal("Field cipher is already installed".to_string()))
+}
+
+/// Get the application cipher
+pub fn cipher() -> Result<&'static FieldCipher> {
+    CIPHER.get_or_try_init(FieldCipher::from_config)
+}
+
+/// Encrypt a column value with the application cipher
+pub fn encrypt_field(plaintext: &str) -> Result<String> {
+    cipher()?.encrypt(plaintext)
+}
+
+/// Decrypt a column value with the application cipher
+pub fn decrypt_field(value: &str) -> Result<String> {
+    cipher()?.decrypt(value)
+}
+
+/// Reject a raw WHERE clause that references an encrypted column
+///
+/// Encrypted values cannot be compared in SQL, so such a filter would
+/// silently match nothing.
+pub fn ensure_not_filtered(where_clause: &str, encrypted_columns: &[&str]) -> Result<()> {
+    let referenced = where_clause
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .find(|word| {
+            encrypted_columns
+                .iter()
+                .any(|col| col.eq_ignore_ascii_case(word))
+        });
+
+    match referenced {
+        Some(column) => Err(Error::validation(format!(
+            "Cannot filter on encrypted column '{}'",
+            column
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip_and_rotation() {
+        let old = FieldCipher::new("k1", &[1u8; 32]).unwrap();
+        let stored = old.encrypt("123-45-6789").unwrap();
+        assert!(stored.starts_with("enc:k1:"));
+        assert_ne!(stored, old.encrypt("123-45-6789").unwrap());
+        assert_eq!(old.decrypt(&stored).unwrap(), "123-45-6789");
+
+        // New key encrypts, old key still decrypts
+        let rotated = FieldCipher::new("k2", &[2u8; 32])
+            .unwrap()
+            .with_key("k1", &[1u8; 32])
+            .unwrap();
+        assert!(rotated.needs_rotation(&stored));
+        assert_eq!(rotated.decrypt(&stored).unwrap(), "123-45-6789");
+        assert!(rotated.encrypt("x").unwrap().starts_with("enc:k2:"));
+
+        // Tampered or unknown values are rejected
+        let mut payload = STANDARD.decode(&stored["enc:k1:".len()..]).unwrap();
+        payload[NONCE_LEN] ^= 1;
+        let tampered = format!("enc:k1:{}", STANDARD.encode(payload));
+        assert!(old.decrypt(&tampered).is_err());
+        assert!(FieldCipher::new("k3", &[3u8; 32])
+            .unwrap()
+            .decrypt(&stored)
+            .is_err());
+        assert!(FieldCipher::new("short", &[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_ensure_not_filtered() {
+        assert!(ensure_not_filtered("email = $1", &["ssn"]).is_ok());
+        assert!(ensure_not_filtered("status = $1 AND SSN = $2", &["ssn"]).is_err());
+        assert!(ensure_not_filtered("ssn_hash = $1", &["ssn"]).is_ok());
+    }
+}
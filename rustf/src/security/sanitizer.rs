@@ -0,0 +1,686 @@
+//! HTML sanitizing for user-generated content
+//!
+//! `HtmlEscaper` makes any input inert by escaping all of it. Rich-text
+//! fields need some markup to survive, so `HtmlSanitizer` parses the input
+//! and rebuilds it from an allowlist instead:
+//!
+//! - tags outside the allowlist are removed and their text kept, except
+//!   `script`, `style`, `iframe` and similar, which go with their content
+//! - attributes outside the allowlist are removed, as are `on*` handlers
+//! - `href`, `src` and `cite` must be relative or use an allowed scheme
+//!   (`http`, `https`, `mailto` by default), after entities and whitespace
+//!   that could hide a `javascript:` are resolved
+//! - comments and doctypes are removed, unmatched end tags are dropped and
+//!   tags left open are closed, so the result cannot break out of the page
+//!   around it
+//!
+//! ```rust,ignore
+//! let html = HtmlSanitizer::default().clean(r#"<p onclick="x()">Hi <script>x()</script><b>there</b></p>"#);
+//! assert_eq!(html, "<p>Hi <b>there</b></p>");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+/// Elements whose content is parsed as raw text; always removed with it
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script",
+    "style",
+    "textarea",
+    "title",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+];
+
+/// Elements removed with their content when not allowed
+const CLEAN_CONTENT_TAGS: &[&str] = &["template", "object", "svg", "math", "select", "head"];
+
+/// Elements without content or end tag
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Attributes holding a URL, checked against the allowed schemes
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite", "action", "formaction", "poster"];
+
+/// Allowlist-based HTML sanitizer
+///
+/// Start from [`basic`](Self::basic) (also the `Default`),
+/// [`relaxed`](Self::relaxed) or [`new`](Self::new), which allows no markup,
+/// and adjust the allowlists with the builder methods.
+#[derive(Debug, Clone)]
+pub struct HtmlSanitizer {
+    tags: HashSet<String>,
+    generic_attributes: HashSet<String>,
+    tag_attributes: HashMap<String, HashSet<String>>,
+    url_schemes: HashSet<String>,
+    link_rel: Option<String>,
+}
+
+impl Default for HtmlSanitizer {
+    fn default() -> Self {
+        Self::basic()
+    }
+}
+
+impl HtmlSanitizer {
+    /// A sanitizer that allows no tags, leaving only text
+    pub fn new() -> Self {
+        Self {
+            tags: HashSet::new(),
+            generic_attributes: HashSet::new(),
+            tag_attributes: HashMap::new(),
+            url_schemes: HashSet::new(),
+            link_rel: None,
+        }
+        .allow_url_schemes(&["http", "https", "mailto"])
+        .link_rel(Some("noopener noreferrer nofollow"))
+    }
+
+    /// Inline formatting, paragraphs, lists, quotes, code and links; for comments
+    pub fn basic() -> Self {
+        Self::new()
+            .allow_tags(&[
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "i",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "small",
+                "strong",
+                "sub",
+                "sup",
+                "u",
+                "ul",
+            ])
+            .allow_attributes("a", &["href", "title"])
+            .allow_attributes("abbr", &["title"])
+            .allow_attributes("blockquote", &["cite"])
+            .allow_attributes("q", &["cite"])
+            .allow_attributes("ol", &["start", "reversed"])
+    }
+
+    /// `basic` plus headings, images, tables and layout blocks; for posts
+    pub fn relaxed() -> Self {
+        Self::basic()
+            .allow_tags(&[
+                "caption",
+                "cite",
+                "dd",
+                "details",
+                "div",
+                "dl",
+                "dt",
+                "figcaption",
+                "figure",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "img",
+                "span",
+                "summary",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+            ])
+            .allow_generic_attributes(&["title", "lang", "dir"])
+            .allow_attributes("img", &["src", "alt", "width", "height"])
+            .allow_attributes("td", &["colspan", "rowspan"])
+            .allow_attributes("th", &["colspan", "rowspan", "scope"])
+    }
+
+    /// Allow more tags
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        self.tags
+            .extend(tags.iter().map(|tag| tag.to_ascii_lowercase()));
+        self
+    }
+
+    /// Remove tags from the allowlist
+    pub fn remove_tags(mut self, tags: &[&str]) -> Self {
+        for tag in tags {
+            self.tags.remove(&tag.to_ascii_lowercase());
+        }
+        self
+    }
+
+    /// Allow attributes on one tag
+    ///
+    /// `on*` event handlers are never kept, whatever the allowlist says.
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.tag_attributes
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attributes.iter().map(|name| name.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allow attributes on every allowed tag
+    pub fn allow_generic_attributes(mut self, attributes: &[&str]) -> Self {
+        self.generic_attributes
+            .extend(attributes.iter().map(|name| name.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allow more URL schemes in `href`, `src` and `cite`; relative URLs are always allowed
+    pub fn allow_url_schemes(mut self, schemes: &[&str]) -> Self {
+        self.url_schemes
+            .extend(schemes.iter().map(|scheme| scheme.to_ascii_lowercase()));
+        self
+    }
+
+    /// Replace the allowed URL schemes
+    pub fn url_schemes(mut self, schemes: &[&str]) -> Self {
+        self.url_schemes.clear();
+        self.allow_url_schemes(schemes)
+    }
+
+    /// The `rel` set on every link, replacing the one given; `None` leaves `rel` to the allowlist
+    pub fn link_rel(mut self, rel: Option<&str>) -> Self {
+        self.link_rel = rel.map(str::to_string);
+        self
+    }
+
+    /// Whether `tag` is on the allowlist
+    pub fn is_tag_allowed(&self, tag: &str) -> bool {
+        self.tags.contains(&tag.to_ascii_lowercase())
+    }
+
+    /// Sanitize `html`, returning markup that only uses the allowlists
+    pub fn clean(&self, html: &str) -> String {
+        let mut output = String::with_capacity(html.len());
+        let mut open: Vec<String> = Vec::new();
+        // Element whose content is being removed, and how deep it nests
+        let mut skipping: Option<(String, usize)> = None;
+        let mut rest = html;
+
+        while !rest.is_empty() {
+            let Some(lt) = rest.find('<') else {
+                if skipping.is_none() {
+                    push_text(&mut output, rest);
+                }
+                break;
+            };
+            if skipping.is_none() {
+                push_text(&mut output, &rest[..lt]);
+            }
+            rest = &rest[lt..];
+
+            let (token, after) = next_token(rest);
+            rest = after;
+            match token {
+                Token::Text(text) => {
+                    if skipping.is_none() {
+                        push_text(&mut output, text);
+                    }
+                }
+                Token::Ignored => {}
+                Token::Start {
+                    name,
+                    attributes,
+                    self_closing,
+                } => {
+                    if let Some((skipped, depth)) = &mut skipping {
+                        if *skipped == name && !self_closing {
+                            *depth += 1;
+                        }
+                        continue;
+                    }
+                    if RAW_TEXT_TAGS.contains(&name.as_str()) {
+                        rest = skip_raw_text(rest, &name);
+                        continue;
+                    }
+                    if !self.is_tag_allowed(&name) {
+                        if CLEAN_CONTENT_TAGS.contains(&name.as_str()) && !self_closing {
+                            skipping = Some((name, 1));
+                        }
+                        continue;
+                    }
+
+                    self.push_start_tag(&mut output, &name, &attributes);
+                    if !VOID_TAGS.contains(&name.as_str()) {
+                        if self_closing {
+                            output.push_str(&format!("</{}>", name));
+                        } else {
+                            open.push(name);
+                        }
+                    }
+                }
+                Token::End { name } => {
+                    if let Some((skipped, depth)) = &mut skipping {
+                        if *skipped == name {
+                            *depth -= 1;
+                            if *depth == 0 {
+                                skipping = None;
+                            }
+                        }
+                        continue;
+                    }
+                    // Close up to the matching open tag; drop stray end tags
+                    if let Some(index) = open.iter().rposition(|tag| *tag == name) {
+                        for tag in open.drain(index..).rev() {
+                            output.push_str(&format!("</{}>", tag));
+                        }
+                    }
+                }
+            }
+        }
+
+        for tag in open.iter().rev() {
+            output.push_str(&format!("</{}>", tag));
+        }
+        output
+    }
+
+    fn push_start_tag(&self, output: &mut String, name: &str, attributes: &[(String, String)]) {
+        output.push('<');
+        output.push_str(name);
+
+        let tag_attributes = self.tag_attributes.get(name);
+        let mut seen = HashSet::new();
+        for (attribute, value) in attributes {
+            // The browser keeps the first of duplicated attributes
+            if !seen.insert(attribute.as_str()) {
+                continue;
+            }
+            let allowed = self.generic_attributes.contains(attribute)
+                || tag_attributes.is_some_and(|names| names.contains(attribute));
+            if !allowed || attribute.starts_with("on") {
+                continue;
+            }
+            if name == "a" && attribute == "rel" && self.link_rel.is_some() {
+                continue;
+            }
+
+            let value = decode_entities(value);
+            if URL_ATTRIBUTES.contains(&attribute.as_str()) && !self.is_url_allowed(&value) {
+                continue;
+            }
+            output.push_str(&format!(" {}=\"{}\"", attribute, escape_attribute(&value)));
+        }
+
+        if name == "a" {
+            if let Some(rel) = &self.link_rel {
+                output.push_str(&format!(" rel=\"{}\"", escape_attribute(rel)));
+            }
+        }
+        output.push('>');
+    }
+
+    /// Relative URLs pass; absolute ones need an allowed scheme
+    fn is_url_allowed(&self, url: &str) -> bool {
+        // Browsers ignore tabs and newlines anywhere in a URL, and leading spaces
+        let url: String = url
+            .trim_start_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+
+        let scheme_end = url.find([':', '/', '?', '#']);
+        match scheme_end {
+            Some(index) if url[index..].starts_with(':') => self
+                .url_schemes
+                .contains(&url[..index].to_ascii_lowercase()),
+            _ => true,
+        }
+    }
+}
+
+enum Token<'a> {
+    Start {
+        name: String,
+        attributes: Attributes,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+    /// A `<` that does not start markup
+    Text(&'a str),
+    /// Comments, doctypes, processing instructions, unterminated tags
+    Ignored,
+}
+
+/// Read the markup token at the start of `input`, which begins with `<`
+fn next_token(input: &str) -> (Token<'_>, &str) {
+    let bytes = input.as_bytes();
+    match bytes.get(1) {
+        Some(b'!') if input.starts_with("<!--") => {
+            let end = input[4..].find("-->").map_or(input.len(), |i| i + 7);
+            (Token::Ignored, &input[end..])
+        }
+        Some(b'!' | b'?') => {
+            let end = input.find('>').map_or(input.len(), |i| i + 1);
+            (Token::Ignored, &input[end..])
+        }
+        Some(b'/') if bytes.get(2).is_some_and(u8::is_ascii_alphabetic) => {
+            let (name, rest) = tag_name(&input[2..]);
+            match rest.find('>') {
+                Some(end) => (Token::End { name }, &rest[end + 1..]),
+                None => (Token::Ignored, ""),
+            }
+        }
+        Some(b'/') => {
+            // `</>` is dropped, `</ 1>` is a bogus comment
+            let end = input.find('>').map_or(input.len(), |i| i + 1);
+            (Token::Ignored, &input[end..])
+        }
+        Some(byte) if byte.is_ascii_alphabetic() => {
+            let (name, rest) = tag_name(&input[1..]);
+            match attributes(rest) {
+                Some((attributes, self_closing, rest)) => (
+                    Token::Start {
+                        name,
+                        attributes,
+                        self_closing,
+                    },
+                    rest,
+                ),
+                None => (Token::Ignored, ""),
+            }
+        }
+        _ => (Token::Text("<"), &input[1..]),
+    }
+}
+
+fn tag_name(input: &str) -> (String, &str) {
+    let end = input
+        .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .unwrap_or(input.len());
+    (input[..end].to_ascii_lowercase(), &input[end..])
+}
+
+/// Lowercased attribute names with their raw values
+type Attributes = Vec<(String, String)>;
+
+/// Parse attributes up to the closing `>`; `None` when the tag never closes
+fn attributes(mut input: &str) -> Option<(Attributes, bool, &str)> {
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        input = input.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        match input.chars().next()? {
+            '>' => return Some((attributes, self_closing, &input[1..])),
+            '/' => {
+                self_closing = true;
+                input = &input[1..];
+                continue;
+            }
+            _ => self_closing = false,
+        }
+
+        let end = input
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_ascii_whitespace() || matches!(c, '/' | '>' | '='))
+            .map_or(input.len(), |(i, _)| i);
+        let name = input[..end].to_ascii_lowercase();
+        input = input[end..].trim_start_matches(|c: char| c.is_ascii_whitespace());
+
+        let mut value = "";
+        if let Some(after) = input.strip_prefix('=') {
+            input = after.trim_start_matches(|c: char| c.is_ascii_whitespace());
+            match input.chars().next()? {
+                quote @ ('"' | '\'') => {
+                    let end = input[1..].find(quote)?;
+                    value = &input[1..end + 1];
+                    input = &input[end + 2..];
+                }
+                _ => {
+                    let end = input
+                        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                        .unwrap_or(input.len());
+                    value = &input[..end];
+                    input = &input[end..];
+                }
+            }
+        }
+        attributes.push((name, value.to_string()));
+    }
+}
+
+/// Skip the content of a raw text element and its end tag
+fn skip_raw_text<'a>(input: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lower = input.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(index) = lower[from..].find(&closing) {
+        let start = from + index;
+        let after = start + closing.len();
+        if lower[after..].starts_with(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>') {
+            return input[after..]
+                .find('>')
+                .map_or("", |i| &input[after + i + 1..]);
+        }
+        from = after;
+    }
+    ""
+}
+
+/// Escape text, keeping character references that are already well-formed
+fn push_text(output: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(index) = rest.find(['&', '<', '>']) {
+        output.push_str(&rest[..index]);
+        match rest.as_bytes()[index] {
+            b'<' => output.push_str("&lt;"),
+            b'>' => output.push_str("&gt;"),
+            _ if character_reference_len(&rest[index..]).is_some() => output.push('&'),
+            _ => output.push_str("&amp;"),
+        }
+        rest = &rest[index + 1..];
+    }
+    output.push_str(rest);
+}
+
+/// Length of the `&...;` reference at the start of `input`, if well-formed
+fn character_reference_len(input: &str) -> Option<usize> {
+    let body = input.strip_prefix('&')?;
+    let end = body.find(';')?;
+    let name = &body[..end];
+    let valid = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else if let Some(decimal) = name.strip_prefix('#') {
+        !decimal.is_empty() && decimal.chars().all(|c| c.is_ascii_digit())
+    } else {
+        name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+    valid.then_some(end + 2)
+}
+
+/// Resolve character references in an attribute value
+///
+/// Numeric references and the named ones likely to hide a URL scheme are
+/// resolved; others stay literal, and are escaped as such on output, so the
+/// browser reads exactly the value that was checked.
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(index) = rest.find('&') {
+        decoded.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let body = &rest[1..];
+        // Numeric references end at the first non-digit, `;` or not
+        let (character, len) =
+            if let Some(hex) = body.strip_prefix("#x").or_else(|| body.strip_prefix("#X")) {
+                let digits = hex
+                    .find(|c: char| !c.is_ascii_hexdigit())
+                    .unwrap_or(hex.len());
+                let code = u32::from_str_radix(&hex[..digits], 16).ok();
+                (code.and_then(char::from_u32), 2 + digits)
+            } else if let Some(decimal) = body.strip_prefix('#') {
+                let digits = decimal
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(decimal.len());
+                let code = decimal[..digits].parse().ok();
+                (code.and_then(char::from_u32), 1 + digits)
+            } else {
+                let letters = body
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(body.len());
+                (named_reference(&body[..letters]), letters)
+            };
+
+        match character {
+            Some(c) => {
+                decoded.push(c);
+                let consumed = 1 + len + usize::from(body[len..].starts_with(';'));
+                rest = &rest[consumed..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn named_reference(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" | "AMP" => '&',
+        "lt" | "LT" => '<',
+        "gt" | "GT" => '>',
+        "quot" | "QUOT" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "colon" => ':',
+        "Tab" => '\t',
+        "NewLine" => '\n',
+        "sol" => '/',
+        "quest" => '?',
+        "num" => '#',
+        "lpar" => '(',
+        "rpar" => ')',
+        _ => return None,
+    })
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_allowlist() {
+        let sanitizer = HtmlSanitizer::default();
+
+        assert_eq!(
+            sanitizer.clean(r#"<p onclick="steal()">Hi <script>alert(1)</script><b>there</b></p>"#),
+            "<p>Hi <b>there</b></p>"
+        );
+        // Unknown tags go, their text stays; comments and styles go entirely
+        assert_eq!(
+            sanitizer.clean("<div><font>big</font> <!-- note --><style>p{}</style>news</div>"),
+            "big news"
+        );
+        assert_eq!(
+            sanitizer.clean(r#"<a href="https://example.com" rel="opener" target="_blank">x</a>"#),
+            r#"<a href="https://example.com" rel="noopener noreferrer nofollow">x</a>"#
+        );
+        // Text is escaped, well-formed references kept
+        assert_eq!(
+            sanitizer.clean("1 < 2 && 3 > 2 &copy; &#169;"),
+            "1 &lt; 2 &amp;&amp; 3 &gt; 2 &copy; &#169;"
+        );
+    }
+
+    #[test]
+    fn test_dangerous_urls_removed() {
+        let sanitizer = HtmlSanitizer::relaxed();
+
+        for href in [
+            "javascript:alert(1)",
+            "JaVaScRiPt:alert(1)",
+            " \tjavascript:alert(1)",
+            "java\nscript:alert(1)",
+            "javascript&colon;alert(1)",
+            "&#106;avascript:alert(1)",
+            "&#x6A&#x61vascript:alert(1)",
+            "data:text/html;base64,PHNjcmlwdD4=",
+            "vbscript:msgbox(1)",
+        ] {
+            let html = format!(r#"<a href="{}">x</a>"#, href);
+            assert_eq!(
+                sanitizer.clean(&html),
+                r#"<a rel="noopener noreferrer nofollow">x</a>"#,
+                "{}",
+                href
+            );
+        }
+
+        assert_eq!(
+            sanitizer.clean(r#"<img src="/avatars/1.png" alt="a&quot;b" onerror=alert(1)>"#),
+            r#"<img src="/avatars/1.png" alt="a&quot;b">"#
+        );
+        assert_eq!(
+            sanitizer.clean(r#"<a href="mailto:a@b.c?subject=x&amp;y">m</a>"#),
+            r#"<a href="mailto:a@b.c?subject=x&amp;y" rel="noopener noreferrer nofollow">m</a>"#
+        );
+    }
+
+    #[test]
+    fn test_structure_is_balanced() {
+        let sanitizer = HtmlSanitizer::default();
+
+        assert_eq!(
+            sanitizer.clean("<b><i>open</b> stray</p></ul>"),
+            "<b><i>open</i></b> stray"
+        );
+        assert_eq!(
+            HtmlSanitizer::relaxed().clean("<p>a<hr/><b/>b"),
+            "<p>a<hr><b></b>b</p>"
+        );
+        // Tricks that rely on sloppy parsing
+        assert_eq!(
+            sanitizer.clean("<scr<script>ipt>alert(1)</script>"),
+            "ipt&gt;alert(1)"
+        );
+        assert_eq!(
+            sanitizer.clean("<svg><p>hidden</p><svg></svg>still</svg>after"),
+            "after"
+        );
+        assert_eq!(sanitizer.clean("<b title=\"x>y\" "), "");
+        assert_eq!(
+            HtmlSanitizer::new().clean("<p>text <b>only</b></p>"),
+            "text only"
+        );
+    }
+}
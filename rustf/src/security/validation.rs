@@ -3,7 +3,7 @@
 //! This module provides comprehensive input validation and sanitization functions
 //! to prevent injection attacks, XSS, and other input-based vulnerabilities.
 
-use crate::error::{Error, Result};
+use crate::error::{Error, FieldError, Result};
 use regex::Regex;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -379,10 +379,14 @@ impl InputValidator {
                 .iter()
                 .map(|e| format!("{}: {}", e.field, e.message))
                 .collect();
-            return Err(Error::template(format!(
-                "Validation failed: {}",
-                error_messages.join(", ")
-            )));
+            let field_errors = errors
+                .iter()
+                .map(|e| FieldError::new(&e.field, &e.message, &e.code))
+                .collect();
+            return Err(Error::validation_fields(
+                format!("Validation failed: {}", error_messages.join(", ")),
+                field_errors,
+            ));
         }
 
         Ok(validated_data)
@@ -563,6 +567,11 @@ mod tests {
 
         let result = validator.validate(&data);
         assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        let fields = error.field_errors().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].field, "email");
     }
 
     #[test]
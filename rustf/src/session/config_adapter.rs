@@ -12,6 +12,7 @@ impl From<config::SessionConfig> for InternalSessionConfig {
             idle_timeout: Duration::from_secs(cfg.idle_timeout),
             absolute_timeout: Duration::from_secs(cfg.absolute_timeout),
             same_site: parse_same_site(&cfg.same_site),
+            same_site_none: cfg.same_site_none,
             exempt_routes: cfg.exempt_routes,
             enabled: cfg.enabled,
 
@@ -90,11 +91,14 @@ fn is_production() -> bool {
 }
 
 /// Create SessionMiddleware from app config (dual-phase version)
+///
+/// Falls back to memory storage when the configured storage cannot be
+/// created, but fails when the session cookie itself is refused.
 pub async fn create_session_middleware(
     config: &config::AppConfig,
-) -> Option<crate::middleware::builtin::session::SessionMiddleware> {
+) -> crate::error::Result<Option<crate::middleware::builtin::session::SessionMiddleware>> {
     if !config.session.enabled {
-        return None;
+        return Ok(None);
     }
 
     let session_config: InternalSessionConfig = config.session.clone().into();
@@ -106,15 +110,13 @@ pub async fn create_session_middleware(
     )
     .await
     {
-        Ok(middleware) => Some(middleware),
+        Ok(middleware) => Ok(Some(middleware)),
         Err(e) => {
             log::warn!(
                 "Failed to create configured session storage: {}, falling back to memory",
                 e
             );
-            Some(crate::middleware::builtin::session::SessionMiddleware::new(
-                session_config,
-            ))
+            crate::middleware::builtin::session::SessionMiddleware::new(session_config).map(Some)
         }
     }
 }
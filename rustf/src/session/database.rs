@@ -0,0 +1,390 @@
+use crate::database::{DatabaseAdapter, SqlValue};
+use crate::error::{Error, Result};
+use crate::models::query_builder::dialects::create_dialect;
+use crate::models::query_builder::{DatabaseBackend, SchemaBuilder};
+use crate::session::storage::fingerprint_matches;
+use crate::session::{
+    unix_timestamp, FingerprintMode, SessionData, SessionFingerprint, SessionStorage, StorageStats,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Database-backed session storage implementation
+///
+/// Sessions live in one table of a database the application already uses,
+/// through its `DatabaseAdapter`: the session ID as primary key, the
+/// `SessionData` as JSON (`JSONB` on PostgreSQL) and `expires_at` as a Unix
+/// timestamp in seconds. Rows past `expires_at` are ignored by reads and
+/// removed by `cleanup_expired`.
+pub struct DatabaseSessionStorage {
+    adapter: Arc<dyn DatabaseAdapter>,
+    table: String,
+    queries: Queries,
+    fingerprint_mode: FingerprintMode,
+}
+
+/// Statements in the adapter's dialect, built once
+struct Queries {
+    select: String,
+    upsert: String,
+    touch: String,
+    delete: String,
+    exists: String,
+    cleanup: String,
+    stats: String,
+}
+
+impl Queries {
+    fn new(backend: DatabaseBackend, table: &str) -> Self {
+        let dialect = create_dialect(backend);
+        let table = dialect.quote_identifier(table);
+        let p = |position| dialect.placeholder(position);
+
+        let upsert = match backend {
+            DatabaseBackend::MySQL | DatabaseBackend::MariaDB => format!(
+                "INSERT INTO {} (id, data, expires_at) VALUES (?, ?, ?) \
+                 ON DUPLICATE KEY UPDATE data = VALUES(data), expires_at = VALUES(expires_at)",
+                table
+            ),
+            DatabaseBackend::Postgres | DatabaseBackend::SQLite => format!(
+                "INSERT INTO {} (id, data, expires_at) VALUES ({}, {}, {}) \
+                 ON CONFLICT (id) DO UPDATE SET data = excluded.data, expires_at = excluded.expires_at",
+                table,
+                p(1),
+                p(2),
+                p(3)
+            ),
+        };
+
+        Self {
+            select: format!(
+                "SELECT data FROM {} WHERE id = {} AND expires_at > {}",
+                table,
+                p(1),
+                p(2)
+            ),
+            upsert,
+            touch: format!(
+                "UPDATE {} SET expires_at = {} WHERE id = {} AND expires_at > {}",
+                table,
+                p(1),
+                p(2),
+                p(3)
+            ),
+            delete: format!("DELETE FROM {} WHERE id = {}", table, p(1)),
+            exists: format!(
+                "SELECT 1 AS found FROM {} WHERE id = {} AND expires_at > {}",
+                table,
+                p(1),
+                p(2)
+            ),
+            cleanup: format!("DELETE FROM {} WHERE expires_at < {}", table, p(1)),
+            stats: format!(
+                "SELECT COUNT(*) AS total, \
+                 SUM(CASE WHEN expires_at > {} THEN 1 ELSE 0 END) AS active FROM {}",
+                p(1),
+                table
+            ),
+        }
+    }
+}
+
+impl DatabaseSessionStorage {
+    /// Create database session storage on an adapter, in `table`
+    pub fn new(
+        adapter: Box<dyn DatabaseAdapter>,
+        table: &str,
+        fingerprint_mode: FingerprintMode,
+    ) -> Self {
+        Self {
+            queries: Queries::new(adapter.backend(), table),
+            adapter: Arc::from(adapter),
+            table: table.to_string(),
+            fingerprint_mode,
+        }
+    }
+
+    /// Create database session storage on a connection of the database registry
+    ///
+    /// `None` uses the default connection.
+    pub async fn from_registry(
+        connection: Option<&str>,
+        table: &str,
+        fingerprint_mode: FingerprintMode,
+    ) -> Result<Self> {
+        let adapter = match connection {
+            Some(name) => crate::db::DB::adapter(name).await?,
+            None => crate::db::DB::default_adapter().await?,
+        };
+        Ok(Self::new(adapter, table, fingerprint_mode))
+    }
+
+    /// Create the sessions table unless it already exists
+    ///
+    /// An index on `expires_at` speeds up `cleanup_expired` on large tables;
+    /// add it in a migration if needed.
+    pub async fn create_table(&self) -> Result<()> {
+        let sql = SchemaBuilder::new(self.adapter.backend())
+            .create_table(&self.table)
+            .if_not_exists()
+            .string("id", Some(128))
+            .not_null()
+            .json("data")
+            .not_null()
+            .big_integer("expires_at")
+            .not_null()
+            .primary_key(&["id"])
+            .build();
+
+        self.adapter.execute(&sql, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Delete expired sessions every `interval` in a background task
+    pub fn start_cleanup_task(self: &Arc<Self>, interval: Duration) {
+        let storage = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match storage.cleanup_expired().await {
+                    Ok(0) => {}
+                    Ok(cleaned_up) => log::info!(
+                        "Database storage cleaned up {} expired sessions",
+                        cleaned_up
+                    ),
+                    Err(e) => log::warn!("Database session cleanup failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Unix timestamp `ttl` from now
+    fn expires_at(ttl: Duration) -> i64 {
+        i64::try_from(unix_timestamp().saturating_add(ttl.as_secs())).unwrap_or(i64::MAX)
+    }
+
+    fn now() -> SqlValue {
+        SqlValue::BigInt(Self::expires_at(Duration::ZERO))
+    }
+}
+
+/// Session data from its column, stored as JSON or as JSON text
+fn decode(value: Option<&Value>) -> Result<SessionData> {
+    let decoded = match value {
+        Some(Value::String(text)) => serde_json::from_str(text),
+        Some(value) => serde_json::from_value(value.clone()),
+        None => return Err(Error::internal("Session row has no data column")),
+    };
+    decoded.map_err(|e| {
+        Error::internal(format!(
+            "Failed to deserialize session data (corrupted?): {}",
+            e
+        ))
+    })
+}
+
+/// A count column, which some drivers return as a decimal string
+fn count(value: Option<&Value>) -> usize {
+    match value {
+        Some(Value::Number(number)) => number.as_u64().unwrap_or(0) as usize,
+        Some(Value::String(text)) => text.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[async_trait]
+impl SessionStorage for DatabaseSessionStorage {
+    async fn get(
+        &self,
+        session_id: &str,
+        current_fingerprint: Option<&SessionFingerprint>,
+    ) -> Result<Option<SessionData>> {
+        let row = self
+            .adapter
+            .fetch_one(
+                &self.queries.select,
+                vec![SqlValue::String(session_id.to_string()), Self::now()],
+            )
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        // `expires_at` covers the idle timeout: `touch` moves it without
+        // rewriting the `last_accessed` stored in `data`
+        let mut session_data = decode(row.get("data"))?;
+
+        // Validate fingerprint if provided
+        if let Some(current_fp) = current_fingerprint {
+            if let Some(ref stored_fp) = session_data.fingerprint {
+                if !fingerprint_matches(self.fingerprint_mode, stored_fp, current_fp) {
+                    log::warn!(
+                        "DatabaseStorage: Session {} failed fingerprint validation",
+                        session_id
+                    );
+                    return Ok(None);
+                }
+            }
+        }
+
+        session_data.touch();
+        Ok(Some(session_data))
+    }
+
+    async fn set(&self, session_id: &str, data: &SessionData, ttl: Duration) -> Result<()> {
+        let json_data = serde_json::to_value(data)
+            .map_err(|e| Error::internal(format!("Failed to serialize session data: {}", e)))?;
+
+        self.adapter
+            .execute(
+                &self.queries.upsert,
+                vec![
+                    SqlValue::String(session_id.to_string()),
+                    SqlValue::Json(json_data),
+                    SqlValue::BigInt(Self::expires_at(ttl)),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn touch(&self, session_id: &str, ttl: Duration) -> Result<bool> {
+        let result = self
+            .adapter
+            .execute(
+                &self.queries.touch,
+                vec![
+                    SqlValue::BigInt(Self::expires_at(ttl)),
+                    SqlValue::String(session_id.to_string()),
+                    Self::now(),
+                ],
+            )
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete(&self, session_id: &str) -> Result<()> {
+        self.adapter
+            .execute(
+                &self.queries.delete,
+                vec![SqlValue::String(session_id.to_string())],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn exists(&self, session_id: &str) -> Result<bool> {
+        let row = self
+            .adapter
+            .fetch_one(
+                &self.queries.exists,
+                vec![SqlValue::String(session_id.to_string()), Self::now()],
+            )
+            .await?;
+        Ok(row.is_some())
+    }
+
+    async fn cleanup_expired(&self) -> Result<usize> {
+        let result = self
+            .adapter
+            .execute(&self.queries.cleanup, vec![Self::now()])
+            .await?;
+        Ok(result.rows_affected as usize)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "database"
+    }
+
+    async fn stats(&self) -> Result<StorageStats> {
+        let row = self
+            .adapter
+            .fetch_one(&self.queries.stats, vec![Self::now()])
+            .await?
+            .unwrap_or(Value::Null);
+        let total_sessions = count(row.get("total"));
+        let active_sessions = count(row.get("active"));
+
+        let mut backend_metrics = HashMap::new();
+        backend_metrics.insert("database_table".to_string(), self.table.clone());
+        backend_metrics.insert(
+            "database_connection".to_string(),
+            self.adapter.name().to_string(),
+        );
+
+        Ok(StorageStats {
+            total_sessions,
+            active_sessions,
+            expired_sessions: total_sessions.saturating_sub(active_sessions),
+            backend_metrics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queries_per_dialect() {
+        let postgres = Queries::new(DatabaseBackend::Postgres, "sessions");
+        assert_eq!(
+            postgres.select,
+            r#"SELECT data FROM "sessions" WHERE id = $1 AND expires_at > $2"#
+        );
+        assert!(postgres
+            .upsert
+            .contains("VALUES ($1, $2, $3) ON CONFLICT (id)"));
+        assert_eq!(
+            postgres.cleanup,
+            r#"DELETE FROM "sessions" WHERE expires_at < $1"#
+        );
+
+        let mysql = Queries::new(DatabaseBackend::MySQL, "sessions");
+        assert!(mysql.upsert.contains("ON DUPLICATE KEY UPDATE"));
+        assert_eq!(
+            mysql.touch,
+            "UPDATE `sessions` SET expires_at = ? WHERE id = ? AND expires_at > ?"
+        );
+    }
+
+    #[test]
+    fn test_decode_json_or_text() {
+        let data = serde_json::to_value(SessionData::new()).unwrap();
+        assert!(decode(Some(&data)).is_ok());
+        assert!(decode(Some(&Value::String(data.to_string()))).is_ok());
+        assert!(decode(Some(&Value::String("{".to_string()))).is_err());
+        assert_eq!(count(Some(&Value::String("12".to_string()))), 12);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_requests_keep_session_alive() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("sessions.db").display()
+        );
+        let adapter = crate::db::DB::create_adapter("sessions", &url)
+            .await
+            .unwrap();
+        let storage = DatabaseSessionStorage::new(adapter, "sessions", FingerprintMode::Soft);
+        storage.create_table().await.unwrap();
+
+        let ttl = Duration::from_secs(2);
+        let session_id = "read_only_database_session";
+        storage
+            .set(session_id, &SessionData::new(), ttl)
+            .await
+            .unwrap();
+
+        // Each read-only request only touches the row, past the TTL in total
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            assert!(storage.get(session_id, None).await.unwrap().is_some());
+            assert!(storage.touch(session_id, ttl).await.unwrap());
+        }
+    }
+}
@@ -32,6 +32,7 @@ impl SessionStorageFactory {
                 absolute_timeout: 86400,              // 24 hours default
                 same_site: "lax".to_string(),
                 fingerprint_mode: "soft".to_string(),
+                same_site_none: Default::default(),
                 exempt_routes: vec![],
                 storage: config.clone(),
             };
@@ -86,19 +87,61 @@ impl SessionStorageFactory {
                 Ok(storage)
             }
 
+            #[cfg(feature = "memcached")]
+            SessionStorageConfig::Memcached {
+                servers,
+                prefix,
+                pool_size,
+                connection_timeout,
+                command_timeout,
+            } => {
+                use crate::session::memcached::MemcachedSessionStorage;
+                let storage = Arc::new(
+                    MemcachedSessionStorage::from_servers(
+                        servers,
+                        prefix,
+                        *pool_size,
+                        fingerprint_mode,
+                        Duration::from_millis(*connection_timeout),
+                        Duration::from_millis(*command_timeout),
+                    )
+                    .await?,
+                );
+                Ok(storage)
+            }
+
+            #[cfg(not(feature = "memcached"))]
+            SessionStorageConfig::Memcached { .. } => Err(crate::error::Error::internal(
+                "Memcached session storage requires the `memcached` feature of rustf",
+            )),
+
             SessionStorageConfig::Database {
-                table: _,
-                connection_url: _,
-                cleanup_interval: _,
+                table,
+                connection_url,
+                connection,
+                cleanup_interval,
             } => {
-                // Database storage should be implemented by users via the SessionStorage trait
-                // This allows for custom database backends and configurations
-                Err(crate::error::Error::internal(
-                    "Database session storage must be implemented by the application. \
-                     Please implement the SessionStorage trait for your database backend. \
-                     See the documentation for examples using SQLx or other database libraries."
-                        .to_string(),
-                ))
+                use crate::session::database::DatabaseSessionStorage;
+                let storage = match connection_url {
+                    Some(url) => DatabaseSessionStorage::new(
+                        crate::db::DB::create_adapter("sessions", url).await?,
+                        table,
+                        fingerprint_mode,
+                    ),
+                    None => {
+                        DatabaseSessionStorage::from_registry(
+                            connection.as_deref(),
+                            table,
+                            fingerprint_mode,
+                        )
+                        .await?
+                    }
+                };
+                storage.create_table().await?;
+
+                let storage = Arc::new(storage);
+                storage.start_cleanup_task(Duration::from_secs(*cleanup_interval));
+                Ok(storage)
             }
         }
     }
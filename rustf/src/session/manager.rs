@@ -1,3 +1,4 @@
+use crate::config::SameSiteNoneMode;
 use crate::error::Result;
 use crate::http::Request;
 use crate::session::{
@@ -56,6 +57,8 @@ pub struct SessionConfig {
     pub http_only: bool,
     /// Cookie SameSite attribute
     pub same_site: SameSite,
+    /// What to do with a `SameSite=None` cookie that is not `Secure`
+    pub same_site_none: SameSiteNoneMode,
     /// Cookie domain
     pub domain: Option<String>,
     /// Cookie path
@@ -89,6 +92,7 @@ impl Default for SessionConfig {
             secure: true,
             http_only: true,
             same_site: SameSite::Lax,
+            same_site_none: SameSiteNoneMode::default(),
             domain: None,
             path: "/".to_string(),
 
@@ -115,7 +119,18 @@ impl SessionConfig {
 
 impl SessionManager {
     /// Create new session manager
-    pub fn new(storage: Arc<dyn SessionStorage>, config: SessionConfig) -> Arc<Self> {
+    ///
+    /// Browsers drop `SameSite=None` cookies that are not `Secure`: such a
+    /// session cookie is made `Secure`, or refused with an error when
+    /// `session.same_site_none = "error"` is configured.
+    pub fn new(storage: Arc<dyn SessionStorage>, mut config: SessionConfig) -> Result<Arc<Self>> {
+        config.secure = crate::http::cookie::secure_attribute_with(
+            &config.cookie_name,
+            config.same_site,
+            config.secure,
+            config.same_site_none,
+        )?;
+
         let manager = Arc::new(Self {
             storage,
             save_strategy: config.save_strategy.clone(),
@@ -132,7 +147,7 @@ impl SessionManager {
             });
         }
 
-        manager
+        Ok(manager)
     }
 
     /// Create session with storage based on configuration
@@ -147,11 +162,11 @@ impl SessionManager {
 
         let storage =
             SessionStorageFactory::create_storage(storage_config, config.fingerprint_mode).await?;
-        Ok(Self::new(storage, config))
+        Self::new(storage, config)
     }
 
     /// Create session with default memory storage (for backwards compatibility)
-    pub fn with_default_storage(config: SessionConfig) -> Arc<Self> {
+    pub fn with_default_storage(config: SessionConfig) -> Result<Arc<Self>> {
         use crate::session::storage::MemorySessionStorage;
         let storage = Arc::new(MemorySessionStorage::new());
         Self::new(storage, config)
@@ -273,9 +288,33 @@ impl SessionManager {
         Ok(())
     }
 
-    /// Force save session (used by middleware at end of request)
+    /// Persist a session at the end of a request (used by middleware)
+    ///
+    /// Changed sessions are written in full. Sessions that were only read get
+    /// their idle timeout extended with `SessionStorage::touch`, falling back
+    /// to a full write on backends that cannot do that.
+    pub async fn save_at_end_of_request(&self, session: &Session) -> Result<()> {
+        if session.is_dirty() {
+            return self.force_save(session).await;
+        }
+
+        if self
+            .storage
+            .touch(session.id(), self.config.idle_timeout)
+            .await?
+        {
+            log::trace!(
+                "SessionManager: Extended unchanged session {}",
+                session.id()
+            );
+            Ok(())
+        } else {
+            self.force_save(session).await
+        }
+    }
+
+    /// Force save session, whether or not it changed
     pub async fn force_save(&self, session: &Session) -> Result<()> {
-        // Always save the session at end of request to ensure data persistence
         let storage_data = session.to_data()?;
         log::debug!(
             "SessionManager: Force saving session {} (dirty: {})",
@@ -367,3 +406,157 @@ impl SessionManager {
         cookie
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::storage::MemorySessionStorage;
+    use crate::session::SessionStore;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Memory storage that counts full writes
+    struct CountingStorage {
+        inner: MemorySessionStorage,
+        supports_touch: bool,
+        writes: AtomicUsize,
+    }
+
+    impl CountingStorage {
+        fn new(supports_touch: bool) -> Arc<Self> {
+            Arc::new(Self {
+                inner: MemorySessionStorage::new(),
+                supports_touch,
+                writes: AtomicUsize::new(0),
+            })
+        }
+
+        fn writes(&self) -> usize {
+            self.writes.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl SessionStorage for CountingStorage {
+        async fn get(
+            &self,
+            session_id: &str,
+            current_fingerprint: Option<&SessionFingerprint>,
+        ) -> Result<Option<SessionData>> {
+            self.inner.get(session_id, current_fingerprint).await
+        }
+
+        async fn set(&self, session_id: &str, data: &SessionData, ttl: Duration) -> Result<()> {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            self.inner.set(session_id, data, ttl).await
+        }
+
+        async fn delete(&self, session_id: &str) -> Result<()> {
+            self.inner.delete(session_id).await
+        }
+
+        async fn exists(&self, session_id: &str) -> Result<bool> {
+            self.inner.exists(session_id).await
+        }
+
+        async fn cleanup_expired(&self) -> Result<usize> {
+            self.inner.cleanup_expired().await
+        }
+
+        async fn touch(&self, session_id: &str, ttl: Duration) -> Result<bool> {
+            if self.supports_touch {
+                self.inner.touch(session_id, ttl).await
+            } else {
+                Ok(false)
+            }
+        }
+
+        fn backend_name(&self) -> &'static str {
+            "counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_sessions_are_not_rewritten() {
+        let storage = CountingStorage::new(true);
+        let manager = SessionManager::new(storage.clone(), SessionConfig::default()).unwrap();
+        let session = Session::new("session-1");
+
+        // Not stored yet, so the touch fails and the session is written
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(storage.writes(), 1);
+
+        // Read-only requests only extend the session
+        session.get::<i64>("user_id");
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(storage.writes(), 1);
+
+        session.set("user_id", 7).unwrap();
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(storage.writes(), 2);
+        assert!(!session.is_dirty());
+
+        // Setting and consuming flash messages are changes too
+        session.flash_set("notice", "Saved").unwrap();
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(
+            session.flash_get::<String>("notice").as_deref(),
+            Some("Saved")
+        );
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert!(session.flash_get_all().is_empty());
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(storage.writes(), 4);
+
+        // Backends without a cheaper touch get a full write every time
+        let storage = CountingStorage::new(false);
+        let manager = SessionManager::new(storage.clone(), SessionConfig::default()).unwrap();
+        manager.save_at_end_of_request(&session).await.unwrap();
+        manager.save_at_end_of_request(&session).await.unwrap();
+        assert_eq!(storage.writes(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_touch_on_read_prefers_touch() {
+        let storage = CountingStorage::new(true);
+        let store = SessionStore::with_storage(storage.clone()).with_touch_on_read(true);
+        storage
+            .set("active", &SessionData::new(), Duration::from_secs(1800))
+            .await
+            .unwrap();
+
+        assert!(store.get("active").await.unwrap().is_some());
+        assert!(store.get("active").await.unwrap().is_some());
+        assert_eq!(storage.writes(), 1);
+
+        // Without a cheaper touch each read writes the session back
+        let storage = CountingStorage::new(false);
+        let store = SessionStore::with_storage(storage.clone()).with_touch_on_read(true);
+        storage
+            .set("active", &SessionData::new(), Duration::from_secs(1800))
+            .await
+            .unwrap();
+        assert!(store.get("active").await.unwrap().is_some());
+        assert_eq!(storage.writes(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_same_site_none_without_secure() {
+        let config = SessionConfig {
+            secure: false,
+            same_site: SameSite::None,
+            ..SessionConfig::default()
+        };
+
+        // Upgraded to Secure by default
+        let manager = SessionManager::with_default_storage(config.clone()).unwrap();
+        assert!(manager.config.secure);
+
+        // Refused under the error policy
+        let config = SessionConfig {
+            same_site_none: SameSiteNoneMode::Error,
+            ..config
+        };
+        assert!(SessionManager::with_default_storage(config).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use crate::error::{Error, Result};
+use crate::session::storage::fingerprint_matches;
+use crate::session::{
+    FingerprintMode, SessionData, SessionFingerprint, SessionStorage, StorageStats,
+};
+use async_trait::async_trait;
+use memcache::{Client, MemcacheError};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest expiration Memcached reads as relative; larger values are Unix timestamps
+const MAX_RELATIVE_EXPIRATION: u64 = 30 * 24 * 60 * 60;
+
+/// Memcached-based session storage implementation
+///
+/// Sessions are stored as JSON items whose expiration is the session TTL, so
+/// Memcached drops idle sessions by itself. Keys are spread over all the
+/// configured servers. The `memcache` client is blocking; every command runs
+/// on the blocking thread pool under the command timeout.
+#[derive(Clone)]
+pub struct MemcachedSessionStorage {
+    client: Client,
+    servers: Vec<String>,
+    prefix: String,
+    fingerprint_mode: FingerprintMode,
+    command_timeout: Duration,
+}
+
+impl MemcachedSessionStorage {
+    /// Create a new Memcached session storage with the default configuration
+    ///
+    /// Uses server memcache://localhost:11211 with prefix "rustf:session:"
+    pub async fn new() -> Result<Self> {
+        Self::from_servers(
+            &["memcache://localhost:11211".to_string()],
+            "rustf:session:",
+            10,
+            FingerprintMode::Soft,
+            Duration::from_secs(5), // 5 seconds connection timeout
+            Duration::from_secs(3), // 3 seconds command timeout
+        )
+        .await
+    }
+
+    /// Create Memcached session storage with custom servers and settings
+    ///
+    /// Servers are `memcache://host:port` URLs; the pool size is per server.
+    pub async fn from_servers(
+        servers: &[String],
+        prefix: &str,
+        pool_size: u32,
+        fingerprint_mode: FingerprintMode,
+        connection_timeout: Duration,
+        command_timeout: Duration,
+    ) -> Result<Self> {
+        if servers.is_empty() {
+            return Err(Error::internal(
+                "Memcached session storage needs at least one server",
+            ));
+        }
+
+        // Connecting opens the pools and checks each server
+        let urls = servers.to_vec();
+        let client = tokio::time::timeout(
+            connection_timeout,
+            tokio::task::spawn_blocking(move || {
+                let client = Client::with_pool_size(urls, pool_size)?;
+                client.set_read_timeout(Some(command_timeout))?;
+                client.set_write_timeout(Some(command_timeout))?;
+                client.version()?;
+                Ok::<_, MemcacheError>(client)
+            }),
+        )
+        .await
+        .map_err(|_| Error::internal("Memcached connection test timed out"))?
+        .map_err(|e| Error::internal(format!("Memcached connection task failed: {}", e)))?
+        .map_err(|e| Error::internal(format!("Memcached connection test failed: {}", e)))?;
+
+        Ok(Self {
+            client,
+            servers: servers.to_vec(),
+            prefix: prefix.to_string(),
+            fingerprint_mode,
+            command_timeout,
+        })
+    }
+
+    /// Get the Memcached key for a session ID
+    fn session_key(&self, session_id: &str) -> String {
+        format!("{}{}", self.prefix, session_id)
+    }
+
+    /// Run a blocking client command with the command timeout
+    async fn command<T, F>(&self, name: &str, command: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Client) -> std::result::Result<T, MemcacheError> + Send + 'static,
+    {
+        let client = self.client.clone();
+        tokio::time::timeout(
+            self.command_timeout,
+            tokio::task::spawn_blocking(move || command(&client)),
+        )
+        .await
+        .map_err(|_| Error::internal(format!("Memcached {} operation timed out", name)))?
+        .map_err(|e| Error::internal(format!("Memcached {} task failed: {}", name, e)))?
+        .map_err(|e| Error::internal(format!("Memcached {} failed: {}", name, e)))
+    }
+}
+
+/// Item expiration for a session TTL
+///
+/// TTLs over 30 days become a Unix timestamp, as Memcached would otherwise
+/// read them as one. A zero TTL would never expire, so it is raised to 1s.
+fn expiration(ttl: Duration) -> u32 {
+    let seconds = ttl.as_secs().max(1);
+    let expiration = if seconds > MAX_RELATIVE_EXPIRATION {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            + seconds
+    } else {
+        seconds
+    };
+    u32::try_from(expiration).unwrap_or(u32::MAX)
+}
+
+#[async_trait]
+impl SessionStorage for MemcachedSessionStorage {
+    async fn get(
+        &self,
+        session_id: &str,
+        current_fingerprint: Option<&SessionFingerprint>,
+    ) -> Result<Option<SessionData>> {
+        let key = self.session_key(session_id);
+        let json_data = self
+            .command("GET", move |client| client.get::<String>(&key))
+            .await?;
+
+        let Some(data) = json_data else {
+            return Ok(None);
+        };
+
+        // Deserialize the session data using simd-json, like the Redis backend
+        let mut json_bytes = data.into_bytes();
+        let mut session_data: SessionData =
+            simd_json::from_slice(&mut json_bytes).map_err(|e| {
+                Error::internal(format!(
+                    "Failed to deserialize session data (corrupted?): {}",
+                    e
+                ))
+            })?;
+
+        // The item expiration covers the idle timeout: `touch` extends it
+        // without rewriting the stored `last_accessed`, so that is not checked
+
+        // Validate fingerprint if provided
+        if let Some(current_fp) = current_fingerprint {
+            if let Some(ref stored_fp) = session_data.fingerprint {
+                if !fingerprint_matches(self.fingerprint_mode, stored_fp, current_fp) {
+                    log::warn!(
+                        "MemcachedStorage: Session {} failed fingerprint validation",
+                        session_id
+                    );
+                    return Ok(None);
+                }
+            }
+        }
+
+        // The item's expiration is extended by `touch` or `set` at the end of
+        // the request
+        session_data.touch();
+        Ok(Some(session_data))
+    }
+
+    async fn set(&self, session_id: &str, data: &SessionData, ttl: Duration) -> Result<()> {
+        let key = self.session_key(session_id);
+
+        // Serialize session data to JSON using serde_json, like the Redis backend
+        let json_data = serde_json::to_string(data)
+            .map_err(|e| Error::internal(format!("Failed to serialize session data: {}", e)))?;
+        let expiration = expiration(ttl);
+
+        self.command("SET", move |client| {
+            client.set(&key, json_data.as_str(), expiration)
+        })
+        .await
+    }
+
+    async fn touch(&self, session_id: &str, ttl: Duration) -> Result<bool> {
+        let key = self.session_key(session_id);
+        let expiration = expiration(ttl);
+
+        // TOUCH only resets the expiration; it reports a miss when the item is gone
+        self.command("TOUCH", move |client| client.touch(&key, expiration))
+            .await
+    }
+
+    async fn delete(&self, session_id: &str) -> Result<()> {
+        let key = self.session_key(session_id);
+        self.command("DELETE", move |client| client.delete(&key))
+            .await?;
+        Ok(())
+    }
+
+    async fn exists(&self, session_id: &str) -> Result<bool> {
+        Ok(self.get(session_id, None).await?.is_some())
+    }
+
+    async fn cleanup_expired(&self) -> Result<usize> {
+        // Memcached drops expired items itself
+        Ok(0)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "memcached"
+    }
+
+    async fn stats(&self) -> Result<StorageStats> {
+        let server_stats = self.command("STATS", |client| client.stats()).await?;
+
+        // Memcached cannot list keys, so sessions are not counted; the
+        // server figures cover every item, sessions or not
+        let mut backend_metrics = HashMap::new();
+        backend_metrics.insert("memcached_servers".to_string(), self.servers.join(","));
+        backend_metrics.insert("memcached_prefix".to_string(), self.prefix.clone());
+        for (server, stats) in server_stats {
+            for name in ["curr_items", "bytes", "limit_maxbytes", "evictions"] {
+                if let Some(value) = stats.get(name) {
+                    backend_metrics.insert(format!("{}:{}", server, name), value.clone());
+                }
+            }
+        }
+
+        Ok(StorageStats {
+            backend_metrics,
+            ..StorageStats::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiration() {
+        assert_eq!(expiration(Duration::from_secs(1800)), 1800);
+        assert_eq!(expiration(Duration::ZERO), 1);
+        assert_eq!(
+            expiration(Duration::from_secs(MAX_RELATIVE_EXPIRATION)),
+            MAX_RELATIVE_EXPIRATION as u32
+        );
+
+        // Longer TTLs are absolute timestamps in the future
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let ttl = MAX_RELATIVE_EXPIRATION + 60;
+        let absolute = expiration(Duration::from_secs(ttl)) as u64;
+        assert!(absolute >= now + ttl && absolute <= now + ttl + 5);
+    }
+
+    async fn create_test_storage() -> MemcachedSessionStorage {
+        MemcachedSessionStorage::from_servers(
+            &["memcache://localhost:11211".to_string()],
+            "rustf:test:session:",
+            2,
+            FingerprintMode::Soft,
+            Duration::from_secs(5),
+            Duration::from_secs(3),
+        )
+        .await
+        .expect("Memcached connection failed - tests require running Memcached server")
+    }
+
+    #[tokio::test]
+    async fn test_read_only_requests_keep_session_alive() {
+        let ttl = Duration::from_secs(2);
+        let storage = create_test_storage().await;
+        let session_id = "read_only_memcached_session";
+        storage
+            .set(session_id, &SessionData::new(), ttl)
+            .await
+            .unwrap();
+
+        // Each read-only request only touches the item, past the TTL in total
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            assert!(storage.get(session_id, None).await.unwrap().is_some());
+            assert!(storage.touch(session_id, ttl).await.unwrap());
+        }
+
+        storage.delete(session_id).await.unwrap();
+    }
+}
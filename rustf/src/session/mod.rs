@@ -8,11 +8,15 @@ use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub mod config_adapter;
+pub mod database;
 pub mod factory;
 pub mod manager;
+#[cfg(feature = "memcached")]
+pub mod memcached;
 pub mod redis;
 pub mod security;
 pub mod storage;
+pub mod tiered;
 
 /// Security fingerprint for session validation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -220,6 +224,17 @@ pub trait SessionStorage: Send + Sync {
             .collect()
     }
 
+    /// Extend a session's lifetime without rewriting its data
+    ///
+    /// Called at the end of requests that read the session but did not change
+    /// it. Returns `false` when the backend has nothing cheaper than `set` or
+    /// no longer holds the session; the caller then writes it in full.
+    /// Backends that implement it must still let the next `get` report the
+    /// session as recently accessed, or it will hit the idle timeout.
+    async fn touch(&self, _session_id: &str, _ttl: Duration) -> Result<bool> {
+        Ok(false)
+    }
+
     /// Get storage backend name for logging/debugging
     fn backend_name(&self) -> &'static str;
 
@@ -251,6 +266,8 @@ pub struct StorageStats {
 pub struct SessionStore {
     storage: Arc<dyn SessionStorage>,
     session_timeout: Duration,
+    /// Write sessions back with a fresh TTL when they are read
+    touch_on_read: bool,
 }
 
 /// Thread-safe session with JSON-native storage and security features
@@ -448,6 +465,7 @@ impl Session {
         if let Value::Object(ref mut map) = *flash {
             map.insert(key.to_string(), value);
         }
+        self.mark_dirty();
         Ok(())
     }
 
@@ -455,7 +473,9 @@ impl Session {
     pub fn flash_get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
         let mut flash = self.flash.write().ok()?;
         if let Value::Object(ref mut map) = *flash {
-            map.remove(key).and_then(|v| serde_json::from_value(v).ok())
+            let value = map.remove(key)?;
+            self.mark_dirty();
+            serde_json::from_value(value).ok()
         } else {
             None
         }
@@ -471,7 +491,10 @@ impl Session {
         if let Value::Object(ref mut map) = *flash {
             // Clone the map and clear the original
             let all_flash: HashMap<String, Value> = map.clone().into_iter().collect();
-            map.clear();
+            if !all_flash.is_empty() {
+                map.clear();
+                self.mark_dirty();
+            }
             all_flash
         } else {
             HashMap::new()
@@ -485,7 +508,10 @@ impl Session {
     pub fn flash_clear(&self) {
         if let Ok(mut flash) = self.flash.write() {
             if let Value::Object(ref mut map) = *flash {
-                map.clear();
+                if !map.is_empty() {
+                    map.clear();
+                    self.mark_dirty();
+                }
             }
         }
     }
@@ -497,7 +523,9 @@ impl Session {
     pub fn flash_remove(&self, key: &str) -> Option<Value> {
         let mut flash = self.flash.write().ok()?;
         if let Value::Object(ref mut map) = *flash {
-            map.remove(key)
+            let value = map.remove(key)?;
+            self.mark_dirty();
+            Some(value)
         } else {
             None
         }
@@ -657,6 +685,12 @@ impl Session {
         }
     }
 
+    fn mark_dirty(&self) {
+        if let Ok(mut dirty) = self.dirty.lock() {
+            *dirty = true;
+        }
+    }
+
     /// Check if session is expired
     pub fn is_expired(&self, idle_timeout_secs: u64) -> bool {
         let now = unix_timestamp();
@@ -705,7 +739,10 @@ impl Session {
             if level > *current {
                 self.mark_for_rotation();
             }
-            *current = level;
+            if level != *current {
+                *current = level;
+                self.mark_dirty();
+            }
         }
     }
 
@@ -748,6 +785,7 @@ impl SessionStore {
         Self {
             storage,
             session_timeout: Duration::from_secs(30 * 60), // 30 minutes default
+            touch_on_read: false,
         }
     }
 
@@ -756,16 +794,60 @@ impl SessionStore {
         Self {
             storage,
             session_timeout,
+            touch_on_read: false,
         }
     }
 
+    /// Refresh the storage TTL whenever a session is read
+    ///
+    /// Each successful `get` then extends the session once, so the idle
+    /// timeout slides with activity: with `SessionStorage::touch` where the
+    /// backend supports it, with a full write otherwise. The TTL never reaches
+    /// past the session's absolute timeout, and expired sessions are not
+    /// extended.
+    pub fn with_touch_on_read(mut self, touch_on_read: bool) -> Self {
+        self.touch_on_read = touch_on_read;
+        self
+    }
+
+    /// Give a session just read a fresh TTL
+    ///
+    /// Touches the session, and only writes it back when the backend cannot.
+    /// Returns `false`, without either, when the session has expired.
+    async fn refresh_on_read(
+        &self,
+        session_id: &str,
+        session_data: &mut SessionData,
+    ) -> crate::error::Result<bool> {
+        let remaining = session_data
+            .absolute_timeout
+            .saturating_sub(unix_timestamp());
+        if remaining == 0 || session_data.is_expired(self.session_timeout.as_secs()) {
+            return Ok(false);
+        }
+
+        let ttl = self.session_timeout.min(Duration::from_secs(remaining));
+        session_data.touch();
+        if !self.storage.touch(session_id, ttl).await? {
+            self.storage.set(session_id, session_data, ttl).await?;
+        }
+        Ok(true)
+    }
+
     /// Get or create a session with automatic expiration tracking
     ///
     /// This method fetches session data from the storage backend or creates
     /// a new session if one doesn't exist.
     pub async fn get_or_create(&self, session_id: &str) -> crate::error::Result<Session> {
         // SimpleSessionManager doesn't have request context, so no fingerprint validation
-        match self.storage.get(session_id, None).await? {
+        let mut found = self.storage.get(session_id, None).await?;
+        if let Some(session_data) = found.as_mut() {
+            if self.touch_on_read && !self.refresh_on_read(session_id, session_data).await? {
+                found = None;
+            }
+        }
+
+        match found {
             Some(session_data) => {
                 // Convert SessionData back to Session
                 Ok(Session::from_data(session_id, session_data))
@@ -788,7 +870,10 @@ impl SessionStore {
     /// Get a session without creating it
     pub async fn get(&self, session_id: &str) -> crate::error::Result<Option<Session>> {
         // SimpleSessionManager doesn't have request context, so no fingerprint validation
-        if let Some(session_data) = self.storage.get(session_id, None).await? {
+        if let Some(mut session_data) = self.storage.get(session_id, None).await? {
+            if self.touch_on_read && !self.refresh_on_read(session_id, &mut session_data).await? {
+                return Ok(None);
+            }
             Ok(Some(Session::from_data(session_id, session_data)))
         } else {
             Ok(None)
@@ -11,6 +11,9 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Most session keys `stats()` scans unless configured otherwise
+const DEFAULT_STATS_SCAN_LIMIT: usize = 100_000;
+
 /// Redis-based session storage implementation with connection pooling
 ///
 /// This implementation provides persistent session storage using Redis as the backend.
@@ -23,6 +26,7 @@ pub struct RedisSessionStorage {
     default_ttl: Duration,
     connection_timeout: Duration,
     command_timeout: Duration,
+    stats_scan_limit: usize,
 }
 
 impl RedisSessionStorage {
@@ -80,6 +84,7 @@ impl RedisSessionStorage {
             default_ttl,
             connection_timeout,
             command_timeout,
+            stats_scan_limit: DEFAULT_STATS_SCAN_LIMIT,
         })
     }
 
@@ -108,9 +113,19 @@ impl RedisSessionStorage {
             default_ttl,
             connection_timeout,
             command_timeout,
+            stats_scan_limit: DEFAULT_STATS_SCAN_LIMIT,
         })
     }
 
+    /// Set the most session keys `stats()` scans (100 000 by default)
+    ///
+    /// Counts stop at the limit, so statistics stay cheap on large instances;
+    /// `backend_metrics["scan_truncated"]` tells when it was reached.
+    pub fn with_stats_scan_limit(mut self, limit: usize) -> Self {
+        self.stats_scan_limit = limit;
+        self
+    }
+
     /// Get the Redis key for a session ID
     fn session_key(&self, session_id: &str) -> String {
         format!("{}{}", self.prefix, session_id)
@@ -257,7 +272,7 @@ impl SessionStorage for RedisSessionStorage {
                     current_ttl as u64
                 } else if current_ttl == -1 {
                     // Key exists but has no expiry - set default TTL using EXPIRE
-                    tokio::time::timeout(
+                    let _: i64 = tokio::time::timeout(
                         self.command_timeout,
                         redis::cmd("EXPIRE").arg(&key).arg(self.default_ttl.as_secs()).query_async(&mut conn),
                     )
@@ -273,7 +288,7 @@ impl SessionStorage for RedisSessionStorage {
                 // Refresh TTL using EXPIRE (much faster than SETEX - no data rewrite)
                 // Only if TTL is less than 50% remaining
                 if ttl_to_use < (self.default_ttl.as_secs() / 2) {
-                    tokio::time::timeout(
+                    let _: i64 = tokio::time::timeout(
                         self.command_timeout,
                         redis::cmd("EXPIRE").arg(&key).arg(self.default_ttl.as_secs()).query_async(&mut conn),
                     )
@@ -314,6 +329,22 @@ impl SessionStorage for RedisSessionStorage {
         Ok(())
     }
 
+    async fn touch(&self, session_id: &str, ttl: Duration) -> Result<bool> {
+        let mut conn = self.pool.get().await?;
+        let key = self.session_key(session_id);
+
+        // EXPIRE only resets the TTL; it answers 0 when the key is already gone
+        let extended: i64 = tokio::time::timeout(
+            self.command_timeout,
+            redis::cmd("EXPIRE").arg(&key).arg(ttl.as_secs()).query_async(&mut conn),
+        )
+        .await
+        .map_err(|_| Error::internal("Redis EXPIRE operation timed out"))?
+        .map_err(|e| Error::internal(format!("Redis EXPIRE failed: {}", e)))?;
+
+        Ok(extended == 1)
+    }
+
     async fn delete(&self, session_id: &str) -> Result<()> {
         let mut conn = self.pool.get().await?;
         let key = self.session_key(session_id);
@@ -362,12 +393,14 @@ impl SessionStorage for RedisSessionStorage {
 
         // Use SCAN to count sessions with our prefix
         let pattern = format!("{}*", self.prefix);
-        let mut total_sessions = 0;
+        let mut active_sessions = 0;
+        let mut expired_sessions = 0;
+        let mut sessions_without_ttl = 0;
+        let mut scanned = 0;
         let mut cursor = 0u64;
 
-        // Count sessions using SCAN (non-blocking iteration)
-        // Use larger batch size for better performance
-        loop {
+        // Count sessions using SCAN (non-blocking iteration), up to the scan limit
+        let truncated = loop {
             let (new_cursor, keys): (u64, Vec<String>) = tokio::time::timeout(
                 self.command_timeout,
                 redis::cmd("SCAN")
@@ -382,18 +415,46 @@ impl SessionStorage for RedisSessionStorage {
             .map_err(|_| Error::internal("Redis SCAN operation timed out"))?
             .map_err(|e| Error::internal(format!("Redis SCAN failed: {}", e)))?;
 
-            total_sessions += keys.len();
+            let remaining = self.stats_scan_limit - scanned;
+            let truncated = keys.len() > remaining || (keys.len() == remaining && new_cursor != 0);
+            let keys = &keys[..keys.len().min(remaining)];
+            scanned += keys.len();
             cursor = new_cursor;
 
-            if cursor == 0 {
-                break;
+            // TTL of each key in one round trip: -2 once the key has expired
+            // since the scan, -1 for a key without expiration
+            if !keys.is_empty() {
+                let mut pipe = redis::pipe();
+                for key in keys {
+                    pipe.cmd("TTL").arg(key);
+                }
+                let ttls: Vec<i64> =
+                    tokio::time::timeout(self.command_timeout, pipe.query_async(&mut conn))
+                        .await
+                        .map_err(|_| Error::internal("Redis TTL operation timed out"))?
+                        .map_err(|e| Error::internal(format!("Redis TTL failed: {}", e)))?;
+
+                for ttl in ttls {
+                    match ttl {
+                        -2 => expired_sessions += 1,
+                        -1 => {
+                            sessions_without_ttl += 1;
+                            active_sessions += 1;
+                        }
+                        _ => active_sessions += 1,
+                    }
+                }
+            }
+
+            if cursor == 0 || truncated {
+                break truncated;
             }
-        }
+        };
 
         // Get Redis info for additional metrics
         let info: String = tokio::time::timeout(
             self.command_timeout,
-            redis::cmd("INFO").arg("memory").query_async(&mut conn),
+            redis::cmd("INFO").query_async(&mut conn),
         )
         .await
         .map_err(|_| Error::internal("Redis INFO operation timed out"))?
@@ -402,28 +463,39 @@ impl SessionStorage for RedisSessionStorage {
         let mut backend_metrics = HashMap::new();
         backend_metrics.insert("redis_pattern".to_string(), pattern);
         backend_metrics.insert("scan_method".to_string(), "non-blocking".to_string());
+        backend_metrics.insert("scan_truncated".to_string(), truncated.to_string());
+        backend_metrics.insert(
+            "sessions_without_ttl".to_string(),
+            sessions_without_ttl.to_string(),
+        );
 
-        // Parse memory info from Redis INFO command
-        for line in info.lines() {
-            if line.starts_with("used_memory_human:") {
-                let memory = line.split(':').nth(1).unwrap_or("unknown").trim();
-                backend_metrics.insert("redis_memory_used".to_string(), memory.to_string());
-            }
-            if line.starts_with("used_memory_peak_human:") {
-                let memory = line.split(':').nth(1).unwrap_or("unknown").trim();
-                backend_metrics.insert("redis_memory_peak".to_string(), memory.to_string());
+        // Pick figures from the Redis INFO sections
+        for (field, metric) in [
+            ("used_memory", "redis_used_memory"),
+            ("used_memory_human", "redis_memory_used"),
+            ("used_memory_peak_human", "redis_memory_peak"),
+            ("connected_clients", "redis_connected_clients"),
+        ] {
+            if let Some(value) = info_field(&info, field) {
+                backend_metrics.insert(metric.to_string(), value.to_string());
             }
         }
 
         Ok(StorageStats {
-            total_sessions,
-            active_sessions: total_sessions, // Redis only stores active sessions
-            expired_sessions: 0,             // Redis automatically removes expired sessions
+            total_sessions: active_sessions + expired_sessions,
+            active_sessions,
+            expired_sessions,
             backend_metrics,
         })
     }
 }
 
+/// Value of a `field:value` line of a Redis INFO reply
+fn info_field<'a>(info: &'a str, field: &str) -> Option<&'a str> {
+    info.lines()
+        .find_map(|line| line.strip_prefix(field)?.strip_prefix(':').map(str::trim))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,6 +626,16 @@ mod tests {
         }
     }
 
+    #[test]
+    async fn test_info_field() {
+        let info = "# Memory\r\nused_memory:1048576\r\nused_memory_human:1.00M\r\n\
+                    # Clients\r\nconnected_clients:3\r\n";
+        assert_eq!(info_field(info, "used_memory"), Some("1048576"));
+        assert_eq!(info_field(info, "used_memory_human"), Some("1.00M"));
+        assert_eq!(info_field(info, "connected_clients"), Some("3"));
+        assert_eq!(info_field(info, "used_memory_peak"), None);
+    }
+
     #[test]
     async fn test_redis_storage_concurrent_access() {
         let storage = create_test_storage().await;
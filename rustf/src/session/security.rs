@@ -451,7 +451,10 @@ impl SecureSessionManager {
             cookie.push_str("; HttpOnly");
         }
 
-        if self.config.cookie_config.secure {
+        // SameSite=None cookies are dropped by browsers unless Secure
+        if self.config.cookie_config.secure
+            || matches!(self.config.cookie_config.same_site, SameSitePolicy::None)
+        {
             cookie.push_str("; Secure");
         }
 
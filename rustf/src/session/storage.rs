@@ -75,44 +75,7 @@ impl MemorySessionStorage {
         stored: &SessionFingerprint,
         current: &SessionFingerprint,
     ) -> bool {
-        match self.fingerprint_mode {
-            FingerprintMode::Disabled => true,
-            FingerprintMode::Soft => {
-                // Compare IP prefix (first 3 octets) and user agent hash
-                let stored_ip_prefix = Self::extract_ip_prefix(&stored.ip);
-                let current_ip_prefix = Self::extract_ip_prefix(&current.ip);
-                let stored_ua_hash = Self::hash_user_agent(&stored.user_agent);
-                let current_ua_hash = Self::hash_user_agent(&current.user_agent);
-
-                stored_ip_prefix == current_ip_prefix && stored_ua_hash == current_ua_hash
-            }
-            FingerprintMode::Strict => {
-                // Exact match on both IP and user agent
-                stored.ip == current.ip && stored.user_agent == current.user_agent
-            }
-        }
-    }
-
-    /// Extract IP prefix (first 3 octets) for soft validation
-    fn extract_ip_prefix(ip: &str) -> String {
-        // Handle both IPv4 and IPv6
-        if ip.contains(':') {
-            // IPv6: take first 3 segments
-            ip.split(':').take(3).collect::<Vec<_>>().join(":")
-        } else {
-            // IPv4: take first 3 octets
-            ip.split('.').take(3).collect::<Vec<_>>().join(".")
-        }
-    }
-
-    /// Hash user agent for soft validation
-    fn hash_user_agent(user_agent: &str) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        user_agent.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+        fingerprint_matches(self.fingerprint_mode, stored, current)
     }
 
     /// Start background cleanup task to remove expired sessions
@@ -159,6 +122,52 @@ impl MemorySessionStorage {
     }
 }
 
+/// Compare a stored fingerprint with the current one in the given mode
+pub(crate) fn fingerprint_matches(
+    mode: FingerprintMode,
+    stored: &SessionFingerprint,
+    current: &SessionFingerprint,
+) -> bool {
+    match mode {
+        FingerprintMode::Disabled => true,
+        FingerprintMode::Soft => {
+            // Compare IP prefix (first 3 octets) and user agent hash
+            let stored_ip_prefix = extract_ip_prefix(&stored.ip);
+            let current_ip_prefix = extract_ip_prefix(&current.ip);
+            let stored_ua_hash = hash_user_agent(&stored.user_agent);
+            let current_ua_hash = hash_user_agent(&current.user_agent);
+
+            stored_ip_prefix == current_ip_prefix && stored_ua_hash == current_ua_hash
+        }
+        FingerprintMode::Strict => {
+            // Exact match on both IP and user agent
+            stored.ip == current.ip && stored.user_agent == current.user_agent
+        }
+    }
+}
+
+/// Extract IP prefix (first 3 octets) for soft validation
+fn extract_ip_prefix(ip: &str) -> String {
+    // Handle both IPv4 and IPv6
+    if ip.contains(':') {
+        // IPv6: take first 3 segments
+        ip.split(':').take(3).collect::<Vec<_>>().join(":")
+    } else {
+        // IPv4: take first 3 octets
+        ip.split('.').take(3).collect::<Vec<_>>().join(".")
+    }
+}
+
+/// Hash user agent for soft validation
+fn hash_user_agent(user_agent: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    user_agent.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
 impl Default for MemorySessionStorage {
     fn default() -> Self {
         Self::new()
@@ -208,6 +217,16 @@ impl SessionStorage for MemorySessionStorage {
         Ok(())
     }
 
+    async fn touch(&self, session_id: &str, _ttl: Duration) -> Result<bool> {
+        match self.sessions.get_mut(session_id) {
+            Some(mut session_data) => {
+                session_data.touch();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     async fn delete(&self, session_id: &str) -> Result<()> {
         self.sessions.remove(session_id);
         Ok(())
@@ -374,6 +393,42 @@ mod tests {
         assert!(!storage.exists(session_id).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_session_store_touch_on_read() {
+        let storage = Arc::new(MemorySessionStorage::new());
+        let store =
+            crate::session::SessionStore::with_storage(storage.clone()).with_touch_on_read(true);
+
+        let mut session_data = SessionData::new();
+        session_data.last_accessed -= 60;
+        let absolute_timeout = session_data.absolute_timeout;
+        storage
+            .set("active", &session_data, Duration::from_secs(1800))
+            .await
+            .unwrap();
+
+        let session = store.get("active").await.unwrap().unwrap();
+        let stored = session.to_data().unwrap();
+        assert!(stored.last_accessed > session_data.last_accessed);
+        assert_eq!(stored.absolute_timeout, absolute_timeout);
+
+        let stored = storage.get("active", None).await.unwrap().unwrap();
+        assert!(stored.last_accessed > session_data.last_accessed);
+        assert_eq!(stored.absolute_timeout, absolute_timeout);
+
+        // Past the absolute timeout nothing is written back
+        let mut session_data = SessionData::new();
+        session_data.absolute_timeout = MemorySessionStorage::now() - 1;
+        storage
+            .set("expired", &session_data, Duration::from_secs(1800))
+            .await
+            .unwrap();
+        assert!(store.get("expired").await.unwrap().is_none());
+        assert!(!storage.exists("expired").await.unwrap());
+        assert!(store.get("missing").await.unwrap().is_none());
+        assert!(!storage.exists("missing").await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_memory_storage_expiration() {
         let storage = MemorySessionStorage::with_timeout(
@@ -0,0 +1,328 @@
+use crate::error::Result;
+use crate::session::{SessionData, SessionFingerprint, SessionStorage, StorageStats};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Two-tier session storage: a fast local layer in front of a shared one
+///
+/// Reads are served from the local layer (L1, typically
+/// `MemorySessionStorage`) and fall back to the shared layer (L2, typically
+/// `RedisSessionStorage`), copying the session into L1 on a hit. Writes and
+/// deletes go to both layers, the shared one first.
+///
+/// A local copy is only trusted for the L1 TTL after it was loaded or
+/// written, so changes made on other nodes show up within that time. Keep
+/// it short (a few seconds) when several nodes serve the same sessions.
+///
+/// Fingerprint validation is delegated to whichever layer returns the data:
+/// both layers should use the same `FingerprintMode`.
+pub struct TieredSessionStorage {
+    local: Arc<dyn SessionStorage>,
+    remote: Arc<dyn SessionStorage>,
+    local_ttl: Duration,
+    /// When each local copy was loaded or written
+    loaded_at: DashMap<String, Instant>,
+}
+
+impl TieredSessionStorage {
+    /// Create tiered storage over a local and a shared layer
+    ///
+    /// `local_ttl` bounds how long a local copy is served without going back
+    /// to the shared layer, independently of the session TTL.
+    pub fn new(
+        local: Arc<dyn SessionStorage>,
+        remote: Arc<dyn SessionStorage>,
+        local_ttl: Duration,
+    ) -> Self {
+        Self {
+            local,
+            remote,
+            local_ttl,
+            loaded_at: DashMap::new(),
+        }
+    }
+
+    /// Whether the local copy of a session is recent enough to serve
+    fn is_fresh(&self, session_id: &str) -> bool {
+        self.loaded_at
+            .get(session_id)
+            .is_some_and(|loaded_at| loaded_at.elapsed() < self.local_ttl)
+    }
+
+    /// Store a local copy, logging rather than failing: L2 holds the session
+    async fn store_local(&self, session_id: &str, data: &SessionData, ttl: Duration) {
+        match self
+            .local
+            .set(session_id, data, ttl.min(self.local_ttl))
+            .await
+        {
+            Ok(()) => {
+                self.loaded_at
+                    .insert(session_id.to_string(), Instant::now());
+            }
+            Err(e) => {
+                self.loaded_at.remove(session_id);
+                log::warn!(
+                    "TieredStorage: Failed to cache session {} locally: {}",
+                    session_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Drop the local copy of a session
+    async fn evict_local(&self, session_id: &str) -> Result<()> {
+        self.loaded_at.remove(session_id);
+        self.local.delete(session_id).await
+    }
+}
+
+#[async_trait]
+impl SessionStorage for TieredSessionStorage {
+    async fn get(
+        &self,
+        session_id: &str,
+        current_fingerprint: Option<&SessionFingerprint>,
+    ) -> Result<Option<SessionData>> {
+        if self.is_fresh(session_id) {
+            if let Some(session_data) = self.local.get(session_id, current_fingerprint).await? {
+                return Ok(Some(session_data));
+            }
+        } else if self.loaded_at.contains_key(session_id) {
+            // Stale local copy
+            self.evict_local(session_id).await?;
+        }
+
+        match self.remote.get(session_id, current_fingerprint).await? {
+            Some(session_data) => {
+                self.store_local(session_id, &session_data, self.local_ttl)
+                    .await;
+                Ok(Some(session_data))
+            }
+            None => {
+                self.evict_local(session_id).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn set(&self, session_id: &str, data: &SessionData, ttl: Duration) -> Result<()> {
+        self.remote.set(session_id, data, ttl).await?;
+        self.store_local(session_id, data, ttl).await;
+        Ok(())
+    }
+
+    async fn touch(&self, session_id: &str, ttl: Duration) -> Result<bool> {
+        let touched = self.remote.touch(session_id, ttl).await?;
+        if touched {
+            if self.is_fresh(session_id) {
+                self.local
+                    .touch(session_id, ttl.min(self.local_ttl))
+                    .await?;
+            }
+        } else {
+            self.evict_local(session_id).await?;
+        }
+        Ok(touched)
+    }
+
+    async fn delete(&self, session_id: &str) -> Result<()> {
+        self.remote.delete(session_id).await?;
+        self.evict_local(session_id).await
+    }
+
+    async fn exists(&self, session_id: &str) -> Result<bool> {
+        // The shared layer is authoritative
+        self.remote.exists(session_id).await
+    }
+
+    async fn cleanup_expired(&self) -> Result<usize> {
+        self.loaded_at
+            .retain(|_, loaded_at| loaded_at.elapsed() < self.local_ttl);
+        let local = self.local.cleanup_expired().await?;
+        let remote = self.remote.cleanup_expired().await?;
+        Ok(local + remote)
+    }
+
+    fn generate_id(&self) -> String {
+        self.remote.generate_id()
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "tiered"
+    }
+
+    async fn stats(&self) -> Result<StorageStats> {
+        // Session counts come from the shared layer
+        let mut stats = self.remote.stats().await?;
+        let local = self.local.stats().await?;
+
+        let metrics = &mut stats.backend_metrics;
+        metrics.insert(
+            "local_backend".to_string(),
+            self.local.backend_name().to_string(),
+        );
+        metrics.insert(
+            "remote_backend".to_string(),
+            self.remote.backend_name().to_string(),
+        );
+        metrics.insert(
+            "local_sessions".to_string(),
+            local.total_sessions.to_string(),
+        );
+        metrics.insert(
+            "local_ttl_secs".to_string(),
+            self.local_ttl.as_secs().to_string(),
+        );
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::storage::MemorySessionStorage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Shared layer counting its reads
+    struct FakeRemote {
+        storage: MemorySessionStorage,
+        gets: AtomicUsize,
+    }
+
+    impl FakeRemote {
+        fn new() -> Self {
+            Self {
+                storage: MemorySessionStorage::new(),
+                gets: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SessionStorage for FakeRemote {
+        async fn get(
+            &self,
+            session_id: &str,
+            current_fingerprint: Option<&SessionFingerprint>,
+        ) -> Result<Option<SessionData>> {
+            self.gets.fetch_add(1, Ordering::SeqCst);
+            self.storage.get(session_id, current_fingerprint).await
+        }
+
+        async fn set(&self, session_id: &str, data: &SessionData, ttl: Duration) -> Result<()> {
+            self.storage.set(session_id, data, ttl).await
+        }
+
+        async fn delete(&self, session_id: &str) -> Result<()> {
+            self.storage.delete(session_id).await
+        }
+
+        async fn exists(&self, session_id: &str) -> Result<bool> {
+            self.storage.exists(session_id).await
+        }
+
+        async fn cleanup_expired(&self) -> Result<usize> {
+            self.storage.cleanup_expired().await
+        }
+
+        fn backend_name(&self) -> &'static str {
+            "fake"
+        }
+    }
+
+    fn session_with(key: &str, value: &str) -> SessionData {
+        let mut session_data = SessionData::new();
+        if let serde_json::Value::Object(ref mut map) = session_data.data {
+            map.insert(
+                key.to_string(),
+                serde_json::Value::String(value.to_string()),
+            );
+        }
+        session_data
+    }
+
+    #[tokio::test]
+    async fn test_tiered_storage_local_miss_repopulates_from_remote() {
+        let local = Arc::new(MemorySessionStorage::new());
+        let remote = Arc::new(FakeRemote::new());
+        let storage =
+            TieredSessionStorage::new(local.clone(), remote.clone(), Duration::from_secs(60));
+
+        // Written by another node: only the shared layer has it
+        remote
+            .set(
+                "session_1",
+                &session_with("user", "alice"),
+                Duration::from_secs(3600),
+            )
+            .await
+            .unwrap();
+        assert!(local.get("session_1", None).await.unwrap().is_none());
+
+        let session_data = storage.get("session_1", None).await.unwrap().unwrap();
+        assert_eq!(session_data.data["user"], "alice");
+        assert_eq!(remote.gets.load(Ordering::SeqCst), 1);
+        assert!(local.get("session_1", None).await.unwrap().is_some());
+
+        // Served locally from now on
+        storage.get("session_1", None).await.unwrap().unwrap();
+        assert_eq!(remote.gets.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tiered_storage_stale_local_copy() {
+        let local = Arc::new(MemorySessionStorage::new());
+        let remote = Arc::new(FakeRemote::new());
+        let storage = TieredSessionStorage::new(local.clone(), remote.clone(), Duration::ZERO);
+
+        storage
+            .set(
+                "session_1",
+                &session_with("user", "alice"),
+                Duration::from_secs(3600),
+            )
+            .await
+            .unwrap();
+        // Changed by another node
+        remote
+            .set(
+                "session_1",
+                &session_with("user", "bob"),
+                Duration::from_secs(3600),
+            )
+            .await
+            .unwrap();
+
+        let session_data = storage.get("session_1", None).await.unwrap().unwrap();
+        assert_eq!(session_data.data["user"], "bob");
+        assert_eq!(remote.gets.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tiered_storage_delete_removes_both() {
+        let local = Arc::new(MemorySessionStorage::new());
+        let remote = Arc::new(FakeRemote::new());
+        let storage =
+            TieredSessionStorage::new(local.clone(), remote.clone(), Duration::from_secs(60));
+
+        storage
+            .set(
+                "session_1",
+                &session_with("user", "alice"),
+                Duration::from_secs(3600),
+            )
+            .await
+            .unwrap();
+        assert!(local.exists("session_1").await.unwrap());
+        assert!(remote.exists("session_1").await.unwrap());
+
+        storage.delete("session_1").await.unwrap();
+        assert!(!local.exists("session_1").await.unwrap());
+        assert!(!remote.exists("session_1").await.unwrap());
+        assert!(storage.get("session_1", None).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,194 @@
+//! OpenTelemetry tracing integration
+//!
+//! With the `otel` feature enabled, `TracingMiddleware` opens a server span
+//! for every request and queries made through `DB` are recorded as child
+//! spans of it. Spans go to the tracer provider installed with
+//! `opentelemetry::global::set_tracer_provider`; without one they are no-ops.
+//!
+//! ```rust,ignore
+//! use rustf::middleware::builtin::TracingMiddleware;
+//!
+//! // Install an exporter (e.g. opentelemetry-otlp) before starting the app
+//! opentelemetry::global::set_tracer_provider(provider);
+//!
+//! let app = RustF::new()
+//!     .middleware_from(|registry| registry.register_dual("tracing", TracingMiddleware::new()));
+//! ```
+//!
+//! Incoming `traceparent` headers are honoured so the request span joins the
+//! caller's trace. If a global text map propagator is installed it is used,
+//! otherwise the W3C header is parsed directly.
+
+use opentelemetry::global::{self, BoxedTracer};
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::{
+    FutureExt as _, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId,
+    TraceState, Tracer,
+};
+use opentelemetry::{Context, KeyValue};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Instrumentation name used for all RustF spans
+pub const TRACER_NAME: &str = "rustf";
+
+tokio::task_local! {
+    /// Trace context of the request being handled on this task
+    static REQUEST_CONTEXT: RefCell<Option<Context>>;
+}
+
+/// Get the RustF tracer from the global tracer provider
+pub fn tracer() -> BoxedTracer {
+    global::tracer(TRACER_NAME)
+}
+
+/// Run a request future with a slot for its trace context
+///
+/// `TracingMiddleware` stores the request span here so that spans created
+/// further down the call chain (database queries) become its children.
+pub async fn scope<F: Future>(fut: F) -> F::Output {
+    REQUEST_CONTEXT.scope(RefCell::new(None), fut).await
+}
+
+/// Set the trace context of the current request
+///
+/// Does nothing when called outside of [`scope`].
+pub fn set_current(cx: Context) {
+    let _ = REQUEST_CONTEXT.try_with(|slot| *slot.borrow_mut() = Some(cx));
+}
+
+/// Trace context of the current request, if one is being traced
+pub fn current() -> Option<Context> {
+    REQUEST_CONTEXT
+        .try_with(|slot| slot.borrow().clone())
+        .ok()
+        .flatten()
+}
+
+/// Extract the parent trace context from request headers
+///
+/// Header names are expected in lowercase, as stored on `Request`.
+pub fn extract_parent(headers: &HashMap<String, String>) -> Context {
+    let cx =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    if cx.span().span_context().is_valid() {
+        return cx;
+    }
+
+    match headers
+        .get("traceparent")
+        .and_then(|value| parse_traceparent(value))
+    {
+        Some(span_context) => Context::new().with_remote_span_context(span_context),
+        None => Context::new(),
+    }
+}
+
+/// Parse a W3C `traceparent` header into a remote span context
+fn parse_traceparent(value: &str) -> Option<SpanContext> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    // Version 00 has exactly four fields; later versions may append more
+    if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+        return None;
+    }
+    if trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+        return None;
+    }
+
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+
+    let span_context = SpanContext::new(
+        trace_id,
+        span_id,
+        TraceFlags::new(flags) & TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    span_context.is_valid().then_some(span_context)
+}
+
+/// Record a database query as a child span of the current request
+///
+/// The span carries `db.system` and `db.statement` and is marked as an error
+/// when the query fails.
+pub async fn trace_query<T, E, F>(system: &'static str, sql: &str, fut: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let parent = current().unwrap_or_else(Context::current);
+    let span = tracer()
+        .span_builder("db.query")
+        .with_kind(SpanKind::Client)
+        .with_attributes([
+            KeyValue::new("db.system", system),
+            KeyValue::new("db.statement", sql.to_string()),
+        ])
+        .start_with_context(&tracer(), &parent);
+    let cx = parent.with_span(span);
+
+    let result = fut.with_context(cx.clone()).await;
+
+    let span = cx.span();
+    if let Err(e) = &result {
+        span.set_status(Status::error(e.to_string()));
+    }
+    span.end();
+    result
+}
+
+/// Read-only view of request headers for text map propagators
+struct HeaderExtractor<'a>(&'a HashMap<String, String>);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(&key.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_traceparent() {
+        let mut headers = HashMap::new();
+        headers.insert(
+            "traceparent".to_string(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+        );
+
+        let cx = extract_parent(&headers);
+        let span = cx.span();
+        let parent = span.span_context();
+        assert!(parent.is_valid());
+        assert!(parent.is_remote());
+        assert!(parent.is_sampled());
+        assert_eq!(
+            parent.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+
+        // All-zero ids and unknown field layouts are rejected
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-xx")
+                .is_none()
+        );
+        assert!(parse_traceparent("garbage").is_none());
+    }
+}
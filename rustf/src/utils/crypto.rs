@@ -7,7 +7,11 @@
 //! - MD5 and SHA1 are considered cryptographically broken for security purposes
 //! - Use SHA256 or higher for security-sensitive applications
 //! - These functions are provided for compatibility and non-security use cases
+//! - Never store passwords with any of them; use [`hash_password`] (Argon2id)
 
+use crate::error::{Error, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
 use md5::Md5;
 use sha1::Sha1;
 use sha2::{Digest, Sha256, Sha512};
@@ -345,6 +349,175 @@ pub fn sha512_bytes(input: &[u8]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Compute an HMAC-SHA256 message authentication code
+///
+/// Implements RFC 2104 on top of SHA256. Use this to sign values that must be
+/// verified later (signed cookies, webhook payloads, tokens).
+///
+/// # Arguments
+/// * `key` - Secret key
+/// * `data` - Message to authenticate
+///
+/// # Example
+/// ```rust,ignore
+/// let mac = hmac_sha256(b"secret", b"message");
+/// assert_eq!(mac.len(), 32);
+/// ```
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        let digest = Sha256::digest(key);
+        block[..digest.len()].copy_from_slice(&digest);
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; BLOCK_SIZE];
+    let mut opad = [0x5cu8; BLOCK_SIZE];
+    for ((i, o), k) in ipad.iter_mut().zip(opad.iter_mut()).zip(block.iter()) {
+        *i ^= k;
+        *o ^= k;
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(data);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_hash);
+    outer.finalize().to_vec()
+}
+
+/// Compute an HMAC-SHA256 and return it as a lowercase hex string
+///
+/// # Example
+/// ```rust,ignore
+/// let signature = hmac_sha256_hex("secret", "message");
+/// ```
+pub fn hmac_sha256_hex(key: &str, data: &str) -> String {
+    hmac_sha256(key.as_bytes(), data.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Argon2id cost settings for [`hash_password_with`]
+///
+/// The defaults follow the OWASP Password Storage Cheat Sheet: 19 MiB of
+/// memory, 2 iterations, 1 lane. Raise them as far as your login latency
+/// budget allows; existing hashes keep verifying with the settings they were
+/// created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes over the memory
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for PasswordParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl PasswordParams {
+    fn hasher(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| Error::internal(format!("Invalid password hashing parameters: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+/// Hash a password with Argon2id and the default [`PasswordParams`]
+///
+/// Returns a PHC string (`$argon2id$v=19$m=...`) that embeds the salt and
+/// parameters, ready to be stored as is. Hashing takes tens of milliseconds
+/// of CPU by design, so it runs on tokio's blocking thread pool rather than
+/// stalling the worker thread serving other requests.
+///
+/// # Example
+/// ```rust,ignore
+/// let hash = U::Crypto::hash_password("correct horse battery staple").await?;
+/// assert!(U::Crypto::verify_password("correct horse battery staple", &hash).await);
+/// ```
+pub async fn hash_password(password: &str) -> Result<String> {
+    hash_password_with(password, PasswordParams::default()).await
+}
+
+/// Hash a password with Argon2id and custom cost settings
+pub async fn hash_password_with(password: &str, params: PasswordParams) -> Result<String> {
+    let hasher = params.hasher()?;
+    let password = password.to_string();
+    blocking(move || {
+        let salt: [u8; 16] = rand::random();
+        let salt = SaltString::encode_b64(&salt)
+            .map_err(|e| Error::internal(format!("Failed to encode password salt: {}", e)))?;
+
+        hasher
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| Error::internal(format!("Failed to hash password: {}", e)))
+    })
+    .await?
+}
+
+/// Check a password against a hash from [`hash_password`]
+///
+/// The comparison runs in constant time on the blocking thread pool.
+/// Malformed hashes never match.
+pub async fn verify_password(password: &str, hash: &str) -> bool {
+    let password = password.to_string();
+    let hash = hash.to_string();
+    blocking(move || match PasswordHash::new(&hash) {
+        // The parameters stored in the hash are used, not the defaults
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    })
+    .await
+    .unwrap_or(false)
+}
+
+/// Run CPU-heavy password work on the blocking thread pool
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| Error::internal(format!("Password hashing task failed: {}", e)))
+}
+
+/// Whether a stored hash was made with other settings than `params`
+///
+/// Check it after a successful login and store a fresh hash when it returns
+/// true, so old hashes are upgraded as users sign in.
+pub fn password_needs_rehash(hash: &str, params: PasswordParams) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return true;
+    };
+    let Ok(stored) = Params::try_from(&parsed) else {
+        return true;
+    };
+    parsed.algorithm.as_str() != "argon2id"
+        || stored.m_cost() != params.memory_kib
+        || stored.t_cost() != params.iterations
+        || stored.p_cost() != params.parallelism
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -606,4 +779,51 @@ mod tests {
         assert!(!sha256("").is_empty());
         assert!(!sha512("").is_empty());
     }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256_hex("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        // Keys longer than the block size are hashed first
+        let long_key = vec![0xaau8; 131];
+        let mac = hmac_sha256(
+            &long_key,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        let hex: String = mac.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            hex,
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_password_hashing() {
+        let params = PasswordParams {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let hash = hash_password_with("s3cret", params).await.unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+        assert!(verify_password("s3cret", &hash).await);
+        assert!(!verify_password("S3cret", &hash).await);
+        assert!(!verify_password("s3cret", "not a hash").await);
+
+        // Each hash gets its own salt
+        assert_ne!(hash, hash_password_with("s3cret", params).await.unwrap());
+
+        assert!(!password_needs_rehash(&hash, params));
+        assert!(password_needs_rehash(&hash, PasswordParams::default()));
+
+        let invalid = PasswordParams {
+            memory_kib: 1,
+            ..params
+        };
+        assert!(hash_password_with("s3cret", invalid).await.is_err());
+    }
 }
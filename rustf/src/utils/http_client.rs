@@ -0,0 +1,502 @@
+//! Outbound HTTP client with retries
+//!
+//! A thin layer over `reqwest` with the conventions apps otherwise re-derive
+//! for every external API: connect and request timeouts, pooled keep-alive
+//! connections, retries through `error::retry` and an optional circuit
+//! breaker. Failures come back as framework errors:
+//!
+//! - transport failures become `Error::Network`, timeouts `Error::Timeout`
+//! - non-2xx responses become `Error::ExternalService` with the status and
+//!   the start of the response body
+//!
+//! Only connection failures, timeouts, 5xx, 408 and 429 responses are
+//! retried, and only for idempotent methods unless a request opts in with
+//! [`HttpRequest::retryable`]. A `Retry-After` header on those responses
+//! sets the delay before the next attempt; when it asks for longer than the
+//! policy's `max_delay`, the call fails instead.
+//!
+//! Requires the `http-client` feature.
+//!
+//! ```rust,ignore
+//! use rustf::U;
+//!
+//! let user: GithubUser = U::Http::client()?
+//!     .get("https://api.github.com/users/octocat")
+//!     .header("Accept", "application/vnd.github+json")
+//!     .send_json()
+//!     .await?;
+//!
+//! let payments = HttpClient::builder()
+//!     .service("payments")
+//!     .timeout(Duration::from_secs(10))
+//!     .circuit_breaker(Arc::new(CircuitBreaker::new(5, 2, Duration::from_secs(30), 1)))
+//!     .build()?;
+//! payments
+//!     .post("https://payments.example.com/charges")
+//!     .header("Idempotency-Key", &order_id)
+//!     .json(&charge)
+//!     .retryable(true)
+//!     .send()
+//!     .await?;
+//! ```
+
+use crate::error::retry::{CircuitBreaker, RetryPolicy};
+use crate::error::{Error, Result};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Shared client returned by [`client`]
+static DEFAULT_CLIENT: OnceLock<HttpClient> = OnceLock::new();
+
+/// Longest part of an error response body kept in the error message
+const ERROR_BODY_LIMIT: usize = 512;
+
+/// The shared client with default settings
+///
+/// Clones share one connection pool, so this is cheap to call per request.
+/// Fails only if the TLS backend cannot be initialized.
+pub fn client() -> Result<HttpClient> {
+    if let Some(client) = DEFAULT_CLIENT.get() {
+        return Ok(client.clone());
+    }
+    let client = HttpClient::builder().build()?;
+    Ok(DEFAULT_CLIENT.get_or_init(|| client).clone())
+}
+
+/// HTTP client with timeouts, retries and error mapping
+#[derive(Clone)]
+pub struct HttpClient {
+    inner: reqwest::Client,
+    service: Option<String>,
+    retry: Option<RetryPolicy>,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl HttpClient {
+    /// Start configuring a client
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder::default()
+    }
+
+    /// Start a GET request
+    pub fn get(&self, url: &str) -> HttpRequest {
+        self.request(Method::GET, url)
+    }
+
+    /// Start a POST request
+    pub fn post(&self, url: &str) -> HttpRequest {
+        self.request(Method::POST, url)
+    }
+
+    /// Start a PUT request
+    pub fn put(&self, url: &str) -> HttpRequest {
+        self.request(Method::PUT, url)
+    }
+
+    /// Start a PATCH request
+    pub fn patch(&self, url: &str) -> HttpRequest {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Start a DELETE request
+    pub fn delete(&self, url: &str) -> HttpRequest {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Start a request with any method
+    pub fn request(&self, method: Method, url: &str) -> HttpRequest {
+        let retryable = is_idempotent(&method);
+        HttpRequest {
+            client: self.clone(),
+            builder: self.inner.request(method.clone(), url),
+            method,
+            url: url.to_string(),
+            retryable,
+        }
+    }
+
+    /// The underlying `reqwest` client, for anything not covered here
+    pub fn inner(&self) -> &reqwest::Client {
+        &self.inner
+    }
+}
+
+/// Settings for an [`HttpClient`]
+#[derive(Clone)]
+pub struct HttpClientBuilder {
+    service: Option<String>,
+    timeout: Duration,
+    connect_timeout: Duration,
+    pool_idle_timeout: Duration,
+    pool_max_idle_per_host: usize,
+    user_agent: String,
+    retry: Option<RetryPolicy>,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl Default for HttpClientBuilder {
+    fn default() -> Self {
+        Self {
+            service: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(5),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 32,
+            user_agent: format!("rustf/{}", env!("CARGO_PKG_VERSION")),
+            retry: Some(RetryPolicy::exponential(3)),
+            breaker: None,
+        }
+    }
+}
+
+impl HttpClientBuilder {
+    /// Name used in `Error::ExternalService` (defaults to the URL's host)
+    pub fn service(mut self, name: &str) -> Self {
+        self.service = Some(name.to_string());
+        self
+    }
+
+    /// Limit for a whole request, including reading the body (default 30s)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Limit for establishing a connection (default 5s)
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// How long unused pooled connections are kept (default 90s)
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Idle connections kept per host (default 32)
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Set the `User-Agent` header (default `rustf/<version>`)
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Retry policy for failed attempts (default: 3 attempts, exponential)
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Never retry
+    pub fn no_retry(mut self) -> Self {
+        self.retry = None;
+        self
+    }
+
+    /// Send every request through `breaker`
+    ///
+    /// Share the breaker between clients that call the same service. Only
+    /// failures that would be retried count against it; a 404 means the
+    /// service is up.
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Create the client
+    pub fn build(self) -> Result<HttpClient> {
+        let inner = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .user_agent(self.user_agent)
+            .build()
+            .map_err(|e| Error::internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(HttpClient {
+            inner,
+            service: self.service,
+            retry: self.retry,
+            breaker: self.breaker,
+        })
+    }
+}
+
+/// A request being built
+pub struct HttpRequest {
+    client: HttpClient,
+    builder: RequestBuilder,
+    method: Method,
+    url: String,
+    retryable: bool,
+}
+
+impl HttpRequest {
+    /// Add a header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.builder = self.builder.header(name, value);
+        self
+    }
+
+    /// Add query parameters
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    /// Send a JSON body
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        self.builder = self.builder.json(body);
+        self
+    }
+
+    /// Send a URL-encoded form body
+    pub fn form<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        self.builder = self.builder.form(body);
+        self
+    }
+
+    /// Send a raw body
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.builder = self.builder.body(body.into());
+        self
+    }
+
+    /// Add an `Authorization: Bearer` header
+    pub fn bearer_auth(mut self, token: &str) -> Self {
+        self.builder = self.builder.bearer_auth(token);
+        self
+    }
+
+    /// Override the timeout for this request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.timeout(timeout);
+        self
+    }
+
+    /// Allow or forbid retries regardless of the method
+    ///
+    /// Enable it for POSTs the service deduplicates, e.g. with an
+    /// idempotency key.
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Send the request and return the successful response
+    pub async fn send(self) -> Result<Response> {
+        let service = self.service_name();
+        let retried = || self.send_with_retries(&service);
+        let response = match &self.client.breaker {
+            Some(breaker) => breaker.execute(retried).await?,
+            None => retried().await?,
+        };
+
+        // Statuses that are not worth retrying still fail the call
+        if !response.status().is_success() {
+            return Err(self.status_error(&service, response).await);
+        }
+        Ok(response)
+    }
+
+    /// Send the request and deserialize the JSON response
+    pub async fn send_json<T: DeserializeOwned>(self) -> Result<T> {
+        let service = self.service_name();
+        let url = self.url.clone();
+        let response = self.send().await?;
+        response.json::<T>().await.map_err(|e| {
+            Error::external_service(service, format!("Invalid JSON from {}: {}", url, e))
+        })
+    }
+
+    /// Configured service name, or the URL's host
+    fn service_name(&self) -> String {
+        self.client
+            .service
+            .clone()
+            .or_else(|| {
+                reqwest::Url::parse(&self.url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_string))
+            })
+            .unwrap_or_else(|| "http".to_string())
+    }
+
+    /// Attempts allowed by the retry policy, waiting between them
+    ///
+    /// The delay is the policy's unless the response said how long to wait
+    /// with `Retry-After`.
+    async fn send_with_retries(&self, service: &str) -> Result<Response> {
+        let Some(policy) = self.client.retry.as_ref().filter(|_| self.retryable) else {
+            return self.attempt(service).await.map_err(|failure| failure.error);
+        };
+
+        let mut attempt = 1;
+        loop {
+            let failure = match self.attempt(service).await {
+                Ok(response) => return Ok(response),
+                Err(failure) => failure,
+            };
+            if !failure.error.is_retryable() || attempt >= policy.max_attempts {
+                return Err(failure.error);
+            }
+
+            let delay = match failure.retry_after {
+                Some(delay) if delay > policy.max_delay => {
+                    log::warn!(
+                        "{} {} asked to retry after {:?}, longer than the {:?} allowed",
+                        self.method,
+                        self.url,
+                        delay,
+                        policy.max_delay
+                    );
+                    return Err(failure.error);
+                }
+                Some(delay) => delay,
+                None => policy.calculate_delay(attempt),
+            };
+            log::warn!(
+                "Retry attempt {}/{} after {:?} for error: {}",
+                attempt,
+                policy.max_attempts,
+                delay,
+                failure.error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// One attempt; only failures worth retrying are returned as errors
+    async fn attempt(&self, service: &str) -> std::result::Result<Response, Failure> {
+        let request = self.builder.try_clone().ok_or_else(|| {
+            Failure::from(Error::internal(
+                "HTTP request body cannot be cloned for sending",
+            ))
+        })?;
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Failure::from(self.transport_error(e)))?;
+
+        if is_retryable_status(response.status()) {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(Failure {
+                error: self.status_error(service, response).await,
+                retry_after,
+            });
+        }
+        Ok(response)
+    }
+
+    fn transport_error(&self, error: reqwest::Error) -> Error {
+        let message = format!("{} {} failed: {}", self.method, self.url, error);
+        if error.is_timeout() {
+            Error::timeout(message)
+        } else {
+            Error::network(message)
+        }
+    }
+
+    async fn status_error(&self, service: &str, response: Response) -> Error {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let body: String = body.chars().take(ERROR_BODY_LIMIT).collect();
+        Error::external_service(
+            service,
+            format!(
+                "{} {} returned {}: {}",
+                self.method,
+                self.url,
+                status,
+                body.trim()
+            ),
+        )
+    }
+}
+
+/// A failed attempt and the delay the server asked for, if any
+struct Failure {
+    error: Error,
+    retry_after: Option<Duration>,
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Self {
+            error,
+            retry_after: None,
+        }
+    }
+}
+
+/// Delay of a `Retry-After` value, given in seconds or as an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.signed_duration_since(chrono::Utc::now());
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Statuses that may succeed when the request is sent again
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_classification() {
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::OK));
+
+        let client = HttpClient::builder().build().unwrap();
+        assert!(client.get("https://example.com").retryable);
+        assert!(!client.post("https://example.com").retryable);
+        assert!(client.post("https://example.com").retryable(true).retryable);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        // Dates in the past mean retry now
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let delay = parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -16,6 +16,8 @@ pub mod crypto;
 pub mod encoding;
 pub mod geo;
 pub mod http;
+#[cfg(feature = "http-client")]
+pub mod http_client;
 pub mod object;
 pub mod pagination;
 pub mod parsing;
@@ -61,6 +63,8 @@ pub mod U {
     /// HTTP utilities namespace
     pub mod Http {
         pub use super::super::http::*;
+        #[cfg(feature = "http-client")]
+        pub use super::super::http_client::{client, HttpClient, HttpClientBuilder, HttpRequest};
     }
 
     /// Object utilities namespace
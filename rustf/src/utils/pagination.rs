@@ -95,3 +95,51 @@ impl Pagination {
         })
     }
 }
+
+/// Page information for paginated API responses
+///
+/// Serialized as the `meta` object of `ctx.json_paginated()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageMeta {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+impl PageMeta {
+    /// Describe page `page` (1-based) of `total` items
+    pub fn new(page: u32, per_page: u32, total: i64) -> Self {
+        let total_pages = if per_page == 0 {
+            0
+        } else {
+            (total.max(0) as u64).div_ceil(per_page as u64) as u32
+        };
+        Self {
+            page,
+            per_page,
+            total,
+            total_pages,
+        }
+    }
+}
+
+/// One page of query results with its page information
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub meta: PageMeta,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_meta_total_pages() {
+        assert_eq!(PageMeta::new(1, 20, 0).total_pages, 0);
+        assert_eq!(PageMeta::new(1, 20, 20).total_pages, 1);
+        assert_eq!(PageMeta::new(2, 20, 41).total_pages, 3);
+        assert_eq!(PageMeta::new(1, 0, 41).total_pages, 0);
+    }
+}
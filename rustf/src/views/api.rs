@@ -1,12 +1,15 @@
+use crate::error::Error;
 use crate::views::{
     totaljs::{
         parser::Parser,
         renderer::{RenderContext, Renderer},
+        TotalJsEngine,
     },
-    ViewEngine,
+    ViewEngine, ViewEngineImpl,
 };
 use crate::Result;
 use serde_json::{json, Value};
+use std::path::Path;
 use std::sync::{Arc, OnceLock};
 
 /// Global ViewEngine instance for inline template rendering
@@ -138,6 +141,88 @@ impl VIEW {
         let mut renderer = Renderer::new(context);
         renderer.render(&template)
     }
+
+    /// Render a template from the `views` directory without a running app
+    ///
+    /// Meant for unit and snapshot tests: it needs neither the global VIEW
+    /// nor a server, and `cargo test` runs from the package root so `views`
+    /// is the application's own views directory. `data` reaches the template
+    /// the same way `ctx.view()` passes it, so the output matches what a
+    /// handler would send.
+    ///
+    /// # Returns
+    /// The rendered HTML, or `Error::Template` naming the template that
+    /// could not be loaded, parsed or rendered
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[test]
+    /// fn product_page_renders() {
+    ///     let html = VIEW::render_to_string(
+    ///         "products/show",
+    ///         json!({"name": "Widget", "price": 9.5}),
+    ///         Some("main"),
+    ///     )
+    ///     .unwrap();
+    ///     assert!(html.contains("<h1>Widget</h1>"));
+    /// }
+    /// ```
+    pub fn render_to_string(template: &str, data: Value, layout: Option<&str>) -> Result<String> {
+        Self::render_to_string_in("views", template, data, layout)
+    }
+
+    /// Render a template from `views_dir` without a running app
+    ///
+    /// Same as [`VIEW::render_to_string`] for tests that keep their templates
+    /// somewhere else, such as fixtures or a temporary directory.
+    pub fn render_to_string_in(
+        views_dir: impl AsRef<Path>,
+        template: &str,
+        data: Value,
+        layout: Option<&str>,
+    ) -> Result<String> {
+        let views_dir = views_dir.as_ref();
+        let engine = TotalJsEngine::new(&views_dir.to_string_lossy());
+
+        // Same shape as Context::view() so internal fields stay out of the model
+        let data = match data {
+            Value::Object(mut map) => {
+                map.insert("_context_repository".to_string(), json!({}));
+                map.insert("_context_session".to_string(), json!({}));
+                Value::Object(map)
+            }
+            other => json!({
+                "data": other,
+                "_context_repository": {},
+                "_context_session": {}
+            }),
+        };
+
+        engine.render(template, &data, layout).map_err(|e| {
+            let reason = match e {
+                Error::Template(reason) => reason,
+                other => other.to_string(),
+            };
+            Error::template(format!(
+                "Failed to render '{}' from {}: {}",
+                template,
+                views_dir.display(),
+                reason
+            ))
+        })
+    }
+
+    /// Drop all compiled templates
+    ///
+    /// Templates are cached after their first render. Outside production the
+    /// cache notices changed files on its own; in production it trusts the
+    /// cache, so call this after replacing template files on disk.
+    /// Does nothing if the global VIEW is not initialized.
+    pub fn clear_cache() {
+        if let Some(engine) = GLOBAL_VIEW_ENGINE.get() {
+            engine.clear_cache();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -284,4 +369,32 @@ mod tests {
         assert!(html.contains("Hello, Bob!"));
         assert!(html.contains("© 2025 My Company"));
     }
+
+    #[test]
+    fn test_render_to_string() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("products")).unwrap();
+        std::fs::write(dir.path().join("products/show.html"), "<h1>@{M.name}</h1>").unwrap();
+        std::fs::create_dir(dir.path().join("layouts")).unwrap();
+        std::fs::write(dir.path().join("layouts/main.html"), "<main>@{body}</main>").unwrap();
+
+        let html =
+            VIEW::render_to_string_in(dir.path(), "products/show", json!({"name": "Widget"}), None)
+                .unwrap();
+        assert_eq!(html, "<h1>Widget</h1>");
+
+        let html = VIEW::render_to_string_in(
+            dir.path(),
+            "products/show",
+            json!({"name": "Widget"}),
+            Some("main"),
+        )
+        .unwrap();
+        assert_eq!(html, "<main><h1>Widget</h1></main>");
+
+        let error =
+            VIEW::render_to_string_in(dir.path(), "products/missing", json!({}), None).unwrap_err();
+        assert!(matches!(error, Error::Template(_)));
+        assert!(error.to_string().contains("'products/missing'"));
+    }
 }
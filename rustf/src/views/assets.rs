@@ -0,0 +1,163 @@
+//! Cache-busting URLs for static assets
+//!
+//! `@{asset('css/app.css')}` renders the public URL of a file in the static
+//! directory with a version taken from its content:
+//!
+//! ```text
+//! /static/css/app.css?v=5d41402abc4b
+//! ```
+//!
+//! The version only changes when the file does, so assets can be served with
+//! a long `cache_max_age` and clients still fetch the new file after a
+//! deploy. With `views.cache_enabled` each file is hashed once and
+//! remembered until the view cache is cleared; otherwise it is hashed on
+//! every render so edits show up during development.
+
+use crate::config::StaticConfig;
+use crate::utils::crypto::sha256_bytes;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Hex digits of the content hash used as the version
+const VERSION_LENGTH: usize = 12;
+
+/// Versioned URLs for the files served from `static_files.directory`
+#[derive(Debug)]
+pub struct AssetVersions {
+    directory: PathBuf,
+    url_prefix: String,
+    remember: bool,
+    /// Version per asset path, `None` for files that could not be read
+    versions: RwLock<HashMap<String, Option<String>>>,
+}
+
+impl AssetVersions {
+    /// Resolve assets from the static file configuration
+    ///
+    /// `remember` keeps each version once computed instead of hashing the
+    /// file again on every call.
+    pub fn new(config: &StaticConfig, remember: bool) -> Self {
+        Self {
+            directory: PathBuf::from(&config.directory),
+            url_prefix: config.url_prefix.trim_end_matches('/').to_string(),
+            remember,
+            versions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The public URL of `path` with its version appended
+    ///
+    /// Absolute URLs are returned unchanged. A file that is missing from the
+    /// static directory gets its URL without a version and a warning.
+    pub fn url(&self, path: &str) -> String {
+        if path.contains("://") || path.starts_with("//") {
+            return path.to_string();
+        }
+
+        let path = path.trim_start_matches('/');
+        let url = format!("{}/{}", self.url_prefix, path);
+        match self.version(path) {
+            Some(version) => format!("{}?v={}", url, version),
+            None => url,
+        }
+    }
+
+    /// Forget the computed versions so changed files are hashed again
+    pub fn clear(&self) {
+        if let Ok(mut versions) = self.versions.write() {
+            versions.clear();
+        }
+    }
+
+    fn version(&self, path: &str) -> Option<String> {
+        if !self.remember {
+            return self.compute_version(path);
+        }
+        if let Some(version) = self.versions.read().ok()?.get(path) {
+            return version.clone();
+        }
+
+        let version = self.compute_version(path);
+        if let Ok(mut versions) = self.versions.write() {
+            versions.insert(path.to_string(), version.clone());
+        }
+        version
+    }
+
+    fn compute_version(&self, path: &str) -> Option<String> {
+        if path.split('/').any(|segment| segment == "..") {
+            log::warn!("asset('{}'): path leaves the static directory", path);
+            return None;
+        }
+
+        match std::fs::read(self.directory.join(path)) {
+            Ok(content) => {
+                let mut hash = sha256_bytes(&content);
+                hash.truncate(VERSION_LENGTH);
+                Some(hash)
+            }
+            Err(e) => {
+                log::warn!(
+                    "asset('{}'): cannot read {}: {}",
+                    path,
+                    self.directory.join(path).display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset_versions(directory: &std::path::Path, remember: bool) -> AssetVersions {
+        let config = StaticConfig {
+            directory: directory.to_string_lossy().to_string(),
+            ..StaticConfig::default()
+        };
+        AssetVersions::new(&config, remember)
+    }
+
+    #[test]
+    fn test_versioned_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("css")).unwrap();
+        std::fs::write(dir.path().join("css/app.css"), "body {}").unwrap();
+        let assets = asset_versions(dir.path(), true);
+
+        let url = assets.url("css/app.css");
+        let version = url.strip_prefix("/static/css/app.css?v=").unwrap();
+        assert_eq!(version.len(), VERSION_LENGTH);
+        assert_eq!(assets.url("/css/app.css"), url);
+
+        // Versions are remembered until cleared
+        std::fs::write(dir.path().join("css/app.css"), "body { margin: 0 }").unwrap();
+        assert_eq!(assets.url("css/app.css"), url);
+        assets.clear();
+        let changed = assets.url("css/app.css");
+        assert_ne!(changed, url);
+
+        // Without remembering, every call sees the current content
+        let fresh = asset_versions(dir.path(), false);
+        assert_eq!(fresh.url("css/app.css"), changed);
+        std::fs::write(dir.path().join("css/app.css"), "body {}").unwrap();
+        assert_eq!(fresh.url("css/app.css"), url);
+    }
+
+    #[test]
+    fn test_unversioned_fallbacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let assets = asset_versions(dir.path(), true);
+
+        assert_eq!(assets.url("js/missing.js"), "/static/js/missing.js");
+        assert_eq!(assets.url("../secret.txt"), "/static/../secret.txt");
+        assert_eq!(
+            assets.url("https://cdn.example.com/app.js"),
+            "https://cdn.example.com/app.js"
+        );
+    }
+}
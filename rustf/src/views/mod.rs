@@ -4,12 +4,39 @@ use serde_json::Value;
 use std::sync::Arc;
 
 pub mod api;
+pub mod assets;
 pub mod totaljs; // Total.js is the default built-in template engine // Global VIEW API for inline template rendering
 
+/// Field errors and submitted values of a form rendered again after validation failed
+#[derive(Clone, Debug, Default)]
+pub struct FormState {
+    /// First error of each field, nested like the fields
+    pub errors: Value,
+    /// Submitted values to refill the fields with
+    pub old: Value,
+}
+
 /// Trait for view engine implementations
 pub trait ViewEngineImpl: Send + Sync {
     fn render(&self, template: &str, data: &Value, layout: Option<&str>) -> Result<String>;
     fn set_directory(&mut self, dir: &str);
+
+    /// Render a redrawn form, exposing `form` to the template as `errors` and `old`
+    ///
+    /// Engines without form state render the template as usual.
+    fn render_form(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        form: &FormState,
+    ) -> Result<String> {
+        let _ = form;
+        self.render(template, data, layout)
+    }
+
+    /// Drop compiled templates so the next render reads them from disk
+    fn clear_cache(&self) {}
 }
 
 /// Main ViewEngine that delegates to different implementations
@@ -22,6 +49,7 @@ pub use totaljs::TotalJsEngine;
 
 // Re-export the global VIEW API
 pub use api::VIEW;
+pub use assets::AssetVersions;
 
 impl Default for ViewEngine {
     fn default() -> Self {
@@ -118,6 +146,22 @@ impl ViewEngine {
     pub fn render(&self, template: &str, data: &Value, layout: Option<&str>) -> Result<String> {
         self.engine.render(template, data, layout)
     }
+
+    /// Render a redrawn form with its errors and submitted values
+    pub fn render_form(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        form: &FormState,
+    ) -> Result<String> {
+        self.engine.render_form(template, data, layout, form)
+    }
+
+    /// Drop compiled templates so changed files are picked up on next render
+    pub fn clear_cache(&self) {
+        self.engine.clear_cache();
+    }
 }
 
 /// Builder for ViewEngine configuration
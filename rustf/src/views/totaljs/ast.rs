@@ -87,6 +87,12 @@ pub enum Node {
         is_key: bool,
     },
 
+    /// Plural translation @(#key, count)
+    TranslatePlural {
+        key: String,
+        count: Expression,
+    },
+
     /// Configuration value @{'%config-key'}
     Config(String),
 
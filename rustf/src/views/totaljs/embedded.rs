@@ -1,13 +1,14 @@
 use super::{
     ast::Template,
+    engine::RequestData,
     parser::Parser,
     renderer::{RenderContext, Renderer, TemplateLoader},
     translation::TranslationSystem,
 };
-use crate::config::{AppConfig, ViewConfig};
+use crate::config::{AppConfig, StaticConfig, ViewConfig};
 use crate::error::{Error, Result};
 use crate::repository::APP;
-use crate::views::ViewEngineImpl;
+use crate::views::{AssetVersions, FormState, ViewEngineImpl};
 use rust_embed::RustEmbed;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -106,6 +107,8 @@ pub struct EmbeddedTotalJsEngine {
     app_config: Option<Arc<AppConfig>>,
     /// Translation system
     translator: Arc<RwLock<Option<TranslationSystem>>>,
+    /// Versioned static asset URLs for `@{asset(...)}`
+    assets: Arc<AssetVersions>,
 }
 
 impl EmbeddedTotalJsEngine {
@@ -129,6 +132,10 @@ impl EmbeddedTotalJsEngine {
             config: Arc::new(RwLock::new(HashMap::new())),
             app_config: None,
             translator: Arc::new(RwLock::new(None)),
+            assets: Arc::new(AssetVersions::new(
+                &StaticConfig::default(),
+                !enable_hot_reload,
+            )),
         }
     }
 
@@ -154,6 +161,10 @@ impl EmbeddedTotalJsEngine {
         Self {
             cache: TemplateCache::new(enable_hot_reload),
             config: Arc::new(RwLock::new(config)),
+            assets: Arc::new(AssetVersions::new(
+                &app_config.static_files,
+                app_config.views.cache_enabled,
+            )),
             app_config: Some(app_config),
             translator: Arc::new(RwLock::new(None)),
         }
@@ -225,14 +236,14 @@ impl EmbeddedTotalJsEngine {
     }
 
     /// Create a render context with common data
-    fn create_context(
-        &self,
-        data: &Value,
-        context_repository: Option<&Value>,
-        session_data: Option<&Value>,
-    ) -> RenderContext {
+    fn create_context(&self, data: &Value, request: &RequestData) -> RenderContext {
         let mut context = RenderContext::new(data.clone());
 
+        // Add the errors and submitted values of a redrawn form
+        if let Some(form) = request.form {
+            context = context.with_form(form);
+        }
+
         // Add global repository (APP/MAIN)
         if let Some(repo) = APP::get_repository() {
             if let Ok(data) = repo.read() {
@@ -241,7 +252,7 @@ impl EmbeddedTotalJsEngine {
         }
 
         // Add context repository if provided (repository/R)
-        if let Some(ctx_repo) = context_repository {
+        if let Some(ctx_repo) = request.repository {
             context = context.with_repository(ctx_repo.clone());
         }
 
@@ -267,15 +278,22 @@ impl EmbeddedTotalJsEngine {
             }
         }
 
-        // Add translator if available
+        context = context.with_assets(Arc::clone(&self.assets));
+
+        // Add translator if available, in the request's locale
         if let Ok(trans) = self.translator.read() {
             if let Some(translator) = trans.as_ref() {
-                context = context.with_translator(translator.clone());
+                let mut translator = translator.clone();
+                if let Some(locale) = request.locale {
+                    translator.set_language(locale);
+                }
+                context = context.with_translator(translator);
             }
         }
 
         // Add session data if provided, otherwise use empty session
-        let session_value = session_data
+        let session_value = request
+            .session
             .cloned()
             .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
 
@@ -296,12 +314,50 @@ impl EmbeddedTotalJsEngine {
         layout: Option<&str>,
         context_repository: Option<&Value>,
         session_data: Option<&Value>,
+    ) -> Result<String> {
+        self.render_localized(
+            template,
+            data,
+            layout,
+            context_repository,
+            session_data,
+            None,
+        )
+    }
+
+    /// Render with layout, context repository and session data, translating
+    /// into `locale` instead of the translator's current language
+    pub fn render_localized(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        context_repository: Option<&Value>,
+        session_data: Option<&Value>,
+        locale: Option<&str>,
+    ) -> Result<String> {
+        let request = RequestData {
+            repository: context_repository,
+            session: session_data,
+            locale,
+            form: None,
+        };
+        self.render_request(template, data, layout, &request)
+    }
+
+    /// Render with the per-request data of `request`
+    fn render_request(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        request: &RequestData,
     ) -> Result<String> {
         let template_path = self.template_path(template);
         let template_ast = self.load_template(&template_path)?;
 
         // Create render context with session data
-        let context = self.create_context(data, context_repository, session_data);
+        let context = self.create_context(data, request);
 
         // Create template loader that uses embedded templates
         let cache = self.cache.clone();
@@ -364,8 +420,7 @@ impl EmbeddedTotalJsEngine {
                 layout_data = Value::Object(map);
             }
 
-            let mut layout_context =
-                self.create_context(&layout_data, context_repository, session_data);
+            let mut layout_context = self.create_context(&layout_data, request);
 
             // Transfer child template sections to layout context
             // This allows child views to define sections that parent layouts can render
@@ -437,6 +492,7 @@ impl EmbeddedTotalJsEngine {
     /// Clear template cache
     pub fn clear_cache(&self) {
         self.cache.clear();
+        self.assets.clear();
     }
 
     /// Get cache statistics
@@ -461,27 +517,18 @@ impl ViewEngineImpl for EmbeddedTotalJsEngine {
     }
 
     fn render(&self, template: &str, data: &Value, layout: Option<&str>) -> Result<String> {
-        // Extract session data if provided in the data object
-        let session_data = if let Value::Object(map) = data {
-            map.get("_context_session")
-        } else {
-            None
-        };
-
-        // Extract context repository if provided
-        let context_repository = if let Value::Object(map) = data {
-            map.get("_context_repository")
-        } else {
-            None
-        };
+        self.render_request(template, data, layout, &RequestData::from_data(data, None))
+    }
 
-        self.render_with_layout_and_session(
-            template,
-            data,
-            layout,
-            context_repository,
-            session_data,
-        )
+    fn render_form(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        form: &FormState,
+    ) -> Result<String> {
+        let request = RequestData::from_data(data, Some(form));
+        self.render_request(template, data, layout, &request)
     }
 }
 
@@ -5,25 +5,58 @@ use super::{
     resource_translation::ResourceTranslationSystem,
     translation::TranslationSystem,
 };
-use crate::config::{AppConfig, ViewConfig};
+use crate::config::{AppConfig, StaticConfig, ViewConfig};
 use crate::error::{Error, Result};
 use crate::repository::APP;
-use crate::views::ViewEngineImpl;
+use crate::views::{AssetVersions, FormState, ViewEngineImpl};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Per-request data a render context is built from
+#[derive(Clone, Copy, Default)]
+pub(super) struct RequestData<'a> {
+    /// Context repository (repository/R)
+    pub repository: Option<&'a Value>,
+    /// Session data, flash messages included
+    pub session: Option<&'a Value>,
+    /// Locale to translate into instead of the translator's language
+    pub locale: Option<&'a str>,
+    /// State of a redrawn form (errors/old)
+    pub form: Option<&'a FormState>,
+}
+
+impl<'a> RequestData<'a> {
+    /// Read the internal `_context_*` fields `Context::view` adds to the data
+    pub(super) fn from_data(data: &'a Value, form: Option<&'a FormState>) -> Self {
+        let field = |name: &str| match data {
+            Value::Object(map) => map.get(name),
+            _ => None,
+        };
+        Self {
+            repository: field("_context_repository"),
+            session: field("_context_session"),
+            locale: field("_context_locale").and_then(Value::as_str),
+            form,
+        }
+    }
+}
+
 /// Cache entry for compiled templates
 #[derive(Clone)]
 struct CacheEntry {
     template: Arc<Template>,
     _compiled_at: u64,
-    file_modified: Option<u64>,
+    file_modified: Option<SystemTime>,
 }
 
 /// Template cache for performance
+///
+/// Compiled templates are keyed by file path. A cached template is reused
+/// while the file's modification time is unchanged; with `trust_cache` the
+/// file is not checked at all after the first load.
 #[derive(Clone)]
 struct TemplateCache {
     cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
@@ -50,72 +83,64 @@ impl TemplateCache {
         }
     }
 
-    fn get_file_mtime(path: &Path) -> Option<u64> {
-        std::fs::metadata(path)
-            .and_then(|m| m.modified())
-            .ok()
-            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-            .map(|d| d.as_secs())
+    fn get_file_mtime(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
     }
 
-    fn get_or_compile(&self, path: &Path, content: &str) -> Result<Template> {
+    /// Read and parse a template file
+    fn compile(path: &Path) -> Result<Template> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::template(format!("Failed to load template {:?}: {}", path, e)))?;
+
+        let mut parser = Parser::new(&content)?;
+        parser.parse()
+    }
+
+    /// Get the compiled template for `path`
+    ///
+    /// The file is only read and parsed on a cache miss or after it changed.
+    fn get_or_compile(&self, path: &Path) -> Result<Arc<Template>> {
         // If hot reload is enabled (cache disabled), always recompile
         if self.enable_hot_reload {
-            let mut parser = Parser::new(content)?;
-            return parser.parse();
+            return Self::compile(path).map(Arc::new);
         }
 
         let path_str = path.to_string_lossy().to_string();
 
+        // Read the mtime before the file so a concurrent edit is caught next time
+        let current_mtime = if self.trust_cache {
+            None
+        } else {
+            Self::get_file_mtime(path)
+        };
+
         // Check cache first - if trust_cache is enabled, skip mtime check entirely
         if let Ok(cache) = self.cache.read() {
             if let Some(entry) = cache.get(&path_str) {
-                if self.trust_cache {
-                    // Production mode: trust cache, no filesystem check
-                    // Return cloned Arc (cheap reference increment)
-                    return Ok((*entry.template).clone());
-                } else {
-                    // Development mode: check file modification time
-                    let current_mtime = Self::get_file_mtime(path);
-                    if let (Some(cached_mtime), Some(current)) = (entry.file_modified, current_mtime) {
-                        if current <= cached_mtime {
-                            // Return cloned Arc (cheap reference increment)
-                            return Ok((*entry.template).clone());
-                        }
-                    }
+                if self.trust_cache
+                    || (current_mtime.is_some() && entry.file_modified == current_mtime)
+                {
+                    return Ok(Arc::clone(&entry.template));
                 }
             }
         }
 
         // Compile template (cache miss or file changed)
-        let mut parser = Parser::new(content)?;
-        let template = parser.parse()?;
-
-        // Update cache with Arc to avoid cloning on subsequent accesses
-        let file_mtime = if self.trust_cache {
-            // In trust mode, we still record mtime on first load, but don't check it later
-            Self::get_file_mtime(path)
-        } else {
-            Self::get_file_mtime(path)
-        };
-
-        // Store template in Arc to share across requests
-        let template_arc = Arc::new(template);
-        let template_clone = (*template_arc).clone();
+        let template = Arc::new(Self::compile(path)?);
 
         if let Ok(mut cache) = self.cache.write() {
             let entry = CacheEntry {
-                template: template_arc,
+                template: Arc::clone(&template),
                 _compiled_at: SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs(),
-                file_modified: file_mtime,
+                file_modified: current_mtime.or_else(|| Self::get_file_mtime(path)),
             };
             cache.insert(path_str, entry);
         }
 
-        Ok(template_clone)
+        Ok(template)
     }
 
     fn clear(&self) {
@@ -137,6 +162,8 @@ pub struct TotalJsEngine {
     translator: Arc<RwLock<Option<TranslationSystem>>>,
     /// Resource translation system (new .res file based)
     resource_translator: Arc<RwLock<Option<ResourceTranslationSystem>>>,
+    /// Versioned static asset URLs for `@{asset(...)}`
+    assets: Arc<AssetVersions>,
 }
 
 impl TotalJsEngine {
@@ -172,6 +199,7 @@ impl TotalJsEngine {
             app_config: None,
             translator: Arc::new(RwLock::new(None)),
             resource_translator: Arc::new(RwLock::new(None)),
+            assets: Arc::new(AssetVersions::new(&StaticConfig::default(), cache_enabled)),
         }
     }
 
@@ -204,6 +232,10 @@ impl TotalJsEngine {
             base_dir: PathBuf::from(base_dir),
             cache: TemplateCache::new_with_trust_cache(enable_hot_reload, trust_cache),
             config: Arc::new(RwLock::new(config)),
+            assets: Arc::new(AssetVersions::new(
+                &app_config.static_files,
+                app_config.views.cache_enabled,
+            )),
             app_config: Some(app_config),
             translator: Arc::new(RwLock::new(None)),
             resource_translator: Arc::new(RwLock::new(None)),
@@ -263,6 +295,7 @@ impl TotalJsEngine {
     /// Clear template cache
     pub fn clear_cache(&self) {
         self.cache.clear();
+        self.assets.clear();
     }
 
     /// Set the full application configuration
@@ -282,6 +315,10 @@ impl TotalJsEngine {
                 app_config.views.cache_enabled.to_string(),
             );
         }
+        self.assets = Arc::new(AssetVersions::new(
+            &app_config.static_files,
+            app_config.views.cache_enabled,
+        ));
         self.app_config = Some(app_config);
     }
 
@@ -328,22 +365,19 @@ impl TotalJsEngine {
     }
 
     /// Load and compile a template
-    fn load_template(&self, path: &Path) -> Result<Template> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::template(format!("Failed to load template {:?}: {}", path, e)))?;
-
-        self.cache.get_or_compile(path, &content)
+    fn load_template(&self, path: &Path) -> Result<Arc<Template>> {
+        self.cache.get_or_compile(path)
     }
 
     /// Create a render context with common data
-    fn create_context(
-        &self,
-        data: &Value,
-        context_repository: Option<&Value>,
-        session_data: Option<&Value>,
-    ) -> RenderContext {
+    fn create_context(&self, data: &Value, request: &RequestData) -> RenderContext {
         let mut context = RenderContext::new(data.clone());
 
+        // Add the errors and submitted values of a redrawn form
+        if let Some(form) = request.form {
+            context = context.with_form(form);
+        }
+
         // Add global repository (APP/MAIN)
         if let Some(repo) = APP::get_repository() {
             if let Ok(data) = repo.read() {
@@ -352,7 +386,7 @@ impl TotalJsEngine {
         }
 
         // Add context repository if provided (repository/R)
-        if let Some(ctx_repo) = context_repository {
+        if let Some(ctx_repo) = request.repository {
             context = context.with_repository(ctx_repo.clone());
         }
 
@@ -378,6 +412,8 @@ impl TotalJsEngine {
             }
         }
 
+        context = context.with_assets(Arc::clone(&self.assets));
+
         // Add translator if available
         if let Ok(trans) = self.translator.read() {
             if let Some(translator) = trans.as_ref() {
@@ -386,7 +422,8 @@ impl TotalJsEngine {
         }
 
         // Add session data if provided, otherwise use empty session
-        let session_value = session_data
+        let session_value = request
+            .session
             .cloned()
             .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
 
@@ -399,6 +436,29 @@ impl TotalJsEngine {
         context
     }
 
+    /// Translator for `view`, switched to `locale` when one is given
+    fn view_translator(&self, view: &str, locale: Option<&str>) -> Option<TranslationSystem> {
+        if let Ok(trans) = self.resource_translator.read() {
+            if let Some(resource_trans) = trans.as_ref() {
+                let language = locale.unwrap_or_else(|| resource_trans.language());
+                // Get view-specific translations
+                let view_translations = resource_trans.get_view_translations_in(language, view);
+                // Convert to legacy format temporarily (TODO: update renderer to use resource translator directly)
+                let mut legacy_trans = TranslationSystem::new();
+                legacy_trans.set_language(language);
+                legacy_trans.add_translations(language, (*view_translations).clone());
+                return Some(legacy_trans);
+            }
+        }
+
+        let trans = self.translator.read().ok()?;
+        let mut translator = trans.as_ref()?.clone();
+        if let Some(locale) = locale {
+            translator.set_language(locale);
+        }
+        Some(translator)
+    }
+
     /// Render a template with context repository data
     pub fn render_with_context(
         &self,
@@ -429,12 +489,50 @@ impl TotalJsEngine {
         layout: Option<&str>,
         context_repository: Option<&Value>,
         session_data: Option<&Value>,
+    ) -> Result<String> {
+        self.render_localized(
+            template,
+            data,
+            layout,
+            context_repository,
+            session_data,
+            None,
+        )
+    }
+
+    /// Render with layout, context repository and session data, translating
+    /// into `locale` instead of the translator's current language
+    pub fn render_localized(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        context_repository: Option<&Value>,
+        session_data: Option<&Value>,
+        locale: Option<&str>,
+    ) -> Result<String> {
+        let request = RequestData {
+            repository: context_repository,
+            session: session_data,
+            locale,
+            form: None,
+        };
+        self.render_request(template, data, layout, &request)
+    }
+
+    /// Render with the per-request data of `request`
+    fn render_request(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        request: &RequestData,
     ) -> Result<String> {
         let template_path = self.template_path(template);
         let template_ast = self.load_template(&template_path)?;
 
         // Create render context with session data
-        let context = self.create_context(data, context_repository, session_data);
+        let context = self.create_context(data, request);
 
         // Create template loader that uses the cache
         let base_dir = self.base_dir.clone();
@@ -454,33 +552,16 @@ impl TotalJsEngine {
                 path.push(format!("{}.html", clean_name));
             }
 
-            let content = std::fs::read_to_string(&path).map_err(|e| {
-                crate::error::Error::template(format!("Failed to load partial '{}': {}", name, e))
-            })?;
-
-            cache.get_or_compile(&path, &content)
+            // Partials are modified while rendering, so hand out a copy
+            cache
+                .get_or_compile(&path)
+                .map(|template| (*template).clone())
         });
 
         // Add translator to context if available (prefer resource translator over legacy)
-        let context = if let Ok(trans) = self.resource_translator.read() {
-            if let Some(resource_trans) = trans.as_ref() {
-                // Get view-specific translations
-                let view_translations = resource_trans.get_view_translations(template);
-                // Convert to legacy format temporarily (TODO: update renderer to use resource translator directly)
-                let mut legacy_trans = TranslationSystem::new();
-                legacy_trans.add_translations("current", (*view_translations).clone());
-                context.with_translator(legacy_trans)
-            } else {
-                context
-            }
-        } else if let Ok(trans) = self.translator.read() {
-            if let Some(translator) = trans.as_ref() {
-                context.with_translator(translator.clone())
-            } else {
-                context
-            }
-        } else {
-            context
+        let context = match self.view_translator(template, request.locale) {
+            Some(translator) => context.with_translator(translator),
+            None => context,
         };
 
         // Render the template with template loader for partials
@@ -505,8 +586,7 @@ impl TotalJsEngine {
                 layout_data = Value::Object(map);
             }
 
-            let mut layout_context =
-                self.create_context(&layout_data, context_repository, session_data);
+            let mut layout_context = self.create_context(&layout_data, request);
 
             // Transfer child template sections to layout context
             // This allows child views to define sections that parent layouts can render
@@ -527,31 +607,15 @@ impl TotalJsEngine {
                     path.push(format!("{}.html", clean_name));
                 }
 
-                let content = std::fs::read_to_string(&path).map_err(|e| {
-                    Error::template(format!("Failed to load partial '{}': {}", name, e))
-                })?;
-
-                cache.get_or_compile(&path, &content)
+                cache
+                    .get_or_compile(&path)
+                    .map(|template| (*template).clone())
             });
 
             // Add translator to layout context
-            let layout_context = if let Ok(trans) = self.resource_translator.read() {
-                if let Some(resource_trans) = trans.as_ref() {
-                    let view_translations = resource_trans.get_view_translations(layout_name);
-                    let mut legacy_trans = TranslationSystem::new();
-                    legacy_trans.add_translations("current", (*view_translations).clone());
-                    layout_context.with_translator(legacy_trans)
-                } else {
-                    layout_context
-                }
-            } else if let Ok(trans) = self.translator.read() {
-                if let Some(translator) = trans.as_ref() {
-                    layout_context.with_translator(translator.clone())
-                } else {
-                    layout_context
-                }
-            } else {
-                layout_context
+            let layout_context = match self.view_translator(layout_name, request.locale) {
+                Some(translator) => layout_context.with_translator(translator),
+                None => layout_context,
             };
 
             let mut layout_renderer = Renderer::new(layout_context)
@@ -563,38 +627,54 @@ impl TotalJsEngine {
             Ok(content)
         }
     }
-}
 
-impl ViewEngineImpl for TotalJsEngine {
-    fn set_directory(&mut self, dir: &str) {
-        self.base_dir = PathBuf::from(dir);
-        self.cache.clear();
-    }
-
-    fn render(&self, template: &str, data: &Value, layout: Option<&str>) -> Result<String> {
-        // Extract context repository and session from data if present
-        let (context_repository, session_data) = if let Value::Object(map) = data {
-            (map.get("_context_repository"), map.get("_context_session"))
-        } else {
-            (None, None)
-        };
+    /// Render data coming from `Context::view`, with its internal fields split off
+    fn render_view(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        form: Option<&FormState>,
+    ) -> Result<String> {
+        let request = RequestData::from_data(data, form);
 
         // Create clean data without the internal fields
         let clean_data = if let Value::Object(mut map) = data.clone() {
             map.remove("_context_repository");
             map.remove("_context_session");
+            map.remove("_context_locale");
             Value::Object(map)
         } else {
             data.clone()
         };
 
-        self.render_with_layout_and_session(
-            template,
-            &clean_data,
-            layout,
-            context_repository,
-            session_data,
-        )
+        self.render_request(template, &clean_data, layout, &request)
+    }
+}
+
+impl ViewEngineImpl for TotalJsEngine {
+    fn set_directory(&mut self, dir: &str) {
+        self.base_dir = PathBuf::from(dir);
+        self.cache.clear();
+    }
+
+    fn clear_cache(&self) {
+        self.cache.clear();
+        self.assets.clear();
+    }
+
+    fn render(&self, template: &str, data: &Value, layout: Option<&str>) -> Result<String> {
+        self.render_view(template, data, layout, None)
+    }
+
+    fn render_form(
+        &self,
+        template: &str,
+        data: &Value,
+        layout: Option<&str>,
+        form: &FormState,
+    ) -> Result<String> {
+        self.render_view(template, data, layout, Some(form))
     }
 }
 
@@ -922,6 +1002,27 @@ mod tests {
         assert_eq!(result, "User: john_doe, Theme: dark");
     }
 
+    #[test]
+    fn test_redrawn_form_state() {
+        let (engine, temp_dir) = create_test_engine();
+
+        let template_content = r#"<input value="@{old.email}">@{if errors.email}<b>@{errors.email}</b>@{fi}@{errors.address.zip}|@{errors.name}|@{M.plan}"#;
+        fs::write(temp_dir.path().join("signup.html"), template_content).unwrap();
+
+        let form = FormState {
+            errors: json!({ "email": "Invalid email", "address": { "zip": "Required" } }),
+            old: json!({ "email": "ada@" }),
+        };
+
+        let result = engine
+            .render_form("signup", &json!({ "plan": "pro" }), None, &form)
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"<input value="ada@"><b>Invalid email</b>Required||pro"#
+        );
+    }
+
     #[test]
     fn test_model_data_with_m_alias() {
         let (engine, temp_dir) = create_test_engine();
@@ -986,4 +1087,42 @@ Page: @{M.page_title}"#;
         let result = engine.render("repo_alias", &data, None).unwrap();
         assert_eq!(result, "Via repository: test_value, Via R: test_value");
     }
+
+    #[test]
+    fn test_cache_recompiles_changed_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("page.html");
+        fs::write(&path, "v1").unwrap();
+
+        let cache = TemplateCache::new_with_trust_cache(false, false);
+        let first = cache.get_or_compile(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.get_or_compile(&path).unwrap()));
+
+        // Same-second edits must be noticed, so move the mtime explicitly
+        fs::write(&path, "v2").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        let second = cache.get_or_compile(&path).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_trusted_cache_needs_clearing() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("page.html");
+        fs::write(&path, "v1").unwrap();
+
+        let cache = TemplateCache::new_with_trust_cache(false, true);
+        let first = cache.get_or_compile(&path).unwrap();
+
+        fs::write(&path, "v2").unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.get_or_compile(&path).unwrap()));
+
+        cache.clear();
+        assert!(!Arc::ptr_eq(&first, &cache.get_or_compile(&path).unwrap()));
+    }
 }
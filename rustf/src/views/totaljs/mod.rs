@@ -13,6 +13,7 @@ pub mod engine;
 /// - CSRF and security features
 pub mod lexer;
 pub mod parser;
+pub mod plural;
 pub mod renderer;
 pub mod resource_translation;
 pub mod translation;
@@ -206,9 +206,17 @@ impl Parser {
             }
 
             TokenKind::TranslateKey(key) => {
-                let node = Node::Translate {
-                    text: key.clone(),
-                    is_key: true,
+                // @(#key, count) selects a plural variant
+                let node = if let Some((key, count)) = key.split_once(',') {
+                    Node::TranslatePlural {
+                        key: key.trim().to_string(),
+                        count: self.parse_expression(count.trim())?,
+                    }
+                } else {
+                    Node::Translate {
+                        text: key.clone(),
+                        is_key: true,
+                    }
                 };
                 self.advance();
                 Ok(node)
@@ -976,6 +984,21 @@ mod tests {
         assert!(!template.nodes.is_empty());
     }
 
+    #[test]
+    fn test_parse_plural_translation() {
+        let mut parser = Parser::new("@(#cart.items, M.count)").unwrap();
+        let template = parser.parse().unwrap();
+
+        assert_eq!(template.nodes.len(), 1);
+        match &template.nodes[0] {
+            Node::TranslatePlural { key, count } => {
+                assert_eq!(key, "cart.items");
+                assert_eq!(count, &Expression::from_path("M.count"));
+            }
+            _ => panic!("Expected plural translation node"),
+        }
+    }
+
     #[test]
     fn test_expression_parsing() {
         let parser = Parser::new("").unwrap();
@@ -0,0 +1,213 @@
+//! CLDR plural rules for translation lookups
+//!
+//! Maps a count to its CLDR plural category for a locale. Only the cardinal
+//! rules for integer counts are implemented; fractional counts always map to
+//! `Other`, which is correct for most languages and a safe default otherwise.
+
+/// CLDR plural category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// Suffix used for plural variants in resource files (`key.one`, `key.other`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// Get the plural category of `count` for `locale`
+///
+/// The locale may be a bare language ("fr") or include a region ("pt-PT", "en_US").
+/// Unknown languages use the English rule (`one` for 1, `other` otherwise).
+pub fn plural_category(locale: &str, count: f64) -> PluralCategory {
+    if count.fract() != 0.0 || !count.is_finite() {
+        return PluralCategory::Other;
+    }
+
+    let n = count.abs() as u64;
+    let locale = locale.to_lowercase().replace('_', "-");
+    let language = locale.split('-').next().unwrap_or("");
+
+    match language {
+        // No plural distinction
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" | "km" => {
+            PluralCategory::Other
+        }
+
+        // 0 and 1 are singular (Brazilian Portuguese is the CLDR default for "pt")
+        "fr" | "hy" | "kab" => zero_one_singular(n),
+        "pt" if locale != "pt-pt" => zero_one_singular(n),
+
+        // East Slavic
+        "ru" | "uk" | "be" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n10 == 1 && n100 != 11 {
+                PluralCategory::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+
+        // South Slavic
+        "hr" | "sr" | "bs" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n10 == 1 && n100 != 11 {
+                PluralCategory::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        "pl" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n == 1 {
+                PluralCategory::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+
+        "cs" | "sk" => match n {
+            1 => PluralCategory::One,
+            2..=4 => PluralCategory::Few,
+            _ => PluralCategory::Other,
+        },
+
+        "ro" => {
+            let n100 = n % 100;
+            if n == 1 {
+                PluralCategory::One
+            } else if n == 0 || (2..=19).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        "lt" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n10 == 1 && !(11..=19).contains(&n100) {
+                PluralCategory::One
+            } else if (2..=9).contains(&n10) && !(11..=19).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        "lv" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n10 == 0 || (11..=19).contains(&n100) {
+                PluralCategory::Zero
+            } else if n10 == 1 && n100 != 11 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        "he" => match n {
+            1 => PluralCategory::One,
+            2 => PluralCategory::Two,
+            _ => PluralCategory::Other,
+        },
+
+        "ar" => {
+            let n100 = n % 100;
+            match n {
+                0 => PluralCategory::Zero,
+                1 => PluralCategory::One,
+                2 => PluralCategory::Two,
+                _ if (3..=10).contains(&n100) => PluralCategory::Few,
+                _ if (11..=99).contains(&n100) => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        }
+
+        // English and most Germanic/Romance languages
+        _ => {
+            if n == 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+    }
+}
+
+fn zero_one_singular(n: u64) -> PluralCategory {
+    if n <= 1 {
+        PluralCategory::One
+    } else {
+        PluralCategory::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_rules() {
+        assert_eq!(plural_category("en", 0.0), PluralCategory::Other);
+        assert_eq!(plural_category("en", 1.0), PluralCategory::One);
+        assert_eq!(plural_category("en-US", 5.0), PluralCategory::Other);
+        assert_eq!(plural_category("en", 1.5), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_french_and_portuguese_rules() {
+        assert_eq!(plural_category("fr", 0.0), PluralCategory::One);
+        assert_eq!(plural_category("fr", 1.0), PluralCategory::One);
+        assert_eq!(plural_category("fr", 2.0), PluralCategory::Other);
+        assert_eq!(plural_category("pt", 0.0), PluralCategory::One);
+        assert_eq!(plural_category("pt_PT", 0.0), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_slavic_rules() {
+        assert_eq!(plural_category("ru", 1.0), PluralCategory::One);
+        assert_eq!(plural_category("ru", 21.0), PluralCategory::One);
+        assert_eq!(plural_category("ru", 3.0), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 12.0), PluralCategory::Many);
+        assert_eq!(plural_category("ru", 25.0), PluralCategory::Many);
+        assert_eq!(plural_category("pl", 22.0), PluralCategory::Few);
+        assert_eq!(plural_category("pl", 21.0), PluralCategory::Many);
+        assert_eq!(plural_category("cs", 4.0), PluralCategory::Few);
+    }
+
+    #[test]
+    fn test_arabic_rules() {
+        assert_eq!(plural_category("ar", 0.0), PluralCategory::Zero);
+        assert_eq!(plural_category("ar", 2.0), PluralCategory::Two);
+        assert_eq!(plural_category("ar", 105.0), PluralCategory::Few);
+        assert_eq!(plural_category("ar", 111.0), PluralCategory::Many);
+        assert_eq!(plural_category("ar", 100.0), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_no_plural_languages() {
+        assert_eq!(plural_category("ja", 1.0), PluralCategory::Other);
+        assert_eq!(plural_category("zh-CN", 3.0), PluralCategory::Other);
+    }
+}
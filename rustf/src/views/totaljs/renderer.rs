@@ -2,6 +2,7 @@ use super::ast::{BinaryOperator, Expression, Helper, Node, Template, UnaryOperat
 use super::translation::TranslationSystem;
 use crate::error::Result;
 use crate::security::HtmlEscaper;
+use crate::views::{AssetVersions, FormState};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -34,6 +35,12 @@ pub struct RenderContext {
     /// User data
     pub user: Value,
 
+    /// Field errors of a redrawn form (errors)
+    pub errors: Value,
+
+    /// Submitted values of a redrawn form (old)
+    pub old: Value,
+
     /// Configuration values
     pub config: HashMap<String, String>,
 
@@ -63,6 +70,9 @@ pub struct RenderContext {
 
     /// Translation system
     translator: Option<TranslationSystem>,
+
+    /// Versioned static asset URLs for `asset()`
+    assets: Option<Arc<AssetVersions>>,
 }
 
 #[derive(Clone)]
@@ -81,10 +91,13 @@ impl Clone for RenderContext {
             session: self.session.clone(),
             query: self.query.clone(),
             user: self.user.clone(),
+            errors: self.errors.clone(),
+            old: self.old.clone(),
             config: self.config.clone(),
             conf: self.conf.clone(),
             functions: HashMap::new(), // We'll re-register functions below
             translator: self.translator.clone(),
+            assets: self.assets.clone(),
             url: self.url.clone(),
             hostname: self.hostname.clone(),
             loop_stack: self.loop_stack.clone(),
@@ -117,6 +130,8 @@ impl RenderContext {
             session: Value::Object(serde_json::Map::new()),
             query: Value::Object(serde_json::Map::new()),
             user: Value::Null,
+            errors: Value::Object(serde_json::Map::new()),
+            old: Value::Object(serde_json::Map::new()),
             config: HashMap::new(),
             conf: Value::Object(serde_json::Map::new()),
             url: "/".to_string(),
@@ -127,6 +142,7 @@ impl RenderContext {
             helpers: HashMap::new(),
             functions,
             translator: None,
+            assets: None,
         };
 
         // Register context-aware functions
@@ -182,6 +198,13 @@ impl RenderContext {
         self
     }
 
+    /// Set the field errors and submitted values of a redrawn form
+    pub fn with_form(mut self, form: &FormState) -> Self {
+        self.errors = form.errors.clone();
+        self.old = form.old.clone();
+        self
+    }
+
     /// Add configuration values
     pub fn with_config(mut self, config: HashMap<String, String>) -> Self {
         self.config = config;
@@ -252,6 +275,12 @@ impl RenderContext {
         self
     }
 
+    /// Set the static assets resolved by `asset()`
+    pub fn with_assets(mut self, assets: Arc<AssetVersions>) -> Self {
+        self.assets = Some(assets);
+        self
+    }
+
     /// Set sections (for layout rendering with child-defined sections)
     pub fn with_sections(mut self, sections: HashMap<String, Vec<Node>>) -> Self {
         self.sections = sections;
@@ -592,6 +621,14 @@ impl RenderContext {
                 return value.clone();
             }
 
+            // Redrawn form state, unless a loop or helper uses the name
+            if name == "errors" {
+                return self.errors.clone();
+            }
+            if name == "old" {
+                return self.old.clone();
+            }
+
             // No fallback to model data - direct variable access is not allowed
             // Variables must be accessed via explicit prefixes: model.key, repository.key, session.key, etc.
         } else {
@@ -663,6 +700,10 @@ impl RenderContext {
                         v
                     } else if let Some(value) = self.locals.get(base) {
                         value.clone()
+                    } else if base == "errors" {
+                        self.errors.clone()
+                    } else if base == "old" {
+                        self.old.clone()
                     } else {
                         // No fallback to model data - unknown base names are not allowed
                         // Only known prefixes (repository, R, model, M, session, etc.) are permitted
@@ -1068,6 +1109,19 @@ impl RenderContext {
                 }
             }
 
+            "asset" => {
+                // @{asset('css/app.css')} -> /static/css/app.css?v=<content hash>
+                if args.is_empty() {
+                    return Ok(Value::String(String::new()));
+                }
+                let arg = self.evaluate_expression(&args[0])?;
+                let path = arg.as_str().unwrap_or_default();
+                Ok(Value::String(match &self.assets {
+                    Some(assets) => assets.url(path),
+                    None => path.to_string(),
+                }))
+            }
+
             _ => {
                 // Unknown function - return null
                 Ok(Value::Null)
@@ -1685,6 +1739,21 @@ impl Renderer {
                 }
             }
 
+            Node::TranslatePlural { key, count } => {
+                let count = match self.context.evaluate_expression(count)? {
+                    Value::Number(n) => n.as_f64().unwrap_or(0.0),
+                    Value::String(s) => s.trim().parse().unwrap_or(0.0),
+                    Value::Array(arr) => arr.len() as f64,
+                    _ => 0.0,
+                };
+
+                if let Some(translator) = &self.context.translator {
+                    Ok(translator.translate_plural(key, count))
+                } else {
+                    Ok(format!("[#{}]", key))
+                }
+            }
+
             Node::Config(key) => Ok(self.context.config.get(key).cloned().unwrap_or_default()),
 
             Node::Repository(key) => Ok(self
@@ -2328,4 +2397,23 @@ mod tests {
         // Should return empty string (Null value)
         assert_eq!(result, "");
     }
+
+    #[test]
+    fn test_render_plural_translation() {
+        let mut translator = TranslationSystem::new();
+        let mut translations = HashMap::new();
+        translations.insert("items.one".to_string(), "{count} item".to_string());
+        translations.insert("items.other".to_string(), "{count} items".to_string());
+        translator.add_translations("en", translations);
+
+        let mut parser = super::super::parser::Parser::new("@(#items, M.count)").unwrap();
+        let template = parser.parse().unwrap();
+
+        for (count, expected) in [(1, "1 item"), (5, "5 items")] {
+            let context =
+                RenderContext::new(json!({ "count": count })).with_translator(translator.clone());
+            let mut renderer = Renderer::new(context);
+            assert_eq!(renderer.render(&template).unwrap(), expected);
+        }
+    }
 }
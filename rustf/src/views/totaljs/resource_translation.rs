@@ -80,6 +80,11 @@ impl ResourceTranslationSystem {
         }
     }
 
+    /// Get the current language
+    pub fn language(&self) -> &str {
+        &self.current_language
+    }
+
     /// Set the fallback language
     pub fn set_fallback(&mut self, language: &str) {
         if self.fallback_language != language {
@@ -90,7 +95,16 @@ impl ResourceTranslationSystem {
 
     /// Get translations for a specific view (with caching)
     pub fn get_view_translations(&self, view_path: &str) -> Arc<HashMap<String, String>> {
-        let cache_key = (self.current_language.clone(), view_path.to_string());
+        self.get_view_translations_in(&self.current_language, view_path)
+    }
+
+    /// Get translations for a specific view in `language` instead of the current one
+    pub fn get_view_translations_in(
+        &self,
+        language: &str,
+        view_path: &str,
+    ) -> Arc<HashMap<String, String>> {
+        let cache_key = (language.to_string(), view_path.to_string());
 
         // Check cache first
         if let Some(cached) = self.view_cache.get(&cache_key) {
@@ -100,8 +114,13 @@ impl ResourceTranslationSystem {
         // Build merged translations for this view
         let mut merged = HashMap::new();
 
-        // Start with global translations from current language
-        if let Some(lang_translations) = self.translations.get(&self.current_language) {
+        // Start with global translations from the language, or its base
+        // language when there is no file for the region ("fr-CH" -> "fr")
+        let lang_translations = self.translations.get(language).or_else(|| {
+            let base = language.split(['-', '_']).next()?;
+            self.translations.get(base)
+        });
+        if let Some(lang_translations) = lang_translations {
             if let Some(global) = &lang_translations.global {
                 for (key, value) in global {
                     merged.insert(key.clone(), value.clone());
@@ -118,7 +137,7 @@ impl ResourceTranslationSystem {
         }
 
         // Add fallback translations for missing keys
-        if self.fallback_language != self.current_language {
+        if self.fallback_language != language {
             if let Some(fallback_translations) = self.translations.get(&self.fallback_language) {
                 // Add global fallbacks
                 if let Some(global) = &fallback_translations.global {
@@ -347,4 +366,25 @@ title : "Home Page"
         assert_eq!(translations.get("save"), Some(&"Save".to_string()));
         assert_eq!(translations.get("welcome"), Some(&"Welcome".to_string()));
     }
+
+    #[test]
+    fn test_view_translations_in_locale() {
+        let mut system = ResourceTranslationSystem::new();
+
+        let dir = tempdir().unwrap();
+        let en_path = dir.path().join("en.res");
+        let fr_path = dir.path().join("fr.res");
+        fs::write(&en_path, "[global]\nsave : \"Save\"\ncancel : \"Cancel\"\n").unwrap();
+        fs::write(&fr_path, "[global]\nsave : \"Enregistrer\"\n").unwrap();
+        system.load_resource("en", &en_path).unwrap();
+        system.load_resource("fr", &fr_path).unwrap();
+
+        // A regional locale falls back to its language, then to the fallback
+        let translations = system.get_view_translations_in("fr-CH", "home/index");
+        assert_eq!(translations.get("save"), Some(&"Enregistrer".to_string()));
+        assert_eq!(translations.get("cancel"), Some(&"Cancel".to_string()));
+
+        // The current language is untouched
+        assert_eq!(system.language(), "en");
+    }
 }
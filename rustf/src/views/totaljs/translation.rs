@@ -1,3 +1,4 @@
+use super::plural::{plural_category, PluralCategory};
 use std::collections::HashMap;
 
 /// Translation system for Total.js templates
@@ -73,6 +74,41 @@ impl TranslationSystem {
         format!("[{}]", key)
     }
 
+    /// Translate a key with plural variants selected by `count`
+    ///
+    /// Variants are stored as `key.zero`, `key.one`, `key.two`, `key.few`,
+    /// `key.many` and `key.other`, following the CLDR plural categories of the
+    /// current language. An explicit `key.zero` is preferred when `count` is 0,
+    /// even for languages without a zero category. Falls back to `key.other`,
+    /// then to `key` itself. `{count}` in the translation is replaced by the count.
+    pub fn translate_plural(&self, key: &str, count: f64) -> String {
+        let category = plural_category(&self.current_language, count);
+
+        let mut candidates = Vec::with_capacity(4);
+        if count == 0.0 {
+            candidates.push(format!("{}.{}", key, PluralCategory::Zero.as_str()));
+        }
+        candidates.push(format!("{}.{}", key, category.as_str()));
+        candidates.push(format!("{}.{}", key, PluralCategory::Other.as_str()));
+        candidates.push(key.to_string());
+
+        let count_text = if count.fract() == 0.0 {
+            format!("{}", count as i64)
+        } else {
+            count.to_string()
+        };
+
+        let find_in = |language: &str| {
+            let translations = self.translations.get(language)?;
+            candidates.iter().find_map(|candidate| translations.get(candidate))
+        };
+
+        find_in(&self.current_language)
+            .or_else(|| find_in(&self.fallback_language))
+            .map(|translation| translation.replace("{count}", &count_text))
+            .unwrap_or_else(|| format!("[{}]", key))
+    }
+
     /// Translate text (simple text-based translation)
     pub fn translate_text(&self, text: &str) -> String {
         // For text translation, we use the text itself as the key
@@ -103,6 +139,11 @@ impl TranslationSystem {
         text.to_string()
     }
 
+    /// Get the current language
+    pub fn language(&self) -> &str {
+        &self.current_language
+    }
+
     /// Check if a language is available
     pub fn has_language(&self, language: &str) -> bool {
         self.translations.contains_key(language)
@@ -186,4 +227,36 @@ mod tests {
         assert_eq!(translator.translate_key("world"), "World");
         assert_eq!(translator.translate_key("app.name"), "My App");
     }
+
+    #[test]
+    fn test_translate_plural() {
+        let mut translator = TranslationSystem::new();
+
+        let mut en_translations = HashMap::new();
+        en_translations.insert("items.zero".to_string(), "No items".to_string());
+        en_translations.insert("items.one".to_string(), "{count} item".to_string());
+        en_translations.insert("items.other".to_string(), "{count} items".to_string());
+        translator.add_translations("en", en_translations);
+
+        let mut ru_translations = HashMap::new();
+        ru_translations.insert("items.one".to_string(), "{count} товар".to_string());
+        ru_translations.insert("items.few".to_string(), "{count} товара".to_string());
+        ru_translations.insert("items.many".to_string(), "{count} товаров".to_string());
+        translator.add_translations("ru", ru_translations);
+
+        translator.set_language("en");
+        assert_eq!(translator.translate_plural("items", 0.0), "No items");
+        assert_eq!(translator.translate_plural("items", 1.0), "1 item");
+        assert_eq!(translator.translate_plural("items", 5.0), "5 items");
+
+        translator.set_language("ru");
+        assert_eq!(translator.translate_plural("items", 21.0), "21 товар");
+        assert_eq!(translator.translate_plural("items", 3.0), "3 товара");
+        assert_eq!(translator.translate_plural("items", 11.0), "11 товаров");
+
+        // Missing variants fall back to the fallback language, then to the key
+        translator.set_language("de");
+        assert_eq!(translator.translate_plural("items", 2.0), "2 items");
+        assert_eq!(translator.translate_plural("missing", 2.0), "[missing]");
+    }
 }
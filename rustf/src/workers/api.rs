@@ -6,7 +6,10 @@ use std::time::Duration;
 use once_cell::sync::OnceCell;
 use serde_json::Value;
 
+use super::lock::{self, LockStatus, LockStore};
 use super::manager::{WorkerHandle, WorkerManager};
+use super::meta::WorkerMeta;
+use super::schedule::{CronSchedule, OverlapPolicy};
 use super::types::{WorkerDefinition, WorkerStats};
 use crate::error::{Error, Result};
 
@@ -37,6 +40,39 @@ impl WORKER {
         manager()?.register_definition(definition).await
     }
 
+    /// Register a worker and run it on a cron schedule (UTC).
+    ///
+    /// A tick is skipped while the previous run is still going; use
+    /// [`WORKER::schedule_with`] to let runs overlap.
+    pub async fn schedule<F, Fut>(name: impl Into<String>, cron: &str, handler: F) -> Result<()>
+    where
+        F: Fn(super::context::WorkerContext) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        WORKER::schedule_with(name, cron, OverlapPolicy::Skip, handler).await
+    }
+
+    /// Register a worker and run it on a cron schedule with the given overlap policy.
+    pub async fn schedule_with<F, Fut>(
+        name: impl Into<String>,
+        cron: &str,
+        overlap: OverlapPolicy,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(super::context::WorkerContext) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        let schedule = CronSchedule::parse(cron)?;
+        let definition = WorkerDefinition::new(name, handler);
+        manager()?.schedule(definition, schedule, overlap).await
+    }
+
+    /// Stop scheduling a worker; it stays registered for on-demand calls.
+    pub async fn unschedule(worker_name: &str) -> Result<()> {
+        manager()?.unschedule(worker_name).await
+    }
+
     /// Invoke a worker asynchronously and obtain a handle for the running instance.
     pub async fn call(
         worker_name: &str,
@@ -46,6 +82,18 @@ impl WORKER {
         manager()?.call(worker_name, timeout, payload).await
     }
 
+    /// Invoke a worker with a snapshot of request values (see `WorkerMeta`).
+    pub async fn call_with_meta(
+        worker_name: &str,
+        timeout: Option<Duration>,
+        payload: Option<Value>,
+        meta: WorkerMeta,
+    ) -> Result<WorkerHandle> {
+        manager()?
+            .call_with_meta(worker_name, timeout, payload, meta)
+            .await
+    }
+
     /// Convenience helper that runs the worker and waits for completion.
     pub async fn run(worker_name: &str, payload: Option<Value>) -> Result<()> {
         let handle = WORKER::call(worker_name, None, payload).await?;
@@ -80,6 +128,29 @@ impl WORKER {
         }
     }
 
+    /// Run `task` only if no other run, on this or another instance, holds the lock `name`.
+    ///
+    /// Returns `None` without running `task` when the lock is taken. The lock is
+    /// released when `task` completes and lapses after `ttl` otherwise; see
+    /// `workers::lock` for sharing locks between instances.
+    pub async fn with_lock<F, Fut, T>(name: &str, ttl: Duration, task: F) -> Result<Option<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        lock::with_lock(name, ttl, task).await
+    }
+
+    /// Set the store behind `with_lock`; the default only covers this process.
+    pub fn set_lock_store(store: impl LockStore + 'static) {
+        lock::set_store(Arc::new(store));
+    }
+
+    /// Report who holds the lock `name`, or `None` when it is free.
+    pub async fn lock_status(name: &str) -> Result<Option<LockStatus>> {
+        lock::store().status(name).await
+    }
+
     /// Suspend all running workers.
     pub async fn shutdown() -> Result<()> {
         manager()?.shutdown_all().await
@@ -2,6 +2,7 @@
 
 use crate::config::AppConfig;
 use crate::error::{Error, Result};
+use crate::workers::meta::{WorkerMeta, META_REQUEST_ID};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -17,6 +18,7 @@ pub struct WorkerContext {
     state: Arc<RwLock<HashMap<String, Value>>>,
     environment: String,
     data: Option<Value>,
+    meta: WorkerMeta,
 }
 
 impl WorkerContext {
@@ -36,6 +38,7 @@ impl WorkerContext {
                 .or_else(|_| std::env::var("NODE_ENV"))
                 .unwrap_or_else(|_| "development".to_string()),
             data: None,
+            meta: WorkerMeta::default(),
         }
     }
 
@@ -44,6 +47,11 @@ impl WorkerContext {
         self
     }
 
+    pub fn with_meta(mut self, meta: WorkerMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
     pub fn worker_name(&self) -> &str {
         &self.worker_name
     }
@@ -60,6 +68,16 @@ impl WorkerContext {
         self.data.as_ref()
     }
 
+    /// Get a request value captured when the worker was called
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(key)
+    }
+
+    /// All request values captured when the worker was called
+    pub fn meta_all(&self) -> &WorkerMeta {
+        &self.meta
+    }
+
     /// Send a message to the caller (if supported)
     pub fn emit(&self, message: Value) -> Result<()> {
         if let Some(tx) = &self.message_tx {
@@ -93,13 +111,23 @@ impl WorkerContext {
     }
 
     pub fn log(&self, level: log::Level, message: &str) {
-        log::log!(
-            level,
-            "[Worker:{} run:{}] {}",
-            self.worker_name,
-            self.run_id,
-            message
-        );
+        match self.meta.get(META_REQUEST_ID) {
+            Some(request_id) => log::log!(
+                level,
+                "[Worker:{} run:{} request:{}] {}",
+                self.worker_name,
+                self.run_id,
+                request_id,
+                message
+            ),
+            None => log::log!(
+                level,
+                "[Worker:{} run:{}] {}",
+                self.worker_name,
+                self.run_id,
+                message
+            ),
+        }
     }
 
     pub fn info(&self, message: &str) {
@@ -0,0 +1,527 @@
+//! Cluster-wide locks for workers
+//!
+//! A scheduled worker fires on every instance of the application. Wrapping
+//! the job in [`WORKER::with_lock`](super::WORKER::with_lock) lets one
+//! instance run it while the others skip that tick:
+//!
+//! ```rust,ignore
+//! WORKER::set_lock_store(RedisLockStore::new("redis://127.0.0.1:6379")?);
+//!
+//! WORKER::schedule("nightly-cleanup", "0 3 * * *", |ctx| async move {
+//!     let ran = WORKER::with_lock("nightly-cleanup", Duration::from_secs(600), || async {
+//!         ctx.info("Purging expired sessions...");
+//!         Ok(())
+//!     })
+//!     .await?;
+//!     if ran.is_none() {
+//!         ctx.info("Another instance is running the cleanup");
+//!     }
+//!     Ok(())
+//! })
+//! .await?;
+//! ```
+//!
+//! The lock is released when the job returns, fails or is cancelled. If the
+//! instance dies first, the lock lapses after its TTL, so pick a TTL longer
+//! than the job takes. The default [`MemoryLockStore`] only keeps runs in
+//! one process apart; share locks between instances with [`RedisLockStore`]
+//! or [`DatabaseLockStore`].
+
+use crate::error::{Error, Result};
+use crate::models::query_builder::AnyDatabase;
+use async_trait::async_trait;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use deadpool_redis::{Config, Pool, Runtime};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::pool::PoolConnection;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Who holds a lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LockStatus {
+    /// Owner token of the holder (`host:pid:instance:run`), when the store records it
+    pub owner: Option<String>,
+    /// Time left before the lock lapses, when the store records it
+    pub expires_in: Option<Duration>,
+    /// Held by a run in this process
+    pub held_here: bool,
+}
+
+/// Storage for worker locks
+///
+/// `try_acquire` must be atomic: of several callers racing for a free key,
+/// exactly one gets it. A lock left unreleased lapses after `ttl`.
+#[async_trait]
+pub trait LockStore: Send + Sync {
+    /// Take `key` for `owner` unless someone holds it
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool>;
+
+    /// Release `key` if `owner` still holds it
+    async fn release(&self, key: &str, owner: &str) -> Result<()>;
+
+    /// Current holder of `key`, `None` when it is free
+    async fn status(&self, key: &str) -> Result<Option<LockStatus>>;
+}
+
+static STORE: Lazy<RwLock<Arc<dyn LockStore>>> =
+    Lazy::new(|| RwLock::new(Arc::new(MemoryLockStore::new())));
+
+/// Identifies this process in lock owners: `host:pid:random`
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: Lazy<String> = Lazy::new(|| {
+        let host = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .unwrap_or_else(|_| "localhost".to_string());
+        format!(
+            "{}:{}:{:08x}",
+            host,
+            std::process::id(),
+            rand::random::<u32>()
+        )
+    });
+    &INSTANCE_ID
+}
+
+fn is_local(owner: &str) -> bool {
+    owner
+        .strip_prefix(instance_id())
+        .is_some_and(|run| run.starts_with(':'))
+}
+
+pub(crate) fn set_store(store: Arc<dyn LockStore>) {
+    *STORE.write().unwrap_or_else(|e| e.into_inner()) = store;
+}
+
+pub(crate) fn store() -> Arc<dyn LockStore> {
+    STORE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub(crate) async fn with_lock<F, Fut, T>(key: &str, ttl: Duration, task: F) -> Result<Option<T>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let store = store();
+    let owner = format!("{}:{:08x}", instance_id(), rand::random::<u32>());
+    if !store.try_acquire(key, &owner, ttl).await? {
+        log::debug!("Lock '{}' is held elsewhere, skipping the task", key);
+        return Ok(None);
+    }
+
+    let mut guard = LockGuard {
+        store,
+        key: key.to_string(),
+        owner,
+        released: false,
+    };
+    let result = task().await;
+
+    guard.released = true;
+    if let Err(e) = guard.store.release(&guard.key, &guard.owner).await {
+        log::warn!(
+            "Failed to release lock '{}', it lapses after its TTL: {}",
+            key,
+            e
+        );
+    }
+    result.map(Some)
+}
+
+/// Releases the lock of a task that was cancelled or panicked
+struct LockGuard {
+    store: Arc<dyn LockStore>,
+    key: String,
+    owner: String,
+    released: bool,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let store = self.store.clone();
+            let key = std::mem::take(&mut self.key);
+            let owner = std::mem::take(&mut self.owner);
+            runtime.spawn(async move {
+                let _ = store.release(&key, &owner).await;
+            });
+        }
+    }
+}
+
+/// In-process locks; the default store
+#[derive(Clone, Default)]
+pub struct MemoryLockStore {
+    locks: Arc<DashMap<String, (String, Instant)>>,
+}
+
+impl MemoryLockStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LockStore for MemoryLockStore {
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        let now = Instant::now();
+        match self.locks.entry(key.to_string()) {
+            Entry::Occupied(entry) if entry.get().1 > now => Ok(false),
+            Entry::Occupied(mut entry) => {
+                entry.insert((owner.to_string(), now + ttl));
+                Ok(true)
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((owner.to_string(), now + ttl));
+                Ok(true)
+            }
+        }
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> Result<()> {
+        self.locks.remove_if(key, |_, (holder, _)| holder == owner);
+        Ok(())
+    }
+
+    async fn status(&self, key: &str) -> Result<Option<LockStatus>> {
+        let now = Instant::now();
+        Ok(self
+            .locks
+            .get(key)
+            .filter(|entry| entry.1 > now)
+            .map(|entry| LockStatus {
+                owner: Some(entry.0.clone()),
+                expires_in: Some(entry.1 - now),
+                held_here: true,
+            }))
+    }
+}
+
+/// Locks shared through Redis
+///
+/// A lock is a key set with `NX` and a millisecond expiry, holding the owner
+/// token; it is only deleted by the owner that set it.
+#[derive(Clone)]
+pub struct RedisLockStore {
+    pool: Pool,
+    prefix: String,
+}
+
+/// DEL the key only if it still holds the caller's owner token
+const RELEASE_SCRIPT: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+";
+
+impl RedisLockStore {
+    /// Create a store for `redis_url` with the prefix `rustf:lock:`
+    ///
+    /// Connections are opened on first use.
+    pub fn new(redis_url: &str) -> Result<Self> {
+        let pool = Config::from_url(redis_url).create_pool(Some(Runtime::Tokio1))?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Create a store on an existing connection pool
+    pub fn from_pool(pool: Pool) -> Self {
+        Self {
+            pool,
+            prefix: "rustf:lock:".to_string(),
+        }
+    }
+
+    /// Set the prefix of the lock keys
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl LockStore for RedisLockStore {
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        let mut conn = self.pool.get().await?;
+        let set: Option<String> = redis::cmd("SET")
+            .arg(format!("{}{}", self.prefix, key))
+            .arg(owner)
+            .arg("NX")
+            .arg("PX")
+            .arg((ttl.as_millis() as u64).max(1))
+            .query_async(&mut conn)
+            .await?;
+        Ok(set.is_some())
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> Result<()> {
+        let mut conn = self.pool.get().await?;
+        let _: i64 = redis::Script::new(RELEASE_SCRIPT)
+            .key(format!("{}{}", self.prefix, key))
+            .arg(owner)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn status(&self, key: &str) -> Result<Option<LockStatus>> {
+        let mut conn = self.pool.get().await?;
+        let (owner, ttl_ms): (Option<String>, i64) = redis::pipe()
+            .cmd("GET")
+            .arg(format!("{}{}", self.prefix, key))
+            .cmd("PTTL")
+            .arg(format!("{}{}", self.prefix, key))
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(owner.map(|owner| LockStatus {
+            held_here: is_local(&owner),
+            owner: Some(owner),
+            expires_in: (ttl_ms >= 0).then(|| Duration::from_millis(ttl_ms as u64)),
+        }))
+    }
+}
+
+/// Locks held as database advisory locks
+///
+/// PostgreSQL `pg_try_advisory_lock` and MySQL `GET_LOCK` tie a lock to the
+/// connection that took it, so the connection is kept out of the pool until
+/// the lock is released or its TTL passes. If the instance dies, the server
+/// drops the lock with the connection. SQLite has no advisory locks.
+pub struct DatabaseLockStore {
+    db: Arc<AnyDatabase>,
+    held: Arc<DashMap<String, HeldLock>>,
+}
+
+struct HeldLock {
+    owner: String,
+    expires_at: Instant,
+    connection: HeldConnection,
+}
+
+enum HeldConnection {
+    Postgres(PoolConnection<sqlx::Postgres>),
+    MySql(PoolConnection<sqlx::MySql>),
+}
+
+impl DatabaseLockStore {
+    /// Create a store on a PostgreSQL or MySQL database, such as `DB::get().await?`
+    pub fn new(db: Arc<AnyDatabase>) -> Result<Self> {
+        if matches!(db.as_ref(), AnyDatabase::SQLite(_)) {
+            return Err(Error::internal(
+                "SQLite has no advisory locks; use RedisLockStore or MemoryLockStore",
+            ));
+        }
+        Ok(Self {
+            db,
+            held: Arc::new(DashMap::new()),
+        })
+    }
+
+    /// Take the lock on a fresh connection, which then holds it
+    async fn lock(&self, key: &str) -> Result<Option<HeldConnection>> {
+        match self.db.as_ref() {
+            AnyDatabase::Postgres(pool) => {
+                let mut conn = pool.acquire().await.map_err(pool_error)?;
+                let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+                    .bind(advisory_id(key))
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(query_error)?;
+                Ok(locked.then_some(HeldConnection::Postgres(conn)))
+            }
+            AnyDatabase::MySQL(pool) => {
+                let mut conn = pool.acquire().await.map_err(pool_error)?;
+                let locked: Option<i64> =
+                    sqlx::query_scalar("SELECT CAST(GET_LOCK(?, 0) AS SIGNED)")
+                        .bind(advisory_name(key))
+                        .fetch_one(&mut *conn)
+                        .await
+                        .map_err(query_error)?;
+                Ok((locked == Some(1)).then_some(HeldConnection::MySql(conn)))
+            }
+            AnyDatabase::SQLite(_) => Ok(None),
+        }
+    }
+}
+
+/// Unlock and return the connection to its pool
+async fn unlock(key: &str, connection: HeldConnection) -> Result<()> {
+    match connection {
+        HeldConnection::Postgres(mut conn) => {
+            sqlx::query("SELECT pg_advisory_unlock($1)")
+                .bind(advisory_id(key))
+                .execute(&mut *conn)
+                .await
+                .map_err(query_error)?;
+        }
+        HeldConnection::MySql(mut conn) => {
+            sqlx::query("SELECT RELEASE_LOCK(?)")
+                .bind(advisory_name(key))
+                .execute(&mut *conn)
+                .await
+                .map_err(query_error)?;
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl LockStore for DatabaseLockStore {
+    async fn try_acquire(&self, key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        // Another run in this process; the server would let the same session in twice
+        if self.held.contains_key(key) {
+            return Ok(false);
+        }
+        let Some(connection) = self.lock(key).await? else {
+            return Ok(false);
+        };
+
+        self.held.insert(
+            key.to_string(),
+            HeldLock {
+                owner: owner.to_string(),
+                expires_at: Instant::now() + ttl,
+                connection,
+            },
+        );
+
+        let held = self.held.clone();
+        let (key, owner) = (key.to_string(), owner.to_string());
+        tokio::spawn(async move {
+            tokio::time::sleep(ttl).await;
+            if let Some((_, lock)) = held.remove_if(&key, |_, lock| lock.owner == owner) {
+                log::warn!("Lock '{}' reached its TTL and was released", key);
+                let _ = unlock(&key, lock.connection).await;
+            }
+        });
+        Ok(true)
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> Result<()> {
+        match self.held.remove_if(key, |_, lock| lock.owner == owner) {
+            Some((_, lock)) => unlock(key, lock.connection).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn status(&self, key: &str) -> Result<Option<LockStatus>> {
+        if let Some(lock) = self.held.get(key) {
+            return Ok(Some(LockStatus {
+                owner: Some(lock.owner.clone()),
+                expires_in: Some(lock.expires_at.saturating_duration_since(Instant::now())),
+                held_here: true,
+            }));
+        }
+
+        // Held by another instance: the server knows, but not by whom
+        let held = match self.db.as_ref() {
+            AnyDatabase::Postgres(pool) => sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM pg_locks WHERE locktype = 'advisory' \
+                 AND classid = (($1 >> 32) & 4294967295)::oid \
+                 AND objid = ($1 & 4294967295)::oid AND objsubid = 1)",
+            )
+            .bind(advisory_id(key))
+            .fetch_one(pool)
+            .await
+            .map_err(query_error)?,
+            AnyDatabase::MySQL(pool) => {
+                let used: i64 =
+                    sqlx::query_scalar("SELECT CAST(IS_USED_LOCK(?) IS NOT NULL AS SIGNED)")
+                        .bind(advisory_name(key))
+                        .fetch_one(pool)
+                        .await
+                        .map_err(query_error)?;
+                used == 1
+            }
+            AnyDatabase::SQLite(_) => false,
+        };
+
+        Ok(held.then_some(LockStatus {
+            owner: None,
+            expires_in: None,
+            held_here: false,
+        }))
+    }
+}
+
+/// PostgreSQL advisory lock id for a key, stable across builds and hosts
+fn advisory_id(key: &str) -> i64 {
+    let digest = Sha256::digest(format!("rustf:lock:{}", key));
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    i64::from_be_bytes(bytes)
+}
+
+/// MySQL lock name for a key, within the 64 character limit
+fn advisory_name(key: &str) -> String {
+    let digest = Sha256::digest(key);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("rustf:lock:{}", hex)
+}
+
+fn pool_error(e: sqlx::Error) -> Error {
+    Error::database_pool(e.to_string())
+}
+
+fn query_error(e: sqlx::Error) -> Error {
+    Error::database_query(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        let store = MemoryLockStore::new();
+        let ttl = Duration::from_secs(60);
+
+        assert!(store.try_acquire("job", "a", ttl).await.unwrap());
+        assert!(!store.try_acquire("job", "b", ttl).await.unwrap());
+        assert_eq!(
+            store.status("job").await.unwrap().unwrap().owner.as_deref(),
+            Some("a")
+        );
+
+        // Only the owner releases
+        store.release("job", "b").await.unwrap();
+        assert!(!store.try_acquire("job", "b", ttl).await.unwrap());
+        store.release("job", "a").await.unwrap();
+        assert!(store.status("job").await.unwrap().is_none());
+
+        // An expired lock is free again
+        assert!(store.try_acquire("job", "b", Duration::ZERO).await.unwrap());
+        assert!(store.try_acquire("job", "c", ttl).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_with_lock_runs_once() {
+        let key = "test-with-lock-runs-once";
+        let ttl = Duration::from_secs(60);
+
+        let outer = with_lock(key, ttl, || async {
+            // A second run while the first holds the lock is skipped
+            let inner = with_lock(key, ttl, || async { Ok(()) }).await?;
+            assert!(store().status(key).await?.unwrap().held_here);
+            Ok(inner.is_none())
+        })
+        .await
+        .unwrap();
+        assert_eq!(outer, Some(true));
+
+        // Released after success and after failure
+        let failed: Result<Option<()>> =
+            with_lock(key, ttl, || async { Err(Error::internal("boom")) }).await;
+        assert!(failed.is_err());
+        assert!(store().status(key).await.unwrap().is_none());
+    }
+}
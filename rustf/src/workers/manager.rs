@@ -4,12 +4,14 @@ use crate::config::AppConfig;
 use crate::error::{Error, Result};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::task::JoinHandle;
 
 use super::context::WorkerContext;
+use super::meta::WorkerMeta;
+use super::schedule::{CronSchedule, OverlapPolicy};
 use super::types::{WorkerDefinition, WorkerHandler, WorkerId, WorkerStats};
 
 #[derive(Clone)]
@@ -22,9 +24,19 @@ struct WorkerManagerInner {
     stats: RwLock<HashMap<String, WorkerStats>>,
     active_runs: RwLock<HashMap<WorkerId, ActiveRun>>,
     definition_runs: RwLock<HashMap<String, HashSet<WorkerId>>>,
+    schedules: RwLock<HashMap<String, JoinHandle<()>>>,
     config: Arc<AppConfig>,
 }
 
+impl Drop for WorkerManagerInner {
+    fn drop(&mut self) {
+        // Schedule tasks only hold a weak reference; stop them with the manager
+        for (_, task) in self.schedules.get_mut().drain() {
+            task.abort();
+        }
+    }
+}
+
 struct ActiveRun {
     definition: String,
     handle: JoinHandle<()>,
@@ -42,6 +54,7 @@ impl WorkerManager {
                 stats: RwLock::new(HashMap::new()),
                 active_runs: RwLock::new(HashMap::new()),
                 definition_runs: RwLock::new(HashMap::new()),
+                schedules: RwLock::new(HashMap::new()),
                 config,
             }),
         })
@@ -57,6 +70,109 @@ impl WorkerManager {
         Ok(())
     }
 
+    /// Register a worker and start it on every tick of `schedule`
+    ///
+    /// Scheduling a name again replaces its previous schedule.
+    pub async fn schedule(
+        &self,
+        definition: WorkerDefinition,
+        schedule: CronSchedule,
+        overlap: OverlapPolicy,
+    ) -> Result<()> {
+        let name = definition.name.clone();
+        self.register_definition(definition).await?;
+
+        {
+            let mut stats = self.inner.stats.write().await;
+            let entry = stats.entry(name.clone()).or_default();
+            entry.schedule = Some(schedule.expression().to_string());
+            entry.next_run = schedule.next_after(chrono::Utc::now());
+        }
+
+        let task = tokio::spawn(Self::run_schedule(
+            Arc::downgrade(&self.inner),
+            name.clone(),
+            schedule,
+            overlap,
+        ));
+        if let Some(previous) = self.inner.schedules.write().await.insert(name, task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop scheduling a worker without unregistering it
+    pub async fn unschedule(&self, worker_name: &str) -> Result<()> {
+        let task = self.inner.schedules.write().await.remove(worker_name);
+        match task {
+            Some(task) => {
+                task.abort();
+                if let Some(stats) = self.inner.stats.write().await.get_mut(worker_name) {
+                    stats.schedule = None;
+                    stats.next_run = None;
+                }
+                Ok(())
+            }
+            None => Err(Error::InvalidInput(format!(
+                "Worker '{}' is not scheduled",
+                worker_name
+            ))),
+        }
+    }
+
+    /// Start `name` on every tick of `schedule` while the manager is alive
+    ///
+    /// The task holds the manager weakly, so it does not keep it (and itself)
+    /// alive through the manager's own `schedules` map.
+    async fn run_schedule(
+        manager: Weak<WorkerManagerInner>,
+        name: String,
+        schedule: CronSchedule,
+        overlap: OverlapPolicy,
+    ) {
+        loop {
+            let now = chrono::Utc::now();
+            let next = schedule.next_after(now);
+            let Some(inner) = manager.upgrade() else {
+                return;
+            };
+            if let Some(stats) = inner.stats.write().await.get_mut(&name) {
+                stats.next_run = next;
+            }
+            drop(inner);
+            let Some(next) = next else {
+                log::warn!(
+                    "Schedule '{}' of worker '{}' never fires again",
+                    schedule,
+                    name
+                );
+                return;
+            };
+
+            let wait = (next - now).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            let Some(inner) = manager.upgrade() else {
+                return;
+            };
+            let this = Self { inner };
+            if overlap == OverlapPolicy::Skip && !this.running_for(&name).await.is_empty() {
+                log::warn!(
+                    "Skipping scheduled run of worker '{}': previous run still in progress",
+                    name
+                );
+                if let Some(stats) = this.inner.stats.write().await.get_mut(&name) {
+                    stats.skipped_runs += 1;
+                }
+                continue;
+            }
+
+            if let Err(e) = this.call(&name, None, None).await {
+                log::error!("Failed to start scheduled worker '{}': {}", name, e);
+            }
+        }
+    }
+
     pub async fn definitions(&self) -> Vec<String> {
         self.inner
             .definitions
@@ -72,6 +188,18 @@ impl WorkerManager {
         worker_name: &str,
         timeout: Option<Duration>,
         payload: Option<Value>,
+    ) -> Result<WorkerHandle> {
+        self.call_with_meta(worker_name, timeout, payload, WorkerMeta::default())
+            .await
+    }
+
+    /// Start a worker run with request values readable through `ctx.meta(key)`
+    pub async fn call_with_meta(
+        &self,
+        worker_name: &str,
+        timeout: Option<Duration>,
+        payload: Option<Value>,
+        meta: WorkerMeta,
     ) -> Result<WorkerHandle> {
         let handler = {
             let definitions = self.inner.definitions.read().await;
@@ -91,7 +219,8 @@ impl WorkerManager {
             Arc::clone(&self.inner.config),
             Some(message_tx.clone()),
         )
-        .with_data(payload.unwrap_or(Value::Null));
+        .with_data(payload.unwrap_or(Value::Null))
+        .with_meta(meta);
 
         let manager = self.clone();
         let name = worker_name.to_string();
@@ -220,6 +349,10 @@ impl WorkerManager {
     }
 
     pub async fn shutdown_all(&self) -> Result<()> {
+        for (_, task) in self.inner.schedules.write().await.drain() {
+            task.abort();
+        }
+
         let mut active = self.inner.active_runs.write().await;
         for (_, run) in active.drain() {
             run.handle.abort();
@@ -262,3 +395,26 @@ impl WorkerHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_schedule_does_not_keep_the_manager_alive() {
+        let manager = WorkerManager::new().unwrap();
+        manager
+            .schedule(
+                WorkerDefinition::new("tick", |_ctx| async { Ok(()) }),
+                CronSchedule::parse("* * * * *").unwrap(),
+                OverlapPolicy::Skip,
+            )
+            .await
+            .unwrap();
+        tokio::task::yield_now().await;
+
+        let inner = Arc::downgrade(&manager.inner);
+        drop(manager);
+        assert!(inner.upgrade().is_none());
+    }
+}
@@ -0,0 +1,138 @@
+//! Request metadata carried into worker runs
+//!
+//! Workers run outside the request that started them, so nothing from the
+//! request is visible to them unless it is passed along. `WorkerMeta` is an
+//! explicit snapshot of selected request values, handed to
+//! `WORKER::call_with_meta` and read back with `ctx.meta(key)`:
+//!
+//! ```rust,ignore
+//! use rustf::workers::{WorkerMeta, META_LOCALE, META_REQUEST_ID, META_USER_ID};
+//!
+//! async fn export(ctx: &mut Context) -> Result<()> {
+//!     let meta = WorkerMeta::capture(ctx, &[META_REQUEST_ID, META_USER_ID, META_LOCALE]);
+//!     WORKER::call_with_meta("export-report", None, None, meta).await?;
+//!     ctx.success(None::<()>)
+//! }
+//!
+//! WORKER::register("export-report", |ctx| async move {
+//!     let locale = ctx.meta(META_LOCALE).unwrap_or("en");
+//!     ctx.info("Building report"); // log line includes the request id
+//!     Ok(())
+//! }).await?;
+//! ```
+
+use crate::context::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Request id, from the `X-Request-Id` (or `X-Correlation-Id`) header
+pub const META_REQUEST_ID: &str = "request_id";
+
+/// Id of the logged-in user, from the session
+pub const META_USER_ID: &str = "user_id";
+
+/// Preferred language, from the `Accept-Language` header
+pub const META_LOCALE: &str = "locale";
+
+/// Client IP address
+pub const META_IP: &str = "ip";
+
+/// Request path
+pub const META_PATH: &str = "path";
+
+/// Snapshot of request values made available to a worker run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerMeta {
+    values: BTreeMap<String, String>,
+}
+
+impl WorkerMeta {
+    /// Create an empty snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture the given keys from a request context
+    ///
+    /// The `META_*` keys are read from their documented source. Any other key
+    /// is read from a `String` stored on the context with `ctx.set(key, ...)`.
+    /// Keys without a value in this request are left out.
+    pub fn capture(ctx: &Context, keys: &[&str]) -> Self {
+        let mut meta = Self::new();
+        for key in keys {
+            if let Some(value) = capture_value(ctx, key) {
+                meta.values.insert(key.to_string(), value);
+            }
+        }
+        meta
+    }
+
+    /// Add or replace a value
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+
+    /// Get a value
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Iterate over all values, sorted by key
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Check whether no values were captured
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+fn capture_value(ctx: &Context, key: &str) -> Option<String> {
+    match key {
+        META_REQUEST_ID => crate::http::request_id::current(),
+        META_USER_ID => ctx
+            .session()
+            .and_then(|session| session.get_user_id())
+            .map(|id| id.to_string()),
+        META_LOCALE => ctx.language().map(str::to_string),
+        META_IP => Some(ctx.ip()),
+        META_PATH => Some(ctx.path().to_string()),
+        _ => ctx.get::<String>(key).cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Request;
+    use crate::views::ViewEngine;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_capture_selected_keys() {
+        let mut request = Request::default();
+        request.uri = "/reports".to_string();
+        request
+            .headers
+            .insert("accept-language".to_string(), "fr-FR,fr;q=0.9".to_string());
+
+        let mut ctx = Context::new(request, Arc::new(ViewEngine::from_directory("views")));
+        ctx.set("tenant", "acme".to_string()).unwrap();
+
+        // The dispatcher sets the request id for the whole request
+        let keys = [META_REQUEST_ID, META_LOCALE, META_USER_ID, "tenant"];
+        let meta = crate::http::request_id::scope(Some("req-42".to_string()), async {
+            WorkerMeta::capture(&ctx, &keys)
+        })
+        .await;
+
+        assert_eq!(meta.get(META_REQUEST_ID), Some("req-42"));
+        assert_eq!(meta.get(META_LOCALE), Some("fr-FR"));
+        assert_eq!(meta.get("tenant"), Some("acme"));
+        // No session, and paths are only captured when asked for
+        assert_eq!(meta.get(META_USER_ID), None);
+        assert_eq!(meta.get(META_PATH), None);
+    }
+}
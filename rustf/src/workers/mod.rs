@@ -18,19 +18,34 @@
 //! // Later in the application
 //! let mut handle = WORKER::call("send-email", None, Some(json!({"to":"user@example.com"}))).await?;
 //! handle.await_result().await?;
+//!
+//! // Recurring jobs run on a cron schedule (UTC)
+//! WORKER::schedule("cleanup", "0 */6 * * *", |ctx| async move {
+//!     ctx.info("Purging expired sessions...");
+//!     Ok(())
+//! }).await?;
+//!
+//! // Run on one instance only when the app is scaled out (see `lock`)
+//! WORKER::with_lock("cleanup", Duration::from_secs(300), || async { purge().await }).await?;
 //! ```
 
 pub mod api;
 pub mod context;
+pub mod lock;
 pub mod manager;
+pub mod meta;
 pub mod registry;
+pub mod schedule;
 pub mod types;
 
 // Re-export main types for public API
 pub use api::WORKER;
 pub use context::WorkerContext;
+pub use lock::{DatabaseLockStore, LockStatus, LockStore, MemoryLockStore, RedisLockStore};
 pub use manager::{WorkerHandle, WorkerManager};
+pub use meta::{WorkerMeta, META_IP, META_LOCALE, META_PATH, META_REQUEST_ID, META_USER_ID};
 pub use registry::{WorkerRegistry, WORKER_REGISTRY};
+pub use schedule::{CronSchedule, OverlapPolicy};
 pub use types::{WorkerDefinition, WorkerHandler, WorkerId, WorkerStats, WorkerStatus};
 
 use crate::error::Result;
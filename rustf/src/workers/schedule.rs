@@ -0,0 +1,348 @@
+//! Cron schedules for recurring workers
+//!
+//! Expressions use the classic five fields, evaluated in UTC:
+//!
+//! ```text
+//! ┌──────── minute (0-59)
+//! │ ┌────── hour (0-23)
+//! │ │ ┌──── day of month (1-31)
+//! │ │ │ ┌── month (1-12 or JAN-DEC)
+//! │ │ │ │ ┌ day of week (0-7 or SUN-SAT, 0 and 7 are Sunday)
+//! * * * * *
+//! ```
+//!
+//! Each field accepts `*`, values, ranges (`1-5`), steps (`*/15`, `0-30/10`)
+//! and comma-separated lists of those. As in Vixie cron, when both day fields
+//! are restricted a day matches if either does; only a bare `*` leaves a day
+//! field unrestricted, so `*/2` or `1-31` count as restrictions. `@yearly`, `@monthly`,
+//! `@weekly`, `@daily` and `@hourly` are accepted as shorthands.
+
+use crate::error::{Error, Result};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How far ahead `next_after` looks before deciding a schedule never fires
+/// (leap days only come around every four years)
+const SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// What a scheduled worker does when its previous run is still going
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlapPolicy {
+    /// Skip this tick and wait for the next one
+    #[default]
+    Skip,
+    /// Start another run alongside the previous one
+    Allow,
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// The day fields were a bare `*`, which changes how they combine
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression or an `@` shorthand
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => expression,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(
+                expression,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        };
+
+        let mut days_of_week = parse_field(weekday, 0, 7, DAY_NAMES)
+            .map_err(|reason| invalid(expression, format!("day of week: {}", reason)))?;
+        // 7 is another name for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, &[])
+                .map_err(|reason| invalid(expression, format!("minute: {}", reason)))?,
+            hours: parse_field(hour, 0, 23, &[])
+                .map_err(|reason| invalid(expression, format!("hour: {}", reason)))?,
+            days_of_month: parse_field(day, 1, 31, &[])
+                .map_err(|reason| invalid(expression, format!("day of month: {}", reason)))?,
+            months: parse_field(month, 1, 12, MONTH_NAMES)
+                .map_err(|reason| invalid(expression, format!("month: {}", reason)))?,
+            days_of_week,
+            any_day_of_month: day == "*",
+            any_day_of_week: weekday == "*",
+        })
+    }
+
+    /// The expression as it was written
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The first time strictly after `after` that matches the schedule
+    ///
+    /// Returns `None` for schedules that can never fire, such as `0 0 30 2 *`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start.year() + SEARCH_YEARS;
+        let mut time = start;
+
+        while time.year() <= limit {
+            if !has(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.matches_day(&time) {
+                time = (time + Duration::days(1)).with_hour(0)?.with_minute(0)?;
+                continue;
+            }
+            if !has(self.hours, time.hour()) {
+                time = (time + Duration::hours(1)).with_minute(0)?;
+                continue;
+            }
+            if !has(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+                continue;
+            }
+            return Some(time);
+        }
+        None
+    }
+
+    /// Whether `time` (to the minute) matches the schedule
+    pub fn matches(&self, time: &DateTime<Utc>) -> bool {
+        has(self.months, time.month())
+            && self.matches_day(time)
+            && has(self.hours, time.hour())
+            && has(self.minutes, time.minute())
+    }
+
+    fn matches_day(&self, time: &DateTime<Utc>) -> bool {
+        let day_of_month = has(self.days_of_month, time.day());
+        let day_of_week = has(self.days_of_week, time.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        Self::parse(expression)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn invalid(expression: &str, reason: String) -> Error {
+    Error::InvalidInput(format!(
+        "Invalid cron expression '{}': {}",
+        expression, reason
+    ))
+}
+
+/// Parse one field into a bit set of the values it allows
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> std::result::Result<u64, String> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, names)?,
+                parse_value(end, min, max, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // `5/15` means from 5 to the end in steps of 15
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("range '{}' is reversed", range));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn parse_value(
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> std::result::Result<u32, String> {
+    let parsed = match names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        // Month names start at 1, day names at 0
+        Some(index) => index as u32 + min,
+        None => value
+            .parse()
+            .map_err(|_| format!("invalid value '{}'", value))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("{} is outside {}-{}", parsed, min, max));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_next_after() {
+        let every_six_hours = CronSchedule::parse("0 */6 * * *").unwrap();
+        assert_eq!(
+            every_six_hours.next_after(at("2024-03-10T05:59:30Z")),
+            Some(at("2024-03-10T06:00:00Z"))
+        );
+        assert_eq!(
+            every_six_hours.next_after(at("2024-03-10T06:00:00Z")),
+            Some(at("2024-03-10T12:00:00Z"))
+        );
+        assert_eq!(
+            every_six_hours.next_after(at("2024-12-31T18:00:00Z")),
+            Some(at("2025-01-01T00:00:00Z"))
+        );
+
+        // Weekdays at 9:30, by name
+        let weekdays = CronSchedule::parse("30 9 * * MON-FRI").unwrap();
+        assert_eq!(
+            weekdays.next_after(at("2024-03-08T10:00:00Z")), // a Friday
+            Some(at("2024-03-11T09:30:00Z"))
+        );
+
+        // Leap days are found; impossible dates never fire
+        let leap_day = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(at("2024-03-01T00:00:00Z")),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(at("2024-01-01T00:00:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_day_fields_combine_like_cron() {
+        // The 13th of the month or any Friday
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert!(schedule.matches(&at("2024-03-13T00:00:00Z"))); // Wednesday the 13th
+        assert!(schedule.matches(&at("2024-03-15T00:00:00Z"))); // Friday
+        assert!(!schedule.matches(&at("2024-03-14T00:00:00Z")));
+
+        // A stepped day of month is a restriction too: odd days or any Monday
+        let schedule = CronSchedule::parse("0 0 */2 * 1").unwrap();
+        assert!(schedule.matches(&at("2024-03-13T00:00:00Z"))); // Wednesday the 13th
+        assert!(schedule.matches(&at("2024-03-18T00:00:00Z"))); // Monday the 18th
+        assert!(!schedule.matches(&at("2024-03-14T00:00:00Z"))); // Thursday the 14th
+
+        // With the day of week unrestricted, only the day of month counts
+        let schedule = CronSchedule::parse("0 0 */2 * *").unwrap();
+        assert!(!schedule.matches(&at("2024-03-18T00:00:00Z")));
+
+        // Sunday is both 0 and 7
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7")
+                .unwrap()
+                .next_after(at("2024-03-11T00:00:00Z")),
+            Some(at("2024-03-17T00:00:00Z"))
+        );
+        assert_eq!(
+            CronSchedule::parse("@daily")
+                .unwrap()
+                .next_after(at("2024-03-11T08:00:00Z")),
+            Some(at("2024-03-12T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 0 * FOO *",
+        ] {
+            assert!(CronSchedule::parse(expression).is_err(), "{}", expression);
+        }
+    }
+}
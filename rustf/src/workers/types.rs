@@ -1,6 +1,7 @@
 //! Core worker types and definitions
 
 use crate::error::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
@@ -61,6 +62,12 @@ pub struct WorkerStats {
     pub errors: u64,
     pub last_run_ms: Option<u64>,
     pub total_runtime_ms: u64,
+    /// Cron expression the worker runs on, for scheduled workers
+    pub schedule: Option<String>,
+    /// When the scheduler starts the worker next
+    pub next_run: Option<DateTime<Utc>>,
+    /// Scheduled runs skipped because the previous run was still going
+    pub skipped_runs: u64,
 }
 
 impl WorkerStats {
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use hyper::{Body, StatusCode};
+use rustf::prelude::*;
+use rustf::{InboundAction, InboundMiddleware, OutboundMiddleware};
+
+async fn explode(_ctx: &mut Context) -> Result<()> {
+    panic!("handler exploded");
+}
+
+#[derive(Clone)]
+struct MarkResponse;
+
+#[async_trait]
+impl InboundMiddleware for MarkResponse {
+    async fn process_request(&self, _ctx: &mut Context) -> Result<InboundAction> {
+        Ok(InboundAction::Capture)
+    }
+}
+
+#[async_trait]
+impl OutboundMiddleware for MarkResponse {
+    async fn process_response(&self, ctx: &mut Context) -> Result<()> {
+        if let Some(response) = ctx.res.as_mut() {
+            response.add_header("X-Outbound", "ran");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_handler_panic_gets_500_and_outbound_middleware_runs() {
+    let app = RustF::new()
+        .controllers(routes![
+            GET "/explode" => explode,
+        ])
+        .middleware_from(|registry| {
+            registry.register_dual("mark", MarkResponse);
+        });
+
+    let request = hyper::Request::builder()
+        .uri("/explode")
+        .header("Accept", "application/json")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.handle_request(request).await.unwrap();
+
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response
+        .headers
+        .iter()
+        .any(|(name, value)| name == "X-Outbound" && value == "ran"));
+}
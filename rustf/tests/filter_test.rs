@@ -46,4 +46,25 @@ mod tests {
 
         assert_eq!(filter.len(), 13);
     }
+
+    #[test]
+    fn test_filter_or_grouping() {
+        let published = ModelFilter::new()
+            .where_eq("status", "published")
+            .where_not_null("published_at");
+        let own = ModelFilter::new().where_eq("author_id", 7);
+
+        // Group markers are not counted as conditions
+        let visible = published.or(own);
+        assert_eq!(visible.len(), 3);
+
+        let scoped = ModelFilter::new().where_eq("tenant_id", 1).and(visible);
+        assert_eq!(scoped.len(), 4);
+
+        // An empty side matches everything
+        assert!(ModelFilter::new()
+            .where_eq("a", 1)
+            .or(ModelFilter::new())
+            .is_empty());
+    }
 }
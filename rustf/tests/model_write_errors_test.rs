@@ -0,0 +1,109 @@
+use rustf::database::types::SqlValue;
+use rustf::db::DB;
+use rustf::models::{BaseModel, ChangeTracking};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Account {
+    id: i64,
+    email: String,
+    #[serde(skip)]
+    changed: HashSet<String>,
+    #[serde(skip)]
+    nulls: HashSet<String>,
+}
+
+impl ChangeTracking for Account {
+    fn mark_changed(&mut self, field: &str, _is_null: bool) {
+        self.changed.insert(field.to_string());
+    }
+    fn is_changed(&self, field: &str) -> bool {
+        self.changed.contains(field)
+    }
+    fn is_null(&self, field: &str) -> bool {
+        self.nulls.contains(field)
+    }
+    fn has_changes(&self) -> bool {
+        !self.changed.is_empty()
+    }
+    fn clear_changes(&mut self) {
+        self.changed.clear();
+    }
+    fn changed_fields(&self) -> Vec<String> {
+        self.changed.iter().cloned().collect()
+    }
+    fn changed_fields_set(&self) -> &HashSet<String> {
+        &self.changed
+    }
+    fn null_fields_set(&self) -> &HashSet<String> {
+        &self.nulls
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseModel for Account {
+    type IdType = i64;
+    const TABLE_NAME: &'static str = "accounts";
+    const PRIMARY_KEY: &'static str = "id";
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    async fn from_row_data(data: serde_json::Value) -> anyhow::Result<Self> {
+        Ok(serde_json::from_value(data)?)
+    }
+
+    fn get_field_value(&self, field_name: &str) -> rustf::Result<SqlValue> {
+        match field_name {
+            "id" => Ok(self.id.into()),
+            "email" => Ok(self.email.clone().into()),
+            other => Err(rustf::Error::internal(format!("Unknown field {}", other))),
+        }
+    }
+
+    async fn execute_select_query(_sql: &str, _params: Vec<SqlValue>) -> anyhow::Result<Vec<Self>> {
+        Ok(Vec::new())
+    }
+
+    async fn execute_select_one_query(
+        _sql: &str,
+        _params: Vec<SqlValue>,
+    ) -> anyhow::Result<Option<Self>> {
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_unique_violation_survives_model_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}?mode=rwc", dir.path().join("app.db").display());
+    DB::init(Some(&url)).await.unwrap();
+    DB::execute_with_params(
+        "CREATE TABLE accounts (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    DB::execute_with_params(
+        "INSERT INTO accounts (id, email) VALUES (1, 'ann@example.com'), (2, 'bob@example.com')",
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let mut bob = Account {
+        id: 2,
+        email: "ann@example.com".to_string(),
+        changed: HashSet::new(),
+        nulls: HashSet::new(),
+    };
+    bob.mark_changed("email", false);
+    let error = bob.update().await.unwrap_err();
+    assert!(error.is_unique_violation(), "{:?}", error);
+
+    let duplicate = HashMap::from([("email".to_string(), SqlValue::from("bob@example.com"))]);
+    let error = Account::insert_many(vec![duplicate]).await.unwrap_err();
+    assert!(error.is_unique_violation(), "{:?}", error);
+}
@@ -116,6 +116,7 @@ enabled = false
             idle_timeout: 1800,
             absolute_timeout: 7200,
             same_site: "Strict".to_string(),
+            same_site_none: Default::default(),
             fingerprint_mode: "soft".to_string(),
             storage: Default::default(),
             exempt_routes: vec![],
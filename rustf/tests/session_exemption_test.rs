@@ -24,7 +24,7 @@ async fn test_session_exemption_paths() {
     config.absolute_timeout = Duration::from_secs(3600);
 
     // Create middleware with the config
-    let middleware = SessionMiddleware::new(config);
+    let middleware = SessionMiddleware::new(config).unwrap();
 
     // Test 1: Regular path should get session
     let mut ctx = create_test_context("/");
@@ -88,7 +88,7 @@ async fn test_session_globally_disabled() {
     config.enabled = false;
 
     // Create middleware with the config
-    let middleware = SessionMiddleware::new(config);
+    let middleware = SessionMiddleware::new(config).unwrap();
 
     // Test: All paths should skip session when globally disabled
     let mut ctx = create_test_context("/");
@@ -114,7 +114,7 @@ async fn test_session_exemption_pattern_matching() {
     let mut config = SessionConfig::new();
     config.exempt_routes = vec!["/api/*".to_string(), "/static/*".to_string()];
 
-    let middleware = SessionMiddleware::new(config);
+    let middleware = SessionMiddleware::new(config).unwrap();
 
     // Test edge cases for pattern matching
 
@@ -0,0 +1,21 @@
+use hyper::{Body, StatusCode};
+use rustf::prelude::*;
+
+async fn missing(ctx: &mut Context) -> Result<()> {
+    ctx.stream_file("storage/does-not-exist.mp4").await
+}
+
+#[tokio::test]
+async fn test_missing_file_is_not_found() {
+    let app = RustF::new().controllers(routes![
+        GET "/video" => missing,
+    ]);
+
+    let request = hyper::Request::builder()
+        .uri("/video")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.handle_request(request).await.unwrap();
+
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,58 @@
+use hyper::{Body, StatusCode};
+use rustf::middleware::builtin::TimeoutMiddleware;
+use rustf::prelude::*;
+use std::time::Duration;
+
+async fn slow(ctx: &mut Context) -> Result<()> {
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    ctx.text("too late")
+}
+
+async fn fast(ctx: &mut Context) -> Result<()> {
+    ctx.text("done")
+}
+
+fn app(timeout: TimeoutMiddleware) -> RustF {
+    RustF::new()
+        .controllers(routes![
+            GET "/slow" => slow,
+            GET "/fast" => fast,
+        ])
+        .middleware_from(move |registry| {
+            registry.register_inbound("timeout", timeout);
+        })
+}
+
+fn get(path: &str) -> hyper::Request<Body> {
+    hyper::Request::builder()
+        .uri(path)
+        .header("Accept", "application/json")
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_handler_past_timeout_gets_503() {
+    let app = app(TimeoutMiddleware::new(Duration::from_millis(50)));
+
+    let started = std::time::Instant::now();
+    let response = app.handle_request(get("/slow")).await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["error"]["code"], "E_TIMEOUT");
+
+    let response = app.handle_request(get("/fast")).await.unwrap();
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, b"done");
+}
+
+#[tokio::test]
+async fn test_timeout_status_is_configurable() {
+    let app = app(
+        TimeoutMiddleware::new(Duration::from_millis(50)).status(StatusCode::GATEWAY_TIMEOUT),
+    );
+
+    let response = app.handle_request(get("/slow")).await.unwrap();
+    assert_eq!(response.status, StatusCode::GATEWAY_TIMEOUT);
+}
@@ -0,0 +1,75 @@
+use hyper::{Body, StatusCode};
+use rustf::error::FieldError;
+use rustf::prelude::*;
+
+#[derive(Deserialize)]
+struct UserCreate {
+    name: String,
+}
+
+#[rustf::validate(body = "UserCreate")]
+async fn create(ctx: &mut Context, user: UserCreate) -> Result<()> {
+    ctx.json(json!({ "name": user.name }))
+}
+
+async fn app() -> RustF {
+    rustf::definitions::get_mut().await.validators.register_fn(
+        "UserCreate",
+        "New user",
+        |value, _| match value.get("name").and_then(Value::as_str) {
+            Some(name) if !name.is_empty() => Ok(()),
+            _ => Err(Error::validation_fields(
+                "Invalid user",
+                vec![FieldError::new("name", "Name is required", "required")],
+            )),
+        },
+    );
+
+    RustF::new().controllers(routes![
+        POST "/users" => create,
+    ])
+}
+
+fn post(body: &str) -> hyper::Request<Body> {
+    hyper::Request::builder()
+        .method("POST")
+        .uri("/users")
+        .header("Accept", "application/json")
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_valid_body_reaches_the_handler() {
+    let response = app()
+        .await
+        .handle_request(post(r#"{"name": "ann"}"#))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status, StatusCode::OK);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["name"], "ann");
+}
+
+#[tokio::test]
+async fn test_invalid_body_is_rejected_before_the_handler() {
+    let app = app().await;
+
+    let response = app.handle_request(post(r#"{"name": ""}"#)).await.unwrap();
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["errors"][0]["field"], "name");
+    assert_eq!(body["errors"][0]["code"], "required");
+
+    let response = app.handle_request(post("")).await.unwrap();
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["errors"][0]["field"], "body");
+
+    let response = app.handle_request(post("{not json")).await.unwrap();
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["errors"][0]["code"], "invalid_json");
+}
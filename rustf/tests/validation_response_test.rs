@@ -0,0 +1,58 @@
+use hyper::{Body, StatusCode};
+use rustf::error::FieldError;
+use rustf::prelude::*;
+
+async fn register(_ctx: &mut Context) -> Result<()> {
+    Err(Error::validation_fields(
+        "Invalid registration data",
+        vec![FieldError::new("email", "Required", "required")],
+    ))
+}
+
+fn app() -> RustF {
+    RustF::new().controllers(routes![
+        POST "/register" => register,
+    ])
+}
+
+fn post(accept: &str) -> hyper::Request<Body> {
+    hyper::Request::builder()
+        .method("POST")
+        .uri("/register")
+        .header("Accept", accept)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn content_type(response: &Response) -> Option<&str> {
+    response
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.as_str())
+}
+
+#[tokio::test]
+async fn test_validation_error_as_problem_json_for_api_requests() {
+    let response = app()
+        .handle_request(post("application/json"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(content_type(&response), Some("application/problem+json"));
+    let body: Value = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(body["instance"], "/register");
+    assert_eq!(body["errors"][0]["field"], "email");
+}
+
+#[tokio::test]
+async fn test_validation_error_as_error_page_for_browsers() {
+    let response = app()
+        .handle_request(post("text/html,application/xhtml+xml"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert!(content_type(&response).is_some_and(|value| value.starts_with("text/html")));
+}
@@ -1349,7 +1349,7 @@ impl Users {
             "users",
             "id"
         ).await
-            .map_err(|e| rustf::Error::DatabaseQuery(format!("Failed to insert: {}", e)))?;
+            .map_err(|e| e.with_context("Failed to insert into users"))?;
         
         if let Some(json_data) = result {
             // Convert JSON back to model